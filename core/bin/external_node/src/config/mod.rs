@@ -1,6 +1,7 @@
 use std::{
     env,
     ffi::OsString,
+    net::IpAddr,
     num::{NonZeroU32, NonZeroU64, NonZeroUsize},
     path::PathBuf,
     time::Duration,
@@ -10,7 +11,7 @@ use anyhow::Context;
 use serde::Deserialize;
 use zksync_config::{
     configs::{
-        api::{MaxResponseSize, MaxResponseSizeOverrides},
+        api::{ComputeUnitsMap, MaxResponseSize, MaxResponseSizeOverrides},
         consensus::{ConsensusConfig, ConsensusSecrets},
         en_config::ENConfig,
         GeneralConfig, Secrets,
@@ -20,6 +21,7 @@ use zksync_config::{
 use zksync_consensus_crypto::TextFmt;
 use zksync_consensus_roles as roles;
 use zksync_core_leftovers::temp_config_store::{decode_yaml_repr, read_yaml_repr};
+use zksync_dal::pruning_dal::RetentionPolicy;
 #[cfg(test)]
 use zksync_dal::{ConnectionPool, Core};
 use zksync_metadata_calculator::MetadataCalculatorRecoveryConfig;
@@ -31,7 +33,7 @@ use zksync_protobuf_config::proto;
 use zksync_snapshots_applier::SnapshotsApplierConfig;
 use zksync_types::{
     api::BridgeAddresses, commitment::L1BatchCommitmentMode, url::SensitiveUrl, Address,
-    L1BatchNumber, L1ChainId, L2ChainId, SLChainId, ETHEREUM_ADDRESS,
+    L1BatchNumber, L1ChainId, L2ChainId, SLChainId, ETHEREUM_ADDRESS, H256,
 };
 use zksync_web3_decl::{
     client::{DynClient, L2},
@@ -203,6 +205,27 @@ impl RemoteENConfig {
         })
     }
 
+    /// Creates a config with placeholder values. Used when the node is not started, e.g. when dumping the wiring graph,
+    /// so that the main node isn't contacted.
+    pub fn placeholder() -> Self {
+        Self {
+            bridgehub_proxy_addr: None,
+            state_transition_proxy_addr: None,
+            transparent_proxy_admin_addr: None,
+            diamond_proxy_addr: Address::zero(),
+            l1_erc20_bridge_proxy_addr: None,
+            l2_erc20_bridge_addr: None,
+            l2_weth_bridge_addr: None,
+            l2_testnet_paymaster_addr: None,
+            base_token_addr: ETHEREUM_ADDRESS,
+            l1_shared_bridge_proxy_addr: None,
+            l1_weth_bridge_addr: None,
+            l2_shared_bridge_addr: Some(Address::zero()),
+            l1_batch_commit_data_generator_mode: L1BatchCommitmentMode::default(),
+            dummy_verifier: false,
+        }
+    }
+
     #[cfg(test)]
     fn mock() -> Self {
         Self {
@@ -251,6 +274,37 @@ pub(crate) struct OptionalENConfig {
     /// Limit for fee history block range.
     #[serde(default = "OptionalENConfig::default_fee_history_limit")]
     pub fee_history_limit: u64,
+    /// Maximum number of L2 blocks scanned by a single `trace_filter` call.
+    #[serde(default = "OptionalENConfig::default_trace_filter_block_range_limit")]
+    pub trace_filter_block_range_limit: u64,
+    /// Path to the Unix domain socket for the IPC JSON-RPC server. The IPC server is run together with the WS server
+    /// and serves the same namespaces and subscriptions. If not set, the IPC server is disabled.
+    pub ipc_path: Option<PathBuf>,
+    /// Maximum number of concurrent connections to the IPC server.
+    #[serde(default = "OptionalENConfig::default_ipc_max_connections")]
+    pub ipc_max_connections: usize,
+    /// Maximum number of active subscriptions for a single IPC connection.
+    #[serde(default = "OptionalENConfig::default_ipc_subscriptions_per_connection_limit")]
+    pub ipc_subscriptions_per_connection_limit: usize,
+    /// Maximum number of requests processed concurrently for a single IPC connection.
+    #[serde(default = "OptionalENConfig::default_ipc_max_concurrent_requests_per_connection")]
+    pub ipc_max_concurrent_requests_per_connection: usize,
+    /// Unix permissions for the IPC socket file as an octal number (e.g., `660`). If not set, permissions
+    /// are determined by the process umask.
+    pub ipc_socket_permissions: Option<String>,
+    /// Maximum number of compute units per minute spent by a single HTTP client identified by its API key or IP address.
+    /// If not set, HTTP requests are not rate-limited.
+    pub http_compute_units_per_minute_limit: Option<NonZeroU32>,
+    /// Per-minute compute unit limits for HTTP clients authenticated with API keys (passed in the `x-api-key` header).
+    #[serde(default)]
+    pub http_api_key_compute_units_per_minute_limits: ComputeUnitsMap,
+    /// Overrides for compute unit costs of specific RPC methods used for HTTP rate limiting.
+    #[serde(default)]
+    pub method_compute_units: ComputeUnitsMap,
+    /// IP addresses of reverse proxies trusted to set `x-forwarded-for` / `x-real-ip` headers for HTTP rate limiting.
+    /// If empty, clients are identified by the remote address of the connection.
+    #[serde(default)]
+    pub http_trusted_proxies: Vec<IpAddr>,
     /// Maximum number of requests in a single batch JSON RPC request. Default is 500.
     #[serde(default = "OptionalENConfig::default_max_batch_request_size")]
     pub max_batch_request_size: usize,
@@ -371,6 +425,11 @@ pub(crate) struct OptionalENConfig {
     /// Timeout to wait for the Merkle tree database to run compaction on stalled writes.
     #[serde(default = "OptionalENConfig::default_merkle_tree_stalled_writes_timeout_sec")]
     merkle_tree_stalled_writes_timeout_sec: u64,
+    /// Whether to initialize an empty Merkle tree from the latest tree checkpoint exported by the main node
+    /// to the snapshot recovery object store. The checkpoint root hash is validated against Postgres, so root hashes
+    /// must be available locally (e.g., via the tree data fetcher component).
+    #[serde(default)]
+    pub merkle_tree_checkpoint_import_enabled: bool,
 
     // Postgres config (new parameters)
     /// Threshold in milliseconds for the DB connection lifetime to denote it as long-living and log its details.
@@ -401,6 +460,11 @@ pub(crate) struct OptionalENConfig {
     /// Number of requests per second allocated for the main node HTTP client. Default is 100 requests.
     #[serde(default = "OptionalENConfig::default_main_node_rate_limit_rps")]
     pub main_node_rate_limit_rps: NonZeroUsize,
+    /// URLs of upstreams (e.g., trusted external nodes) used if the main node is unavailable, ordered by priority.
+    /// Requests are failed over to these upstreams while the main node is unavailable; reorg detection only uses
+    /// upstreams that don't lag behind other upstreams.
+    #[serde(default)]
+    pub main_node_fallback_urls: Vec<SensitiveUrl>,
 
     #[serde(default)]
     pub l1_batch_commit_data_generator_mode: L1BatchCommitmentMode,
@@ -438,6 +502,19 @@ pub(crate) struct OptionalENConfig {
     /// If set to 0, L1 batches will not be retained based on their timestamp. The default value is 7 days.
     #[serde(default = "OptionalENConfig::default_pruning_data_retention_sec")]
     pruning_data_retention_sec: u64,
+    /// Contract addresses whose transactions, events and receipts are retained beyond the pruning horizon.
+    /// A transaction is retained if it's initiated by or calls one of these addresses, or if it emits an event
+    /// from one of these addresses.
+    #[serde(default)]
+    pub pruning_retained_addresses: Vec<Address>,
+    /// Event topics; transactions emitting events with any of these topics (at any position) are retained
+    /// beyond the pruning horizon together with their events and receipts.
+    #[serde(default)]
+    pub pruning_retained_topics: Vec<H256>,
+    /// If set, storage history of `pruning_retained_addresses` is retained as well, so that `eth_getStorageAt`
+    /// can be answered for these contracts at pruned blocks.
+    #[serde(default)]
+    pub pruning_retain_storage_history: bool,
     /// Gateway RPC URL, needed for operating during migration.
     #[allow(dead_code)]
     pub gateway_url: Option<SensitiveUrl>,
@@ -479,6 +556,50 @@ impl OptionalENConfig {
                 web3_json_rpc.fee_history_limit,
                 default_fee_history_limit
             ),
+            trace_filter_block_range_limit: load_optional_config_or_default!(
+                general_config.api_config,
+                web3_json_rpc.trace_filter_block_range_limit,
+                default_trace_filter_block_range_limit
+            ),
+            ipc_path: load_config!(general_config.api_config, web3_json_rpc.ipc_path),
+            ipc_max_connections: load_optional_config_or_default!(
+                general_config.api_config,
+                web3_json_rpc.ipc_max_connections,
+                default_ipc_max_connections
+            ),
+            ipc_subscriptions_per_connection_limit: load_optional_config_or_default!(
+                general_config.api_config,
+                web3_json_rpc.ipc_subscriptions_per_connection_limit,
+                default_ipc_subscriptions_per_connection_limit
+            ),
+            ipc_max_concurrent_requests_per_connection: load_optional_config_or_default!(
+                general_config.api_config,
+                web3_json_rpc.ipc_max_concurrent_requests_per_connection,
+                default_ipc_max_concurrent_requests_per_connection
+            ),
+            ipc_socket_permissions: load_config!(
+                general_config.api_config,
+                web3_json_rpc.ipc_socket_permissions
+            ),
+            http_compute_units_per_minute_limit: load_config!(
+                general_config.api_config,
+                web3_json_rpc.http_compute_units_per_minute_limit
+            ),
+            http_api_key_compute_units_per_minute_limits: load_config_or_default!(
+                general_config.api_config,
+                web3_json_rpc.http_api_key_compute_units_per_minute_limits,
+                default_compute_units_map
+            ),
+            method_compute_units: load_config_or_default!(
+                general_config.api_config,
+                web3_json_rpc.method_compute_units,
+                default_compute_units_map
+            ),
+            http_trusted_proxies: load_config_or_default!(
+                general_config.api_config,
+                web3_json_rpc.http_trusted_proxies,
+                default_http_trusted_proxies
+            ),
             max_batch_request_size: load_optional_config_or_default!(
                 general_config.api_config,
                 web3_json_rpc.max_batch_request_size,
@@ -592,6 +713,10 @@ impl OptionalENConfig {
                 merkle_tree.stalled_writes_timeout_sec,
                 default_merkle_tree_stalled_writes_timeout_sec
             ),
+            merkle_tree_checkpoint_import_enabled: general_config
+                .db_config
+                .as_ref()
+                .map_or(false, |config| config.merkle_tree.checkpoint_import_enabled),
             database_long_connection_threshold_ms: load_config!(
                 general_config.postgres_config,
                 long_connection_threshold_ms
@@ -640,6 +765,21 @@ impl OptionalENConfig {
                 data_retention_sec,
                 default_pruning_data_retention_sec
             ),
+            pruning_retained_addresses: general_config
+                .pruning
+                .as_ref()
+                .map(|a| a.retained_addresses.clone())
+                .unwrap_or_default(),
+            pruning_retained_topics: general_config
+                .pruning
+                .as_ref()
+                .map(|a| a.retained_topics.clone())
+                .unwrap_or_default(),
+            pruning_retain_storage_history: general_config
+                .pruning
+                .as_ref()
+                .map(|a| a.retain_storage_history)
+                .unwrap_or_default(),
             protective_reads_persistence_enabled: general_config
                 .db_config
                 .as_ref()
@@ -663,6 +803,7 @@ impl OptionalENConfig {
             main_node_rate_limit_rps: enconfig
                 .main_node_rate_limit_rps
                 .unwrap_or_else(Self::default_main_node_rate_limit_rps),
+            main_node_fallback_urls: enconfig.main_node_fallback_urls.clone(),
             api_namespaces,
             contracts_diamond_proxy_addr: None,
             gateway_url: enconfig.gateway_url.clone(),
@@ -752,6 +893,22 @@ impl OptionalENConfig {
         1_024
     }
 
+    const fn default_trace_filter_block_range_limit() -> u64 {
+        100
+    }
+
+    const fn default_ipc_max_connections() -> usize {
+        1_000
+    }
+
+    const fn default_ipc_subscriptions_per_connection_limit() -> usize {
+        1_000
+    }
+
+    const fn default_ipc_max_concurrent_requests_per_connection() -> usize {
+        64
+    }
+
     const fn default_max_batch_request_size() -> usize {
         500 // The default limit is chosen to be reasonably permissive.
     }
@@ -764,6 +921,14 @@ impl OptionalENConfig {
         MaxResponseSizeOverrides::empty()
     }
 
+    fn default_compute_units_map() -> ComputeUnitsMap {
+        ComputeUnitsMap::default()
+    }
+
+    fn default_http_trusted_proxies() -> Vec<IpAddr> {
+        Vec::new()
+    }
+
     const fn default_l2_block_seal_queue_capacity() -> usize {
         10
     }
@@ -892,6 +1057,14 @@ impl OptionalENConfig {
         Duration::from_secs(self.pruning_data_retention_sec)
     }
 
+    pub fn pruning_retention_policy(&self) -> RetentionPolicy {
+        RetentionPolicy {
+            addresses: self.pruning_retained_addresses.clone(),
+            topics: self.pruning_retained_topics.clone(),
+            storage_history: self.pruning_retain_storage_history,
+        }
+    }
+
     #[cfg(test)]
     fn mock() -> Self {
         // Set all values to their defaults
@@ -1049,6 +1222,9 @@ pub(crate) struct ExperimentalENConfig {
     /// This is a temporary flag that will eventually be removed together with version 0 snapshot support.
     #[serde(default)]
     pub snapshots_recovery_drop_storage_key_preimages: bool,
+    /// Path to a local snapshot archive to recover from. If set, snapshot data is read from the archive
+    /// instead of the snapshot recovery object store, and the main node isn't queried during recovery.
+    pub snapshots_recovery_archive_path: Option<PathBuf>,
     /// Approximate chunk size (measured in the number of entries) to recover in a single iteration.
     /// Reasonable values are order of 100,000 (meaning an iteration takes several seconds).
     ///
@@ -1062,6 +1238,16 @@ pub(crate) struct ExperimentalENConfig {
     /// If not set, parallel persistence will be disabled.
     #[serde(default)] // Temporarily use a conservative option (sequential recovery) as default
     pub snapshots_recovery_tree_parallel_persistence_buffer: Option<NonZeroUsize>,
+    /// If set, node state is recovered from data published on L1 instead of a snapshot, starting from the specified
+    /// L1 block (should be the block the diamond proxy contract was deployed in). Has no effect unless snapshot recovery
+    /// is enabled. Requires the diamond proxy address to be set in the local config.
+    pub snapshots_recovery_l1_first_block: Option<u64>,
+    /// Maximum number of L1 blocks to query events for in a single request during recovery from L1.
+    #[serde(default = "ExperimentalENConfig::default_snapshots_recovery_l1_block_range")]
+    pub snapshots_recovery_l1_block_range: NonZeroU64,
+    /// Base URL of a blob archive API (e.g., Blobscan) used during recovery from L1 to fetch blobs with pubdata.
+    /// Required if the chain published pubdata in blobs.
+    pub snapshots_recovery_l1_blob_api_url: Option<SensitiveUrl>,
 
     // Commitment generator
     /// Maximum degree of parallelism during commitment generation, i.e., the maximum number of L1 batches being processed in parallel.
@@ -1078,6 +1264,10 @@ impl ExperimentalENConfig {
         MetadataCalculatorRecoveryConfig::default().desired_chunk_size
     }
 
+    fn default_snapshots_recovery_l1_block_range() -> NonZeroU64 {
+        NonZeroU64::new(50_000).unwrap()
+    }
+
     #[cfg(test)]
     fn mock() -> Self {
         Self {
@@ -1086,8 +1276,12 @@ impl ExperimentalENConfig {
             state_keeper_db_max_open_files: None,
             snapshots_recovery_l1_batch: None,
             snapshots_recovery_drop_storage_key_preimages: false,
+            snapshots_recovery_archive_path: None,
             snapshots_recovery_tree_chunk_size: Self::default_snapshots_recovery_tree_chunk_size(),
             snapshots_recovery_tree_parallel_persistence_buffer: None,
+            snapshots_recovery_l1_first_block: None,
+            snapshots_recovery_l1_block_range: Self::default_snapshots_recovery_l1_block_range(),
+            snapshots_recovery_l1_blob_api_url: None,
             commitment_generator_max_parallelism: None,
         }
     }
@@ -1122,6 +1316,24 @@ impl ExperimentalENConfig {
                 .snapshot_recovery
                 .as_ref()
                 .map_or(false, |config| config.drop_storage_key_preimages),
+            snapshots_recovery_archive_path: load_config!(
+                general_config.snapshot_recovery,
+                archive_path
+            )
+            .map(PathBuf::from),
+            snapshots_recovery_l1_first_block: general_config
+                .snapshot_recovery
+                .as_ref()
+                .and_then(|config| Some(config.l1.as_ref()?.first_l1_block)),
+            snapshots_recovery_l1_block_range: general_config
+                .snapshot_recovery
+                .as_ref()
+                .and_then(|config| config.l1.as_ref()?.l1_block_range)
+                .unwrap_or_else(Self::default_snapshots_recovery_l1_block_range),
+            snapshots_recovery_l1_blob_api_url: general_config
+                .snapshot_recovery
+                .as_ref()
+                .and_then(|config| config.l1.as_ref()?.blob_api_url.clone()),
             commitment_generator_max_parallelism: general_config
                 .commitment_generator
                 .as_ref()
@@ -1327,7 +1539,12 @@ impl ExternalNodeConfig<()> {
                 returned by main node: {remote_diamond_proxy_addr:?}"
             );
         }
-        Ok(ExternalNodeConfig {
+        Ok(self.with_remote(remote))
+    }
+
+    /// Completes the configuration with the provided remote part.
+    pub fn with_remote(self, remote: RemoteENConfig) -> ExternalNodeConfig {
+        ExternalNodeConfig {
             required: self.required,
             postgres: self.postgres,
             optional: self.optional,
@@ -1338,7 +1555,7 @@ impl ExternalNodeConfig<()> {
             api_component: self.api_component,
             consensus_secrets: self.consensus_secrets,
             remote,
-        })
+        }
     }
 }
 
@@ -1397,6 +1614,7 @@ impl From<&ExternalNodeConfig> for InternalApiConfig {
             l2_testnet_paymaster_addr: config.remote.l2_testnet_paymaster_addr,
             req_entities_limit: config.optional.req_entities_limit,
             fee_history_limit: config.optional.fee_history_limit,
+            trace_filter_block_range_limit: config.optional.trace_filter_block_range_limit,
             base_token_address: Some(config.remote.base_token_addr),
             filters_disabled: config.optional.filters_disabled,
             dummy_verifier: config.remote.dummy_verifier,
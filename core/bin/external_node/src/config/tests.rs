@@ -100,6 +100,7 @@ fn parsing_optional_config_from_empty_env() {
         config.l1_batch_commit_data_generator_mode,
         L1BatchCommitmentMode::Rollup
     );
+    assert!(config.main_node_fallback_urls.is_empty());
 }
 
 #[test]
@@ -125,6 +126,10 @@ fn parsing_optional_config_from_env() {
             "zks_getProof=100,eth_call=2",
         ),
         ("EN_L1_BATCH_COMMIT_DATA_GENERATOR_MODE", "Validium"),
+        (
+            "EN_MAIN_NODE_FALLBACK_URLS",
+            "http://en-1.local:3060/,http://en-2.local:3060/",
+        ),
     ];
     let env_vars = env_vars
         .into_iter()
@@ -170,6 +175,15 @@ fn parsing_optional_config_from_env() {
         config.l1_batch_commit_data_generator_mode,
         L1BatchCommitmentMode::Validium
     );
+    let fallback_urls: Vec<_> = config
+        .main_node_fallback_urls
+        .iter()
+        .map(SensitiveUrl::expose_str)
+        .collect();
+    assert_eq!(
+        fallback_urls,
+        ["http://en-1.local:3060/", "http://en-2.local:3060/"]
+    );
 }
 
 #[test]
@@ -3,9 +3,11 @@ use std::{collections::HashSet, str::FromStr};
 use anyhow::Context as _;
 use clap::Parser;
 use node_builder::ExternalNodeBuilder;
-use zksync_web3_decl::client::{Client, DynClient, L2};
+use zksync_node_framework::service::WiringGraphFormat;
+use zksync_types::url::SensitiveUrl;
+use zksync_web3_decl::client::{Client, DynClient, FailoverClient, L2};
 
-use crate::config::{generate_consensus_secrets, ExternalNodeConfig};
+use crate::config::{generate_consensus_secrets, ExternalNodeConfig, RemoteENConfig};
 
 mod config;
 mod metadata;
@@ -58,6 +60,11 @@ struct Cli {
         requires = "enable_consensus"
     )]
     consensus_path: Option<std::path::PathBuf>,
+    /// Instead of running the node, prints its wiring graph (i.e., resources and tasks consumed and produced
+    /// by wiring layers) in the specified format (`json` or `dot`), and validates it.
+    /// Note that the remote part of the node config is still fetched from the main node.
+    #[arg(long)]
+    dump_wiring_graph: Option<WiringGraphFormat>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Hash, Eq)]
@@ -160,19 +167,45 @@ fn main() -> anyhow::Result<()> {
     // Build L1 and L2 clients.
     let main_node_url = &config.required.main_node_url;
     tracing::info!("Main node URL is: {main_node_url:?}");
-    let main_node_client = Client::http(main_node_url.clone())
-        .context("failed creating JSON-RPC client for main node")?
-        .for_network(config.required.l2_chain_id.into())
-        .with_allowed_requests_per_second(config.optional.main_node_rate_limit_rps)
-        .build();
-    let main_node_client = Box::new(main_node_client) as Box<DynClient<L2>>;
+    let build_main_node_client = |url: &SensitiveUrl| {
+        let client = Client::http(url.clone())?
+            .for_network(config.required.l2_chain_id.into())
+            .with_allowed_requests_per_second(config.optional.main_node_rate_limit_rps)
+            .build();
+        anyhow::Ok(Box::new(client) as Box<DynClient<L2>>)
+    };
+    let mut main_node_client = build_main_node_client(main_node_url)
+        .context("failed creating JSON-RPC client for main node")?;
+    let fallback_urls = &config.optional.main_node_fallback_urls;
+    if !fallback_urls.is_empty() {
+        tracing::info!("Main node fallback URLs are: {fallback_urls:?}");
+        let mut upstreams = vec![main_node_client];
+        for url in fallback_urls {
+            upstreams.push(
+                build_main_node_client(url)
+                    .context("failed creating JSON-RPC client for main node fallback")?,
+            );
+        }
+        main_node_client = Box::new(FailoverClient::new(upstreams));
+    }
 
-    let config = runtime
-        .block_on(config.fetch_remote(main_node_client.as_ref()))
-        .context("failed fetching remote part of node config from main node")?;
+    // The wiring graph doesn't depend on the remote config, so it can be dumped without contacting the main node.
+    let config = if opt.dump_wiring_graph.is_some() {
+        config.with_remote(RemoteENConfig::placeholder())
+    } else {
+        runtime
+            .block_on(config.fetch_remote(main_node_client.as_ref()))
+            .context("failed fetching remote part of node config from main node")?
+    };
 
     let node = ExternalNodeBuilder::on_runtime(runtime, config)
         .build(opt.components.0.into_iter().collect())?;
+    if let Some(format) = opt.dump_wiring_graph {
+        let graph = node.wiring_graph();
+        println!("{}", graph.render(format));
+        graph.validate()?;
+        return Ok(());
+    }
     node.run(guard)?;
     anyhow::Ok(())
 }
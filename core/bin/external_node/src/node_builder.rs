@@ -5,14 +5,19 @@ use anyhow::Context as _;
 use zksync_block_reverter::NodeRole;
 use zksync_config::{
     configs::{
-        api::{HealthCheckConfig, MerkleTreeApiConfig},
-        database::MerkleTreeMode,
+        api::{parse_socket_permissions, HealthCheckConfig, MerkleTreeApiConfig},
+        database::{MerkleTreeMode, MerkleTreeStorage},
         DatabaseSecrets,
     },
     PostgresConfig,
 };
-use zksync_metadata_calculator::{MetadataCalculatorConfig, MetadataCalculatorRecoveryConfig};
-use zksync_node_api_server::{tx_sender::ApiContracts, web3::Namespace};
+use zksync_metadata_calculator::{
+    MetadataCalculatorCheckpointsConfig, MetadataCalculatorConfig, MetadataCalculatorRecoveryConfig,
+};
+use zksync_node_api_server::{
+    tx_sender::ApiContracts,
+    web3::{HttpRateLimits, IpcConfig, Namespace},
+};
 use zksync_node_framework::{
     implementations::layers::{
         batch_status_updater::BatchStatusUpdaterLayer,
@@ -27,7 +32,9 @@ use zksync_node_framework::{
         main_node_fee_params_fetcher::MainNodeFeeParamsFetcherLayer,
         metadata_calculator::MetadataCalculatorLayer,
         node_storage_init::{
-            external_node_strategy::{ExternalNodeInitStrategyLayer, SnapshotRecoveryConfig},
+            external_node_strategy::{
+                ExternalNodeInitStrategyLayer, L1RecoveryConfig, SnapshotRecoveryConfig,
+            },
             NodeStorageInitializerLayer,
         },
         pools_layer::PoolsLayerBuilder,
@@ -142,7 +149,8 @@ impl ExternalNodeBuilder {
             self.config.required.main_node_url.clone(),
             self.config.optional.main_node_rate_limit_rps,
             self.config.required.l2_chain_id,
-        );
+        )
+        .with_fallback_urls(self.config.optional.main_node_fallback_urls.clone());
         self.node.add_layer(layer);
         Ok(self)
     }
@@ -160,6 +168,8 @@ impl ExternalNodeBuilder {
                 .optional
                 .healthcheck_hard_time_limit()
                 .map(|d| d.as_millis() as u64),
+            // The admin server isn't supported by external nodes yet.
+            admin_port: None,
         };
         self.node.add_layer(HealthCheckLayer(healthcheck_config));
         Ok(self)
@@ -206,12 +216,10 @@ impl ExternalNodeBuilder {
 
         let io_layer = ExternalIOLayer::new(self.config.required.l2_chain_id);
 
-        // We only need call traces on the external node if the `debug_` namespace is enabled.
-        let save_call_traces = self
-            .config
-            .optional
-            .api_namespaces()
-            .contains(&Namespace::Debug);
+        // We only need call traces on the external node if the `debug_` or `trace_` namespace is enabled.
+        let api_namespaces = self.config.optional.api_namespaces();
+        let save_call_traces = api_namespaces.contains(&Namespace::Debug)
+            || api_namespaces.contains(&Namespace::Trace);
         let main_node_batch_executor_builder_layer =
             MainBatchExecutorLayer::new(save_call_traces, OPTIONAL_BYTECODE_COMPRESSION);
 
@@ -254,7 +262,8 @@ impl ExternalNodeBuilder {
                 self.config.optional.pruning_removal_delay(),
                 self.config.optional.pruning_chunk_size,
                 self.config.optional.pruning_data_retention(),
-            );
+            )
+            .with_retention_policy(self.config.optional.pruning_retention_policy());
             self.node.add_layer(layer);
         } else {
             tracing::info!("Pruning is disabled");
@@ -326,6 +335,7 @@ impl ExternalNodeBuilder {
             db_path: self.config.required.merkle_tree_path.clone(),
             max_open_files: self.config.optional.merkle_tree_max_open_files,
             mode: MerkleTreeMode::Lightweight,
+            storage: MerkleTreeStorage::RocksDB,
             delay_interval: self.config.optional.merkle_tree_processing_delay(),
             max_l1_batches_per_iter: self.config.optional.merkle_tree_max_l1_batches_per_iter,
             multi_get_chunk_size: self.config.optional.merkle_tree_multi_get_chunk_size,
@@ -347,10 +357,25 @@ impl ExternalNodeBuilder {
                     .experimental
                     .snapshots_recovery_tree_parallel_persistence_buffer,
             },
+            checkpoints: MetadataCalculatorCheckpointsConfig {
+                export_interval: None,
+                import_enabled: self.config.optional.merkle_tree_checkpoint_import_enabled,
+            },
         };
 
         // Configure basic tree layer.
         let mut layer = MetadataCalculatorLayer::new(metadata_calculator_config);
+        if self.config.optional.merkle_tree_checkpoint_import_enabled {
+            let object_store_config = self
+                .config
+                .optional
+                .snapshots_recovery_object_store
+                .clone()
+                .context(
+                    "Merkle tree checkpoint import requires snapshot recovery object store config",
+                )?;
+            layer = layer.with_checkpoint_object_store_config(object_store_config);
+        }
 
         // Add tree API if needed.
         if with_tree_api {
@@ -434,12 +459,26 @@ impl ExternalNodeBuilder {
             pruning_info_refresh_interval: Some(pruning_info_refresh_interval),
             polling_interval: Some(self.config.optional.polling_interval()),
             websocket_requests_per_minute_limit: None, // To be set by WS server layer method if required.
-            replication_lag_limit: None,               // TODO: Support replication lag limit
+            http_rate_limits: None, // To be set by HTTP server layer method if required.
+            ipc_config: None,       // To be set by IPC server layer method if required.
+            replication_lag_limit: None, // TODO: Support replication lag limit
         }
     }
 
     fn add_http_web3_api_layer(mut self) -> anyhow::Result<Self> {
-        let optional_config = self.web3_api_optional_config();
+        let mut optional_config = self.web3_api_optional_config();
+        let en_config = &self.config.optional;
+        optional_config.http_rate_limits =
+            en_config
+                .http_compute_units_per_minute_limit
+                .map(|limit| HttpRateLimits {
+                    compute_units_per_minute: limit,
+                    api_key_limits: en_config
+                        .http_api_key_compute_units_per_minute_limits
+                        .clone(),
+                    method_compute_units: en_config.method_compute_units.clone(),
+                    trusted_proxies: en_config.http_trusted_proxies.iter().copied().collect(),
+                });
         self.node.add_layer(Web3ServerLayer::http(
             self.config.required.http_port,
             (&self.config).into(),
@@ -452,6 +491,31 @@ impl ExternalNodeBuilder {
     fn add_ws_web3_api_layer(mut self) -> anyhow::Result<Self> {
         // TODO: Support websocket requests per minute limit
         let optional_config = self.web3_api_optional_config();
+        // The IPC server is run together with the WS server since it supports the same functionality.
+        if let Some(ipc_path) = &self.config.optional.ipc_path {
+            let en_config = &self.config.optional;
+            let socket_permissions = en_config
+                .ipc_socket_permissions
+                .as_deref()
+                .map(parse_socket_permissions)
+                .transpose()
+                .context("ipc_socket_permissions")?;
+            let ipc_config = IpcConfig {
+                max_connections: en_config.ipc_max_connections,
+                max_subscriptions_per_connection: en_config.ipc_subscriptions_per_connection_limit,
+                max_concurrent_requests_per_connection: en_config
+                    .ipc_max_concurrent_requests_per_connection,
+                socket_permissions,
+            };
+            self.node.add_layer(Web3ServerLayer::ipc(
+                ipc_path.clone(),
+                (&self.config).into(),
+                Web3ServerOptionalConfig {
+                    ipc_config: Some(ipc_config),
+                    ..optional_config.clone()
+                },
+            ));
+        }
         self.node.add_layer(Web3ServerLayer::ws(
             self.config.required.ws_port,
             (&self.config).into(),
@@ -493,17 +557,36 @@ impl ExternalNodeBuilder {
     /// the precondition will prevent node from starting until the database is initialized.
     fn add_storage_initialization_layer(mut self, kind: LayerKind) -> anyhow::Result<Self> {
         let config = &self.config;
-        let snapshot_recovery_config =
-            config
-                .optional
-                .snapshots_recovery_enabled
-                .then_some(SnapshotRecoveryConfig {
-                    snapshot_l1_batch_override: config.experimental.snapshots_recovery_l1_batch,
-                    drop_storage_key_preimages: config
-                        .experimental
-                        .snapshots_recovery_drop_storage_key_preimages,
-                    object_store_config: config.optional.snapshots_recovery_object_store.clone(),
-                });
+        let l1_recovery_first_block = config
+            .experimental
+            .snapshots_recovery_l1_first_block
+            .filter(|_| config.optional.snapshots_recovery_enabled);
+        let l1_recovery_config = match l1_recovery_first_block {
+            Some(first_l1_block) => {
+                // The diamond proxy address reported by the main node cannot be trusted.
+                let diamond_proxy_addr = config.optional.contracts_diamond_proxy_addr.context(
+                    "Recovery from L1 requires the diamond proxy address to be set in the local config",
+                )?;
+                Some(L1RecoveryConfig {
+                    diamond_proxy_addr,
+                    first_l1_block,
+                    l1_block_range: config.experimental.snapshots_recovery_l1_block_range.get(),
+                    l1_batch: config.experimental.snapshots_recovery_l1_batch,
+                    tree_path: format!("{}_l1_recovery", config.required.merkle_tree_path).into(),
+                })
+            }
+            None => None,
+        };
+        let snapshot_recovery_config = (config.optional.snapshots_recovery_enabled
+            && l1_recovery_config.is_none())
+        .then_some(SnapshotRecoveryConfig {
+            snapshot_l1_batch_override: config.experimental.snapshots_recovery_l1_batch,
+            drop_storage_key_preimages: config
+                .experimental
+                .snapshots_recovery_drop_storage_key_preimages,
+            object_store_config: config.optional.snapshots_recovery_object_store.clone(),
+            archive_path: config.experimental.snapshots_recovery_archive_path.clone(),
+        });
         self.node.add_layer(ExternalNodeInitStrategyLayer {
             l2_chain_id: self.config.required.l2_chain_id,
             max_postgres_concurrency: self
@@ -511,6 +594,12 @@ impl ExternalNodeBuilder {
                 .optional
                 .snapshots_recovery_postgres_max_concurrency,
             snapshot_recovery_config,
+            l1_recovery_config,
+            l1_recovery_blob_api_url: self
+                .config
+                .experimental
+                .snapshots_recovery_l1_blob_api_url
+                .clone(),
         });
         let mut layer = NodeStorageInitializerLayer::new();
         if matches!(kind, LayerKind::Precondition) {
@@ -9,8 +9,9 @@ use zksync_node_framework::{
             sigint::SigintHandlerLayer,
         },
         resources::{
-            eth_interface::EthInterfaceResource, healthcheck::AppHealthCheckResource,
-            main_node_client::MainNodeClientResource,
+            eth_interface::EthInterfaceResource,
+            healthcheck::AppHealthCheckResource,
+            main_node_client::{ConsistentMainNodeClientResource, MainNodeClientResource},
         },
     },
     service::ServiceContext,
@@ -146,10 +147,16 @@ struct MockL2ClientLayer {
     client: MockClient<L2>,
 }
 
+#[derive(Debug, IntoContext)]
+struct MockL2ClientOutput {
+    main_node_client: MainNodeClientResource,
+    consistent_main_node_client: ConsistentMainNodeClientResource,
+}
+
 #[async_trait::async_trait]
 impl WiringLayer for MockL2ClientLayer {
     type Input = ();
-    type Output = MainNodeClientResource;
+    type Output = MockL2ClientOutput;
 
     fn layer_name(&self) -> &'static str {
         // We don't care about values, we just want to hijack the layer name.
@@ -162,6 +169,9 @@ impl WiringLayer for MockL2ClientLayer {
     }
 
     async fn wire(self, _: Self::Input) -> Result<Self::Output, WiringError> {
-        Ok(MainNodeClientResource(Box::new(self.client)))
+        Ok(MockL2ClientOutput {
+            main_node_client: MainNodeClientResource(Box::new(self.client.clone())),
+            consistent_main_node_client: ConsistentMainNodeClientResource(Box::new(self.client)),
+        })
     }
 }
@@ -6,12 +6,12 @@ use anyhow::Context as _;
 use tokio::sync::Semaphore;
 use zksync_config::SnapshotsCreatorConfig;
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal, DalResult};
-use zksync_object_store::{ObjectStore, StoredObject};
+use zksync_object_store::{ObjectStore, ObjectStoreError, StoredObject};
 use zksync_types::{
     snapshots::{
         uniform_hashed_keys_chunk, SnapshotFactoryDependencies, SnapshotFactoryDependency,
-        SnapshotMetadata, SnapshotStorageLog, SnapshotStorageLogsChunk,
-        SnapshotStorageLogsStorageKey, SnapshotVersion,
+        SnapshotHeader, SnapshotMetadata, SnapshotStorageLog, SnapshotStorageLogsChunk,
+        SnapshotStorageLogsChunkMetadata, SnapshotStorageLogsStorageKey, SnapshotVersion,
     },
     L1BatchNumber, L2BlockNumber,
 };
@@ -20,11 +20,20 @@ use crate::metrics::{FactoryDepsStage, StorageChunkStage, METRICS};
 #[cfg(test)]
 use crate::tests::HandleEvent;
 
+/// Base of a delta snapshot, i.e. the snapshot that the delta is applied on top of.
+#[derive(Debug, Clone, Copy)]
+struct SnapshotBase {
+    l1_batch_number: L1BatchNumber,
+    l2_block_number: L2BlockNumber,
+}
+
 /// Encapsulates progress of creating a particular storage snapshot.
 #[derive(Debug)]
 struct SnapshotProgress {
     version: SnapshotVersion,
     l1_batch_number: L1BatchNumber,
+    /// Base snapshot for delta snapshots; `None` for full snapshots.
+    base: Option<SnapshotBase>,
     /// `true` if the snapshot is new (i.e., its progress is not recovered from Postgres).
     is_new_snapshot: bool,
     chunk_count: u64,
@@ -32,17 +41,23 @@ struct SnapshotProgress {
 }
 
 impl SnapshotProgress {
-    fn new(version: SnapshotVersion, l1_batch_number: L1BatchNumber, chunk_count: u64) -> Self {
+    fn new(
+        version: SnapshotVersion,
+        l1_batch_number: L1BatchNumber,
+        base: Option<SnapshotBase>,
+        chunk_count: u64,
+    ) -> Self {
         Self {
             version,
             l1_batch_number,
+            base,
             is_new_snapshot: true,
             chunk_count,
             remaining_chunk_ids: (0..chunk_count).collect(),
         }
     }
 
-    fn from_existing_snapshot(snapshot: &SnapshotMetadata) -> Self {
+    fn from_existing_snapshot(snapshot: &SnapshotMetadata, base: Option<SnapshotBase>) -> Self {
         let remaining_chunk_ids = snapshot
             .storage_logs_filepaths
             .iter()
@@ -53,6 +68,7 @@ impl SnapshotProgress {
         Self {
             version: snapshot.version,
             l1_batch_number: snapshot.l1_batch_number,
+            base,
             is_new_snapshot: false,
             chunk_count: snapshot.storage_logs_filepaths.len() as u64,
             remaining_chunk_ids,
@@ -100,6 +116,10 @@ impl SnapshotCreator {
             METRICS.storage_logs_processing_duration[&StorageChunkStage::LoadFromPostgres].start();
         let (output_filepath, latency) = match progress.version {
             SnapshotVersion::Version0 => {
+                anyhow::ensure!(
+                    progress.base.is_none(),
+                    "delta snapshots are not supported for snapshot version 0"
+                );
                 #[allow(deprecated)] // support of version 0 snapshots will be removed eventually
                 let logs = conn
                     .snapshots_creator_dal()
@@ -121,11 +141,20 @@ impl SnapshotCreator {
                     .await?
             }
             SnapshotVersion::Version1 => {
-                let logs = conn
-                    .snapshots_creator_dal()
-                    .get_storage_logs_chunk(l2_block_number, l1_batch_number, hashed_keys_range)
+                let mut dal = conn.snapshots_creator_dal();
+                let logs = if let Some(base) = progress.base {
+                    dal.get_modified_storage_logs_chunk(
+                        base.l2_block_number,
+                        l2_block_number,
+                        l1_batch_number,
+                        hashed_keys_range,
+                    )
                     .await
-                    .context("error fetching storage logs")?;
+                } else {
+                    dal.get_storage_logs_chunk(l2_block_number, l1_batch_number, hashed_keys_range)
+                        .await
+                };
+                let logs = logs.context("error fetching storage logs")?;
                 drop(conn);
 
                 let latency = latency.observe();
@@ -188,6 +217,7 @@ impl SnapshotCreator {
 
     async fn process_factory_deps(
         &self,
+        base: Option<SnapshotBase>,
         l2_block_number: L2BlockNumber,
         l1_batch_number: L1BatchNumber,
     ) -> anyhow::Result<String> {
@@ -196,10 +226,13 @@ impl SnapshotCreator {
         tracing::info!("Loading factory deps from Postgres...");
         let latency =
             METRICS.factory_deps_processing_duration[&FactoryDepsStage::LoadFromPostgres].start();
-        let factory_deps = conn
-            .snapshots_creator_dal()
-            .get_all_factory_deps(l2_block_number)
-            .await?;
+        let mut dal = conn.snapshots_creator_dal();
+        let factory_deps = if let Some(base) = base {
+            dal.get_new_factory_deps(base.l2_block_number, l2_block_number)
+                .await?
+        } else {
+            dal.get_all_factory_deps(l2_block_number).await?
+        };
         drop(conn);
         let latency = latency.observe();
         tracing::info!("Loaded {} factory deps in {latency:?}", factory_deps.len());
@@ -232,6 +265,96 @@ impl SnapshotCreator {
         Ok(output_filepath)
     }
 
+    /// Persists the header of a complete snapshot to the object store, so that the snapshot
+    /// can be verified without access to Postgres.
+    async fn persist_snapshot_header(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        l2_block_number: L2BlockNumber,
+    ) -> anyhow::Result<()> {
+        let mut master_conn = self
+            .master_pool
+            .connection_tagged("snapshots_creator")
+            .await?;
+        let snapshot = master_conn
+            .snapshots_dal()
+            .get_snapshot_metadata(l1_batch_number)
+            .await?
+            .with_context(|| format!("snapshot for L1 batch #{l1_batch_number} disappeared"))?;
+        drop(master_conn);
+
+        if !snapshot.is_complete() {
+            // Can happen if the creator was interrupted in tests.
+            tracing::warn!("Snapshot is incomplete: {snapshot:?}; not persisting its header");
+            return Ok(());
+        }
+        let storage_logs_chunks = snapshot
+            .storage_logs_filepaths
+            .into_iter()
+            .enumerate()
+            .filter_map(|(chunk_id, filepath)| {
+                Some(SnapshotStorageLogsChunkMetadata {
+                    chunk_id: chunk_id as u64,
+                    filepath: filepath?,
+                })
+            })
+            .collect();
+        let header = SnapshotHeader {
+            version: snapshot.version.into(),
+            l1_batch_number,
+            l2_block_number,
+            base_l1_batch_number: snapshot.base_l1_batch_number,
+            storage_logs_chunks,
+            factory_deps_filepath: snapshot.factory_deps_filepath,
+        };
+        let filename = self
+            .blob_store
+            .put(l1_batch_number, &header)
+            .await
+            .context("Error storing snapshot header in blob store")?;
+        tracing::info!("Saved snapshot header to location: {filename}");
+        Ok(())
+    }
+
+    /// Persists the header of the latest complete snapshot if it's missing in the object store. This can happen
+    /// if the creator was interrupted after the snapshot was marked as complete in Postgres (which happens
+    /// once the last storage logs chunk is saved), but before the header was persisted.
+    async fn restore_missing_snapshot_header(&self) -> anyhow::Result<()> {
+        let mut master_conn = self
+            .master_pool
+            .connection_tagged("snapshots_creator")
+            .await?;
+        let complete_snapshots = master_conn
+            .snapshots_dal()
+            .get_all_complete_snapshots()
+            .await?
+            .snapshots_l1_batch_numbers;
+        drop(master_conn);
+        // Snapshots are ordered by descending L1 batch number.
+        let Some(&l1_batch_number) = complete_snapshots.first() else {
+            return Ok(());
+        };
+
+        match self.blob_store.get::<SnapshotHeader>(l1_batch_number).await {
+            Ok(_) => return Ok(()),
+            Err(ObjectStoreError::KeyNotFound(_)) => { /* continue */ }
+            Err(err) => {
+                return Err(anyhow::Error::from(err).context(format!(
+                    "failed fetching header for snapshot for L1 batch #{l1_batch_number}"
+                )));
+            }
+        }
+
+        tracing::warn!(
+            "Header for complete snapshot for L1 batch #{l1_batch_number} is missing in the object store; restoring it"
+        );
+        let mut conn = self.connect_to_replica().await?;
+        let l2_block_number = Self::last_l2_block_in_batch(l1_batch_number, &mut conn).await?;
+        drop(conn);
+        self.persist_snapshot_header(l1_batch_number, l2_block_number)
+            .await
+    }
+
     /// Returns `Ok(None)` if the created snapshot would coincide with `latest_snapshot`.
     async fn initialize_snapshot_progress(
         config: &SnapshotsCreatorConfig,
@@ -254,13 +377,24 @@ impl SnapshotCreator {
                 )
             })?;
 
-        let distinct_storage_logs_keys_count = conn
-            .snapshots_creator_dal()
-            .get_distinct_storage_logs_keys_count(l1_batch_number)
-            .await?;
+        let base = if snapshot_version == SnapshotVersion::Version1 {
+            Self::select_delta_base(config, l1_batch_number, conn).await?
+        } else {
+            None
+        };
+        let storage_logs_keys_count = if let Some(base) = base {
+            let l2_block_number = Self::last_l2_block_in_batch(l1_batch_number, conn).await?;
+            conn.snapshots_creator_dal()
+                .get_modified_storage_logs_keys_count(base.l2_block_number, l2_block_number)
+                .await?
+        } else {
+            conn.snapshots_creator_dal()
+                .get_distinct_storage_logs_keys_count(l1_batch_number)
+                .await?
+        };
         let chunk_size = config.storage_logs_chunk_size;
         // We force the minimum number of chunks to avoid situations where only one chunk is created in tests.
-        let chunk_count = distinct_storage_logs_keys_count
+        let chunk_count = storage_logs_keys_count
             .div_ceil(chunk_size)
             .max(min_chunk_count);
 
@@ -271,10 +405,88 @@ impl SnapshotCreator {
         Ok(Some(SnapshotProgress::new(
             snapshot_version,
             l1_batch_number,
+            base,
             chunk_count,
         )))
     }
 
+    async fn last_l2_block_in_batch(
+        l1_batch_number: L1BatchNumber,
+        conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<L2BlockNumber> {
+        let (_, last_l2_block_number) = conn
+            .blocks_dal()
+            .get_l2_block_range_of_l1_batch(l1_batch_number)
+            .await?
+            .with_context(|| format!("No L2 blocks for L1 batch #{l1_batch_number}"))?;
+        Ok(last_l2_block_number)
+    }
+
+    /// Selects the base for a delta snapshot, or returns `Ok(None)` if a full snapshot should be created.
+    async fn select_delta_base(
+        config: &SnapshotsCreatorConfig,
+        l1_batch_number: L1BatchNumber,
+        conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<Option<SnapshotBase>> {
+        if config.max_delta_chain_length == 0 {
+            return Ok(None);
+        }
+
+        let complete_snapshots = conn
+            .snapshots_dal()
+            .get_all_complete_snapshots()
+            .await?
+            .snapshots_l1_batch_numbers;
+        // Snapshots are ordered by descending L1 batch number.
+        let Some(&base_l1_batch_number) = complete_snapshots
+            .iter()
+            .find(|&&number| number < l1_batch_number)
+        else {
+            tracing::info!("No complete snapshots before L1 batch #{l1_batch_number}; creating a full snapshot");
+            return Ok(None);
+        };
+
+        // Compute the number of deltas in the chain ending with the base snapshot.
+        let mut delta_count = 0_u32;
+        let mut current = base_l1_batch_number;
+        loop {
+            let snapshot = conn
+                .snapshots_dal()
+                .get_snapshot_metadata(current)
+                .await?
+                .with_context(|| format!("snapshot for L1 batch #{current} disappeared"))?;
+            if snapshot.version != SnapshotVersion::Version1 {
+                tracing::info!(
+                    "Snapshot chain ending with L1 batch #{base_l1_batch_number} contains snapshot {snapshot:?} \
+                     with unsupported version; creating a full snapshot"
+                );
+                return Ok(None);
+            }
+            let Some(base) = snapshot.base_l1_batch_number else {
+                break;
+            };
+            delta_count += 1;
+            current = base;
+        }
+
+        if delta_count >= config.max_delta_chain_length {
+            tracing::info!(
+                "Snapshot chain ending with L1 batch #{base_l1_batch_number} already contains {delta_count} deltas \
+                 (max: {}); creating a full snapshot",
+                config.max_delta_chain_length
+            );
+            return Ok(None);
+        }
+        tracing::info!(
+            "Creating delta snapshot on top of snapshot for L1 batch #{base_l1_batch_number} \
+             ({delta_count} deltas in its chain)"
+        );
+        Ok(Some(SnapshotBase {
+            l1_batch_number: base_l1_batch_number,
+            l2_block_number: Self::last_l2_block_in_batch(base_l1_batch_number, conn).await?,
+        }))
+    }
+
     /// Returns `Ok(None)` if a snapshot should not be created / resumed.
     async fn load_or_initialize_snapshot_progress(
         &self,
@@ -318,7 +530,22 @@ impl SnapshotCreator {
                 Ok(None)
             }
             Some(snapshot) if config.l1_batch_number.is_some() => {
-                Ok(Some(SnapshotProgress::from_existing_snapshot(&snapshot)))
+                let base = if let Some(base_l1_batch_number) = snapshot.base_l1_batch_number {
+                    let mut conn = self.connect_to_replica().await?;
+                    Some(SnapshotBase {
+                        l1_batch_number: base_l1_batch_number,
+                        l2_block_number: Self::last_l2_block_in_batch(
+                            base_l1_batch_number,
+                            &mut conn,
+                        )
+                        .await?,
+                    })
+                } else {
+                    None
+                };
+                Ok(Some(SnapshotProgress::from_existing_snapshot(
+                    &snapshot, base,
+                )))
             }
             Some(snapshot) => {
                 // Unless creating a snapshot for a specific L1 batch is requested, we never continue an existing snapshot, even if it's incomplete.
@@ -352,6 +579,7 @@ impl SnapshotCreator {
         );
         let latency = METRICS.snapshot_generation_duration.start();
 
+        self.restore_missing_snapshot_header().await?;
         let Some(progress) = self
             .load_or_initialize_snapshot_progress(&config, min_chunk_count)
             .await?
@@ -361,11 +589,8 @@ impl SnapshotCreator {
         };
 
         let mut conn = self.connect_to_replica().await?;
-        let (_, last_l2_block_number_in_batch) = conn
-            .blocks_dal()
-            .get_l2_block_range_of_l1_batch(progress.l1_batch_number)
-            .await?
-            .context("No L2 blocks for L1 batch")?;
+        let last_l2_block_number_in_batch =
+            Self::last_l2_block_in_batch(progress.l1_batch_number, &mut conn).await?;
         drop(conn);
 
         METRICS.storage_logs_chunks_count.set(progress.chunk_count);
@@ -377,22 +602,36 @@ impl SnapshotCreator {
 
         if progress.is_new_snapshot {
             let factory_deps_output_file = self
-                .process_factory_deps(last_l2_block_number_in_batch, progress.l1_batch_number)
+                .process_factory_deps(
+                    progress.base,
+                    last_l2_block_number_in_batch,
+                    progress.l1_batch_number,
+                )
                 .await?;
 
             let mut master_conn = self
                 .master_pool
                 .connection_tagged("snapshots_creator")
                 .await?;
-            master_conn
-                .snapshots_dal()
-                .add_snapshot(
+            let mut dal = master_conn.snapshots_dal();
+            if let Some(base) = progress.base {
+                dal.add_delta_snapshot(
                     progress.version,
                     progress.l1_batch_number,
+                    base.l1_batch_number,
                     progress.chunk_count,
                     &factory_deps_output_file,
                 )
                 .await?;
+            } else {
+                dal.add_snapshot(
+                    progress.version,
+                    progress.l1_batch_number,
+                    progress.chunk_count,
+                    &factory_deps_output_file,
+                )
+                .await?;
+            }
         }
 
         METRICS
@@ -412,6 +651,8 @@ impl SnapshotCreator {
                 )
             });
         futures::future::try_join_all(tasks).await?;
+        self.persist_snapshot_header(progress.l1_batch_number, last_l2_block_number_in_batch)
+            .await?;
 
         METRICS
             .snapshot_l1_batch
@@ -8,22 +8,43 @@
 //!
 //! It is assumed that the snapshot creator is run as a singleton process (no more than 1 instance
 //! at a time).
+//!
+//! # Verification
+//!
+//! The `verify` subcommand checks a snapshot persisted in the object store without creating new snapshots.
+//! It recovers the Merkle tree from the snapshot storage logs and compares its root hash with the expected one,
+//! so it can be used as a gate before publishing a snapshot.
+//!
+//! # Export
+//!
+//! The `export` subcommand packs a snapshot persisted in the object store into a portable single-file archive
+//! (see [`SnapshotArchive`]), which can be used to bootstrap nodes without access to the object store or the main node.
+//! Besides snapshot data, the archive contains details of the snapshot L1 batch and L2 block, and tokens, which are loaded
+//! from Postgres.
+
+use std::{path::PathBuf, sync::Arc};
 
 use anyhow::Context as _;
 use structopt::StructOpt;
 use tokio::{sync::watch, task::JoinHandle};
-use zksync_config::configs::PrometheusConfig;
+use zksync_config::{
+    configs::{DatabaseSecrets, PrometheusConfig},
+    SnapshotsCreatorConfig,
+};
 use zksync_core_leftovers::temp_config_store::{load_database_secrets, load_general_config};
-use zksync_dal::{ConnectionPool, Core};
-use zksync_object_store::ObjectStoreFactory;
+use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
+use zksync_merkle_tree::{PatchSet, RocksDBWrapper};
+use zksync_object_store::{ObjectStore, ObjectStoreFactory, SnapshotArchive, SnapshotRecoveryData};
+use zksync_types::{block::L1BatchTreeData, snapshots::SnapshotHeader, L1BatchNumber, H256};
 use zksync_vlog::prometheus::PrometheusExporterConfig;
 
-use crate::creator::SnapshotCreator;
+use crate::{creator::SnapshotCreator, verifier::SnapshotVerifier};
 
 mod creator;
 mod metrics;
 #[cfg(test)]
 mod tests;
+mod verifier;
 
 async fn maybe_enable_prometheus_metrics(
     prometheus_config: Option<PrometheusConfig>,
@@ -57,6 +78,178 @@ struct Opt {
     /// Path to the secrets file.
     #[structopt(long)]
     secrets_path: Option<std::path::PathBuf>,
+
+    #[structopt(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(StructOpt)]
+enum Command {
+    /// Verifies a snapshot persisted in the object store instead of creating a new snapshot.
+    Verify(VerifyOpt),
+    /// Exports a snapshot persisted in the object store to a portable archive.
+    Export(ExportOpt),
+}
+
+#[derive(StructOpt)]
+struct ExportOpt {
+    /// L1 batch number of the snapshot to export.
+    #[structopt(long)]
+    l1_batch_number: u32,
+    /// Path to write the archive to. If the file exists, it will be overwritten.
+    #[structopt(long)]
+    output: PathBuf,
+}
+
+#[derive(StructOpt)]
+struct VerifyOpt {
+    /// L1 batch number of the snapshot to verify.
+    #[structopt(long)]
+    l1_batch_number: u32,
+    /// Expected Merkle tree root hash after the snapshot L1 batch. If not specified, the expected tree data
+    /// is loaded from Postgres.
+    #[structopt(long, requires = "expected-rollup-last-leaf-index")]
+    expected_root_hash: Option<H256>,
+    /// Expected index of the last leaf in the Merkle tree after the snapshot L1 batch (i.e., the number of
+    /// storage logs in the snapshot + 1), as committed on L1.
+    #[structopt(long, requires = "expected-root-hash")]
+    expected_rollup_last_leaf_index: Option<u64>,
+    /// Path to an empty directory to recover the Merkle tree in. If not specified, the tree is recovered in memory.
+    #[structopt(long)]
+    tree_path: Option<PathBuf>,
+    /// Path to a snapshot archive to verify instead of the snapshot in the object store.
+    #[structopt(long)]
+    archive_path: Option<PathBuf>,
+}
+
+async fn load_expected_tree_data(
+    opt: &VerifyOpt,
+    database_secrets: &DatabaseSecrets,
+) -> anyhow::Result<L1BatchTreeData> {
+    if let (Some(hash), Some(rollup_last_leaf_index)) =
+        (opt.expected_root_hash, opt.expected_rollup_last_leaf_index)
+    {
+        return Ok(L1BatchTreeData {
+            hash,
+            rollup_last_leaf_index,
+        });
+    }
+
+    let l1_batch_number = L1BatchNumber(opt.l1_batch_number);
+    let pool = ConnectionPool::<Core>::singleton(database_secrets.replica_url()?)
+        .build()
+        .await?;
+    let mut conn = pool.connection_tagged("snapshots_creator").await?;
+    conn.blocks_dal()
+        .get_l1_batch_tree_data(l1_batch_number)
+        .await?
+        .with_context(|| format!("L1 batch #{l1_batch_number} doesn't have tree data in Postgres"))
+}
+
+async fn verify_snapshot(
+    opt: VerifyOpt,
+    blob_store: Arc<dyn ObjectStore>,
+    database_secrets: &DatabaseSecrets,
+) -> anyhow::Result<()> {
+    let l1_batch_number = L1BatchNumber(opt.l1_batch_number);
+    let expected_tree_data = load_expected_tree_data(&opt, database_secrets).await?;
+    let blob_store: Arc<dyn ObjectStore> = if let Some(archive_path) = &opt.archive_path {
+        let archive = SnapshotArchive::open(archive_path)
+            .await
+            .with_context(|| format!("failed opening snapshot archive {archive_path:?}"))?;
+        archive
+            .verify_checksums()
+            .await
+            .context("snapshot archive is corrupted")?;
+        Arc::new(archive)
+    } else {
+        blob_store
+    };
+    let verifier = SnapshotVerifier::new(blob_store);
+
+    let report = if let Some(tree_path) = &opt.tree_path {
+        let is_empty = match tree_path.read_dir() {
+            Ok(mut entries) => entries.next().is_none(),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => true,
+            Err(err) => return Err(err).context("cannot read Merkle tree directory"),
+        };
+        anyhow::ensure!(
+            is_empty,
+            "Merkle tree directory {tree_path:?} is not empty; the verifier requires an empty directory"
+        );
+        let db =
+            RocksDBWrapper::new(tree_path).context("failed initializing Merkle tree RocksDB")?;
+        verifier
+            .verify(l1_batch_number, expected_tree_data, db)
+            .await?
+    } else {
+        verifier
+            .verify(l1_batch_number, expected_tree_data, PatchSet::default())
+            .await?
+    };
+    tracing::info!("Snapshot verification succeeded: {report:?}");
+    Ok(())
+}
+
+/// Loads data necessary to recover from the snapshot that is not a part of the snapshot itself.
+async fn load_recovery_data(
+    conn: &mut Connection<'_, Core>,
+    header: &SnapshotHeader,
+) -> anyhow::Result<SnapshotRecoveryData> {
+    let l1_batch_number = header.l1_batch_number;
+    let l2_block_number = header.l2_block_number;
+    let l1_batch_details = conn
+        .blocks_web3_dal()
+        .get_l1_batch_details(l1_batch_number)
+        .await?
+        .with_context(|| format!("L1 batch #{l1_batch_number} is missing in Postgres"))?;
+    let l2_block_details = conn
+        .blocks_web3_dal()
+        .get_block_details(l2_block_number)
+        .await?
+        .with_context(|| format!("L2 block #{l2_block_number} is missing in Postgres"))?;
+    let tokens = conn
+        .tokens_web3_dal()
+        .get_all_tokens(Some(l2_block_number))
+        .await?;
+    Ok(SnapshotRecoveryData {
+        l1_batch_details,
+        l2_block_details,
+        tokens,
+    })
+}
+
+async fn export_snapshot(
+    opt: ExportOpt,
+    blob_store: Arc<dyn ObjectStore>,
+    database_secrets: &DatabaseSecrets,
+) -> anyhow::Result<()> {
+    let l1_batch_number = L1BatchNumber(opt.l1_batch_number);
+    let header: SnapshotHeader = blob_store
+        .get(l1_batch_number)
+        .await
+        .with_context(|| format!("failed fetching header for snapshot #{l1_batch_number}"))?;
+    let pool = ConnectionPool::<Core>::singleton(database_secrets.replica_url()?)
+        .build()
+        .await?;
+    let mut conn = pool.connection_tagged("snapshots_creator").await?;
+    let recovery_data = load_recovery_data(&mut conn, &header).await?;
+    drop(conn);
+
+    let archive = SnapshotArchive::export(
+        blob_store.as_ref(),
+        l1_batch_number,
+        recovery_data,
+        &opt.output,
+    )
+    .await
+    .with_context(|| format!("failed exporting snapshot #{l1_batch_number}"))?;
+    tracing::info!(
+        "Exported snapshot #{l1_batch_number} ({} snapshot(s) in chain) to {:?}",
+        archive.headers().len(),
+        archive.path()
+    );
+    Ok(())
 }
 
 #[tokio::main]
@@ -88,6 +281,32 @@ async fn main() -> anyhow::Result<()> {
         .create_store()
         .await?;
 
+    match opt.command {
+        Some(Command::Verify(verify_opt)) => {
+            verify_snapshot(verify_opt, blob_store, &database_secrets).await?;
+        }
+        Some(Command::Export(export_opt)) => {
+            export_snapshot(export_opt, blob_store, &database_secrets).await?;
+        }
+        None => {
+            run_creator(creator_config, blob_store, &database_secrets).await?;
+        }
+    }
+
+    stop_sender.send(true).ok();
+    if let Some(prometheus_exporter_task) = prometheus_exporter_task {
+        prometheus_exporter_task
+            .await?
+            .context("Prometheus did not finish gracefully")?;
+    }
+    Ok(())
+}
+
+async fn run_creator(
+    creator_config: SnapshotsCreatorConfig,
+    blob_store: Arc<dyn ObjectStore>,
+    database_secrets: &DatabaseSecrets,
+) -> anyhow::Result<()> {
     let replica_pool = ConnectionPool::<Core>::builder(
         database_secrets.replica_url()?,
         creator_config.concurrent_queries_count,
@@ -109,11 +328,5 @@ async fn main() -> anyhow::Result<()> {
     creator.run(creator_config, MIN_CHUNK_COUNT).await?;
 
     tracing::info!("Finished running snapshot creator!");
-    stop_sender.send(true).ok();
-    if let Some(prometheus_exporter_task) = prometheus_exporter_task {
-        prometheus_exporter_task
-            .await?
-            .context("Prometheus did not finish gracefully")?;
-    }
     Ok(())
 }
@@ -16,7 +16,7 @@ use zksync_object_store::{MockObjectStore, ObjectStore};
 use zksync_types::{
     block::{L1BatchHeader, L1BatchTreeData, L2BlockHeader},
     snapshots::{
-        SnapshotFactoryDependencies, SnapshotFactoryDependency, SnapshotStorageLog,
+        SnapshotFactoryDependencies, SnapshotFactoryDependency, SnapshotHeader, SnapshotStorageLog,
         SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
     },
     AccountTreeId, Address, L1BatchNumber, L2BlockNumber, ProtocolVersion, StorageKey, StorageLog,
@@ -28,6 +28,7 @@ use super::*;
 const TEST_CONFIG: SnapshotsCreatorConfig = SnapshotsCreatorConfig {
     version: 1,
     l1_batch_number: None,
+    max_delta_chain_length: 0,
     storage_logs_chunk_size: 1_000_000,
     concurrent_queries_count: 10,
     object_store: None,
@@ -267,7 +268,7 @@ async fn persisting_snapshot_metadata() {
     let mut conn = pool.connection().await.unwrap();
     prepare_postgres(&mut rng, &mut conn, 10).await;
 
-    SnapshotCreator::for_tests(object_store, pool.clone())
+    SnapshotCreator::for_tests(object_store.clone(), pool.clone())
         .run(TEST_CONFIG, MIN_CHUNK_COUNT)
         .await
         .unwrap();
@@ -303,6 +304,113 @@ async fn persisting_snapshot_metadata() {
             .unwrap();
         assert!(path.ends_with(".proto.gzip"));
     }
+
+    // Check the snapshot header persisted in the object store.
+    let header: SnapshotHeader = object_store.get(snapshot_l1_batch_number).await.unwrap();
+    assert_eq!(header.version, TEST_CONFIG.version);
+    assert_eq!(header.l1_batch_number, snapshot_l1_batch_number);
+    assert_eq!(header.factory_deps_filepath, *factory_deps_path);
+    let chunk_paths: Vec<_> = header
+        .storage_logs_chunks
+        .into_iter()
+        .map(|chunk| Some(chunk.filepath))
+        .collect();
+    assert_eq!(chunk_paths, snapshot_metadata.storage_logs_filepaths);
+}
+
+#[tokio::test]
+async fn restoring_missing_snapshot_header() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut rng = thread_rng();
+    let object_store = MockObjectStore::arc();
+    let mut conn = pool.connection().await.unwrap();
+    prepare_postgres(&mut rng, &mut conn, 10).await;
+
+    SnapshotCreator::for_tests(object_store.clone(), pool.clone())
+        .run(TEST_CONFIG, MIN_CHUNK_COUNT)
+        .await
+        .unwrap();
+    let snapshot_l1_batch_number = L1BatchNumber(8);
+    let header: SnapshotHeader = object_store.get(snapshot_l1_batch_number).await.unwrap();
+    // Emulate the creator being interrupted after marking the snapshot as complete in Postgres.
+    object_store
+        .remove::<SnapshotHeader>(snapshot_l1_batch_number)
+        .await
+        .unwrap();
+
+    // The repeated run doesn't create a new snapshot, but should restore the header.
+    SnapshotCreator::for_tests(object_store.clone(), pool.clone())
+        .run(TEST_CONFIG, MIN_CHUNK_COUNT)
+        .await
+        .unwrap();
+    let restored_header: SnapshotHeader = object_store.get(snapshot_l1_batch_number).await.unwrap();
+    assert_eq!(restored_header.l1_batch_number, header.l1_batch_number);
+    assert_eq!(restored_header.l2_block_number, header.l2_block_number);
+    assert_eq!(
+        restored_header.factory_deps_filepath,
+        header.factory_deps_filepath
+    );
+    assert_eq!(
+        restored_header.storage_logs_chunks.len(),
+        header.storage_logs_chunks.len()
+    );
+}
+
+#[tokio::test]
+async fn exporting_snapshot_to_archive() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut rng = thread_rng();
+    let object_store = MockObjectStore::arc();
+    let mut conn = pool.connection().await.unwrap();
+    let expected_outputs = prepare_postgres(&mut rng, &mut conn, 10).await;
+
+    SnapshotCreator::for_tests(object_store.clone(), pool.clone())
+        .run(TEST_CONFIG, MIN_CHUNK_COUNT)
+        .await
+        .unwrap();
+    let snapshot_l1_batch_number = L1BatchNumber(8);
+
+    let header: SnapshotHeader = object_store.get(snapshot_l1_batch_number).await.unwrap();
+    let recovery_data = load_recovery_data(&mut conn, &header).await.unwrap();
+    assert_eq!(
+        recovery_data.l1_batch_details.number,
+        snapshot_l1_batch_number
+    );
+    assert_eq!(
+        recovery_data.l2_block_details.number,
+        header.l2_block_number
+    );
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let archive_path = dir.path().join("snapshot.bin");
+    let archive = SnapshotArchive::export(
+        &*object_store,
+        snapshot_l1_batch_number,
+        recovery_data,
+        &archive_path,
+    )
+    .await
+    .unwrap();
+    assert_eq!(archive.header().l1_batch_number, snapshot_l1_batch_number);
+    assert_eq!(
+        archive.header().storage_logs_chunks.len(),
+        MIN_CHUNK_COUNT as usize
+    );
+
+    // The archive should be usable in place of the original object store.
+    let archive = SnapshotArchive::open(&archive_path).await.unwrap();
+    archive.verify_checksums().await.unwrap();
+    assert_eq!(
+        archive.recovery_data().l2_block_details.l1_batch_number,
+        snapshot_l1_batch_number
+    );
+    assert_storage_logs(&archive, snapshot_l1_batch_number, &expected_outputs).await;
+    let SnapshotFactoryDependencies { factory_deps } = (&archive as &dyn ObjectStore)
+        .get(snapshot_l1_batch_number)
+        .await
+        .unwrap();
+    let actual_deps: HashSet<_> = factory_deps.into_iter().collect();
+    assert_eq!(actual_deps, expected_outputs.deps);
 }
 
 #[tokio::test]
@@ -388,6 +496,96 @@ async fn assert_storage_logs(
     assert_eq!(actual_logs, expected_logs);
 }
 
+#[tokio::test]
+async fn creating_delta_snapshots() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut rng = thread_rng();
+    let object_store = MockObjectStore::arc();
+    let mut conn = pool.connection().await.unwrap();
+    let expected_outputs = prepare_postgres(&mut rng, &mut conn, 10).await;
+
+    let config = SnapshotsCreatorConfig {
+        l1_batch_number: Some(L1BatchNumber(4)),
+        max_delta_chain_length: 1,
+        ..TEST_CONFIG
+    };
+    SnapshotCreator::for_tests(object_store.clone(), pool.clone())
+        .run(config.clone(), MIN_CHUNK_COUNT)
+        .await
+        .unwrap();
+    let snapshot = conn
+        .snapshots_dal()
+        .get_snapshot_metadata(L1BatchNumber(4))
+        .await
+        .unwrap()
+        .expect("no snapshot");
+    assert_eq!(snapshot.base_l1_batch_number, None);
+
+    let config = SnapshotsCreatorConfig {
+        l1_batch_number: Some(L1BatchNumber(7)),
+        ..config
+    };
+    SnapshotCreator::for_tests(object_store.clone(), pool.clone())
+        .run(config.clone(), MIN_CHUNK_COUNT)
+        .await
+        .unwrap();
+    let snapshot = conn
+        .snapshots_dal()
+        .get_snapshot_metadata(L1BatchNumber(7))
+        .await
+        .unwrap()
+        .expect("no snapshot");
+    assert!(snapshot.is_complete());
+    assert_eq!(snapshot.base_l1_batch_number, Some(L1BatchNumber(4)));
+
+    let header: SnapshotHeader = object_store.get(L1BatchNumber(7)).await.unwrap();
+    assert_eq!(header.base_l1_batch_number, Some(L1BatchNumber(4)));
+
+    // The delta must only contain logs written after the base snapshot.
+    let mut actual_logs = HashSet::new();
+    for chunk in &header.storage_logs_chunks {
+        let key = SnapshotStorageLogsStorageKey {
+            l1_batch_number: L1BatchNumber(7),
+            chunk_id: chunk.chunk_id,
+        };
+        let chunk: SnapshotStorageLogsChunk = object_store.get(key).await.unwrap();
+        actual_logs.extend(chunk.storage_logs);
+    }
+    let expected_logs: HashSet<_> = expected_outputs
+        .storage_logs
+        .iter()
+        .filter(|log| (5..=7).contains(&log.l1_batch_number_of_initial_write.0))
+        .cloned()
+        .collect();
+    assert_eq!(actual_logs, expected_logs);
+
+    // ...and only factory deps added after the base snapshot.
+    let SnapshotFactoryDependencies { factory_deps } =
+        object_store.get(L1BatchNumber(7)).await.unwrap();
+    assert_eq!(factory_deps.len(), 30);
+    assert!(factory_deps
+        .iter()
+        .all(|dep| expected_outputs.deps.contains(dep)));
+
+    // The chain length is exceeded, so the next snapshot must be full.
+    let config = SnapshotsCreatorConfig {
+        l1_batch_number: Some(L1BatchNumber(8)),
+        ..config
+    };
+    SnapshotCreator::for_tests(object_store.clone(), pool.clone())
+        .run(config, MIN_CHUNK_COUNT)
+        .await
+        .unwrap();
+    let snapshot = conn
+        .snapshots_dal()
+        .get_snapshot_metadata(L1BatchNumber(8))
+        .await
+        .unwrap()
+        .expect("no snapshot");
+    assert_eq!(snapshot.base_l1_batch_number, None);
+    assert_storage_logs(&*object_store, L1BatchNumber(8), &expected_outputs).await;
+}
+
 #[tokio::test]
 async fn persisting_snapshot_logs_for_v0_snapshot() {
     let pool = ConnectionPool::<Core>::test_pool().await;
@@ -0,0 +1,795 @@
+//! Offline verification of snapshots persisted in an object store.
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    ops::RangeInclusive,
+    sync::Arc,
+    time::Instant,
+};
+
+use anyhow::Context as _;
+use zksync_merkle_tree::{recovery::MerkleTreeRecovery, PruneDatabase, TreeEntry};
+use zksync_object_store::ObjectStore;
+use zksync_types::{
+    block::L1BatchTreeData,
+    get_known_code_key,
+    snapshots::{
+        uniform_hashed_keys_chunk, SnapshotFactoryDependencies, SnapshotHeader, SnapshotStorageLog,
+        SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey, SnapshotVersion,
+    },
+    L1BatchNumber, H256,
+};
+use zksync_utils::{
+    bytecode::{hash_bytecode, validate_bytecode},
+    h256_to_u256,
+};
+
+/// Summary of a successfully verified snapshot.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SnapshotVerificationReport {
+    pub version: SnapshotVersion,
+    pub l1_batch_number: L1BatchNumber,
+    /// Number of snapshots in the verified chain (1 for full snapshots).
+    pub chain_len: usize,
+    /// Number of storage logs chunks in the full snapshot of the chain.
+    pub chunk_count: u64,
+    pub storage_log_count: u64,
+    pub factory_dep_count: usize,
+    pub root_hash: H256,
+}
+
+/// Verifies snapshots produced by [`SnapshotCreator`](crate::creator::SnapshotCreator) using only
+/// the data in the object store.
+///
+/// The verifier checks that:
+///
+/// - The snapshot header is consistent (correct L1 batch number and version, contiguous chunk IDs).
+///   For delta snapshots, this applies to all headers in the chain down to the full snapshot.
+/// - Each storage logs chunk contains only keys from its hashed key range, and the keys are not duplicated.
+/// - Storage logs in delta snapshots preserve enumeration indices of the logs they overwrite.
+/// - Enumeration indices of storage logs are exactly `1..=N`, where `N` is the number of storage logs,
+///   and match the expected last leaf index.
+/// - The Merkle tree recovered from storage logs has the expected root hash.
+/// - All factory dependencies are valid bytecodes marked as known in the snapshot storage.
+#[derive(Debug)]
+pub(crate) struct SnapshotVerifier {
+    blob_store: Arc<dyn ObjectStore>,
+}
+
+impl SnapshotVerifier {
+    pub fn new(blob_store: Arc<dyn ObjectStore>) -> Self {
+        Self { blob_store }
+    }
+
+    /// Verifies the snapshot for the specified L1 batch. `tree_db` is used to recover the Merkle tree;
+    /// it must be empty.
+    ///
+    /// If the snapshot is a delta, all snapshots in its chain down to the full snapshot are loaded,
+    /// and the verified state is the result of applying the chain starting from the full snapshot.
+    pub async fn verify<DB: 'static + PruneDatabase>(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        expected_tree_data: L1BatchTreeData,
+        tree_db: DB,
+    ) -> anyhow::Result<SnapshotVerificationReport> {
+        let started_at = Instant::now();
+        let header: SnapshotHeader = self
+            .blob_store
+            .get(l1_batch_number)
+            .await
+            .with_context(|| format!("failed fetching header for snapshot #{l1_batch_number}"))?;
+        let version = Self::verify_header(&header, l1_batch_number)?;
+        let chain = self.load_chain(header).await?;
+        // Key ranges are taken from the full snapshot; chunks of deltas are matched to them by intersecting key ranges.
+        let chunk_count = chain[0].storage_logs_chunks.len() as u64;
+        tracing::info!(
+            "Verifying {version:?} snapshot for L1 batch #{l1_batch_number} with {} snapshots in chain and \
+             {chunk_count} storage logs chunks in the full snapshot; expected tree data: {expected_tree_data:?}",
+            chain.len()
+        );
+
+        let mut unmarked_bytecodes = HashMap::new();
+        for header in &chain {
+            self.load_factory_deps(header.l1_batch_number, &mut unmarked_bytecodes)
+                .await
+                .with_context(|| {
+                    format!(
+                        "invalid factory dependencies in snapshot for L1 batch #{}",
+                        header.l1_batch_number
+                    )
+                })?;
+        }
+        let factory_dep_count = unmarked_bytecodes.len();
+        tracing::info!("Verified {factory_dep_count} factory dependencies");
+
+        let mut recovery = MerkleTreeRecovery::new(tree_db, l1_batch_number.0.into())
+            .context("failed initializing Merkle tree recovery")?;
+        let mut enumeration_indices = vec![];
+        let mut snapshots: Vec<_> = chain.iter().map(ChainedSnapshot::new).collect();
+        for chunk_id in 0..chunk_count {
+            let hashed_keys_range = uniform_hashed_keys_chunk(chunk_id, chunk_count);
+            let mut merged_logs = BTreeMap::new();
+            for snapshot in &mut snapshots {
+                snapshot
+                    .merge_storage_logs(&*self.blob_store, &hashed_keys_range, &mut merged_logs)
+                    .await?;
+            }
+
+            let entries: Vec<_> = merged_logs
+                .into_values()
+                .map(|log| {
+                    if !log.value.is_zero() {
+                        unmarked_bytecodes.remove(&log.key);
+                    }
+                    enumeration_indices.push(log.enumeration_index);
+                    TreeEntry::new(h256_to_u256(log.key), log.enumeration_index, log.value)
+                })
+                .collect();
+            let entry_count = entries.len();
+            if !entries.is_empty() {
+                recovery = tokio::task::spawn_blocking(move || {
+                    recovery.extend_linear(entries)?;
+                    anyhow::Ok(recovery)
+                })
+                .await
+                .context("panicked while extending Merkle tree")??;
+            }
+            tracing::info!(
+                "Verified storage logs in key range #{chunk_id} ({entry_count} logs); overall progress {}/{chunk_count}",
+                chunk_id + 1
+            );
+        }
+
+        if let Some((code_key, bytecode_hash)) = unmarked_bytecodes.into_iter().next() {
+            anyhow::bail!(
+                "factory dependency with hash {bytecode_hash:?} is not marked as known in storage logs \
+                 (expected non-zero value for hashed key {code_key:?})"
+            );
+        }
+
+        let storage_log_count = enumeration_indices.len() as u64;
+        enumeration_indices.sort_unstable();
+        for (expected_index, &index) in (1..).zip(&enumeration_indices) {
+            anyhow::ensure!(
+                index == expected_index,
+                "enumeration indices are not contiguous: expected index {expected_index}, got {index}"
+            );
+        }
+        anyhow::ensure!(
+            storage_log_count + 1 == expected_tree_data.rollup_last_leaf_index,
+            "unexpected number of storage logs: expected {}, got {storage_log_count}",
+            expected_tree_data.rollup_last_leaf_index.saturating_sub(1)
+        );
+
+        let root_hash = tokio::task::spawn_blocking(move || recovery.root_hash())
+            .await
+            .context("panicked while computing Merkle tree root hash")?;
+        anyhow::ensure!(
+            root_hash == expected_tree_data.hash,
+            "root hash of the Merkle tree recovered from snapshot ({root_hash:?}) differs from the expected one ({:?})",
+            expected_tree_data.hash
+        );
+
+        tracing::info!(
+            "Verified snapshot for L1 batch #{l1_batch_number} in {:?}",
+            started_at.elapsed()
+        );
+        Ok(SnapshotVerificationReport {
+            version,
+            l1_batch_number,
+            chain_len: chain.len(),
+            chunk_count,
+            storage_log_count,
+            factory_dep_count,
+            root_hash,
+        })
+    }
+
+    fn verify_header(
+        header: &SnapshotHeader,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<SnapshotVersion> {
+        anyhow::ensure!(
+            header.l1_batch_number == l1_batch_number,
+            "snapshot header has unexpected L1 batch number: expected {l1_batch_number}, got {}",
+            header.l1_batch_number
+        );
+        if let Some(base_l1_batch_number) = header.base_l1_batch_number {
+            anyhow::ensure!(
+                base_l1_batch_number < l1_batch_number,
+                "delta snapshot header references base snapshot for L1 batch #{base_l1_batch_number}, \
+                 which is not older than the snapshot"
+            );
+        }
+        let version = SnapshotVersion::try_from(header.version).map_err(|err| {
+            anyhow::anyhow!(
+                "snapshot header has unsupported version {}: {err}",
+                header.version
+            )
+        })?;
+        match version {
+            SnapshotVersion::Version0 => {
+                anyhow::bail!(
+                    "snapshot has version 0, which stores storage keys instead of hashed keys; \
+                     only version 1 snapshots can be verified"
+                );
+            }
+            SnapshotVersion::Version1 => { /* supported */ }
+        }
+        anyhow::ensure!(
+            !header.storage_logs_chunks.is_empty(),
+            "snapshot header has no storage logs chunks"
+        );
+        for (expected_id, chunk) in (0_u64..).zip(&header.storage_logs_chunks) {
+            anyhow::ensure!(
+                chunk.chunk_id == expected_id,
+                "storage logs chunks in snapshot header are not ordered: expected chunk #{expected_id}, got {chunk:?}"
+            );
+        }
+        Ok(version)
+    }
+
+    /// Loads and verifies headers of all snapshots in the chain ending with the provided snapshot.
+    /// The returned chain starts with the full snapshot.
+    async fn load_chain(&self, header: SnapshotHeader) -> anyhow::Result<Vec<SnapshotHeader>> {
+        let mut chain = vec![header];
+        while let Some(base_l1_batch_number) = chain.last().unwrap().base_l1_batch_number {
+            let base_header: SnapshotHeader = self
+                .blob_store
+                .get(base_l1_batch_number)
+                .await
+                .with_context(|| {
+                    format!("failed fetching header for base snapshot #{base_l1_batch_number}")
+                })?;
+            Self::verify_header(&base_header, base_l1_batch_number).with_context(|| {
+                format!("header of base snapshot #{base_l1_batch_number} is invalid")
+            })?;
+            chain.push(base_header);
+        }
+        chain.reverse();
+        Ok(chain)
+    }
+
+    /// Loads and validates factory dependencies. Adds entries mapping the hashed storage key marking a bytecode
+    /// as known to the bytecode hash to `bytecodes`.
+    async fn load_factory_deps(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        bytecodes: &mut HashMap<H256, H256>,
+    ) -> anyhow::Result<()> {
+        let factory_deps: SnapshotFactoryDependencies = self
+            .blob_store
+            .get(l1_batch_number)
+            .await
+            .context("failed fetching factory dependencies")?;
+
+        bytecodes.reserve(factory_deps.factory_deps.len());
+        for (i, dep) in factory_deps.factory_deps.iter().enumerate() {
+            validate_bytecode(&dep.bytecode.0)
+                .with_context(|| format!("factory dependency #{i} is not a valid bytecode"))?;
+            let bytecode_hash = hash_bytecode(&dep.bytecode.0);
+            let code_key = get_known_code_key(&bytecode_hash).hashed_key();
+            let prev_hash = bytecodes.insert(code_key, bytecode_hash);
+            anyhow::ensure!(
+                prev_hash.is_none(),
+                "factory dependency with hash {bytecode_hash:?} is duplicated"
+            );
+        }
+        Ok(())
+    }
+
+    fn verify_storage_logs_chunk(
+        chunk: &SnapshotStorageLogsChunk,
+        chunk_id: u64,
+        chunk_count: u64,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        let hashed_keys_range = uniform_hashed_keys_chunk(chunk_id, chunk_count);
+        let mut keys = HashSet::with_capacity(chunk.storage_logs.len());
+        for log in &chunk.storage_logs {
+            anyhow::ensure!(
+                hashed_keys_range.contains(&log.key),
+                "storage log {log:?} is outside the chunk key range {hashed_keys_range:?}"
+            );
+            anyhow::ensure!(
+                log.l1_batch_number_of_initial_write <= l1_batch_number,
+                "storage log {log:?} is initially written after the snapshot L1 batch"
+            );
+            anyhow::ensure!(
+                log.enumeration_index > 0,
+                "storage log {log:?} has zero enumeration index"
+            );
+            anyhow::ensure!(
+                keys.insert(log.key),
+                "storage log {log:?} has the same hashed key as another log in the chunk"
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Snapshot in a verified chain together with its most recently loaded storage logs chunk. Since key ranges
+/// are processed in the ascending order, each chunk is loaded and verified exactly once.
+#[derive(Debug)]
+struct ChainedSnapshot {
+    l1_batch_number: L1BatchNumber,
+    chunk_count: u64,
+    loaded_chunk: Option<(u64, Vec<SnapshotStorageLog>)>,
+}
+
+impl ChainedSnapshot {
+    fn new(header: &SnapshotHeader) -> Self {
+        Self {
+            l1_batch_number: header.l1_batch_number,
+            chunk_count: header.storage_logs_chunks.len() as u64,
+            loaded_chunk: None,
+        }
+    }
+
+    /// Merges storage logs of this snapshot in the specified key range into `merged_logs`, overwriting logs
+    /// from the previous snapshots in the chain.
+    async fn merge_storage_logs(
+        &mut self,
+        blob_store: &dyn ObjectStore,
+        hashed_keys_range: &RangeInclusive<H256>,
+        merged_logs: &mut BTreeMap<H256, SnapshotStorageLog>,
+    ) -> anyhow::Result<()> {
+        let l1_batch_number = self.l1_batch_number;
+        for chunk_id in 0..self.chunk_count {
+            let chunk_range = uniform_hashed_keys_chunk(chunk_id, self.chunk_count);
+            if chunk_range.end() < hashed_keys_range.start()
+                || chunk_range.start() > hashed_keys_range.end()
+            {
+                continue;
+            }
+
+            let logs = self.load_chunk(blob_store, chunk_id).await?;
+            for log in logs
+                .iter()
+                .filter(|log| hashed_keys_range.contains(&log.key))
+            {
+                if let Some(prev_log) = merged_logs.insert(log.key, log.clone()) {
+                    anyhow::ensure!(
+                        prev_log.enumeration_index == log.enumeration_index
+                            && prev_log.l1_batch_number_of_initial_write
+                                == log.l1_batch_number_of_initial_write,
+                        "storage log {log:?} in snapshot for L1 batch #{l1_batch_number} has enumeration index \
+                         or initial write L1 batch differing from {prev_log:?} in its base snapshot"
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn load_chunk(
+        &mut self,
+        blob_store: &dyn ObjectStore,
+        chunk_id: u64,
+    ) -> anyhow::Result<&[SnapshotStorageLog]> {
+        let l1_batch_number = self.l1_batch_number;
+        let is_loaded = matches!(&self.loaded_chunk, Some((id, _)) if *id == chunk_id);
+        if !is_loaded {
+            let key = SnapshotStorageLogsStorageKey {
+                l1_batch_number,
+                chunk_id,
+            };
+            let chunk: SnapshotStorageLogsChunk = blob_store.get(key).await.with_context(|| {
+                format!(
+                    "failed fetching storage logs chunk #{chunk_id} (snapshot for L1 batch #{l1_batch_number})"
+                )
+            })?;
+            SnapshotVerifier::verify_storage_logs_chunk(
+                &chunk,
+                chunk_id,
+                self.chunk_count,
+                l1_batch_number,
+            )
+            .with_context(|| {
+                format!(
+                    "storage logs chunk #{chunk_id} is invalid (snapshot for L1 batch #{l1_batch_number})"
+                )
+            })?;
+            self.loaded_chunk = Some((chunk_id, chunk.storage_logs));
+        }
+        Ok(&self.loaded_chunk.as_ref().unwrap().1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_merkle_tree::{MerkleTree, PatchSet};
+    use zksync_object_store::MockObjectStore;
+    use zksync_types::{
+        snapshots::{
+            SnapshotFactoryDependency, SnapshotStorageLog, SnapshotStorageLogsChunkMetadata,
+        },
+        web3::keccak256,
+        L2BlockNumber,
+    };
+
+    use super::*;
+
+    const L1_BATCH_NUMBER: L1BatchNumber = L1BatchNumber(5);
+    const CHUNK_COUNT: u64 = 3;
+
+    fn mock_bytecode(seed: u8) -> Vec<u8> {
+        vec![seed; 32 * 3]
+    }
+
+    fn mock_storage_logs() -> Vec<SnapshotStorageLog> {
+        let mut logs: Vec<_> = (1..=20_u64)
+            .map(|i| SnapshotStorageLog {
+                // Hash keys so that they are spread uniformly across chunks.
+                key: H256(keccak256(&i.to_be_bytes())),
+                value: H256::from_low_u64_be(i * 100),
+                l1_batch_number_of_initial_write: L1BatchNumber(i as u32 % 5 + 1),
+                enumeration_index: i,
+            })
+            .collect();
+        for (i, seed) in [1_u8, 2].into_iter().enumerate() {
+            let bytecode_hash = hash_bytecode(&mock_bytecode(seed));
+            logs.push(SnapshotStorageLog {
+                key: get_known_code_key(&bytecode_hash).hashed_key(),
+                value: H256::from_low_u64_be(1),
+                l1_batch_number_of_initial_write: L1BatchNumber(1),
+                enumeration_index: 21 + i as u64,
+            });
+        }
+        logs
+    }
+
+    fn expected_tree_data(logs: &[SnapshotStorageLog]) -> L1BatchTreeData {
+        // Storage logs are ordered by enumeration index, so the tree assigns leaf indices in the same way.
+        let entries = logs
+            .iter()
+            .map(|log| TreeEntry::new(h256_to_u256(log.key), log.enumeration_index, log.value))
+            .collect();
+        let mut tree = MerkleTree::new(PatchSet::default()).unwrap();
+        let output = tree.extend(entries).unwrap();
+        L1BatchTreeData {
+            hash: output.root_hash,
+            rollup_last_leaf_index: logs.len() as u64 + 1,
+        }
+    }
+
+    async fn persist_snapshot(
+        store: &dyn ObjectStore,
+        logs: &[SnapshotStorageLog],
+        factory_deps: Vec<Vec<u8>>,
+    ) {
+        persist_snapshot_with_base(
+            store,
+            L1_BATCH_NUMBER,
+            None,
+            CHUNK_COUNT,
+            logs,
+            factory_deps,
+        )
+        .await;
+    }
+
+    async fn persist_snapshot_with_base(
+        store: &dyn ObjectStore,
+        l1_batch_number: L1BatchNumber,
+        base_l1_batch_number: Option<L1BatchNumber>,
+        chunk_count: u64,
+        logs: &[SnapshotStorageLog],
+        factory_deps: Vec<Vec<u8>>,
+    ) {
+        let mut storage_logs_chunks = vec![];
+        for chunk_id in 0..chunk_count {
+            let range = uniform_hashed_keys_chunk(chunk_id, chunk_count);
+            let storage_logs = logs
+                .iter()
+                .filter(|log| range.contains(&log.key))
+                .cloned()
+                .collect();
+            let key = SnapshotStorageLogsStorageKey {
+                l1_batch_number,
+                chunk_id,
+            };
+            let filepath = store
+                .put(key, &SnapshotStorageLogsChunk { storage_logs })
+                .await
+                .unwrap();
+            storage_logs_chunks.push(SnapshotStorageLogsChunkMetadata { chunk_id, filepath });
+        }
+
+        let factory_deps = SnapshotFactoryDependencies {
+            factory_deps: factory_deps
+                .into_iter()
+                .map(|bytecode| SnapshotFactoryDependency {
+                    bytecode: bytecode.into(),
+                })
+                .collect(),
+        };
+        let factory_deps_filepath = store.put(l1_batch_number, &factory_deps).await.unwrap();
+        let header = SnapshotHeader {
+            version: SnapshotVersion::Version1.into(),
+            l1_batch_number,
+            l2_block_number: L2BlockNumber(l1_batch_number.0 * 2),
+            base_l1_batch_number,
+            storage_logs_chunks,
+            factory_deps_filepath,
+        };
+        store.put(l1_batch_number, &header).await.unwrap();
+    }
+
+    /// Returns storage logs for a delta snapshot on top of a snapshot with `base_logs`, and all storage logs
+    /// after applying the delta.
+    fn mock_delta_storage_logs(
+        base_logs: &[SnapshotStorageLog],
+        l1_batch_number: L1BatchNumber,
+    ) -> (Vec<SnapshotStorageLog>, Vec<SnapshotStorageLog>) {
+        let mut all_logs = base_logs.to_vec();
+        let mut delta_logs = vec![];
+        for log in all_logs.iter_mut().step_by(3) {
+            log.value = H256::repeat_byte(0xff);
+            delta_logs.push(log.clone());
+        }
+        let new_bytecode_hash = hash_bytecode(&mock_bytecode(3));
+        let new_keys = [
+            H256::repeat_byte(0x11),
+            H256::repeat_byte(0xee),
+            get_known_code_key(&new_bytecode_hash).hashed_key(),
+        ];
+        for (i, key) in (1..).zip(new_keys) {
+            let log = SnapshotStorageLog {
+                key,
+                value: H256::from_low_u64_be(i),
+                l1_batch_number_of_initial_write: l1_batch_number,
+                enumeration_index: base_logs.len() as u64 + i,
+            };
+            all_logs.push(log.clone());
+            delta_logs.push(log);
+        }
+        (delta_logs, all_logs)
+    }
+
+    #[tokio::test]
+    async fn verifying_valid_snapshot() {
+        let store = MockObjectStore::arc();
+        let logs = mock_storage_logs();
+        persist_snapshot(&*store, &logs, vec![mock_bytecode(1), mock_bytecode(2)]).await;
+        let expected_tree_data = expected_tree_data(&logs);
+
+        let report = SnapshotVerifier::new(store)
+            .verify(L1_BATCH_NUMBER, expected_tree_data, PatchSet::default())
+            .await
+            .unwrap();
+        assert_eq!(report.version, SnapshotVersion::Version1);
+        assert_eq!(report.l1_batch_number, L1_BATCH_NUMBER);
+        assert_eq!(report.chunk_count, CHUNK_COUNT);
+        assert_eq!(report.storage_log_count, logs.len() as u64);
+        assert_eq!(report.factory_dep_count, 2);
+        assert_eq!(report.root_hash, expected_tree_data.hash);
+    }
+
+    #[tokio::test]
+    async fn verifying_snapshot_with_root_hash_mismatch() {
+        let store = MockObjectStore::arc();
+        let logs = mock_storage_logs();
+        persist_snapshot(&*store, &logs, vec![]).await;
+        let expected_tree_data = L1BatchTreeData {
+            hash: H256::repeat_byte(1),
+            ..expected_tree_data(&logs)
+        };
+
+        let err = SnapshotVerifier::new(store)
+            .verify(L1_BATCH_NUMBER, expected_tree_data, PatchSet::default())
+            .await
+            .unwrap_err();
+        let err = format!("{err:#}");
+        assert!(err.contains("root hash"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn verifying_snapshot_with_enumeration_index_gap() {
+        let store = MockObjectStore::arc();
+        let mut logs = mock_storage_logs();
+        logs[3].enumeration_index = 100;
+        persist_snapshot(&*store, &logs, vec![]).await;
+        let expected_tree_data = expected_tree_data(&logs);
+
+        let err = SnapshotVerifier::new(store)
+            .verify(L1_BATCH_NUMBER, expected_tree_data, PatchSet::default())
+            .await
+            .unwrap_err();
+        let err = format!("{err:#}");
+        assert!(err.contains("enumeration indices"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn verifying_snapshot_with_misplaced_storage_log() {
+        let store = MockObjectStore::arc();
+        let logs = mock_storage_logs();
+        persist_snapshot(&*store, &logs, vec![]).await;
+        // Move a log from the last chunk to the first one.
+        let last_range = uniform_hashed_keys_chunk(CHUNK_COUNT - 1, CHUNK_COUNT);
+        let misplaced_log = logs
+            .iter()
+            .find(|log| last_range.contains(&log.key))
+            .unwrap()
+            .clone();
+        let key = SnapshotStorageLogsStorageKey {
+            l1_batch_number: L1_BATCH_NUMBER,
+            chunk_id: 0,
+        };
+        let mut first_chunk: SnapshotStorageLogsChunk = store.get(key).await.unwrap();
+        first_chunk.storage_logs.push(misplaced_log);
+        store.put(key, &first_chunk).await.unwrap();
+
+        let err = SnapshotVerifier::new(store)
+            .verify(
+                L1_BATCH_NUMBER,
+                expected_tree_data(&logs),
+                PatchSet::default(),
+            )
+            .await
+            .unwrap_err();
+        let err = format!("{err:#}");
+        assert!(err.contains("storage logs chunk #0 is invalid"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn verifying_snapshot_with_unknown_factory_dep() {
+        let store = MockObjectStore::arc();
+        let logs = mock_storage_logs();
+        persist_snapshot(&*store, &logs, vec![mock_bytecode(1), mock_bytecode(3)]).await;
+
+        let err = SnapshotVerifier::new(store)
+            .verify(
+                L1_BATCH_NUMBER,
+                expected_tree_data(&logs),
+                PatchSet::default(),
+            )
+            .await
+            .unwrap_err();
+        let err = format!("{err:#}");
+        assert!(err.contains("not marked as known"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn verifying_snapshot_with_invalid_factory_dep() {
+        let store = MockObjectStore::arc();
+        let logs = mock_storage_logs();
+        persist_snapshot(&*store, &logs, vec![vec![1; 64]]).await;
+
+        let err = SnapshotVerifier::new(store)
+            .verify(
+                L1_BATCH_NUMBER,
+                expected_tree_data(&logs),
+                PatchSet::default(),
+            )
+            .await
+            .unwrap_err();
+        let err = format!("{err:#}");
+        assert!(err.contains("not a valid bytecode"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn verifying_version_0_snapshot_is_rejected() {
+        let store = MockObjectStore::arc();
+        let logs = mock_storage_logs();
+        persist_snapshot(&*store, &logs, vec![]).await;
+        let mut header: SnapshotHeader = store.get(L1_BATCH_NUMBER).await.unwrap();
+        header.version = SnapshotVersion::Version0.into();
+        store.put(L1_BATCH_NUMBER, &header).await.unwrap();
+
+        let err = SnapshotVerifier::new(store)
+            .verify(
+                L1_BATCH_NUMBER,
+                expected_tree_data(&logs),
+                PatchSet::default(),
+            )
+            .await
+            .unwrap_err();
+        let err = format!("{err:#}");
+        assert!(err.contains("snapshot has version 0"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn verifying_delta_snapshot() {
+        let store = MockObjectStore::arc();
+        let base_logs = mock_storage_logs();
+        persist_snapshot(
+            &*store,
+            &base_logs,
+            vec![mock_bytecode(1), mock_bytecode(2)],
+        )
+        .await;
+        let delta_l1_batch_number = L1_BATCH_NUMBER + 2;
+        let (delta_logs, all_logs) = mock_delta_storage_logs(&base_logs, delta_l1_batch_number);
+        // Use a different number of chunks so that chunks of the delta and base snapshots don't align.
+        persist_snapshot_with_base(
+            &*store,
+            delta_l1_batch_number,
+            Some(L1_BATCH_NUMBER),
+            2,
+            &delta_logs,
+            vec![mock_bytecode(3)],
+        )
+        .await;
+        let expected_tree_data = expected_tree_data(&all_logs);
+
+        let report = SnapshotVerifier::new(store)
+            .verify(
+                delta_l1_batch_number,
+                expected_tree_data,
+                PatchSet::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(report.l1_batch_number, delta_l1_batch_number);
+        assert_eq!(report.chain_len, 2);
+        assert_eq!(report.chunk_count, CHUNK_COUNT);
+        assert_eq!(report.storage_log_count, all_logs.len() as u64);
+        assert_eq!(report.factory_dep_count, 3);
+        assert_eq!(report.root_hash, expected_tree_data.hash);
+    }
+
+    #[tokio::test]
+    async fn verifying_delta_snapshot_with_changed_enumeration_index() {
+        let store = MockObjectStore::arc();
+        let base_logs = mock_storage_logs();
+        persist_snapshot(&*store, &base_logs, vec![]).await;
+        let delta_l1_batch_number = L1_BATCH_NUMBER + 2;
+        let (mut delta_logs, all_logs) = mock_delta_storage_logs(&base_logs, delta_l1_batch_number);
+        // Swap enumeration indices of 2 overwritten logs; this doesn't change the set of indices.
+        let first_index = delta_logs[0].enumeration_index;
+        delta_logs[0].enumeration_index = delta_logs[1].enumeration_index;
+        delta_logs[1].enumeration_index = first_index;
+        persist_snapshot_with_base(
+            &*store,
+            delta_l1_batch_number,
+            Some(L1_BATCH_NUMBER),
+            2,
+            &delta_logs,
+            vec![mock_bytecode(3)],
+        )
+        .await;
+
+        let err = SnapshotVerifier::new(store)
+            .verify(
+                delta_l1_batch_number,
+                expected_tree_data(&all_logs),
+                PatchSet::default(),
+            )
+            .await
+            .unwrap_err();
+        let err = format!("{err:#}");
+        assert!(err.contains("enumeration index"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn verifying_delta_snapshot_with_missing_base() {
+        let store = MockObjectStore::arc();
+        let base_logs = mock_storage_logs();
+        let delta_l1_batch_number = L1_BATCH_NUMBER + 2;
+        let (delta_logs, all_logs) = mock_delta_storage_logs(&base_logs, delta_l1_batch_number);
+        persist_snapshot_with_base(
+            &*store,
+            delta_l1_batch_number,
+            Some(L1_BATCH_NUMBER),
+            2,
+            &delta_logs,
+            vec![mock_bytecode(3)],
+        )
+        .await;
+
+        let err = SnapshotVerifier::new(store)
+            .verify(
+                delta_l1_batch_number,
+                expected_tree_data(&all_logs),
+                PatchSet::default(),
+            )
+            .await
+            .unwrap_err();
+        let err = format!("{err:#}");
+        assert!(
+            err.contains("failed fetching header for base snapshot"),
+            "{err}"
+        );
+    }
+}
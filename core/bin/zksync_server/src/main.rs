@@ -11,11 +11,11 @@ use zksync_config::{
         },
         fri_prover_group::FriProverGroupConfig,
         house_keeper::HouseKeeperConfig,
-        BasicWitnessInputProducerConfig, ContractsConfig, DatabaseSecrets, ExperimentalVmConfig,
-        ExternalPriceApiClientConfig, FriProofCompressorConfig, FriProverConfig,
-        FriProverGatewayConfig, FriWitnessGeneratorConfig, FriWitnessVectorGeneratorConfig,
-        L1Secrets, ObservabilityConfig, PrometheusConfig, ProofDataHandlerConfig,
-        ProtectiveReadsWriterConfig, Secrets,
+        AddressPolicyConfig, AdminSecrets, BasicWitnessInputProducerConfig, ContractsConfig,
+        DatabaseSecrets, ExperimentalVmConfig, ExternalPriceApiClientConfig,
+        FriProofCompressorConfig, FriProverConfig, FriProverGatewayConfig,
+        FriWitnessGeneratorConfig, FriWitnessVectorGeneratorConfig, L1Secrets, ObservabilityConfig,
+        PrometheusConfig, ProofDataHandlerConfig, ProtectiveReadsWriterConfig, Secrets,
     },
     ApiConfig, BaseTokenAdjusterConfig, ContractVerifierConfig, DAClientConfig, DADispatcherConfig,
     DBConfig, EthConfig, EthWatchConfig, ExternalProofIntegrationApiConfig, GasAdjusterConfig,
@@ -26,6 +26,7 @@ use zksync_core_leftovers::{
     Component, Components,
 };
 use zksync_env_config::FromEnv;
+use zksync_node_framework::service::WiringGraphFormat;
 
 use crate::node_builder::MainNodeBuilder;
 
@@ -66,6 +67,10 @@ struct Cli {
     /// Now the node framework is used by default and this argument is left for backward compatibility.
     #[arg(long)]
     use_node_framework: bool,
+    /// Instead of running the node, prints its wiring graph (i.e., resources and tasks consumed and produced
+    /// by wiring layers) in the specified format (`json` or `dot`), and validates it.
+    #[arg(long)]
+    dump_wiring_graph: Option<WiringGraphFormat>,
 }
 
 #[derive(Debug, Clone)]
@@ -126,6 +131,7 @@ fn main() -> anyhow::Result<()> {
             consensus: config::read_consensus_secrets().context("read_consensus_secrets()")?,
             database: DatabaseSecrets::from_env().ok(),
             l1: L1Secrets::from_env().ok(),
+            admin: AdminSecrets::from_env().ok(),
         },
     };
 
@@ -167,7 +173,14 @@ fn main() -> anyhow::Result<()> {
         return Ok(());
     }
 
-    node.build(opt.components.0)?.run(observability_guard)?;
+    let node = node.build(opt.components.0)?;
+    if let Some(format) = opt.dump_wiring_graph {
+        let graph = node.wiring_graph();
+        println!("{}", graph.render(format));
+        graph.validate()?;
+        return Ok(());
+    }
+    node.run(observability_guard)?;
     Ok(())
 }
 
@@ -212,5 +225,8 @@ fn load_env_config() -> anyhow::Result<TempConfigStore> {
         external_proof_integration_api_config: ExternalProofIntegrationApiConfig::from_env().ok(),
         experimental_vm_config: ExperimentalVmConfig::from_env().ok(),
         prover_job_monitor_config: None,
+        address_policy_config: AddressPolicyConfig::from_env()
+            .ok()
+            .filter(AddressPolicyConfig::is_enabled),
     })
 }
@@ -13,10 +13,12 @@ use zksync_core_leftovers::Component;
 use zksync_metadata_calculator::MetadataCalculatorConfig;
 use zksync_node_api_server::{
     tx_sender::{ApiContracts, TxSenderConfig},
-    web3::{state::InternalApiConfig, Namespace},
+    web3::{state::InternalApiConfig, HttpRateLimits, IpcConfig, Namespace},
 };
 use zksync_node_framework::{
     implementations::layers::{
+        address_policy::AddressPolicyLayer,
+        admin_server::AdminServerLayer,
         base_token::{
             base_token_ratio_persister::BaseTokenRatioPersisterLayer,
             base_token_ratio_provider::BaseTokenRatioProviderLayer,
@@ -64,6 +66,7 @@ use zksync_node_framework::{
         },
         web3_api::{
             caches::MempoolCacheLayer,
+            preconfirmations::PreconfirmationsLayer,
             server::{Web3ServerLayer, Web3ServerOptionalConfig},
             tree_api_client::TreeApiClientLayer,
             tx_sender::{PostgresStorageCachesConfig, TxSenderLayer},
@@ -197,6 +200,14 @@ impl MainNodeBuilder {
         Ok(self)
     }
 
+    /// Adds the address policy layer if the policy is configured. No-op otherwise.
+    fn add_address_policy_layer(mut self) -> anyhow::Result<Self> {
+        if let Some(config) = self.configs.address_policy_config.clone() {
+            self.node.add_layer(AddressPolicyLayer::new(config));
+        }
+        Ok(self)
+    }
+
     fn add_object_store_layer(mut self) -> anyhow::Result<Self> {
         let object_store_config = try_load_config!(self.configs.core_object_store);
         self.node
@@ -300,6 +311,21 @@ impl MainNodeBuilder {
         Ok(self)
     }
 
+    fn add_admin_server_layer(mut self) -> anyhow::Result<Self> {
+        let healthcheck_config = try_load_config!(self.configs.api_config).healthcheck;
+        let Some(bind_addr) = healthcheck_config.admin_bind_addr() else {
+            return Ok(self);
+        };
+        let secrets = self
+            .secrets
+            .admin
+            .clone()
+            .context("Admin secrets have to be provided if admin server is enabled")?;
+        self.node
+            .add_layer(AdminServerLayer::new(bind_addr, secrets));
+        Ok(self)
+    }
+
     fn add_tx_sender_layer(mut self) -> anyhow::Result<Self> {
         let sk_config = try_load_config!(self.configs.state_keeper_config);
         let rpc_config = try_load_config!(self.configs.api_config).web3_json_rpc;
@@ -336,6 +362,16 @@ impl MainNodeBuilder {
         Ok(self)
     }
 
+    /// Adds the preconfirmations channel. Preconfirmations are published by the state keeper directly,
+    /// so the channel is only useful if the state keeper and the WebSocket API server run in the same process.
+    fn add_preconfirmations_layer(mut self, with_ws_api: bool) -> anyhow::Result<Self> {
+        let rpc_config = try_load_config!(self.configs.api_config).web3_json_rpc;
+        if with_ws_api && rpc_config.preconfirmations_enabled {
+            self.node.add_layer(PreconfirmationsLayer);
+        }
+        Ok(self)
+    }
+
     fn add_tree_api_client_layer(mut self) -> anyhow::Result<Self> {
         let rpc_config = try_load_config!(self.configs.api_config).web3_json_rpc;
         self.node
@@ -361,12 +397,24 @@ impl MainNodeBuilder {
         }
         namespaces.push(Namespace::Snapshots);
 
+        let http_rate_limits =
+            rpc_config
+                .http_compute_units_per_minute_limit
+                .map(|limit| HttpRateLimits {
+                    compute_units_per_minute: limit,
+                    api_key_limits: rpc_config
+                        .http_api_key_compute_units_per_minute_limits
+                        .clone(),
+                    method_compute_units: rpc_config.method_compute_units.clone(),
+                    trusted_proxies: rpc_config.http_trusted_proxies.iter().copied().collect(),
+                });
         let optional_config = Web3ServerOptionalConfig {
             namespaces: Some(namespaces),
             filters_limit: Some(rpc_config.filters_limit()),
             subscriptions_limit: Some(rpc_config.subscriptions_limit()),
             batch_request_size_limit: Some(rpc_config.max_batch_request_size()),
             response_body_size_limit: Some(rpc_config.max_response_body_size()),
+            http_rate_limits,
             with_extended_tracing: rpc_config.extended_api_tracing,
             ..Default::default()
         };
@@ -411,9 +459,32 @@ impl MainNodeBuilder {
             with_extended_tracing: rpc_config.extended_api_tracing,
             ..Default::default()
         };
+        let internal_api_config =
+            InternalApiConfig::new(&rpc_config, &self.contracts_config, &self.genesis_config);
+        // The IPC server is run together with the WS server since it supports the same functionality.
+        if let Some(ipc_path) = &rpc_config.ipc_path {
+            let ipc_config = IpcConfig {
+                max_connections: rpc_config.ipc_max_connections(),
+                max_subscriptions_per_connection: rpc_config
+                    .ipc_subscriptions_per_connection_limit(),
+                max_concurrent_requests_per_connection: rpc_config
+                    .ipc_max_concurrent_requests_per_connection(),
+                socket_permissions: rpc_config
+                    .ipc_socket_permissions()
+                    .context("ipc_socket_permissions")?,
+            };
+            self.node.add_layer(Web3ServerLayer::ipc(
+                ipc_path.into(),
+                internal_api_config.clone(),
+                Web3ServerOptionalConfig {
+                    ipc_config: Some(ipc_config),
+                    ..optional_config.clone()
+                },
+            ));
+        }
         self.node.add_layer(Web3ServerLayer::ws(
             rpc_config.ws_port,
-            InternalApiConfig::new(&rpc_config, &self.contracts_config, &self.genesis_config),
+            internal_api_config,
             optional_config,
         ));
 
@@ -671,6 +742,7 @@ impl MainNodeBuilder {
             .add_object_store_layer()?
             .add_circuit_breaker_checker_layer()?
             .add_healthcheck_layer()?
+            .add_admin_server_layer()?
             .add_prometheus_exporter_layer()?
             .add_query_eth_client_layer()?
             .add_gas_adjuster_layer()?;
@@ -695,8 +767,11 @@ impl MainNodeBuilder {
                 Component::StateKeeper => {
                     // State keeper is the core component of the sequencer,
                     // which is why we consider it to be responsible for the storage initialization.
+                    let with_ws_api = components.contains(&Component::WsApi);
                     self = self
                         .add_l1_gas_layer()?
+                        .add_address_policy_layer()?
+                        .add_preconfirmations_layer(with_ws_api)?
                         .add_storage_initialization_layer(LayerKind::Task)?
                         .add_state_keeper_layer()?
                         .add_logs_bloom_backfill_layer()?;
@@ -704,6 +779,7 @@ impl MainNodeBuilder {
                 Component::HttpApi => {
                     self = self
                         .add_l1_gas_layer()?
+                        .add_address_policy_layer()?
                         .add_tx_sender_layer()?
                         .add_tree_api_client_layer()?
                         .add_api_caches_layer()?
@@ -712,6 +788,7 @@ impl MainNodeBuilder {
                 Component::WsApi => {
                     self = self
                         .add_l1_gas_layer()?
+                        .add_address_policy_layer()?
                         .add_tx_sender_layer()?
                         .add_tree_api_client_layer()?
                         .add_api_caches_layer()?
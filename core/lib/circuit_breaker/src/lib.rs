@@ -35,6 +35,10 @@ pub enum CircuitBreakerError {
     FailedL1Transaction,
     #[error("Replication lag ({lag:?}) is above the threshold ({threshold:?})")]
     ReplicationLag { lag: Duration, threshold: Duration },
+    #[error(
+        "Priority operation #{serial_id} processed from L1 block #{l1_block} was reorged out of L1"
+    )]
+    PriorityOpReorged { serial_id: u64, l1_block: u64 },
     #[error("Internal error running circuit breaker checks")]
     Internal(#[from] anyhow::Error),
 }
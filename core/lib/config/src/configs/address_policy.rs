@@ -0,0 +1,40 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+use zksync_basic_types::Address;
+
+/// Configuration of the address policy restricting which L2 transactions can be included by the sequencer.
+///
+/// The policy is enforced both when transactions are submitted via the API server, and by the state keeper
+/// right before executing a transaction taken from the mempool.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct AddressPolicyConfig {
+    /// Path to a JSON file containing the static policy, e.g. `{ "allowlist": ["0x..."], "denylist": ["0x..."] }`.
+    /// Both lists are optional. If the allowlist is specified, only transactions initiated by the listed accounts
+    /// are accepted; the recipient and paymaster are not checked against it. Transactions initiated by, sent to
+    /// or paid for by a denylisted address are rejected. The file is reloaded without restarting the node
+    /// once it's modified.
+    pub policy_file_path: Option<String>,
+    /// Address of an L1 contract exposing `getDeniedAddresses() returns (address[])`. If specified,
+    /// the returned addresses are added to the denylist.
+    pub onchain_denylist_addr: Option<Address>,
+    /// Interval between consecutive policy reloads.
+    #[serde(default = "AddressPolicyConfig::default_reload_interval_ms")]
+    pub reload_interval_ms: u64,
+}
+
+impl AddressPolicyConfig {
+    pub fn default_reload_interval_ms() -> u64 {
+        10_000
+    }
+
+    pub fn reload_interval(&self) -> Duration {
+        Duration::from_millis(self.reload_interval_ms)
+    }
+
+    /// Checks whether the config specifies at least one policy source. A config without sources
+    /// (e.g., one loaded from an env with no `ADDRESS_POLICY_*` vars) doesn't restrict anything.
+    pub fn is_enabled(&self) -> bool {
+        self.policy_file_path.is_some() || self.onchain_denylist_addr.is_some()
+    }
+}
@@ -1,7 +1,7 @@
 use std::{
     collections::HashMap,
     fmt,
-    net::SocketAddr,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
     num::{NonZeroU32, NonZeroUsize},
     str::FromStr,
     time::Duration,
@@ -123,8 +123,91 @@ impl<'de> Deserialize<'de> for MaxResponseSizeOverrides {
     }
 }
 
+/// Compute units assigned to named entities, such as RPC methods (to specify method costs) or API keys
+/// (to specify per-key rate limits).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ComputeUnitsMap(HashMap<String, NonZeroU32>);
+
+impl<S: Into<String>> FromIterator<(S, NonZeroU32)> for ComputeUnitsMap {
+    fn from_iter<I: IntoIterator<Item = (S, NonZeroU32)>>(iter: I) -> Self {
+        Self(
+            iter.into_iter()
+                .map(|(name, units)| (name.into(), units))
+                .collect(),
+        )
+    }
+}
+
+impl FromStr for ComputeUnitsMap {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut map = HashMap::new();
+        for part in s.split(',') {
+            let part = part.trim();
+            if part.is_empty() {
+                continue;
+            }
+            let (name, units) = part
+                .split_once('=')
+                .with_context(|| format!("Part `{part}` doesn't have form <name>=<int>"))?;
+            let name = name.trim();
+            let units = units.trim();
+            let units = units.parse().with_context(|| {
+                format!("`{units}` specified for `{name}` is not a valid number of compute units")
+            })?;
+
+            if let Some(prev_units) = map.insert(name.to_owned(), units) {
+                anyhow::bail!(
+                    "Compute units for `{name}` are redefined from {prev_units} to {units}"
+                );
+            }
+        }
+        Ok(Self(map))
+    }
+}
+
+impl ComputeUnitsMap {
+    /// Gets compute units for the specified name, or `None` if they are not set.
+    pub fn get(&self, name: &str) -> Option<NonZeroU32> {
+        self.0.get(name).copied()
+    }
+
+    /// Iterates over all entries.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (&str, NonZeroU32)> + '_ {
+        self.0.iter().map(|(name, &units)| (name.as_str(), units))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl<'de> Deserialize<'de> for ComputeUnitsMap {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ParseVisitor;
+
+        impl<'v> de::Visitor<'v> for ParseVisitor {
+            type Value = ComputeUnitsMap;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("comma-separated list of <name>=<int> tuples, such as: eth_getLogs=20,debug_traceCall=50")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                value.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(ParseVisitor)
+    }
+}
+
 /// Response size limits for JSON-RPC servers.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct MaxResponseSize {
     /// Global limit applied to all RPC methods. Measured in bytes.
     pub global: usize,
@@ -142,6 +225,19 @@ pub struct Web3JsonRpcConfig {
     pub ws_port: u16,
     /// URL to access WebSocket RPC server.
     pub ws_url: String,
+    /// Path to the Unix domain socket for the IPC RPC server. If not set, the IPC server is disabled.
+    pub ipc_path: Option<String>,
+    /// Maximum number of concurrent connections to the IPC server. Default is 1,000.
+    pub ipc_max_connections: Option<u32>,
+    /// Maximum number of active subscriptions for a single IPC connection. Default is 1,000.
+    pub ipc_subscriptions_per_connection_limit: Option<u32>,
+    /// Maximum number of requests processed concurrently for a single IPC connection. Once the limit is reached,
+    /// the server stops reading requests from the connection until some of the in-flight requests complete.
+    /// Default is 64.
+    pub ipc_max_concurrent_requests_per_connection: Option<u32>,
+    /// Unix permissions for the IPC socket file as an octal number (e.g., `660`). If not set, permissions
+    /// are determined by the process umask.
+    pub ipc_socket_permissions: Option<String>,
     /// Max possible limit of entities to be requested once.
     pub req_entities_limit: Option<u32>,
     /// Whether to support HTTP methods that install filters and query filter changes.
@@ -187,6 +283,8 @@ pub struct Web3JsonRpcConfig {
     pub latest_values_cache_size_mb: Option<usize>,
     /// Limit for fee history block range.
     pub fee_history_limit: Option<u64>,
+    /// Maximum number of L2 blocks scanned by a single `trace_filter` call. Default is 100.
+    pub trace_filter_block_range_limit: Option<u64>,
     /// Maximum number of requests in a single batch JSON RPC request. Default is 500.
     pub max_batch_request_size: Option<usize>,
     /// Maximum response body size in MiBs. Default is 10 MiB.
@@ -198,6 +296,24 @@ pub struct Web3JsonRpcConfig {
     /// The value is per active connection.
     /// Note: For HTTP, rate limiting is expected to be configured on the infra level.
     pub websocket_requests_per_minute_limit: Option<NonZeroU32>,
+    /// Maximum number of compute units per minute spent by a single HTTP client. A client is identified by its API key
+    /// (passed in the `x-api-key` header) if the key is listed in `http_api_key_compute_units_per_minute_limits`,
+    /// and by its IP address otherwise (see `http_trusted_proxies` on how the address is determined).
+    /// If not set, HTTP requests are not rate-limited.
+    pub http_compute_units_per_minute_limit: Option<NonZeroU32>,
+    /// Per-minute compute unit limits for HTTP clients authenticated with API keys. Only has effect
+    /// if `http_compute_units_per_minute_limit` is set.
+    #[serde(default)]
+    pub http_api_key_compute_units_per_minute_limits: ComputeUnitsMap,
+    /// Overrides for compute unit costs of specific RPC methods used for HTTP rate limiting. Methods not mentioned here
+    /// use built-in costs (e.g., tracing methods and `eth_getLogs` are more expensive than `eth_chainId`).
+    #[serde(default)]
+    pub method_compute_units: ComputeUnitsMap,
+    /// IP addresses of reverse proxies trusted to set `x-forwarded-for` / `x-real-ip` headers. For HTTP rate limiting,
+    /// a client is identified by the remote address of the connection unless it's one of these proxies, in which case
+    /// the rightmost untrusted address in `x-forwarded-for` is used. If empty, forwarding headers are ignored.
+    #[serde(default)]
+    pub http_trusted_proxies: Vec<IpAddr>,
     /// Tree API url, currently used to proxy `getProof` calls to the tree
     pub tree_api_url: Option<String>,
     /// Polling period for mempool cache update - how often the mempool cache is updated from the database.
@@ -216,6 +332,11 @@ pub struct Web3JsonRpcConfig {
     /// (hundreds or thousands RPS).
     #[serde(default)]
     pub extended_api_tracing: bool,
+    /// Enables the `preconfirmations` WebSocket subscription streaming results of transactions executed
+    /// by the state keeper before their L2 block is sealed. Only has effect if the state keeper runs
+    /// in the same process as the WebSocket API server.
+    #[serde(default)]
+    pub preconfirmations_enabled: bool,
 }
 
 impl Web3JsonRpcConfig {
@@ -228,6 +349,11 @@ impl Web3JsonRpcConfig {
             http_url: "http://localhost:3050".into(),
             ws_port: 3051,
             ws_url: "ws://localhost:3051".into(),
+            ipc_path: None,
+            ipc_max_connections: None,
+            ipc_subscriptions_per_connection_limit: None,
+            ipc_max_concurrent_requests_per_connection: None,
+            ipc_socket_permissions: None,
             req_entities_limit: Some(10000),
             filters_disabled: false,
             filters_limit: Some(10000),
@@ -244,16 +370,22 @@ impl Web3JsonRpcConfig {
             initial_writes_cache_size_mb: Default::default(),
             latest_values_cache_size_mb: Default::default(),
             fee_history_limit: Default::default(),
+            trace_filter_block_range_limit: Default::default(),
             max_batch_request_size: Default::default(),
             max_response_body_size_mb: Default::default(),
             max_response_body_size_overrides_mb: MaxResponseSizeOverrides::empty(),
             websocket_requests_per_minute_limit: Default::default(),
+            http_compute_units_per_minute_limit: Default::default(),
+            http_api_key_compute_units_per_minute_limits: ComputeUnitsMap::default(),
+            method_compute_units: ComputeUnitsMap::default(),
+            http_trusted_proxies: Vec::new(),
             mempool_cache_update_interval: Default::default(),
             mempool_cache_size: Default::default(),
             tree_api_url: None,
             whitelisted_tokens_for_aa: Default::default(),
             api_namespaces: None,
             extended_api_tracing: false,
+            preconfirmations_enabled: false,
         }
     }
 
@@ -277,6 +409,27 @@ impl Web3JsonRpcConfig {
         self.subscriptions_limit.unwrap_or(10000) as usize
     }
 
+    pub fn ipc_max_connections(&self) -> usize {
+        self.ipc_max_connections.unwrap_or(1_000) as usize
+    }
+
+    pub fn ipc_subscriptions_per_connection_limit(&self) -> usize {
+        self.ipc_subscriptions_per_connection_limit.unwrap_or(1_000) as usize
+    }
+
+    pub fn ipc_max_concurrent_requests_per_connection(&self) -> usize {
+        self.ipc_max_concurrent_requests_per_connection
+            .unwrap_or(64) as usize
+    }
+
+    /// Parses Unix permissions for the IPC socket file.
+    pub fn ipc_socket_permissions(&self) -> anyhow::Result<Option<u32>> {
+        self.ipc_socket_permissions
+            .as_deref()
+            .map(parse_socket_permissions)
+            .transpose()
+    }
+
     pub fn pubsub_interval(&self) -> Duration {
         Duration::from_millis(self.pubsub_polling_interval.unwrap_or(200))
     }
@@ -307,6 +460,10 @@ impl Web3JsonRpcConfig {
         self.fee_history_limit.unwrap_or(1024)
     }
 
+    pub fn trace_filter_block_range_limit(&self) -> u64 {
+        self.trace_filter_block_range_limit.unwrap_or(100)
+    }
+
     pub fn max_batch_request_size(&self) -> usize {
         // The default limit is chosen to be reasonably permissive.
         self.max_batch_request_size.unwrap_or(500)
@@ -349,6 +506,10 @@ pub struct HealthCheckConfig {
     /// Time limit in milliseconds to abort a health check and return "not ready" status for the corresponding component.
     /// If not specified, the default value in the health check crate will be used.
     pub hard_time_limit_ms: Option<u64>,
+    /// Port of the admin server allowing to pause / resume node components and trigger one-off actions.
+    /// The server is only bound to the loopback interface and requires the auth token specified in secrets.
+    /// If not specified, the admin server is not started.
+    pub admin_port: Option<u16>,
 }
 
 impl HealthCheckConfig {
@@ -356,6 +517,11 @@ impl HealthCheckConfig {
         SocketAddr::new("0.0.0.0".parse().unwrap(), self.port)
     }
 
+    pub fn admin_bind_addr(&self) -> Option<SocketAddr> {
+        let port = self.admin_port?;
+        Some(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), port))
+    }
+
     pub fn slow_time_limit(&self) -> Option<Duration> {
         self.slow_time_limit_ms.map(Duration::from_millis)
     }
@@ -393,6 +559,18 @@ impl MerkleTreeApiConfig {
     }
 }
 
+/// Parses Unix file permissions specified as an octal number (e.g., `660` or `0o660`).
+pub fn parse_socket_permissions(permissions: &str) -> anyhow::Result<u32> {
+    let digits = permissions.trim().trim_start_matches("0o");
+    let mode = u32::from_str_radix(digits, 8)
+        .with_context(|| format!("`{permissions}` is not a valid octal number"))?;
+    anyhow::ensure!(
+        mode <= 0o777,
+        "socket permissions `{permissions}` exceed 777"
+    );
+    Ok(mode)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -416,4 +594,41 @@ mod tests {
         assert_eq!(scaled.get("zks_getProof"), Some(32_000));
         assert_eq!(scaled.get("eth_blockNumber"), None);
     }
+
+    #[test]
+    fn parsing_compute_units_map() {
+        let map: ComputeUnitsMap = "eth_getLogs=20, debug_traceCall = 50,".parse().unwrap();
+        assert_eq!(map.iter().len(), 2);
+        assert_eq!(map.get("eth_getLogs"), NonZeroU32::new(20));
+        assert_eq!(map.get("debug_traceCall"), NonZeroU32::new(50));
+        assert_eq!(map.get("eth_chainId"), None);
+
+        let map: ComputeUnitsMap = "".parse().unwrap();
+        assert!(map.is_empty());
+
+        let err = "eth_getLogs=0".parse::<ComputeUnitsMap>().unwrap_err();
+        assert!(err.to_string().contains("eth_getLogs"), "{err}");
+        let err = "eth_getLogs=1,eth_getLogs=2"
+            .parse::<ComputeUnitsMap>()
+            .unwrap_err();
+        assert!(err.to_string().contains("redefined"), "{err}");
+    }
+
+    #[test]
+    fn parsing_ipc_socket_permissions() {
+        let mut config = Web3JsonRpcConfig::for_tests();
+        assert_eq!(config.ipc_socket_permissions().unwrap(), None);
+
+        for (permissions, expected_mode) in [("660", 0o660), ("0600", 0o600), ("0o777", 0o777)] {
+            config.ipc_socket_permissions = Some(permissions.to_owned());
+            assert_eq!(
+                config.ipc_socket_permissions().unwrap(),
+                Some(expected_mode)
+            );
+        }
+        for permissions in ["", "rw", "680", "1777"] {
+            config.ipc_socket_permissions = Some(permissions.to_owned());
+            config.ipc_socket_permissions().unwrap_err();
+        }
+    }
 }
@@ -41,6 +41,78 @@ pub enum FeeModelVersion {
     V2,
 }
 
+/// Seal criterion that can be enabled for an L1 batch.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SealCriterionKind {
+    /// Limits the number of transactions in a batch (`transaction_slots`).
+    Slots,
+    /// Limits L1 gas required to commit, prove and execute a batch.
+    Gas,
+    /// Limits pubdata published by a batch (`max_pubdata_per_batch`).
+    PubdataBytes,
+    /// Limits the number of circuits required to prove a batch (`max_circuits_per_batch`).
+    Circuits,
+    /// Limits the bootloader encoding size of transactions.
+    TxEncodingSize,
+    /// Ensures that there is enough gas left to execute the batch tip.
+    GasForBatchTip,
+    /// Limits the wall-clock age of a batch (`max_l1_batch_age_ms`).
+    L1BatchAge,
+    /// Limits the number of L2 blocks in a batch (`max_l2_blocks_per_batch`).
+    L2BlocksCount,
+    /// Limits the number of priority transactions in a batch (`max_priority_txs_per_batch`).
+    PriorityTxsCount,
+}
+
+impl SealCriterionKind {
+    /// Criteria used if no criteria set is configured for a protocol version.
+    pub const DEFAULT: &'static [Self] = &[
+        Self::Slots,
+        Self::Gas,
+        Self::PubdataBytes,
+        Self::Circuits,
+        Self::TxEncodingSize,
+        Self::GasForBatchTip,
+    ];
+
+    /// Criteria that must be present in every criteria set. Without them, the state keeper may produce
+    /// L1 batches that cannot be proven or committed (e.g., batches exceeding the bootloader memory
+    /// or L1 gas limits).
+    pub const REQUIRED: &'static [Self] = &[
+        Self::Slots,
+        Self::Gas,
+        Self::GasForBatchTip,
+        Self::PubdataBytes,
+        Self::Circuits,
+        Self::TxEncodingSize,
+    ];
+}
+
+/// Set of seal criteria applied starting from a certain protocol version.
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct SealCriteriaSet {
+    /// Minimum protocol version (inclusive) this set applies to.
+    pub from_protocol_version: u16,
+    pub criteria: Vec<SealCriterionKind>,
+}
+
+impl SealCriteriaSet {
+    /// Checks that the set contains all [required criteria](SealCriterionKind::REQUIRED).
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let missing: Vec<_> = SealCriterionKind::REQUIRED
+            .iter()
+            .filter(|kind| !self.criteria.contains(kind))
+            .collect();
+        anyhow::ensure!(
+            missing.is_empty(),
+            "seal criteria set for protocol versions >={} misses required criteria: {missing:?}",
+            self.from_protocol_version
+        );
+        Ok(())
+    }
+}
+
 impl Default for FeeModelVersion {
     fn default() -> Self {
         Self::V1
@@ -66,6 +138,18 @@ pub struct StateKeeperConfig {
     /// The max payload size threshold (in bytes) that triggers sealing of an L2 block.
     #[serde(alias = "miniblock_max_payload_size")]
     pub l2_block_max_payload_size: usize,
+    /// Target amount of L2 gas used by transactions in an L2 block. If set, an L2 block is sealed once it reaches
+    /// the target, and the L2 base fee is adjusted after each L2 block similarly to EIP-1559: it increases if the block
+    /// uses more gas than the target, and decreases (down to `minimal_l2_gas_price`) otherwise.
+    ///
+    /// Fee input is fixed when an L1 batch is opened, so the adjusted base fee takes effect starting from the next
+    /// L1 batch (and in API fee estimates); all L2 blocks in a batch share the same base fee.
+    #[serde(default)]
+    pub l2_block_gas_target: Option<u64>,
+    /// Bounds the relative L2 base fee change between consecutive L2 blocks by `1 / l2_base_fee_max_change_denominator`.
+    /// Only used if `l2_block_gas_target` is set. If not specified, the EIP-1559 value (8) is used.
+    #[serde(default)]
+    pub l2_base_fee_max_change_denominator: Option<u64>,
 
     /// The max number of gas to spend on an L1 tx before its batch should be sealed by the gas sealer.
     pub max_single_tx_gas: u32,
@@ -125,6 +209,23 @@ pub struct StateKeeperConfig {
     /// the recursion layers' circuits.
     pub max_circuits_per_batch: usize,
 
+    /// Maximum wall-clock age of an L1 batch in milliseconds. Used by the `l1_batch_age` seal criterion;
+    /// unlike `block_commit_deadline_ms`, the criterion is only checked after executing a transaction.
+    #[serde(default)]
+    pub max_l1_batch_age_ms: Option<u64>,
+    /// Maximum number of L2 blocks in an L1 batch. Used by the `l2_blocks_count` seal criterion.
+    #[serde(default)]
+    pub max_l2_blocks_per_batch: Option<u32>,
+    /// Maximum number of L1 -> L2 priority transactions in an L1 batch. Used by the `priority_txs_count` seal criterion.
+    /// Must be positive if set.
+    #[serde(default)]
+    pub max_priority_txs_per_batch: Option<u32>,
+    /// Seal criteria sets depending on the protocol version. For each protocol version, the set with the greatest
+    /// `from_protocol_version` not exceeding it is used. If there is no such set, [the default criteria](SealCriterionKind::DEFAULT)
+    /// are used. Each set must contain [the required criteria](SealCriterionKind::REQUIRED). Cannot be set via env variables.
+    #[serde(default)]
+    pub seal_criteria: Vec<SealCriteriaSet>,
+
     /// Configures whether to persist protective reads when persisting L1 batches in the state keeper.
     /// Protective reads can be written asynchronously in VM runner instead.
     /// By default, set to `false` as it is expected that a separate `vm_runner_protective_reads` component
@@ -144,6 +245,27 @@ pub struct StateKeeperConfig {
 }
 
 impl StateKeeperConfig {
+    const DEFAULT_L2_BASE_FEE_MAX_CHANGE_DENOMINATOR: u64 = 8;
+
+    /// Validates seal criteria-related params: each criteria set must contain the required criteria, and
+    /// `max_priority_txs_per_batch` must be positive (otherwise, any L1 transaction would be unexecutable).
+    pub fn validate_seal_criteria(&self) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.max_priority_txs_per_batch != Some(0),
+            "`max_priority_txs_per_batch` must be positive"
+        );
+        for set in &self.seal_criteria {
+            set.validate()?;
+        }
+        Ok(())
+    }
+
+    /// Returns the L2 base fee max change denominator, falling back to the default value if it's not specified.
+    pub fn l2_base_fee_max_change_denominator(&self) -> u64 {
+        self.l2_base_fee_max_change_denominator
+            .unwrap_or(Self::DEFAULT_L2_BASE_FEE_MAX_CHANGE_DENOMINATOR)
+    }
+
     /// Creates a config object suitable for use in unit tests.
     /// Values mostly repeat the values used in the localhost environment.
     pub fn for_tests() -> Self {
@@ -154,6 +276,8 @@ impl StateKeeperConfig {
             l2_block_commit_deadline_ms: 1000,
             l2_block_seal_queue_capacity: 10,
             l2_block_max_payload_size: 1_000_000,
+            l2_block_gas_target: None,
+            l2_base_fee_max_change_denominator: None,
             max_single_tx_gas: 6000000,
             max_allowed_l2_tx_gas_limit: 4000000000,
             reject_tx_at_geometry_percentage: 0.95,
@@ -175,6 +299,10 @@ impl StateKeeperConfig {
             validation_computational_gas_limit: 300000,
             save_call_traces: true,
             max_circuits_per_batch: 24100,
+            max_l1_batch_age_ms: None,
+            max_l2_blocks_per_batch: None,
+            max_priority_txs_per_batch: None,
+            seal_criteria: vec![],
             protective_reads_persistence_enabled: true,
             bootloader_hash: None,
             default_aa_hash: None,
@@ -241,3 +369,47 @@ impl MempoolConfig {
         Duration::from_millis(self.delay_interval)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validating_seal_criteria() {
+        let mut config = StateKeeperConfig::for_tests();
+        config.validate_seal_criteria().unwrap();
+
+        config.seal_criteria = vec![SealCriteriaSet {
+            from_protocol_version: 24,
+            criteria: SealCriterionKind::DEFAULT.to_vec(),
+        }];
+        config.validate_seal_criteria().unwrap();
+
+        config.seal_criteria.push(SealCriteriaSet {
+            from_protocol_version: 25,
+            criteria: vec![SealCriterionKind::Slots, SealCriterionKind::Gas],
+        });
+        let err = config.validate_seal_criteria().unwrap_err().to_string();
+        assert!(err.contains("GasForBatchTip"), "{err}");
+        assert!(err.contains("TxEncodingSize"), "{err}");
+        assert!(err.contains(">=25"), "{err}");
+
+        config.seal_criteria.pop();
+        let criteria_without_gas = SealCriterionKind::DEFAULT
+            .iter()
+            .copied()
+            .filter(|&kind| kind != SealCriterionKind::Gas)
+            .collect();
+        config.seal_criteria.push(SealCriteriaSet {
+            from_protocol_version: 25,
+            criteria: criteria_without_gas,
+        });
+        let err = config.validate_seal_criteria().unwrap_err().to_string();
+        assert!(err.contains("[Gas]"), "{err}");
+
+        config.seal_criteria.pop();
+        config.max_priority_txs_per_batch = Some(0);
+        let err = config.validate_seal_criteria().unwrap_err().to_string();
+        assert!(err.contains("max_priority_txs_per_batch"), "{err}");
+    }
+}
@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::{num::NonZeroU32, time::Duration};
 
 use anyhow::Context as _;
 use serde::{Deserialize, Serialize};
@@ -20,6 +20,19 @@ pub enum MerkleTreeMode {
     Lightweight,
 }
 
+/// Storage backend for the Merkle tree.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MerkleTreeStorage {
+    /// Tree nodes are stored in a local RocksDB instance at [`MerkleTreeConfig::path`].
+    #[default]
+    #[serde(rename = "rocksdb")]
+    RocksDB,
+    /// Tree nodes are stored in the main Postgres database. Can be used if local disk for RocksDB is unavailable;
+    /// expect the tree to be significantly slower than with RocksDB. Tree checkpoints are not supported with this storage.
+    Postgres,
+}
+
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct MerkleTreeConfig {
     /// Path to the RocksDB data directory for Merkle tree.
@@ -28,6 +41,9 @@ pub struct MerkleTreeConfig {
     /// Operation mode for the Merkle tree. If not specified, the full mode will be used.
     #[serde(default)]
     pub mode: MerkleTreeMode,
+    /// Storage backend for the Merkle tree. If not specified, RocksDB will be used.
+    #[serde(default)]
+    pub storage: MerkleTreeStorage,
     /// Chunk size for multi-get operations. Can speed up loading data for the Merkle tree on some environments,
     /// but the effects vary wildly depending on the setup (e.g., the filesystem used).
     #[serde(default = "MerkleTreeConfig::default_multi_get_chunk_size")]
@@ -46,6 +62,16 @@ pub struct MerkleTreeConfig {
     /// Maximum number of L1 batches to be processed by the Merkle tree at a time.
     #[serde(default = "MerkleTreeConfig::default_max_l1_batches_per_iter")]
     pub max_l1_batches_per_iter: usize,
+    /// If set, the tree will periodically export RocksDB checkpoints (with the tree version and root hash manifest)
+    /// to the object store. A new checkpoint is exported once the tree advances by the specified number of L1 batches
+    /// since the latest exported checkpoint. Checkpoints are never removed automatically; use object store
+    /// lifecycle policies to limit their retention.
+    #[serde(default)]
+    pub checkpoint_interval_l1_batches: Option<NonZeroU32>,
+    /// Whether to initialize an empty tree from the latest checkpoint in the object store (if any) instead of
+    /// building the tree from scratch. The checkpoint root hash is validated against Postgres before the import.
+    #[serde(default)]
+    pub checkpoint_import_enabled: bool,
 }
 
 impl Default for MerkleTreeConfig {
@@ -53,11 +79,14 @@ impl Default for MerkleTreeConfig {
         Self {
             path: Self::default_path(),
             mode: MerkleTreeMode::default(),
+            storage: MerkleTreeStorage::default(),
             multi_get_chunk_size: Self::default_multi_get_chunk_size(),
             block_cache_size_mb: Self::default_block_cache_size_mb(),
             memtable_capacity_mb: Self::default_memtable_capacity_mb(),
             stalled_writes_timeout_sec: Self::default_stalled_writes_timeout_sec(),
             max_l1_batches_per_iter: Self::default_max_l1_batches_per_iter(),
+            checkpoint_interval_l1_batches: None,
+            checkpoint_import_enabled: false,
         }
     }
 }
@@ -17,6 +17,9 @@ pub struct ENConfig {
     // Main node configuration
     pub main_node_url: SensitiveUrl,
     pub main_node_rate_limit_rps: Option<NonZeroUsize>,
+    /// URLs of upstreams (e.g., trusted external nodes) used if the main node is unavailable, ordered by priority.
+    #[serde(default)]
+    pub main_node_fallback_urls: Vec<SensitiveUrl>,
 
     pub gateway_url: Option<SensitiveUrl>,
 }
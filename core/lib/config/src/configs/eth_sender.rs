@@ -60,6 +60,7 @@ impl EthConfig {
             }),
             watcher: Some(EthWatchConfig {
                 confirmations_for_eth_event: None,
+                confirmations_for_priority_ops: None,
                 eth_node_poll_interval: 0,
             }),
         }
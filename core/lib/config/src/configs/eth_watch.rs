@@ -8,6 +8,12 @@ pub struct EthWatchConfig {
     /// Amount of confirmations for the priority operation to be processed.
     /// If not specified operation will be processed once its block is finalized.
     pub confirmations_for_eth_event: Option<u64>,
+    /// If set, priority operations are processed once they have this many L1 confirmations, i.e. potentially
+    /// before their L1 block is finalized. Such operations are tracked until finalization; if any of them is reorged
+    /// out of L1, the corresponding circuit breaker halts the node. L1 batches containing such operations are committed
+    /// only after their L1 blocks are finalized. Protocol upgrades are still processed only after finalization.
+    #[serde(default)]
+    pub confirmations_for_priority_ops: Option<u64>,
     /// How often we want to poll the Ethereum node.
     /// Value in milliseconds.
     pub eth_node_poll_interval: u64,
@@ -1,6 +1,7 @@
 use std::num::NonZeroU64;
 
 use serde::Deserialize;
+use zksync_basic_types::{Address, H256};
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct PruningConfig {
@@ -16,4 +17,16 @@ pub struct PruningConfig {
     /// the retention period greater than that implicitly imposed by other criteria (e.g., 7 or 30 days).
     /// If set to 0, L1 batches will not be retained based on their timestamp. The default value is 1 hour.
     pub data_retention_sec: Option<u64>,
+    /// Addresses for which transactions, receipts and events are retained beyond the pruning horizon. A transaction
+    /// is retained if it's initiated by or sent to one of these addresses, or if it emits an event from one of these addresses.
+    #[serde(default)]
+    pub retained_addresses: Vec<Address>,
+    /// Event topics for which transactions, receipts and events are retained beyond the pruning horizon. A transaction
+    /// is retained if it emits an event with one of these topics (at any position).
+    #[serde(default)]
+    pub retained_topics: Vec<H256>,
+    /// If set, storage history of `retained_addresses` is retained as well, so that historical storage slots
+    /// of these contracts can be queried for pruned blocks.
+    #[serde(default)]
+    pub retain_storage_history: bool,
 }
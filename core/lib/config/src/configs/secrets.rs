@@ -1,4 +1,5 @@
 use anyhow::Context;
+use secrecy::{ExposeSecret as _, Secret};
 use zksync_basic_types::url::SensitiveUrl;
 
 use crate::configs::consensus::ConsensusSecrets;
@@ -15,11 +16,25 @@ pub struct L1Secrets {
     pub l1_rpc_url: SensitiveUrl,
 }
 
+/// Secrets for the admin server.
+#[derive(Debug, Clone)]
+pub struct AdminSecrets {
+    /// Bearer token required by the admin server for all requests.
+    pub auth_token: Secret<String>,
+}
+
+impl PartialEq for AdminSecrets {
+    fn eq(&self, other: &Self) -> bool {
+        self.auth_token.expose_secret() == other.auth_token.expose_secret()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Secrets {
     pub consensus: Option<ConsensusSecrets>,
     pub database: Option<DatabaseSecrets>,
     pub l1: Option<L1Secrets>,
+    pub admin: Option<AdminSecrets>,
 }
 
 impl DatabaseSecrets {
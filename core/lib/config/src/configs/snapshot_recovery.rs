@@ -1,7 +1,7 @@
-use std::num::NonZeroUsize;
+use std::num::{NonZeroU64, NonZeroUsize};
 
 use serde::Deserialize;
-use zksync_basic_types::L1BatchNumber;
+use zksync_basic_types::{url::SensitiveUrl, L1BatchNumber};
 
 use crate::ObjectStoreConfig;
 
@@ -28,6 +28,20 @@ pub struct PostgresRecoveryConfig {
     pub max_concurrency: Option<NonZeroUsize>,
 }
 
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct L1RecoveryConfig {
+    /// First L1 block to scan for committed L1 batches. Should be set to the block the diamond proxy contract
+    /// of the chain was deployed in.
+    pub first_l1_block: u64,
+    /// Maximum number of L1 blocks to query events for in a single request. If not set, a default value is used.
+    pub l1_block_range: Option<NonZeroU64>,
+    /// Base URL of a blob archive API (e.g., Blobscan) used to fetch blobs with pubdata. Required to recover
+    /// L1 batches that published pubdata in blobs; consensus layer nodes prune blobs after several weeks,
+    /// so an archive is necessary in the general case.
+    #[serde(default)]
+    pub blob_api_url: Option<SensitiveUrl>,
+}
+
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct SnapshotRecoveryConfig {
     /// Enables application-level snapshot recovery. Required to start a node that was recovered from a snapshot,
@@ -42,6 +56,17 @@ pub struct SnapshotRecoveryConfig {
     /// This is a temporary flag that will eventually be removed together with version 0 snapshot support.
     #[serde(default)]
     pub drop_storage_key_preimages: bool,
+    /// Path to a local snapshot archive (produced by the `export` command of the snapshots creator) to recover from.
+    /// If set, all recovery data is read from the archive instead of the object store and the main node.
+    #[serde(default)]
+    pub archive_path: Option<String>,
+    /// If set, node state is recovered from data published on L1 instead of a snapshot. `l1_batch` specifies
+    /// the L1 batch to recover; if it's not set, the last executed L1 batch is recovered.
+    ///
+    /// This is an experimental feature; it only supports rollup chains that published all their pubdata
+    /// via calldata or blobs.
+    #[serde(default)]
+    pub l1: Option<L1RecoveryConfig>,
     pub tree: TreeRecoveryConfig,
     pub postgres: PostgresRecoveryConfig,
     pub object_store: Option<ObjectStoreConfig>,
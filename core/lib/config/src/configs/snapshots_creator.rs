@@ -16,6 +16,14 @@ pub struct SnapshotsCreatorConfig {
     /// - If a snapshot with this L1 batch exists and is incomplete, the creator will continue creating it,
     ///   regardless of whether the specified snapshot `version` matches.
     pub l1_batch_number: Option<L1BatchNumber>,
+    /// Maximum number of delta snapshots in a chain ending with a full snapshot. If set to a positive value,
+    /// the creator will produce a delta snapshot containing only storage logs and factory deps changed since
+    /// the latest complete snapshot, provided that the resulting chain doesn't exceed this length; otherwise,
+    /// a full snapshot is created. If set to 0 (the default), only full snapshots are created.
+    ///
+    /// Delta snapshots are only supported for snapshot version 1.
+    #[serde(default)]
+    pub max_delta_chain_length: u32,
     #[serde(default = "SnapshotsCreatorConfig::storage_logs_chunk_size_default")]
     pub storage_logs_chunk_size: u64,
     #[serde(default = "SnapshotsCreatorConfig::concurrent_queries_count")]
@@ -1,4 +1,7 @@
-use std::num::NonZeroUsize;
+use std::{
+    net::IpAddr,
+    num::{NonZeroU32, NonZeroUsize},
+};
 
 use rand::{distributions::Distribution, Rng};
 use zksync_basic_types::{
@@ -50,6 +53,37 @@ impl Distribution<configs::chain::FeeModelVersion> for EncodeDist {
     }
 }
 
+impl Distribution<configs::chain::SealCriterionKind> for EncodeDist {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::chain::SealCriterionKind {
+        type T = configs::chain::SealCriterionKind;
+        match rng.gen_range(0..9) {
+            0 => T::Slots,
+            1 => T::Gas,
+            2 => T::PubdataBytes,
+            3 => T::Circuits,
+            4 => T::TxEncodingSize,
+            5 => T::GasForBatchTip,
+            6 => T::L1BatchAge,
+            7 => T::L2BlocksCount,
+            _ => T::PriorityTxsCount,
+        }
+    }
+}
+
+impl Distribution<configs::chain::SealCriteriaSet> for EncodeDist {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::chain::SealCriteriaSet {
+        type T = configs::chain::SealCriterionKind;
+        // Required criteria are always present so that the set passes validation.
+        let mut criteria = T::REQUIRED.to_vec();
+        let extra_criteria: Vec<T> = self.sample_collect(rng);
+        criteria.extend(extra_criteria);
+        configs::chain::SealCriteriaSet {
+            from_protocol_version: self.sample(rng),
+            criteria,
+        }
+    }
+}
+
 impl Distribution<configs::ApiConfig> for EncodeDist {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::ApiConfig {
         configs::ApiConfig {
@@ -68,6 +102,11 @@ impl Distribution<configs::api::Web3JsonRpcConfig> for EncodeDist {
             http_url: self.sample(rng),
             ws_port: self.sample(rng),
             ws_url: self.sample(rng),
+            ipc_path: self.sample(rng),
+            ipc_max_connections: self.sample(rng),
+            ipc_subscriptions_per_connection_limit: self.sample(rng),
+            ipc_max_concurrent_requests_per_connection: self.sample(rng),
+            ipc_socket_permissions: self.sample(rng),
             req_entities_limit: self.sample(rng),
             filters_disabled: self.sample(rng),
             filters_limit: self.sample(rng),
@@ -84,6 +123,7 @@ impl Distribution<configs::api::Web3JsonRpcConfig> for EncodeDist {
             initial_writes_cache_size_mb: self.sample(rng),
             latest_values_cache_size_mb: self.sample(rng),
             fee_history_limit: self.sample(rng),
+            trace_filter_block_range_limit: self.sample(rng),
             max_batch_request_size: self.sample(rng),
             max_response_body_size_mb: self.sample(rng),
             max_response_body_size_overrides_mb: [
@@ -99,6 +139,29 @@ impl Distribution<configs::api::Web3JsonRpcConfig> for EncodeDist {
             .into_iter()
             .collect(),
             websocket_requests_per_minute_limit: self.sample(rng),
+            http_compute_units_per_minute_limit: self.sample(rng),
+            http_api_key_compute_units_per_minute_limits: [(
+                "test-api-key",
+                NonZeroU32::new(self.sample(rng)).unwrap_or(NonZeroU32::MAX),
+            )]
+            .into_iter()
+            .collect(),
+            method_compute_units: [
+                (
+                    "eth_getLogs",
+                    NonZeroU32::new(self.sample(rng)).unwrap_or(NonZeroU32::MAX),
+                ),
+                (
+                    "debug_traceCall",
+                    NonZeroU32::new(self.sample(rng)).unwrap_or(NonZeroU32::MAX),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+            http_trusted_proxies: self
+                .sample_range(rng)
+                .map(|_| IpAddr::from(rng.gen::<[u8; 4]>()))
+                .collect(),
             tree_api_url: self.sample(rng),
             mempool_cache_update_interval: self.sample(rng),
             mempool_cache_size: self.sample(rng),
@@ -106,6 +169,7 @@ impl Distribution<configs::api::Web3JsonRpcConfig> for EncodeDist {
             api_namespaces: self
                 .sample_opt(|| self.sample_range(rng).map(|_| self.sample(rng)).collect()),
             extended_api_tracing: self.sample(rng),
+            preconfirmations_enabled: self.sample(rng),
         }
     }
 }
@@ -116,6 +180,7 @@ impl Distribution<configs::api::HealthCheckConfig> for EncodeDist {
             port: self.sample(rng),
             slow_time_limit_ms: self.sample(rng),
             hard_time_limit_ms: self.sample(rng),
+            admin_port: self.sample(rng),
         }
     }
 }
@@ -166,6 +231,8 @@ impl Distribution<configs::chain::StateKeeperConfig> for EncodeDist {
             l2_block_commit_deadline_ms: self.sample(rng),
             l2_block_seal_queue_capacity: self.sample(rng),
             l2_block_max_payload_size: self.sample(rng),
+            l2_block_gas_target: self.sample(rng),
+            l2_base_fee_max_change_denominator: self.sample(rng),
             max_single_tx_gas: self.sample(rng),
             max_allowed_l2_tx_gas_limit: self.sample(rng),
             reject_tx_at_geometry_percentage: self.sample(rng),
@@ -184,6 +251,10 @@ impl Distribution<configs::chain::StateKeeperConfig> for EncodeDist {
             validation_computational_gas_limit: self.sample(rng),
             save_call_traces: self.sample(rng),
             max_circuits_per_batch: self.sample(rng),
+            max_l1_batch_age_ms: self.sample(rng),
+            max_l2_blocks_per_batch: self.sample(rng),
+            max_priority_txs_per_batch: self.sample_opt(|| rng.gen_range(1..100)),
+            seal_criteria: self.sample_collect(rng),
             protective_reads_persistence_enabled: self.sample(rng),
             // These values are not involved into files serialization skip them
             fee_account_addr: None,
@@ -272,16 +343,29 @@ impl Distribution<configs::database::MerkleTreeMode> for EncodeDist {
     }
 }
 
+impl Distribution<configs::database::MerkleTreeStorage> for EncodeDist {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::database::MerkleTreeStorage {
+        type T = configs::database::MerkleTreeStorage;
+        match rng.gen_range(0..2) {
+            0 => T::RocksDB,
+            _ => T::Postgres,
+        }
+    }
+}
+
 impl Distribution<configs::database::MerkleTreeConfig> for EncodeDist {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::database::MerkleTreeConfig {
         configs::database::MerkleTreeConfig {
             path: self.sample(rng),
             mode: self.sample(rng),
+            storage: self.sample(rng),
             multi_get_chunk_size: self.sample(rng),
             block_cache_size_mb: self.sample(rng),
             memtable_capacity_mb: self.sample(rng),
             stalled_writes_timeout_sec: self.sample(rng),
             max_l1_batches_per_iter: self.sample(rng),
+            checkpoint_interval_l1_batches: self.sample_opt(|| rng.gen()),
+            checkpoint_import_enabled: self.sample(rng),
         }
     }
 }
@@ -445,6 +529,7 @@ impl Distribution<configs::EthWatchConfig> for EncodeDist {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::EthWatchConfig {
         configs::EthWatchConfig {
             confirmations_for_eth_event: self.sample(rng),
+            confirmations_for_priority_ops: self.sample(rng),
             eth_node_poll_interval: self.sample(rng),
         }
     }
@@ -684,6 +769,7 @@ impl Distribution<configs::SnapshotsCreatorConfig> for EncodeDist {
         configs::SnapshotsCreatorConfig {
             l1_batch_number: self.sample_opt(|| L1BatchNumber(rng.gen())),
             version: if rng.gen() { 0 } else { 1 },
+            max_delta_chain_length: self.sample(rng),
             storage_logs_chunk_size: self.sample(rng),
             concurrent_queries_count: self.sample(rng),
             object_store: self.sample(rng),
@@ -850,6 +936,14 @@ impl Distribution<configs::secrets::DatabaseSecrets> for EncodeDist {
     }
 }
 
+impl Distribution<configs::secrets::AdminSecrets> for EncodeDist {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::secrets::AdminSecrets {
+        configs::secrets::AdminSecrets {
+            auth_token: String::into(self.sample(rng)),
+        }
+    }
+}
+
 impl Distribution<configs::secrets::Secrets> for EncodeDist {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::secrets::Secrets {
         use configs::secrets::Secrets;
@@ -857,6 +951,7 @@ impl Distribution<configs::secrets::Secrets> for EncodeDist {
             consensus: self.sample_opt(|| self.sample(rng)),
             database: self.sample_opt(|| self.sample(rng)),
             l1: self.sample_opt(|| self.sample(rng)),
+            admin: self.sample_opt(|| self.sample(rng)),
         }
     }
 }
@@ -920,6 +1015,9 @@ impl Distribution<configs::en_config::ENConfig> for EncodeDist {
                 _ => L1BatchCommitmentMode::Validium,
             },
             main_node_rate_limit_rps: self.sample_opt(|| rng.gen()),
+            main_node_fallback_urls: (0..rng.gen_range(0..3))
+                .map(|_| format!("localhost:{}", rng.gen::<u16>()).parse().unwrap())
+                .collect(),
             gateway_url: self
                 .sample_opt(|| format!("localhost:{}", rng.gen::<u16>()).parse().unwrap()),
         }
@@ -1023,6 +1121,19 @@ impl Distribution<configs::snapshot_recovery::SnapshotRecoveryConfig> for Encode
             tree,
             postgres: self.sample(rng),
             object_store: self.sample(rng),
+            archive_path: self.sample(rng),
+            l1: self.sample(rng),
+        }
+    }
+}
+
+impl Distribution<configs::snapshot_recovery::L1RecoveryConfig> for EncodeDist {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::snapshot_recovery::L1RecoveryConfig {
+        configs::snapshot_recovery::L1RecoveryConfig {
+            first_l1_block: self.sample(rng),
+            l1_block_range: self.sample_opt(|| rng.gen()),
+            blob_api_url: self
+                .sample_opt(|| format!("localhost:{}", rng.gen::<u16>()).parse().unwrap()),
         }
     }
 }
@@ -1034,6 +1145,9 @@ impl Distribution<configs::pruning::PruningConfig> for EncodeDist {
             chunk_size: self.sample(rng),
             removal_delay_sec: self.sample_opt(|| rng.gen()),
             data_retention_sec: self.sample(rng),
+            retained_addresses: self.sample_range(rng).map(|_| rng.gen()).collect(),
+            retained_topics: self.sample_range(rng).map(|_| rng.gen()).collect(),
+            retain_storage_history: self.sample(rng),
         }
     }
 }
@@ -1154,6 +1268,17 @@ impl Distribution<configs::GeneralConfig> for EncodeDist {
             external_proof_integration_api_config: self.sample(rng),
             experimental_vm_config: self.sample(rng),
             prover_job_monitor_config: self.sample(rng),
+            address_policy_config: self.sample(rng),
+        }
+    }
+}
+
+impl Distribution<configs::AddressPolicyConfig> for EncodeDist {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::AddressPolicyConfig {
+        configs::AddressPolicyConfig {
+            policy_file_path: self.sample(rng),
+            onchain_denylist_addr: self.sample_opt(|| rng.gen()),
+            reload_interval_ms: self.sample(rng),
         }
     }
 }
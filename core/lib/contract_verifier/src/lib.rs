@@ -6,19 +6,14 @@ use std::{
 
 use anyhow::Context as _;
 use chrono::Utc;
-use ethabi::{Contract, Token};
-use lazy_static::lazy_static;
 use regex::Regex;
 use tokio::time;
 use zksync_config::ContractVerifierConfig;
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
 use zksync_queued_job_processor::{async_trait, JobProcessor};
-use zksync_types::{
-    contract_verification_api::{
-        CompilationArtifacts, CompilerType, DeployContractCalldata, SourceCodeData,
-        VerificationInfo, VerificationRequest,
-    },
-    Address,
+use zksync_types::contract_verification_api::{
+    CompilationArtifacts, CompilerType, ConstructorArgs, SourceCodeData, VerificationInfo,
+    VerificationMatchType, VerificationRequest,
 };
 use zksync_utils::env::Workspace;
 
@@ -30,24 +25,17 @@ use crate::{
 };
 
 pub mod error;
+mod metadata;
 mod metrics;
+#[cfg(test)]
+mod tests;
 mod zksolc_utils;
 mod zkvyper_utils;
 
-lazy_static! {
-    static ref DEPLOYER_CONTRACT: Contract = zksync_contracts::deployer_contract();
-}
-
 fn home_path() -> PathBuf {
     Workspace::locate().core()
 }
 
-#[derive(Debug)]
-enum ConstructorArgs {
-    Check(Vec<u8>),
-    Ignore,
-}
-
 #[derive(Debug)]
 pub struct ContractVerifier {
     config: ContractVerifierConfig,
@@ -78,20 +66,9 @@ impl ContractVerifier {
                 tracing::warn!("Contract is missing in DB for already accepted verification request. Contract address: {:#?}", request.req.contract_address);
                 ContractVerifierError::InternalError
             })?;
-        let constructor_args = Self::decode_constructor_arguments_from_calldata(
-            creation_tx_calldata,
-            request.req.contract_address,
-        );
+        let constructor_args = creation_tx_calldata.constructor_args(request.req.contract_address);
 
-        if artifacts.bytecode != deployed_bytecode {
-            tracing::info!(
-                "Bytecode mismatch req {}, deployed: 0x{}, compiled 0x{}",
-                request.id,
-                hex::encode(deployed_bytecode),
-                hex::encode(artifacts.bytecode)
-            );
-            return Err(ContractVerifierError::BytecodeMismatch);
-        }
+        let match_type = Self::match_bytecodes(&request, &artifacts.bytecode, &deployed_bytecode)?;
 
         match constructor_args {
             ConstructorArgs::Check(args) => {
@@ -108,9 +85,41 @@ impl ContractVerifier {
             request,
             artifacts,
             verified_at: Utc::now(),
+            match_type,
+            similar_match_address: None,
         })
     }
 
+    /// Compares the compiled bytecode with the deployed one. Bytecodes differing only in metadata are considered
+    /// a partial match, provided that metadata is enabled in compiler settings and is present in both bytecodes.
+    fn match_bytecodes(
+        request: &VerificationRequest,
+        compiled_bytecode: &[u8],
+        deployed_bytecode: &[u8],
+    ) -> Result<VerificationMatchType, ContractVerifierError> {
+        if compiled_bytecode == deployed_bytecode {
+            return Ok(VerificationMatchType::Full);
+        }
+
+        if metadata::is_metadata_enabled(&request.req.source_code_data)
+            && metadata::bytecodes_match_without_metadata(compiled_bytecode, deployed_bytecode)
+        {
+            tracing::info!(
+                "Bytecode for req {} matches the deployed one only without metadata",
+                request.id
+            );
+            Ok(VerificationMatchType::Partial)
+        } else {
+            tracing::info!(
+                "Bytecode mismatch req {}, deployed: 0x{}, compiled 0x{}",
+                request.id,
+                hex::encode(deployed_bytecode),
+                hex::encode(compiled_bytecode)
+            );
+            Err(ContractVerifierError::BytecodeMismatch)
+        }
+    }
+
     async fn compile_zksolc(
         request: VerificationRequest,
         config: ContractVerifierConfig,
@@ -366,82 +375,6 @@ impl ContractVerifier {
         })
     }
 
-    fn decode_constructor_arguments_from_calldata(
-        calldata: DeployContractCalldata,
-        contract_address_to_verify: Address,
-    ) -> ConstructorArgs {
-        match calldata {
-            DeployContractCalldata::Deploy(calldata) => {
-                let create = DEPLOYER_CONTRACT.function("create").unwrap();
-                let create2 = DEPLOYER_CONTRACT.function("create2").unwrap();
-
-                let create_acc = DEPLOYER_CONTRACT.function("createAccount").unwrap();
-                let create2_acc = DEPLOYER_CONTRACT.function("create2Account").unwrap();
-
-                let force_deploy = DEPLOYER_CONTRACT
-                    .function("forceDeployOnAddresses")
-                    .unwrap();
-                // It's assumed that `create` and `create2` methods have the same parameters
-                // and the same for `createAccount` and `create2Account`.
-                match &calldata[0..4] {
-                    selector
-                        if selector == create.short_signature()
-                            || selector == create2.short_signature() =>
-                    {
-                        let tokens = create
-                            .decode_input(&calldata[4..])
-                            .expect("Failed to decode input");
-                        // Constructor arguments are in the third parameter.
-                        ConstructorArgs::Check(tokens[2].clone().into_bytes().expect(
-                            "The third parameter of `create/create2` should be of type `bytes`",
-                        ))
-                    }
-                    selector
-                        if selector == create_acc.short_signature()
-                            || selector == create2_acc.short_signature() =>
-                    {
-                        let tokens = create
-                            .decode_input(&calldata[4..])
-                            .expect("Failed to decode input");
-                        // Constructor arguments are in the third parameter.
-                        ConstructorArgs::Check(
-                            tokens[2].clone().into_bytes().expect(
-                                "The third parameter of `createAccount/create2Account` should be of type `bytes`",
-                            ),
-                        )
-                    }
-                    selector if selector == force_deploy.short_signature() => {
-                        let tokens = force_deploy
-                            .decode_input(&calldata[4..])
-                            .expect("Failed to decode input");
-                        let deployments = tokens[0].clone().into_array().unwrap();
-                        for deployment in deployments {
-                            match deployment {
-                                Token::Tuple(tokens) => {
-                                    let address = tokens[1].clone().into_address().unwrap();
-                                    if address == contract_address_to_verify {
-                                        let call_constructor =
-                                            tokens[2].clone().into_bool().unwrap();
-                                        return if call_constructor {
-                                            let input = tokens[4].clone().into_bytes().unwrap();
-                                            ConstructorArgs::Check(input)
-                                        } else {
-                                            ConstructorArgs::Ignore
-                                        };
-                                    }
-                                }
-                                _ => panic!("Expected `deployment` to be a tuple"),
-                            }
-                        }
-                        panic!("Couldn't find force deployment for given address");
-                    }
-                    _ => ConstructorArgs::Ignore,
-                }
-            }
-            DeployContractCalldata::Ignore => ConstructorArgs::Ignore,
-        }
-    }
-
     async fn process_result(
         storage: &mut Connection<'_, Core>,
         request_id: usize,
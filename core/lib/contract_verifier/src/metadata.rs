@@ -0,0 +1,296 @@
+//! Handling of the metadata section appended to EraVM bytecodes by `zksolc` and `zkvyper`.
+//!
+//! Modern compiler versions (`zksolc` / `zkvyper` 1.5+) append a CBOR-encoded map followed by its length encoded
+//! as 2 big-endian bytes, similar to `solc`. The result is padded with zero bytes so that the bytecode consists
+//! of an odd number of words.
+//!
+//! Bytecodes are only compared without metadata if metadata is enabled in compiler settings, and if both bytecodes
+//! end with metadata that can be parsed exactly: the length trailer must point to a well-formed CBOR map spanning
+//! all bytes between the code and the trailer. Legacy metadata (a bare 32-byte hash word) is not recognized since
+//! it cannot be distinguished from code.
+
+use serde_json::Value;
+use zksync_types::contract_verification_api::SourceCodeData;
+
+const WORD_SIZE: usize = 32;
+
+/// Checks whether metadata is appended to bytecodes compiled with the provided sources and settings.
+pub(crate) fn is_metadata_enabled(source_code_data: &SourceCodeData) -> bool {
+    let SourceCodeData::StandardJsonInput(input) = source_code_data else {
+        // Metadata is enabled by default.
+        return true;
+    };
+    let Some(metadata) = input
+        .get("settings")
+        .and_then(|settings| settings.get("metadata"))
+    else {
+        return true;
+    };
+    // `zksolc` 1.5+ uses `bytecodeHash` similar to `solc`; older versions use `hash`.
+    let is_hash_disabled = ["bytecodeHash", "hash"]
+        .into_iter()
+        .any(|field| metadata.get(field).and_then(Value::as_str) == Some("none"));
+    let is_cbor_disabled = metadata.get("appendCBOR").and_then(Value::as_bool) == Some(false);
+    !is_hash_disabled && !is_cbor_disabled
+}
+
+/// Checks whether the provided bytecodes are equal after stripping their metadata sections. Returns `false`
+/// if any of the bytecodes doesn't end with a metadata section.
+pub(crate) fn bytecodes_match_without_metadata(compiled: &[u8], deployed: &[u8]) -> bool {
+    match (strip_cbor_metadata(compiled), strip_cbor_metadata(deployed)) {
+        (Some(compiled), Some(deployed)) => !compiled.is_empty() && compiled == deployed,
+        _ => false,
+    }
+}
+
+/// Returns the bytecode part preceding CBOR metadata, or `None` if the bytecode doesn't end with CBOR metadata.
+fn strip_cbor_metadata(bytecode: &[u8]) -> Option<&[u8]> {
+    if bytecode.len() % WORD_SIZE != 0 {
+        return None;
+    }
+    // Metadata can be followed by less than 2 words of zero padding.
+    let min_end = bytecode.len().saturating_sub(2 * WORD_SIZE - 1).max(2);
+    let mut end = bytecode.len();
+    while end >= min_end {
+        let metadata_len = usize::from(u16::from_be_bytes([bytecode[end - 2], bytecode[end - 1]]));
+        if let Some(start) = (end - 2).checked_sub(metadata_len) {
+            if is_cbor_map(&bytecode[start..end - 2]) {
+                return Some(&bytecode[..start]);
+            }
+        }
+        // Only zero bytes can follow the metadata.
+        if bytecode[end - 1] != 0 {
+            break;
+        }
+        end -= 1;
+    }
+    None
+}
+
+/// Checks that the payload is exactly one CBOR map with non-empty alphanumeric text keys (e.g., `{"ipfs": ..}`
+/// or `{"solc": .., "zksolc": ..}`) and scalar values.
+fn is_cbor_map(payload: &[u8]) -> bool {
+    let mut reader = CborReader { bytes: payload };
+    reader.read_metadata_map().is_some() && reader.bytes.is_empty()
+}
+
+/// Minimal CBOR reader sufficient to parse metadata maps emitted by compilers.
+#[derive(Debug)]
+struct CborReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> CborReader<'a> {
+    const MAJOR_TYPE_UNSIGNED: u8 = 0;
+    const MAJOR_TYPE_NEGATIVE: u8 = 1;
+    const MAJOR_TYPE_BYTES: u8 = 2;
+    const MAJOR_TYPE_TEXT: u8 = 3;
+    const MAJOR_TYPE_MAP: u8 = 5;
+    const MAJOR_TYPE_SIMPLE: u8 = 7;
+
+    fn read_bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.bytes.len() < len {
+            return None;
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Some(head)
+    }
+
+    /// Reads the major type and the argument of a CBOR data item.
+    fn read_header(&mut self) -> Option<(u8, u64)> {
+        let initial_byte = self.read_bytes(1)?[0];
+        let major_type = initial_byte >> 5;
+        let argument = match initial_byte & 0x1f {
+            short @ 0..=23 => u64::from(short),
+            24 => u64::from(self.read_bytes(1)?[0]),
+            25 => u64::from(u16::from_be_bytes(self.read_bytes(2)?.try_into().ok()?)),
+            26 => u64::from(u32::from_be_bytes(self.read_bytes(4)?.try_into().ok()?)),
+            27 => u64::from_be_bytes(self.read_bytes(8)?.try_into().ok()?),
+            // Indefinite-length items and reserved values are never emitted for metadata.
+            _ => return None,
+        };
+        Some((major_type, argument))
+    }
+
+    fn read_string(&mut self, expected_type: u8) -> Option<&'a [u8]> {
+        let (major_type, len) = self.read_header()?;
+        if major_type != expected_type {
+            return None;
+        }
+        self.read_bytes(usize::try_from(len).ok()?)
+    }
+
+    fn skip_scalar_value(&mut self) -> Option<()> {
+        let (major_type, argument) = self.read_header()?;
+        match major_type {
+            Self::MAJOR_TYPE_UNSIGNED | Self::MAJOR_TYPE_NEGATIVE => {}
+            Self::MAJOR_TYPE_BYTES | Self::MAJOR_TYPE_TEXT => {
+                self.read_bytes(usize::try_from(argument).ok()?)?;
+            }
+            // `false` / `true`
+            Self::MAJOR_TYPE_SIMPLE if matches!(argument, 20 | 21) => {}
+            _ => return None,
+        }
+        Some(())
+    }
+
+    fn read_metadata_map(&mut self) -> Option<()> {
+        let (major_type, entry_count) = self.read_header()?;
+        if major_type != Self::MAJOR_TYPE_MAP || entry_count == 0 {
+            return None;
+        }
+        for _ in 0..entry_count {
+            let key = self.read_string(Self::MAJOR_TYPE_TEXT)?;
+            if key.is_empty() || !key.iter().all(u8::is_ascii_alphanumeric) {
+                return None;
+            }
+            self.skip_scalar_value()?;
+        }
+        Some(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code(word_count: usize) -> Vec<u8> {
+        (0..word_count * WORD_SIZE)
+            .map(|i| (i % 251) as u8 + 1)
+            .collect()
+    }
+
+    fn pad(mut bytecode: Vec<u8>) -> Vec<u8> {
+        while bytecode.len() % WORD_SIZE != 0 || (bytecode.len() / WORD_SIZE) % 2 == 0 {
+            bytecode.push(0);
+        }
+        bytecode
+    }
+
+    fn with_cbor_metadata(mut bytecode: Vec<u8>, hash: u8) -> Vec<u8> {
+        // `{"ipfs": <34 bytes>, "solc": "0.8.24"}`
+        let mut metadata = vec![0xa2, 0x64];
+        metadata.extend_from_slice(b"ipfs");
+        metadata.extend_from_slice(&[0x58, 34]);
+        metadata.extend_from_slice(&[hash; 34]);
+        metadata.push(0x64);
+        metadata.extend_from_slice(b"solc");
+        metadata.push(0x66);
+        metadata.extend_from_slice(b"0.8.24");
+        bytecode.extend_from_slice(&metadata);
+        bytecode.extend_from_slice(&(metadata.len() as u16).to_be_bytes());
+        pad(bytecode)
+    }
+
+    #[test]
+    fn stripping_cbor_metadata() {
+        let bytecode = with_cbor_metadata(code(3), 1);
+        assert_eq!(strip_cbor_metadata(&bytecode).unwrap(), code(3));
+
+        assert!(bytecodes_match_without_metadata(
+            &with_cbor_metadata(code(3), 1),
+            &with_cbor_metadata(code(3), 2)
+        ));
+        assert!(!bytecodes_match_without_metadata(
+            &with_cbor_metadata(code(3), 1),
+            &with_cbor_metadata(code(5), 1)
+        ));
+        let mut other_code = code(3);
+        other_code[10] = 0;
+        assert!(!bytecodes_match_without_metadata(
+            &with_cbor_metadata(code(3), 1),
+            &with_cbor_metadata(other_code, 1)
+        ));
+    }
+
+    #[test]
+    fn bytecode_without_metadata_is_not_stripped() {
+        assert_eq!(strip_cbor_metadata(&code(3)), None);
+        assert_eq!(strip_cbor_metadata(&[]), None);
+        assert_eq!(strip_cbor_metadata(&[0; 3 * WORD_SIZE]), None);
+        assert!(!bytecodes_match_without_metadata(&[], &[]));
+    }
+
+    #[test]
+    fn bytecodes_differing_in_last_word_without_metadata_do_not_match() {
+        let compiled = code(3);
+        let mut deployed = code(3);
+        for byte in &mut deployed[2 * WORD_SIZE..] {
+            *byte = 0xff;
+        }
+        assert!(!bytecodes_match_without_metadata(&compiled, &deployed));
+
+        // Same with a last word that was previously treated as a legacy metadata hash.
+        let mut compiled = code(2);
+        compiled.extend_from_slice(&[1; WORD_SIZE]);
+        let mut deployed = code(2);
+        deployed.extend_from_slice(&[2; WORD_SIZE]);
+        assert!(!bytecodes_match_without_metadata(&compiled, &deployed));
+    }
+
+    #[test]
+    fn metadata_with_inexact_length_is_not_stripped() {
+        let bytecode = with_cbor_metadata(code(3), 1);
+        let metadata_end = bytecode.iter().rposition(|&byte| byte != 0).unwrap() + 1;
+        let (len_hi, len_lo) = (metadata_end - 2, metadata_end - 1);
+        let metadata_len = u16::from_be_bytes([bytecode[len_hi], bytecode[len_lo]]);
+
+        // Length trailer pointing to a prefix of the map, or capturing a code byte before it.
+        for wrong_len in [metadata_len - 1, metadata_len + 1] {
+            let mut bytecode = bytecode.clone();
+            [bytecode[len_hi], bytecode[len_lo]] = wrong_len.to_be_bytes();
+            assert_eq!(strip_cbor_metadata(&bytecode), None, "{wrong_len}");
+        }
+
+        // Non-zero byte after the metadata.
+        let mut bytecode = bytecode.clone();
+        *bytecode.last_mut().unwrap() = 1;
+        assert_eq!(strip_cbor_metadata(&bytecode), None);
+    }
+
+    #[test]
+    fn truncated_or_non_map_cbor_is_rejected() {
+        assert!(!is_cbor_map(&[]));
+        // `{"ipfs": <34 bytes>}` with the byte string truncated
+        let mut payload = vec![0xa1, 0x64];
+        payload.extend_from_slice(b"ipfs");
+        payload.extend_from_slice(&[0x58, 34]);
+        payload.extend_from_slice(&[0; 33]);
+        assert!(!is_cbor_map(&payload));
+        payload.push(0);
+        assert!(is_cbor_map(&payload));
+        // Trailing data after the map
+        payload.push(0);
+        assert!(!is_cbor_map(&payload));
+        // Array instead of a map
+        assert!(!is_cbor_map(&[0x81, 0x01]));
+    }
+
+    #[test]
+    fn detecting_disabled_metadata() {
+        assert!(is_metadata_enabled(&SourceCodeData::SolSingleFile(
+            String::new()
+        )));
+
+        let input_with_metadata = |metadata: Value| {
+            let input = serde_json::json!({
+                "language": "Solidity",
+                "sources": {},
+                "settings": { "metadata": metadata },
+            });
+            let Value::Object(input) = input else {
+                unreachable!();
+            };
+            SourceCodeData::StandardJsonInput(input)
+        };
+        let enabled = input_with_metadata(serde_json::json!({ "bytecodeHash": "ipfs" }));
+        assert!(is_metadata_enabled(&enabled));
+        let disabled = input_with_metadata(serde_json::json!({ "bytecodeHash": "none" }));
+        assert!(!is_metadata_enabled(&disabled));
+        let disabled = input_with_metadata(serde_json::json!({ "hash": "none" }));
+        assert!(!is_metadata_enabled(&disabled));
+        let disabled = input_with_metadata(serde_json::json!({ "appendCBOR": false }));
+        assert!(!is_metadata_enabled(&disabled));
+    }
+}
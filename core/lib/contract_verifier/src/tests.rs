@@ -0,0 +1,126 @@
+//! Tests for the contract verifier.
+
+use zksync_types::{
+    contract_verification_api::{CompilerVersions, VerificationIncomingRequest},
+    Address,
+};
+
+use super::*;
+
+const WORD_SIZE: usize = 32;
+
+fn mock_request(source_code_data: SourceCodeData) -> VerificationRequest {
+    VerificationRequest {
+        id: 1,
+        req: VerificationIncomingRequest {
+            contract_address: Address::repeat_byte(1),
+            source_code_data,
+            contract_name: "Counter".to_owned(),
+            compiler_versions: CompilerVersions::Solc {
+                compiler_zksolc_version: "v1.5.0".to_owned(),
+                compiler_solc_version: "0.8.24".to_owned(),
+            },
+            optimization_used: true,
+            optimizer_mode: None,
+            constructor_arguments: Default::default(),
+            is_system: false,
+            force_evmla: false,
+        },
+    }
+}
+
+fn standard_json_request(metadata: serde_json::Value) -> VerificationRequest {
+    let input = serde_json::json!({
+        "language": "Solidity",
+        "sources": {},
+        "settings": { "metadata": metadata },
+    });
+    let serde_json::Value::Object(input) = input else {
+        unreachable!();
+    };
+    mock_request(SourceCodeData::StandardJsonInput(input))
+}
+
+fn mock_code() -> Vec<u8> {
+    (0..3 * WORD_SIZE).map(|i| i as u8 + 1).collect()
+}
+
+/// Appends `{"ipfs": <34 bytes>}` CBOR metadata and padding to the provided code.
+fn with_metadata(mut bytecode: Vec<u8>, hash: u8) -> Vec<u8> {
+    let mut metadata = vec![0xa1, 0x64];
+    metadata.extend_from_slice(b"ipfs");
+    metadata.extend_from_slice(&[0x58, 34]);
+    metadata.extend_from_slice(&[hash; 34]);
+    bytecode.extend_from_slice(&metadata);
+    bytecode.extend_from_slice(&(metadata.len() as u16).to_be_bytes());
+    while bytecode.len() % WORD_SIZE != 0 || (bytecode.len() / WORD_SIZE) % 2 == 0 {
+        bytecode.push(0);
+    }
+    bytecode
+}
+
+#[test]
+fn matching_equal_bytecodes() {
+    let request = mock_request(SourceCodeData::SolSingleFile(String::new()));
+    let bytecode = with_metadata(mock_code(), 1);
+    let match_type = ContractVerifier::match_bytecodes(&request, &bytecode, &bytecode).unwrap();
+    assert_eq!(match_type, VerificationMatchType::Full);
+}
+
+#[test]
+fn matching_bytecodes_with_different_metadata() {
+    let compiled = with_metadata(mock_code(), 1);
+    let deployed = with_metadata(mock_code(), 2);
+
+    let request = mock_request(SourceCodeData::SolSingleFile(String::new()));
+    let match_type = ContractVerifier::match_bytecodes(&request, &compiled, &deployed).unwrap();
+    assert_eq!(match_type, VerificationMatchType::Partial);
+
+    let request = standard_json_request(serde_json::json!({ "bytecodeHash": "ipfs" }));
+    let match_type = ContractVerifier::match_bytecodes(&request, &compiled, &deployed).unwrap();
+    assert_eq!(match_type, VerificationMatchType::Partial);
+}
+
+#[test]
+fn partial_match_is_rejected_if_metadata_is_disabled() {
+    let compiled = with_metadata(mock_code(), 1);
+    let deployed = with_metadata(mock_code(), 2);
+    let request = standard_json_request(serde_json::json!({ "bytecodeHash": "none" }));
+
+    let err = ContractVerifier::match_bytecodes(&request, &compiled, &deployed).unwrap_err();
+    assert!(
+        matches!(err, ContractVerifierError::BytecodeMismatch),
+        "{err:?}"
+    );
+}
+
+#[test]
+fn bytecodes_differing_in_last_word_without_metadata_do_not_match() {
+    let request = mock_request(SourceCodeData::SolSingleFile(String::new()));
+    let mut compiled = mock_code();
+    compiled.extend_from_slice(&[1; WORD_SIZE]);
+    compiled.extend_from_slice(&[0; WORD_SIZE]);
+    let mut deployed = mock_code();
+    deployed.extend_from_slice(&[2; WORD_SIZE]);
+    deployed.extend_from_slice(&[0; WORD_SIZE]);
+
+    let err = ContractVerifier::match_bytecodes(&request, &compiled, &deployed).unwrap_err();
+    assert!(
+        matches!(err, ContractVerifierError::BytecodeMismatch),
+        "{err:?}"
+    );
+}
+
+#[test]
+fn bytecode_with_metadata_does_not_match_bytecode_without_it() {
+    let request = mock_request(SourceCodeData::SolSingleFile(String::new()));
+    let compiled = with_metadata(mock_code(), 1);
+    let mut deployed = mock_code();
+    deployed.resize(compiled.len(), 0);
+
+    let err = ContractVerifier::match_bytecodes(&request, &compiled, &deployed).unwrap_err();
+    assert!(
+        matches!(err, ContractVerifierError::BytecodeMismatch),
+        "{err:?}"
+    );
+}
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS merkle_tree_stale_keys;
+DROP TABLE IF EXISTS merkle_tree_nodes;
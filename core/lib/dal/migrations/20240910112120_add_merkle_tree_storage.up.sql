@@ -0,0 +1,12 @@
+-- Storage for Merkle tree nodes used by the Postgres-backed tree database. The layout mirrors
+-- the RocksDB column families of the tree: keys and values are serialized in the same way.
+CREATE TABLE IF NOT EXISTS merkle_tree_nodes (
+    key BYTEA PRIMARY KEY,
+    value BYTEA NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS merkle_tree_stale_keys (
+    replaced_in_version BIGINT NOT NULL,
+    key BYTEA NOT NULL,
+    PRIMARY KEY (replaced_in_version, key)
+);
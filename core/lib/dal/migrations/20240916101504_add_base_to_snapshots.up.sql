@@ -0,0 +1,3 @@
+-- Delta snapshots reference their base snapshot, which must not be removed while it's still in use.
+ALTER TABLE snapshots
+    ADD COLUMN base_l1_batch_number BIGINT REFERENCES snapshots (l1_batch_number);
@@ -0,0 +1,19 @@
+ALTER TABLE contracts_verification_info ADD COLUMN IF NOT EXISTS bytecode_hash BYTEA;
+CREATE INDEX IF NOT EXISTS contracts_verification_info_bytecode_hash_idx
+    ON contracts_verification_info (bytecode_hash);
+
+-- Backfill bytecode hashes for already verified contracts from `ContractDeployed` events
+-- (`topic3` is the bytecode hash, `topic4` is the deployed address padded to 32 bytes).
+UPDATE contracts_verification_info
+SET bytecode_hash = deploy_events.topic3
+FROM (
+    SELECT DISTINCT ON (topic4) topic3, topic4
+    FROM events
+    WHERE
+        address = '\x0000000000000000000000000000000000008006'
+        AND topic1 = '\x290afdae231a3fc0bbae8b1af63698b0a1d79b21ad17df0342dfb952fe74f8e5'
+    ORDER BY topic4, miniblock_number DESC, event_index_in_block DESC
+) AS deploy_events
+WHERE
+    contracts_verification_info.bytecode_hash IS NULL
+    AND deploy_events.topic4 = '\x000000000000000000000000'::BYTEA || contracts_verification_info.address;
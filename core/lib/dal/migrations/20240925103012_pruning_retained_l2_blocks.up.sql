@@ -0,0 +1,8 @@
+-- Headers of pruned L2 blocks containing transactions retained by the pruning retention policy.
+-- Used to serve receipts and logs of retained transactions after the corresponding `miniblocks` rows are removed.
+CREATE TABLE IF NOT EXISTS pruning_retained_l2_blocks (
+    number BIGINT NOT NULL PRIMARY KEY,
+    l1_batch_number BIGINT NOT NULL,
+    hash BYTEA NOT NULL,
+    timestamp BIGINT NOT NULL
+);
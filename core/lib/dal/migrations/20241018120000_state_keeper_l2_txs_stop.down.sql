@@ -0,0 +1 @@
+DROP TABLE IF EXISTS state_keeper_l2_txs_stop;
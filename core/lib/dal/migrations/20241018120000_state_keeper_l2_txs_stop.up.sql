@@ -0,0 +1,8 @@
+-- L1 batch after which the state keeper stops accepting L2 transactions (set via the admin server).
+-- Persisted so that the setting survives node restarts.
+CREATE TABLE IF NOT EXISTS state_keeper_l2_txs_stop (
+    l1_batch_number BIGINT NOT NULL,
+    -- artificial primary key ensuring that the table contains at most 1 row.
+    fake_key BOOLEAN PRIMARY KEY,
+    CHECK (fake_key)
+);
@@ -0,0 +1,9 @@
+-- Entries of the pruning retention policy (retained addresses, topics and addresses with retained storage history)
+-- together with the first L2 block pruned with the entry in effect. Data in L2 blocks pruned before this block
+-- isn't retained for the entry. An entry is removed once it's dropped from the retention policy.
+CREATE TABLE IF NOT EXISTS pruning_retention_entries (
+    kind TEXT NOT NULL CHECK (kind IN ('address', 'topic', 'storage')),
+    value BYTEA NOT NULL,
+    first_l2_block BIGINT NOT NULL,
+    PRIMARY KEY (kind, value)
+);
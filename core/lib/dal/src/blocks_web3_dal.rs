@@ -1,3 +1,5 @@
+use std::ops;
+
 use zksync_db_connection::{
     connection::Connection, error::DalResult, instrument::InstrumentExt, interpolate_query,
     match_query_as,
@@ -18,7 +20,7 @@ use crate::{
         parse_protocol_version,
         storage_block::{
             ResolvedL1BatchForL2Block, StorageBlockDetails, StorageL1BatchDetails,
-            LEGACY_BLOCK_GAS_LIMIT,
+            StorageL1BatchEthTxs, LEGACY_BLOCK_GAS_LIMIT,
         },
         storage_transaction::CallTrace,
     },
@@ -759,6 +761,52 @@ impl BlocksWeb3Dal<'_, '_> {
 
         Ok(l1_batch_details.map(Into::into))
     }
+
+    /// Returns L1 batches in the specified range together with hashes of confirmed L1 transactions
+    /// committing, proving and executing them. Batches are ordered by number.
+    pub async fn get_l1_batches_eth_txs(
+        &mut self,
+        number_range: ops::RangeInclusive<L1BatchNumber>,
+    ) -> DalResult<Vec<api::L1BatchEthTxs>> {
+        let rows = sqlx::query_as!(
+            StorageL1BatchEthTxs,
+            r#"
+            SELECT
+                l1_batches.number,
+                l1_batches.timestamp,
+                commit_tx.tx_hash AS "commit_tx_hash?",
+                prove_tx.tx_hash AS "prove_tx_hash?",
+                execute_tx.tx_hash AS "execute_tx_hash?"
+            FROM
+                l1_batches
+                LEFT JOIN eth_txs_history AS commit_tx ON (
+                    l1_batches.eth_commit_tx_id = commit_tx.eth_tx_id
+                    AND commit_tx.confirmed_at IS NOT NULL
+                )
+                LEFT JOIN eth_txs_history AS prove_tx ON (
+                    l1_batches.eth_prove_tx_id = prove_tx.eth_tx_id
+                    AND prove_tx.confirmed_at IS NOT NULL
+                )
+                LEFT JOIN eth_txs_history AS execute_tx ON (
+                    l1_batches.eth_execute_tx_id = execute_tx.eth_tx_id
+                    AND execute_tx.confirmed_at IS NOT NULL
+                )
+            WHERE
+                l1_batches.number BETWEEN $1 AND $2
+            ORDER BY
+                l1_batches.number
+            "#,
+            i64::from(number_range.start().0),
+            i64::from(number_range.end().0)
+        )
+        .instrument("get_l1_batches_eth_txs")
+        .with_arg("number_range", &number_range)
+        .report_latency()
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
 }
 
 #[cfg(test)]
@@ -1008,6 +1056,84 @@ mod tests {
         assert_eq!(resolved_l2_block_number, Some(l2_block_header.number));
     }
 
+    #[tokio::test]
+    async fn getting_l1_batches_eth_txs() {
+        let connection_pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = connection_pool.connection().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(&ProtocolVersion::default())
+            .await
+            .unwrap();
+        for number in 0..3 {
+            conn.blocks_dal()
+                .insert_mock_l1_batch(&create_l1_batch_header(number))
+                .await
+                .unwrap();
+        }
+
+        let mut eth_tx_hashes = vec![];
+        for action_type in [
+            AggregatedActionType::Commit,
+            AggregatedActionType::PublishProofOnchain,
+        ] {
+            let eth_tx = conn
+                .eth_sender_dal()
+                .save_eth_tx(
+                    0,
+                    vec![],
+                    action_type,
+                    Address::default(),
+                    0,
+                    None,
+                    None,
+                    false,
+                )
+                .await
+                .unwrap();
+            let tx_hash = H256::random();
+            conn.eth_sender_dal()
+                .insert_tx_history(eth_tx.id, 0, 0, None, tx_hash, &[], 0)
+                .await
+                .unwrap();
+            conn.blocks_dal()
+                .set_eth_tx_id(L1BatchNumber(1)..=L1BatchNumber(2), eth_tx.id, action_type)
+                .await
+                .unwrap();
+            eth_tx_hashes.push(tx_hash);
+        }
+        // Only confirm the commit transaction.
+        conn.eth_sender_dal()
+            .confirm_tx(eth_tx_hashes[0], U256::zero())
+            .await
+            .unwrap();
+
+        let batches = conn
+            .blocks_web3_dal()
+            .get_l1_batches_eth_txs(L1BatchNumber(0)..=L1BatchNumber(5))
+            .await
+            .unwrap();
+        assert_eq!(batches.len(), 3);
+        assert_eq!(batches[0].number, L1BatchNumber(0));
+        assert_eq!(batches[0].commit_tx_hash, None);
+        for batch in &batches[1..] {
+            assert_eq!(batch.commit_tx_hash, Some(eth_tx_hashes[0]));
+            assert_eq!(batch.prove_tx_hash, None);
+            assert_eq!(batch.execute_tx_hash, None);
+            assert_eq!(
+                batch.tx_hash(api::L1BatchStage::Committed),
+                batch.commit_tx_hash
+            );
+        }
+
+        let batches = conn
+            .blocks_web3_dal()
+            .get_l1_batches_eth_txs(L1BatchNumber(2)..=L1BatchNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].number, L1BatchNumber(2));
+    }
+
     #[tokio::test]
     async fn resolving_block_by_hash() {
         let connection_pool = ConnectionPool::<Core>::test_pool().await;
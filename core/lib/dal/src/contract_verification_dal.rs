@@ -9,8 +9,8 @@ use sqlx::postgres::types::PgInterval;
 use zksync_db_connection::connection::Connection;
 use zksync_types::{
     contract_verification_api::{
-        DeployContractCalldata, VerificationIncomingRequest, VerificationInfo, VerificationRequest,
-        VerificationRequestStatus,
+        ConstructorArgs, DeployContractCalldata, VerificationIncomingRequest, VerificationInfo,
+        VerificationRequest, VerificationRequestStatus,
     },
     Address, CONTRACT_DEPLOYER_ADDRESS,
 };
@@ -195,19 +195,42 @@ impl ContractVerificationDal<'_, '_> {
         // Serialization should always succeed.
         let verification_info_json = serde_json::to_value(verification_info)
             .expect("Failed to serialize verification info into serde_json");
+        // The bytecode hash is taken from the deployment event and is used to find contracts with the same bytecode.
         sqlx::query!(
             r#"
             INSERT INTO
-                contracts_verification_info (address, verification_info)
+                contracts_verification_info (address, verification_info, bytecode_hash)
             VALUES
-                ($1, $2)
+                (
+                    $1,
+                    $2,
+                    (
+                        SELECT
+                            topic3
+                        FROM
+                            events
+                        WHERE
+                            address = $3
+                            AND topic1 = $4
+                            AND topic4 = $5
+                        ORDER BY
+                            miniblock_number DESC,
+                            event_index_in_block DESC
+                        LIMIT
+                            1
+                    )
+                )
             ON CONFLICT (address) DO
             UPDATE
             SET
-                verification_info = $2
+                verification_info = $2,
+                bytecode_hash = excluded.bytecode_hash
             "#,
             address.as_bytes(),
-            &verification_info_json
+            &verification_info_json,
+            CONTRACT_DEPLOYER_ADDRESS.as_bytes(),
+            VmEvent::DEPLOY_EVENT_SIGNATURE.as_bytes(),
+            address_to_h256(&address).as_bytes(),
         )
         .execute(transaction.conn())
         .await?;
@@ -310,6 +333,9 @@ impl ContractVerificationDal<'_, '_> {
                         address = $1
                         AND topic1 = $2
                         AND topic4 = $3
+                    ORDER BY
+                        miniblock_number DESC,
+                        event_index_in_block DESC
                     LIMIT
                         1
                 ) deploy_event
@@ -341,7 +367,8 @@ impl ContractVerificationDal<'_, '_> {
                     data.get("calldata").context("calldata missing")?.clone(),
                 )
                 .context("failed parsing calldata")?;
-                let calldata = hex::decode(&calldata_str[2..]).context("invalid calldata")?;
+                let calldata_str = calldata_str.strip_prefix("0x").unwrap_or(&calldata_str);
+                let calldata = hex::decode(calldata_str).context("invalid calldata")?;
                 DeployContractCalldata::Deploy(calldata)
             }
             _ => DeployContractCalldata::Ignore,
@@ -502,9 +529,22 @@ impl ContractVerificationDal<'_, '_> {
         Ok(result)
     }
 
+    /// Returns verification info for the contract. If the contract wasn't verified directly, but has the same bytecode
+    /// as a verified contract, returns verification info of that contract (a "similar match") with constructor arguments
+    /// decoded for the requested contract.
     pub async fn get_contract_verification_info(
         &mut self,
         address: Address,
+    ) -> anyhow::Result<Option<VerificationInfo>> {
+        if let Some(info) = self.get_direct_contract_verification_info(address).await? {
+            return Ok(Some(info));
+        }
+        self.get_similar_contract_verification_info(address).await
+    }
+
+    async fn get_direct_contract_verification_info(
+        &mut self,
+        address: Address,
     ) -> anyhow::Result<Option<VerificationInfo>> {
         let Some(row) = sqlx::query!(
             r#"
@@ -527,4 +567,376 @@ impl ContractVerificationDal<'_, '_> {
         };
         Ok(Some(serde_json::from_value(info).context("invalid info")?))
     }
+
+    async fn get_similar_contract_verification_info(
+        &mut self,
+        address: Address,
+    ) -> anyhow::Result<Option<VerificationInfo>> {
+        let Some(row) = sqlx::query!(
+            r#"
+            SELECT
+                contracts_verification_info.address,
+                contracts_verification_info.verification_info
+            FROM
+                (
+                    SELECT
+                        topic3
+                    FROM
+                        events
+                    WHERE
+                        address = $1
+                        AND topic1 = $2
+                        AND topic4 = $3
+                    ORDER BY
+                        miniblock_number DESC,
+                        event_index_in_block DESC
+                    LIMIT
+                        1
+                ) deploy_event
+                JOIN contracts_verification_info ON contracts_verification_info.bytecode_hash = deploy_event.topic3
+            LIMIT
+                1
+            "#,
+            CONTRACT_DEPLOYER_ADDRESS.as_bytes(),
+            VmEvent::DEPLOY_EVENT_SIGNATURE.as_bytes(),
+            address_to_h256(&address).as_bytes(),
+        )
+        .fetch_optional(self.storage.conn())
+        .await?
+        else {
+            return Ok(None);
+        };
+        let Some(info) = row.verification_info else {
+            return Ok(None);
+        };
+        let mut info: VerificationInfo = serde_json::from_value(info).context("invalid info")?;
+
+        let Some((_, calldata)) = self.get_contract_info_for_verification(address).await? else {
+            return Ok(None);
+        };
+        info.request.req.constructor_arguments = match calldata.constructor_args(address) {
+            ConstructorArgs::Check(args) => args.into(),
+            ConstructorArgs::Ignore => Vec::new().into(),
+        };
+        info.request.req.contract_address = address;
+        info.similar_match_address = Some(Address::from_slice(&row.address));
+        Ok(Some(info))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use zksync_types::{
+        contract_verification_api::{
+            CompilationArtifacts, CompilerVersions, SourceCodeData, VerificationMatchType,
+        },
+        ethabi::Token,
+        tx::IncludedTxLocation,
+        L1BatchNumber, L2BlockNumber, ProtocolVersion, H256,
+    };
+    use zksync_vm_interface::TransactionExecutionMetrics;
+
+    use super::*;
+    use crate::{
+        tests::{create_l2_block_header, mock_l2_transaction},
+        ConnectionPool, CoreDal,
+    };
+
+    const BYTECODE_HASH: H256 = H256::repeat_byte(0x42);
+
+    fn mock_deploy_event(address: Address, bytecode_hash: H256) -> VmEvent {
+        VmEvent {
+            location: (L1BatchNumber(1), 0),
+            address: CONTRACT_DEPLOYER_ADDRESS,
+            indexed_topics: vec![
+                VmEvent::DEPLOY_EVENT_SIGNATURE,
+                address_to_h256(&Address::repeat_byte(0xff)),
+                bytecode_hash,
+                address_to_h256(&address),
+            ],
+            value: vec![],
+        }
+    }
+
+    fn mock_verification_info(address: Address) -> VerificationInfo {
+        VerificationInfo {
+            request: VerificationRequest {
+                id: 1,
+                req: VerificationIncomingRequest {
+                    contract_address: address,
+                    source_code_data: SourceCodeData::SolSingleFile("contract Counter {}".into()),
+                    contract_name: "Counter".to_owned(),
+                    compiler_versions: CompilerVersions::Solc {
+                        compiler_zksolc_version: "v1.5.0".to_owned(),
+                        compiler_solc_version: "0.8.24".to_owned(),
+                    },
+                    optimization_used: true,
+                    optimizer_mode: None,
+                    constructor_arguments: vec![1, 2, 3].into(),
+                    is_system: false,
+                    force_evmla: false,
+                },
+            },
+            artifacts: CompilationArtifacts {
+                bytecode: vec![0; 32],
+                abi: serde_json::json!([]),
+            },
+            verified_at: Default::default(),
+            match_type: VerificationMatchType::Full,
+            similar_match_address: None,
+        }
+    }
+
+    /// Creates a transaction calling `ContractDeployer.create()` with the specified constructor args.
+    fn mock_deploy_transaction(constructor_args: Vec<u8>) -> zksync_types::l2::L2Tx {
+        let calldata = zksync_contracts::deployer_contract()
+            .function("create")
+            .unwrap()
+            .encode_input(&[
+                Token::FixedBytes(H256::zero().0.to_vec()),
+                Token::FixedBytes(BYTECODE_HASH.0.to_vec()),
+                Token::Bytes(constructor_args),
+            ])
+            .unwrap();
+        let mut tx = mock_l2_transaction();
+        tx.execute.contract_address = CONTRACT_DEPLOYER_ADDRESS;
+        tx.execute.calldata = calldata;
+        tx
+    }
+
+    async fn prepare_storage(
+        conn: &mut Connection<'_, Core>,
+        deployments: &[(Address, zksync_types::l2::L2Tx)],
+    ) {
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(&ProtocolVersion::default())
+            .await
+            .unwrap();
+        conn.blocks_dal()
+            .insert_l2_block(&create_l2_block_header(1))
+            .await
+            .unwrap();
+        let factory_deps = HashMap::from([(BYTECODE_HASH, vec![0; 32])]);
+        conn.factory_deps_dal()
+            .insert_factory_deps(L2BlockNumber(1), &factory_deps)
+            .await
+            .unwrap();
+
+        let mut events = vec![];
+        for (i, (address, tx)) in deployments.iter().enumerate() {
+            conn.transactions_dal()
+                .insert_transaction_l2(tx, TransactionExecutionMetrics::default())
+                .await
+                .unwrap();
+            let location = IncludedTxLocation {
+                tx_hash: tx.hash(),
+                tx_index_in_l2_block: i as u32,
+                tx_initiator_address: tx.initiator_account(),
+            };
+            events.push((location, mock_deploy_event(*address, BYTECODE_HASH)));
+        }
+        let events: Vec<_> = events
+            .iter()
+            .map(|(location, event)| (*location, vec![event]))
+            .collect();
+        conn.events_dal()
+            .save_events(L2BlockNumber(1), &events)
+            .await
+            .unwrap();
+    }
+
+    /// Emulates re-deploying contracts with the specified bytecodes in a new L2 block (e.g., as a result
+    /// of `forceDeployOnAddresses` during a system contract upgrade).
+    async fn redeploy_contracts(
+        conn: &mut Connection<'_, Core>,
+        l2_block_number: L2BlockNumber,
+        deployments: &[(Address, H256)],
+    ) {
+        conn.blocks_dal()
+            .insert_l2_block(&create_l2_block_header(l2_block_number.0))
+            .await
+            .unwrap();
+        let factory_deps = deployments
+            .iter()
+            .map(|&(_, bytecode_hash)| (bytecode_hash, vec![0; 32]))
+            .collect();
+        conn.factory_deps_dal()
+            .insert_factory_deps(l2_block_number, &factory_deps)
+            .await
+            .unwrap();
+
+        let mut events = vec![];
+        for (i, &(address, bytecode_hash)) in deployments.iter().enumerate() {
+            let tx = mock_deploy_transaction(vec![]);
+            conn.transactions_dal()
+                .insert_transaction_l2(&tx, TransactionExecutionMetrics::default())
+                .await
+                .unwrap();
+            let location = IncludedTxLocation {
+                tx_hash: tx.hash(),
+                tx_index_in_l2_block: i as u32,
+                tx_initiator_address: tx.initiator_account(),
+            };
+            events.push((location, mock_deploy_event(address, bytecode_hash)));
+        }
+        let events: Vec<_> = events
+            .iter()
+            .map(|(location, event)| (*location, vec![event]))
+            .collect();
+        conn.events_dal()
+            .save_events(l2_block_number, &events)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn getting_similar_contract_verification_info() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let verified_address = Address::repeat_byte(1);
+        let similar_address = Address::repeat_byte(2);
+        prepare_storage(
+            &mut conn,
+            &[
+                (verified_address, mock_deploy_transaction(vec![1, 2, 3])),
+                (similar_address, mock_deploy_transaction(vec![4, 5])),
+            ],
+        )
+        .await;
+
+        let info = mock_verification_info(verified_address);
+        conn.contract_verification_dal()
+            .add_contract_verification_request(info.request.req.clone())
+            .await
+            .unwrap();
+        conn.contract_verification_dal()
+            .save_verification_info(info)
+            .await
+            .unwrap();
+
+        let info = conn
+            .contract_verification_dal()
+            .get_contract_verification_info(verified_address)
+            .await
+            .unwrap()
+            .expect("no verification info");
+        assert_eq!(info.request.req.contract_address, verified_address);
+        assert_eq!(info.request.req.constructor_arguments.0, [1, 2, 3]);
+        assert_eq!(info.similar_match_address, None);
+
+        let info = conn
+            .contract_verification_dal()
+            .get_contract_verification_info(similar_address)
+            .await
+            .unwrap()
+            .expect("no similar verification info");
+        assert_eq!(info.request.req.contract_address, similar_address);
+        assert_eq!(info.request.req.constructor_arguments.0, [4, 5]);
+        assert_eq!(info.similar_match_address, Some(verified_address));
+        assert_eq!(info.artifacts.bytecode, [0; 32]);
+
+        let info = conn
+            .contract_verification_dal()
+            .get_contract_verification_info(Address::repeat_byte(3))
+            .await
+            .unwrap();
+        assert!(info.is_none());
+    }
+
+    #[tokio::test]
+    async fn similar_match_with_malformed_deploy_calldata() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let verified_address = Address::repeat_byte(1);
+        let similar_address = Address::repeat_byte(2);
+        let mut malformed_tx = mock_deploy_transaction(vec![]);
+        malformed_tx.execute.calldata.truncate(10);
+        prepare_storage(
+            &mut conn,
+            &[
+                (verified_address, mock_deploy_transaction(vec![1, 2, 3])),
+                (similar_address, malformed_tx),
+            ],
+        )
+        .await;
+
+        let info = mock_verification_info(verified_address);
+        conn.contract_verification_dal()
+            .add_contract_verification_request(info.request.req.clone())
+            .await
+            .unwrap();
+        conn.contract_verification_dal()
+            .save_verification_info(info)
+            .await
+            .unwrap();
+
+        let info = conn
+            .contract_verification_dal()
+            .get_contract_verification_info(similar_address)
+            .await
+            .unwrap()
+            .expect("no similar verification info");
+        assert_eq!(info.similar_match_address, Some(verified_address));
+        assert!(info.request.req.constructor_arguments.0.is_empty());
+    }
+
+    #[tokio::test]
+    async fn similar_match_for_redeployed_contracts() {
+        const NEW_BYTECODE_HASH: H256 = H256::repeat_byte(0x23);
+
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let verified_address = Address::repeat_byte(1);
+        let redeployed_verified_address = Address::repeat_byte(2);
+        let redeployed_address = Address::repeat_byte(3);
+        let new_address = Address::repeat_byte(4);
+        prepare_storage(
+            &mut conn,
+            &[
+                (verified_address, mock_deploy_transaction(vec![])),
+                (redeployed_verified_address, mock_deploy_transaction(vec![])),
+                (redeployed_address, mock_deploy_transaction(vec![])),
+            ],
+        )
+        .await;
+        redeploy_contracts(
+            &mut conn,
+            L2BlockNumber(2),
+            &[
+                (redeployed_verified_address, NEW_BYTECODE_HASH),
+                (redeployed_address, NEW_BYTECODE_HASH),
+                (new_address, NEW_BYTECODE_HASH),
+            ],
+        )
+        .await;
+
+        for address in [verified_address, redeployed_verified_address] {
+            let info = mock_verification_info(address);
+            conn.contract_verification_dal()
+                .add_contract_verification_request(info.request.req.clone())
+                .await
+                .unwrap();
+            conn.contract_verification_dal()
+                .save_verification_info(info)
+                .await
+                .unwrap();
+        }
+
+        // Similar matches must use the latest deployed bytecode both for the verified and the queried contract.
+        for address in [redeployed_address, new_address] {
+            let info = conn
+                .contract_verification_dal()
+                .get_contract_verification_info(address)
+                .await
+                .unwrap()
+                .expect("no similar verification info");
+            assert_eq!(info.request.req.contract_address, address);
+            assert_eq!(
+                info.similar_match_address,
+                Some(redeployed_verified_address)
+            );
+        }
+    }
 }
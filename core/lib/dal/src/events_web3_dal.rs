@@ -82,7 +82,11 @@ impl EventsWeb3Dal<'_, '_> {
             SELECT miniblocks.hash as "block_hash", miniblocks.l1_batch_number as "l1_batch_number",
                 miniblocks.timestamp as block_timestamp, events_select.*
             FROM events_select
-            INNER JOIN miniblocks ON events_select.miniblock_number = miniblocks.number
+            INNER JOIN (
+                SELECT number, hash, l1_batch_number, timestamp FROM miniblocks
+                UNION ALL
+                SELECT number, hash, l1_batch_number, timestamp FROM pruning_retained_l2_blocks
+            ) miniblocks ON events_select.miniblock_number = miniblocks.number
             ORDER BY miniblock_number ASC, event_index_in_block ASC
             "#,
             where_sql, arg_index
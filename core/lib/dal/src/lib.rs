@@ -16,12 +16,14 @@ use crate::{
     consensus_dal::ConsensusDal, contract_verification_dal::ContractVerificationDal,
     data_availability_dal::DataAvailabilityDal, eth_sender_dal::EthSenderDal,
     events_dal::EventsDal, events_web3_dal::EventsWeb3Dal, factory_deps_dal::FactoryDepsDal,
-    proof_generation_dal::ProofGenerationDal, protocol_versions_dal::ProtocolVersionsDal,
+    merkle_tree_storage_dal::MerkleTreeStorageDal, proof_generation_dal::ProofGenerationDal,
+    protocol_versions_dal::ProtocolVersionsDal,
     protocol_versions_web3_dal::ProtocolVersionsWeb3Dal, pruning_dal::PruningDal,
     snapshot_recovery_dal::SnapshotRecoveryDal, snapshots_creator_dal::SnapshotsCreatorDal,
-    snapshots_dal::SnapshotsDal, storage_logs_dal::StorageLogsDal,
-    storage_logs_dedup_dal::StorageLogsDedupDal, storage_web3_dal::StorageWeb3Dal,
-    sync_dal::SyncDal, system_dal::SystemDal, tee_proof_generation_dal::TeeProofGenerationDal,
+    snapshots_dal::SnapshotsDal, state_keeper_dal::StateKeeperDal,
+    storage_logs_dal::StorageLogsDal, storage_logs_dedup_dal::StorageLogsDedupDal,
+    storage_web3_dal::StorageWeb3Dal, sync_dal::SyncDal, system_dal::SystemDal,
+    tee_proof_generation_dal::TeeProofGenerationDal,
     tee_verifier_input_producer_dal::TeeVerifierInputProducerDal, tokens_dal::TokensDal,
     tokens_web3_dal::TokensWeb3Dal, transactions_dal::TransactionsDal,
     transactions_web3_dal::TransactionsWeb3Dal, vm_runner_dal::VmRunnerDal,
@@ -39,6 +41,7 @@ pub mod events_dal;
 pub mod events_web3_dal;
 pub mod factory_deps_dal;
 pub mod helpers;
+pub mod merkle_tree_storage_dal;
 pub mod metrics;
 mod models;
 pub mod proof_generation_dal;
@@ -48,6 +51,7 @@ pub mod pruning_dal;
 pub mod snapshot_recovery_dal;
 pub mod snapshots_creator_dal;
 pub mod snapshots_dal;
+pub mod state_keeper_dal;
 pub mod storage_logs_dal;
 pub mod storage_logs_dedup_dal;
 pub mod storage_web3_dal;
@@ -132,6 +136,10 @@ where
     fn vm_runner_dal(&mut self) -> VmRunnerDal<'_, 'a>;
 
     fn base_token_dal(&mut self) -> BaseTokenDal<'_, 'a>;
+
+    fn merkle_tree_storage_dal(&mut self) -> MerkleTreeStorageDal<'_, 'a>;
+
+    fn state_keeper_dal(&mut self) -> StateKeeperDal<'_, 'a>;
 }
 
 #[derive(Clone, Debug)]
@@ -258,4 +266,12 @@ impl<'a> CoreDal<'a> for Connection<'a, Core> {
     fn base_token_dal(&mut self) -> BaseTokenDal<'_, 'a> {
         BaseTokenDal { storage: self }
     }
+
+    fn merkle_tree_storage_dal(&mut self) -> MerkleTreeStorageDal<'_, 'a> {
+        MerkleTreeStorageDal { storage: self }
+    }
+
+    fn state_keeper_dal(&mut self) -> StateKeeperDal<'_, 'a> {
+        StateKeeperDal { storage: self }
+    }
 }
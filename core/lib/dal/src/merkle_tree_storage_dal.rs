@@ -0,0 +1,195 @@
+//! Storage for the Merkle tree nodes backing the Postgres-based tree database.
+
+use std::{collections::HashMap, ops};
+
+use zksync_db_connection::{connection::Connection, error::DalResult, instrument::InstrumentExt};
+
+use crate::Core;
+
+/// DAL for raw Merkle tree storage. Keys and values are opaque byte sequences serialized by the tree.
+#[derive(Debug)]
+pub struct MerkleTreeStorageDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Core>,
+}
+
+impl MerkleTreeStorageDal<'_, '_> {
+    /// Returns values for the nodes with the specified keys. Missing nodes are not present in the returned map.
+    pub async fn get_nodes(&mut self, keys: &[Vec<u8>]) -> DalResult<HashMap<Vec<u8>, Vec<u8>>> {
+        let keys: Vec<_> = keys.iter().map(Vec::as_slice).collect();
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                key,
+                value
+            FROM
+                merkle_tree_nodes
+            WHERE
+                key = ANY ($1)
+            "#,
+            &keys as &[&[u8]],
+        )
+        .instrument("get_merkle_tree_nodes")
+        .with_arg("keys.len", &keys.len())
+        .report_latency()
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows.into_iter().map(|row| (row.key, row.value)).collect())
+    }
+
+    /// Inserts or replaces nodes with the specified keys and values.
+    pub async fn upsert_nodes(&mut self, nodes: &[(Vec<u8>, Vec<u8>)]) -> DalResult<()> {
+        let (keys, values): (Vec<_>, Vec<_>) = nodes
+            .iter()
+            .map(|(key, value)| (key.as_slice(), value.as_slice()))
+            .unzip();
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                merkle_tree_nodes (key, value)
+            SELECT
+                u.key,
+                u.value
+            FROM
+                UNNEST($1::bytea[], $2::bytea[]) AS u (key, value)
+            ON CONFLICT (key) DO
+            UPDATE
+            SET
+                value = excluded.value
+            "#,
+            &keys as &[&[u8]],
+            &values as &[&[u8]],
+        )
+        .instrument("upsert_merkle_tree_nodes")
+        .with_arg("nodes.len", &nodes.len())
+        .report_latency()
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Removes nodes with the specified keys.
+    pub async fn delete_nodes(&mut self, keys: &[Vec<u8>]) -> DalResult<()> {
+        let keys: Vec<_> = keys.iter().map(Vec::as_slice).collect();
+        sqlx::query!(
+            r#"
+            DELETE FROM merkle_tree_nodes
+            WHERE
+                key = ANY ($1)
+            "#,
+            &keys as &[&[u8]],
+        )
+        .instrument("delete_merkle_tree_nodes")
+        .with_arg("keys.len", &keys.len())
+        .report_latency()
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Removes all nodes with keys in the specified range (the start is inclusive, the end is exclusive).
+    pub async fn delete_node_range(&mut self, start: &[u8], end: &[u8]) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM merkle_tree_nodes
+            WHERE
+                key >= $1
+                AND key < $2
+            "#,
+            start,
+            end
+        )
+        .instrument("delete_merkle_tree_node_range")
+        .report_latency()
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns the minimum tree version in stale keys.
+    pub async fn get_min_stale_key_version(&mut self) -> DalResult<Option<u64>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                MIN(replaced_in_version) AS "version"
+            FROM
+                merkle_tree_stale_keys
+            "#
+        )
+        .instrument("get_min_merkle_tree_stale_key_version")
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(row.version.map(|version| version as u64))
+    }
+
+    /// Returns stale node keys replaced in the specified tree version.
+    pub async fn get_stale_keys(&mut self, replaced_in_version: u64) -> DalResult<Vec<Vec<u8>>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                key
+            FROM
+                merkle_tree_stale_keys
+            WHERE
+                replaced_in_version = $1
+            "#,
+            replaced_in_version as i64
+        )
+        .instrument("get_merkle_tree_stale_keys")
+        .with_arg("replaced_in_version", &replaced_in_version)
+        .report_latency()
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows.into_iter().map(|row| row.key).collect())
+    }
+
+    /// Inserts stale node keys. Each key is supplied together with the tree version it was replaced in.
+    pub async fn insert_stale_keys(&mut self, stale_keys: &[(u64, Vec<u8>)]) -> DalResult<()> {
+        let (versions, keys): (Vec<_>, Vec<_>) = stale_keys
+            .iter()
+            .map(|(version, key)| (*version as i64, key.as_slice()))
+            .unzip();
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                merkle_tree_stale_keys (replaced_in_version, key)
+            SELECT
+                u.replaced_in_version,
+                u.key
+            FROM
+                UNNEST($1::BIGINT[], $2::bytea[]) AS u (replaced_in_version, key)
+            ON CONFLICT (replaced_in_version, key) DO NOTHING
+            "#,
+            &versions,
+            &keys as &[&[u8]],
+        )
+        .instrument("insert_merkle_tree_stale_keys")
+        .with_arg("stale_keys.len", &stale_keys.len())
+        .report_latency()
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Removes stale keys replaced in the specified range of tree versions.
+    pub async fn delete_stale_keys(&mut self, versions: ops::Range<u64>) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM merkle_tree_stale_keys
+            WHERE
+                replaced_in_version >= $1
+                AND replaced_in_version < $2
+            "#,
+            versions.start as i64,
+            versions.end as i64
+        )
+        .instrument("delete_merkle_tree_stale_keys")
+        .with_arg("versions", &versions)
+        .report_latency()
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+}
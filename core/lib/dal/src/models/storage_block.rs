@@ -404,6 +404,36 @@ impl From<StorageL1BatchDetails> for api::L1BatchDetails {
     }
 }
 
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub(crate) struct StorageL1BatchEthTxs {
+    pub number: i64,
+    pub timestamp: i64,
+    pub commit_tx_hash: Option<String>,
+    pub prove_tx_hash: Option<String>,
+    pub execute_tx_hash: Option<String>,
+}
+
+impl From<StorageL1BatchEthTxs> for api::L1BatchEthTxs {
+    fn from(row: StorageL1BatchEthTxs) -> Self {
+        Self {
+            number: L1BatchNumber(row.number as u32),
+            timestamp: row.timestamp as u64,
+            commit_tx_hash: row
+                .commit_tx_hash
+                .as_deref()
+                .map(|hash| H256::from_str(hash).expect("Incorrect commit_tx hash")),
+            prove_tx_hash: row
+                .prove_tx_hash
+                .as_deref()
+                .map(|hash| H256::from_str(hash).expect("Incorrect prove_tx hash")),
+            execute_tx_hash: row
+                .execute_tx_hash
+                .as_deref()
+                .map(|hash| H256::from_str(hash).expect("Incorrect execute_tx hash")),
+        }
+    }
+}
+
 pub(crate) struct StorageL2BlockHeader {
     pub number: i64,
     pub timestamp: i64,
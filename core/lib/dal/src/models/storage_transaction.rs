@@ -4,7 +4,7 @@ use bigdecimal::Zero;
 use serde_json::Value;
 use sqlx::types::chrono::{DateTime, NaiveDateTime, Utc};
 use zksync_types::{
-    api::{self, TransactionDetails, TransactionReceipt, TransactionStatus},
+    api::{self, trace::TraceLocation, TransactionDetails, TransactionReceipt, TransactionStatus},
     fee::Fee,
     l1::{OpProcessingType, PriorityQueueType},
     l2::TransactionType,
@@ -590,3 +590,33 @@ impl CallTrace {
         Self { call_trace }
     }
 }
+
+/// Call trace together with the location of the corresponding transaction in the chain.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub(crate) struct CallTraceWithLocation {
+    pub tx_hash: Vec<u8>,
+    pub call_trace: Vec<u8>,
+    pub miniblock_number: i64,
+    pub index_in_block: i32,
+    pub block_hash: Vec<u8>,
+    pub protocol_version: Option<i32>,
+}
+
+impl CallTraceWithLocation {
+    pub(crate) fn into_call(self) -> (Call, TraceLocation) {
+        let protocol_version = self
+            .protocol_version
+            .map(|v| (v as u16).try_into().unwrap())
+            .unwrap_or_else(ProtocolVersionId::last_potentially_undefined);
+        let location = TraceLocation {
+            block_hash: H256::from_slice(&self.block_hash),
+            block_number: U64::from(self.miniblock_number as u64),
+            transaction_hash: H256::from_slice(&self.tx_hash),
+            transaction_position: U64::from(self.index_in_block as u64),
+        };
+        let call_trace = CallTrace {
+            call_trace: self.call_trace,
+        };
+        (call_trace.into_call(protocol_version), location)
+    }
+}
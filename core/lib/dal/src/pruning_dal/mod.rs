@@ -1,7 +1,7 @@
-use std::ops;
+use std::{collections::HashMap, ops};
 
 use zksync_db_connection::{connection::Connection, error::DalResult, instrument::InstrumentExt};
-use zksync_types::{L1BatchNumber, L2BlockNumber};
+use zksync_types::{Address, L1BatchNumber, L2BlockNumber, H256};
 
 use crate::Core;
 
@@ -31,6 +31,101 @@ pub struct HardPruningStats {
     pub deleted_events: u64,
     pub deleted_call_traces: u64,
     pub deleted_l2_to_l1_logs: u64,
+    pub retained_transactions: u64,
+}
+
+/// Policy specifying which data is retained beyond the pruning horizon.
+///
+/// Data is retained on the per-transaction basis: if a transaction matches the policy, its data, receipt, events,
+/// L2-to-L1 logs and call traces are retained, together with the header of its L2 block.
+/// The policy only applies to L2 blocks pruned while it's in effect; see [`AppliedRetentionPolicy`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RetentionPolicy {
+    /// Transactions initiated by or sent to these addresses, or emitting events from these addresses, are retained.
+    pub addresses: Vec<Address>,
+    /// Transactions emitting events with any of these topics (at any position) are retained.
+    pub topics: Vec<H256>,
+    /// Whether to retain storage history of `addresses`.
+    pub storage_history: bool,
+}
+
+impl RetentionPolicy {
+    /// Checks whether this policy doesn't retain any data.
+    pub fn is_empty(&self) -> bool {
+        self.addresses.is_empty() && self.topics.is_empty()
+    }
+
+    /// Returns kinds and values of all policy entries, in the format persisted in `pruning_retention_entries`.
+    fn entries(&self) -> (Vec<&'static str>, Vec<&[u8]>) {
+        let addresses = self
+            .addresses
+            .iter()
+            .map(|address| (RetentionEntryKind::ADDRESS, address.as_bytes()));
+        let topics = self
+            .topics
+            .iter()
+            .map(|topic| (RetentionEntryKind::TOPIC, topic.as_bytes()));
+        let storage_history = self
+            .addresses
+            .iter()
+            .filter(|_| self.storage_history)
+            .map(|address| (RetentionEntryKind::STORAGE, address.as_bytes()));
+        addresses.chain(topics).chain(storage_history).unzip()
+    }
+}
+
+/// Kinds of entries persisted in `pruning_retention_entries`.
+struct RetentionEntryKind;
+
+impl RetentionEntryKind {
+    const ADDRESS: &'static str = "address";
+    const TOPIC: &'static str = "topic";
+    const STORAGE: &'static str = "storage";
+}
+
+/// Retention policy that was applied when pruning data. Unlike [`RetentionPolicy`], specifies the first
+/// pruned L2 block for each entry starting from which the entry was in effect; data in earlier L2 blocks was pruned
+/// without taking the entry into account.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AppliedRetentionPolicy {
+    pub addresses: HashMap<Address, L2BlockNumber>,
+    pub topics: HashMap<H256, L2BlockNumber>,
+    /// Addresses with retained storage history.
+    pub storage_history: HashMap<Address, L2BlockNumber>,
+}
+
+impl AppliedRetentionPolicy {
+    /// Returns the first L2 block starting from which all events matching the specified filter are guaranteed
+    /// to be retained. This is the case if the filter requires event addresses to be retained, or if it requires topics
+    /// at a certain position to be retained. Returns `None` if the events are not retained.
+    pub fn first_block_retaining_events(
+        &self,
+        addresses: &[Address],
+        topics: &[(u32, Vec<H256>)],
+    ) -> Option<L2BlockNumber> {
+        let addresses_start = Self::first_block_retaining_all(&self.addresses, addresses);
+        let topic_starts = topics
+            .iter()
+            .map(|(_, topics)| Self::first_block_retaining_all(&self.topics, topics));
+        topic_starts.chain([addresses_start]).flatten().min()
+    }
+
+    fn first_block_retaining_all<T: Eq + std::hash::Hash>(
+        entries: &HashMap<T, L2BlockNumber>,
+        values: &[T],
+    ) -> Option<L2BlockNumber> {
+        if values.is_empty() {
+            return None;
+        }
+        values.iter().try_fold(L2BlockNumber(0), |start, value| {
+            Some(start.max(*entries.get(value)?))
+        })
+    }
+
+    /// Returns the first L2 block starting from which storage history of the specified contract is retained.
+    pub fn first_block_retaining_storage_of(&self, address: &Address) -> Option<L2BlockNumber> {
+        self.storage_history.get(address).copied()
+    }
 }
 
 #[derive(Debug, sqlx::Type)]
@@ -139,6 +234,21 @@ impl PruningDal<'_, '_> {
         &mut self,
         last_l1_batch_to_prune: L1BatchNumber,
         last_l2_block_to_prune: L2BlockNumber,
+    ) -> DalResult<HardPruningStats> {
+        self.hard_prune_batches_range_with_retention(
+            last_l1_batch_to_prune,
+            last_l2_block_to_prune,
+            &RetentionPolicy::default(),
+        )
+        .await
+    }
+
+    /// Same as [`Self::hard_prune_batches_range()`], but retains data matching the specified policy.
+    pub async fn hard_prune_batches_range_with_retention(
+        &mut self,
+        last_l1_batch_to_prune: L1BatchNumber,
+        last_l2_block_to_prune: L2BlockNumber,
+        retention_policy: &RetentionPolicy,
     ) -> DalResult<HardPruningStats> {
         let row = sqlx::query!(
             r#"
@@ -160,21 +270,39 @@ impl PruningDal<'_, '_> {
         // We don't have any L2 blocks available when recovering from a snapshot
         let stats = if let Some(first_l2_block_to_prune) = row.first_miniblock_to_prune {
             let first_l2_block_to_prune = L2BlockNumber(first_l2_block_to_prune as u32);
+            let l2_blocks_to_prune = first_l2_block_to_prune..=last_l2_block_to_prune;
+            self.update_retention_entries(retention_policy, first_l2_block_to_prune)
+                .await?;
+
+            let retained_tx_hashes = if retention_policy.is_empty() {
+                vec![]
+            } else {
+                let hashes = self
+                    .get_retained_tx_hashes(l2_blocks_to_prune.clone(), retention_policy)
+                    .await?;
+                self.save_retained_l2_block_headers(&hashes).await?;
+                hashes
+            };
+            let retained_storage_addresses = if retention_policy.storage_history {
+                retention_policy.addresses.as_slice()
+            } else {
+                &[]
+            };
 
             let deleted_events = self
-                .delete_events(first_l2_block_to_prune..=last_l2_block_to_prune)
+                .delete_events(l2_blocks_to_prune.clone(), &retained_tx_hashes)
                 .await?;
             let deleted_l2_to_l1_logs = self
-                .delete_l2_to_l1_logs(first_l2_block_to_prune..=last_l2_block_to_prune)
+                .delete_l2_to_l1_logs(l2_blocks_to_prune.clone(), &retained_tx_hashes)
                 .await?;
             let deleted_call_traces = self
-                .delete_call_traces(first_l2_block_to_prune..=last_l2_block_to_prune)
+                .delete_call_traces(l2_blocks_to_prune.clone(), &retained_tx_hashes)
                 .await?;
-            self.clear_transaction_fields(first_l2_block_to_prune..=last_l2_block_to_prune)
+            self.clear_transaction_fields(l2_blocks_to_prune.clone(), &retained_tx_hashes)
                 .await?;
 
             let deleted_storage_logs = self
-                .prune_storage_logs(first_l2_block_to_prune..=last_l2_block_to_prune)
+                .prune_storage_logs(l2_blocks_to_prune, retained_storage_addresses)
                 .await?;
             let deleted_l1_batches = self.delete_l1_batches(last_l1_batch_to_prune).await?;
             let deleted_l2_blocks = self.delete_l2_blocks(last_l2_block_to_prune).await?;
@@ -186,6 +314,7 @@ impl PruningDal<'_, '_> {
                 deleted_l2_to_l1_logs,
                 deleted_call_traces,
                 deleted_storage_logs,
+                retained_transactions: retained_tx_hashes.len() as u64,
             }
         } else {
             HardPruningStats::default()
@@ -196,18 +325,207 @@ impl PruningDal<'_, '_> {
         Ok(stats)
     }
 
+    /// Returns the retention policy applied during pruning.
+    pub async fn get_applied_retention_policy(&mut self) -> DalResult<AppliedRetentionPolicy> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                kind,
+                value,
+                first_l2_block
+            FROM
+                pruning_retention_entries
+            "#
+        )
+        .instrument("get_applied_retention_policy")
+        .report_latency()
+        .fetch_all(self.storage)
+        .await?;
+
+        let mut policy = AppliedRetentionPolicy::default();
+        for row in rows {
+            let first_l2_block = L2BlockNumber(row.first_l2_block as u32);
+            match row.kind.as_str() {
+                RetentionEntryKind::ADDRESS => {
+                    let address = Address::from_slice(&row.value);
+                    policy.addresses.insert(address, first_l2_block);
+                }
+                RetentionEntryKind::TOPIC => {
+                    let topic = H256::from_slice(&row.value);
+                    policy.topics.insert(topic, first_l2_block);
+                }
+                RetentionEntryKind::STORAGE => {
+                    let address = Address::from_slice(&row.value);
+                    policy.storage_history.insert(address, first_l2_block);
+                }
+                _ => {
+                    tracing::warn!("Unknown retention entry kind: {}", row.kind);
+                }
+            }
+        }
+        Ok(policy)
+    }
+
+    /// Persists entries of the retention policy used to prune L2 blocks starting from `first_l2_block_to_prune`.
+    /// New entries are marked as applying from this block; entries dropped from the policy are removed.
+    async fn update_retention_entries(
+        &mut self,
+        retention_policy: &RetentionPolicy,
+        first_l2_block_to_prune: L2BlockNumber,
+    ) -> DalResult<()> {
+        let (kinds, values) = retention_policy.entries();
+        sqlx::query!(
+            r#"
+            DELETE FROM pruning_retention_entries
+            WHERE
+                (kind, value) NOT IN (
+                    SELECT
+                        *
+                    FROM
+                        UNNEST($1::TEXT[], $2::BYTEA[])
+                )
+            "#,
+            &kinds as &[&str],
+            &values as &[&[u8]],
+        )
+        .instrument("hard_prune_batches_range#delete_retention_entries")
+        .with_arg("retention_policy", retention_policy)
+        .report_latency()
+        .execute(self.storage)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                pruning_retention_entries (kind, value, first_l2_block)
+            SELECT
+                u.kind,
+                u.value,
+                $3
+            FROM
+                UNNEST($1::TEXT[], $2::BYTEA[]) AS u (kind, value)
+            ON CONFLICT (kind, value) DO NOTHING
+            "#,
+            &kinds as &[&str],
+            &values as &[&[u8]],
+            i64::from(first_l2_block_to_prune.0),
+        )
+        .instrument("hard_prune_batches_range#insert_retention_entries")
+        .with_arg("retention_policy", retention_policy)
+        .with_arg("first_l2_block_to_prune", &first_l2_block_to_prune)
+        .report_latency()
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_retained_tx_hashes(
+        &mut self,
+        l2_blocks_to_prune: ops::RangeInclusive<L2BlockNumber>,
+        retention_policy: &RetentionPolicy,
+    ) -> DalResult<Vec<H256>> {
+        let addresses: Vec<_> = retention_policy
+            .addresses
+            .iter()
+            .map(Address::as_bytes)
+            .collect();
+        let topics: Vec<_> = retention_policy.topics.iter().map(H256::as_bytes).collect();
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                hash AS "hash!"
+            FROM
+                transactions
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+                AND (
+                    initiator_address = ANY ($3)
+                    OR contract_address = ANY ($3)
+                )
+            UNION
+            SELECT
+                tx_hash
+            FROM
+                events
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+                AND (
+                    address = ANY ($3)
+                    OR topic1 = ANY ($4)
+                    OR topic2 = ANY ($4)
+                    OR topic3 = ANY ($4)
+                    OR topic4 = ANY ($4)
+                )
+            "#,
+            i64::from(l2_blocks_to_prune.start().0),
+            i64::from(l2_blocks_to_prune.end().0),
+            &addresses as &[&[u8]],
+            &topics as &[&[u8]],
+        )
+        .instrument("hard_prune_batches_range#get_retained_tx_hashes")
+        .with_arg("l2_blocks_to_prune", &l2_blocks_to_prune)
+        .with_arg("retention_policy", retention_policy)
+        .report_latency()
+        .fetch_all(self.storage)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| H256::from_slice(&row.hash))
+            .collect())
+    }
+
+    /// Saves headers of L2 blocks containing retained transactions, so that retained data can be served
+    /// after the L2 blocks are removed.
+    async fn save_retained_l2_block_headers(&mut self, tx_hashes: &[H256]) -> DalResult<()> {
+        let tx_hashes: Vec<_> = tx_hashes.iter().map(H256::as_bytes).collect();
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                pruning_retained_l2_blocks (number, l1_batch_number, hash, timestamp)
+            SELECT
+                number,
+                l1_batch_number,
+                hash,
+                timestamp
+            FROM
+                miniblocks
+            WHERE
+                number IN (
+                    SELECT
+                        miniblock_number
+                    FROM
+                        transactions
+                    WHERE
+                        hash = ANY ($1)
+                )
+            ON CONFLICT (number) DO NOTHING
+            "#,
+            &tx_hashes as &[&[u8]],
+        )
+        .instrument("hard_prune_batches_range#save_retained_l2_block_headers")
+        .with_arg("tx_hashes.len", &tx_hashes.len())
+        .report_latency()
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
     async fn delete_events(
         &mut self,
         l2_blocks_to_prune: ops::RangeInclusive<L2BlockNumber>,
+        retained_tx_hashes: &[H256],
     ) -> DalResult<u64> {
+        let retained_tx_hashes: Vec<_> = retained_tx_hashes.iter().map(H256::as_bytes).collect();
         let execution_result = sqlx::query!(
             r#"
             DELETE FROM events
             WHERE
                 miniblock_number BETWEEN $1 AND $2
+                AND NOT (tx_hash = ANY ($3))
             "#,
             i64::from(l2_blocks_to_prune.start().0),
-            i64::from(l2_blocks_to_prune.end().0)
+            i64::from(l2_blocks_to_prune.end().0),
+            &retained_tx_hashes as &[&[u8]]
         )
         .instrument("hard_prune_batches_range#delete_events")
         .with_arg("l2_blocks_to_prune", &l2_blocks_to_prune)
@@ -220,15 +538,19 @@ impl PruningDal<'_, '_> {
     async fn delete_l2_to_l1_logs(
         &mut self,
         l2_blocks_to_prune: ops::RangeInclusive<L2BlockNumber>,
+        retained_tx_hashes: &[H256],
     ) -> DalResult<u64> {
+        let retained_tx_hashes: Vec<_> = retained_tx_hashes.iter().map(H256::as_bytes).collect();
         let execution_result = sqlx::query!(
             r#"
             DELETE FROM l2_to_l1_logs
             WHERE
                 miniblock_number BETWEEN $1 AND $2
+                AND NOT (tx_hash = ANY ($3))
             "#,
             i64::from(l2_blocks_to_prune.start().0),
-            i64::from(l2_blocks_to_prune.end().0)
+            i64::from(l2_blocks_to_prune.end().0),
+            &retained_tx_hashes as &[&[u8]]
         )
         .instrument("hard_prune_batches_range#delete_l2_to_l1_logs")
         .with_arg("l2_blocks_to_prune", &l2_blocks_to_prune)
@@ -244,7 +566,9 @@ impl PruningDal<'_, '_> {
     async fn delete_call_traces(
         &mut self,
         l2_blocks_to_prune: ops::RangeInclusive<L2BlockNumber>,
+        retained_tx_hashes: &[H256],
     ) -> DalResult<u64> {
+        let retained_tx_hashes: Vec<_> = retained_tx_hashes.iter().map(H256::as_bytes).collect();
         let execution_result = sqlx::query!(
             r#"
             DELETE FROM call_traces
@@ -257,9 +581,11 @@ impl PruningDal<'_, '_> {
                     WHERE
                         miniblock_number BETWEEN $1 AND $2
                 )
+                AND NOT (tx_hash = ANY ($3))
             "#,
             i64::from(l2_blocks_to_prune.start().0),
-            i64::from(l2_blocks_to_prune.end().0)
+            i64::from(l2_blocks_to_prune.end().0),
+            &retained_tx_hashes as &[&[u8]]
         )
         .instrument("hard_prune_batches_range#delete_call_traces")
         .with_arg("l2_blocks_to_prune", &l2_blocks_to_prune)
@@ -280,7 +606,9 @@ impl PruningDal<'_, '_> {
     async fn clear_transaction_fields(
         &mut self,
         l2_blocks_to_prune: ops::RangeInclusive<L2BlockNumber>,
+        retained_tx_hashes: &[H256],
     ) -> DalResult<u64> {
+        let retained_tx_hashes: Vec<_> = retained_tx_hashes.iter().map(H256::as_bytes).collect();
         let execution_result = sqlx::query!(
             r#"
             UPDATE transactions
@@ -292,9 +620,11 @@ impl PruningDal<'_, '_> {
             WHERE
                 miniblock_number BETWEEN $1 AND $2
                 AND upgrade_id IS NULL
+                AND NOT (hash = ANY ($3))
             "#,
             i64::from(l2_blocks_to_prune.start().0),
-            i64::from(l2_blocks_to_prune.end().0)
+            i64::from(l2_blocks_to_prune.end().0),
+            &retained_tx_hashes as &[&[u8]]
         )
         .instrument("hard_prune_batches_range#clear_transaction_fields")
         .with_arg("l2_blocks_to_prune", &l2_blocks_to_prune)
@@ -304,11 +634,18 @@ impl PruningDal<'_, '_> {
         Ok(execution_result.rows_affected())
     }
 
-    /// Removes storage logs overwritten by the specified new logs.
+    /// Removes storage logs overwritten by the specified new logs. Logs for `retained_addresses` are not removed.
+    ///
+    /// Logs recovered from a snapshot don't have the `address` set. For such logs, the address is taken from the overwriting log
+    /// (the address is uniquely determined by the hashed key). If the address cannot be determined this way either, the log
+    /// is conservatively retained if `retained_addresses` is non-empty (`NULL = ANY(..)` evaluates to `NULL` for a non-empty array
+    /// and to `FALSE` for an empty one).
     async fn prune_storage_logs(
         &mut self,
         l2_blocks_to_prune: ops::RangeInclusive<L2BlockNumber>,
+        retained_addresses: &[Address],
     ) -> DalResult<u64> {
+        let retained_addresses: Vec<_> = retained_addresses.iter().map(Address::as_bytes).collect();
         // Storage log pruning is designed to use deterministic indexes and thus have predictable performance.
         //
         // - The WITH query is guaranteed to use the block number index (that's the only WHERE condition),
@@ -322,6 +659,7 @@ impl PruningDal<'_, '_> {
                 new_logs AS MATERIALIZED (
                     SELECT DISTINCT
                         ON (hashed_key) hashed_key,
+                        address,
                         miniblock_number,
                         operation_number
                     FROM
@@ -338,9 +676,11 @@ impl PruningDal<'_, '_> {
                 storage_logs.hashed_key = new_logs.hashed_key
                 AND storage_logs.miniblock_number <= $2
                 AND (storage_logs.miniblock_number, storage_logs.operation_number) < (new_logs.miniblock_number, new_logs.operation_number)
+                AND NOT (COALESCE(storage_logs.address, new_logs.address) = ANY ($3))
             "#,
             i64::from(l2_blocks_to_prune.start().0),
-            i64::from(l2_blocks_to_prune.end().0)
+            i64::from(l2_blocks_to_prune.end().0),
+            &retained_addresses as &[&[u8]]
         )
         .instrument("hard_prune_batches_range#prune_storage_logs")
         .with_arg("l2_blocks_to_prune", &l2_blocks_to_prune)
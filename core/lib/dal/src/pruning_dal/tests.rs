@@ -1,9 +1,9 @@
-use std::ops;
+use std::{collections::HashMap, ops};
 
 use zksync_db_connection::connection::Connection;
 use zksync_types::{
-    tx::IncludedTxLocation, AccountTreeId, Address, L1BatchNumber, L2BlockNumber, L2ChainId,
-    ProtocolVersion, ProtocolVersionId, StorageKey, StorageLog, H256,
+    snapshots::SnapshotStorageLog, tx::IncludedTxLocation, AccountTreeId, Address, L1BatchNumber,
+    L2BlockNumber, L2ChainId, ProtocolVersion, ProtocolVersionId, StorageKey, StorageLog, H256,
 };
 use zksync_vm_interface::TransactionExecutionMetrics;
 
@@ -503,3 +503,295 @@ async fn transactions_are_handled_correctly_after_pruning() {
         .unwrap();
     assert!(transaction_details.is_none(), "{transaction_details:?}");
 }
+
+#[tokio::test]
+async fn hard_pruning_respects_retention_policy() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+    insert_realistic_l1_batches(&mut conn, 4).await;
+
+    // Events emitted by this address belong to the second transaction in each L2 block.
+    let retention_policy = RetentionPolicy {
+        addresses: vec![Address::repeat_byte(3)],
+        ..RetentionPolicy::default()
+    };
+    let stats = conn
+        .pruning_dal()
+        .hard_prune_batches_range_with_retention(
+            L1BatchNumber(1),
+            L2BlockNumber(3),
+            &retention_policy,
+        )
+        .await
+        .unwrap();
+    assert_eq!(stats.deleted_l1_batches, 2);
+    assert_eq!(stats.deleted_l2_blocks, 4);
+    assert_eq!(stats.retained_transactions, 1);
+    // Only events and logs of the first transaction in each L2 block should be removed.
+    assert_eq!(stats.deleted_events, 8);
+    assert_eq!(stats.deleted_l2_to_l1_logs, 8);
+    assert_l1_batch_objects_dont_exist(&mut conn, L1BatchNumber(0)..=L1BatchNumber(1)).await;
+}
+
+#[tokio::test]
+async fn storage_logs_without_address_respect_retention_policy() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+    insert_realistic_l1_batches(&mut conn, 4).await;
+
+    // Logs recovered from a snapshot don't have an address.
+    let recovered_logs: Vec<_> = (1..=3)
+        .map(|seed| SnapshotStorageLog {
+            key: random_storage_log(seed, 1).key.hashed_key(),
+            value: H256::repeat_byte(1),
+            l1_batch_number_of_initial_write: L1BatchNumber(0),
+            enumeration_index: seed.into(),
+        })
+        .collect();
+    conn.storage_logs_dal()
+        .insert_storage_logs_from_snapshot(L2BlockNumber(0), &recovered_logs)
+        .await
+        .unwrap();
+    insert_l2_block_storage_logs(
+        &mut conn,
+        L2BlockNumber(2),
+        vec![random_storage_log(1, 2), random_storage_log(2, 2)],
+    )
+    .await;
+    // The address cannot be determined for the overwriting log either.
+    let overwriting_log = SnapshotStorageLog {
+        value: H256::repeat_byte(2),
+        ..recovered_logs[2]
+    };
+    conn.storage_logs_dal()
+        .insert_storage_logs_from_snapshot(L2BlockNumber(2), &[overwriting_log])
+        .await
+        .unwrap();
+
+    let retention_policy = RetentionPolicy {
+        addresses: vec![Address::repeat_byte(1)],
+        storage_history: true,
+        ..RetentionPolicy::default()
+    };
+    let stats = conn
+        .pruning_dal()
+        .hard_prune_batches_range_with_retention(
+            L1BatchNumber(1),
+            L2BlockNumber(3),
+            &retention_policy,
+        )
+        .await
+        .unwrap();
+    assert_eq!(stats.deleted_storage_logs, 1);
+
+    let actual_logs = conn
+        .storage_logs_dal()
+        .dump_all_storage_logs_for_tests()
+        .await;
+    let retained_keys: Vec<_> = actual_logs
+        .iter()
+        .filter(|log| log.l2_block_number == L2BlockNumber(0))
+        .map(|log| log.hashed_key)
+        .collect();
+    assert_eq!(
+        retained_keys,
+        [recovered_logs[0].key, recovered_logs[2].key]
+    );
+}
+
+#[tokio::test]
+async fn retained_transactions_are_served_after_pruning() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+    conn.protocol_versions_dal()
+        .save_protocol_version_with_tx(&ProtocolVersion::default())
+        .await
+        .unwrap();
+
+    let tx = mock_l2_transaction();
+    let tx_hash = tx.hash();
+    conn.transactions_dal()
+        .insert_transaction_l2(&tx, TransactionExecutionMetrics::default())
+        .await
+        .unwrap();
+    insert_l1_batch(&mut conn, L1BatchNumber(0)).await;
+    insert_l2_block(&mut conn, L2BlockNumber(0), L1BatchNumber(0)).await;
+    conn.transactions_dal()
+        .mark_txs_as_executed_in_l2_block(
+            L2BlockNumber(0),
+            &[mock_execution_result(tx.clone())],
+            1.into(),
+            ProtocolVersionId::latest(),
+            false,
+        )
+        .await
+        .unwrap();
+
+    let retention_policy = RetentionPolicy {
+        addresses: vec![tx.initiator_account()],
+        ..RetentionPolicy::default()
+    };
+    let stats = conn
+        .pruning_dal()
+        .hard_prune_batches_range_with_retention(
+            L1BatchNumber(0),
+            L2BlockNumber(0),
+            &retention_policy,
+        )
+        .await
+        .unwrap();
+    assert_eq!(stats.deleted_l2_blocks, 1);
+    assert_eq!(stats.retained_transactions, 1);
+    assert!(conn
+        .blocks_dal()
+        .get_l2_block_header(L2BlockNumber(0))
+        .await
+        .unwrap()
+        .is_none());
+
+    let api_transactions = conn
+        .transactions_web3_dal()
+        .get_transactions(&[tx_hash], L2ChainId::default())
+        .await
+        .unwrap();
+    assert_eq!(api_transactions.len(), 1);
+    assert_eq!(api_transactions[0].block_number, Some(0.into()));
+
+    let transaction_receipts = conn
+        .transactions_web3_dal()
+        .get_transaction_receipts(&[tx_hash])
+        .await
+        .unwrap();
+    assert_eq!(transaction_receipts.len(), 1);
+    assert_eq!(transaction_receipts[0].block_number, 0.into());
+}
+
+#[tokio::test]
+async fn retention_policy_enabled_after_pruning() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+    insert_realistic_l1_batches(&mut conn, 6).await;
+
+    let retained_address = Address::repeat_byte(3);
+    let retained_topic = H256::repeat_byte(2);
+    conn.pruning_dal()
+        .hard_prune_batches_range(L1BatchNumber(1), L2BlockNumber(3))
+        .await
+        .unwrap();
+    let applied_policy = conn
+        .pruning_dal()
+        .get_applied_retention_policy()
+        .await
+        .unwrap();
+    assert_eq!(applied_policy, AppliedRetentionPolicy::default());
+
+    let retention_policy = RetentionPolicy {
+        addresses: vec![retained_address],
+        topics: vec![retained_topic],
+        storage_history: true,
+    };
+    conn.pruning_dal()
+        .hard_prune_batches_range_with_retention(
+            L1BatchNumber(3),
+            L2BlockNumber(7),
+            &retention_policy,
+        )
+        .await
+        .unwrap();
+    let applied_policy = conn
+        .pruning_dal()
+        .get_applied_retention_policy()
+        .await
+        .unwrap();
+    // Data in L2 blocks pruned before the retention policy was enabled is not retained.
+    let expected_policy = AppliedRetentionPolicy {
+        addresses: HashMap::from([(retained_address, L2BlockNumber(4))]),
+        topics: HashMap::from([(retained_topic, L2BlockNumber(4))]),
+        storage_history: HashMap::from([(retained_address, L2BlockNumber(4))]),
+    };
+    assert_eq!(applied_policy, expected_policy);
+
+    // Dropping an entry from the policy removes it, and re-adding it later resets the first retained block.
+    let retention_policy = RetentionPolicy {
+        addresses: vec![retained_address],
+        ..RetentionPolicy::default()
+    };
+    conn.pruning_dal()
+        .hard_prune_batches_range_with_retention(
+            L1BatchNumber(4),
+            L2BlockNumber(9),
+            &retention_policy,
+        )
+        .await
+        .unwrap();
+    let retention_policy = RetentionPolicy {
+        addresses: vec![retained_address],
+        topics: vec![retained_topic],
+        ..RetentionPolicy::default()
+    };
+    conn.pruning_dal()
+        .hard_prune_batches_range_with_retention(
+            L1BatchNumber(5),
+            L2BlockNumber(11),
+            &retention_policy,
+        )
+        .await
+        .unwrap();
+    let applied_policy = conn
+        .pruning_dal()
+        .get_applied_retention_policy()
+        .await
+        .unwrap();
+    let expected_policy = AppliedRetentionPolicy {
+        addresses: HashMap::from([(retained_address, L2BlockNumber(4))]),
+        topics: HashMap::from([(retained_topic, L2BlockNumber(10))]),
+        storage_history: HashMap::new(),
+    };
+    assert_eq!(applied_policy, expected_policy);
+}
+
+#[test]
+fn checking_retained_events() {
+    let retained_address = Address::repeat_byte(1);
+    let retained_topic = H256::repeat_byte(2);
+    let policy = AppliedRetentionPolicy {
+        addresses: HashMap::from([(retained_address, L2BlockNumber(5))]),
+        topics: HashMap::from([(retained_topic, L2BlockNumber(10))]),
+        storage_history: HashMap::new(),
+    };
+
+    assert_eq!(
+        policy.first_block_retaining_events(&[retained_address], &[]),
+        Some(L2BlockNumber(5))
+    );
+    assert_eq!(
+        policy.first_block_retaining_events(&[retained_address, Address::repeat_byte(3)], &[]),
+        None
+    );
+    assert_eq!(policy.first_block_retaining_events(&[], &[]), None);
+    assert_eq!(
+        policy.first_block_retaining_events(&[], &[(1, vec![retained_topic])]),
+        Some(L2BlockNumber(10))
+    );
+    assert_eq!(
+        policy.first_block_retaining_events(
+            &[Address::repeat_byte(3)],
+            &[
+                (1, vec![H256::zero(), H256::repeat_byte(3)]),
+                (2, vec![retained_topic])
+            ]
+        ),
+        Some(L2BlockNumber(10))
+    );
+    assert_eq!(
+        policy.first_block_retaining_events(&[retained_address], &[(2, vec![retained_topic])]),
+        Some(L2BlockNumber(5))
+    );
+    assert_eq!(
+        policy.first_block_retaining_events(&[], &[(1, vec![retained_topic, H256::zero()])]),
+        None
+    );
+    assert!(policy
+        .first_block_retaining_storage_of(&retained_address)
+        .is_none());
+}
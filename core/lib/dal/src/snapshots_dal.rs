@@ -14,6 +14,7 @@ use crate::Core;
 struct StorageSnapshotMetadata {
     version: i32,
     l1_batch_number: i64,
+    base_l1_batch_number: Option<i64>,
     storage_logs_filepaths: Vec<String>,
     factory_deps_filepath: String,
 }
@@ -28,6 +29,9 @@ impl TryFrom<StorageSnapshotMetadata> for SnapshotMetadata {
         Ok(Self {
             version,
             l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+            base_l1_batch_number: row
+                .base_l1_batch_number
+                .map(|number| L1BatchNumber(number as u32)),
             storage_logs_filepaths: row
                 .storage_logs_filepaths
                 .into_iter()
@@ -79,6 +83,46 @@ impl SnapshotsDal<'_, '_> {
         Ok(())
     }
 
+    /// Adds a delta snapshot based on the snapshot for `base_l1_batch_number`.
+    pub async fn add_delta_snapshot(
+        &mut self,
+        version: SnapshotVersion,
+        l1_batch_number: L1BatchNumber,
+        base_l1_batch_number: L1BatchNumber,
+        storage_logs_chunk_count: u64,
+        factory_deps_filepaths: &str,
+    ) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                snapshots (
+                    VERSION,
+                    l1_batch_number,
+                    base_l1_batch_number,
+                    storage_logs_filepaths,
+                    factory_deps_filepath,
+                    created_at,
+                    updated_at
+                )
+            VALUES
+                ($1, $2, $3, ARRAY_FILL(''::TEXT, ARRAY[$4::INTEGER]), $5, NOW(), NOW())
+            "#,
+            version as i32,
+            l1_batch_number.0 as i32,
+            base_l1_batch_number.0 as i32,
+            storage_logs_chunk_count as i32,
+            factory_deps_filepaths,
+        )
+        .instrument("add_delta_snapshot")
+        .with_arg("version", &version)
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .with_arg("base_l1_batch_number", &base_l1_batch_number)
+        .report_latency()
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
     pub async fn add_storage_logs_filepath_for_snapshot(
         &mut self,
         l1_batch_number: L1BatchNumber,
@@ -107,6 +151,7 @@ impl SnapshotsDal<'_, '_> {
         Ok(())
     }
 
+    /// Returns L1 batch numbers of all complete snapshots, including delta snapshots, in descending order.
     pub async fn get_all_complete_snapshots(&mut self) -> DalResult<AllSnapshots> {
         let rows = sqlx::query!(
             r#"
@@ -135,6 +180,36 @@ impl SnapshotsDal<'_, '_> {
         })
     }
 
+    /// Same as [`Self::get_all_complete_snapshots()`], but only returns full snapshots (i.e., excludes delta snapshots).
+    pub async fn get_all_complete_full_snapshots(&mut self) -> DalResult<AllSnapshots> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                l1_batch_number
+            FROM
+                snapshots
+            WHERE
+                NOT (''::TEXT = ANY (storage_logs_filepaths))
+                AND base_l1_batch_number IS NULL
+            ORDER BY
+                l1_batch_number DESC
+            "#
+        )
+        .instrument("get_all_complete_full_snapshots")
+        .report_latency()
+        .fetch_all(self.storage)
+        .await?;
+
+        let snapshots_l1_batch_numbers = rows
+            .into_iter()
+            .map(|row| L1BatchNumber(row.l1_batch_number as u32))
+            .collect();
+
+        Ok(AllSnapshots {
+            snapshots_l1_batch_numbers,
+        })
+    }
+
     pub async fn get_newest_snapshot_metadata(&mut self) -> DalResult<Option<SnapshotMetadata>> {
         sqlx::query_as!(
             StorageSnapshotMetadata,
@@ -142,6 +217,7 @@ impl SnapshotsDal<'_, '_> {
             SELECT
                 VERSION,
                 l1_batch_number,
+                base_l1_batch_number,
                 factory_deps_filepath,
                 storage_logs_filepaths
             FROM
@@ -169,6 +245,7 @@ impl SnapshotsDal<'_, '_> {
             SELECT
                 VERSION,
                 l1_batch_number,
+                base_l1_batch_number,
                 factory_deps_filepath,
                 storage_logs_filepaths
             FROM
@@ -200,6 +277,7 @@ impl SnapshotsDal<'_, '_> {
             RETURNING
                 VERSION,
                 l1_batch_number,
+                base_l1_batch_number,
                 factory_deps_filepath,
                 storage_logs_filepaths
             "#,
@@ -265,6 +343,84 @@ mod tests {
             .unwrap()
             .expect("snapshot is not persisted");
         assert_eq!(snapshot_metadata.l1_batch_number, l1_batch_number);
+        assert_eq!(snapshot_metadata.base_l1_batch_number, None);
+    }
+
+    #[tokio::test]
+    async fn adding_delta_snapshot() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let mut dal = conn.snapshots_dal();
+        let base_l1_batch_number = L1BatchNumber(100);
+        dal.add_snapshot(
+            SnapshotVersion::Version1,
+            base_l1_batch_number,
+            1,
+            "gs:///bucket/factory_deps.bin",
+        )
+        .await
+        .unwrap();
+        let l1_batch_number = L1BatchNumber(110);
+        dal.add_delta_snapshot(
+            SnapshotVersion::Version1,
+            l1_batch_number,
+            base_l1_batch_number,
+            1,
+            "gs:///bucket/factory_deps_delta.bin",
+        )
+        .await
+        .unwrap();
+
+        let snapshot_metadata = dal
+            .get_snapshot_metadata(l1_batch_number)
+            .await
+            .unwrap()
+            .expect("snapshot is not persisted");
+        assert_eq!(snapshot_metadata.l1_batch_number, l1_batch_number);
+        assert_eq!(
+            snapshot_metadata.base_l1_batch_number,
+            Some(base_l1_batch_number)
+        );
+        let newest_snapshot_metadata = dal.get_newest_snapshot_metadata().await.unwrap().unwrap();
+        assert_eq!(
+            newest_snapshot_metadata.base_l1_batch_number,
+            Some(base_l1_batch_number)
+        );
+
+        for number in [base_l1_batch_number, l1_batch_number] {
+            dal.add_storage_logs_filepath_for_snapshot(number, 0, "gs:///bucket/chunk.bin")
+                .await
+                .unwrap();
+        }
+        let all_snapshots = dal.get_all_complete_snapshots().await.unwrap();
+        assert_eq!(
+            all_snapshots.snapshots_l1_batch_numbers,
+            [l1_batch_number, base_l1_batch_number]
+        );
+        let full_snapshots = dal.get_all_complete_full_snapshots().await.unwrap();
+        assert_eq!(
+            full_snapshots.snapshots_l1_batch_numbers,
+            [base_l1_batch_number]
+        );
+
+        // The base snapshot cannot be removed while it's referenced by a delta snapshot.
+        let err = sqlx::query("DELETE FROM snapshots WHERE l1_batch_number = $1")
+            .bind(i64::from(base_l1_batch_number.0))
+            .execute(conn.conn())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("foreign key"), "{err}");
+
+        let mut dal = conn.snapshots_dal();
+        let deleted_snapshots = dal
+            .delete_snapshots_after(base_l1_batch_number)
+            .await
+            .unwrap();
+        assert_eq!(deleted_snapshots.len(), 1);
+        assert_eq!(
+            deleted_snapshots[0].base_l1_batch_number,
+            Some(base_l1_batch_number)
+        );
     }
 
     #[tokio::test]
@@ -0,0 +1,69 @@
+//! Persistent state keeper settings.
+
+use zksync_db_connection::{connection::Connection, error::DalResult, instrument::InstrumentExt};
+use zksync_types::L1BatchNumber;
+
+use crate::Core;
+
+#[derive(Debug)]
+pub struct StateKeeperDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Core>,
+}
+
+impl StateKeeperDal<'_, '_> {
+    /// Returns the L1 batch after which L2 transactions are not accepted, if any.
+    pub async fn get_l2_txs_stop_l1_batch(&mut self) -> DalResult<Option<L1BatchNumber>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                l1_batch_number
+            FROM
+                state_keeper_l2_txs_stop
+            WHERE
+                fake_key
+            "#
+        )
+        .instrument("get_l2_txs_stop_l1_batch")
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(row.map(|row| L1BatchNumber(row.l1_batch_number as u32)))
+    }
+
+    /// Sets the L1 batch after which L2 transactions are not accepted. `None` removes the limit.
+    pub async fn set_l2_txs_stop_l1_batch(
+        &mut self,
+        l1_batch_number: Option<L1BatchNumber>,
+    ) -> DalResult<()> {
+        let Some(l1_batch_number) = l1_batch_number else {
+            sqlx::query!(
+                r#"
+                DELETE FROM state_keeper_l2_txs_stop
+                "#
+            )
+            .instrument("set_l2_txs_stop_l1_batch#remove")
+            .execute(self.storage)
+            .await?;
+            return Ok(());
+        };
+
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                state_keeper_l2_txs_stop (fake_key, l1_batch_number)
+            VALUES
+                (TRUE, $1)
+            ON CONFLICT (fake_key) DO
+            UPDATE
+            SET
+                l1_batch_number = excluded.l1_batch_number
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("set_l2_txs_stop_l1_batch")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+}
@@ -151,6 +151,68 @@ impl StorageLogsDal<'_, '_> {
         copy.send(buffer.as_bytes()).await
     }
 
+    /// Upserts storage logs from a delta snapshot. Unlike [`Self::insert_storage_logs_from_snapshot()`],
+    /// logs for keys already recovered from a previous snapshot in the chain overwrite the previous values.
+    pub async fn upsert_storage_logs_from_snapshot(
+        &mut self,
+        l2_block_number: L2BlockNumber,
+        snapshot_storage_logs: &[SnapshotStorageLog],
+    ) -> DalResult<()> {
+        let hashed_keys: Vec<_> = snapshot_storage_logs
+            .iter()
+            .map(|log| log.key.as_bytes())
+            .collect();
+        let values: Vec<_> = snapshot_storage_logs
+            .iter()
+            .map(|log| log.value.as_bytes())
+            .collect();
+        let operation_numbers: Vec<_> = snapshot_storage_logs
+            .iter()
+            .map(|log| log.enumeration_index as i64)
+            .collect();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                storage_logs (
+                    hashed_key,
+                    value,
+                    operation_number,
+                    tx_hash,
+                    miniblock_number,
+                    created_at,
+                    updated_at
+                )
+            SELECT
+                u.hashed_key,
+                u.value,
+                u.operation_number,
+                $4,
+                $5,
+                NOW(),
+                NOW()
+            FROM
+                UNNEST($1::bytea[], $2::bytea[], $3::BIGINT[]) AS u (hashed_key, value, operation_number)
+            ON CONFLICT (hashed_key, miniblock_number, operation_number) DO
+            UPDATE
+            SET
+                value = excluded.value,
+                updated_at = NOW()
+            "#,
+            &hashed_keys as &[&[u8]],
+            &values as &[&[u8]],
+            &operation_numbers,
+            H256::zero().as_bytes(),
+            i64::from(l2_block_number.0)
+        )
+        .instrument("upsert_storage_logs_from_snapshot")
+        .with_arg("l2_block_number", &l2_block_number)
+        .with_arg("storage_logs.len", &snapshot_storage_logs.len())
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
     pub async fn append_storage_logs(
         &mut self,
         block_number: L2BlockNumber,
@@ -78,6 +78,50 @@ impl StorageLogsDedupDal<'_, '_> {
         copy.send(&bytes).await
     }
 
+    /// Inserts initial writes from a delta snapshot, skipping keys already recovered from a previous snapshot
+    /// in the chain.
+    pub async fn upsert_initial_writes_from_snapshot(
+        &mut self,
+        snapshot_storage_logs: &[SnapshotStorageLog],
+    ) -> DalResult<()> {
+        let hashed_keys: Vec<_> = snapshot_storage_logs
+            .iter()
+            .map(|log| log.key.as_bytes())
+            .collect();
+        let indices: Vec<_> = snapshot_storage_logs
+            .iter()
+            .map(|log| log.enumeration_index as i64)
+            .collect();
+        let l1_batch_numbers: Vec<_> = snapshot_storage_logs
+            .iter()
+            .map(|log| i64::from(log.l1_batch_number_of_initial_write.0))
+            .collect();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                initial_writes (hashed_key, INDEX, l1_batch_number, created_at, updated_at)
+            SELECT
+                u.hashed_key,
+                u.index,
+                u.l1_batch_number,
+                NOW(),
+                NOW()
+            FROM
+                UNNEST($1::bytea[], $2::BIGINT[], $3::BIGINT[]) AS u (hashed_key, INDEX, l1_batch_number)
+            ON CONFLICT (hashed_key) DO NOTHING
+            "#,
+            &hashed_keys as &[&[u8]],
+            &indices,
+            &l1_batch_numbers
+        )
+        .instrument("upsert_initial_writes_from_snapshot")
+        .with_arg("storage_logs.len", &snapshot_storage_logs.len())
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
     pub async fn insert_initial_writes(
         &mut self,
         l1_batch_number: L1BatchNumber,
@@ -1,4 +1,4 @@
-use std::{collections::HashMap, fmt, time::Duration};
+use std::{collections::HashMap, fmt, ops, time::Duration};
 
 use bigdecimal::BigDecimal;
 use itertools::Itertools;
@@ -10,9 +10,10 @@ use zksync_db_connection::{
     utils::pg_interval_from_duration,
 };
 use zksync_types::{
-    block::L2BlockExecutionData, l1::L1Tx, l2::L2Tx, protocol_upgrade::ProtocolUpgradeTx, Address,
-    ExecuteTransactionCommon, L1BatchNumber, L1BlockNumber, L2BlockNumber, PriorityOpId,
-    ProtocolVersionId, Transaction, H256, PROTOCOL_UPGRADE_TX_TYPE, U256,
+    api::trace::TraceLocation, block::L2BlockExecutionData, l1::L1Tx, l2::L2Tx,
+    protocol_upgrade::ProtocolUpgradeTx, Address, ExecuteTransactionCommon, L1BatchNumber,
+    L1BlockNumber, L2BlockNumber, PriorityOpId, ProtocolVersionId, Transaction, H256,
+    PROTOCOL_UPGRADE_TX_TYPE, U256,
 };
 use zksync_utils::u256_to_big_decimal;
 use zksync_vm_interface::{
@@ -20,7 +21,7 @@ use zksync_vm_interface::{
 };
 
 use crate::{
-    models::storage_transaction::{CallTrace, StorageTransaction},
+    models::storage_transaction::{CallTrace, CallTraceWithLocation, StorageTransaction},
     Core, CoreDal,
 };
 
@@ -1832,6 +1833,74 @@ impl TransactionsDal<'_, '_> {
             .map(|number| L1BlockNumber(number as u32)))
     }
 
+    /// Returns priority operations (serial ID, originating L1 block and canonical hash) originating from L1 blocks
+    /// after the specified one, ordered by serial ID.
+    pub async fn get_priority_ops_after_l1_block(
+        &mut self,
+        l1_block: L1BlockNumber,
+    ) -> DalResult<Vec<(PriorityOpId, L1BlockNumber, H256)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                priority_op_id AS "priority_op_id!",
+                l1_block_number AS "l1_block_number!",
+                hash
+            FROM
+                transactions
+            WHERE
+                priority_op_id IS NOT NULL
+                AND l1_block_number > $1
+            ORDER BY
+                priority_op_id
+            "#,
+            l1_block.0 as i32
+        )
+        .instrument("get_priority_ops_after_l1_block")
+        .with_arg("l1_block", &l1_block)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                (
+                    PriorityOpId(row.priority_op_id as u64),
+                    L1BlockNumber(row.l1_block_number as u32),
+                    H256::from_slice(&row.hash),
+                )
+            })
+            .collect())
+    }
+
+    /// Returns the first L1 batch containing a priority operation that originates from an L1 block
+    /// after the specified one.
+    pub async fn get_first_l1_batch_with_priority_op_after_l1_block(
+        &mut self,
+        l1_block: L1BlockNumber,
+    ) -> DalResult<Option<L1BatchNumber>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                MIN(l1_batch_number) AS "l1_batch_number"
+            FROM
+                transactions
+            WHERE
+                priority_op_id IS NOT NULL
+                AND l1_block_number > $1
+                AND l1_batch_number IS NOT NULL
+            "#,
+            l1_block.0 as i32
+        )
+        .instrument("get_first_l1_batch_with_priority_op_after_l1_block")
+        .with_arg("l1_block", &l1_block)
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(row
+            .l1_batch_number
+            .map(|number| L1BatchNumber(number as u32)))
+    }
+
     pub async fn last_priority_id(&mut self) -> DalResult<Option<PriorityOpId>> {
         let maybe_row = sqlx::query!(
             r#"
@@ -2147,6 +2216,75 @@ impl TransactionsDal<'_, '_> {
         .map(|call_trace| call_trace.into_call(protocol_version)))
     }
 
+    /// Returns call traces for all transactions in the specified L2 block range, ordered by the L2 block number
+    /// and the transaction index in the block.
+    pub async fn get_call_traces_for_l2_blocks(
+        &mut self,
+        l2_blocks: ops::RangeInclusive<L2BlockNumber>,
+    ) -> DalResult<Vec<(Call, TraceLocation)>> {
+        Ok(sqlx::query_as!(
+            CallTraceWithLocation,
+            r#"
+            SELECT
+                call_traces.tx_hash,
+                call_traces.call_trace,
+                transactions.miniblock_number AS "miniblock_number!",
+                transactions.index_in_block AS "index_in_block!",
+                miniblocks.hash AS block_hash,
+                miniblocks.protocol_version
+            FROM
+                call_traces
+                INNER JOIN transactions ON call_traces.tx_hash = transactions.hash
+                INNER JOIN miniblocks ON transactions.miniblock_number = miniblocks.number
+            WHERE
+                transactions.miniblock_number BETWEEN $1 AND $2
+            ORDER BY
+                transactions.miniblock_number,
+                transactions.index_in_block
+            "#,
+            i64::from(l2_blocks.start().0),
+            i64::from(l2_blocks.end().0)
+        )
+        .instrument("get_call_traces_for_l2_blocks")
+        .with_arg("l2_blocks", &l2_blocks)
+        .fetch_all(self.storage)
+        .await?
+        .into_iter()
+        .map(CallTraceWithLocation::into_call)
+        .collect())
+    }
+
+    /// Returns the call trace for the specified transaction together with its location in the chain.
+    pub async fn get_call_trace_with_location(
+        &mut self,
+        tx_hash: H256,
+    ) -> DalResult<Option<(Call, TraceLocation)>> {
+        Ok(sqlx::query_as!(
+            CallTraceWithLocation,
+            r#"
+            SELECT
+                call_traces.tx_hash,
+                call_traces.call_trace,
+                transactions.miniblock_number AS "miniblock_number!",
+                transactions.index_in_block AS "index_in_block!",
+                miniblocks.hash AS block_hash,
+                miniblocks.protocol_version
+            FROM
+                call_traces
+                INNER JOIN transactions ON call_traces.tx_hash = transactions.hash
+                INNER JOIN miniblocks ON transactions.miniblock_number = miniblocks.number
+            WHERE
+                call_traces.tx_hash = $1
+            "#,
+            tx_hash.as_bytes()
+        )
+        .instrument("get_call_trace_with_location")
+        .with_arg("tx_hash", &tx_hash)
+        .fetch_optional(self.storage)
+        .await?
+        .map(CallTraceWithLocation::into_call))
+    }
+
     pub(crate) async fn get_tx_by_hash(&mut self, hash: H256) -> DalResult<Option<Transaction>> {
         sqlx::query_as!(
             StorageTransaction,
@@ -2223,6 +2361,33 @@ mod tests {
             .unwrap()
             .expect("no call trace");
         assert_eq!(call_trace, expected_call_trace);
+
+        let (call_trace, location) = conn
+            .transactions_dal()
+            .get_call_trace_with_location(tx_hash)
+            .await
+            .unwrap()
+            .expect("no call trace");
+        assert_eq!(call_trace, expected_call_trace);
+        assert_eq!(location.transaction_hash, tx_hash);
+        assert_eq!(location.block_number, 1.into());
+        assert_eq!(location.block_hash, create_l2_block_header(1).hash);
+        assert_eq!(location.transaction_position, 0.into());
+
+        let traces = conn
+            .transactions_dal()
+            .get_call_traces_for_l2_blocks(L2BlockNumber(0)..=L2BlockNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(traces.len(), 1);
+        assert_eq!(traces[0].0, expected_call_trace);
+        assert_eq!(traces[0].1.transaction_hash, tx_hash);
+        let traces = conn
+            .transactions_dal()
+            .get_call_traces_for_l2_blocks(L2BlockNumber(2)..=L2BlockNumber(3))
+            .await
+            .unwrap();
+        assert!(traces.is_empty());
     }
 
     #[tokio::test]
@@ -75,13 +75,29 @@ impl TransactionsWeb3Dal<'_, '_> {
                 transactions.tx_format AS "tx_format?",
                 transactions.refunded_gas AS refunded_gas,
                 transactions.gas_limit AS gas_limit,
-                miniblocks.hash AS "block_hash",
+                miniblocks.hash AS "block_hash!",
                 miniblocks.l1_batch_number AS "l1_batch_number?",
                 events.topic4 AS "contract_address?",
                 miniblocks.timestamp AS "block_timestamp?"
             FROM
                 transactions
-                JOIN miniblocks ON miniblocks.number = transactions.miniblock_number
+                JOIN (
+                    SELECT
+                        number,
+                        hash,
+                        l1_batch_number,
+                        timestamp
+                    FROM
+                        miniblocks
+                    UNION ALL
+                    SELECT
+                        number,
+                        hash,
+                        l1_batch_number,
+                        timestamp
+                    FROM
+                        pruning_retained_l2_blocks
+                ) miniblocks ON miniblocks.number = transactions.miniblock_number
                 LEFT JOIN events ON events.tx_hash = transactions.hash
             WHERE
                 transactions.hash = ANY ($3)
@@ -223,7 +239,11 @@ impl TransactionsWeb3Dal<'_, '_> {
                     transactions.data->'calldata' AS "calldata",
                     miniblocks.hash AS "block_hash"
                 FROM transactions
-                LEFT JOIN miniblocks ON miniblocks.number = transactions.miniblock_number
+                LEFT JOIN (
+                    SELECT number, hash FROM miniblocks
+                    UNION ALL
+                    SELECT number, hash FROM pruning_retained_l2_blocks
+                ) miniblocks ON miniblocks.number = transactions.miniblock_number
                 WHERE
                 "#,
                 _, // WHERE condition
@@ -0,0 +1,72 @@
+use zksync_config::configs::AddressPolicyConfig;
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for AddressPolicyConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("address_policy", "ADDRESS_POLICY_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{addr, EnvMutex};
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    #[test]
+    fn from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            ADDRESS_POLICY_POLICY_FILE_PATH="/etc/zksync/address_policy.json"
+            ADDRESS_POLICY_ONCHAIN_DENYLIST_ADDR="0x0000000000000000000000000000000000000010"
+            ADDRESS_POLICY_RELOAD_INTERVAL_MS=5000
+        "#;
+        lock.set_env(config);
+
+        let actual = AddressPolicyConfig::from_env().unwrap();
+        assert_eq!(
+            actual,
+            AddressPolicyConfig {
+                policy_file_path: Some("/etc/zksync/address_policy.json".to_owned()),
+                onchain_denylist_addr: Some(addr("0000000000000000000000000000000000000010")),
+                reload_interval_ms: 5_000,
+            }
+        );
+    }
+
+    #[test]
+    fn from_env_with_defaults() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            ADDRESS_POLICY_POLICY_FILE_PATH="/etc/zksync/address_policy.json"
+        "#;
+        lock.set_env(config);
+        lock.remove_env(&[
+            "ADDRESS_POLICY_ONCHAIN_DENYLIST_ADDR",
+            "ADDRESS_POLICY_RELOAD_INTERVAL_MS",
+        ]);
+
+        let actual = AddressPolicyConfig::from_env().unwrap();
+        assert_eq!(actual.onchain_denylist_addr, None);
+        assert_eq!(
+            actual.reload_interval_ms,
+            AddressPolicyConfig::default_reload_interval_ms()
+        );
+        assert!(actual.is_enabled());
+    }
+
+    #[test]
+    fn from_env_without_sources() {
+        let mut lock = MUTEX.lock();
+        lock.remove_env(&[
+            "ADDRESS_POLICY_POLICY_FILE_PATH",
+            "ADDRESS_POLICY_ONCHAIN_DENYLIST_ADDR",
+            "ADDRESS_POLICY_RELOAD_INTERVAL_MS",
+        ]);
+
+        let actual = AddressPolicyConfig::from_env().unwrap();
+        assert!(!actual.is_enabled());
+    }
+}
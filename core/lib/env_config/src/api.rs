@@ -3,7 +3,7 @@ use zksync_config::configs::{
     api::{
         ContractVerificationApiConfig, HealthCheckConfig, MerkleTreeApiConfig, Web3JsonRpcConfig,
     },
-    ApiConfig, PrometheusConfig,
+    AdminSecrets, ApiConfig, PrometheusConfig,
 };
 
 use crate::{envy_load, FromEnv};
@@ -31,6 +31,16 @@ impl FromEnv for HealthCheckConfig {
     }
 }
 
+impl FromEnv for AdminSecrets {
+    fn from_env() -> anyhow::Result<Self> {
+        let auth_token = std::env::var("API_HEALTHCHECK_ADMIN_AUTH_TOKEN")
+            .context("API_HEALTHCHECK_ADMIN_AUTH_TOKEN")?;
+        Ok(Self {
+            auth_token: auth_token.into(),
+        })
+    }
+}
+
 impl FromEnv for ContractVerificationApiConfig {
     fn from_env() -> anyhow::Result<Self> {
         envy_load("contract_verification", "API_CONTRACT_VERIFICATION_")
@@ -60,6 +70,11 @@ mod tests {
                 http_url: "http://127.0.0.1:3050".into(),
                 ws_port: 3051,
                 ws_url: "ws://127.0.0.1:3051".into(),
+                ipc_path: Some("/var/run/zksync/web3.ipc".into()),
+                ipc_max_connections: Some(100),
+                ipc_subscriptions_per_connection_limit: Some(50),
+                ipc_max_concurrent_requests_per_connection: Some(16),
+                ipc_socket_permissions: Some("660".into()),
                 req_entities_limit: Some(10000),
                 filters_disabled: false,
                 filters_limit: Some(10000),
@@ -76,6 +91,7 @@ mod tests {
                 initial_writes_cache_size_mb: Some(32),
                 latest_values_cache_size_mb: Some(256),
                 fee_history_limit: Some(100),
+                trace_filter_block_range_limit: Some(50),
                 max_batch_request_size: Some(200),
                 max_response_body_size_mb: Some(10),
                 max_response_body_size_overrides_mb: [
@@ -86,6 +102,20 @@ mod tests {
                 .into_iter()
                 .collect(),
                 websocket_requests_per_minute_limit: Some(NonZeroU32::new(10).unwrap()),
+                http_compute_units_per_minute_limit: Some(NonZeroU32::new(6_000).unwrap()),
+                http_api_key_compute_units_per_minute_limits: [(
+                    "test-key",
+                    NonZeroU32::new(60_000).unwrap(),
+                )]
+                .into_iter()
+                .collect(),
+                method_compute_units: [
+                    ("eth_getLogs", NonZeroU32::new(20).unwrap()),
+                    ("debug_traceCall", NonZeroU32::new(50).unwrap()),
+                ]
+                .into_iter()
+                .collect(),
+                http_trusted_proxies: vec![[10, 0, 0, 1].into(), "::1".parse().unwrap()],
                 tree_api_url: None,
                 mempool_cache_update_interval: Some(50),
                 mempool_cache_size: Some(10000),
@@ -95,6 +125,7 @@ mod tests {
                 ],
                 api_namespaces: Some(vec!["debug".to_string()]),
                 extended_api_tracing: true,
+                preconfirmations_enabled: true,
             },
             prometheus: PrometheusConfig {
                 listener_port: 3312,
@@ -105,6 +136,7 @@ mod tests {
                 port: 8081,
                 slow_time_limit_ms: Some(250),
                 hard_time_limit_ms: Some(2_000),
+                admin_port: Some(8083),
             },
             merkle_tree: MerkleTreeApiConfig { port: 8082 },
         }
@@ -118,6 +150,11 @@ mod tests {
             API_WEB3_JSON_RPC_HTTP_URL="http://127.0.0.1:3050"
             API_WEB3_JSON_RPC_WS_PORT="3051"
             API_WEB3_JSON_RPC_WS_URL="ws://127.0.0.1:3051"
+            API_WEB3_JSON_RPC_IPC_PATH="/var/run/zksync/web3.ipc"
+            API_WEB3_JSON_RPC_IPC_MAX_CONNECTIONS=100
+            API_WEB3_JSON_RPC_IPC_SUBSCRIPTIONS_PER_CONNECTION_LIMIT=50
+            API_WEB3_JSON_RPC_IPC_MAX_CONCURRENT_REQUESTS_PER_CONNECTION=16
+            API_WEB3_JSON_RPC_IPC_SOCKET_PERMISSIONS="660"
             API_WEB3_JSON_RPC_REQ_ENTITIES_LIMIT=10000
             API_WEB3_JSON_RPC_FILTERS_DISABLED=false
             API_WEB3_JSON_RPC_FILTERS_LIMIT=10000
@@ -127,6 +164,7 @@ mod tests {
             API_WEB3_JSON_RPC_GAS_PRICE_SCALE_FACTOR=1.2
             API_WEB3_JSON_RPC_API_NAMESPACES=debug
             API_WEB3_JSON_RPC_EXTENDED_API_TRACING=true
+            API_WEB3_JSON_RPC_PRECONFIRMATIONS_ENABLED=true
             API_WEB3_JSON_RPC_WHITELISTED_TOKENS_FOR_AA="0x0000000000000000000000000000000000000001,0x0000000000000000000000000000000000000002"
             API_WEB3_JSON_RPC_ESTIMATE_GAS_SCALE_FACTOR=1.0
             API_WEB3_JSON_RPC_ESTIMATE_GAS_ACCEPTABLE_OVERESTIMATION=1000
@@ -136,8 +174,13 @@ mod tests {
             API_WEB3_JSON_RPC_INITIAL_WRITES_CACHE_SIZE_MB=32
             API_WEB3_JSON_RPC_LATEST_VALUES_CACHE_SIZE_MB=256
             API_WEB3_JSON_RPC_FEE_HISTORY_LIMIT=100
+            API_WEB3_JSON_RPC_TRACE_FILTER_BLOCK_RANGE_LIMIT=50
             API_WEB3_JSON_RPC_MAX_BATCH_REQUEST_SIZE=200
             API_WEB3_JSON_RPC_WEBSOCKET_REQUESTS_PER_MINUTE_LIMIT=10
+            API_WEB3_JSON_RPC_HTTP_COMPUTE_UNITS_PER_MINUTE_LIMIT=6000
+            API_WEB3_JSON_RPC_HTTP_API_KEY_COMPUTE_UNITS_PER_MINUTE_LIMITS="test-key=60000"
+            API_WEB3_JSON_RPC_METHOD_COMPUTE_UNITS="eth_getLogs=20,debug_traceCall=50"
+            API_WEB3_JSON_RPC_HTTP_TRUSTED_PROXIES="10.0.0.1,::1"
             API_WEB3_JSON_RPC_MEMPOOL_CACHE_SIZE=10000
             API_WEB3_JSON_RPC_MEMPOOL_CACHE_UPDATE_INTERVAL=50
             API_CONTRACT_VERIFICATION_PORT="3070"
@@ -150,6 +193,7 @@ mod tests {
             API_HEALTHCHECK_PORT=8081
             API_HEALTHCHECK_SLOW_TIME_LIMIT_MS=250
             API_HEALTHCHECK_HARD_TIME_LIMIT_MS=2000
+            API_HEALTHCHECK_ADMIN_PORT=8083
             API_MERKLE_TREE_PORT=8082
         "#;
         lock.set_env(config);
@@ -157,4 +201,19 @@ mod tests {
         let actual = ApiConfig::from_env().unwrap();
         assert_eq!(actual, expected_config());
     }
+
+    #[test]
+    fn admin_secrets_from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            API_HEALTHCHECK_ADMIN_AUTH_TOKEN="correct-horse-battery-staple"
+        "#;
+        lock.set_env(config);
+
+        let actual = AdminSecrets::from_env().unwrap();
+        let expected = AdminSecrets {
+            auth_token: "correct-horse-battery-staple".to_owned().into(),
+        };
+        assert_eq!(actual, expected);
+    }
 }
@@ -12,7 +12,9 @@ impl FromEnv for NetworkConfig {
 
 impl FromEnv for StateKeeperConfig {
     fn from_env() -> anyhow::Result<Self> {
-        envy_load("state_keeper", "CHAIN_STATE_KEEPER_")
+        let config: Self = envy_load("state_keeper", "CHAIN_STATE_KEEPER_")?;
+        config.validate_seal_criteria()?;
+        Ok(config)
     }
 }
 
@@ -78,6 +80,8 @@ mod tests {
             l2_block_commit_deadline_ms: 1000,
             l2_block_seal_queue_capacity: 10,
             l2_block_max_payload_size: 1_000_000,
+            l2_block_gas_target: Some(50_000_000),
+            l2_base_fee_max_change_denominator: Some(16),
             max_single_tx_gas: 1_000_000,
             max_allowed_l2_tx_gas_limit: 2_000_000_000,
             close_block_at_eth_params_percentage: 0.2,
@@ -104,6 +108,10 @@ mod tests {
             )),
             l1_batch_commit_data_generator_mode,
             max_circuits_per_batch: 24100,
+            max_l1_batch_age_ms: Some(60_000),
+            max_l2_blocks_per_batch: Some(100),
+            max_priority_txs_per_batch: Some(50),
+            seal_criteria: vec![],
             protective_reads_persistence_enabled: true,
         }
     }
@@ -121,10 +129,15 @@ mod tests {
             CHAIN_STATE_KEEPER_REJECT_TX_AT_GEOMETRY_PERCENTAGE="0.3"
             CHAIN_STATE_KEEPER_REJECT_TX_AT_ETH_PARAMS_PERCENTAGE="0.8"
             CHAIN_STATE_KEEPER_REJECT_TX_AT_GAS_PERCENTAGE="0.5"
+            CHAIN_STATE_KEEPER_MAX_L1_BATCH_AGE_MS="60000"
+            CHAIN_STATE_KEEPER_MAX_L2_BLOCKS_PER_BATCH="100"
+            CHAIN_STATE_KEEPER_MAX_PRIORITY_TXS_PER_BATCH="50"
             CHAIN_STATE_KEEPER_BLOCK_COMMIT_DEADLINE_MS="2500"
             CHAIN_STATE_KEEPER_MINIBLOCK_COMMIT_DEADLINE_MS="1000"
             CHAIN_STATE_KEEPER_MINIBLOCK_SEAL_QUEUE_CAPACITY="10"
             CHAIN_STATE_KEEPER_MINIBLOCK_MAX_PAYLOAD_SIZE="1000000"
+            CHAIN_STATE_KEEPER_L2_BLOCK_GAS_TARGET="50000000"
+            CHAIN_STATE_KEEPER_L2_BASE_FEE_MAX_CHANGE_DENOMINATOR="16"
             CHAIN_STATE_KEEPER_MINIMAL_L2_GAS_PRICE="100000000"
             CHAIN_STATE_KEEPER_COMPUTE_OVERHEAD_PART="0.0"
             CHAIN_STATE_KEEPER_PUBDATA_OVERHEAD_PART="1.0"
@@ -163,6 +176,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn state_keeper_with_zero_priority_txs_limit_is_rejected() {
+        let mut lock = MUTEX.lock();
+        let config = state_keeper_config(ROLLUP_L1_BATCH_COMMIT_DATA_GENERATOR_MODE).replace(
+            r#"CHAIN_STATE_KEEPER_MAX_PRIORITY_TXS_PER_BATCH="50""#,
+            r#"CHAIN_STATE_KEEPER_MAX_PRIORITY_TXS_PER_BATCH="0""#,
+        );
+        lock.set_env(&config);
+
+        let err = StateKeeperConfig::from_env().unwrap_err().to_string();
+        assert!(err.contains("max_priority_txs_per_batch"), "{err}");
+    }
+
     fn expected_mempool_config() -> MempoolConfig {
         MempoolConfig {
             sync_interval_ms: 10,
@@ -83,7 +83,7 @@ impl FromEnv for PostgresConfig {
 mod tests {
     use std::{num::NonZeroU32, time::Duration};
 
-    use zksync_config::configs::database::MerkleTreeMode;
+    use zksync_config::configs::database::{MerkleTreeMode, MerkleTreeStorage};
 
     use super::*;
     use crate::test_utils::EnvMutex;
@@ -97,10 +97,13 @@ mod tests {
             DATABASE_STATE_KEEPER_DB_PATH="/db/state_keeper"
             DATABASE_MERKLE_TREE_PATH="/db/tree"
             DATABASE_MERKLE_TREE_MODE=lightweight
+            DATABASE_MERKLE_TREE_STORAGE=postgres
             DATABASE_MERKLE_TREE_MULTI_GET_CHUNK_SIZE=250
             DATABASE_MERKLE_TREE_MEMTABLE_CAPACITY_MB=512
             DATABASE_MERKLE_TREE_STALLED_WRITES_TIMEOUT_SEC=60
             DATABASE_MERKLE_TREE_MAX_L1_BATCHES_PER_ITER=50
+            DATABASE_MERKLE_TREE_CHECKPOINT_INTERVAL_L1_BATCHES=1000
+            DATABASE_MERKLE_TREE_CHECKPOINT_IMPORT_ENABLED=true
             DATABASE_EXPERIMENTAL_STATE_KEEPER_DB_BLOCK_CACHE_CAPACITY_MB=64
             DATABASE_EXPERIMENTAL_STATE_KEEPER_DB_MAX_OPEN_FILES=100
         "#;
@@ -110,10 +113,16 @@ mod tests {
         assert_eq!(db_config.state_keeper_db_path, "/db/state_keeper");
         assert_eq!(db_config.merkle_tree.path, "/db/tree");
         assert_eq!(db_config.merkle_tree.mode, MerkleTreeMode::Lightweight);
+        assert_eq!(db_config.merkle_tree.storage, MerkleTreeStorage::Postgres);
         assert_eq!(db_config.merkle_tree.multi_get_chunk_size, 250);
         assert_eq!(db_config.merkle_tree.max_l1_batches_per_iter, 50);
         assert_eq!(db_config.merkle_tree.memtable_capacity_mb, 512);
         assert_eq!(db_config.merkle_tree.stalled_writes_timeout_sec, 60);
+        assert_eq!(
+            db_config.merkle_tree.checkpoint_interval_l1_batches,
+            NonZeroU32::new(1_000)
+        );
+        assert!(db_config.merkle_tree.checkpoint_import_enabled);
         assert_eq!(
             db_config
                 .experimental
@@ -136,22 +145,28 @@ mod tests {
             "DATABASE_MERKLE_TREE_BACKUP_PATH",
             "DATABASE_MERKLE_TREE_PATH",
             "DATABASE_MERKLE_TREE_MODE",
+            "DATABASE_MERKLE_TREE_STORAGE",
             "DATABASE_MERKLE_TREE_MULTI_GET_CHUNK_SIZE",
             "DATABASE_MERKLE_TREE_BLOCK_CACHE_SIZE_MB",
             "DATABASE_MERKLE_TREE_MEMTABLE_CAPACITY_MB",
             "DATABASE_MERKLE_TREE_STALLED_WRITES_TIMEOUT_SEC",
             "DATABASE_MERKLE_TREE_MAX_L1_BATCHES_PER_ITER",
+            "DATABASE_MERKLE_TREE_CHECKPOINT_INTERVAL_L1_BATCHES",
+            "DATABASE_MERKLE_TREE_CHECKPOINT_IMPORT_ENABLED",
         ]);
 
         let db_config = DBConfig::from_env().unwrap();
         assert_eq!(db_config.state_keeper_db_path, "./db/state_keeper");
         assert_eq!(db_config.merkle_tree.path, "./db/lightweight-new");
         assert_eq!(db_config.merkle_tree.mode, MerkleTreeMode::Full);
+        assert_eq!(db_config.merkle_tree.storage, MerkleTreeStorage::RocksDB);
         assert_eq!(db_config.merkle_tree.multi_get_chunk_size, 500);
         assert_eq!(db_config.merkle_tree.max_l1_batches_per_iter, 20);
         assert_eq!(db_config.merkle_tree.block_cache_size_mb, 128);
         assert_eq!(db_config.merkle_tree.memtable_capacity_mb, 256);
         assert_eq!(db_config.merkle_tree.stalled_writes_timeout_sec, 30);
+        assert_eq!(db_config.merkle_tree.checkpoint_interval_l1_batches, None);
+        assert!(!db_config.merkle_tree.checkpoint_import_enabled);
         assert_eq!(
             db_config
                 .experimental
@@ -90,6 +90,7 @@ mod tests {
                 }),
                 watcher: Some(EthWatchConfig {
                     confirmations_for_eth_event: Some(0),
+                    confirmations_for_priority_ops: None,
                     eth_node_poll_interval: 300,
                 }),
             },
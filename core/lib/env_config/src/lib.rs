@@ -1,6 +1,7 @@
 use anyhow::Context as _;
 use serde::de::DeserializeOwned;
 
+mod address_policy;
 mod api;
 mod chain;
 mod contract_verifier;
@@ -132,6 +132,61 @@ impl MockSettlementLayerInner {
         self.executed_txs.insert(tx_hash, status);
     }
 
+    fn get_logs(&self, filter: web3::Filter) -> Vec<web3::Log> {
+        let resolve_block_number = |number: Option<web3::BlockNumber>| match number {
+            Some(web3::BlockNumber::Number(number)) => number.as_u64(),
+            Some(web3::BlockNumber::Earliest) => 0,
+            _ => self.block_number,
+        };
+        let from_block = resolve_block_number(filter.from_block);
+        let to_block = resolve_block_number(filter.to_block);
+        let addresses = filter.address.map(web3::ValueOrArray::flatten);
+        let topics: Vec<_> = filter
+            .topics
+            .unwrap_or_default()
+            .into_iter()
+            .map(|topic| topic.map(web3::ValueOrArray::flatten))
+            .collect();
+
+        let mut executed_txs: Vec<_> = self
+            .executed_txs
+            .values()
+            .filter(|tx| tx.success)
+            .map(|tx| &tx.receipt)
+            .filter(|receipt| {
+                let block_number = receipt.block_number.unwrap_or_default().as_u64();
+                (from_block..=to_block).contains(&block_number)
+            })
+            .collect();
+        executed_txs.sort_unstable_by_key(|receipt| {
+            let nonce = self.sent_txs[&receipt.transaction_hash].nonce;
+            (receipt.block_number, nonce)
+        });
+
+        let logs = executed_txs.into_iter().flat_map(|receipt| {
+            receipt.logs.iter().map(|log| web3::Log {
+                block_number: receipt.block_number,
+                transaction_hash: Some(receipt.transaction_hash),
+                ..log.clone()
+            })
+        });
+        logs.filter(|log| {
+            let address_matches = addresses
+                .as_ref()
+                .map_or(true, |addresses| addresses.contains(&log.address));
+            let topics_match = topics.iter().enumerate().all(|(i, expected)| {
+                let Some(expected) = expected else {
+                    return true;
+                };
+                log.topics
+                    .get(i)
+                    .map_or(false, |topic| expected.contains(topic))
+            });
+            address_matches && topics_match
+        })
+        .collect()
+    }
+
     fn get_transaction_count(&self, address: Address, block: web3::BlockNumber) -> U256 {
         if address != MOCK_SENDER_ACCOUNT {
             unimplemented!("Getting nonce for custom account is not supported");
@@ -383,6 +438,10 @@ impl<Net: SupportedMockSLNetwork> MockSettlementLayerBuilder<Net> {
                     Ok(Some(web3::Transaction::from(tx.clone())))
                 }
             })
+            .method("eth_getLogs", {
+                let inner = self.inner.clone();
+                move |filter| Ok(inner.read().unwrap().get_logs(filter))
+            })
             .method("eth_getTransactionReceipt", {
                 let inner = self.inner.clone();
                 move |hash: H256| {
@@ -840,6 +899,70 @@ mod tests {
         assert_matches!(commitment_mode, L1BatchCommitmentMode::Rollup);
     }
 
+    #[tokio::test]
+    async fn getting_logs() {
+        let client = MockSettlementLayer::<L1>::builder().build();
+        let contract_addr = Address::repeat_byte(1);
+        let topics = [H256::repeat_byte(0x11), H256::repeat_byte(0x22)];
+        let mut tx_hashes = vec![];
+        for (i, &topic) in topics.iter().enumerate() {
+            let signed_tx = client
+                .sign_prepared_tx(
+                    vec![i as u8],
+                    contract_addr,
+                    Options {
+                        nonce: Some(i.into()),
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+            let tx_hash = client.as_ref().send_raw_tx(signed_tx.raw_tx).await.unwrap();
+            client
+                .execute_tx(tx_hash, true, 1)
+                .with_logs(vec![web3::Log {
+                    address: contract_addr,
+                    topics: vec![topic],
+                    ..web3::Log::default()
+                }]);
+            tx_hashes.push(tx_hash);
+        }
+
+        let filter = web3::FilterBuilder::default()
+            .address(vec![contract_addr])
+            .from_block(web3::BlockNumber::Earliest)
+            .to_block(web3::BlockNumber::Latest)
+            .build();
+        let logs = client.as_ref().logs(&filter).await.unwrap();
+        assert_eq!(logs.len(), 2);
+        for (i, log) in logs.iter().enumerate() {
+            assert_eq!(log.topics, [topics[i]]);
+            assert_eq!(log.transaction_hash, Some(tx_hashes[i]));
+            assert_eq!(log.block_number, Some((i as u64).into()));
+        }
+
+        let filter = web3::FilterBuilder::default()
+            .address(vec![contract_addr])
+            .topics(Some(vec![topics[1]]), None, None, None)
+            .build();
+        let logs = client.as_ref().logs(&filter).await.unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].transaction_hash, Some(tx_hashes[1]));
+
+        let filter = web3::FilterBuilder::default()
+            .from_block(web3::BlockNumber::Number(1.into()))
+            .to_block(web3::BlockNumber::Number(1.into()))
+            .build();
+        let logs = client.as_ref().logs(&filter).await.unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].topics, [topics[1]]);
+
+        let filter = web3::FilterBuilder::default()
+            .address(vec![Address::repeat_byte(2)])
+            .build();
+        let logs = client.as_ref().logs(&filter).await.unwrap();
+        assert!(logs.is_empty());
+    }
+
     #[tokio::test]
     async fn getting_transaction_failure_reason() {
         let client = MockSettlementLayer::<L1>::default();
@@ -124,7 +124,7 @@ impl MempoolStore {
 
     /// Returns `true` if there is a transaction in the mempool satisfying the filter.
     pub fn has_next(&self, filter: &L2TxFilter) -> bool {
-        self.l1_transactions.contains_key(&self.next_priority_id)
+        self.has_next_l1()
             || self
                 .l2_priority_queue
                 .iter()
@@ -132,11 +132,22 @@ impl MempoolStore {
                 .is_some()
     }
 
+    /// Returns `true` if there is a priority (L1) transaction in the mempool ready for execution.
+    pub fn has_next_l1(&self) -> bool {
+        self.l1_transactions.contains_key(&self.next_priority_id)
+    }
+
+    /// Returns next priority (L1) transaction for execution from mempool. L2 transactions are left intact.
+    pub fn next_l1_transaction(&mut self) -> Option<Transaction> {
+        let transaction = self.l1_transactions.remove(&self.next_priority_id)?;
+        self.next_priority_id += 1;
+        Some(transaction.into())
+    }
+
     /// Returns next transaction for execution from mempool
     pub fn next_transaction(&mut self, filter: &L2TxFilter) -> Option<Transaction> {
-        if let Some(transaction) = self.l1_transactions.remove(&self.next_priority_id) {
-            self.next_priority_id += 1;
-            return Some(transaction.into());
+        if let Some(transaction) = self.next_l1_transaction() {
+            return Some(transaction);
         }
 
         let mut removed = 0;
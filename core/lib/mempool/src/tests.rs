@@ -117,6 +117,26 @@ fn prioritize_l1_txns() {
         .is_l1())
 }
 
+#[test]
+fn getting_only_l1_txns() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    let account = Address::random();
+    mempool.insert(vec![gen_l2_tx(account, Nonce(0))], HashMap::new());
+    assert!(!mempool.has_next_l1());
+    assert!(mempool.next_l1_transaction().is_none());
+
+    mempool.insert(vec![gen_l1_tx(PriorityOpId(0))], HashMap::new());
+    assert!(mempool.has_next_l1());
+    assert!(mempool.next_l1_transaction().unwrap().is_l1());
+    assert!(!mempool.has_next_l1());
+    assert!(mempool.next_l1_transaction().is_none());
+    // The L2 transaction must be left in the mempool.
+    assert_eq!(
+        view(mempool.next_transaction(&L2TxFilter::default())),
+        (account, 0)
+    );
+}
+
 #[test]
 fn l1_txns_priority_id() {
     let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
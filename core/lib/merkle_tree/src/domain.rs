@@ -7,10 +7,10 @@ use zksync_types::{L1BatchNumber, StorageKey};
 
 use crate::{
     consistency::ConsistencyError,
-    storage::{PatchSet, Patched, RocksDBWrapper},
+    storage::{PatchSet, Patched, PruneDatabase, RocksDBWrapper},
     types::{
-        Key, Root, TreeEntry, TreeEntryWithProof, TreeInstruction, TreeLogEntry, ValueHash,
-        TREE_DEPTH,
+        Key, Root, TreeEntry, TreeEntryWithProof, TreeInstruction, TreeLogEntry, TreeMultiProof,
+        ValueHash, TREE_DEPTH,
     },
     BlockOutput, HashTree, MerkleTree, MerkleTreePruner, MerkleTreePrunerHandle, NoVersionError,
 };
@@ -50,11 +50,13 @@ enum TreeMode {
 ///
 /// This wrapper will accumulate changes introduced by [`Self::process_l1_batch()`],
 /// [`Self::process_l1_batches()`] and [`Self::revert_logs()`] in RAM without saving them
-/// to RocksDB. The accumulated changes can be saved to RocksDB via [`Self::save()`]
+/// to the database. The accumulated changes can be saved to the database via [`Self::save()`]
 /// or discarded via [`Self::reset()`].
+///
+/// By default, the tree is backed by RocksDB; other databases can be used via [`Self::with_database()`].
 #[derive(Debug)]
-pub struct ZkSyncTree {
-    tree: MerkleTree<Patched<RocksDBWrapper>>,
+pub struct ZkSyncTree<DB = RocksDBWrapper> {
+    tree: MerkleTree<Patched<DB>>,
     thread_pool: Option<ThreadPool>,
     mode: TreeMode,
     pruning_enabled: bool,
@@ -66,14 +68,6 @@ impl ZkSyncTree {
         Blake2Hasher.empty_tree_hash()
     }
 
-    fn create_thread_pool(thread_count: usize) -> ThreadPool {
-        ThreadPoolBuilder::new()
-            .thread_name(|idx| format!("new-merkle-tree-{idx}"))
-            .num_threads(thread_count)
-            .build()
-            .expect("failed initializing `rayon` thread pool")
-    }
-
     /// Returns metadata based on `storage_logs` generated by the genesis L1 batch. This does not
     /// create a persistent tree.
     #[allow(clippy::missing_panics_doc)] // false positive
@@ -114,7 +108,47 @@ impl ZkSyncTree {
         Self::new_with_mode(db, TreeMode::Lightweight)
     }
 
-    fn new_with_mode(db: RocksDBWrapper, mode: TreeMode) -> anyhow::Result<Self> {
+    /// Sets the chunk size for multi-get operations. The requested keys will be split
+    /// into chunks of this size and requested in parallel using `rayon`. Setting chunk size
+    /// to a large value (e.g., `usize::MAX`) will effectively disable parallelism.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero.
+    pub fn set_multi_get_chunk_size(&mut self, chunk_size: usize) {
+        assert!(chunk_size > 0, "Multi-get chunk size must be positive");
+        self.tree
+            .db
+            .inner_mut()
+            .set_multi_get_chunk_size(chunk_size);
+    }
+}
+
+impl<DB: PruneDatabase + Clone> ZkSyncTree<DB> {
+    fn create_thread_pool(thread_count: usize) -> ThreadPool {
+        ThreadPoolBuilder::new()
+            .thread_name(|idx| format!("new-merkle-tree-{idx}"))
+            .num_threads(thread_count)
+            .build()
+            .expect("failed initializing `rayon` thread pool")
+    }
+
+    /// Creates a tree backed by an arbitrary database. If `lightweight` is set, the tree will use
+    /// the lightweight processing mode; otherwise, the full mode.
+    ///
+    /// # Errors
+    ///
+    /// Errors if sanity checks fail.
+    pub fn with_database(db: DB, lightweight: bool) -> anyhow::Result<Self> {
+        let mode = if lightweight {
+            TreeMode::Lightweight
+        } else {
+            TreeMode::Full
+        };
+        Self::new_with_mode(db, mode)
+    }
+
+    fn new_with_mode(db: DB, mode: TreeMode) -> anyhow::Result<Self> {
         Ok(Self {
             tree: MerkleTree::new(Patched::new(db))?,
             thread_pool: None,
@@ -129,7 +163,7 @@ impl ZkSyncTree {
     ///
     /// Panics if this method was already called for the tree instance; it's logically unsound to run
     /// multiple pruners for the same tree concurrently.
-    pub fn pruner(&mut self) -> (MerkleTreePruner<RocksDBWrapper>, MerkleTreePrunerHandle) {
+    pub fn pruner(&mut self) -> (MerkleTreePruner<DB>, MerkleTreePrunerHandle) {
         assert!(
             !self.pruning_enabled,
             "pruner was already obtained for the tree"
@@ -140,27 +174,12 @@ impl ZkSyncTree {
     }
 
     /// Returns a readonly handle to the tree. The handle **does not** see uncommitted changes to the tree,
-    /// only ones flushed to RocksDB.
-    pub fn reader(&self) -> ZkSyncTreeReader {
+    /// only ones flushed to the database.
+    pub fn reader(&self) -> ZkSyncTreeReader<DB> {
         let db = self.tree.db.inner().clone();
         ZkSyncTreeReader(MerkleTree::new_unchecked(db))
     }
 
-    /// Sets the chunk size for multi-get operations. The requested keys will be split
-    /// into chunks of this size and requested in parallel using `rayon`. Setting chunk size
-    /// to a large value (e.g., `usize::MAX`) will effectively disable parallelism.
-    ///
-    /// # Panics
-    ///
-    /// Panics if `chunk_size` is zero.
-    pub fn set_multi_get_chunk_size(&mut self, chunk_size: usize) {
-        assert!(chunk_size > 0, "Multi-get chunk size must be positive");
-        self.tree
-            .db
-            .inner_mut()
-            .set_multi_get_chunk_size(chunk_size);
-    }
-
     /// Signals that the tree should use a dedicated `rayon` thread pool for parallel operations
     /// (for now, hash computations).
     ///
@@ -362,7 +381,7 @@ impl ZkSyncTree {
         self.tree.truncate_recent_versions(retained_version_count)
     }
 
-    /// Saves the accumulated changes in the tree to RocksDB.
+    /// Saves the accumulated changes in the tree to the database.
     ///
     /// # Errors
     ///
@@ -370,7 +389,7 @@ impl ZkSyncTree {
     pub fn save(&mut self) -> anyhow::Result<()> {
         let mut l1_batch_numbers = self.tree.db.patched_versions();
         l1_batch_numbers.sort_unstable();
-        tracing::info!("Flushing L1 batches #{l1_batch_numbers:?} to RocksDB");
+        tracing::info!("Flushing L1 batches #{l1_batch_numbers:?} to the database");
         self.tree.db.flush()
     }
 
@@ -382,27 +401,38 @@ impl ZkSyncTree {
 
 /// Readonly handle to a [`ZkSyncTree`].
 #[derive(Debug)]
-pub struct ZkSyncTreeReader(MerkleTree<RocksDBWrapper>);
+pub struct ZkSyncTreeReader<DB = RocksDBWrapper>(MerkleTree<DB>);
 
 // While cloning `MerkleTree` is logically unsound, cloning a reader is reasonable since it is readonly.
-impl Clone for ZkSyncTreeReader {
+impl<DB: PruneDatabase + Clone> Clone for ZkSyncTreeReader<DB> {
     fn clone(&self) -> Self {
         Self(MerkleTree::new_unchecked(self.0.db.clone()))
     }
 }
 
 impl ZkSyncTreeReader {
-    /// Creates a tree reader based on the provided database.
+    /// Creates a tree reader based on the provided RocksDB database.
     ///
     /// # Errors
     ///
     /// Errors if sanity checks fail.
     pub fn new(db: RocksDBWrapper) -> anyhow::Result<Self> {
+        Self::with_database(db)
+    }
+}
+
+impl<DB: PruneDatabase + Clone> ZkSyncTreeReader<DB> {
+    /// Creates a tree reader based on the provided database.
+    ///
+    /// # Errors
+    ///
+    /// Errors if sanity checks fail.
+    pub fn with_database(db: DB) -> anyhow::Result<Self> {
         MerkleTree::new(db).map(Self)
     }
 
     /// Returns a reference to the database this.
-    pub fn db(&self) -> &RocksDBWrapper {
+    pub fn db(&self) -> &DB {
         &self.0.db
     }
 
@@ -444,6 +474,21 @@ impl ZkSyncTreeReader {
         self.0.entries_with_proofs(version, keys)
     }
 
+    /// Reads entries with the specified keys together with a compact multiproof. Entries in the returned proof
+    /// are ordered by key and deduplicated.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree `version` is missing.
+    pub fn entries_with_multiproof(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        keys: &[Key],
+    ) -> Result<TreeMultiProof, NoVersionError> {
+        let version = u64::from(l1_batch_number.0);
+        self.0.entries_with_multiproof(version, keys)
+    }
+
     /// Verifies consistency of the tree at the specified L1 batch number.
     ///
     /// # Errors
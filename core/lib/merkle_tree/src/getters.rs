@@ -4,7 +4,7 @@ use crate::{
     hasher::HasherWithStats,
     recovery::MerkleTreeRecovery,
     storage::{LoadAncestorsResult, SortedKeys, WorkingPatchSet},
-    types::{Nibbles, Node, ProfiledTreeOperation, TreeEntry, TreeEntryWithProof},
+    types::{Nibbles, Node, ProfiledTreeOperation, TreeEntry, TreeEntryWithProof, TreeMultiProof},
     Database, HashTree, Key, MerkleTree, NoVersionError, PruneDatabase, ValueHash,
 };
 
@@ -59,6 +59,22 @@ impl<DB: Database, H: HashTree> MerkleTree<DB, H> {
             },
         )
     }
+
+    /// Reads entries with the specified keys together with a compact [multiproof](TreeMultiProof).
+    /// Unlike with [`Self::entries_with_proofs()`], entries in the returned proof are ordered by key
+    /// and deduplicated.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree `version` is missing.
+    pub fn entries_with_multiproof(
+        &self,
+        version: u64,
+        leaf_keys: &[Key],
+    ) -> Result<TreeMultiProof, NoVersionError> {
+        self.entries_with_proofs(version, leaf_keys)
+            .map(TreeMultiProof::new)
+    }
 }
 
 fn load_and_transform_entries<T>(
@@ -2,13 +2,13 @@
 
 use std::mem;
 
-use anyhow::ensure;
+use anyhow::{ensure, Context as _};
 
 use crate::{
     hasher::{HashTree, HasherWithStats},
     types::{
         BlockOutputWithProofs, Key, LeafNode, TreeEntry, TreeEntryWithProof, TreeInstruction,
-        TreeLogEntry, ValueHash, TREE_DEPTH,
+        TreeLogEntry, TreeMultiProof, ValueHash, TREE_DEPTH,
     },
     utils,
 };
@@ -104,6 +104,198 @@ impl TreeEntryWithProof {
     }
 }
 
+/// Node of a multiproof on a certain tree level.
+#[derive(Debug)]
+struct MultiProofNode<T> {
+    /// Index of the node on its level, i.e., the key of any entry in the node subtree shifted right by the level.
+    index: Key,
+    /// Number of bottom-most tree levels for which the sibling subtrees of the node are empty.
+    empty_levels: usize,
+    data: T,
+}
+
+/// Folds multiproof nodes (ordered by index) on the specified `level` into nodes on the next level. `merge` is called
+/// for sibling nodes that are both present in the multiproof; `fold_with_sibling` is called for other nodes
+/// with two flags: whether the node is the right child of its parent, and whether the sibling subtree is empty.
+fn fold_multiproof_level<T>(
+    nodes: Vec<MultiProofNode<T>>,
+    level: usize,
+    mut merge: impl FnMut(MultiProofNode<T>, MultiProofNode<T>) -> T,
+    mut fold_with_sibling: impl FnMut(T, bool, bool) -> anyhow::Result<T>,
+) -> anyhow::Result<Vec<MultiProofNode<T>>> {
+    let mut folded = Vec::with_capacity(nodes.len());
+    let mut nodes = nodes.into_iter().peekable();
+    while let Some(node) = nodes.next() {
+        let index = node.index >> 1;
+        let is_right = node.index.bit(0);
+        let sibling = if is_right {
+            None
+        } else {
+            nodes.next_if(|next| next.index == node.index + 1)
+        };
+
+        let (data, empty_levels) = if let Some(sibling) = sibling {
+            let empty_levels = node.empty_levels.min(sibling.empty_levels);
+            (merge(node, sibling), empty_levels)
+        } else {
+            let sibling_is_empty = level < node.empty_levels;
+            let empty_levels = node.empty_levels;
+            (
+                fold_with_sibling(node.data, is_right, sibling_is_empty)?,
+                empty_levels,
+            )
+        };
+        folded.push(MultiProofNode {
+            index,
+            empty_levels,
+            data,
+        });
+    }
+    Ok(folded)
+}
+
+impl TreeMultiProof {
+    /// Compacts proofs for separate entries into a multiproof. Proofs for duplicate keys are deduplicated.
+    /// All proofs must be obtained for the same tree version.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the proofs are inconsistent, e.g. if a Merkle path is longer than the tree depth.
+    pub fn new(mut proofs: Vec<TreeEntryWithProof>) -> Self {
+        proofs.sort_unstable_by_key(|proof| proof.base.key);
+        proofs.dedup_by_key(|proof| proof.base.key);
+
+        let mut nodes = Vec::with_capacity(proofs.len());
+        let mut path_lengths = Vec::with_capacity(proofs.len());
+        for (i, proof) in proofs.iter().enumerate() {
+            let path_length = proof.merkle_path.len();
+            assert!(
+                path_length <= TREE_DEPTH,
+                "Merkle path for key {:0>64x} is too long",
+                proof.base.key
+            );
+            path_lengths.push(u16::try_from(path_length).unwrap());
+            // ^ `unwrap()` is safe due to the check above
+            nodes.push(MultiProofNode {
+                index: proof.base.key,
+                empty_levels: TREE_DEPTH - path_length,
+                data: i,
+            });
+        }
+
+        let mut hashes = vec![];
+        for level in 0..TREE_DEPTH {
+            let merge = |lhs: MultiProofNode<usize>, rhs: MultiProofNode<usize>| {
+                // Use the proof with the longest Merkle path so that it covers all remaining levels.
+                if lhs.empty_levels <= rhs.empty_levels {
+                    lhs.data
+                } else {
+                    rhs.data
+                }
+            };
+            let fold_with_sibling =
+                |i: usize, _: bool, sibling_is_empty: bool| -> anyhow::Result<_> {
+                    if !sibling_is_empty {
+                        let merkle_path = &proofs[i].merkle_path;
+                        let path_index = (level + merkle_path.len())
+                            .checked_sub(TREE_DEPTH)
+                            .context("inconsistent Merkle paths")?;
+                        hashes.push(merkle_path[path_index]);
+                    }
+                    Ok(i)
+                };
+            nodes = fold_multiproof_level(nodes, level, merge, fold_with_sibling)
+                .expect("failed compacting Merkle proofs");
+        }
+
+        Self {
+            entries: proofs.into_iter().map(|proof| proof.base).collect(),
+            path_lengths,
+            hashes,
+        }
+    }
+
+    /// Verifies this multiproof.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error <=> proof is invalid.
+    pub fn verify(
+        &self,
+        hasher: &dyn HashTree,
+        trusted_root_hash: ValueHash,
+    ) -> anyhow::Result<()> {
+        ensure!(!self.entries.is_empty(), "Multiproof contains no entries");
+        ensure!(
+            self.entries.len() == self.path_lengths.len(),
+            "Mismatch between number of entries ({}) and Merkle path lengths ({})",
+            self.entries.len(),
+            self.path_lengths.len()
+        );
+        let are_keys_sorted = self
+            .entries
+            .windows(2)
+            .all(|window| window[0].key < window[1].key);
+        ensure!(
+            are_keys_sorted,
+            "Entries are not sorted by key or contain duplicate keys"
+        );
+
+        let mut nodes = Vec::with_capacity(self.entries.len());
+        for (entry, &path_length) in self.entries.iter().zip(&self.path_lengths) {
+            let path_length = usize::from(path_length);
+            ensure!(
+                path_length <= TREE_DEPTH,
+                "Merkle path for key {:0>64x} is too long",
+                entry.key
+            );
+            if entry.leaf_index == 0 {
+                ensure!(
+                    entry.value.is_zero(),
+                    "Invalid missing value specification: leaf index is zero, but value is non-default"
+                );
+            }
+            nodes.push(MultiProofNode {
+                index: entry.key,
+                empty_levels: TREE_DEPTH - path_length,
+                data: hasher.hash_leaf(&entry.value, entry.leaf_index),
+            });
+        }
+
+        let mut hashes = self.hashes.iter();
+        for level in 0..TREE_DEPTH {
+            let merge = |lhs: MultiProofNode<ValueHash>, rhs: MultiProofNode<ValueHash>| {
+                hasher.hash_branch(&lhs.data, &rhs.data)
+            };
+            let fold_with_sibling =
+                |hash: ValueHash, is_right: bool, sibling_is_empty: bool| -> anyhow::Result<_> {
+                    let sibling_hash = if sibling_is_empty {
+                        hasher.empty_subtree_hash(level)
+                    } else {
+                        *hashes
+                            .next()
+                            .context("Multiproof contains too few hashes")?
+                    };
+                    Ok(if is_right {
+                        hasher.hash_branch(&sibling_hash, &hash)
+                    } else {
+                        hasher.hash_branch(&hash, &sibling_hash)
+                    })
+                };
+            nodes = fold_multiproof_level(nodes, level, merge, fold_with_sibling)?;
+        }
+        ensure!(hashes.next().is_none(), "Multiproof contains extra hashes");
+
+        // By construction, `nodes` contains a single root node at this point.
+        let root_hash = nodes[0].data;
+        ensure!(
+            root_hash == trusted_root_hash,
+            "Root hash mismatch: got {root_hash}, want {trusted_root_hash}"
+        );
+        Ok(())
+    }
+}
+
 /// Range digest in a Merkle tree allowing to compute its root hash based on the provided entries.
 ///
 /// - The entries must be ordered by key. I.e., the first entry must have the numerically smallest key,
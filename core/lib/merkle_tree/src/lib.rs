@@ -53,12 +53,12 @@ pub use crate::{
     hasher::{HashTree, TreeRangeDigest},
     pruning::{MerkleTreePruner, MerkleTreePrunerHandle},
     storage::{
-        Database, MerkleTreeColumnFamily, PatchSet, Patched, PruneDatabase, PrunePatchSet,
-        RocksDBWrapper,
+        Database, InMemoryDatabase, InMemoryStorage, KeyValueDatabase, KeyValueOp, KeyValueStorage,
+        MerkleTreeColumnFamily, PatchSet, Patched, PruneDatabase, PrunePatchSet, RocksDBWrapper,
     },
     types::{
         BlockOutput, BlockOutputWithProofs, Key, TreeEntry, TreeEntryWithProof, TreeInstruction,
-        TreeLogEntry, TreeLogEntryWithProof, ValueHash,
+        TreeLogEntry, TreeLogEntryWithProof, TreeMultiProof, ValueHash,
     },
 };
 use crate::{storage::Storage, types::Root};
@@ -80,8 +80,11 @@ mod utils;
 /// these types will remain stable.
 #[doc(hidden)]
 pub mod unstable {
+    /// Test scenarios allowing to check custom [`Database`](crate::Database) implementations.
+    pub use crate::storage::testonly;
     pub use crate::{
         errors::DeserializeError,
+        storage::NodeKeys,
         types::{Manifest, Node, NodeKey, ProfiledTreeOperation, Root},
     };
 }
@@ -456,7 +456,8 @@ mod tests {
     use super::*;
     use crate::{
         storage::{
-            tests::{create_patch, generate_nodes, FIRST_KEY},
+            testonly::FIRST_KEY,
+            tests::{create_patch, generate_nodes},
             Operation,
         },
         types::{InternalNode, Nibbles},
@@ -0,0 +1,254 @@
+//! In-memory [`KeyValueStorage`] with optional spilling of values to disk.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    io::{self, Read, Seek, SeekFrom, Write},
+    ops,
+    path::{Path, PathBuf},
+    process,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+};
+
+use anyhow::Context as _;
+
+use crate::storage::{
+    key_value::{KeyValueDatabase, KeyValueOp, KeyValueStorage},
+    RocksDBWrapper,
+};
+
+/// Merkle tree database keeping all data in memory (with optional spilling to disk).
+pub type InMemoryDatabase = KeyValueDatabase<InMemoryStorage>;
+
+impl Default for InMemoryDatabase {
+    fn default() -> Self {
+        Self::new(InMemoryStorage::default())
+    }
+}
+
+#[derive(Debug)]
+enum StoredValue {
+    InMemory(Vec<u8>),
+    Spilled { offset: u64, len: usize },
+}
+
+/// Append-only file used to store spilled node values. The file is removed on drop.
+#[derive(Debug)]
+struct SpillFile {
+    path: PathBuf,
+    file: Mutex<fs::File>,
+    len: u64,
+}
+
+impl SpillFile {
+    fn new(dir: &Path) -> io::Result<Self> {
+        static FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+        let idx = FILE_COUNTER.fetch_add(1, Ordering::Relaxed);
+        let path = dir.join(format!("merkle_tree_spill_{}_{idx}.bin", process::id()));
+        let file = fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+            len: 0,
+        })
+    }
+
+    fn append(&mut self, value: &[u8]) -> io::Result<u64> {
+        let offset = self.len;
+        let file = self.file.get_mut().expect("spill file is poisoned");
+        file.seek(SeekFrom::Start(offset))?;
+        file.write_all(value)?;
+        self.len += value.len() as u64;
+        Ok(offset)
+    }
+
+    fn read(&self, offset: u64, len: usize) -> io::Result<Vec<u8>> {
+        let mut file = self.file.lock().expect("spill file is poisoned");
+        file.seek(SeekFrom::Start(offset))?;
+        let mut buffer = vec![0_u8; len];
+        file.read_exact(&mut buffer)?;
+        Ok(buffer)
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        if let Err(err) = fs::remove_file(&self.path) {
+            tracing::warn!(
+                "Failed removing Merkle tree spill file `{}`: {err}",
+                self.path.display()
+            );
+        }
+    }
+}
+
+/// In-memory [`KeyValueStorage`] implementation.
+///
+/// By default, all data is kept in memory. If created using [`Self::with_spill_to_disk()`], memory used by node values
+/// is bounded: once it exceeds the limit, values of the oldest nodes (i.e., nodes with the smallest versions)
+/// are moved to an append-only file until the memory usage drops to a half of the limit. Node keys and stale keys
+/// are always kept in memory. The spill file is never compacted; it is removed when the storage is dropped.
+#[derive(Debug, Default)]
+pub struct InMemoryStorage {
+    nodes: BTreeMap<Vec<u8>, StoredValue>,
+    stale_keys: BTreeMap<u64, BTreeSet<Vec<u8>>>,
+    memory_limit: Option<usize>,
+    /// Total size of node values stored in memory.
+    memory_usage: usize,
+    spill_file: Option<SpillFile>,
+}
+
+impl InMemoryStorage {
+    /// Creates storage that spills node values to a file in the specified directory once memory used by values
+    /// exceeds `memory_limit` bytes.
+    ///
+    /// # Errors
+    ///
+    /// Propagates I/O errors creating the spill file.
+    pub fn with_spill_to_disk(dir: &Path, memory_limit: usize) -> io::Result<Self> {
+        Ok(Self {
+            memory_limit: Some(memory_limit),
+            spill_file: Some(SpillFile::new(dir)?),
+            ..Self::default()
+        })
+    }
+
+    /// Returns the total size of node values currently kept in memory.
+    pub fn memory_usage(&self) -> usize {
+        self.memory_usage
+    }
+
+    /// Returns the number of stored nodes (including the tree manifest).
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns the number of nodes with values spilled to disk.
+    pub fn spilled_node_count(&self) -> usize {
+        self.nodes
+            .values()
+            .filter(|value| matches!(value, StoredValue::Spilled { .. }))
+            .count()
+    }
+
+    fn remove_node(&mut self, key: &[u8]) {
+        if let Some(StoredValue::InMemory(value)) = self.nodes.remove(key) {
+            self.memory_usage -= value.len();
+        }
+    }
+
+    fn remove_node_range(&mut self, range: ops::Range<Vec<u8>>) {
+        let removed_keys: Vec<_> = self
+            .nodes
+            .range(range)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in removed_keys {
+            self.remove_node(&key);
+        }
+    }
+
+    fn spill_if_necessary(&mut self) -> io::Result<()> {
+        let (Some(memory_limit), Some(spill_file)) = (self.memory_limit, &mut self.spill_file)
+        else {
+            return Ok(());
+        };
+        if self.memory_usage <= memory_limit {
+            return Ok(());
+        }
+
+        let target_usage = memory_limit / 2;
+        // Node keys start with the big-endian tree version, so iterating in key order spills the oldest nodes first.
+        for (key, stored) in &mut self.nodes {
+            if self.memory_usage <= target_usage {
+                break;
+            }
+            if key.as_slice() == RocksDBWrapper::MANIFEST_KEY {
+                continue; // The manifest is accessed on each tree operation, so it's never spilled
+            }
+            if let StoredValue::InMemory(value) = stored {
+                let len = value.len();
+                let offset = spill_file.append(value)?;
+                *stored = StoredValue::Spilled { offset, len };
+                self.memory_usage -= len;
+            }
+        }
+        tracing::debug!(
+            "Spilled Merkle tree nodes to disk; memory usage: {}B, spill file size: {}B",
+            self.memory_usage,
+            spill_file.len
+        );
+        Ok(())
+    }
+}
+
+impl KeyValueStorage for InMemoryStorage {
+    fn node(&self, key: &[u8]) -> anyhow::Result<Option<Vec<u8>>> {
+        Ok(match self.nodes.get(key) {
+            None => None,
+            Some(StoredValue::InMemory(value)) => Some(value.clone()),
+            Some(&StoredValue::Spilled { offset, len }) => {
+                let spill_file = self
+                    .spill_file
+                    .as_ref()
+                    .context("spilled node without spill file")?;
+                let value = spill_file
+                    .read(offset, len)
+                    .context("failed reading node from spill file")?;
+                Some(value)
+            }
+        })
+    }
+
+    fn min_stale_key_version(&self) -> anyhow::Result<Option<u64>> {
+        Ok(self.stale_keys.keys().next().copied())
+    }
+
+    fn stale_keys(&self, replaced_in_version: u64) -> anyhow::Result<Vec<Vec<u8>>> {
+        let keys = self.stale_keys.get(&replaced_in_version);
+        Ok(keys.into_iter().flatten().cloned().collect())
+    }
+
+    fn write(&mut self, ops: Vec<KeyValueOp>) -> anyhow::Result<()> {
+        for op in ops {
+            match op {
+                KeyValueOp::PutNode { key, value } => {
+                    self.memory_usage += value.len();
+                    if let Some(StoredValue::InMemory(prev_value)) =
+                        self.nodes.insert(key, StoredValue::InMemory(value))
+                    {
+                        self.memory_usage -= prev_value.len();
+                    }
+                }
+                KeyValueOp::DeleteNode { key } => self.remove_node(&key),
+                KeyValueOp::DeleteNodeRange { start, end } => self.remove_node_range(start..end),
+                KeyValueOp::PutStaleKey {
+                    replaced_in_version,
+                    key,
+                } => {
+                    self.stale_keys
+                        .entry(replaced_in_version)
+                        .or_default()
+                        .insert(key);
+                }
+                KeyValueOp::DeleteStaleKeys { versions } => {
+                    let retained = self.stale_keys.split_off(&versions.end);
+                    self.stale_keys
+                        .retain(|version, _| *version < versions.start);
+                    self.stale_keys.extend(retained);
+                }
+            }
+        }
+        // Spilling doesn't change the logical storage contents, so an error here doesn't break atomicity.
+        self.spill_if_necessary()
+            .context("failed spilling Merkle tree nodes to disk")
+    }
+}
@@ -0,0 +1,278 @@
+//! Generic [`Database`] implementation on top of a key-value storage.
+
+use std::{any::Any, ops};
+
+use crate::{
+    errors::{DeserializeError, ErrorContext},
+    metrics::ApplyPatchStats,
+    storage::{
+        database::{PruneDatabase, PrunePatchSet},
+        Database, NodeKeys, PatchSet, RocksDBWrapper,
+    },
+    types::{Manifest, Nibbles, Node, NodeKey, ProfiledTreeOperation, Root},
+};
+
+/// Write operation for a [`KeyValueStorage`].
+///
+/// Node keys and values are opaque byte sequences; node keys are ordered by the tree version
+/// (i.e., the first 8 bytes of a key are the big-endian tree version), so that all nodes for a certain version
+/// form a contiguous key range. The manifest is stored as a node with a special key ordered before all other keys.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyValueOp {
+    /// Inserts or replaces a node.
+    PutNode { key: Vec<u8>, value: Vec<u8> },
+    /// Removes a node. No-op if the node is not present.
+    DeleteNode { key: Vec<u8> },
+    /// Removes all nodes in the specified key range (the start is inclusive, the end is exclusive).
+    DeleteNodeRange { start: Vec<u8>, end: Vec<u8> },
+    /// Inserts a stale node key replaced in the specified tree version.
+    PutStaleKey {
+        replaced_in_version: u64,
+        key: Vec<u8>,
+    },
+    /// Removes all stale keys replaced in the specified range of tree versions.
+    DeleteStaleKeys { versions: ops::Range<u64> },
+}
+
+/// Key-value storage that can be used as a Merkle tree [`Database`] via [`KeyValueDatabase`].
+///
+/// Keys and values have the same format as in [`RocksDBWrapper`]. I/O errors when reading from the storage
+/// are considered fatal and lead to a panic (this is consistent with `RocksDBWrapper`).
+pub trait KeyValueStorage: Send + Sync {
+    /// Returns the value for the node with the specified key.
+    ///
+    /// # Errors
+    ///
+    /// Should propagate I/O errors.
+    fn node(&self, key: &[u8]) -> anyhow::Result<Option<Vec<u8>>>;
+
+    /// Returns values for nodes with the specified keys in the same order as the keys.
+    ///
+    /// # Errors
+    ///
+    /// Should propagate I/O errors.
+    fn nodes(&self, keys: &[Vec<u8>]) -> anyhow::Result<Vec<Option<Vec<u8>>>> {
+        keys.iter().map(|key| self.node(key)).collect()
+    }
+
+    /// Returns the minimum tree version in stale keys, or `None` if there are no stale keys.
+    ///
+    /// # Errors
+    ///
+    /// Should propagate I/O errors.
+    fn min_stale_key_version(&self) -> anyhow::Result<Option<u64>>;
+
+    /// Returns stale node keys replaced in the specified tree version.
+    ///
+    /// # Errors
+    ///
+    /// Should propagate I/O errors.
+    fn stale_keys(&self, replaced_in_version: u64) -> anyhow::Result<Vec<Vec<u8>>>;
+
+    /// Atomically applies the provided operations in order.
+    ///
+    /// # Errors
+    ///
+    /// Should propagate I/O errors.
+    fn write(&mut self, ops: Vec<KeyValueOp>) -> anyhow::Result<()>;
+}
+
+impl<S: KeyValueStorage + ?Sized> KeyValueStorage for Box<S> {
+    fn node(&self, key: &[u8]) -> anyhow::Result<Option<Vec<u8>>> {
+        (**self).node(key)
+    }
+
+    fn nodes(&self, keys: &[Vec<u8>]) -> anyhow::Result<Vec<Option<Vec<u8>>>> {
+        (**self).nodes(keys)
+    }
+
+    fn min_stale_key_version(&self) -> anyhow::Result<Option<u64>> {
+        (**self).min_stale_key_version()
+    }
+
+    fn stale_keys(&self, replaced_in_version: u64) -> anyhow::Result<Vec<Vec<u8>>> {
+        (**self).stale_keys(replaced_in_version)
+    }
+
+    fn write(&mut self, ops: Vec<KeyValueOp>) -> anyhow::Result<()> {
+        (**self).write(ops)
+    }
+}
+
+/// [`Database`] and [`PruneDatabase`] implementation on top of a [`KeyValueStorage`].
+#[derive(Debug, Clone)]
+pub struct KeyValueDatabase<S> {
+    storage: S,
+}
+
+impl<S: KeyValueStorage> KeyValueDatabase<S> {
+    /// Wraps the provided storage.
+    pub fn new(storage: S) -> Self {
+        Self { storage }
+    }
+
+    /// Returns a reference to the wrapped storage.
+    pub fn storage(&self) -> &S {
+        &self.storage
+    }
+
+    /// Returns the wrapped storage.
+    pub fn into_inner(self) -> S {
+        self.storage
+    }
+
+    fn raw_node(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.storage
+            .node(key)
+            .expect("Failed reading from key-value storage")
+    }
+}
+
+impl<S: KeyValueStorage> Database for KeyValueDatabase<S> {
+    fn try_manifest(&self) -> Result<Option<Manifest>, DeserializeError> {
+        let Some(raw_manifest) = self.raw_node(RocksDBWrapper::MANIFEST_KEY) else {
+            return Ok(None);
+        };
+        Manifest::deserialize(&raw_manifest)
+            .map(Some)
+            .map_err(|err| err.with_context(ErrorContext::Manifest))
+    }
+
+    fn try_root(&self, version: u64) -> Result<Option<Root>, DeserializeError> {
+        let Some(raw_root) = self.raw_node(&NodeKey::empty(version).to_db_key()) else {
+            return Ok(None);
+        };
+        Root::deserialize(&raw_root)
+            .map(Some)
+            .map_err(|err| err.with_context(ErrorContext::Root(version)))
+    }
+
+    fn try_tree_node(
+        &self,
+        key: &NodeKey,
+        is_leaf: bool,
+    ) -> Result<Option<Node>, DeserializeError> {
+        let Some(raw_node) = self.raw_node(&key.to_db_key()) else {
+            return Ok(None);
+        };
+        RocksDBWrapper::deserialize_node(&raw_node, key, is_leaf).map(Some)
+    }
+
+    fn tree_nodes(&self, keys: &NodeKeys) -> Vec<Option<Node>> {
+        let raw_keys: Vec<_> = keys.iter().map(|(key, _)| key.to_db_key()).collect();
+        let raw_nodes = self
+            .storage
+            .nodes(&raw_keys)
+            .expect("Failed reading from key-value storage");
+        assert_eq!(
+            raw_nodes.len(),
+            keys.len(),
+            "Storage returned unexpected number of nodes"
+        );
+
+        let nodes = raw_nodes
+            .into_iter()
+            .zip(keys)
+            .map(|(maybe_node, (key, is_leaf))| {
+                maybe_node
+                    .map(|raw_node| RocksDBWrapper::deserialize_node(&raw_node, key, *is_leaf))
+                    .transpose()
+            });
+        nodes
+            .collect::<Result<_, _>>()
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    fn start_profiling(&self, _operation: ProfiledTreeOperation) -> Box<dyn Any> {
+        Box::new(()) // no stats are collected
+    }
+
+    #[allow(clippy::missing_errors_doc)] // this is a trait implementation method
+    fn apply_patch(&mut self, patch: PatchSet) -> anyhow::Result<()> {
+        let mut metrics = ApplyPatchStats::new(patch.copied_hashes_count());
+        let mut ops = vec![];
+
+        let mut manifest_bytes = vec![];
+        patch.manifest.serialize(&mut manifest_bytes);
+        ops.push(KeyValueOp::PutNode {
+            key: RocksDBWrapper::MANIFEST_KEY.to_vec(),
+            value: manifest_bytes,
+        });
+
+        for (version, sub_patch) in patch.patches_by_version {
+            let is_update = patch.updated_version == Some(version);
+            let root_key = NodeKey::empty(version);
+            if !is_update {
+                // Delete the key range corresponding to the entire new version. This removes
+                // potential garbage left after reverting the tree to a previous version.
+                ops.push(KeyValueOp::DeleteNodeRange {
+                    start: root_key.to_db_key(),
+                    end: NodeKey::empty(version + 1).to_db_key(),
+                });
+            }
+
+            if let Some(root) = sub_patch.root {
+                let mut node_bytes = vec![];
+                root.serialize(&mut node_bytes);
+                metrics.update_node_bytes(&Nibbles::EMPTY, &node_bytes);
+                ops.push(KeyValueOp::PutNode {
+                    key: root_key.to_db_key(),
+                    value: node_bytes,
+                });
+            }
+            for (node_key, node) in sub_patch.nodes {
+                let mut node_bytes = Vec::with_capacity(128);
+                node.serialize(&mut node_bytes);
+                metrics.update_node_bytes(&node_key.nibbles, &node_bytes);
+                ops.push(KeyValueOp::PutNode {
+                    key: node_key.to_db_key(),
+                    value: node_bytes,
+                });
+            }
+        }
+
+        for (version, stale_keys) in patch.stale_keys_by_version {
+            ops.extend(stale_keys.into_iter().map(|key| KeyValueOp::PutStaleKey {
+                replaced_in_version: version,
+                key: key.to_db_key(),
+            }));
+        }
+
+        self.storage.write(ops)?;
+        metrics.report();
+        Ok(())
+    }
+}
+
+impl<S: KeyValueStorage> PruneDatabase for KeyValueDatabase<S> {
+    fn min_stale_key_version(&self) -> Option<u64> {
+        self.storage
+            .min_stale_key_version()
+            .expect("Failed reading from key-value storage")
+    }
+
+    fn stale_keys(&self, version: u64) -> Vec<NodeKey> {
+        let raw_keys = self
+            .storage
+            .stale_keys(version)
+            .expect("Failed reading from key-value storage");
+        raw_keys
+            .iter()
+            .map(|raw_key| NodeKey::from_db_key(raw_key))
+            .collect()
+    }
+
+    fn prune(&mut self, patch: PrunePatchSet) -> anyhow::Result<()> {
+        let mut ops: Vec<_> = patch
+            .pruned_node_keys
+            .into_iter()
+            .map(|key| KeyValueOp::DeleteNode {
+                key: key.to_db_key(),
+            })
+            .collect();
+        ops.push(KeyValueOp::DeleteStaleKeys {
+            versions: patch.deleted_stale_key_versions,
+        });
+        self.storage.write(ops)
+    }
+}
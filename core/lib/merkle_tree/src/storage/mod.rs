@@ -2,6 +2,8 @@
 
 pub use self::{
     database::{Database, NodeKeys, Patched, PruneDatabase, PrunePatchSet},
+    in_memory::{InMemoryDatabase, InMemoryStorage},
+    key_value::{KeyValueDatabase, KeyValueOp, KeyValueStorage},
     parallel::PersistenceThreadHandle,
     patch::PatchSet,
     rocksdb::{MerkleTreeColumnFamily, RocksDBWrapper},
@@ -20,11 +22,14 @@ use crate::{
 };
 
 mod database;
+mod in_memory;
+mod key_value;
 mod parallel;
 mod patch;
 mod proofs;
 mod rocksdb;
 mod serialization;
+pub mod testonly;
 #[cfg(test)]
 mod tests;
 
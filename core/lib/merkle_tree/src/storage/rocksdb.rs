@@ -78,7 +78,7 @@ impl RocksDBWrapper {
     /// Key to store the tree [`Manifest`].
     // This key must not overlap with keys for nodes; easy to see that it's true,
     // since the minimum node key is [0, 0, 0, 0, 0, 0, 0, 0].
-    const MANIFEST_KEY: &'static [u8] = &[0];
+    pub(super) const MANIFEST_KEY: &'static [u8] = &[0];
 
     /// Creates a new wrapper, initializing RocksDB at the specified directory.
     ///
@@ -136,7 +136,7 @@ impl RocksDBWrapper {
             .collect()
     }
 
-    fn deserialize_node(
+    pub(super) fn deserialize_node(
         raw_node: &[u8],
         key: &NodeKey,
         is_leaf: bool,
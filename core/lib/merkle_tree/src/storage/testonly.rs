@@ -0,0 +1,281 @@
+//! Test scenarios shared among [`Database`] implementations. The scenarios are public so that they can be run
+//! for databases defined outside this crate (e.g., one backed by Postgres).
+
+use zksync_types::{H256, U256};
+
+use super::{Database, PruneDatabase, SortedKeys, Storage, TreeUpdater};
+use crate::{
+    types::{
+        Key, Nibbles, Node, Root, TreeEntry, TreeInstruction, TreeLogEntry, ValueHash, KEY_SIZE,
+    },
+    MerkleTree, MerkleTreePruner, PatchSet,
+};
+
+pub(super) const FIRST_KEY: Key = U256([0, 0, 0, 0x_dead_beef_0000_0000]);
+pub(super) const SECOND_KEY: Key = U256([0, 0, 0, 0x_dead_beef_0100_0000]);
+pub(super) const THIRD_KEY: Key = U256([0, 0, 0, 0x_dead_d00d_1234_5678]);
+pub(super) const E_KEY: U256 = U256([0, 0, 0, 0x_e000_0000_0000_0000]);
+
+pub(super) fn assert_storage_with_3_keys(updater: &TreeUpdater) {
+    // The 'dead' internal node should now contain 'b' and 'd' children.
+    let nibbles = Nibbles::new(&FIRST_KEY, 4);
+    let node = updater.patch_set.get(&nibbles).unwrap();
+    let Node::Internal(node) = node else {
+        panic!("Unexpected node at {nibbles}: {node:?}");
+    };
+    assert_eq!(node.child_count(), 2);
+
+    let child_ref = node.child_ref(0xb).unwrap();
+    assert!(!child_ref.is_leaf);
+    let child_ref = node.child_ref(0xd).unwrap();
+    assert!(child_ref.is_leaf);
+
+    let third_leaf_nibbles = Nibbles::new(&THIRD_KEY, 5);
+    let node = updater.patch_set.get(&third_leaf_nibbles).unwrap();
+    let Node::Leaf(leaf) = node else {
+        panic!("Unexpected node at {third_leaf_nibbles}: {node:?}");
+    };
+    assert_eq!(leaf.full_key, THIRD_KEY);
+    assert_eq!(leaf.value_hash, H256([3; 32]));
+}
+
+/// Tests inserting nodes into a non-empty database. `db` must be empty.
+pub fn test_inserting_node_in_non_empty_database<DB: Database + ?Sized>(db: &mut DB) {
+    let storage = Storage::new(&*db, &(), 0, true);
+    let kvs = vec![
+        TreeEntry::new(FIRST_KEY, 1, H256([1; 32])),
+        TreeEntry::new(SECOND_KEY, 2, H256([2; 32])),
+    ];
+    let (_, patch) = storage.extend(kvs);
+    db.apply_patch(patch).unwrap();
+
+    let mut updater = TreeUpdater::new(1, db.root(0).unwrap());
+    let sorted_keys = SortedKeys::new([THIRD_KEY, E_KEY, SECOND_KEY].into_iter());
+    let parent_nibbles = updater.load_ancestors(&sorted_keys, &*db);
+    assert_eq!(updater.metrics.db_reads, 10);
+    assert_eq!(
+        parent_nibbles,
+        [
+            Nibbles::new(&THIRD_KEY, 4), // dead
+            Nibbles::EMPTY,
+            Nibbles::new(&SECOND_KEY, 10), // deadbeef01
+        ]
+    );
+
+    let (op, _) = updater.insert(
+        TreeEntry::new(THIRD_KEY, 3, H256([3; 32])),
+        &parent_nibbles[0],
+    );
+    assert_eq!(op, TreeLogEntry::Inserted);
+    let (op, _) = updater.insert(TreeEntry::new(E_KEY, 4, H256::zero()), &parent_nibbles[1]);
+    assert_eq!(op, TreeLogEntry::Inserted);
+    let (op, _) = updater.insert(
+        TreeEntry::new(SECOND_KEY, 2, H256([2; 32])),
+        &parent_nibbles[2],
+    );
+    assert!(
+        matches!(op, TreeLogEntry::Updated { leaf_index: 2, .. }),
+        "{op:?}"
+    );
+    assert_eq!(updater.metrics.new_internal_nodes, 0);
+    assert_eq!(updater.metrics.new_leaves, 2);
+
+    // Check that all necessary child refs have updated versions.
+    let node = updater.patch_set.get(&Nibbles::EMPTY).unwrap();
+    let Node::Internal(node) = node else {
+        panic!("unexpected root node: {node:?}");
+    };
+    // Check that child refs for the loaded children were updated.
+    assert_eq!(node.child_ref(0xd).unwrap().version, 1);
+    assert_eq!(node.child_ref(0xe).unwrap().version, 1);
+
+    assert_storage_with_3_keys(&updater);
+}
+
+/// Tests inserting a node into a non-empty database, which moves an existing leaf. `db` must be empty.
+pub fn test_inserting_node_in_non_empty_database_with_moved_key<DB: Database + ?Sized>(
+    db: &mut DB,
+) {
+    let storage = Storage::new(&*db, &(), 0, true);
+    let kvs = vec![
+        TreeEntry::new(FIRST_KEY, 1, H256([1; 32])),
+        TreeEntry::new(THIRD_KEY, 2, H256([3; 32])),
+    ];
+    let (_, patch) = storage.extend(kvs);
+    db.apply_patch(patch).unwrap();
+
+    let mut updater = TreeUpdater::new(1, db.root(0).unwrap());
+    let sorted_keys = SortedKeys::new([SECOND_KEY].into_iter());
+    let parent_nibbles = updater.load_ancestors(&sorted_keys, &*db);
+    assert_eq!(
+        parent_nibbles,
+        [Nibbles::new(&SECOND_KEY, 5)] // `deadb`, a leaf node
+    );
+    assert!(matches!(
+        updater.patch_set.get(&parent_nibbles[0]),
+        Some(Node::Leaf(_))
+    ));
+
+    let (op, _) = updater.insert(
+        TreeEntry::new(SECOND_KEY, 3, H256([2; 32])),
+        &parent_nibbles[0],
+    );
+    assert_eq!(op, TreeLogEntry::Inserted);
+    assert!(matches!(
+        updater.patch_set.get(&parent_nibbles[0]),
+        Some(Node::Internal(_))
+    ));
+    assert_eq!(updater.metrics.new_leaves, 1);
+    assert_eq!(updater.metrics.moved_leaves, 1);
+}
+
+/// Tests that read instructions do not change child ref versions. `db` must be empty.
+pub fn test_reading_keys_does_not_change_child_version<DB: Database + ?Sized>(db: &mut DB) {
+    let storage = Storage::new(&*db, &(), 0, true);
+    let kvs = vec![
+        TreeEntry::new(FIRST_KEY, 1, H256([0; 32])),
+        TreeEntry::new(SECOND_KEY, 2, H256([1; 32])),
+    ];
+    let (_, patch) = storage.extend(kvs);
+    db.apply_patch(patch).unwrap();
+
+    let storage = Storage::new(&*db, &(), 1, true);
+    let instructions = vec![
+        TreeInstruction::Read(FIRST_KEY),
+        TreeInstruction::Write(TreeEntry::new(E_KEY, 3, H256([2; 32]))),
+    ];
+
+    let (_, patch) = storage.extend_with_proofs(instructions);
+    let Some(Root::Filled {
+        leaf_count,
+        node: Node::Internal(node),
+    }) = &patch.patches_by_version[&1].root
+    else {
+        panic!("unexpected root");
+    };
+    assert_eq!(u64::from(*leaf_count), 3);
+    assert_eq!(node.child_ref(0xd).unwrap().version, 0);
+    assert_eq!(node.child_ref(0xe).unwrap().version, 1);
+}
+
+/// Tests that read instructions are not reflected in the produced patch. `db` must be empty.
+pub fn test_read_ops_are_not_reflected_in_patch<DB: Database + ?Sized>(db: &mut DB) {
+    let storage = Storage::new(&*db, &(), 0, true);
+    let kvs = vec![
+        TreeEntry::new(FIRST_KEY, 1, H256([0; 32])),
+        TreeEntry::new(SECOND_KEY, 2, H256([1; 32])),
+    ];
+    let (_, patch) = storage.extend(kvs);
+    db.apply_patch(patch).unwrap();
+
+    let storage = Storage::new(&*db, &(), 1, true);
+    let instructions = vec![TreeInstruction::Read(FIRST_KEY)];
+    let (_, patch) = storage.extend_with_proofs(instructions);
+    assert!(patch.patches_by_version[&1].nodes.is_empty());
+}
+
+/// Tests overwriting keys at the terminal tree level. `db` must be empty.
+pub fn test_tree_handles_keys_at_terminal_level<DB: Database + ?Sized>(db: &mut DB) {
+    let kvs = (0_u64..100)
+        .map(|i| TreeEntry::new(Key::from(i), i + 1, ValueHash::zero()))
+        .collect();
+    let (_, patch) = Storage::new(&*db, &(), 0, true).extend(kvs);
+    db.apply_patch(patch).unwrap();
+
+    // Overwrite a key and check that we don't panic.
+    let new_kvs = vec![TreeEntry::new(
+        Key::from(0),
+        1,
+        ValueHash::from_low_u64_be(1),
+    )];
+    let (_, patch) = Storage::new(&*db, &(), 1, true).extend(new_kvs);
+
+    assert_eq!(
+        patch.patches_by_version[&1]
+            .root
+            .as_ref()
+            .unwrap()
+            .leaf_count(),
+        100
+    );
+    assert_eq!(patch.patches_by_version[&1].nodes.len(), 2 * KEY_SIZE); // root is counted separately
+    for (key, node) in &patch.patches_by_version[&1].nodes {
+        let is_terminal = key.nibbles.nibble_count() == 2 * KEY_SIZE;
+        assert_eq!(is_terminal, matches!(node, Node::Leaf(_)));
+    }
+    assert_eq!(patch.stale_keys_by_version[&1].len(), 2 * KEY_SIZE + 1);
+}
+
+/// Tests tree recovery split into multiple stages. `db` must be empty.
+pub fn test_recovery_workflow_with_multiple_stages<DB: Database + ?Sized>(db: &mut DB) {
+    let recovery_version = 100;
+    let recovery_entries = (0_u64..100).map(|i| TreeEntry {
+        key: Key::from(i),
+        value: ValueHash::zero(),
+        leaf_index: i,
+    });
+    let patch = Storage::new(&*db, &(), recovery_version, false)
+        .extend_during_linear_recovery(recovery_entries.collect());
+    assert_eq!(patch.root(recovery_version).unwrap().leaf_count(), 100);
+    db.apply_patch(patch).unwrap();
+
+    let more_recovery_entries = (100_u64..200).map(|i| TreeEntry {
+        key: Key::from(i),
+        value: ValueHash::zero(),
+        leaf_index: i,
+    });
+
+    let patch = Storage::new(&*db, &(), recovery_version, false)
+        .extend_during_linear_recovery(more_recovery_entries.collect());
+    assert_eq!(patch.root(recovery_version).unwrap().leaf_count(), 200);
+    db.apply_patch(patch).unwrap();
+
+    // Check that all entries can be accessed
+    let storage = Storage::new(&*db, &(), recovery_version + 1, true);
+    let instructions = (0_u32..200).map(|i| TreeInstruction::Read(Key::from(i)));
+    let (output, _) = storage.extend_with_proofs(instructions.collect());
+    assert_eq!(output.leaf_count, 200);
+    assert_eq!(output.logs.len(), 200);
+    assert!(output
+        .logs
+        .iter()
+        .all(|log| matches!(log.base, TreeLogEntry::Read { .. })));
+}
+
+/// Tests building a tree with updated keys, checking its consistency, and pruning it. `db` must be empty.
+pub fn test_consistency_and_pruning<DB: PruneDatabase + ?Sized>(db: &mut DB) {
+    let generate_entries = |indices: std::ops::Range<u64>| -> Vec<_> {
+        indices
+            .map(|i| TreeEntry::new(U256::from(i) * 7_919, i + 1, H256::from_low_u64_be(i + 1)))
+            .collect()
+    };
+
+    let mut reference_tree = MerkleTree::new(PatchSet::default()).unwrap();
+    let mut tree = MerkleTree::new(&mut *db).unwrap();
+    for chunk_start in (0..100).step_by(20) {
+        let mut entries = generate_entries(chunk_start..chunk_start + 20);
+        // Update some of the existing entries to produce stale keys.
+        if chunk_start > 0 {
+            entries.push(TreeEntry::new(U256::zero(), 1, H256::repeat_byte(0xff)));
+        }
+        let expected_output = reference_tree.extend(entries.clone()).unwrap();
+        let output = tree.extend(entries).unwrap();
+        assert_eq!(output.root_hash, expected_output.root_hash);
+    }
+
+    let latest_version = tree.latest_version().unwrap();
+    assert_eq!(latest_version, 4);
+    for version in 0..=latest_version {
+        tree.verify_consistency(version, true).unwrap();
+    }
+
+    assert_eq!(db.min_stale_key_version(), Some(1));
+    let (mut pruner, _handle) = MerkleTreePruner::new(&mut *db);
+    pruner.prune_up_to(latest_version).unwrap();
+    drop(pruner);
+    assert_eq!(db.min_stale_key_version(), None);
+
+    let tree = MerkleTree::new(&mut *db).unwrap();
+    tree.verify_consistency(latest_version, true).unwrap();
+    assert_eq!(tree.latest_root_hash(), reference_tree.latest_root_hash());
+}
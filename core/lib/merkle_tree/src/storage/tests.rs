@@ -10,16 +10,38 @@ use test_casing::test_casing;
 use zksync_crypto_primitives::hasher::blake2::Blake2Hasher;
 use zksync_types::{H256, U256};
 
-use super::*;
+use tempfile::TempDir;
+
+use super::{testonly::*, *};
 use crate::{
     hasher::{HasherWithStats, MerklePath},
-    types::{NodeKey, TreeInstruction, KEY_SIZE},
+    types::{NodeKey, TreeInstruction},
 };
 
-pub(super) const FIRST_KEY: Key = U256([0, 0, 0, 0x_dead_beef_0000_0000]);
-const SECOND_KEY: Key = U256([0, 0, 0, 0x_dead_beef_0100_0000]);
-const THIRD_KEY: Key = U256([0, 0, 0, 0x_dead_d00d_1234_5678]);
-const E_KEY: U256 = U256([0, 0, 0, 0x_e000_0000_0000_0000]);
+/// Database kinds the shared test scenarios from the [`testonly`](super::testonly) module are run against.
+#[derive(Debug, Clone, Copy)]
+enum DatabaseKind {
+    PatchSet,
+    InMemory,
+    SpilledInMemory,
+}
+
+impl DatabaseKind {
+    const ALL: [Self; 3] = [Self::PatchSet, Self::InMemory, Self::SpilledInMemory];
+
+    fn run_test(self, test: impl FnOnce(&mut dyn PruneDatabase)) {
+        match self {
+            Self::PatchSet => test(&mut PatchSet::default()),
+            Self::InMemory => test(&mut InMemoryDatabase::default()),
+            Self::SpilledInMemory => {
+                let temp_dir = TempDir::new().expect("failed creating temporary dir");
+                let storage = InMemoryStorage::with_spill_to_disk(temp_dir.path(), 1_024)
+                    .expect("failed creating spilled storage");
+                test(&mut KeyValueDatabase::new(storage));
+            }
+        }
+    }
+}
 
 pub(super) fn generate_nodes(version: u64, nibble_counts: &[usize]) -> HashMap<NodeKey, Node> {
     let nodes = nibble_counts.iter().map(|&count| {
@@ -128,29 +150,6 @@ fn assert_storage_with_2_keys(updater: &TreeUpdater) {
     assert_eq!(leaf.value_hash, H256([2; 32]));
 }
 
-fn assert_storage_with_3_keys(updater: &TreeUpdater) {
-    // The 'dead' internal node should now contain 'b' and 'd' children.
-    let nibbles = Nibbles::new(&FIRST_KEY, 4);
-    let node = updater.patch_set.get(&nibbles).unwrap();
-    let Node::Internal(node) = node else {
-        panic!("Unexpected node at {nibbles}: {node:?}");
-    };
-    assert_eq!(node.child_count(), 2);
-
-    let child_ref = node.child_ref(0xb).unwrap();
-    assert!(!child_ref.is_leaf);
-    let child_ref = node.child_ref(0xd).unwrap();
-    assert!(child_ref.is_leaf);
-
-    let third_leaf_nibbles = Nibbles::new(&THIRD_KEY, 5);
-    let node = updater.patch_set.get(&third_leaf_nibbles).unwrap();
-    let Node::Leaf(leaf) = node else {
-        panic!("Unexpected node at {third_leaf_nibbles}: {node:?}");
-    };
-    assert_eq!(leaf.full_key, THIRD_KEY);
-    assert_eq!(leaf.value_hash, H256([3; 32]));
-}
-
 #[test]
 fn changing_child_ref_type() {
     let mut updater = TreeUpdater::new(0, Root::Empty);
@@ -178,91 +177,16 @@ fn changing_child_ref_type() {
     assert!(node.child_ref(0xe).unwrap().is_leaf);
 }
 
+#[test_casing(3, DatabaseKind::ALL)]
 #[test]
-fn inserting_node_in_non_empty_database() {
-    let mut db = PatchSet::default();
-    let storage = Storage::new(&db, &(), 0, true);
-    let kvs = vec![
-        TreeEntry::new(FIRST_KEY, 1, H256([1; 32])),
-        TreeEntry::new(SECOND_KEY, 2, H256([2; 32])),
-    ];
-    let (_, patch) = storage.extend(kvs);
-    db.apply_patch(patch).unwrap();
-
-    let mut updater = TreeUpdater::new(1, db.root(0).unwrap());
-    let sorted_keys = SortedKeys::new([THIRD_KEY, E_KEY, SECOND_KEY].into_iter());
-    let parent_nibbles = updater.load_ancestors(&sorted_keys, &db);
-    assert_eq!(updater.metrics.db_reads, 10);
-    assert_eq!(
-        parent_nibbles,
-        [
-            Nibbles::new(&THIRD_KEY, 4), // dead
-            Nibbles::EMPTY,
-            Nibbles::new(&SECOND_KEY, 10), // deadbeef01
-        ]
-    );
-
-    let (op, _) = updater.insert(
-        TreeEntry::new(THIRD_KEY, 3, H256([3; 32])),
-        &parent_nibbles[0],
-    );
-    assert_eq!(op, TreeLogEntry::Inserted);
-    let (op, _) = updater.insert(TreeEntry::new(E_KEY, 4, H256::zero()), &parent_nibbles[1]);
-    assert_eq!(op, TreeLogEntry::Inserted);
-    let (op, _) = updater.insert(
-        TreeEntry::new(SECOND_KEY, 2, H256([2; 32])),
-        &parent_nibbles[2],
-    );
-    assert_matches!(op, TreeLogEntry::Updated { leaf_index: 2, .. });
-    assert_eq!(updater.metrics.new_internal_nodes, 0);
-    assert_eq!(updater.metrics.new_leaves, 2);
-
-    // Check that all necessary child refs have updated versions.
-    let node = updater.patch_set.get(&Nibbles::EMPTY).unwrap();
-    let Node::Internal(node) = node else {
-        panic!("unexpected root node: {node:?}");
-    };
-    // Check that child refs for the loaded children were updated.
-    assert_eq!(node.child_ref(0xd).unwrap().version, 1);
-    assert_eq!(node.child_ref(0xe).unwrap().version, 1);
-
-    assert_storage_with_3_keys(&updater);
+fn inserting_node_in_non_empty_database(kind: DatabaseKind) {
+    kind.run_test(|db| test_inserting_node_in_non_empty_database(db));
 }
 
+#[test_casing(3, DatabaseKind::ALL)]
 #[test]
-fn inserting_node_in_non_empty_database_with_moved_key() {
-    let mut db = PatchSet::default();
-    let storage = Storage::new(&db, &(), 0, true);
-    let kvs = vec![
-        TreeEntry::new(FIRST_KEY, 1, H256([1; 32])),
-        TreeEntry::new(THIRD_KEY, 2, H256([3; 32])),
-    ];
-    let (_, patch) = storage.extend(kvs);
-    db.apply_patch(patch).unwrap();
-
-    let mut updater = TreeUpdater::new(1, db.root(0).unwrap());
-    let sorted_keys = SortedKeys::new([SECOND_KEY].into_iter());
-    let parent_nibbles = updater.load_ancestors(&sorted_keys, &db);
-    assert_eq!(
-        parent_nibbles,
-        [Nibbles::new(&SECOND_KEY, 5)] // `deadb`, a leaf node
-    );
-    assert_matches!(
-        updater.patch_set.get(&parent_nibbles[0]),
-        Some(Node::Leaf(_))
-    );
-
-    let (op, _) = updater.insert(
-        TreeEntry::new(SECOND_KEY, 3, H256([2; 32])),
-        &parent_nibbles[0],
-    );
-    assert_eq!(op, TreeLogEntry::Inserted);
-    assert_matches!(
-        updater.patch_set.get(&parent_nibbles[0]),
-        Some(Node::Internal(_))
-    );
-    assert_eq!(updater.metrics.new_leaves, 1);
-    assert_eq!(updater.metrics.moved_leaves, 1);
+fn inserting_node_in_non_empty_database_with_moved_key(kind: DatabaseKind) {
+    kind.run_test(|db| test_inserting_node_in_non_empty_database_with_moved_key(db));
 }
 
 #[test]
@@ -305,51 +229,16 @@ fn finalize_merkle_path(mut path: MerklePath, hasher: &HasherWithStats<'_>) -> V
     path.into_inner()
 }
 
+#[test_casing(3, DatabaseKind::ALL)]
 #[test]
-fn reading_keys_does_not_change_child_version() {
-    let mut db = PatchSet::default();
-    let storage = Storage::new(&db, &(), 0, true);
-    let kvs = vec![
-        TreeEntry::new(FIRST_KEY, 1, H256([0; 32])),
-        TreeEntry::new(SECOND_KEY, 2, H256([1; 32])),
-    ];
-    let (_, patch) = storage.extend(kvs);
-    db.apply_patch(patch).unwrap();
-
-    let storage = Storage::new(&db, &(), 1, true);
-    let instructions = vec![
-        TreeInstruction::Read(FIRST_KEY),
-        TreeInstruction::Write(TreeEntry::new(E_KEY, 3, H256([2; 32]))),
-    ];
-
-    let (_, patch) = storage.extend_with_proofs(instructions);
-    let Some(Root::Filled {
-        leaf_count,
-        node: Node::Internal(node),
-    }) = &patch.patches_by_version[&1].root
-    else {
-        panic!("unexpected root");
-    };
-    assert_eq!(u64::from(*leaf_count), 3);
-    assert_eq!(node.child_ref(0xd).unwrap().version, 0);
-    assert_eq!(node.child_ref(0xe).unwrap().version, 1);
+fn reading_keys_does_not_change_child_version(kind: DatabaseKind) {
+    kind.run_test(|db| test_reading_keys_does_not_change_child_version(db));
 }
 
+#[test_casing(3, DatabaseKind::ALL)]
 #[test]
-fn read_ops_are_not_reflected_in_patch() {
-    let mut db = PatchSet::default();
-    let storage = Storage::new(&db, &(), 0, true);
-    let kvs = vec![
-        TreeEntry::new(FIRST_KEY, 1, H256([0; 32])),
-        TreeEntry::new(SECOND_KEY, 2, H256([1; 32])),
-    ];
-    let (_, patch) = storage.extend(kvs);
-    db.apply_patch(patch).unwrap();
-
-    let storage = Storage::new(&db, &(), 1, true);
-    let instructions = vec![TreeInstruction::Read(FIRST_KEY)];
-    let (_, patch) = storage.extend_with_proofs(instructions);
-    assert!(patch.patches_by_version[&1].nodes.is_empty());
+fn read_ops_are_not_reflected_in_patch(kind: DatabaseKind) {
+    kind.run_test(|db| test_read_ops_are_not_reflected_in_patch(db));
 }
 
 // This maps small indices to keys that differ in the starting nibbles.
@@ -467,37 +356,16 @@ fn assert_replaced_keys(db: &PatchSet, patch: &PatchSet) {
     assert_eq!(replaced_keys, expected_replaced_keys);
 }
 
+#[test_casing(3, DatabaseKind::ALL)]
 #[test]
-fn tree_handles_keys_at_terminal_level() {
-    let mut db = PatchSet::default();
-    let kvs = (0_u64..100)
-        .map(|i| TreeEntry::new(Key::from(i), i + 1, ValueHash::zero()))
-        .collect();
-    let (_, patch) = Storage::new(&db, &(), 0, true).extend(kvs);
-    db.apply_patch(patch).unwrap();
-
-    // Overwrite a key and check that we don't panic.
-    let new_kvs = vec![TreeEntry::new(
-        Key::from(0),
-        1,
-        ValueHash::from_low_u64_be(1),
-    )];
-    let (_, patch) = Storage::new(&db, &(), 1, true).extend(new_kvs);
-
-    assert_eq!(
-        patch.patches_by_version[&1]
-            .root
-            .as_ref()
-            .unwrap()
-            .leaf_count(),
-        100
-    );
-    assert_eq!(patch.patches_by_version[&1].nodes.len(), 2 * KEY_SIZE); // root is counted separately
-    for (key, node) in &patch.patches_by_version[&1].nodes {
-        let is_terminal = key.nibbles.nibble_count() == 2 * KEY_SIZE;
-        assert_eq!(is_terminal, matches!(node, Node::Leaf(_)));
-    }
-    assert_eq!(patch.stale_keys_by_version[&1].len(), 2 * KEY_SIZE + 1);
+fn tree_handles_keys_at_terminal_level(kind: DatabaseKind) {
+    kind.run_test(|db| test_tree_handles_keys_at_terminal_level(db));
+}
+
+#[test_casing(3, DatabaseKind::ALL)]
+#[test]
+fn consistency_and_pruning(kind: DatabaseKind) {
+    kind.run_test(|db| test_consistency_and_pruning(db));
 }
 
 #[test]
@@ -646,41 +514,10 @@ fn recovery_with_deep_node_hierarchy(chunk_size: usize) {
     }
 }
 
+#[test_casing(3, DatabaseKind::ALL)]
 #[test]
-fn recovery_workflow_with_multiple_stages() {
-    let mut db = PatchSet::default();
-    let recovery_version = 100;
-    let recovery_entries = (0_u64..100).map(|i| TreeEntry {
-        key: Key::from(i),
-        value: ValueHash::zero(),
-        leaf_index: i,
-    });
-    let patch = Storage::new(&db, &(), recovery_version, false)
-        .extend_during_linear_recovery(recovery_entries.collect());
-    assert_eq!(patch.root(recovery_version).unwrap().leaf_count(), 100);
-    db.apply_patch(patch).unwrap();
-
-    let more_recovery_entries = (100_u64..200).map(|i| TreeEntry {
-        key: Key::from(i),
-        value: ValueHash::zero(),
-        leaf_index: i,
-    });
-
-    let patch = Storage::new(&db, &(), recovery_version, false)
-        .extend_during_linear_recovery(more_recovery_entries.collect());
-    assert_eq!(patch.root(recovery_version).unwrap().leaf_count(), 200);
-    db.apply_patch(patch).unwrap();
-
-    // Check that all entries can be accessed
-    let storage = Storage::new(&db, &(), recovery_version + 1, true);
-    let instructions = (0_u32..200).map(|i| TreeInstruction::Read(Key::from(i)));
-    let (output, _) = storage.extend_with_proofs(instructions.collect());
-    assert_eq!(output.leaf_count, 200);
-    assert_eq!(output.logs.len(), 200);
-    assert!(output
-        .logs
-        .iter()
-        .all(|log| matches!(log.base, TreeLogEntry::Read { .. })));
+fn recovery_workflow_with_multiple_stages(kind: DatabaseKind) {
+    kind.run_test(|db| test_recovery_workflow_with_multiple_stages(db));
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -116,6 +116,24 @@ pub struct TreeEntryWithProof {
     pub merkle_path: Vec<ValueHash>,
 }
 
+/// Compact proof for multiple entries in a Merkle tree (aka multiproof).
+///
+/// Unlike a set of [`TreeEntryWithProof`]s, a multiproof doesn't contain hashes that can be computed from
+/// the proven entries (e.g., hashes of subtrees containing several entries), and contains each shared hash only once.
+/// Hence, it's significantly more compact if entries are located close to each other in the tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeMultiProof {
+    /// Proven entries ordered by key. Keys are unique.
+    pub entries: Vec<TreeEntry>,
+    /// Length of the Merkle path for each of `entries`, with the same meaning as the length
+    /// of [`TreeEntryWithProof::merkle_path`]. I.e., for `TREE_DEPTH - path_length` bottom-most tree levels,
+    /// the sibling subtrees for the entry are empty, and their hashes are not included into the proof.
+    pub path_lengths: Vec<u16>,
+    /// Hashes of sibling subtrees that cannot be computed from `entries`. Hashes are ordered by the tree level
+    /// (starting from the bottom-most level with leaves), then by the key of the proven subtree.
+    pub hashes: Vec<ValueHash>,
+}
+
 /// Output of inserting a block of entries into a Merkle tree.
 #[derive(Debug, PartialEq, Eq)]
 pub struct BlockOutput {
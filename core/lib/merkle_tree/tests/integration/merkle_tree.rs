@@ -174,6 +174,92 @@ fn proofs_are_computed_correctly_for_missing_keys() {
         .unwrap();
 }
 
+#[test_casing(8, KV_COUNTS)]
+fn multiproofs_are_computed_correctly(kv_count: u64) {
+    const RNG_SEED: u64 = 321;
+
+    let mut rng = StdRng::seed_from_u64(RNG_SEED);
+    let mut tree = MerkleTree::new(PatchSet::default()).unwrap();
+    let kvs = generate_key_value_pairs(0..kv_count);
+    let expected_hash = compute_tree_hash(kvs.iter().copied());
+    tree.extend(kvs.clone()).unwrap();
+
+    let existing_keys = kvs.iter().map(|entry| entry.key);
+    let adjacent_keys = kvs
+        .iter()
+        .map(|entry| entry.key ^ (U256::one() << rng.gen_range(0..256)));
+    let missing_keys = generate_key_value_pairs(kv_count..(kv_count * 2))
+        .into_iter()
+        .map(|entry| entry.key);
+    let mut keys: Vec<_> = existing_keys
+        .chain(adjacent_keys)
+        .chain(missing_keys)
+        .collect();
+    keys.shuffle(&mut rng);
+    // Add a duplicate key.
+    keys.push(keys[0]);
+
+    let proofs = tree.entries_with_proofs(0, &keys).unwrap();
+    let multiproof = tree.entries_with_multiproof(0, &keys).unwrap();
+    multiproof.verify(&Blake2Hasher, expected_hash).unwrap();
+
+    assert_eq!(multiproof.entries.len(), keys.len() - 1);
+    let is_sorted = multiproof
+        .entries
+        .windows(2)
+        .all(|window| window[0].key < window[1].key);
+    assert!(is_sorted);
+    for entry in &multiproof.entries {
+        let proof = proofs
+            .iter()
+            .find(|proof| proof.base.key == entry.key)
+            .unwrap();
+        assert_eq!(proof.base, *entry);
+    }
+
+    let separate_proofs_len: usize = proofs.iter().map(|proof| proof.merkle_path.len()).sum();
+    if kv_count > 1 {
+        assert!(
+            multiproof.hashes.len() < separate_proofs_len,
+            "{} vs {separate_proofs_len}",
+            multiproof.hashes.len()
+        );
+    }
+}
+
+#[test]
+fn invalid_multiproofs_are_rejected() {
+    let mut tree = MerkleTree::new(PatchSet::default()).unwrap();
+    let kvs = generate_key_value_pairs(0..50);
+    let root_hash = tree.extend(kvs.clone()).unwrap().root_hash;
+    let keys: Vec<_> = kvs.iter().step_by(3).map(|entry| entry.key).collect();
+    let multiproof = tree.entries_with_multiproof(0, &keys).unwrap();
+    multiproof.verify(&Blake2Hasher, root_hash).unwrap();
+
+    let mut tampered = multiproof.clone();
+    tampered.entries[1].value = H256::repeat_byte(0xff);
+    tampered.verify(&Blake2Hasher, root_hash).unwrap_err();
+
+    let mut tampered = multiproof.clone();
+    tampered.hashes[0] = H256::repeat_byte(0xff);
+    tampered.verify(&Blake2Hasher, root_hash).unwrap_err();
+
+    let mut tampered = multiproof.clone();
+    tampered.hashes.pop();
+    let err = tampered.verify(&Blake2Hasher, root_hash).unwrap_err();
+    assert!(err.to_string().contains("too few hashes"), "{err}");
+
+    let mut tampered = multiproof.clone();
+    tampered.hashes.push(H256::zero());
+    let err = tampered.verify(&Blake2Hasher, root_hash).unwrap_err();
+    assert!(err.to_string().contains("extra hashes"), "{err}");
+
+    let mut tampered = multiproof;
+    tampered.entries.swap(0, 1);
+    let err = tampered.verify(&Blake2Hasher, root_hash).unwrap_err();
+    assert!(err.to_string().contains("not sorted"), "{err}");
+}
+
 fn test_intermediate_commits(db: &mut impl Database, chunk_size: usize) {
     let (kvs, expected_hash) = &*ENTRIES_AND_HASH;
     let mut final_hash = H256::zero();
@@ -691,3 +777,117 @@ mod rocksdb {
         );
     }
 }
+
+/// Tests for the in-memory database, with and without spilling to disk.
+mod in_memory {
+    use tempfile::TempDir;
+    use test_casing::Product;
+    use zksync_merkle_tree::{
+        InMemoryDatabase, InMemoryStorage, KeyValueDatabase, MerkleTreePruner, PruneDatabase,
+    };
+
+    use super::*;
+
+    /// Memory limit small enough for nodes to be spilled on each tree update.
+    const SMALL_MEMORY_LIMIT: usize = 1_024;
+
+    #[derive(Debug)]
+    struct Harness {
+        db: InMemoryDatabase,
+        _dir: Option<TempDir>,
+    }
+
+    impl Harness {
+        fn new(spill_to_disk: bool) -> Self {
+            if spill_to_disk {
+                let dir = TempDir::new().expect("failed creating temporary dir for spill file");
+                let storage =
+                    InMemoryStorage::with_spill_to_disk(dir.path(), SMALL_MEMORY_LIMIT).unwrap();
+                Self {
+                    db: KeyValueDatabase::new(storage),
+                    _dir: Some(dir),
+                }
+            } else {
+                Self {
+                    db: InMemoryDatabase::default(),
+                    _dir: None,
+                }
+            }
+        }
+
+        fn assert_spilled(&self, spill_to_disk: bool) {
+            let storage = self.db.storage();
+            if spill_to_disk {
+                assert!(storage.spilled_node_count() > 0);
+                assert!(storage.memory_usage() <= SMALL_MEMORY_LIMIT);
+            } else {
+                assert_eq!(storage.spilled_node_count(), 0);
+            }
+        }
+    }
+
+    #[test_casing(2, [false, true])]
+    fn root_hash_equals_to_previous_implementation(spill_to_disk: bool) {
+        let mut harness = Harness::new(spill_to_disk);
+        test_root_hash_equals_to_previous_implementation(&mut harness.db);
+        harness.assert_spilled(spill_to_disk);
+    }
+
+    #[test_casing(2, [false, true])]
+    fn root_hash_is_computed_correctly_with_key_updates(spill_to_disk: bool) {
+        let harness = Harness::new(spill_to_disk);
+        test_root_hash_computing_with_key_updates(harness.db);
+    }
+
+    #[test_casing(6, Product(([3, 8, 21], [false, true])))]
+    fn root_hash_is_computed_correctly_with_intermediate_commits(
+        chunk_size: usize,
+        spill_to_disk: bool,
+    ) {
+        let mut harness = Harness::new(spill_to_disk);
+        test_intermediate_commits(&mut harness.db, chunk_size);
+        harness.assert_spilled(spill_to_disk);
+
+        let tree = MerkleTree::new(&mut harness.db).unwrap();
+        let latest_version = tree.latest_version().unwrap();
+        for version in 0..=latest_version {
+            tree.verify_consistency(version, true).unwrap();
+        }
+    }
+
+    #[test_casing(6, Product(([3, 8, 21], [false, true])))]
+    fn consistency_after_pruning(chunk_size: usize, spill_to_disk: bool) {
+        let mut harness = Harness::new(spill_to_disk);
+        test_intermediate_commits(&mut harness.db, chunk_size);
+        let node_count = harness.db.storage().node_count();
+
+        {
+            let (mut pruner, _handle) = MerkleTreePruner::new(&mut harness.db);
+            let last_prunable_version = pruner.last_prunable_version().unwrap();
+            pruner.prune_up_to(last_prunable_version).unwrap();
+        }
+
+        assert_eq!(harness.db.min_stale_key_version(), None);
+        assert!(harness.db.storage().node_count() < node_count);
+        let tree = MerkleTree::new(&mut harness.db).unwrap();
+        let latest_version = tree.latest_version().unwrap();
+        tree.verify_consistency(latest_version, true).unwrap();
+    }
+
+    #[test_casing(2, [false, true])]
+    fn root_hash_is_computed_correctly_with_reverts(spill_to_disk: bool) {
+        let mut harness = Harness::new(spill_to_disk);
+        test_root_hash_computing_with_reverts(&mut harness.db);
+
+        let mut tree = MerkleTree::new(&mut harness.db).unwrap();
+        assert_eq!(tree.latest_version(), Some(0));
+        tree.extend(vec![]).unwrap();
+        tree.verify_consistency(1, true).unwrap();
+    }
+
+    #[test_casing(6, Product(([3, 10, 42], [false, true])))]
+    fn accumulating_commits(chunk_size: usize, spill_to_disk: bool) {
+        let harness = Harness::new(spill_to_disk);
+        test_accumulated_commits(harness.db, chunk_size);
+    }
+}
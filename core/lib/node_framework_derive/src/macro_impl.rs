@@ -93,6 +93,7 @@ impl MacroImpl {
         let crate_path = self.crate_path();
         let ident = self.ident;
         let mut fields = Vec::new();
+        let mut descriptions = Vec::new();
         for field in self.fields {
             let ty = field.ty;
             let ident = field.ident;
@@ -112,17 +113,28 @@ impl MacroImpl {
                 ));
             }
 
-            let field = if default {
-                quote! {
-                    #ident: ctx.get_resource_or_default::<#ty>()
-                }
+            let (field, description) = if default {
+                (
+                    quote! {
+                        #ident: ctx.get_resource_or_default::<#ty>()
+                    },
+                    quote! {
+                        description.request_resource::<#ty>(#crate_path::service::ResourceRequest::Default);
+                    },
+                )
             } else {
-                quote! {
-                    #ident: <#ty as #crate_path::service::FromContext>::from_context(ctx)?
-                }
+                (
+                    quote! {
+                        #ident: <#ty as #crate_path::service::FromContext>::from_context(ctx)?
+                    },
+                    quote! {
+                        <#ty as #crate_path::service::FromContext>::describe_input(description);
+                    },
+                )
             };
 
-            fields.push(field)
+            fields.push(field);
+            descriptions.push(description);
         }
 
         Ok(quote! {
@@ -132,6 +144,11 @@ impl MacroImpl {
                         #(#fields),*
                     })
                 }
+
+                #[allow(unused_variables)] // The description is unused for empty structures.
+                fn describe_input(description: &mut #crate_path::service::LayerDescription) {
+                    #(#descriptions)*
+                }
             }
         })
     }
@@ -140,6 +157,7 @@ impl MacroImpl {
         let crate_path = self.crate_path();
         let ident = self.ident;
         let mut actions = Vec::new();
+        let mut descriptions = Vec::new();
         for field in self.fields {
             let ty = field.ty;
             let ident = field.ident;
@@ -157,25 +175,44 @@ impl MacroImpl {
                 ));
             }
 
-            let action = if field.label.task {
+            let field_name = ident.to_string();
+            let (action, description) = if field.label.task {
                 // Check whether the task is an `Option`.
-                if let Some(_inner_ty) = crate::helpers::extract_option_inner_type(&ty) {
-                    quote! {
-                        if let Some(task) = self.#ident {
-                            ctx.add_task(task);
-                        }
-                    }
+                if let Some(inner_ty) = crate::helpers::extract_option_inner_type(&ty) {
+                    (
+                        quote! {
+                            if let Some(task) = self.#ident {
+                                ctx.add_task(task);
+                            }
+                        },
+                        quote! {
+                            description.optional(|description| {
+                                description.add_task::<#inner_ty>(#field_name);
+                            });
+                        },
+                    )
                 } else {
-                    quote! {
-                        ctx.add_task(self.#ident);
-                    }
+                    (
+                        quote! {
+                            ctx.add_task(self.#ident);
+                        },
+                        quote! {
+                            description.add_task::<#ty>(#field_name);
+                        },
+                    )
                 }
             } else {
-                quote! {
-                    <#ty as #crate_path::service::IntoContext>::into_context(self.#ident, ctx)?;
-                }
+                (
+                    quote! {
+                        <#ty as #crate_path::service::IntoContext>::into_context(self.#ident, ctx)?;
+                    },
+                    quote! {
+                        <#ty as #crate_path::service::IntoContext>::describe_output(description);
+                    },
+                )
             };
             actions.push(action);
+            descriptions.push(description);
         }
 
         Ok(quote! {
@@ -184,6 +221,11 @@ impl MacroImpl {
                     #(#actions)*
                     Ok(())
                 }
+
+                #[allow(unused_variables)] // The description is unused for empty structures.
+                fn describe_output(description: &mut #crate_path::service::LayerDescription) {
+                    #(#descriptions)*
+                }
             }
         })
     }
@@ -4,6 +4,7 @@
 //! - [File-backed store](FileBackedObjectStore) saving blobs as separate files in the local filesystem
 //! - [GCS-based store](GoogleCloudStore)
 //! - [Mock in-memory store](MockObjectStore)
+//! - [Read-only portable snapshot archive](SnapshotArchive)
 //!
 //! Normally, these implementations are not used directly. Instead, a store trait object (`Arc<dyn ObjectStore>`)
 //! can be constructed using an [`ObjectStoreFactory`] based on the configuration.
@@ -32,6 +33,7 @@ mod mock;
 mod objects;
 mod raw;
 mod retries;
+mod snapshot_archive;
 
 // Re-export `bincode` crate so that client binaries can conveniently use it.
 pub use bincode;
@@ -48,4 +50,5 @@ pub use self::{
     mock::MockObjectStore,
     objects::StoredObject,
     raw::{Bucket, ObjectStore, ObjectStoreError},
+    snapshot_archive::{SnapshotArchive, SnapshotRecoveryData},
 };
@@ -8,7 +8,8 @@ use prost::Message;
 use zksync_protobuf::{decode, ProtoFmt};
 use zksync_types::{
     snapshots::{
-        SnapshotFactoryDependencies, SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
+        SnapshotFactoryDependencies, SnapshotHeader, SnapshotStorageLogsChunk,
+        SnapshotStorageLogsStorageKey,
     },
     L1BatchNumber,
 };
@@ -121,6 +122,25 @@ where
     }
 }
 
+/// Snapshot header is stored alongside the snapshot data so that the snapshot can be inspected
+/// without access to Postgres.
+impl StoredObject for SnapshotHeader {
+    const BUCKET: Bucket = Bucket::StorageSnapshot;
+    type Key<'a> = L1BatchNumber;
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        format!("snapshot_l1_batch_{key}_header.json")
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, BoxedError> {
+        serde_json::to_vec(self).map_err(From::from)
+    }
+
+    fn deserialize(bytes: Vec<u8>) -> Result<Self, BoxedError> {
+        serde_json::from_slice(&bytes).map_err(From::from)
+    }
+}
+
 impl dyn ObjectStore + '_ {
     /// Fetches the value for the given key if it exists.
     ///
@@ -209,9 +229,11 @@ impl dyn ObjectStore + '_ {
 #[cfg(test)]
 mod tests {
     use zksync_types::{
-        snapshots::{SnapshotFactoryDependency, SnapshotStorageLog},
+        snapshots::{
+            SnapshotFactoryDependency, SnapshotStorageLog, SnapshotStorageLogsChunkMetadata,
+        },
         web3::Bytes,
-        H256,
+        L2BlockNumber, H256,
     };
 
     use super::*;
@@ -291,4 +313,39 @@ mod tests {
         let reconstructed_factory_deps = store.get(key).await.unwrap();
         assert_eq!(factory_deps, reconstructed_factory_deps);
     }
+
+    #[tokio::test]
+    async fn test_snapshot_header_can_be_serialized_and_deserialized() {
+        let store = MockObjectStore::arc();
+        let key = L1BatchNumber(123);
+        let header = SnapshotHeader {
+            version: 1,
+            l1_batch_number: key,
+            l2_block_number: L2BlockNumber(456),
+            base_l1_batch_number: None,
+            storage_logs_chunks: vec![SnapshotStorageLogsChunkMetadata {
+                chunk_id: 0,
+                filepath: "snapshot_l1_batch_123_storage_logs_part_0000.proto.gzip".to_owned(),
+            }],
+            factory_deps_filepath: "snapshot_l1_batch_123_factory_deps.proto.gzip".to_owned(),
+        };
+        store.put(key, &header).await.unwrap();
+        assert_eq!(
+            SnapshotHeader::encode_key(key),
+            "snapshot_l1_batch_123_header.json"
+        );
+
+        let reconstructed_header: SnapshotHeader = store.get(key).await.unwrap();
+        assert_eq!(reconstructed_header.version, header.version);
+        assert_eq!(reconstructed_header.l1_batch_number, key);
+        assert_eq!(reconstructed_header.l2_block_number, header.l2_block_number);
+        assert_eq!(
+            reconstructed_header.storage_logs_chunks,
+            header.storage_logs_chunks
+        );
+        assert_eq!(
+            reconstructed_header.factory_deps_filepath,
+            header.factory_deps_filepath
+        );
+    }
 }
@@ -0,0 +1,699 @@
+//! Portable single-file archive for storage snapshots.
+//!
+//! # Format
+//!
+//! An archive consists of the following parts, in order:
+//!
+//! 1. 8-byte magic [`MAGIC`].
+//! 2. Raw objects (snapshot headers, factory dependencies and storage log chunks) concatenated
+//!    without any separators. Objects are stored exactly as they are serialized in the object store
+//!    (e.g., storage log chunks are gzipped Protobuf messages).
+//! 3. Table of contents (TOC) serialized as JSON. The TOC contains headers of all archived snapshots,
+//!    [recovery data](SnapshotRecoveryData) for the target snapshot, and the list of archived objects,
+//!    each with its key, offset, length and SHA-256 checksum.
+//! 4. Trailer: TOC length as a little-endian `u64`, SHA-256 checksum of the TOC, and [`MAGIC`] again.
+//!
+//! If the archived snapshot is a delta snapshot, the archive contains all snapshots in its chain down to
+//! the full snapshot, so that the archive is self-sufficient: a node can recover from it without querying
+//! the main node.
+
+use std::{
+    collections::HashMap,
+    io::SeekFrom,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::{
+    fs,
+    io::{self, AsyncReadExt, AsyncSeekExt, AsyncWriteExt, BufWriter},
+};
+use zksync_types::{
+    api,
+    snapshots::{
+        SnapshotFactoryDependencies, SnapshotHeader, SnapshotStorageLogsChunk,
+        SnapshotStorageLogsStorageKey,
+    },
+    tokens::TokenInfo,
+    L1BatchNumber, H256,
+};
+
+use crate::{
+    objects::StoredObject,
+    raw::{Bucket, ObjectStore, ObjectStoreError},
+};
+
+/// Magic bytes at the start and the end of a snapshot archive.
+const MAGIC: &[u8; 8] = b"ZKSNAPAR";
+/// Current version of the archive format.
+const FORMAT_VERSION: u32 = 1;
+/// Trailer length: TOC length (8 bytes) + TOC checksum (32 bytes) + magic (8 bytes).
+const TRAILER_LEN: u64 = 8 + 32 + 8;
+
+fn sha256(bytes: &[u8]) -> H256 {
+    H256(Sha256::digest(bytes).into())
+}
+
+fn format_error(message: impl Into<String>) -> ObjectStoreError {
+    ObjectStoreError::Initialization {
+        source: message.into().into(),
+        is_retriable: false,
+    }
+}
+
+/// Archived object.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ArchiveEntry {
+    key: String,
+    offset: u64,
+    len: u64,
+    checksum: H256,
+}
+
+/// Data required for snapshot recovery that is not a part of the snapshot itself. Without an archive,
+/// this data is fetched from the main node.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotRecoveryData {
+    /// Details of the snapshot L1 batch.
+    pub l1_batch_details: api::L1BatchDetails,
+    /// Details of the snapshot L2 block (i.e., the last L2 block in the snapshot L1 batch).
+    pub l2_block_details: api::BlockDetails,
+    /// Tokens deployed as of the snapshot L2 block.
+    pub tokens: Vec<TokenInfo>,
+}
+
+impl SnapshotRecoveryData {
+    fn validate(&self, header: &SnapshotHeader) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.l1_batch_details.number == header.l1_batch_number,
+            "recovery data is for L1 batch #{}, while the snapshot is for L1 batch #{}",
+            self.l1_batch_details.number,
+            header.l1_batch_number
+        );
+        anyhow::ensure!(
+            self.l2_block_details.number == header.l2_block_number,
+            "recovery data is for L2 block #{}, while the snapshot is for L2 block #{}",
+            self.l2_block_details.number,
+            header.l2_block_number
+        );
+        anyhow::ensure!(
+            self.l2_block_details.l1_batch_number == header.l1_batch_number,
+            "snapshot L2 block #{} in recovery data doesn't belong to L1 batch #{}",
+            self.l2_block_details.number,
+            header.l1_batch_number
+        );
+        anyhow::ensure!(
+            self.l1_batch_details.base.root_hash.is_some(),
+            "snapshot L1 batch in recovery data doesn't have root hash set"
+        );
+        Ok(())
+    }
+}
+
+/// Table of contents of a snapshot archive.
+#[derive(Debug, Serialize, Deserialize)]
+struct ArchiveToc {
+    format_version: u32,
+    /// Headers of archived snapshots, starting from the target snapshot and followed by its bases (if any).
+    headers: Vec<SnapshotHeader>,
+    recovery_data: SnapshotRecoveryData,
+    entries: Vec<ArchiveEntry>,
+}
+
+/// Writer of snapshot archives.
+#[derive(Debug)]
+struct ArchiveWriter {
+    file: BufWriter<fs::File>,
+    offset: u64,
+    entries: Vec<ArchiveEntry>,
+}
+
+impl ArchiveWriter {
+    async fn create(path: &Path) -> io::Result<Self> {
+        let file = fs::File::create(path).await?;
+        let mut file = BufWriter::new(file);
+        file.write_all(MAGIC).await?;
+        Ok(Self {
+            file,
+            offset: MAGIC.len() as u64,
+            entries: vec![],
+        })
+    }
+
+    async fn add_entry(&mut self, key: String, bytes: &[u8]) -> io::Result<()> {
+        self.file.write_all(bytes).await?;
+        let len = bytes.len() as u64;
+        self.entries.push(ArchiveEntry {
+            key,
+            offset: self.offset,
+            len,
+            checksum: sha256(bytes),
+        });
+        self.offset += len;
+        Ok(())
+    }
+
+    async fn finish(
+        mut self,
+        headers: Vec<SnapshotHeader>,
+        recovery_data: SnapshotRecoveryData,
+    ) -> anyhow::Result<()> {
+        let toc = ArchiveToc {
+            format_version: FORMAT_VERSION,
+            headers,
+            recovery_data,
+            entries: self.entries,
+        };
+        let toc = serde_json::to_vec(&toc).context("failed serializing TOC")?;
+        self.file.write_all(&toc).await?;
+        self.file
+            .write_all(&(toc.len() as u64).to_le_bytes())
+            .await?;
+        self.file.write_all(sha256(&toc).as_bytes()).await?;
+        self.file.write_all(MAGIC).await?;
+        self.file.flush().await?;
+        self.file.get_mut().sync_all().await?;
+        Ok(())
+    }
+}
+
+/// Read-only [`ObjectStore`] backed by a portable snapshot archive.
+///
+/// Archives are created using [`Self::export()`] from a snapshot persisted in an object store. An opened archive
+/// can be used in place of the object store the snapshot was created in, e.g. for snapshot recovery.
+#[derive(Debug)]
+pub struct SnapshotArchive {
+    path: PathBuf,
+    headers: Vec<SnapshotHeader>,
+    recovery_data: SnapshotRecoveryData,
+    entries: HashMap<String, ArchiveEntry>,
+}
+
+impl SnapshotArchive {
+    /// Exports a snapshot for the specified L1 batch from `store` to a new archive at `path`. If the snapshot
+    /// is a delta snapshot, all snapshots in its chain are exported as well. `recovery_data` must correspond
+    /// to the exported snapshot. The archive is written to a temporary file which is atomically renamed
+    /// to `path` once the export is complete.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the snapshot objects cannot be fetched from the store, if `recovery_data`
+    /// doesn't match the snapshot, or on I/O errors.
+    pub async fn export(
+        store: &dyn ObjectStore,
+        l1_batch_number: L1BatchNumber,
+        recovery_data: SnapshotRecoveryData,
+        path: &Path,
+    ) -> anyhow::Result<Self> {
+        let mut tmp_path = path.as_os_str().to_owned();
+        tmp_path.push(".tmp");
+        let tmp_path = PathBuf::from(tmp_path);
+        let mut writer = ArchiveWriter::create(&tmp_path)
+            .await
+            .with_context(|| format!("failed creating archive at {}", tmp_path.display()))?;
+
+        let mut headers = vec![];
+        let mut next_l1_batch_number = Some(l1_batch_number);
+        while let Some(l1_batch_number) = next_l1_batch_number {
+            let header_key = SnapshotHeader::encode_key(l1_batch_number);
+            let header_bytes = store
+                .get_raw(Bucket::StorageSnapshot, &header_key)
+                .await
+                .with_context(|| {
+                    format!("failed fetching header for snapshot #{l1_batch_number}")
+                })?;
+            let header = SnapshotHeader::deserialize(header_bytes.clone()).map_err(|err| {
+                anyhow::anyhow!(
+                    "failed deserializing header for snapshot #{l1_batch_number}: {err}"
+                )
+            })?;
+            anyhow::ensure!(
+                header.l1_batch_number == l1_batch_number,
+                "snapshot header has unexpected L1 batch number: expected {l1_batch_number}, got {}",
+                header.l1_batch_number
+            );
+            if headers.is_empty() {
+                recovery_data
+                    .validate(&header)
+                    .context("recovery data doesn't match the exported snapshot")?;
+            }
+            writer.add_entry(header_key, &header_bytes).await?;
+
+            let factory_deps_key = SnapshotFactoryDependencies::encode_key(l1_batch_number);
+            let factory_deps = store
+                .get_raw(Bucket::StorageSnapshot, &factory_deps_key)
+                .await
+                .with_context(|| {
+                    format!("failed fetching factory deps for snapshot #{l1_batch_number}")
+                })?;
+            writer.add_entry(factory_deps_key, &factory_deps).await?;
+
+            for chunk in &header.storage_logs_chunks {
+                // Chunk keys don't depend on the snapshot version, so we can use any key type here.
+                let chunk_key =
+                    SnapshotStorageLogsChunk::<H256>::encode_key(SnapshotStorageLogsStorageKey {
+                        l1_batch_number,
+                        chunk_id: chunk.chunk_id,
+                    });
+                let chunk_bytes = store
+                    .get_raw(Bucket::StorageSnapshot, &chunk_key)
+                    .await
+                    .with_context(|| {
+                        format!(
+                            "failed fetching storage logs chunk {} for snapshot #{l1_batch_number}",
+                            chunk.chunk_id
+                        )
+                    })?;
+                writer.add_entry(chunk_key, &chunk_bytes).await?;
+            }
+            tracing::info!(
+                "Exported snapshot #{l1_batch_number} with {} storage logs chunks to archive",
+                header.storage_logs_chunks.len()
+            );
+
+            next_l1_batch_number = header.base_l1_batch_number;
+            if let Some(base) = next_l1_batch_number {
+                anyhow::ensure!(
+                    base < l1_batch_number,
+                    "snapshot #{l1_batch_number} has base #{base} which is not older than the snapshot"
+                );
+            }
+            headers.push(header);
+        }
+
+        writer.finish(headers, recovery_data).await?;
+        fs::rename(&tmp_path, path).await.with_context(|| {
+            format!(
+                "failed renaming {} to {}",
+                tmp_path.display(),
+                path.display()
+            )
+        })?;
+        Self::open(path).await.map_err(Into::into)
+    }
+
+    /// Opens an existing archive and reads its table of contents. This doesn't check object checksums;
+    /// they are checked when reading objects, or all at once using [`Self::verify_checksums()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the archive cannot be read or is malformed.
+    pub async fn open(path: &Path) -> Result<Self, ObjectStoreError> {
+        let mut file = fs::File::open(path).await?;
+        let file_len = file.metadata().await?.len();
+        if file_len < MAGIC.len() as u64 + TRAILER_LEN {
+            return Err(format_error("archive is too short"));
+        }
+
+        let mut magic = [0_u8; 8];
+        file.read_exact(&mut magic).await?;
+        if magic != *MAGIC {
+            return Err(format_error(
+                "archive doesn't start with expected magic bytes",
+            ));
+        }
+
+        file.seek(SeekFrom::Start(file_len - TRAILER_LEN)).await?;
+        let toc_len = file.read_u64_le().await?;
+        let mut toc_checksum = [0_u8; 32];
+        file.read_exact(&mut toc_checksum).await?;
+        file.read_exact(&mut magic).await?;
+        if magic != *MAGIC {
+            return Err(format_error(
+                "archive doesn't end with expected magic bytes; it may be truncated",
+            ));
+        }
+        let data_end = (file_len - TRAILER_LEN)
+            .checked_sub(toc_len)
+            .filter(|&offset| offset >= MAGIC.len() as u64)
+            .ok_or_else(|| format_error(format!("invalid TOC length: {toc_len}")))?;
+
+        file.seek(SeekFrom::Start(data_end)).await?;
+        let toc_len = usize::try_from(toc_len)
+            .map_err(|_| format_error(format!("invalid TOC length: {toc_len}")))?;
+        let mut toc = vec![0_u8; toc_len];
+        file.read_exact(&mut toc).await?;
+        if sha256(&toc) != H256(toc_checksum) {
+            return Err(format_error("TOC checksum mismatch"));
+        }
+        let toc: ArchiveToc = serde_json::from_slice(&toc)
+            .map_err(|err| format_error(format!("failed deserializing TOC: {err}")))?;
+
+        if toc.format_version != FORMAT_VERSION {
+            return Err(format_error(format!(
+                "unsupported archive format version {}; expected {FORMAT_VERSION}",
+                toc.format_version
+            )));
+        }
+        let Some(header) = toc.headers.first() else {
+            return Err(format_error("archive contains no snapshots"));
+        };
+        if let Err(err) = toc.recovery_data.validate(header) {
+            return Err(format_error(format!("invalid recovery data: {err}")));
+        }
+
+        let mut entries = HashMap::with_capacity(toc.entries.len());
+        for entry in toc.entries {
+            let end = entry.offset.checked_add(entry.len);
+            if entry.offset < MAGIC.len() as u64 || end.map_or(true, |end| end > data_end) {
+                return Err(format_error(format!(
+                    "archived object `{}` is out of bounds",
+                    entry.key
+                )));
+            }
+            let key = entry.key.clone();
+            if entries.insert(key, entry).is_some() {
+                return Err(format_error("archive contains duplicate objects"));
+            }
+        }
+
+        Ok(Self {
+            path: path.to_owned(),
+            headers: toc.headers,
+            recovery_data: toc.recovery_data,
+            entries,
+        })
+    }
+
+    /// Returns the path to the archive.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the header of the archived snapshot.
+    pub fn header(&self) -> &SnapshotHeader {
+        &self.headers[0]
+    }
+
+    /// Returns headers of all archived snapshots, starting from the target snapshot and followed by its bases.
+    pub fn headers(&self) -> &[SnapshotHeader] {
+        &self.headers
+    }
+
+    /// Returns data required for recovery from the archived snapshot besides the snapshot itself.
+    pub fn recovery_data(&self) -> &SnapshotRecoveryData {
+        &self.recovery_data
+    }
+
+    /// Verifies checksums of all objects in the archive.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an object cannot be read, or if its checksum doesn't match.
+    pub async fn verify_checksums(&self) -> Result<(), ObjectStoreError> {
+        let mut file = fs::File::open(&self.path).await?;
+        for entry in self.entries.values() {
+            Self::read_entry(&mut file, entry).await?;
+        }
+        Ok(())
+    }
+
+    async fn read_entry(
+        file: &mut fs::File,
+        entry: &ArchiveEntry,
+    ) -> Result<Vec<u8>, ObjectStoreError> {
+        let len = usize::try_from(entry.len).map_err(|err| ObjectStoreError::Other {
+            source: err.into(),
+            is_retriable: false,
+        })?;
+        file.seek(SeekFrom::Start(entry.offset)).await?;
+        let mut bytes = vec![0_u8; len];
+        file.read_exact(&mut bytes).await?;
+
+        if sha256(&bytes) != entry.checksum {
+            let message = format!("checksum mismatch for archived object `{}`", entry.key);
+            return Err(ObjectStoreError::Other {
+                source: message.into(),
+                is_retriable: false,
+            });
+        }
+        Ok(bytes)
+    }
+}
+
+#[async_trait]
+impl ObjectStore for SnapshotArchive {
+    async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+        let entry = if bucket == Bucket::StorageSnapshot {
+            self.entries.get(key)
+        } else {
+            None
+        };
+        let entry = entry.ok_or_else(|| {
+            let message = format!("missing key: {key} in bucket {bucket}");
+            ObjectStoreError::KeyNotFound(message.into())
+        })?;
+        let mut file = fs::File::open(&self.path).await?;
+        Self::read_entry(&mut file, entry).await
+    }
+
+    async fn put_raw(
+        &self,
+        _bucket: Bucket,
+        _key: &str,
+        _value: Vec<u8>,
+    ) -> Result<(), ObjectStoreError> {
+        Err(ObjectStoreError::Other {
+            source: "snapshot archive is read-only".into(),
+            is_retriable: false,
+        })
+    }
+
+    async fn remove_raw(&self, _bucket: Bucket, _key: &str) -> Result<(), ObjectStoreError> {
+        Err(ObjectStoreError::Other {
+            source: "snapshot archive is read-only".into(),
+            is_retriable: false,
+        })
+    }
+
+    fn storage_prefix_raw(&self, bucket: Bucket) -> String {
+        format!("{}/{bucket}", self.path.display())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use tempfile::TempDir;
+    use zksync_types::{
+        snapshots::{
+            SnapshotFactoryDependency, SnapshotStorageLog, SnapshotStorageLogsChunkMetadata,
+            SnapshotVersion,
+        },
+        tokens::TokenMetadata,
+        web3::Bytes,
+        Address, L2BlockNumber,
+    };
+
+    use super::*;
+    use crate::MockObjectStore;
+
+    fn mock_recovery_data(header: &SnapshotHeader) -> SnapshotRecoveryData {
+        let base = api::BlockDetailsBase {
+            timestamp: 0,
+            l1_tx_count: 0,
+            l2_tx_count: 0,
+            root_hash: Some(H256::repeat_byte(1)),
+            status: api::BlockStatus::Verified,
+            commit_tx_hash: None,
+            committed_at: None,
+            prove_tx_hash: None,
+            proven_at: None,
+            execute_tx_hash: None,
+            executed_at: None,
+            l1_gas_price: 0,
+            l2_fair_gas_price: 0,
+            fair_pubdata_price: None,
+            base_system_contracts_hashes: Default::default(),
+        };
+        SnapshotRecoveryData {
+            l1_batch_details: api::L1BatchDetails {
+                number: header.l1_batch_number,
+                base: base.clone(),
+            },
+            l2_block_details: api::BlockDetails {
+                number: header.l2_block_number,
+                l1_batch_number: header.l1_batch_number,
+                base,
+                operator_address: Address::zero(),
+                protocol_version: None,
+            },
+            tokens: vec![TokenInfo {
+                l1_address: Address::zero(),
+                l2_address: Address::zero(),
+                metadata: TokenMetadata {
+                    name: "Ether".to_owned(),
+                    symbol: "ETH".to_owned(),
+                    decimals: 18,
+                },
+            }],
+        }
+    }
+
+    async fn prepare_snapshot(
+        store: &dyn ObjectStore,
+        l1_batch_number: L1BatchNumber,
+        base_l1_batch_number: Option<L1BatchNumber>,
+    ) -> SnapshotHeader {
+        let factory_deps = SnapshotFactoryDependencies {
+            factory_deps: vec![SnapshotFactoryDependency {
+                bytecode: Bytes::from(vec![1_u8; 32]),
+            }],
+        };
+        store.put(l1_batch_number, &factory_deps).await.unwrap();
+
+        let mut storage_logs_chunks = vec![];
+        for chunk_id in 0..3 {
+            let chunk = SnapshotStorageLogsChunk {
+                storage_logs: vec![SnapshotStorageLog {
+                    key: H256::from_low_u64_be(chunk_id),
+                    value: H256::repeat_byte(1),
+                    l1_batch_number_of_initial_write: l1_batch_number,
+                    enumeration_index: chunk_id + 1,
+                }],
+            };
+            let key = SnapshotStorageLogsStorageKey {
+                l1_batch_number,
+                chunk_id,
+            };
+            let filepath = store.put(key, &chunk).await.unwrap();
+            storage_logs_chunks.push(SnapshotStorageLogsChunkMetadata { chunk_id, filepath });
+        }
+
+        let header = SnapshotHeader {
+            version: SnapshotVersion::Version1.into(),
+            l1_batch_number,
+            l2_block_number: L2BlockNumber(l1_batch_number.0 * 2),
+            base_l1_batch_number,
+            storage_logs_chunks,
+            factory_deps_filepath: "factory_deps".to_owned(),
+        };
+        store.put(l1_batch_number, &header).await.unwrap();
+        header
+    }
+
+    #[tokio::test]
+    async fn exporting_and_reading_archive() {
+        let store = MockObjectStore::arc();
+        let header = prepare_snapshot(&*store, L1BatchNumber(5), None).await;
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("snapshot.bin");
+
+        let recovery_data = mock_recovery_data(&header);
+        let archive = SnapshotArchive::export(&*store, L1BatchNumber(5), recovery_data, &path)
+            .await
+            .unwrap();
+        assert_eq!(archive.headers().len(), 1);
+        assert_eq!(archive.header().l1_batch_number, header.l1_batch_number);
+        assert_eq!(archive.header().storage_logs_chunks.len(), 3);
+        archive.verify_checksums().await.unwrap();
+
+        let archive = SnapshotArchive::open(&path).await.unwrap();
+        let recovery_data = archive.recovery_data();
+        assert_eq!(recovery_data.l1_batch_details.number, L1BatchNumber(5));
+        assert_eq!(
+            recovery_data.l2_block_details.number,
+            header.l2_block_number
+        );
+        assert_eq!(recovery_data.tokens.len(), 1);
+        let archive: &dyn ObjectStore = &archive;
+        let factory_deps: SnapshotFactoryDependencies =
+            archive.get(L1BatchNumber(5)).await.unwrap();
+        assert_eq!(factory_deps.factory_deps.len(), 1);
+        for chunk_id in 0..3 {
+            let key = SnapshotStorageLogsStorageKey {
+                l1_batch_number: L1BatchNumber(5),
+                chunk_id,
+            };
+            let chunk: SnapshotStorageLogsChunk = archive.get(key).await.unwrap();
+            let expected_chunk: SnapshotStorageLogsChunk = store.get(key).await.unwrap();
+            assert_eq!(chunk.storage_logs, expected_chunk.storage_logs);
+        }
+
+        let missing_key = SnapshotStorageLogsStorageKey {
+            l1_batch_number: L1BatchNumber(5),
+            chunk_id: 3,
+        };
+        let err = archive
+            .get::<SnapshotStorageLogsChunk>(missing_key)
+            .await
+            .unwrap_err();
+        assert_matches!(err, ObjectStoreError::KeyNotFound(_));
+        let err = archive
+            .put(L1BatchNumber(5), &factory_deps)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("read-only"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn exporting_snapshot_chain() {
+        let store = MockObjectStore::arc();
+        prepare_snapshot(&*store, L1BatchNumber(5), None).await;
+        let header = prepare_snapshot(&*store, L1BatchNumber(8), Some(L1BatchNumber(5))).await;
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("snapshot.bin");
+
+        let recovery_data = mock_recovery_data(&header);
+        let archive = SnapshotArchive::export(&*store, L1BatchNumber(8), recovery_data, &path)
+            .await
+            .unwrap();
+        let archived_l1_batches: Vec<_> = archive
+            .headers()
+            .iter()
+            .map(|header| header.l1_batch_number)
+            .collect();
+        assert_eq!(archived_l1_batches, [L1BatchNumber(8), L1BatchNumber(5)]);
+
+        let archive: &dyn ObjectStore = &archive;
+        let base_header: SnapshotHeader = archive.get(L1BatchNumber(5)).await.unwrap();
+        assert_eq!(base_header.base_l1_batch_number, None);
+    }
+
+    #[tokio::test]
+    async fn exporting_snapshot_with_mismatched_recovery_data() {
+        let store = MockObjectStore::arc();
+        prepare_snapshot(&*store, L1BatchNumber(5), None).await;
+        let header = prepare_snapshot(&*store, L1BatchNumber(8), Some(L1BatchNumber(5))).await;
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("snapshot.bin");
+
+        // Recovery data must correspond to the target snapshot rather than to its base.
+        let mut recovery_data = mock_recovery_data(&header);
+        recovery_data.l1_batch_details.number = L1BatchNumber(5);
+        let err = SnapshotArchive::export(&*store, L1BatchNumber(8), recovery_data, &path)
+            .await
+            .unwrap_err();
+        let err = format!("{err:#}");
+        assert!(err.contains("recovery data"), "{err}");
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn detecting_archive_corruption() {
+        let store = MockObjectStore::arc();
+        let header = prepare_snapshot(&*store, L1BatchNumber(5), None).await;
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("snapshot.bin");
+        let recovery_data = mock_recovery_data(&header);
+        SnapshotArchive::export(&*store, L1BatchNumber(5), recovery_data, &path)
+            .await
+            .unwrap();
+
+        // Corrupt a byte in the data section.
+        let mut bytes = fs::read(&path).await.unwrap();
+        bytes[MAGIC.len() + 1] ^= 1;
+        fs::write(&path, &bytes).await.unwrap();
+        let archive = SnapshotArchive::open(&path).await.unwrap();
+        let err = archive.verify_checksums().await.unwrap_err();
+        assert!(err.to_string().contains("checksum mismatch"), "{err}");
+
+        // Truncate the archive.
+        fs::write(&path, &bytes[..bytes.len() - 1]).await.unwrap();
+        let err = SnapshotArchive::open(&path).await.unwrap_err();
+        assert_matches!(err, ObjectStoreError::Initialization { .. });
+    }
+}
@@ -0,0 +1,32 @@
+use anyhow::Context as _;
+use zksync_config::configs::AddressPolicyConfig;
+use zksync_protobuf::ProtoRepr;
+
+use crate::{parse_h160, proto::address_policy as proto};
+
+impl ProtoRepr for proto::AddressPolicy {
+    type Type = AddressPolicyConfig;
+
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            policy_file_path: self.policy_file_path.clone(),
+            onchain_denylist_addr: self
+                .onchain_denylist_addr
+                .as_ref()
+                .map(|x| parse_h160(x))
+                .transpose()
+                .context("onchain_denylist_addr")?,
+            reload_interval_ms: self
+                .reload_interval_ms
+                .unwrap_or(Self::Type::default_reload_interval_ms()),
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            policy_file_path: this.policy_file_path.clone(),
+            onchain_denylist_addr: this.onchain_denylist_addr.map(|x| format!("{x:?}")),
+            reload_interval_ms: Some(this.reload_interval_ms),
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use std::num::NonZeroUsize;
+use std::num::{NonZeroU32, NonZeroUsize};
 
 use anyhow::Context as _;
 use zksync_config::configs::{api, ApiConfig};
@@ -56,6 +56,11 @@ impl ProtoRepr for proto::Web3JsonRpc {
             })
             .collect::<anyhow::Result<_>>()
             .context("max_response_body_size_overrides")?;
+        let http_api_key_compute_units_per_minute_limits =
+            read_compute_units(&self.http_api_key_compute_units_per_minute_limits)
+                .context("http_api_key_compute_units_per_minute_limits")?;
+        let method_compute_units =
+            read_compute_units(&self.method_compute_units).context("method_compute_units")?;
         let api_namespaces = if self.api_namespaces.is_empty() {
             None
         } else {
@@ -70,6 +75,12 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 .and_then(|p| Ok((*p).try_into()?))
                 .context("ws_port")?,
             ws_url: required(&self.ws_url).context("ws_url")?.clone(),
+            ipc_path: self.ipc_path.clone(),
+            ipc_max_connections: self.ipc_max_connections,
+            ipc_subscriptions_per_connection_limit: self.ipc_subscriptions_per_connection_limit,
+            ipc_max_concurrent_requests_per_connection: self
+                .ipc_max_concurrent_requests_per_connection,
+            ipc_socket_permissions: self.ipc_socket_permissions.clone(),
             req_entities_limit: self.req_entities_limit,
             filters_disabled: self.filters_disabled.unwrap_or(false),
             filters_limit: self.filters_limit,
@@ -113,6 +124,7 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 .transpose()
                 .context("latest_values_cache_size_mb")?,
             fee_history_limit: self.fee_history_limit,
+            trace_filter_block_range_limit: self.trace_filter_block_range_limit,
             max_batch_request_size: self
                 .max_batch_request_size
                 .map(|x| x.try_into())
@@ -129,6 +141,20 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 .map(|x| x.try_into())
                 .transpose()
                 .context("websocket_requests_per_minute_limit")?,
+            http_compute_units_per_minute_limit: self
+                .http_compute_units_per_minute_limit
+                .map(|x| x.try_into())
+                .transpose()
+                .context("http_compute_units_per_minute_limit")?,
+            http_api_key_compute_units_per_minute_limits,
+            method_compute_units,
+            http_trusted_proxies: self
+                .http_trusted_proxies
+                .iter()
+                .enumerate()
+                .map(|(i, ip)| ip.parse().context(i))
+                .collect::<Result<Vec<_>, _>>()
+                .context("http_trusted_proxies")?,
             tree_api_url: self.tree_api_url.clone(),
             mempool_cache_update_interval: self.mempool_cache_update_interval,
             mempool_cache_size: self
@@ -144,6 +170,7 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 .collect::<Result<Vec<_>, _>>()
                 .context("whitelisted_tokens_for_aa")?,
             extended_api_tracing: self.extended_api_tracing.unwrap_or_default(),
+            preconfirmations_enabled: self.preconfirmations_enabled.unwrap_or_default(),
             api_namespaces,
         })
     }
@@ -154,6 +181,12 @@ impl ProtoRepr for proto::Web3JsonRpc {
             http_url: Some(this.http_url.clone()),
             ws_port: Some(this.ws_port.into()),
             ws_url: Some(this.ws_url.clone()),
+            ipc_path: this.ipc_path.clone(),
+            ipc_max_connections: this.ipc_max_connections,
+            ipc_subscriptions_per_connection_limit: this.ipc_subscriptions_per_connection_limit,
+            ipc_max_concurrent_requests_per_connection: this
+                .ipc_max_concurrent_requests_per_connection,
+            ipc_socket_permissions: this.ipc_socket_permissions.clone(),
             req_entities_limit: this.req_entities_limit,
             filters_disabled: Some(this.filters_disabled),
             mempool_cache_update_interval: this.mempool_cache_update_interval,
@@ -182,6 +215,7 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 .latest_values_cache_size_mb
                 .map(|x| x.try_into().unwrap()),
             fee_history_limit: this.fee_history_limit,
+            trace_filter_block_range_limit: this.trace_filter_block_range_limit,
             max_batch_request_size: this.max_batch_request_size.map(|x| x.try_into().unwrap()),
             max_response_body_size_mb: this
                 .max_response_body_size_mb
@@ -201,6 +235,18 @@ impl ProtoRepr for proto::Web3JsonRpc {
             websocket_requests_per_minute_limit: this
                 .websocket_requests_per_minute_limit
                 .map(|x| x.into()),
+            http_compute_units_per_minute_limit: this
+                .http_compute_units_per_minute_limit
+                .map(|x| x.into()),
+            http_api_key_compute_units_per_minute_limits: build_compute_units(
+                &this.http_api_key_compute_units_per_minute_limits,
+            ),
+            method_compute_units: build_compute_units(&this.method_compute_units),
+            http_trusted_proxies: this
+                .http_trusted_proxies
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
             tree_api_url: this.tree_api_url.clone(),
             whitelisted_tokens_for_aa: this
                 .whitelisted_tokens_for_aa
@@ -208,11 +254,34 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 .map(|k| format!("{:?}", k))
                 .collect(),
             extended_api_tracing: Some(this.extended_api_tracing),
+            preconfirmations_enabled: Some(this.preconfirmations_enabled),
             api_namespaces: this.api_namespaces.clone().unwrap_or_default(),
         }
     }
 }
 
+fn read_compute_units(entries: &[proto::ComputeUnits]) -> anyhow::Result<api::ComputeUnitsMap> {
+    entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let name = required(&entry.name).with_context(|| format!("[{i}].name"))?;
+            let units = *required(&entry.units).with_context(|| format!("[{i}].units"))?;
+            let units = NonZeroU32::new(units).with_context(|| format!("[{i}].units is zero"))?;
+            Ok((name.clone(), units))
+        })
+        .collect()
+}
+
+fn build_compute_units(map: &api::ComputeUnitsMap) -> Vec<proto::ComputeUnits> {
+    map.iter()
+        .map(|(name, units)| proto::ComputeUnits {
+            name: Some(name.to_owned()),
+            units: Some(units.get()),
+        })
+        .collect()
+}
+
 impl ProtoRepr for proto::HealthCheck {
     type Type = api::HealthCheckConfig;
 
@@ -223,6 +292,11 @@ impl ProtoRepr for proto::HealthCheck {
                 .context("port")?,
             slow_time_limit_ms: self.slow_time_limit_ms,
             hard_time_limit_ms: self.hard_time_limit_ms,
+            admin_port: self
+                .admin_port
+                .map(|port| port.try_into())
+                .transpose()
+                .context("admin_port")?,
         })
     }
 
@@ -231,6 +305,7 @@ impl ProtoRepr for proto::HealthCheck {
             port: Some(this.port.into()),
             slow_time_limit_ms: this.slow_time_limit_ms,
             hard_time_limit_ms: this.hard_time_limit_ms,
+            admin_port: this.admin_port.map(Into::into),
         }
     }
 }
@@ -22,11 +22,75 @@ impl proto::FeeModelVersion {
     }
 }
 
+impl proto::SealCriterion {
+    fn new(n: &configs::chain::SealCriterionKind) -> Self {
+        use configs::chain::SealCriterionKind as From;
+        match n {
+            From::Slots => Self::Slots,
+            From::Gas => Self::Gas,
+            From::PubdataBytes => Self::PubdataBytes,
+            From::Circuits => Self::Circuits,
+            From::TxEncodingSize => Self::TxEncodingSize,
+            From::GasForBatchTip => Self::GasForBatchTip,
+            From::L1BatchAge => Self::L1BatchAge,
+            From::L2BlocksCount => Self::L2BlocksCount,
+            From::PriorityTxsCount => Self::PriorityTxsCount,
+        }
+    }
+
+    fn parse(&self) -> configs::chain::SealCriterionKind {
+        use configs::chain::SealCriterionKind as To;
+        match self {
+            Self::Slots => To::Slots,
+            Self::Gas => To::Gas,
+            Self::PubdataBytes => To::PubdataBytes,
+            Self::Circuits => To::Circuits,
+            Self::TxEncodingSize => To::TxEncodingSize,
+            Self::GasForBatchTip => To::GasForBatchTip,
+            Self::L1BatchAge => To::L1BatchAge,
+            Self::L2BlocksCount => To::L2BlocksCount,
+            Self::PriorityTxsCount => To::PriorityTxsCount,
+        }
+    }
+}
+
+impl ProtoRepr for proto::SealCriteriaSet {
+    type Type = configs::chain::SealCriteriaSet;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            from_protocol_version: required(&self.from_protocol_version)
+                .and_then(|x| Ok((*x).try_into()?))
+                .context("from_protocol_version")?,
+            criteria: self
+                .criteria
+                .iter()
+                .enumerate()
+                .map(|(i, x)| {
+                    Ok(proto::SealCriterion::try_from(*x)
+                        .with_context(|| format!("criteria[{i}]"))?
+                        .parse())
+                })
+                .collect::<anyhow::Result<_>>()?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            from_protocol_version: Some(this.from_protocol_version.into()),
+            criteria: this
+                .criteria
+                .iter()
+                .map(|x| proto::SealCriterion::new(x).into())
+                .collect(),
+        }
+    }
+}
+
 impl ProtoRepr for proto::StateKeeper {
     type Type = configs::chain::StateKeeperConfig;
     fn read(&self) -> anyhow::Result<Self::Type> {
         #[allow(deprecated)]
-        Ok(Self::Type {
+        let config = Self::Type {
             transaction_slots: required(&self.transaction_slots)
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("transaction_slots")?,
@@ -40,6 +104,8 @@ impl ProtoRepr for proto::StateKeeper {
             l2_block_max_payload_size: required(&self.miniblock_max_payload_size)
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("miniblock_max_payload_size")?,
+            l2_block_gas_target: self.l2_block_gas_target,
+            l2_base_fee_max_change_denominator: self.l2_base_fee_max_change_denominator,
             max_single_tx_gas: *required(&self.max_single_tx_gas).context("max_single_tx_gas")?,
             max_allowed_l2_tx_gas_limit: *required(&self.max_allowed_l2_tx_gas_limit)
                 .context("max_allowed_l2_tx_gas_limit")?,
@@ -78,6 +144,15 @@ impl ProtoRepr for proto::StateKeeper {
             max_circuits_per_batch: required(&self.max_circuits_per_batch)
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("max_circuits_per_batch")?,
+            max_l1_batch_age_ms: self.max_l1_batch_age_ms,
+            max_l2_blocks_per_batch: self.max_l2_blocks_per_batch,
+            max_priority_txs_per_batch: self.max_priority_txs_per_batch,
+            seal_criteria: self
+                .seal_criteria
+                .iter()
+                .enumerate()
+                .map(|(i, set)| set.read().with_context(|| format!("seal_criteria[{i}]")))
+                .collect::<anyhow::Result<_>>()?,
             protective_reads_persistence_enabled: self
                 .protective_reads_persistence_enabled
                 .unwrap_or_default(),
@@ -88,7 +163,9 @@ impl ProtoRepr for proto::StateKeeper {
             default_aa_hash: None,
             fee_account_addr: None,
             l1_batch_commit_data_generator_mode: Default::default(),
-        })
+        };
+        config.validate_seal_criteria()?;
+        Ok(config)
     }
 
     fn build(this: &Self::Type) -> Self {
@@ -100,6 +177,8 @@ impl ProtoRepr for proto::StateKeeper {
                 this.l2_block_seal_queue_capacity.try_into().unwrap(),
             ),
             miniblock_max_payload_size: Some(this.l2_block_max_payload_size.try_into().unwrap()),
+            l2_block_gas_target: this.l2_block_gas_target,
+            l2_base_fee_max_change_denominator: this.l2_base_fee_max_change_denominator,
             max_single_tx_gas: Some(this.max_single_tx_gas),
             max_allowed_l2_tx_gas_limit: Some(this.max_allowed_l2_tx_gas_limit),
             reject_tx_at_geometry_percentage: Some(this.reject_tx_at_geometry_percentage),
@@ -118,6 +197,10 @@ impl ProtoRepr for proto::StateKeeper {
             validation_computational_gas_limit: Some(this.validation_computational_gas_limit),
             save_call_traces: Some(this.save_call_traces),
             max_circuits_per_batch: Some(this.max_circuits_per_batch.try_into().unwrap()),
+            max_l1_batch_age_ms: this.max_l1_batch_age_ms,
+            max_l2_blocks_per_batch: this.max_l2_blocks_per_batch,
+            max_priority_txs_per_batch: this.max_priority_txs_per_batch,
+            seal_criteria: this.seal_criteria.iter().map(ProtoRepr::build).collect(),
             protective_reads_persistence_enabled: Some(this.protective_reads_persistence_enabled),
         }
     }
@@ -1,3 +1,5 @@
+use std::num::NonZeroU32;
+
 use anyhow::Context as _;
 use zksync_config::configs;
 use zksync_protobuf::{
@@ -25,6 +27,24 @@ impl proto::MerkleTreeMode {
     }
 }
 
+impl proto::MerkleTreeStorage {
+    fn new(x: &configs::database::MerkleTreeStorage) -> Self {
+        use configs::database::MerkleTreeStorage as From;
+        match x {
+            From::RocksDB => Self::Rocksdb,
+            From::Postgres => Self::Postgres,
+        }
+    }
+
+    fn parse(&self) -> configs::database::MerkleTreeStorage {
+        use configs::database::MerkleTreeStorage as To;
+        match self {
+            Self::Rocksdb => To::RocksDB,
+            Self::Postgres => To::Postgres,
+        }
+    }
+}
+
 impl ProtoRepr for proto::MerkleTree {
     type Type = configs::database::MerkleTreeConfig;
     fn read(&self) -> anyhow::Result<Self::Type> {
@@ -34,6 +54,12 @@ impl ProtoRepr for proto::MerkleTree {
                 .and_then(|x| Ok(proto::MerkleTreeMode::try_from(*x)?))
                 .context("mode")?
                 .parse(),
+            storage: self
+                .storage
+                .map(proto::MerkleTreeStorage::try_from)
+                .transpose()
+                .context("storage")?
+                .map_or_else(Default::default, |storage| storage.parse()),
             multi_get_chunk_size: required(&self.multi_get_chunk_size)
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("multi_get_chunk_size")?,
@@ -48,6 +74,12 @@ impl ProtoRepr for proto::MerkleTree {
             max_l1_batches_per_iter: required(&self.max_l1_batches_per_iter)
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("max_l1_batches_per_iter")?,
+            checkpoint_interval_l1_batches: self
+                .checkpoint_interval_l1_batches
+                .map(|count| NonZeroU32::new(count).context("cannot be 0"))
+                .transpose()
+                .context("checkpoint_interval_l1_batches")?,
+            checkpoint_import_enabled: self.checkpoint_import_enabled.unwrap_or_default(),
         })
     }
 
@@ -55,11 +87,16 @@ impl ProtoRepr for proto::MerkleTree {
         Self {
             path: Some(this.path.clone()),
             mode: Some(proto::MerkleTreeMode::new(&this.mode).into()),
+            storage: Some(proto::MerkleTreeStorage::new(&this.storage).into()),
             multi_get_chunk_size: Some(this.multi_get_chunk_size.try_into().unwrap()),
             block_cache_size_mb: Some(this.block_cache_size_mb.try_into().unwrap()),
             memtable_capacity_mb: Some(this.memtable_capacity_mb.try_into().unwrap()),
             stalled_writes_timeout_sec: Some(this.stalled_writes_timeout_sec),
             max_l1_batches_per_iter: Some(this.max_l1_batches_per_iter.try_into().unwrap()),
+            checkpoint_interval_l1_batches: this
+                .checkpoint_interval_l1_batches
+                .map(NonZeroU32::get),
+            checkpoint_import_enabled: Some(this.checkpoint_import_enabled),
         }
     }
 }
@@ -31,6 +31,15 @@ impl ProtoRepr for proto::ExternalNode {
             main_node_rate_limit_rps: self
                 .main_node_rate_limit_rps
                 .and_then(|a| NonZeroUsize::new(a as usize)),
+            main_node_fallback_urls: self
+                .main_node_fallback_urls
+                .iter()
+                .enumerate()
+                .map(|(i, url)| {
+                    url.parse()
+                        .with_context(|| format!("main_node_fallback_urls[{i}]"))
+                })
+                .collect::<anyhow::Result<_>>()?,
             gateway_url: self
                 .gateway_url
                 .as_ref()
@@ -51,6 +60,11 @@ impl ProtoRepr for proto::ExternalNode {
                 .into(),
             ),
             main_node_rate_limit_rps: this.main_node_rate_limit_rps.map(|a| a.get() as u64),
+            main_node_fallback_urls: this
+                .main_node_fallback_urls
+                .iter()
+                .map(|url| url.expose_str().to_string())
+                .collect(),
             gateway_url: this
                 .gateway_url
                 .as_ref()
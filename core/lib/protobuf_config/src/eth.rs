@@ -213,6 +213,7 @@ impl ProtoRepr for proto::EthWatch {
     fn read(&self) -> anyhow::Result<Self::Type> {
         Ok(Self::Type {
             confirmations_for_eth_event: self.confirmations_for_eth_event,
+            confirmations_for_priority_ops: self.confirmations_for_priority_ops,
             eth_node_poll_interval: *required(&self.eth_node_poll_interval)
                 .context("eth_node_poll_interval")?,
         })
@@ -221,6 +222,7 @@ impl ProtoRepr for proto::EthWatch {
     fn build(this: &Self::Type) -> Self {
         Self {
             confirmations_for_eth_event: this.confirmations_for_eth_event,
+            confirmations_for_priority_ops: this.confirmations_for_priority_ops,
             eth_node_poll_interval: Some(this.eth_node_poll_interval),
         }
     }
@@ -46,6 +46,7 @@ impl ProtoRepr for proto::GeneralConfig {
             ),
             experimental_vm_config: read_optional_repr(&self.experimental_vm),
             prover_job_monitor_config: read_optional_repr(&self.prover_job_monitor),
+            address_policy_config: read_optional_repr(&self.address_policy),
         })
     }
 
@@ -106,6 +107,7 @@ impl ProtoRepr for proto::GeneralConfig {
                 .prover_job_monitor_config
                 .as_ref()
                 .map(ProtoRepr::build),
+            address_policy: this.address_policy_config.as_ref().map(ProtoRepr::build),
         }
     }
 }
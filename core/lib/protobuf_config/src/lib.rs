@@ -4,6 +4,7 @@
 //! * protobuf text format
 //! * protobuf json format
 
+mod address_policy;
 mod api;
 mod base_token_adjuster;
 mod chain;
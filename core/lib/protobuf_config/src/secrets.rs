@@ -6,7 +6,7 @@ use zksync_basic_types::url::SensitiveUrl;
 use zksync_config::configs::{
     consensus::{AttesterSecretKey, ConsensusSecrets, NodeSecretKey, ValidatorSecretKey},
     secrets::Secrets,
-    DatabaseSecrets, L1Secrets,
+    AdminSecrets, DatabaseSecrets, L1Secrets,
 };
 use zksync_protobuf::{required, ProtoRepr};
 
@@ -20,6 +20,7 @@ impl ProtoRepr for proto::Secrets {
             consensus: read_optional_repr(&self.consensus),
             database: read_optional_repr(&self.database),
             l1: read_optional_repr(&self.l1),
+            admin: read_optional_repr(&self.admin),
         })
     }
 
@@ -28,6 +29,7 @@ impl ProtoRepr for proto::Secrets {
             database: this.database.as_ref().map(ProtoRepr::build),
             l1: this.l1.as_ref().map(ProtoRepr::build),
             consensus: this.consensus.as_ref().map(ProtoRepr::build),
+            admin: this.admin.as_ref().map(ProtoRepr::build),
         }
     }
 }
@@ -87,6 +89,24 @@ impl ProtoRepr for proto::L1Secrets {
     }
 }
 
+impl ProtoRepr for proto::AdminSecrets {
+    type Type = AdminSecrets;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            auth_token: required(&self.auth_token)
+                .context("auth_token")?
+                .clone()
+                .into(),
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            auth_token: Some(this.auth_token.expose_secret().clone()),
+        }
+    }
+}
+
 impl ProtoRepr for proto::ConsensusSecrets {
     type Type = ConsensusSecrets;
     fn read(&self) -> anyhow::Result<Self::Type> {
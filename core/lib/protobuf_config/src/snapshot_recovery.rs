@@ -1,11 +1,12 @@
-use std::num::NonZeroUsize;
+use std::num::{NonZeroU64, NonZeroUsize};
 
+use anyhow::Context as _;
 use zksync_basic_types::L1BatchNumber;
 use zksync_config::configs::{
-    snapshot_recovery::{PostgresRecoveryConfig, TreeRecoveryConfig},
+    snapshot_recovery::{L1RecoveryConfig, PostgresRecoveryConfig, TreeRecoveryConfig},
     SnapshotRecoveryConfig,
 };
-use zksync_protobuf::ProtoRepr;
+use zksync_protobuf::{required, ProtoRepr};
 
 use crate::{proto::snapshot_recovery as proto, read_optional_repr};
 
@@ -27,6 +28,33 @@ impl ProtoRepr for proto::Postgres {
     }
 }
 
+impl ProtoRepr for proto::L1Recovery {
+    type Type = L1RecoveryConfig;
+
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            first_l1_block: *required(&self.first_l1_block).context("first_l1_block")?,
+            l1_block_range: self.l1_block_range.and_then(NonZeroU64::new),
+            blob_api_url: self
+                .blob_api_url
+                .as_ref()
+                .map(|url| url.parse().context("blob_api_url"))
+                .transpose()?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            first_l1_block: Some(this.first_l1_block),
+            l1_block_range: this.l1_block_range.map(NonZeroU64::get),
+            blob_api_url: this
+                .blob_api_url
+                .as_ref()
+                .map(|url| url.expose_str().to_string()),
+        }
+    }
+}
+
 impl ProtoRepr for proto::SnapshotRecovery {
     type Type = SnapshotRecoveryConfig;
 
@@ -57,6 +85,13 @@ impl ProtoRepr for proto::SnapshotRecovery {
             postgres: read_optional_repr(&self.postgres).unwrap_or_default(),
             l1_batch: self.l1_batch.map(L1BatchNumber),
             object_store: read_optional_repr(&self.object_store),
+            archive_path: self.archive_path.clone(),
+            l1: self
+                .l1
+                .as_ref()
+                .map(ProtoRepr::read)
+                .transpose()
+                .context("l1")?,
             drop_storage_key_preimages: self
                 .experimental
                 .as_ref()
@@ -94,6 +129,8 @@ impl ProtoRepr for proto::SnapshotRecovery {
             experimental,
             l1_batch: this.l1_batch.map(|a| a.0),
             object_store: this.object_store.as_ref().map(ProtoRepr::build),
+            archive_path: this.archive_path.clone(),
+            l1: this.l1.as_ref().map(ProtoRepr::build),
         }
     }
 }
@@ -54,6 +54,7 @@ fn test_encoding() {
     test_encode_all_formats::<ReprConv<proto::external_price_api_client::ExternalPriceApiClient>>(
         rng,
     );
+    test_encode_all_formats::<ReprConv<proto::address_policy::AddressPolicy>>(rng);
     test_encode_all_formats::<ReprConv<proto::general::GeneralConfig>>(rng);
 }
 
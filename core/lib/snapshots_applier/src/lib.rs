@@ -1,7 +1,8 @@
 //! Logic for applying application-level snapshots to Postgres storage.
 
 use std::{
-    cmp::Ordering, collections::HashMap, fmt, mem, num::NonZeroUsize, sync::Arc, time::Duration,
+    cmp::Ordering, collections::HashMap, fmt, mem, num::NonZeroUsize, ops, path::Path, sync::Arc,
+    time::Duration,
 };
 
 use anyhow::Context as _;
@@ -10,7 +11,7 @@ use serde::Serialize;
 use tokio::sync::{watch, Semaphore};
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal, DalError, SqlxError};
 use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
-use zksync_object_store::{ObjectStore, ObjectStoreError};
+use zksync_object_store::{ObjectStore, ObjectStoreError, SnapshotArchive};
 use zksync_types::{
     api,
     snapshots::{
@@ -24,7 +25,7 @@ use zksync_utils::bytecode::hash_bytecode;
 use zksync_web3_decl::{
     client::{DynClient, L2},
     error::{ClientRpcContext, EnrichedClientError, EnrichedClientResult},
-    jsonrpsee::core::client,
+    jsonrpsee::{core::client, types::error::ErrorCode},
     namespaces::{EnNamespaceClient, SnapshotsNamespaceClient, ZksNamespaceClient},
 };
 
@@ -167,10 +168,21 @@ impl SnapshotsApplierMainNodeClient for Box<DynClient<L2>> {
     async fn fetch_newest_snapshot_l1_batch_number(
         &self,
     ) -> EnrichedClientResult<Option<L1BatchNumber>> {
-        let snapshots = self
-            .get_all_snapshots()
-            .rpc_context("get_all_snapshots")
-            .await?;
+        let snapshots = match self
+            .get_all_snapshots_with_deltas()
+            .rpc_context("get_all_snapshots_with_deltas")
+            .await
+        {
+            Err(err) if is_method_not_found(&err) => {
+                tracing::info!(
+                    "Main node doesn't support delta snapshots; falling back to full snapshots"
+                );
+                self.get_all_snapshots()
+                    .rpc_context("get_all_snapshots")
+                    .await?
+            }
+            res => res?,
+        };
         Ok(snapshots.snapshots_l1_batch_numbers.first().copied())
     }
 
@@ -178,10 +190,20 @@ impl SnapshotsApplierMainNodeClient for Box<DynClient<L2>> {
         &self,
         l1_batch_number: L1BatchNumber,
     ) -> EnrichedClientResult<Option<SnapshotHeader>> {
-        self.get_snapshot_by_l1_batch_number(l1_batch_number)
-            .rpc_context("get_snapshot_by_l1_batch_number")
+        match self
+            .get_snapshot_with_deltas(l1_batch_number)
+            .rpc_context("get_snapshot_with_deltas")
             .with_arg("number", &l1_batch_number)
             .await
+        {
+            Err(err) if is_method_not_found(&err) => {
+                self.get_snapshot_by_l1_batch_number(l1_batch_number)
+                    .rpc_context("get_snapshot_by_l1_batch_number")
+                    .with_arg("number", &l1_batch_number)
+                    .await
+            }
+            res => res,
+        }
     }
 
     async fn fetch_tokens(
@@ -195,6 +217,106 @@ impl SnapshotsApplierMainNodeClient for Box<DynClient<L2>> {
     }
 }
 
+/// Checks whether the error is caused by the main node not supporting the called method (e.g., because
+/// it runs an older server version).
+fn is_method_not_found(err: &EnrichedClientError) -> bool {
+    matches!(
+        err.as_ref(),
+        client::Error::Call(err) if err.code() == ErrorCode::MethodNotFound.code()
+    )
+}
+
+/// [`SnapshotsApplierMainNodeClient`] implementation serving all data from a local snapshot archive
+/// (see [`SnapshotArchive`]), so that recovery from an archive doesn't require access to the main node.
+#[derive(Debug, Clone)]
+pub struct SnapshotArchiveClient {
+    archive: Arc<SnapshotArchive>,
+}
+
+impl SnapshotArchiveClient {
+    /// Opens the archive at the specified path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the archive cannot be opened or is malformed.
+    pub async fn open(archive_path: &Path) -> anyhow::Result<Self> {
+        let archive = SnapshotArchive::open(archive_path).await.with_context(|| {
+            format!(
+                "failed opening snapshot archive at {}",
+                archive_path.display()
+            )
+        })?;
+        Ok(Self {
+            archive: Arc::new(archive),
+        })
+    }
+
+    fn missing_data_error(&self, method: &'static str, message: String) -> EnrichedClientError {
+        let l1_batch_number = self.archive.header().l1_batch_number;
+        let message = format!(
+            "{message}; snapshot archive only contains data for L1 batch #{l1_batch_number}"
+        );
+        EnrichedClientError::custom(message, method)
+    }
+}
+
+#[async_trait]
+impl SnapshotsApplierMainNodeClient for SnapshotArchiveClient {
+    async fn fetch_l1_batch_details(
+        &self,
+        number: L1BatchNumber,
+    ) -> EnrichedClientResult<Option<api::L1BatchDetails>> {
+        let details = &self.archive.recovery_data().l1_batch_details;
+        if details.number != number {
+            let message = format!("L1 batch #{number} is not in the archive");
+            return Err(self.missing_data_error("fetch_l1_batch_details", message));
+        }
+        Ok(Some(details.clone()))
+    }
+
+    async fn fetch_l2_block_details(
+        &self,
+        number: L2BlockNumber,
+    ) -> EnrichedClientResult<Option<api::BlockDetails>> {
+        let details = &self.archive.recovery_data().l2_block_details;
+        if details.number != number {
+            let message = format!("L2 block #{number} is not in the archive");
+            return Err(self.missing_data_error("fetch_l2_block_details", message));
+        }
+        Ok(Some(details.clone()))
+    }
+
+    async fn fetch_newest_snapshot_l1_batch_number(
+        &self,
+    ) -> EnrichedClientResult<Option<L1BatchNumber>> {
+        Ok(Some(self.archive.header().l1_batch_number))
+    }
+
+    async fn fetch_snapshot(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> EnrichedClientResult<Option<SnapshotHeader>> {
+        let header = self
+            .archive
+            .headers()
+            .iter()
+            .find(|header| header.l1_batch_number == l1_batch_number);
+        Ok(header.cloned())
+    }
+
+    async fn fetch_tokens(
+        &self,
+        at_l2_block: L2BlockNumber,
+    ) -> EnrichedClientResult<Vec<TokenInfo>> {
+        let recovery_data = self.archive.recovery_data();
+        if recovery_data.l2_block_details.number != at_l2_block {
+            let message = format!("tokens at L2 block #{at_l2_block} are not in the archive");
+            return Err(self.missing_data_error("fetch_tokens", message));
+        }
+        Ok(recovery_data.tokens.clone())
+    }
+}
+
 /// Reported status of the snapshot recovery progress.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum RecoveryCompletionStatus {
@@ -280,6 +402,35 @@ impl SnapshotsApplierTask {
         }
     }
 
+    /// Creates a task recovering from a local snapshot archive at the specified path (see [`SnapshotArchive`]).
+    /// The archive is used as the source of all recovery data instead of the object store and the main node
+    /// (see [`SnapshotArchiveClient`]). If the snapshot L1 batch is not specified explicitly, the snapshot stored
+    /// in the archive is used.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the archive cannot be opened or is malformed.
+    pub async fn from_archive(
+        config: SnapshotsApplierConfig,
+        connection_pool: ConnectionPool<Core>,
+        archive_path: &Path,
+    ) -> anyhow::Result<Self> {
+        let client = SnapshotArchiveClient::open(archive_path).await?;
+        let archive = client.archive.clone();
+        tracing::info!(
+            "Using snapshot archive at {} containing snapshot for L1 batch #{} (snapshots in chain: {})",
+            archive_path.display(),
+            archive.header().l1_batch_number,
+            archive.headers().len()
+        );
+        Ok(Self::new(
+            config,
+            connection_pool,
+            Box::new(client),
+            archive,
+        ))
+    }
+
     /// Checks whether the snapshot recovery is already completed.
     ///
     /// Returns `None` if no snapshot recovery information is detected in the DB.
@@ -407,13 +558,91 @@ impl SnapshotsApplierTask {
     }
 }
 
-/// Strategy determining how snapshot recovery should proceed.
+/// Snapshot in a chain of snapshots being recovered.
 #[derive(Debug, Clone, Copy)]
+struct SnapshotChainItem {
+    l1_batch_number: L1BatchNumber,
+    version: SnapshotVersion,
+    chunk_count: usize,
+}
+
+/// Chain of snapshots to recover. The first snapshot in the chain is a full snapshot; each subsequent one
+/// is a delta snapshot on top of the previous one. The last snapshot is the one recovery is performed to.
+///
+/// Storage logs chunks of all snapshots in the chain are numbered sequentially starting from the full snapshot;
+/// e.g., if the full snapshot has 10 chunks, the first chunk of the following delta snapshot has ID 10.
+#[derive(Debug, Clone)]
+struct SnapshotChain(Vec<SnapshotChainItem>);
+
+impl SnapshotChain {
+    async fn fetch(
+        main_node_client: &dyn SnapshotsApplierMainNodeClient,
+        header: &SnapshotHeader,
+    ) -> Result<Self, SnapshotsApplierError> {
+        let mut items = vec![SnapshotChainItem {
+            l1_batch_number: header.l1_batch_number,
+            version: SnapshotRecoveryStrategy::check_snapshot_version(header.version)?,
+            chunk_count: header.storage_logs_chunks.len(),
+        }];
+        let mut base_l1_batch_number = header.base_l1_batch_number;
+        while let Some(l1_batch_number) = base_l1_batch_number {
+            // `unwrap()` is safe: `items` is non-empty
+            let last_l1_batch_number = items.last().unwrap().l1_batch_number;
+            if l1_batch_number >= last_l1_batch_number {
+                let err = anyhow::anyhow!(
+                    "snapshot for L1 batch #{last_l1_batch_number} has base L1 batch #{l1_batch_number} \
+                     which is not older than the snapshot"
+                );
+                return Err(err.into());
+            }
+
+            let base = main_node_client
+                .fetch_snapshot(l1_batch_number)
+                .await?
+                .with_context(|| {
+                    format!(
+                        "base snapshot for L1 batch #{l1_batch_number} is not present on main node"
+                    )
+                })?;
+            tracing::info!(
+                "Found base snapshot for L1 batch #{l1_batch_number}, version {version}, \
+                 storage logs are divided into {chunk_count} chunk(s)",
+                version = base.version,
+                chunk_count = base.storage_logs_chunks.len()
+            );
+            items.push(SnapshotChainItem {
+                l1_batch_number,
+                version: SnapshotRecoveryStrategy::check_snapshot_version(base.version)?,
+                chunk_count: base.storage_logs_chunks.len(),
+            });
+            base_l1_batch_number = base.base_l1_batch_number;
+        }
+        items.reverse();
+        Ok(Self(items))
+    }
+
+    fn total_chunk_count(&self) -> usize {
+        self.0.iter().map(|item| item.chunk_count).sum()
+    }
+
+    /// Returns snapshots in the chain together with the range of global chunk IDs for each snapshot.
+    fn segments(&self) -> impl Iterator<Item = (usize, SnapshotChainItem, ops::Range<usize>)> + '_ {
+        let mut start = 0;
+        self.0.iter().enumerate().map(move |(i, item)| {
+            let range = start..start + item.chunk_count;
+            start = range.end;
+            (i, *item, range)
+        })
+    }
+}
+
+/// Strategy determining how snapshot recovery should proceed.
+#[derive(Debug, Clone)]
 enum SnapshotRecoveryStrategy {
     /// Snapshot recovery should proceed from scratch with the specified params.
-    New(SnapshotVersion),
+    New(SnapshotChain),
     /// Snapshot recovery should continue with the specified params.
-    Resumed(SnapshotVersion),
+    Resumed(SnapshotChain),
     /// Snapshot recovery has already been completed.
     Completed,
 }
@@ -446,11 +675,20 @@ impl SnapshotRecoveryStrategy {
                 })?;
             // Old snapshots can theoretically be removed by the node, but in this case the snapshot data may be removed as well,
             // so returning an error looks appropriate here.
-            let snapshot_version = Self::check_snapshot_version(snapshot_header.version)?;
+            let chain = SnapshotChain::fetch(main_node_client, &snapshot_header).await?;
+            let expected_chunk_count = applied_snapshot_status.storage_logs_chunks_processed.len();
+            if chain.total_chunk_count() != expected_chunk_count {
+                let err = anyhow::anyhow!(
+                    "snapshot chain for L1 batch #{l1_batch_number} has {} storage logs chunks in total, \
+                     while the recovery status contains {expected_chunk_count}; the snapshot may have been changed",
+                    chain.total_chunk_count()
+                );
+                return Err(SnapshotsApplierError::Fatal(err));
+            }
 
             let latency = latency.observe();
             tracing::info!("Re-initialized snapshots applier after reset/failure in {latency:?}");
-            Ok((Self::Resumed(snapshot_version), applied_snapshot_status))
+            Ok((Self::Resumed(chain), applied_snapshot_status))
         } else {
             let is_genesis_needed = storage.blocks_dal().is_genesis_needed().await?;
             if !is_genesis_needed {
@@ -460,7 +698,7 @@ impl SnapshotRecoveryStrategy {
                 return Err(SnapshotsApplierError::Fatal(err));
             }
 
-            let (recovery_status, snapshot_version) =
+            let (recovery_status, chain) =
                 Self::create_fresh_recovery_status(main_node_client, snapshot_l1_batch).await?;
 
             let storage_logs_count = storage
@@ -478,14 +716,14 @@ impl SnapshotRecoveryStrategy {
 
             let latency = latency.observe();
             tracing::info!("Initialized fresh snapshots applier in {latency:?}");
-            Ok((Self::New(snapshot_version), recovery_status))
+            Ok((Self::New(chain), recovery_status))
         }
     }
 
     async fn create_fresh_recovery_status(
         main_node_client: &dyn SnapshotsApplierMainNodeClient,
         snapshot_l1_batch: Option<L1BatchNumber>,
-    ) -> Result<(SnapshotRecoveryStatus, SnapshotVersion), SnapshotsApplierError> {
+    ) -> Result<(SnapshotRecoveryStatus, SnapshotChain), SnapshotsApplierError> {
         let l1_batch_number = match snapshot_l1_batch {
             Some(num) => num,
             None => main_node_client
@@ -505,7 +743,14 @@ impl SnapshotRecoveryStrategy {
             version = snapshot.version,
             chunk_count = snapshot.storage_logs_chunks.len()
         );
-        let snapshot_version = Self::check_snapshot_version(snapshot.version)?;
+        let chain = SnapshotChain::fetch(main_node_client, &snapshot).await?;
+        if chain.0.len() > 1 {
+            tracing::info!(
+                "Snapshot is a delta snapshot; recovering from a chain of {} snapshots: {:?}",
+                chain.0.len(),
+                chain.0
+            );
+        }
 
         let l1_batch = main_node_client
             .fetch_l1_batch_details(l1_batch_number)
@@ -541,9 +786,9 @@ impl SnapshotRecoveryStrategy {
             l2_block_timestamp: l2_block.base.timestamp,
             l2_block_hash,
             protocol_version,
-            storage_logs_chunks_processed: vec![false; snapshot.storage_logs_chunks.len()],
+            storage_logs_chunks_processed: vec![false; chain.total_chunk_count()],
         };
-        Ok((status, snapshot_version))
+        Ok((status, chain))
     }
 
     fn check_snapshot_version(raw_version: u16) -> anyhow::Result<SnapshotVersion> {
@@ -593,17 +838,17 @@ impl StorageLogs {
         }
     }
 
-    /// Performs basic sanity check for a storage logs chunk.
-    fn validate(&self, snapshot_status: &SnapshotRecoveryStatus) -> anyhow::Result<()> {
+    /// Performs basic sanity check for a storage logs chunk of a snapshot for the specified L1 batch.
+    fn validate(&self, l1_batch_number: L1BatchNumber) -> anyhow::Result<()> {
         match self {
-            Self::V0(logs) => Self::validate_inner(logs, snapshot_status),
-            Self::V1(logs) => Self::validate_inner(logs, snapshot_status),
+            Self::V0(logs) => Self::validate_inner(logs, l1_batch_number),
+            Self::V1(logs) => Self::validate_inner(logs, l1_batch_number),
         }
     }
 
     fn validate_inner<K: fmt::Debug>(
         storage_logs: &[SnapshotStorageLog<K>],
-        snapshot_status: &SnapshotRecoveryStatus,
+        l1_batch_number: L1BatchNumber,
     ) -> anyhow::Result<()> {
         for log in storage_logs {
             anyhow::ensure!(
@@ -611,7 +856,7 @@ impl StorageLogs {
                 "invalid storage log with zero enumeration_index: {log:?}"
             );
             anyhow::ensure!(
-                log.l1_batch_number_of_initial_write <= snapshot_status.l1_batch_number,
+                log.l1_batch_number_of_initial_write <= l1_batch_number,
                 "invalid storage log with `l1_batch_number_of_initial_write` from the future: {log:?}"
             );
         }
@@ -651,7 +896,7 @@ struct SnapshotsApplier<'a> {
     blob_store: &'a dyn ObjectStore,
     applied_snapshot_status: SnapshotRecoveryStatus,
     health_updater: &'a HealthUpdater,
-    snapshot_version: SnapshotVersion,
+    chain: SnapshotChain,
     max_concurrency: usize,
     drop_storage_key_preimages: bool,
     factory_deps_recovered: bool,
@@ -684,10 +929,10 @@ impl<'a> SnapshotsApplier<'a> {
         )
         .await?;
         tracing::info!("Chosen snapshot recovery strategy: {strategy:?} with status: {applied_snapshot_status:?}");
-        let (created_from_scratch, snapshot_version) = match strategy {
+        let (created_from_scratch, chain) = match &strategy {
             SnapshotRecoveryStrategy::Completed => return Ok((strategy, applied_snapshot_status)),
-            SnapshotRecoveryStrategy::New(version) => (true, version),
-            SnapshotRecoveryStrategy::Resumed(version) => (false, version),
+            SnapshotRecoveryStrategy::New(chain) => (true, chain.clone()),
+            SnapshotRecoveryStrategy::Resumed(chain) => (false, chain.clone()),
         };
 
         let mut this = Self {
@@ -696,7 +941,7 @@ impl<'a> SnapshotsApplier<'a> {
             blob_store: task.blob_store.as_ref(),
             applied_snapshot_status,
             health_updater,
-            snapshot_version,
+            chain,
             max_concurrency: task.config.max_concurrency.get(),
             drop_storage_key_preimages: task.drop_storage_key_preimages,
             factory_deps_recovered: !created_from_scratch,
@@ -782,35 +1027,41 @@ impl<'a> SnapshotsApplier<'a> {
     ) -> Result<(), SnapshotsApplierError> {
         let latency = METRICS.initial_stage_duration[&InitialStage::ApplyFactoryDeps].start();
 
-        tracing::debug!("Fetching factory dependencies from object store");
-        let l1_batch_number = self.applied_snapshot_status.l1_batch_number;
-        let factory_deps: SnapshotFactoryDependencies =
-            self.blob_store.get(l1_batch_number).await.map_err(|err| {
-                let context = format!(
-                    "cannot fetch factory deps for L1 batch #{l1_batch_number} from object store"
-                );
-                SnapshotsApplierError::object_store(err, context)
-            })?;
-        tracing::debug!(
-            "Fetched {} factory dependencies from object store",
-            factory_deps.factory_deps.len()
-        );
+        // Each snapshot in the chain contains factory deps added since its base snapshot, so we need to recover
+        // factory deps from all of them. Duplicate bytecodes are ignored by Postgres.
+        for item in &self.chain.0 {
+            let l1_batch_number = item.l1_batch_number;
+            tracing::debug!(
+                "Fetching factory dependencies for L1 batch #{l1_batch_number} from object store"
+            );
+            let factory_deps: SnapshotFactoryDependencies =
+                self.blob_store.get(l1_batch_number).await.map_err(|err| {
+                    let context = format!(
+                        "cannot fetch factory deps for L1 batch #{l1_batch_number} from object store"
+                    );
+                    SnapshotsApplierError::object_store(err, context)
+                })?;
+            tracing::debug!(
+                "Fetched {} factory dependencies from object store",
+                factory_deps.factory_deps.len()
+            );
 
-        // we cannot insert all factory deps because of field size limit triggered by UNNEST
-        // in underlying query, see `https://www.postgresql.org/docs/current/limits.html`
-        // there were around 100 thousand contracts on mainnet, where this issue first manifested
-        for chunk in factory_deps.factory_deps.chunks(1000) {
-            let chunk_deps_hashmap: HashMap<H256, Vec<u8>> = chunk
-                .iter()
-                .map(|dep| (hash_bytecode(&dep.bytecode.0), dep.bytecode.0.clone()))
-                .collect();
-            storage
-                .factory_deps_dal()
-                .insert_factory_deps(
-                    self.applied_snapshot_status.l2_block_number,
-                    &chunk_deps_hashmap,
-                )
-                .await?;
+            // we cannot insert all factory deps because of field size limit triggered by UNNEST
+            // in underlying query, see `https://www.postgresql.org/docs/current/limits.html`
+            // there were around 100 thousand contracts on mainnet, where this issue first manifested
+            for chunk in factory_deps.factory_deps.chunks(1000) {
+                let chunk_deps_hashmap: HashMap<H256, Vec<u8>> = chunk
+                    .iter()
+                    .map(|dep| (hash_bytecode(&dep.bytecode.0), dep.bytecode.0.clone()))
+                    .collect();
+                storage
+                    .factory_deps_dal()
+                    .insert_factory_deps(
+                        self.applied_snapshot_status.l2_block_number,
+                        &chunk_deps_hashmap,
+                    )
+                    .await?;
+            }
         }
 
         let latency = latency.observe();
@@ -860,33 +1111,59 @@ impl<'a> SnapshotsApplier<'a> {
         Ok(())
     }
 
-    #[tracing::instrument(level = "debug", err, skip(self, semaphore))]
+    async fn upsert_delta_chunk(
+        &self,
+        storage_logs: &[SnapshotStorageLog],
+        storage: &mut Connection<'_, Core>,
+    ) -> Result<(), SnapshotsApplierError> {
+        storage
+            .storage_logs_dal()
+            .upsert_storage_logs_from_snapshot(
+                self.applied_snapshot_status.l2_block_number,
+                storage_logs,
+            )
+            .await?;
+        storage
+            .storage_logs_dedup_dal()
+            .upsert_initial_writes_from_snapshot(storage_logs)
+            .await?;
+        Ok(())
+    }
+
+    /// Recovers a single storage logs chunk. `chunk_id` is the global ID of the chunk in the snapshot chain,
+    /// and `local_chunk_id` is the ID of the chunk in the snapshot it belongs to.
+    #[tracing::instrument(level = "debug", err, skip(self, semaphore, snapshot))]
     async fn recover_storage_logs_single_chunk(
         &self,
         semaphore: &Semaphore,
+        snapshot: SnapshotChainItem,
+        is_delta: bool,
         chunk_id: u64,
+        local_chunk_id: u64,
     ) -> Result<(), SnapshotsApplierError> {
         // `unwrap()` is safe: the semaphore is never closed
         let _permit = semaphore.acquire().await.unwrap();
 
-        tracing::info!("Processing storage logs chunk {chunk_id}");
+        tracing::info!(
+            "Processing storage logs chunk {chunk_id} (chunk {local_chunk_id} of snapshot for L1 batch #{})",
+            snapshot.l1_batch_number
+        );
         let latency =
             METRICS.storage_logs_chunks_duration[&StorageLogsChunksStage::LoadFromGcs].start();
 
         let storage_key = SnapshotStorageLogsStorageKey {
-            chunk_id,
-            l1_batch_number: self.applied_snapshot_status.l1_batch_number,
+            chunk_id: local_chunk_id,
+            l1_batch_number: snapshot.l1_batch_number,
         };
-        let mut storage_logs =
-            StorageLogs::load(self.blob_store, storage_key, self.snapshot_version)
-                .await
-                .map_err(|err| {
-                    let context =
-                        format!("cannot fetch storage logs {storage_key:?} from object store");
-                    SnapshotsApplierError::object_store(err, context)
-                })?;
+        let mut storage_logs = StorageLogs::load(self.blob_store, storage_key, snapshot.version)
+            .await
+            .map_err(|err| {
+                let context =
+                    format!("cannot fetch storage logs {storage_key:?} from object store");
+                SnapshotsApplierError::object_store(err, context)
+            })?;
 
-        storage_logs.validate(&self.applied_snapshot_status)?;
+        storage_logs.validate(snapshot.l1_batch_number)?;
         if self.drop_storage_key_preimages {
             storage_logs.drop_key_preimages();
         }
@@ -907,11 +1184,18 @@ impl<'a> SnapshotsApplier<'a> {
 
         tracing::info!("Loading {} storage logs into Postgres", storage_logs.len());
 
-        self.insert_storage_logs_chunk(&storage_logs, &mut storage_transaction)
-            .await?;
-        let storage_logs = storage_logs.without_preimages();
-        self.insert_initial_writes_chunk(&storage_logs, &mut storage_transaction)
-            .await?;
+        if is_delta {
+            // Keys in a delta snapshot may be already recovered from previous snapshots in the chain.
+            let storage_logs = storage_logs.without_preimages();
+            self.upsert_delta_chunk(&storage_logs, &mut storage_transaction)
+                .await?;
+        } else {
+            self.insert_storage_logs_chunk(&storage_logs, &mut storage_transaction)
+                .await?;
+            let storage_logs = storage_logs.without_preimages();
+            self.insert_initial_writes_chunk(&storage_logs, &mut storage_transaction)
+                .await?;
+        }
 
         storage_transaction
             .snapshot_recovery_dal()
@@ -937,23 +1221,34 @@ impl<'a> SnapshotsApplier<'a> {
         );
         let semaphore = Semaphore::new(effective_concurrency);
 
-        let tasks = self
-            .applied_snapshot_status
-            .storage_logs_chunks_processed
-            .iter()
-            .enumerate()
-            .filter(|(_, is_processed)| !**is_processed)
-            .map(|(chunk_id, _)| {
-                self.recover_storage_logs_single_chunk(&semaphore, chunk_id as u64)
-            });
-        let job_completion = futures::future::try_join_all(tasks);
-
-        tokio::select! {
-            res = job_completion => {
-                res?;
-            },
-            _ = stop_receiver.changed() => {
-                return Err(SnapshotsApplierError::Canceled);
+        // Snapshots in the chain must be applied sequentially, since delta snapshots overwrite storage logs
+        // from the previous snapshots. Chunks within a single snapshot are independent and are recovered concurrently.
+        for (i, snapshot, chunk_ids) in self.chain.segments() {
+            let is_delta = i > 0;
+            let processed_chunks =
+                &self.applied_snapshot_status.storage_logs_chunks_processed[chunk_ids.clone()];
+            let tasks = processed_chunks
+                .iter()
+                .enumerate()
+                .filter(|(_, is_processed)| !**is_processed)
+                .map(|(local_chunk_id, _)| {
+                    self.recover_storage_logs_single_chunk(
+                        &semaphore,
+                        snapshot,
+                        is_delta,
+                        (chunk_ids.start + local_chunk_id) as u64,
+                        local_chunk_id as u64,
+                    )
+                });
+            let job_completion = futures::future::try_join_all(tasks);
+
+            tokio::select! {
+                res = job_completion => {
+                    res?;
+                },
+                _ = stop_receiver.changed() => {
+                    return Err(SnapshotsApplierError::Canceled);
+                }
             }
         }
 
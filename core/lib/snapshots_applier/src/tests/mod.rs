@@ -9,12 +9,15 @@ use assert_matches::assert_matches;
 use test_casing::test_casing;
 use tokio::sync::Barrier;
 use zksync_health_check::CheckHealth;
-use zksync_object_store::MockObjectStore;
+use zksync_object_store::{MockObjectStore, SnapshotRecoveryData};
 use zksync_types::{
     api::{BlockDetails, L1BatchDetails},
     block::L1BatchHeader,
-    get_code_key, L1BatchNumber, ProtocolVersion, ProtocolVersionId,
+    get_code_key,
+    snapshots::{AllSnapshots, SnapshotFactoryDependency},
+    L1BatchNumber, ProtocolVersion, ProtocolVersionId,
 };
+use zksync_web3_decl::client::MockClient;
 
 use self::utils::{
     mock_l2_block_header, mock_recovery_status, mock_snapshot_header, mock_tokens, prepare_clients,
@@ -691,3 +694,231 @@ async fn snapshot_applier_can_be_canceled() {
     assert!(result.canceled);
     assert!(!result.done_work);
 }
+
+#[tokio::test]
+async fn recovering_from_snapshot_chain() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut expected_status = mock_recovery_status();
+    let base_l1_batch_number = expected_status.l1_batch_number - 23;
+    let base_logs = random_storage_logs::<H256>(base_l1_batch_number, 200);
+
+    // The delta snapshot updates some of the existing keys and inserts new ones.
+    let updated_logs = base_logs.iter().step_by(4).map(|log| SnapshotStorageLog {
+        value: H256::random(),
+        ..log.clone()
+    });
+    let new_logs = random_storage_logs::<H256>(expected_status.l1_batch_number, 50)
+        .into_iter()
+        .map(|log| SnapshotStorageLog {
+            enumeration_index: log.enumeration_index + base_logs.len() as u64,
+            ..log
+        });
+    let delta_logs: Vec<_> = updated_logs.chain(new_logs).collect();
+    let (object_store, mut client) = prepare_clients(&expected_status, &delta_logs).await;
+
+    let base_chunk_count = 3;
+    for (chunk_id, chunk) in base_logs.chunks(70).enumerate() {
+        let chunk_key = SnapshotStorageLogsStorageKey {
+            l1_batch_number: base_l1_batch_number,
+            chunk_id: chunk_id as u64,
+        };
+        let chunk = SnapshotStorageLogsChunk {
+            storage_logs: chunk.to_vec(),
+        };
+        object_store.put(chunk_key, &chunk).await.unwrap();
+    }
+    let base_factory_dep_bytes: Vec<u8> = (32..64).collect();
+    let base_factory_deps = SnapshotFactoryDependencies {
+        factory_deps: vec![SnapshotFactoryDependency {
+            bytecode: base_factory_dep_bytes.clone().into(),
+        }],
+    };
+    object_store
+        .put(base_l1_batch_number, &base_factory_deps)
+        .await
+        .unwrap();
+
+    let base_status = SnapshotRecoveryStatus {
+        l1_batch_number: base_l1_batch_number,
+        storage_logs_chunks_processed: vec![true; base_chunk_count],
+        ..expected_status.clone()
+    };
+    client
+        .fetch_snapshot_responses
+        .insert(base_l1_batch_number, mock_snapshot_header(1, &base_status));
+    client
+        .fetch_newest_snapshot_response
+        .as_mut()
+        .unwrap()
+        .base_l1_batch_number = Some(base_l1_batch_number);
+
+    let task = SnapshotsApplierTask::new(
+        SnapshotsApplierConfig::for_tests(),
+        pool.clone(),
+        Box::new(client),
+        object_store,
+    );
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let stats = task.run(stop_receiver).await.unwrap();
+    assert!(stats.done_work);
+
+    let mut storage = pool.connection().await.unwrap();
+    let status = storage
+        .snapshot_recovery_dal()
+        .get_applied_snapshot_status()
+        .await
+        .unwrap()
+        .expect("no recovery status");
+    expected_status.storage_logs_chunks_processed =
+        vec![true; base_chunk_count + expected_status.storage_logs_chunks_processed.len()];
+    assert_eq!(status, expected_status);
+
+    // Logs from the delta snapshot must override logs from the base one.
+    let mut expected_logs: HashMap<_, _> =
+        base_logs.into_iter().map(|log| (log.key, log)).collect();
+    expected_logs.extend(delta_logs.into_iter().map(|log| (log.key, log)));
+    assert_eq!(expected_logs.len(), 250);
+
+    let all_initial_writes = storage
+        .storage_logs_dedup_dal()
+        .dump_all_initial_writes_for_tests()
+        .await;
+    assert_eq!(all_initial_writes.len(), expected_logs.len());
+    for initial_write in all_initial_writes {
+        let log = &expected_logs[&initial_write.hashed_key];
+        assert_eq!(
+            initial_write.l1_batch_number,
+            log.l1_batch_number_of_initial_write
+        );
+        assert_eq!(initial_write.index, log.enumeration_index);
+    }
+
+    let all_storage_logs = storage
+        .storage_logs_dal()
+        .dump_all_storage_logs_for_tests()
+        .await;
+    assert_eq!(all_storage_logs.len(), expected_logs.len());
+    for db_log in all_storage_logs {
+        let expected_log = &expected_logs[&db_log.hashed_key];
+        assert_eq!(db_log.value, expected_log.value);
+        assert_eq!(db_log.l2_block_number, expected_status.l2_block_number);
+    }
+
+    // Factory deps from all snapshots in the chain must be recovered.
+    let base_bytecode_hash = hash_bytecode(&base_factory_dep_bytes);
+    let base_bytecode = storage
+        .factory_deps_dal()
+        .get_sealed_factory_dep(base_bytecode_hash)
+        .await
+        .unwrap();
+    assert_eq!(base_bytecode, Some(base_factory_dep_bytes));
+}
+
+#[tokio::test]
+async fn recovering_from_snapshot_archive() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let expected_status = mock_recovery_status();
+    let tokens = mock_tokens();
+    let mut storage_logs = random_storage_logs::<H256>(expected_status.l1_batch_number, 200);
+    for token in &tokens {
+        if token.l2_address.is_zero() {
+            continue;
+        }
+        storage_logs.push(SnapshotStorageLog {
+            key: get_code_key(&token.l2_address).hashed_key(),
+            value: H256::random(),
+            l1_batch_number_of_initial_write: expected_status.l1_batch_number,
+            enumeration_index: storage_logs.len() as u64 + 1,
+        });
+    }
+    let (object_store, mut client) = prepare_clients(&expected_status, &storage_logs).await;
+    client.tokens_response = tokens;
+
+    let header = client.fetch_newest_snapshot_response.take().unwrap();
+    object_store
+        .put(expected_status.l1_batch_number, &header)
+        .await
+        .unwrap();
+    let recovery_data = SnapshotRecoveryData {
+        l1_batch_details: client.fetch_l1_batch_responses[&expected_status.l1_batch_number].clone(),
+        l2_block_details: client.fetch_l2_block_responses[&expected_status.l2_block_number].clone(),
+        tokens: client.tokens_response.clone(),
+    };
+    let dir = tempfile::TempDir::new().unwrap();
+    let archive_path = dir.path().join("snapshot.bin");
+    SnapshotArchive::export(
+        object_store.as_ref(),
+        expected_status.l1_batch_number,
+        recovery_data,
+        &archive_path,
+    )
+    .await
+    .unwrap();
+
+    // The main node isn't queried at all; all recovery data is taken from the archive.
+    let task = SnapshotsApplierTask::from_archive(
+        SnapshotsApplierConfig::for_tests(),
+        pool.clone(),
+        &archive_path,
+    )
+    .await
+    .unwrap();
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let stats = task.run(stop_receiver).await.unwrap();
+    assert!(stats.done_work);
+
+    let archive_client = SnapshotArchiveClient::open(&archive_path).await.unwrap();
+    let mut storage = pool.connection().await.unwrap();
+    let completion_status =
+        SnapshotsApplierTask::is_recovery_completed(&mut storage, &archive_client)
+            .await
+            .unwrap();
+    assert_eq!(completion_status, RecoveryCompletionStatus::Completed);
+    drop(storage);
+
+    let mut storage = pool.connection().await.unwrap();
+    let status = storage
+        .snapshot_recovery_dal()
+        .get_applied_snapshot_status()
+        .await
+        .unwrap()
+        .expect("no recovery status");
+    assert_eq!(status, expected_status);
+
+    let all_storage_logs = storage
+        .storage_logs_dal()
+        .dump_all_storage_logs_for_tests()
+        .await;
+    assert_eq!(all_storage_logs.len(), storage_logs.len());
+    let all_tokens = storage
+        .tokens_web3_dal()
+        .get_all_tokens(None)
+        .await
+        .unwrap();
+    assert_eq!(all_tokens.len(), client.tokens_response.len());
+}
+
+#[test_casing(2, [false, true])]
+#[tokio::test]
+async fn main_node_client_fetches_delta_snapshots(supports_deltas: bool) {
+    let mut client = MockClient::builder(L2::default()).method("snapshots_getAllSnapshots", || {
+        Ok(AllSnapshots {
+            snapshots_l1_batch_numbers: vec![L1BatchNumber(5)],
+        })
+    });
+    if supports_deltas {
+        client = client.method("snapshots_getAllSnapshotsWithDeltas", || {
+            Ok(AllSnapshots {
+                snapshots_l1_batch_numbers: vec![L1BatchNumber(7), L1BatchNumber(5)],
+            })
+        });
+    }
+    let client: Box<DynClient<L2>> = Box::new(client.build());
+
+    let newest_snapshot = client
+        .fetch_newest_snapshot_l1_batch_number()
+        .await
+        .unwrap();
+    let expected_snapshot = if supports_deltas { 7 } else { 5 };
+    assert_eq!(newest_snapshot, Some(L1BatchNumber(expected_snapshot)));
+}
@@ -53,6 +53,8 @@ pub(super) struct MockMainNodeClient {
     pub fetch_l1_batch_responses: HashMap<L1BatchNumber, api::L1BatchDetails>,
     pub fetch_l2_block_responses: HashMap<L2BlockNumber, api::BlockDetails>,
     pub fetch_newest_snapshot_response: Option<SnapshotHeader>,
+    /// Headers of other snapshots (e.g., bases of delta snapshots) keyed by the L1 batch number.
+    pub fetch_snapshot_responses: HashMap<L1BatchNumber, SnapshotHeader>,
     pub tokens_response: Vec<TokenInfo>,
     pub tokens_response_error: Arc<RwLock<Option<EnrichedClientError>>>,
 }
@@ -98,10 +100,14 @@ impl SnapshotsApplierMainNodeClient for MockMainNodeClient {
         &self,
         l1_batch_number: L1BatchNumber,
     ) -> EnrichedClientResult<Option<SnapshotHeader>> {
-        Ok(self
+        let newest_snapshot = self
             .fetch_newest_snapshot_response
             .clone()
-            .filter(|response| response.l1_batch_number == l1_batch_number))
+            .filter(|response| response.l1_batch_number == l1_batch_number);
+        Ok(
+            newest_snapshot
+                .or_else(|| self.fetch_snapshot_responses.get(&l1_batch_number).cloned()),
+        )
     }
 
     async fn fetch_tokens(
@@ -284,6 +290,7 @@ pub(super) fn mock_snapshot_header(
         version,
         l1_batch_number: status.l1_batch_number,
         l2_block_number: status.l2_block_number,
+        base_l1_batch_number: None,
         storage_logs_chunks: (0..status.storage_logs_chunks_processed.len() as u64)
             .map(|chunk_id| SnapshotStorageLogsChunkMetadata {
                 chunk_id,
@@ -479,6 +479,14 @@ impl<CF: NamedColumnFamily> RocksDB<CF> {
             .unwrap_or(0)
     }
 
+    /// Creates a consistent point-in-time checkpoint of the database at the specified path. SST files are hard-linked
+    /// if the checkpoint is located on the same filesystem as the database, and copied otherwise.
+    /// The path must not exist.
+    pub fn create_checkpoint(&self, path: &Path) -> Result<(), rocksdb::Error> {
+        let checkpoint = rocksdb::checkpoint::Checkpoint::new(&self.inner.db)?;
+        checkpoint.create_checkpoint(path)
+    }
+
     pub fn multi_get<K, I>(&self, keys: I) -> Vec<Result<Option<Vec<u8>>, rocksdb::Error>>
     where
         K: AsRef<[u8]>,
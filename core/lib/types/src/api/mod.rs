@@ -16,6 +16,7 @@ use crate::{protocol_version::L1VerifierConfig, Address, L2BlockNumber, Protocol
 
 pub mod en;
 pub mod state_override;
+pub mod trace;
 
 /// Block Number
 #[derive(Copy, Clone, Debug, PartialEq, Display)]
@@ -768,6 +769,43 @@ pub struct L1BatchDetails {
     pub base: BlockDetailsBase,
 }
 
+/// Stage of the L1 batch lifecycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum L1BatchStage {
+    /// Batch is sealed by the state keeper.
+    Sealed,
+    /// Batch is committed on L1.
+    Committed,
+    /// Batch proof is verified on L1.
+    Proven,
+    /// Batch is executed on L1.
+    Executed,
+}
+
+/// Hashes of confirmed L1 transactions that committed, proved and executed an L1 batch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct L1BatchEthTxs {
+    pub number: L1BatchNumber,
+    pub timestamp: u64,
+    pub commit_tx_hash: Option<H256>,
+    pub prove_tx_hash: Option<H256>,
+    pub execute_tx_hash: Option<H256>,
+}
+
+impl L1BatchEthTxs {
+    /// Returns the hash of the L1 transaction that moved the batch to the specified stage.
+    /// Always returns `None` for [`L1BatchStage::Sealed`].
+    pub fn tx_hash(&self, stage: L1BatchStage) -> Option<H256> {
+        match stage {
+            L1BatchStage::Sealed => None,
+            L1BatchStage::Committed => self.commit_tx_hash,
+            L1BatchStage::Proven => self.prove_tx_hash,
+            L1BatchStage::Executed => self.execute_tx_hash,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct StorageProof {
@@ -777,11 +815,37 @@ pub struct StorageProof {
     pub index: u64,
 }
 
+/// Compact proof for multiple storage slots sharing hashes of common Merkle tree subtrees.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageMultiProof {
+    /// Merkle path length for each proven storage slot. For `TREE_DEPTH - path_length` bottom-most tree levels,
+    /// the sibling subtrees of the slot are empty, so their hashes are omitted.
+    pub path_lengths: Vec<u16>,
+    /// Hashes of sibling subtrees that cannot be computed from the proven slots, ordered by the tree level
+    /// (starting from the leaf level) and then by the hashed key of the proven subtree.
+    pub hashes: Vec<H256>,
+}
+
+/// Options for the `zks_getProof` method.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProofOptions {
+    /// Return a compact [`StorageMultiProof`] instead of separate Merkle paths for each storage slot.
+    #[serde(default)]
+    pub compact: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Proof {
     pub address: Address,
+    /// Proven storage slots. If `multiproof` is present, slots are ordered by their hashed Merkle tree keys
+    /// and deduplicated, and their `proof`s are empty.
     pub storage_proof: Vec<StorageProof>,
+    /// Compact proof for all `storage_proof` entries. Only present if requested via [`ProofOptions`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multiproof: Option<StorageMultiProof>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,276 @@
+//! Types for the Parity-style `trace` namespace.
+
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::{web3::Bytes, H256, U256, U64};
+
+use super::{BlockNumber, DebugCall, DebugCallType};
+use crate::Address;
+
+/// Filter for the `trace_filter` method.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceFilter {
+    /// First L2 block to return traces for (inclusive). If not specified, defaults to the latest block.
+    pub from_block: Option<BlockNumber>,
+    /// Last L2 block to return traces for (inclusive). If not specified, defaults to the latest block.
+    pub to_block: Option<BlockNumber>,
+    /// If specified, only traces with one of these senders are returned.
+    pub from_address: Option<Vec<Address>>,
+    /// If specified, only traces with one of these recipients are returned.
+    pub to_address: Option<Vec<Address>>,
+    /// Number of matching traces to skip (for pagination).
+    pub after: Option<usize>,
+    /// Maximum number of traces to return.
+    pub count: Option<usize>,
+}
+
+/// Type of trace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TraceType {
+    Call,
+    Create,
+}
+
+/// Action of a call trace.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallAction {
+    pub call_type: String,
+    pub from: Address,
+    pub to: Address,
+    pub gas: U256,
+    pub input: Bytes,
+    pub value: U256,
+}
+
+/// Action of a contract creation trace.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateAction {
+    pub from: Address,
+    pub gas: U256,
+    pub init: Bytes,
+    pub value: U256,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TraceAction {
+    Call(CallAction),
+    Create(CreateAction),
+}
+
+/// Result of a successful call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallOutput {
+    pub gas_used: U256,
+    pub output: Bytes,
+}
+
+/// Result of a successful contract creation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateOutput {
+    pub address: Address,
+    pub code: Bytes,
+    pub gas_used: U256,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TraceResult {
+    Call(CallOutput),
+    Create(CreateOutput),
+}
+
+/// Flat trace in the Parity format.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Trace {
+    pub action: TraceAction,
+    /// Set to `None` if the call has failed; in this case, `error` is set.
+    pub result: Option<TraceResult>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub block_hash: H256,
+    pub block_number: U64,
+    /// Number of direct child calls.
+    pub subtraces: usize,
+    /// Path to the call in the call tree of the transaction.
+    pub trace_address: Vec<usize>,
+    pub transaction_hash: H256,
+    pub transaction_position: U64,
+    pub r#type: TraceType,
+}
+
+/// Location of a transaction in the chain used to populate [`Trace`]s.
+#[derive(Debug, Clone, Copy)]
+pub struct TraceLocation {
+    pub block_hash: H256,
+    pub block_number: U64,
+    pub transaction_hash: H256,
+    pub transaction_position: U64,
+}
+
+impl Trace {
+    /// Flattens a transaction call tree into a list of traces in the depth-first order.
+    pub fn flatten(call: &DebugCall, location: TraceLocation) -> Vec<Self> {
+        let mut traces = vec![];
+        Self::flatten_recursive(call, location, &mut vec![], &mut traces);
+        traces
+    }
+
+    fn flatten_recursive(
+        call: &DebugCall,
+        location: TraceLocation,
+        trace_address: &mut Vec<usize>,
+        traces: &mut Vec<Self>,
+    ) {
+        traces.push(Self::new(call, location, trace_address.clone()));
+        for (i, child) in call.calls.iter().enumerate() {
+            trace_address.push(i);
+            Self::flatten_recursive(child, location, trace_address, traces);
+            trace_address.pop();
+        }
+    }
+
+    fn new(call: &DebugCall, location: TraceLocation, trace_address: Vec<usize>) -> Self {
+        let (r#type, action, result) = match call.r#type {
+            DebugCallType::Call => (
+                TraceType::Call,
+                TraceAction::Call(CallAction {
+                    call_type: "call".to_owned(),
+                    from: call.from,
+                    to: call.to,
+                    gas: call.gas,
+                    input: call.input.clone(),
+                    value: call.value,
+                }),
+                TraceResult::Call(CallOutput {
+                    gas_used: call.gas_used,
+                    output: call.output.clone(),
+                }),
+            ),
+            DebugCallType::Create => (
+                TraceType::Create,
+                TraceAction::Create(CreateAction {
+                    from: call.from,
+                    gas: call.gas,
+                    init: call.input.clone(),
+                    value: call.value,
+                }),
+                TraceResult::Create(CreateOutput {
+                    address: call.to,
+                    code: call.output.clone(),
+                    gas_used: call.gas_used,
+                }),
+            ),
+        };
+        let error = call
+            .error
+            .clone()
+            .or_else(|| call.revert_reason.as_ref().map(|_| "Reverted".to_owned()));
+
+        Self {
+            action,
+            result: if error.is_some() { None } else { Some(result) },
+            error,
+            block_hash: location.block_hash,
+            block_number: location.block_number,
+            subtraces: call.calls.len(),
+            trace_address,
+            transaction_hash: location.transaction_hash,
+            transaction_position: location.transaction_position,
+            r#type,
+        }
+    }
+
+    /// Returns the sender of the traced call.
+    pub fn from(&self) -> Address {
+        match &self.action {
+            TraceAction::Call(action) => action.from,
+            TraceAction::Create(action) => action.from,
+        }
+    }
+
+    /// Returns the recipient of the traced call, or the created contract address for contract creation.
+    pub fn to(&self) -> Address {
+        match (&self.action, &self.result) {
+            (TraceAction::Call(action), _) => action.to,
+            (TraceAction::Create(_), Some(TraceResult::Create(output))) => output.address,
+            (TraceAction::Create(_), _) => Address::zero(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(r#type: DebugCallType, to: u64, calls: Vec<DebugCall>) -> DebugCall {
+        DebugCall {
+            r#type,
+            from: Address::repeat_byte(1),
+            to: Address::from_low_u64_be(to),
+            gas: 1_000.into(),
+            gas_used: 100.into(),
+            calls,
+            ..DebugCall::default()
+        }
+    }
+
+    #[test]
+    fn flattening_call_tree() {
+        let call = call(
+            DebugCallType::Call,
+            1,
+            vec![
+                call(
+                    DebugCallType::Create,
+                    2,
+                    vec![call(DebugCallType::Call, 3, vec![])],
+                ),
+                DebugCall {
+                    revert_reason: Some("oops".to_owned()),
+                    ..call(DebugCallType::Call, 4, vec![])
+                },
+            ],
+        );
+        let location = TraceLocation {
+            block_hash: H256::repeat_byte(0xff),
+            block_number: 5.into(),
+            transaction_hash: H256::repeat_byte(0xee),
+            transaction_position: 1.into(),
+        };
+        let traces = Trace::flatten(&call, location);
+
+        let trace_addresses: Vec<_> = traces.iter().map(|trace| &trace.trace_address).collect();
+        assert_eq!(
+            trace_addresses,
+            [vec![], vec![0], vec![0, 0], vec![1]]
+                .iter()
+                .collect::<Vec<_>>()
+        );
+        let subtraces: Vec<_> = traces.iter().map(|trace| trace.subtraces).collect();
+        assert_eq!(subtraces, [2, 1, 0, 0]);
+        let recipients: Vec<_> = traces.iter().map(|trace| trace.to()).collect();
+        assert_eq!(
+            recipients,
+            (1..=4).map(Address::from_low_u64_be).collect::<Vec<_>>()
+        );
+
+        assert_eq!(traces[1].r#type, TraceType::Create);
+        assert!(matches!(traces[1].action, TraceAction::Create(_)));
+        assert_eq!(traces[3].error.as_deref(), Some("Reverted"));
+        assert_eq!(traces[3].result, None);
+
+        let serialized = serde_json::to_value(&traces[0]).unwrap();
+        assert_eq!(serialized["type"], "call");
+        assert_eq!(serialized["action"]["callType"], "call");
+        assert_eq!(serialized["traceAddress"], serde_json::json!([]));
+        assert_eq!(serialized["result"]["gasUsed"], "0x64");
+        assert!(serialized.get("error").is_none());
+    }
+}
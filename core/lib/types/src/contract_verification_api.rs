@@ -1,13 +1,20 @@
 use std::{collections::HashMap, fmt};
 
 use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
 use serde::{
     de::{Deserializer, Error, MapAccess, Unexpected, Visitor},
     Deserialize, Serialize,
 };
 
 pub use crate::Execute as ExecuteData;
-use crate::{web3::Bytes, Address};
+use crate::{
+    ethabi::{Contract, Token},
+    web3::Bytes,
+    Address,
+};
+
+static DEPLOYER_CONTRACT: Lazy<Contract> = Lazy::new(zksync_contracts::deployer_contract);
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(tag = "codeFormat", content = "sourceCode")]
@@ -217,12 +224,31 @@ pub struct CompilationArtifacts {
     pub abi: serde_json::Value,
 }
 
+/// Type of match between the compiled and deployed bytecode.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationMatchType {
+    /// Bytecodes are equal, including the metadata section.
+    #[default]
+    Full,
+    /// Bytecodes are equal after stripping the metadata section (e.g., the compiled contract has a different
+    /// metadata hash because of source comments or compiler metadata settings).
+    Partial,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct VerificationInfo {
     pub request: VerificationRequest,
     pub artifacts: CompilationArtifacts,
     pub verified_at: DateTime<Utc>,
+    /// Verification info saved before partial matches were supported always has the full match type.
+    #[serde(default)]
+    pub match_type: VerificationMatchType,
+    /// Address of the verified contract with the same bytecode if the info is returned for a contract
+    /// that wasn't verified directly (a "similar match"). Never persisted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub similar_match_address: Option<Address>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -241,11 +267,85 @@ pub enum DeployContractCalldata {
     Ignore,
 }
 
+/// Constructor arguments of a deployed contract.
+#[derive(Debug)]
+pub enum ConstructorArgs {
+    /// Arguments that the contract was deployed with.
+    Check(Vec<u8>),
+    /// Arguments cannot be determined (e.g., the contract was deployed by another contract), or the constructor wasn't called.
+    Ignore,
+}
+
+impl DeployContractCalldata {
+    /// Extracts constructor arguments for the contract deployed at `contract_address` from the deployment calldata.
+    /// Malformed calldata (e.g., one sent to the deployer by a faulty or malicious transaction) results
+    /// in [`ConstructorArgs::Ignore`].
+    pub fn constructor_args(self, contract_address: Address) -> ConstructorArgs {
+        let Self::Deploy(calldata) = self else {
+            return ConstructorArgs::Ignore;
+        };
+        Self::decode_constructor_args(&calldata, contract_address)
+            .unwrap_or(ConstructorArgs::Ignore)
+    }
+
+    fn decode_constructor_args(
+        calldata: &[u8],
+        contract_address: Address,
+    ) -> Option<ConstructorArgs> {
+        let create = DEPLOYER_CONTRACT.function("create").ok()?;
+        let create2 = DEPLOYER_CONTRACT.function("create2").ok()?;
+
+        let create_acc = DEPLOYER_CONTRACT.function("createAccount").ok()?;
+        let create2_acc = DEPLOYER_CONTRACT.function("create2Account").ok()?;
+
+        let force_deploy = DEPLOYER_CONTRACT.function("forceDeployOnAddresses").ok()?;
+        let selector = calldata.get(..4)?;
+        let input = &calldata[4..];
+        // It's assumed that `create` and `create2` methods have the same parameters
+        // and the same for `createAccount` and `create2Account`.
+        if selector == create.short_signature() || selector == create2.short_signature() {
+            let tokens = create.decode_input(input).ok()?;
+            // Constructor arguments are in the third parameter.
+            let args = tokens.get(2)?.clone().into_bytes()?;
+            Some(ConstructorArgs::Check(args))
+        } else if selector == create_acc.short_signature()
+            || selector == create2_acc.short_signature()
+        {
+            let tokens = create_acc.decode_input(input).ok()?;
+            // Constructor arguments are in the third parameter.
+            let args = tokens.get(2)?.clone().into_bytes()?;
+            Some(ConstructorArgs::Check(args))
+        } else if selector == force_deploy.short_signature() {
+            let tokens = force_deploy.decode_input(input).ok()?;
+            let deployments = tokens.into_iter().next()?.into_array()?;
+            for deployment in deployments {
+                let Token::Tuple(tokens) = deployment else {
+                    return None;
+                };
+                let address = tokens.get(1)?.clone().into_address()?;
+                if address == contract_address {
+                    let call_constructor = tokens.get(2)?.clone().into_bool()?;
+                    return Some(if call_constructor {
+                        ConstructorArgs::Check(tokens.get(4)?.clone().into_bytes()?)
+                    } else {
+                        ConstructorArgs::Ignore
+                    });
+                }
+            }
+            // No force deployment for the given address
+            None
+        } else {
+            Some(ConstructorArgs::Ignore)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use assert_matches::assert_matches;
 
-    use super::SourceCodeData;
+    use super::*;
+    use crate::H256;
 
     #[test]
     fn source_code_deserialization() {
@@ -274,4 +374,90 @@ mod tests {
             serde_json::from_str::<SourceCodeData>(type_not_specified_object_str);
         assert!(type_not_specified_object_result.is_err());
     }
+
+    fn encode_call(name: &str, tokens: &[Token]) -> Vec<u8> {
+        DEPLOYER_CONTRACT
+            .function(name)
+            .unwrap()
+            .encode_input(tokens)
+            .unwrap()
+    }
+
+    #[test]
+    fn extracting_constructor_args_from_create_calls() {
+        for name in ["create", "create2", "createAccount", "create2Account"] {
+            let mut tokens = vec![
+                Token::FixedBytes(H256::zero().0.to_vec()),
+                Token::FixedBytes(H256::repeat_byte(1).0.to_vec()),
+                Token::Bytes(vec![1, 2, 3]),
+            ];
+            if name.ends_with("Account") {
+                // Account abstraction version
+                tokens.push(Token::Uint(0.into()));
+            }
+            let calldata = DeployContractCalldata::Deploy(encode_call(name, &tokens));
+            let args = calldata.constructor_args(Address::repeat_byte(1));
+            assert_matches!(args, ConstructorArgs::Check(args) if args == [1, 2, 3], "{name}");
+        }
+    }
+
+    #[test]
+    fn extracting_constructor_args_from_force_deployment() {
+        let deployment = |address: Address, call_constructor: bool| {
+            Token::Tuple(vec![
+                Token::FixedBytes(H256::repeat_byte(1).0.to_vec()),
+                Token::Address(address),
+                Token::Bool(call_constructor),
+                Token::Uint(0.into()),
+                Token::Bytes(vec![address.0[0]]),
+            ])
+        };
+        let calldata = encode_call(
+            "forceDeployOnAddresses",
+            &[Token::Array(vec![
+                deployment(Address::repeat_byte(1), true),
+                deployment(Address::repeat_byte(2), false),
+            ])],
+        );
+
+        let args = DeployContractCalldata::Deploy(calldata.clone())
+            .constructor_args(Address::repeat_byte(1));
+        assert_matches!(args, ConstructorArgs::Check(args) if args == [1]);
+        let args = DeployContractCalldata::Deploy(calldata.clone())
+            .constructor_args(Address::repeat_byte(2));
+        assert_matches!(args, ConstructorArgs::Ignore);
+        // Missing deployment
+        let args =
+            DeployContractCalldata::Deploy(calldata).constructor_args(Address::repeat_byte(3));
+        assert_matches!(args, ConstructorArgs::Ignore);
+    }
+
+    #[test]
+    fn malformed_deploy_calldata_is_ignored() {
+        let create_call = encode_call(
+            "create",
+            &[
+                Token::FixedBytes(H256::zero().0.to_vec()),
+                Token::FixedBytes(H256::repeat_byte(1).0.to_vec()),
+                Token::Bytes(vec![1, 2, 3]),
+            ],
+        );
+        let force_deploy_selector = DEPLOYER_CONTRACT
+            .function("forceDeployOnAddresses")
+            .unwrap()
+            .short_signature();
+        let malformed_calldata = [
+            vec![],
+            vec![1, 2],
+            create_call[..4].to_vec(),
+            create_call[..create_call.len() - 32].to_vec(),
+            [force_deploy_selector.as_slice(), &[0xff; 64]].concat(),
+        ];
+
+        for calldata in malformed_calldata {
+            let args = DeployContractCalldata::Deploy(calldata.clone())
+                .constructor_args(Address::repeat_byte(1));
+            assert_matches!(args, ConstructorArgs::Ignore, "{calldata:?}");
+        }
+    }
 }
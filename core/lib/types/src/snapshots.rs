@@ -36,6 +36,9 @@ pub struct SnapshotMetadata {
     pub version: SnapshotVersion,
     /// L1 batch for the snapshot. The data in the snapshot captures node storage at the end of this batch.
     pub l1_batch_number: L1BatchNumber,
+    /// For delta snapshots, L1 batch of the base snapshot. A delta snapshot only contains storage logs modified
+    /// and factory deps added after the base snapshot. `None` for full snapshots.
+    pub base_l1_batch_number: Option<L1BatchNumber>,
     /// Path to the factory dependencies blob.
     pub factory_deps_filepath: String,
     /// Paths to the storage log blobs. Ordered by the chunk ID. If a certain chunk is not produced yet,
@@ -61,6 +64,10 @@ pub struct SnapshotHeader {
     pub l1_batch_number: L1BatchNumber,
     #[serde(rename = "miniblockNumber")] // legacy naming
     pub l2_block_number: L2BlockNumber,
+    /// L1 batch of the base snapshot if this snapshot is a delta one. To recover from a delta snapshot,
+    /// the base snapshot (which may be a delta snapshot itself) must be applied first.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_l1_batch_number: Option<L1BatchNumber>,
     /// Ordered by chunk IDs.
     pub storage_logs_chunks: Vec<SnapshotStorageLogsChunkMetadata>,
     pub factory_deps_filepath: String,
@@ -11,11 +11,11 @@ use serde::de::DeserializeOwned;
 
 use super::{ForWeb3Network, Network, TaggedClient};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct RawParams(pub(super) Option<Box<JsonRawValue>>);
 
 impl RawParams {
-    fn new(params: impl ToRpcParams) -> Result<Self, serde_json::Error> {
+    pub(super) fn new(params: impl ToRpcParams) -> Result<Self, serde_json::Error> {
         params.to_rpc_params().map(Self)
     }
 }
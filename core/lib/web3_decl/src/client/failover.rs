@@ -0,0 +1,354 @@
+//! Client failing over between multiple upstreams.
+
+use std::{
+    fmt, mem,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use async_trait::async_trait;
+use jsonrpsee::{
+    core::{
+        client::{BatchResponse, ClientT, Error},
+        params::BatchRequestBuilder,
+        traits::ToRpcParams,
+    },
+    rpc_params,
+};
+use serde::de::DeserializeOwned;
+use zksync_types::U64;
+
+use super::{boxed::RawParams, DynClient, ForWeb3Network, Network, TaggedClient};
+
+/// Status of a single upstream as observed by a [`FailoverClient`].
+#[derive(Debug, Clone, Copy)]
+struct UpstreamStatus {
+    is_healthy: bool,
+    /// Latest block number reported by the upstream during the last health check. `None` if there was no successful health check yet,
+    /// or if the upstream has failed since then.
+    latest_block: Option<u64>,
+}
+
+impl Default for UpstreamStatus {
+    fn default() -> Self {
+        Self {
+            is_healthy: true,
+            latest_block: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct FailoverState {
+    /// Index of the upstream requests are sent to first.
+    active: usize,
+    upstreams: Vec<UpstreamStatus>,
+    /// Whether at least one health check has completed. Before that, upstream lags are unknown.
+    is_checked: bool,
+}
+
+impl FailoverState {
+    fn max_known_block(&self) -> Option<u64> {
+        self.upstreams
+            .iter()
+            .filter(|status| status.is_healthy)
+            .filter_map(|status| status.latest_block)
+            .max()
+    }
+
+    /// Checks whether the specified upstream lags behind the most advanced healthy upstream by more than `max_lag` blocks.
+    /// An upstream with unknown latest block is considered lagging if the latest block is known for any other healthy upstream.
+    fn is_lagging(&self, idx: usize, max_lag: u64) -> bool {
+        let Some(max_block) = self.max_known_block() else {
+            return false;
+        };
+        match self.upstreams[idx].latest_block {
+            Some(block) => block.saturating_add(max_lag) < max_block,
+            None => true,
+        }
+    }
+}
+
+/// JSON-RPC client distributing requests among several upstreams (e.g., the main node and trusted external nodes).
+///
+/// Upstreams are ordered by priority; the first upstream is the primary one. Requests are sent to the active upstream.
+/// If it fails with a transport-level error (e.g., the upstream is unreachable or returns a 5xx HTTP status), the request
+/// is retried on other upstreams, preferring healthy ones, and the active upstream is switched. Application-level errors
+/// (e.g., JSON-RPC errors returned by the upstream) are not retried.
+///
+/// [`Self::check_upstreams()`] should be called periodically to track upstream health and to switch back to the upstream
+/// with the highest priority once it recovers. All clones of the client share upstream state.
+#[derive(Debug, Clone)]
+pub struct FailoverClient<Net: Network> {
+    upstreams: Vec<Box<DynClient<Net>>>,
+    state: Arc<Mutex<FailoverState>>,
+    max_lag: u64,
+    consistent: bool,
+    component_name: &'static str,
+    network: Net,
+}
+
+impl<Net: Network> FailoverClient<Net> {
+    /// Default maximum lag (in blocks) of an upstream behind the most advanced upstream for it to be selected during health checks.
+    pub const DEFAULT_MAX_LAG: u64 = 10;
+
+    /// Creates a client for the specified upstreams ordered by priority.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `upstreams` are empty.
+    pub fn new(upstreams: Vec<Box<DynClient<Net>>>) -> Self {
+        assert!(!upstreams.is_empty(), "no upstreams provided");
+        let network = upstreams[0].network();
+        let state = FailoverState {
+            active: 0,
+            upstreams: vec![UpstreamStatus::default(); upstreams.len()],
+            is_checked: false,
+        };
+        Self {
+            upstreams,
+            state: Arc::new(Mutex::new(state)),
+            max_lag: Self::DEFAULT_MAX_LAG,
+            consistent: false,
+            component_name: "",
+            network,
+        }
+    }
+
+    /// Sets the maximum lag (in blocks) of an upstream behind the most advanced upstream for it to be selected as active.
+    pub fn with_max_lag(mut self, max_lag: u64) -> Self {
+        self.max_lag = max_lag;
+        self
+    }
+
+    /// Returns a view of this client that only sends requests to upstreams not lagging behind the most advanced healthy upstream,
+    /// according to the latest health check. Such a client is suitable for consistency checks (e.g., reorg detection),
+    /// which could produce false positives if an upstream lags behind. The view shares upstream state with this client,
+    /// but never switches the active upstream. Until the first health check completes, the view only uses the primary upstream.
+    pub fn consistent(mut self) -> Self {
+        self.consistent = true;
+        self
+    }
+
+    /// Returns the number of upstreams.
+    pub fn upstream_count(&self) -> usize {
+        self.upstreams.len()
+    }
+
+    /// Returns the index of the currently active upstream.
+    pub fn active_upstream(&self) -> usize {
+        self.lock_state().active
+    }
+
+    fn lock_state(&self) -> MutexGuard<'_, FailoverState> {
+        self.state.lock().expect("failover state is poisoned")
+    }
+
+    /// Returns upstream indices in the order they should be tried for a request.
+    fn candidates(&self) -> Vec<usize> {
+        let state = self.lock_state();
+        if self.consistent && !state.is_checked {
+            // Upstream lags are unknown, so the primary upstream is the only one we can rely on.
+            return vec![0];
+        }
+        let max_lag = if self.consistent { 0 } else { self.max_lag };
+        let ordered_upstreams = std::iter::once(state.active)
+            .chain((0..self.upstreams.len()).filter(|&idx| idx != state.active));
+
+        let mut preferred = vec![];
+        let mut fallback = vec![];
+        for idx in ordered_upstreams {
+            let is_lagging = state.is_lagging(idx, max_lag);
+            if self.consistent && is_lagging {
+                continue;
+            }
+            if state.upstreams[idx].is_healthy && !is_lagging {
+                preferred.push(idx);
+            } else {
+                fallback.push(idx);
+            }
+        }
+        preferred.extend(fallback);
+        preferred
+    }
+
+    /// Updates upstream status based on the call result. Returns `true` if the request should be retried on another upstream.
+    fn should_fail_over<T>(&self, idx: usize, method: &str, result: &Result<T, Error>) -> bool {
+        let mut state = self.lock_state();
+        match result {
+            Err(err) if is_upstream_failure(err) => {
+                let network_label = self.network.metric_label();
+                tracing::warn!(
+                    network = network_label,
+                    component = self.component_name,
+                    "Upstream #{idx} ({:?}) failed serving `{method}`: {err}",
+                    self.upstreams[idx]
+                );
+                state.upstreams[idx] = UpstreamStatus {
+                    is_healthy: false,
+                    latest_block: None,
+                };
+                true
+            }
+            _ => {
+                state.upstreams[idx].is_healthy = true;
+                // The consistent view may use an upstream not suitable as the active one (e.g., the primary upstream
+                // before the first health check), so it doesn't affect the active upstream.
+                if !self.consistent && state.active != idx {
+                    tracing::info!(
+                        network = self.network.metric_label(),
+                        "Switched active upstream from #{} to #{idx}",
+                        state.active
+                    );
+                    state.active = idx;
+                }
+                false
+            }
+        }
+    }
+
+    fn no_upstreams_error(last_err: Option<Error>) -> Error {
+        last_err.unwrap_or_else(|| {
+            Error::Transport(anyhow::anyhow!("no consistent upstreams are available"))
+        })
+    }
+
+    /// Checks health of all upstreams by requesting their latest block number, and selects the active upstream:
+    /// the first healthy upstream (in the order of priority) that lags behind the most advanced upstream
+    /// by no more than the configured max lag.
+    pub async fn check_upstreams(&self) {
+        let checks = self.upstreams.iter().map(|client| async move {
+            ClientT::request::<U64, _>(&client.as_ref(), "eth_blockNumber", rpc_params![]).await
+        });
+        let results = futures::future::join_all(checks).await;
+
+        let network_label = self.network.metric_label();
+        let mut state = self.lock_state();
+        for (idx, result) in results.into_iter().enumerate() {
+            state.upstreams[idx] = match result {
+                Ok(block_number) => UpstreamStatus {
+                    is_healthy: true,
+                    latest_block: Some(block_number.as_u64()),
+                },
+                Err(err) => {
+                    tracing::warn!(
+                        network = network_label,
+                        "Health check for upstream #{idx} ({:?}) failed: {err}",
+                        self.upstreams[idx]
+                    );
+                    UpstreamStatus {
+                        is_healthy: false,
+                        latest_block: None,
+                    }
+                }
+            };
+        }
+        state.is_checked = true;
+
+        let selected = (0..self.upstreams.len())
+            .find(|&idx| state.upstreams[idx].is_healthy && !state.is_lagging(idx, self.max_lag));
+        if let Some(idx) = selected {
+            if idx != state.active {
+                tracing::info!(
+                    network = network_label,
+                    "Switched active upstream from #{} to #{idx} after health check; upstream statuses: {:?}",
+                    state.active,
+                    state.upstreams
+                );
+                state.active = idx;
+            }
+        } else {
+            tracing::warn!(
+                network = network_label,
+                "All upstreams are unhealthy: {:?}",
+                state.upstreams
+            );
+        }
+    }
+}
+
+/// Checks whether the error is caused by the upstream being unavailable (as opposed to an application-level error).
+fn is_upstream_failure(err: &Error) -> bool {
+    matches!(
+        err,
+        Error::Transport(_) | Error::RestartNeeded(_) | Error::RequestTimeout
+    )
+}
+
+impl<Net: Network> ForWeb3Network for FailoverClient<Net> {
+    type Net = Net;
+
+    fn network(&self) -> Self::Net {
+        self.network
+    }
+
+    fn component(&self) -> &'static str {
+        self.component_name
+    }
+}
+
+impl<Net: Network> TaggedClient for FailoverClient<Net> {
+    fn set_component(&mut self, component_name: &'static str) {
+        self.component_name = component_name;
+        self.upstreams = mem::take(&mut self.upstreams)
+            .into_iter()
+            .map(|client| client.for_component(component_name))
+            .collect();
+    }
+}
+
+#[async_trait]
+impl<Net: Network> ClientT for FailoverClient<Net> {
+    async fn notification<Params>(&self, method: &str, params: Params) -> Result<(), Error>
+    where
+        Params: ToRpcParams + Send,
+    {
+        let params = RawParams::new(params)?;
+        let mut last_err = None;
+        for idx in self.candidates() {
+            let client = self.upstreams[idx].as_ref();
+            let result = ClientT::notification(&client, method, params.clone()).await;
+            if !self.should_fail_over(idx, method, &result) {
+                return result;
+            }
+            last_err = result.err();
+        }
+        Err(Self::no_upstreams_error(last_err))
+    }
+
+    async fn request<R, Params>(&self, method: &str, params: Params) -> Result<R, Error>
+    where
+        R: DeserializeOwned,
+        Params: ToRpcParams + Send,
+    {
+        let params = RawParams::new(params)?;
+        let mut last_err = None;
+        for idx in self.candidates() {
+            let client = self.upstreams[idx].as_ref();
+            let result = ClientT::request(&client, method, params.clone()).await;
+            if !self.should_fail_over(idx, method, &result) {
+                return result;
+            }
+            last_err = result.err();
+        }
+        Err(Self::no_upstreams_error(last_err))
+    }
+
+    async fn batch_request<'a, R>(
+        &self,
+        batch: BatchRequestBuilder<'a>,
+    ) -> Result<BatchResponse<'a, R>, Error>
+    where
+        R: DeserializeOwned + fmt::Debug + 'a,
+    {
+        let mut last_err = None;
+        for idx in self.candidates() {
+            let client = self.upstreams[idx].as_ref();
+            let result = ClientT::batch_request(&client, batch.clone()).await;
+            if !self.should_fail_over(idx, "batch", &result) {
+                return result;
+            }
+            last_err = result.err();
+        }
+        Err(Self::no_upstreams_error(last_err))
+    }
+}
@@ -10,6 +10,8 @@
 //!   where it's possible.
 //! - [`BoxedL2Client`] is a generic client (essentially, a wrapper around a trait object). Use it for dependency injection
 //!   instead of `L2Client`. Both `L2Client` and `MockL2Client` are convertible to `BoxedL2Client`.
+//! - [`FailoverClient`] distributes requests among several upstreams, failing over to another upstream
+//!   if the active one becomes unavailable.
 
 use std::{
     any,
@@ -37,12 +39,14 @@ use zksync_types::url::SensitiveUrl;
 use self::metrics::{L2ClientMetrics, METRICS};
 pub use self::{
     boxed::{DynClient, ObjectSafeClient},
+    failover::FailoverClient,
     mock::{MockClient, MockClientBuilder},
     network::{ForWeb3Network, Network, TaggedClient, L1, L2},
     shared::Shared,
 };
 
 mod boxed;
+mod failover;
 mod metrics;
 mod mock;
 mod network;
@@ -1,7 +1,10 @@
-//! Tests for `L2Client` focused on rate limiting.
+//! Tests for `L2Client` focused on rate limiting, and for `FailoverClient`.
 
 use std::{
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
     time::Duration,
 };
 
@@ -272,3 +275,158 @@ async fn wrapping_mock_client() {
     };
     assert!(metrics.http_errors.contains(&labels), "{metrics:?}");
 }
+
+fn mock_upstream(
+    name: &'static str,
+    block_number: impl Fn() -> Option<u64> + Send + Sync + 'static,
+) -> Box<DynClient<L2>> {
+    let block_number = Arc::new(block_number);
+    let block_number_for_name = block_number.clone();
+    let client = MockClient::builder(L2::default())
+        .method("eth_blockNumber", move || match block_number() {
+            Some(number) => Ok(U64::from(number)),
+            None => Err(Error::Transport(anyhow::anyhow!("{name} is unavailable"))),
+        })
+        .method("name", move || match block_number_for_name() {
+            Some(_) => Ok(name),
+            None => Err(Error::Transport(anyhow::anyhow!("{name} is unavailable"))),
+        })
+        .build();
+    Box::new(client)
+}
+
+#[tokio::test]
+async fn failover_client_basics() {
+    let primary_is_up = Arc::new(AtomicBool::new(true));
+    let primary = mock_upstream("primary", {
+        let primary_is_up = primary_is_up.clone();
+        move || primary_is_up.load(Ordering::Relaxed).then_some(100)
+    });
+    let fallback = mock_upstream("fallback", || Some(100));
+    let client = FailoverClient::new(vec![primary, fallback]);
+    assert_eq!(client.upstream_count(), 2);
+
+    let name: String = client.request("name", rpc_params![]).await.unwrap();
+    assert_eq!(name, "primary");
+    assert_eq!(client.active_upstream(), 0);
+
+    // Application-level errors must not lead to a failover.
+    let err = client
+        .request::<String, _>("unknown", rpc_params![])
+        .await
+        .unwrap_err();
+    assert_matches!(err, Error::Call(_));
+    assert_eq!(client.active_upstream(), 0);
+
+    primary_is_up.store(false, Ordering::Relaxed);
+    let name: String = client.request("name", rpc_params![]).await.unwrap();
+    assert_eq!(name, "fallback");
+    assert_eq!(client.active_upstream(), 1);
+    // The active upstream should be shared among client clones.
+    let cloned_client = Box::new(client.clone()) as Box<DynClient<L2>>;
+    let name: String = cloned_client.request("name", rpc_params![]).await.unwrap();
+    assert_eq!(name, "fallback");
+
+    // The client should not switch back to the primary upstream without a health check.
+    primary_is_up.store(true, Ordering::Relaxed);
+    let name: String = client.request("name", rpc_params![]).await.unwrap();
+    assert_eq!(name, "fallback");
+    client.check_upstreams().await;
+    assert_eq!(client.active_upstream(), 0);
+    let name: String = client.request("name", rpc_params![]).await.unwrap();
+    assert_eq!(name, "primary");
+}
+
+#[tokio::test]
+async fn failover_client_with_all_upstreams_unavailable() {
+    let upstreams = vec![
+        mock_upstream("primary", || None),
+        mock_upstream("fallback", || None),
+    ];
+    let client = FailoverClient::new(upstreams);
+    let err = client
+        .request::<String, _>("name", rpc_params![])
+        .await
+        .unwrap_err();
+    assert_matches!(err, Error::Transport(_));
+
+    client.check_upstreams().await;
+    let err = client
+        .request::<String, _>("name", rpc_params![])
+        .await
+        .unwrap_err();
+    assert_matches!(err, Error::Transport(_));
+}
+
+#[tokio::test]
+async fn health_checks_skip_lagging_upstreams() {
+    let upstreams = vec![
+        mock_upstream("primary", || Some(50)),
+        mock_upstream("fallback", || Some(100)),
+    ];
+    let client = FailoverClient::new(upstreams).with_max_lag(10);
+    client.check_upstreams().await;
+    assert_eq!(client.active_upstream(), 1);
+    let name: String = client.request("name", rpc_params![]).await.unwrap();
+    assert_eq!(name, "fallback");
+}
+
+#[tokio::test]
+async fn consistent_failover_client_ignores_lagging_upstreams() {
+    let upstreams = vec![
+        mock_upstream("primary", || None),
+        mock_upstream("lagging", || Some(95)),
+        mock_upstream("synced", || Some(100)),
+    ];
+    let client = FailoverClient::new(upstreams).with_max_lag(10);
+    let consistent_client = client.clone().consistent();
+    client.check_upstreams().await;
+    assert_eq!(client.active_upstream(), 1);
+
+    let name: String = client.request("name", rpc_params![]).await.unwrap();
+    assert_eq!(name, "lagging");
+    let name: String = consistent_client
+        .request("name", rpc_params![])
+        .await
+        .unwrap();
+    assert_eq!(name, "synced");
+    // The consistent view must not switch the active upstream of the client.
+    assert_eq!(client.active_upstream(), 1);
+    let name: String = client.request("name", rpc_params![]).await.unwrap();
+    assert_eq!(name, "lagging");
+}
+
+#[tokio::test]
+async fn consistent_failover_client_uses_primary_before_health_check() {
+    let primary_is_up = Arc::new(AtomicBool::new(true));
+    let primary = mock_upstream("primary", {
+        let primary_is_up = primary_is_up.clone();
+        move || primary_is_up.load(Ordering::Relaxed).then_some(100)
+    });
+    let upstreams = vec![primary, mock_upstream("lagging", || Some(50))];
+    let client = FailoverClient::new(upstreams).with_max_lag(10);
+    let consistent_client = client.clone().consistent();
+
+    let name: String = consistent_client
+        .request("name", rpc_params![])
+        .await
+        .unwrap();
+    assert_eq!(name, "primary");
+
+    // Lags are unknown before the first health check, so the consistent view must not fail over.
+    primary_is_up.store(false, Ordering::Relaxed);
+    let err = consistent_client
+        .request::<String, _>("name", rpc_params![])
+        .await
+        .unwrap_err();
+    assert_matches!(err, Error::Transport(_));
+    assert_eq!(client.active_upstream(), 0);
+
+    primary_is_up.store(true, Ordering::Relaxed);
+    client.check_upstreams().await;
+    let name: String = consistent_client
+        .request("name", rpc_params![])
+        .await
+        .unwrap();
+    assert_eq!(name, "primary");
+}
@@ -190,7 +190,7 @@ pub trait EthNamespace {
 mod pub_sub {
     use jsonrpsee::{core::SubscriptionResult, proc_macros::rpc};
 
-    use crate::types::PubSubFilter;
+    use crate::types::PubSubParams;
 
     #[rpc(server, namespace = "eth")]
     pub trait EthPubSub {
@@ -198,7 +198,7 @@ mod pub_sub {
         async fn subscribe(
             &self,
             sub_type: String,
-            filter: Option<PubSubFilter>,
+            params: Option<PubSubParams>,
         ) -> SubscriptionResult;
     }
 }
@@ -1,13 +1,14 @@
 pub use self::{
     debug::DebugNamespaceClient, en::EnNamespaceClient, eth::EthNamespaceClient,
-    net::NetNamespaceClient, snapshots::SnapshotsNamespaceClient,
+    net::NetNamespaceClient, snapshots::SnapshotsNamespaceClient, trace::TraceNamespaceClient,
     unstable::UnstableNamespaceClient, web3::Web3NamespaceClient, zks::ZksNamespaceClient,
 };
 #[cfg(feature = "server")]
 pub use self::{
     debug::DebugNamespaceServer, en::EnNamespaceServer, eth::EthNamespaceServer,
     eth::EthPubSubServer, net::NetNamespaceServer, snapshots::SnapshotsNamespaceServer,
-    unstable::UnstableNamespaceServer, web3::Web3NamespaceServer, zks::ZksNamespaceServer,
+    trace::TraceNamespaceServer, unstable::UnstableNamespaceServer, web3::Web3NamespaceServer,
+    zks::ZksNamespaceServer,
 };
 
 mod debug;
@@ -15,6 +16,7 @@ mod en;
 mod eth;
 mod net;
 mod snapshots;
+mod trace;
 mod unstable;
 mod web3;
 mod zks;
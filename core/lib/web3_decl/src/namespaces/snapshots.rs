@@ -17,12 +17,26 @@ use crate::client::{ForWeb3Network, L2};
     rpc(client, namespace = "snapshots", client_bounds(Self: ForWeb3Network<Net = L2>))
 )]
 pub trait SnapshotsNamespace {
+    /// Returns L1 batch numbers of all complete full snapshots. Delta snapshots are not returned, since clients
+    /// unaware of them would recover an incomplete state.
     #[method(name = "getAllSnapshots")]
     async fn get_all_snapshots(&self) -> RpcResult<AllSnapshots>;
 
+    /// Returns the header of a complete full snapshot. Returns `null` for delta snapshots.
     #[method(name = "getSnapshot")]
     async fn get_snapshot_by_l1_batch_number(
         &self,
         l1_batch_number: L1BatchNumber,
     ) -> RpcResult<Option<SnapshotHeader>>;
+
+    /// Same as `getAllSnapshots`, but also returns delta snapshots.
+    #[method(name = "getAllSnapshotsWithDeltas")]
+    async fn get_all_snapshots_with_deltas(&self) -> RpcResult<AllSnapshots>;
+
+    /// Same as `getSnapshot`, but also returns delta snapshots (i.e., ones with `baseL1BatchNumber` set).
+    #[method(name = "getSnapshotWithDeltas")]
+    async fn get_snapshot_with_deltas(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<SnapshotHeader>>;
 }
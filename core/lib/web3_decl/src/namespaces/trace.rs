@@ -0,0 +1,32 @@
+#[cfg_attr(not(feature = "server"), allow(unused_imports))]
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::proc_macros::rpc;
+use zksync_types::api::{
+    trace::{Trace, TraceFilter},
+    BlockNumber,
+};
+
+use crate::{
+    client::{ForWeb3Network, L2},
+    types::H256,
+};
+
+/// Parity-style `trace` namespace. Traces are returned in the flat format.
+#[cfg_attr(
+    feature = "server",
+    rpc(server, client, namespace = "trace", client_bounds(Self: ForWeb3Network<Net = L2>))
+)]
+#[cfg_attr(
+    not(feature = "server"),
+    rpc(client, namespace = "trace", client_bounds(Self: ForWeb3Network<Net = L2>))
+)]
+pub trait TraceNamespace {
+    #[method(name = "block")]
+    async fn trace_block(&self, block: BlockNumber) -> RpcResult<Vec<Trace>>;
+
+    #[method(name = "transaction")]
+    async fn trace_transaction(&self, tx_hash: H256) -> RpcResult<Option<Vec<Trace>>>;
+
+    #[method(name = "filter")]
+    async fn trace_filter(&self, filter: TraceFilter) -> RpcResult<Vec<Trace>>;
+}
@@ -14,7 +14,7 @@ use core::{
 use rlp::Rlp;
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 pub use zksync_types::{
-    api::{Block, BlockNumber, Log, TransactionReceipt, TransactionRequest},
+    api::{Block, BlockNumber, L1BatchStage, Log, TransactionReceipt, TransactionRequest},
     ethabi,
     web3::{BlockHeader, Bytes, CallRequest, FeeHistory, Index, SyncState, TraceFilter, Work},
     Address, Transaction, H160, H256, H64, U256, U64,
@@ -276,6 +276,42 @@ impl PubSubFilter {
     }
 }
 
+/// Options for the `newPendingTransactions` subscription.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PendingTransactionsOptions {
+    /// If set, full transactions are sent instead of their hashes.
+    pub full_transactions: bool,
+}
+
+/// Parameters of an `eth_subscribe` call following the subscription type.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PubSubParams {
+    /// Geth-style flag for `newPendingTransactions` requesting full transactions.
+    FullTransactions(bool),
+    // Must go before `Filter` since all filter fields are optional.
+    PendingTransactions(PendingTransactionsOptions),
+    Filter(PubSubFilter),
+}
+
+impl PubSubParams {
+    /// Checks whether full transactions are requested for the `newPendingTransactions` subscription.
+    pub fn full_transactions(&self) -> bool {
+        match self {
+            Self::FullTransactions(flag) => *flag,
+            Self::PendingTransactions(options) => options.full_transactions,
+            Self::Filter(_) => false,
+        }
+    }
+}
+
+impl From<PubSubFilter> for PubSubParams {
+    fn from(filter: PubSubFilter) -> Self {
+        Self::Filter(filter)
+    }
+}
+
 #[derive(Default, Clone)]
 pub struct PubSubFilterBuilder {
     filter: PubSubFilter,
@@ -333,18 +369,82 @@ fn topic_to_option<T>(topic: ethabi::Topic<T>) -> Option<Vec<T>> {
     }
 }
 
+/// Status of a transaction in the preconfirmations stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PreconfirmationStatus {
+    /// Transaction was executed by the sequencer and included into an L2 block that isn't sealed yet.
+    Preconfirmed,
+    /// Previously preconfirmed transaction was rolled back together with its unsealed L2 block.
+    /// The transaction may be re-executed later with a different result.
+    Retracted,
+}
+
+/// Transaction execution result published before the transaction's L2 block is sealed.
+///
+/// Preconfirmations are **not final**: the transaction may be retracted if the L2 block is not sealed
+/// (e.g., if the sequencer restarts). Use receipts obtained via `eth_getTransactionReceipt` for final results.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxPreconfirmation {
+    pub transaction_hash: H256,
+    pub status: PreconfirmationStatus,
+    /// Always `false`; present to make non-finality explicit for clients.
+    pub is_final: bool,
+    /// Number of the L2 block the transaction is expected to be included in.
+    pub block_number: U64,
+    pub l1_batch_number: U64,
+    /// Index of the transaction in the L2 block.
+    pub transaction_index: Index,
+    /// Receipt-like fields; only set for preconfirmed transactions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub receipt: Option<PreconfirmedReceipt>,
+}
+
+/// Receipt-like execution result of a preconfirmed transaction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreconfirmedReceipt {
+    pub from: Address,
+    pub to: Address,
+    /// Execution status: 1 for success, 0 for failure (same as in transaction receipts).
+    pub execution_status: U64,
+    pub gas_used: U256,
+    pub logs: Vec<Log>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<String>,
+}
+
+/// Notification about an L1 batch reaching a certain stage of its lifecycle.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1BatchEvent {
+    pub l1_batch_number: U64,
+    pub stage: L1BatchStage,
+    /// Timestamp of the L1 batch (not of the stage change).
+    pub timestamp: U64,
+    /// Hash of the confirmed L1 transaction that moved the batch to this stage. Not set for sealed batches.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub l1_tx_hash: Option<H256>,
+}
+
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum PubSubResult {
+    // Must go before `Header` since it has stricter deserialization rules.
+    Preconfirmation(TxPreconfirmation),
+    L1Batch(L1BatchEvent),
     Header(BlockHeader),
     Log(Log),
+    Transaction(zksync_types::api::Transaction),
     TxHash(H256),
     Syncing(bool),
 }
 
 #[cfg(test)]
 mod tests {
+    use assert_matches::assert_matches;
     use zksync_types::api::{BlockId, BlockIdVariant};
 
     use super::*;
@@ -437,4 +537,51 @@ mod tests {
         let restored_value: ValueOrArray<Address> = serde_json::from_value(json).unwrap();
         assert_eq!(restored_value, value);
     }
+
+    #[test]
+    fn deserializing_pub_sub_params() {
+        let params: PubSubParams = serde_json::from_value(serde_json::json!(true)).unwrap();
+        assert_eq!(params, PubSubParams::FullTransactions(true));
+        assert!(params.full_transactions());
+
+        let params: PubSubParams =
+            serde_json::from_value(serde_json::json!({ "fullTransactions": true })).unwrap();
+        assert!(params.full_transactions());
+
+        let params: PubSubParams = serde_json::from_value(serde_json::json!({
+            "address": "0x1f1f1f1f1f1f1f1f1f1f1f1f1f1f1f1f1f1f1f1f",
+        }))
+        .unwrap();
+        assert_eq!(
+            params,
+            PubSubParams::Filter(PubSubFilter {
+                address: Some(Address::repeat_byte(0x1f).into()),
+                topics: None,
+            })
+        );
+        assert!(!params.full_transactions());
+    }
+
+    #[test]
+    fn serializing_l1_batch_event() {
+        let event = L1BatchEvent {
+            l1_batch_number: 3.into(),
+            stage: L1BatchStage::Committed,
+            timestamp: 100.into(),
+            l1_tx_hash: Some(H256::repeat_byte(1)),
+        };
+        let json = serde_json::to_value(PubSubResult::L1Batch(event.clone())).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "l1BatchNumber": "0x3",
+                "stage": "committed",
+                "timestamp": "0x64",
+                "l1TxHash": format!("{:?}", H256::repeat_byte(1)),
+            })
+        );
+
+        let restored: PubSubResult = serde_json::from_value(json).unwrap();
+        assert_matches!(restored, PubSubResult::L1Batch(restored) if restored == event);
+    }
 }
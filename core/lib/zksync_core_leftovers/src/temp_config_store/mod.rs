@@ -3,6 +3,7 @@ use std::path::PathBuf;
 use anyhow::Context;
 use zksync_config::{
     configs::{
+        address_policy::AddressPolicyConfig,
         api::{HealthCheckConfig, MerkleTreeApiConfig, Web3JsonRpcConfig},
         chain::{
             CircuitBreakerConfig, MempoolConfig, NetworkConfig, OperationsManagerConfig,
@@ -81,6 +82,7 @@ pub struct TempConfigStore {
     pub external_proof_integration_api_config: Option<ExternalProofIntegrationApiConfig>,
     pub experimental_vm_config: Option<ExperimentalVmConfig>,
     pub prover_job_monitor_config: Option<ProverJobMonitorConfig>,
+    pub address_policy_config: Option<AddressPolicyConfig>,
 }
 
 impl TempConfigStore {
@@ -122,6 +124,7 @@ impl TempConfigStore {
                 .clone(),
             experimental_vm_config: self.experimental_vm_config.clone(),
             prover_job_monitor_config: self.prover_job_monitor_config.clone(),
+            address_policy_config: self.address_policy_config.clone(),
         }
     }
 
@@ -203,6 +206,9 @@ fn load_env_config() -> anyhow::Result<TempConfigStore> {
         external_proof_integration_api_config: ExternalProofIntegrationApiConfig::from_env().ok(),
         experimental_vm_config: ExperimentalVmConfig::from_env().ok(),
         prover_job_monitor_config: ProverJobMonitorConfig::from_env().ok(),
+        address_policy_config: AddressPolicyConfig::from_env()
+            .ok()
+            .filter(AddressPolicyConfig::is_enabled),
     })
 }
 
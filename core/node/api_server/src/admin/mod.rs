@@ -0,0 +1,332 @@
+//! Admin server allowing to pause / resume node components and to trigger one-off actions at runtime.
+//!
+//! Components opt into admin controls by registering in [`AdminControls`]; the server only exposes
+//! the registered components and actions. The server requires a bearer auth token for all requests,
+//! and is supposed to be bound to a loopback interface.
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use axum::{
+    body::Bytes,
+    extract::{Path, Request, State},
+    http::{header, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Serialize;
+use tokio::sync::watch;
+
+#[cfg(test)]
+mod tests;
+
+/// State of a task registered in [`AdminControls`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskState {
+    Running,
+    Paused,
+}
+
+/// One-off action that can be triggered via the admin server (e.g., forcing an L1 batch seal).
+#[async_trait::async_trait]
+pub trait AdminAction: 'static + fmt::Debug + Send + Sync {
+    /// Runs the action with the provided params (`null` if the request has no JSON body).
+    /// The returned value is sent to the caller as a response.
+    async fn run(&self, params: serde_json::Value) -> anyhow::Result<serde_json::Value>;
+}
+
+/// Errors produced by [`AdminControls`].
+#[derive(Debug, thiserror::Error)]
+pub enum AdminControlsError {
+    #[error("task `{0}` is already registered")]
+    TaskAlreadyRegistered(&'static str),
+    #[error("action `{0}` is already registered")]
+    ActionAlreadyRegistered(&'static str),
+    #[error("task `{0}` is not registered")]
+    UnknownTask(String),
+    #[error("action `{0}` is not registered")]
+    UnknownAction(String),
+    #[error("action params are not valid JSON: {0}")]
+    InvalidParams(#[source] serde_json::Error),
+    #[error("action `{name}` failed: {err:#}")]
+    Action {
+        name: String,
+        #[source]
+        err: anyhow::Error,
+    },
+}
+
+impl IntoResponse for AdminControlsError {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            Self::UnknownTask(_) | Self::UnknownAction(_) => StatusCode::NOT_FOUND,
+            Self::InvalidParams(_) => StatusCode::BAD_REQUEST,
+            Self::TaskAlreadyRegistered(_)
+            | Self::ActionAlreadyRegistered(_)
+            | Self::Action { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        let body = serde_json::json!({ "error": self.to_string() });
+        (status, Json(body)).into_response()
+    }
+}
+
+/// Registry of components and actions controlled by the admin server.
+///
+/// A component that supports pausing registers itself via [`Self::register_task()`] and watches the returned receiver;
+/// `true` means that the component should pause at the next safe point, and `false` that it should resume.
+/// How pausing is implemented is up to the component; e.g., it can stop its main loop, or stop accepting new work.
+#[derive(Debug, Default)]
+pub struct AdminControls {
+    tasks: Mutex<BTreeMap<&'static str, watch::Sender<bool>>>,
+    actions: Mutex<BTreeMap<&'static str, Arc<dyn AdminAction>>>,
+}
+
+impl AdminControls {
+    /// Registers a task with the specified name. Returns a receiver of pause requests for the task.
+    pub fn register_task(
+        &self,
+        name: &'static str,
+    ) -> Result<watch::Receiver<bool>, AdminControlsError> {
+        let mut tasks = self.tasks.lock().expect("admin controls are poisoned");
+        if tasks.contains_key(name) {
+            return Err(AdminControlsError::TaskAlreadyRegistered(name));
+        }
+        let (pause_sender, pause_receiver) = watch::channel(false);
+        tasks.insert(name, pause_sender);
+        Ok(pause_receiver)
+    }
+
+    /// Registers a one-off action with the specified name.
+    pub fn register_action(
+        &self,
+        name: &'static str,
+        action: Arc<dyn AdminAction>,
+    ) -> Result<(), AdminControlsError> {
+        let mut actions = self.actions.lock().expect("admin controls are poisoned");
+        if actions.contains_key(name) {
+            return Err(AdminControlsError::ActionAlreadyRegistered(name));
+        }
+        actions.insert(name, action);
+        Ok(())
+    }
+
+    /// Returns states of all registered tasks.
+    pub fn tasks(&self) -> BTreeMap<&'static str, TaskState> {
+        let tasks = self.tasks.lock().expect("admin controls are poisoned");
+        tasks
+            .iter()
+            .map(|(&name, sender)| (name, Self::task_state(sender)))
+            .collect()
+    }
+
+    /// Returns names of all registered actions.
+    pub fn actions(&self) -> Vec<&'static str> {
+        let actions = self.actions.lock().expect("admin controls are poisoned");
+        actions.keys().copied().collect()
+    }
+
+    fn task_state(sender: &watch::Sender<bool>) -> TaskState {
+        if *sender.borrow() {
+            TaskState::Paused
+        } else {
+            TaskState::Running
+        }
+    }
+
+    fn set_paused(&self, name: &str, paused: bool) -> Result<TaskState, AdminControlsError> {
+        let tasks = self.tasks.lock().expect("admin controls are poisoned");
+        let sender = tasks
+            .get(name)
+            .ok_or_else(|| AdminControlsError::UnknownTask(name.to_owned()))?;
+        // The receiver may be dropped if the task has exited; we still want to record the state.
+        let was_paused = sender.send_replace(paused);
+        if was_paused != paused {
+            let action = if paused { "paused" } else { "resumed" };
+            tracing::info!("Task `{name}` was {action} via admin server");
+        }
+        Ok(Self::task_state(sender))
+    }
+
+    /// Requests the specified task to pause. This is a no-op if the task is already paused.
+    pub fn pause(&self, name: &str) -> Result<TaskState, AdminControlsError> {
+        self.set_paused(name, true)
+    }
+
+    /// Requests the specified task to resume. This is a no-op if the task is not paused.
+    pub fn resume(&self, name: &str) -> Result<TaskState, AdminControlsError> {
+        self.set_paused(name, false)
+    }
+
+    /// Runs the specified action.
+    pub async fn run_action(
+        &self,
+        name: &str,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value, AdminControlsError> {
+        let action = self
+            .actions
+            .lock()
+            .expect("admin controls are poisoned")
+            .get(name)
+            .cloned()
+            .ok_or_else(|| AdminControlsError::UnknownAction(name.to_owned()))?;
+        tracing::info!("Running action `{name}` requested via admin server");
+        action
+            .run(params)
+            .await
+            .map_err(|err| AdminControlsError::Action {
+                name: name.to_owned(),
+                err,
+            })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ServerState {
+    controls: Arc<AdminControls>,
+    auth_token: Arc<str>,
+}
+
+/// Compares tokens in constant time (w.r.t. token contents) to not leak the expected token via timing.
+fn tokens_match(expected: &[u8], actual: &[u8]) -> bool {
+    expected.len() == actual.len()
+        && expected
+            .iter()
+            .zip(actual)
+            .fold(0_u8, |acc, (x, y)| acc | (x ^ y))
+            == 0
+}
+
+async fn authorize(State(state): State<ServerState>, request: Request, next: Next) -> Response {
+    let token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    match token {
+        Some(token) if tokens_match(state.auth_token.as_bytes(), token.as_bytes()) => {
+            next.run(request).await
+        }
+        _ => StatusCode::UNAUTHORIZED.into_response(),
+    }
+}
+
+async fn list_tasks(State(state): State<ServerState>) -> Json<BTreeMap<&'static str, TaskState>> {
+    Json(state.controls.tasks())
+}
+
+async fn pause_task(
+    State(state): State<ServerState>,
+    Path(name): Path<String>,
+) -> Result<Json<TaskState>, AdminControlsError> {
+    state.controls.pause(&name).map(Json)
+}
+
+async fn resume_task(
+    State(state): State<ServerState>,
+    Path(name): Path<String>,
+) -> Result<Json<TaskState>, AdminControlsError> {
+    state.controls.resume(&name).map(Json)
+}
+
+async fn list_actions(State(state): State<ServerState>) -> Json<Vec<&'static str>> {
+    Json(state.controls.actions())
+}
+
+async fn run_action(
+    State(state): State<ServerState>,
+    Path(name): Path<String>,
+    body: Bytes,
+) -> Result<Json<serde_json::Value>, AdminControlsError> {
+    let params = if body.is_empty() {
+        serde_json::Value::Null
+    } else {
+        serde_json::from_slice(&body).map_err(AdminControlsError::InvalidParams)?
+    };
+    state.controls.run_action(&name, params).await.map(Json)
+}
+
+fn router(controls: Arc<AdminControls>, auth_token: &str) -> Router {
+    let state = ServerState {
+        controls,
+        auth_token: auth_token.into(),
+    };
+    Router::new()
+        .route("/tasks", get(list_tasks))
+        .route("/tasks/:name/pause", post(pause_task))
+        .route("/tasks/:name/resume", post(resume_task))
+        .route("/actions", get(list_actions))
+        .route("/actions/:name", post(run_action))
+        .route_layer(middleware::from_fn_with_state(state.clone(), authorize))
+        .with_state(state)
+}
+
+async fn run_server(
+    bind_address: &SocketAddr,
+    router: Router,
+    mut stop_receiver: watch::Receiver<bool>,
+) {
+    tracing::debug!("Starting admin server on {bind_address}");
+
+    let listener = tokio::net::TcpListener::bind(bind_address)
+        .await
+        .unwrap_or_else(|err| panic!("Failed binding admin server to {bind_address}: {err}"));
+    axum::serve(listener, router)
+        .with_graceful_shutdown(async move {
+            if stop_receiver.changed().await.is_err() {
+                tracing::warn!(
+                    "Stop signal sender for admin server was dropped without sending a signal"
+                );
+            }
+            tracing::info!("Stop signal received, admin server is shutting down");
+        })
+        .await
+        .expect("Admin server failed");
+    tracing::info!("Admin server shut down");
+}
+
+#[derive(Debug)]
+pub struct AdminServerHandle {
+    server: tokio::task::JoinHandle<()>,
+    stop_sender: watch::Sender<bool>,
+}
+
+impl AdminServerHandle {
+    /// Spawns the admin server. All requests to the server must contain the `Authorization: Bearer {auth_token}` header.
+    pub fn spawn_server(addr: SocketAddr, auth_token: &str, controls: Arc<AdminControls>) -> Self {
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let router = router(controls, auth_token);
+        let server = tokio::spawn(async move {
+            run_server(&addr, router, stop_receiver).await;
+        });
+
+        Self {
+            server,
+            stop_sender,
+        }
+    }
+
+    pub async fn stop(self) {
+        // See `HealthCheckHandle::stop()` for the reasoning behind the timeout.
+        const GRACEFUL_SHUTDOWN_WAIT: Duration = Duration::from_secs(10);
+
+        self.stop_sender.send(true).ok();
+        let server_result = tokio::time::timeout(GRACEFUL_SHUTDOWN_WAIT, self.server).await;
+        if let Ok(server_result) = server_result {
+            // Propagate potential panics from the server task.
+            server_result.unwrap();
+        } else {
+            tracing::debug!(
+                "Timed out {GRACEFUL_SHUTDOWN_WAIT:?} waiting for admin server to gracefully shut down"
+            );
+        }
+    }
+}
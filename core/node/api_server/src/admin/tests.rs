@@ -0,0 +1,231 @@
+//! Tests for the admin server.
+
+use assert_matches::assert_matches;
+use axum::body::Body;
+use http::Request;
+use tower::ServiceExt;
+
+use super::*;
+
+const AUTH_TOKEN: &str = "test-token";
+
+#[derive(Debug)]
+struct MockAction(anyhow::Result<serde_json::Value>);
+
+#[async_trait::async_trait]
+impl AdminAction for MockAction {
+    async fn run(&self, _params: serde_json::Value) -> anyhow::Result<serde_json::Value> {
+        match &self.0 {
+            Ok(value) => Ok(value.clone()),
+            Err(err) => Err(anyhow::anyhow!("{err}")),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct EchoAction;
+
+#[async_trait::async_trait]
+impl AdminAction for EchoAction {
+    async fn run(&self, params: serde_json::Value) -> anyhow::Result<serde_json::Value> {
+        Ok(params)
+    }
+}
+
+fn create_controls() -> Arc<AdminControls> {
+    let controls = Arc::new(AdminControls::default());
+    controls
+        .register_action(
+            "ok",
+            Arc::new(MockAction(Ok(serde_json::json!({ "sealed": true })))),
+        )
+        .unwrap();
+    controls
+        .register_action("fail", Arc::new(MockAction(Err(anyhow::anyhow!("oops")))))
+        .unwrap();
+    controls
+        .register_action("echo", Arc::new(EchoAction))
+        .unwrap();
+    controls
+}
+
+async fn send_request(
+    controls: &Arc<AdminControls>,
+    method: &str,
+    uri: &str,
+    token: Option<&str>,
+) -> (StatusCode, serde_json::Value) {
+    send_request_with_body(controls, method, uri, token, Body::empty()).await
+}
+
+async fn send_request_with_body(
+    controls: &Arc<AdminControls>,
+    method: &str,
+    uri: &str,
+    token: Option<&str>,
+    body: Body,
+) -> (StatusCode, serde_json::Value) {
+    let mut request = Request::builder().method(method).uri(uri);
+    if let Some(token) = token {
+        request = request.header(header::AUTHORIZATION, format!("Bearer {token}"));
+    }
+    let request = request.body(body).unwrap();
+    let response = router(controls.clone(), AUTH_TOKEN)
+        .oneshot(request)
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body = if body.is_empty() {
+        serde_json::Value::Null
+    } else {
+        serde_json::from_slice(&body).unwrap()
+    };
+    (status, body)
+}
+
+#[test]
+fn registering_tasks_and_actions() {
+    let controls = create_controls();
+    let pause_receiver = controls.register_task("test").unwrap();
+    assert!(!*pause_receiver.borrow());
+
+    let err = controls.register_task("test").unwrap_err();
+    assert_matches!(err, AdminControlsError::TaskAlreadyRegistered("test"));
+    let err = controls
+        .register_action("ok", Arc::new(MockAction(Ok(serde_json::Value::Null))))
+        .unwrap_err();
+    assert_matches!(err, AdminControlsError::ActionAlreadyRegistered("ok"));
+
+    assert_eq!(controls.actions(), ["echo", "fail", "ok"]);
+    assert_eq!(
+        controls.tasks(),
+        BTreeMap::from([("test", TaskState::Running)])
+    );
+}
+
+#[test]
+fn pausing_and_resuming_tasks() {
+    let controls = create_controls();
+    let pause_receiver = controls.register_task("test").unwrap();
+
+    assert_eq!(controls.pause("test").unwrap(), TaskState::Paused);
+    assert!(*pause_receiver.borrow());
+    // Pausing is idempotent.
+    assert_eq!(controls.pause("test").unwrap(), TaskState::Paused);
+    assert_eq!(
+        controls.tasks(),
+        BTreeMap::from([("test", TaskState::Paused)])
+    );
+
+    assert_eq!(controls.resume("test").unwrap(), TaskState::Running);
+    assert!(!*pause_receiver.borrow());
+
+    let err = controls.pause("unknown").unwrap_err();
+    assert_matches!(err, AdminControlsError::UnknownTask(name) if name == "unknown");
+}
+
+#[tokio::test]
+async fn server_requires_auth() {
+    let controls = create_controls();
+    for uri in ["/tasks", "/actions"] {
+        let (status, _) = send_request(&controls, "GET", uri, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = send_request(&controls, "GET", uri, Some("wrong-token")).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    let pause_receiver = controls.register_task("test").unwrap();
+    let (status, _) = send_request(&controls, "POST", "/tasks/test/pause", None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert!(!*pause_receiver.borrow());
+}
+
+#[tokio::test]
+async fn pausing_tasks_via_server() {
+    let controls = create_controls();
+    let pause_receiver = controls.register_task("test").unwrap();
+
+    let (status, body) = send_request(&controls, "GET", "/tasks", Some(AUTH_TOKEN)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, serde_json::json!({ "test": "running" }));
+
+    let (status, body) =
+        send_request(&controls, "POST", "/tasks/test/pause", Some(AUTH_TOKEN)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, "paused");
+    assert!(*pause_receiver.borrow());
+
+    let (status, body) = send_request(&controls, "GET", "/tasks", Some(AUTH_TOKEN)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, serde_json::json!({ "test": "paused" }));
+
+    let (status, body) =
+        send_request(&controls, "POST", "/tasks/test/resume", Some(AUTH_TOKEN)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, "running");
+    assert!(!*pause_receiver.borrow());
+
+    let (status, body) =
+        send_request(&controls, "POST", "/tasks/unknown/pause", Some(AUTH_TOKEN)).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(
+        body["error"].as_str().unwrap().contains("unknown"),
+        "{body}"
+    );
+}
+
+#[tokio::test]
+async fn running_actions_via_server() {
+    let controls = create_controls();
+
+    let (status, body) = send_request(&controls, "GET", "/actions", Some(AUTH_TOKEN)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, serde_json::json!(["echo", "fail", "ok"]));
+
+    let (status, body) = send_request(&controls, "POST", "/actions/ok", Some(AUTH_TOKEN)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, serde_json::json!({ "sealed": true }));
+
+    let (status, body) = send_request(&controls, "POST", "/actions/fail", Some(AUTH_TOKEN)).await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(body["error"].as_str().unwrap().contains("oops"), "{body}");
+
+    let (status, _) = send_request(&controls, "POST", "/actions/unknown", Some(AUTH_TOKEN)).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn passing_params_to_actions() {
+    let controls = create_controls();
+
+    let (status, body) = send_request(&controls, "POST", "/actions/echo", Some(AUTH_TOKEN)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, serde_json::Value::Null);
+
+    let params = serde_json::json!({ "l1_batch": 42 });
+    let (status, body) = send_request_with_body(
+        &controls,
+        "POST",
+        "/actions/echo",
+        Some(AUTH_TOKEN),
+        Body::from(params.to_string()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, params);
+
+    let (status, body) = send_request_with_body(
+        &controls,
+        "POST",
+        "/actions/echo",
+        Some(AUTH_TOKEN),
+        Body::from("{ not JSON"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["error"].as_str().unwrap().contains("JSON"), "{body}");
+}
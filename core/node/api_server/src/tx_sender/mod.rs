@@ -23,6 +23,7 @@ use zksync_multivm::{
 use zksync_node_fee_model::{ApiFeeInputProvider, BatchFeeModelInputProvider};
 use zksync_state::PostgresStorageCaches;
 use zksync_state_keeper::{
+    address_policy::{AddressPolicy, AddressPolicyStage},
     seal_criteria::{ConditionalSealer, NoopSealer, SealData},
     SequencerSealer,
 };
@@ -223,6 +224,8 @@ pub struct TxSenderBuilder {
     sealer: Option<Arc<dyn ConditionalSealer>>,
     /// Cache for tokens that are white-listed for AA.
     whitelisted_tokens_for_aa_cache: Option<Arc<RwLock<Vec<Address>>>>,
+    /// Policy restricting addresses that can participate in transactions.
+    address_policy: Option<AddressPolicy>,
 }
 
 impl TxSenderBuilder {
@@ -237,6 +240,7 @@ impl TxSenderBuilder {
             tx_sink,
             sealer: None,
             whitelisted_tokens_for_aa_cache: None,
+            address_policy: None,
         }
     }
 
@@ -250,6 +254,11 @@ impl TxSenderBuilder {
         self
     }
 
+    pub fn with_address_policy(mut self, address_policy: AddressPolicy) -> Self {
+        self.address_policy = Some(address_policy);
+        self
+    }
+
     pub fn build(
         self,
         batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
@@ -278,6 +287,7 @@ impl TxSenderBuilder {
             storage_caches,
             whitelisted_tokens_for_aa_cache,
             sealer,
+            address_policy: self.address_policy,
             executor: TransactionExecutor::real(missed_storage_invocation_limit),
         }))
     }
@@ -336,6 +346,8 @@ pub struct TxSenderInner {
     pub(super) whitelisted_tokens_for_aa_cache: Arc<RwLock<Vec<Address>>>,
     /// Batch sealer used to check whether transaction can be executed by the sequencer.
     pub(super) sealer: Arc<dyn ConditionalSealer>,
+    /// Policy restricting addresses that can participate in transactions.
+    pub(super) address_policy: Option<AddressPolicy>,
     pub(super) executor: TransactionExecutor,
 }
 
@@ -379,6 +391,7 @@ impl TxSender {
         let mut connection = self.acquire_replica_connection().await?;
         let protocol_version = connection.blocks_dal().pending_protocol_version().await?;
         drop(connection);
+        self.check_address_policy(&tx)?;
         self.validate_tx(&tx, protocol_version).await?;
         stage_latency.observe();
 
@@ -510,6 +523,23 @@ impl TxSender {
         })
     }
 
+    fn check_address_policy(&self, tx: &L2Tx) -> Result<(), SubmitTxError> {
+        let Some(address_policy) = &self.0.address_policy else {
+            return Ok(());
+        };
+        let paymaster = tx.common_data.paymaster_params.paymaster;
+        let paymaster = (paymaster != Address::zero()).then_some(paymaster);
+        let result = address_policy.check_addresses(
+            tx.initiator_account(),
+            tx.recipient_account(),
+            paymaster,
+        );
+        if let Err(violation) = &result {
+            AddressPolicy::report_rejection(AddressPolicyStage::Api, tx.hash(), violation);
+        }
+        Ok(result?)
+    }
+
     async fn validate_tx(
         &self,
         tx: &L2Tx,
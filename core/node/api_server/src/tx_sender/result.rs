@@ -1,5 +1,6 @@
 use thiserror::Error;
 use zksync_multivm::interface::{ExecutionResult, VmExecutionResultAndLogs};
+use zksync_state_keeper::address_policy::AddressPolicyViolation;
 use zksync_types::{l2::error::TxCheckError, U256};
 use zksync_web3_decl::error::EnrichedClientError;
 
@@ -72,6 +73,9 @@ pub enum SubmitTxError {
     ProxyError(#[from] EnrichedClientError),
     #[error("not enough gas to publish compressed bytecodes")]
     FailedToPublishCompressedBytecodes,
+    /// Transaction addresses are rejected by the configured address policy.
+    #[error("transaction rejected by address policy: {0}")]
+    AddressPolicyViolation(#[from] AddressPolicyViolation),
     /// Catch-all internal error (e.g., database error) that should not be exposed to the caller.
     #[error("internal error")]
     Internal(#[from] anyhow::Error),
@@ -107,6 +111,7 @@ impl SubmitTxError {
             Self::IntrinsicGas => "intrinsic-gas",
             Self::ProxyError(_) => "proxy-error",
             Self::FailedToPublishCompressedBytecodes => "failed-to-publish-compressed-bytecodes",
+            Self::AddressPolicyViolation(_) => "address-policy-violation",
             Self::Internal(_) => "internal",
         }
     }
@@ -1,10 +1,14 @@
 use std::{
     cell::RefCell,
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     future::Future,
+    net::{IpAddr, SocketAddr},
     num::NonZeroU32,
     pin::Pin,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Weak,
+    },
     task::{Context, Poll},
     time::{Duration, Instant},
 };
@@ -12,7 +16,7 @@ use std::{
 use governor::{
     clock::DefaultClock,
     middleware::NoOpMiddleware,
-    state::{InMemoryState, NotKeyed},
+    state::{keyed::DefaultKeyedStateStore, InMemoryState, NotKeyed},
     Quota, RateLimiter,
 };
 use once_cell::sync::OnceCell;
@@ -21,8 +25,11 @@ use rand::{rngs::SmallRng, RngCore, SeedableRng};
 use tokio::sync::watch;
 use tracing::instrument::{Instrument, Instrumented};
 use vise::{
-    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, GaugeGuard, Histogram, Metrics,
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, GaugeGuard, Histogram,
+    LabeledFamily, Metrics,
 };
+use zksync_config::configs::api::ComputeUnitsMap;
+use zksync_types::web3::keccak256;
 use zksync_web3_decl::jsonrpsee::{
     server::middleware::rpc::{layer::ResponseFuture, RpcServiceT},
     types::{error::ErrorCode, ErrorObject, Request},
@@ -30,7 +37,10 @@ use zksync_web3_decl::jsonrpsee::{
 };
 
 use super::metadata::{MethodCall, MethodTracer};
-use crate::web3::metrics::{ObservedRpcParams, API_METRICS};
+use crate::web3::{
+    metrics::{ObservedRpcParams, API_METRICS},
+    HttpRateLimits,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
 #[metrics(label = "transport", rename_all = "snake_case")]
@@ -105,6 +115,386 @@ where
     }
 }
 
+/// Remote address of an HTTP connection. Inserted into request extensions by [`RemoteAddrService`]
+/// since `jsonrpsee` doesn't expose it to middleware.
+#[derive(Debug, Clone, Copy)]
+struct RemoteAddr(SocketAddr);
+
+/// [`tower`] service wrapping a per-connection HTTP service and inserting the [`RemoteAddr`] of the connection
+/// into request extensions.
+#[derive(Debug, Clone)]
+pub(crate) struct RemoteAddrService<S> {
+    inner: S,
+    remote_addr: SocketAddr,
+}
+
+impl<S> RemoteAddrService<S> {
+    pub(crate) fn new(inner: S, remote_addr: SocketAddr) -> Self {
+        Self { inner, remote_addr }
+    }
+}
+
+impl<S, B> tower::Service<http::Request<B>> for RemoteAddrService<S>
+where
+    S: tower::Service<http::Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: http::Request<B>) -> Self::Future {
+        request
+            .extensions_mut()
+            .insert(RemoteAddr(self.remote_addr));
+        self.inner.call(request)
+    }
+}
+
+/// Information about an HTTP client extracted from the connection and request headers. Inserted into request extensions
+/// by [`HttpClientInfoLayer`] and consumed by [`HttpRateLimitMiddleware`].
+#[derive(Debug, Clone, Default)]
+struct HttpClientInfo {
+    api_key: Option<String>,
+    /// IP address of the client; `None` if the client cannot be reliably identified.
+    ip: Option<IpAddr>,
+    /// Set by [`HttpRateLimitMiddleware`] if any call in the HTTP request was rate-limited.
+    rate_limited: Arc<AtomicBool>,
+}
+
+impl HttpClientInfo {
+    const API_KEY_HEADER: &'static str = "x-api-key";
+    const FORWARDED_FOR_HEADER: &'static str = "x-forwarded-for";
+    const REAL_IP_HEADER: &'static str = "x-real-ip";
+
+    fn new(
+        remote_ip: Option<IpAddr>,
+        headers: &http::HeaderMap,
+        trusted_proxies: &HashSet<IpAddr>,
+    ) -> Self {
+        let api_key = headers
+            .get(Self::API_KEY_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(|key| key.trim().to_owned());
+        let ip = remote_ip.and_then(|ip| Self::client_ip(ip, headers, trusted_proxies));
+        Self {
+            api_key,
+            ip,
+            rate_limited: Arc::default(),
+        }
+    }
+
+    /// Determines the client IP address. Forwarding headers are only honored if the connection originates
+    /// from a trusted proxy; otherwise, they can be trivially spoofed by the client. In the former case, the client
+    /// is the rightmost untrusted address in `x-forwarded-for` (addresses to the left of it are controlled
+    /// by the client). Returns `None` if headers are malformed or don't contain an untrusted address.
+    fn client_ip(
+        remote_ip: IpAddr,
+        headers: &http::HeaderMap,
+        trusted_proxies: &HashSet<IpAddr>,
+    ) -> Option<IpAddr> {
+        if !trusted_proxies.contains(&remote_ip) {
+            return Some(remote_ip);
+        }
+
+        let forwarded_for: Vec<_> = headers
+            .get_all(Self::FORWARDED_FOR_HEADER)
+            .iter()
+            .map(|value| value.to_str().ok())
+            .collect::<Option<_>>()?;
+        if !forwarded_for.is_empty() {
+            // The header may be repeated, in which case values are concatenated in the order of appearance.
+            let hops = forwarded_for.iter().flat_map(|value| value.split(','));
+            for hop in hops.rev() {
+                let ip: IpAddr = hop.trim().parse().ok()?;
+                if !trusted_proxies.contains(&ip) {
+                    return Some(ip);
+                }
+            }
+            return None;
+        }
+
+        let real_ip = headers
+            .get(Self::REAL_IP_HEADER)?
+            .to_str()
+            .ok()?
+            .trim()
+            .parse()
+            .ok()?;
+        (!trusted_proxies.contains(&real_ip)).then_some(real_ip)
+    }
+}
+
+/// HTTP-level [`tower`] layer that identifies clients for [`HttpRateLimitMiddleware`], and sets the HTTP status
+/// of responses to 429 Too Many Requests if any of the calls in the request was rate-limited.
+#[derive(Debug, Clone)]
+pub(crate) struct HttpClientInfoLayer {
+    trusted_proxies: Arc<HashSet<IpAddr>>,
+}
+
+impl HttpClientInfoLayer {
+    pub(crate) fn new(trusted_proxies: HashSet<IpAddr>) -> Self {
+        Self {
+            trusted_proxies: Arc::new(trusted_proxies),
+        }
+    }
+}
+
+impl<S> tower::Layer<S> for HttpClientInfoLayer {
+    type Service = HttpClientInfoService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        HttpClientInfoService {
+            inner,
+            trusted_proxies: self.trusted_proxies.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct HttpClientInfoService<S> {
+    inner: S,
+    trusted_proxies: Arc<HashSet<IpAddr>>,
+}
+
+impl<S, B, ResB> tower::Service<http::Request<B>> for HttpClientInfoService<S>
+where
+    S: tower::Service<http::Request<B>, Response = http::Response<ResB>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = WithRateLimitStatus<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: http::Request<B>) -> Self::Future {
+        let remote_ip = request
+            .extensions()
+            .get::<RemoteAddr>()
+            .map(|RemoteAddr(addr)| addr.ip());
+        let client_info = HttpClientInfo::new(remote_ip, request.headers(), &self.trusted_proxies);
+        let rate_limited = client_info.rate_limited.clone();
+        request.extensions_mut().insert(client_info);
+        WithRateLimitStatus {
+            inner: self.inner.call(request),
+            rate_limited,
+        }
+    }
+}
+
+pin_project! {
+    #[derive(Debug)]
+    pub(crate) struct WithRateLimitStatus<F> {
+        #[pin]
+        inner: F,
+        rate_limited: Arc<AtomicBool>,
+    }
+}
+
+impl<F, ResB, E> Future for WithRateLimitStatus<F>
+where
+    F: Future<Output = Result<http::Response<ResB>, E>>,
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let projection = self.project();
+        let mut output = futures::ready!(projection.inner.poll(cx));
+        if let Ok(response) = &mut output {
+            if projection.rate_limited.load(Ordering::Relaxed) {
+                *response.status_mut() = http::StatusCode::TOO_MANY_REQUESTS;
+            }
+        }
+        Poll::Ready(output)
+    }
+}
+
+/// Label used in metrics for clients identified by IP addresses.
+const IP_CLIENT_LABEL: &str = "ip";
+/// Label used in metrics for clients that cannot be identified.
+const UNIDENTIFIED_CLIENT_LABEL: &str = "unidentified";
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "api_jsonrpc_backend_http")]
+struct HttpRateLimitMetrics {
+    /// Compute units spent by HTTP clients. Clients authenticated with API keys are labeled with
+    /// a hash digest of the key; clients identified by IP addresses and unidentified clients are aggregated.
+    #[metrics(labels = ["client"])]
+    compute_units: LabeledFamily<String, Counter>,
+    /// Number of rate-limited HTTP calls.
+    #[metrics(labels = ["client"])]
+    rate_limited: LabeledFamily<String, Counter>,
+}
+
+#[vise::register]
+static HTTP_METRICS: vise::Global<HttpRateLimitMetrics> = vise::Global::new();
+
+type DirectRateLimiter = RateLimiter<NotKeyed, InMemoryState, DefaultClock, NoOpMiddleware>;
+type IpRateLimiter =
+    RateLimiter<IpAddr, DefaultKeyedStateStore<IpAddr>, DefaultClock, NoOpMiddleware>;
+
+struct ApiKeyRateLimiter {
+    limiter: DirectRateLimiter,
+    /// Metrics label for the key. We don't use the key itself to not leak it.
+    label: String,
+}
+
+impl ApiKeyRateLimiter {
+    fn new(api_key: &str, compute_units_per_minute: NonZeroU32) -> Self {
+        Self {
+            limiter: RateLimiter::direct(Quota::per_minute(compute_units_per_minute)),
+            label: hex::encode(&keccak256(api_key.as_bytes())[..4]),
+        }
+    }
+}
+
+/// Compute unit-based rate limiter for HTTP clients shared among all HTTP connections.
+pub(crate) struct HttpRateLimiter {
+    ip_limiter: IpRateLimiter,
+    /// Limiter shared by all clients without an identifiable IP address, so that such clients cannot bypass limits.
+    unidentified_limiter: DirectRateLimiter,
+    api_key_limiters: HashMap<String, ApiKeyRateLimiter>,
+    method_compute_units: ComputeUnitsMap,
+}
+
+impl HttpRateLimiter {
+    /// Interval to prune state for IP addresses that didn't send requests recently.
+    const PRUNING_INTERVAL: Duration = Duration::from_secs(60);
+
+    pub fn new(limits: &HttpRateLimits) -> Self {
+        let api_key_limiters = limits
+            .api_key_limits
+            .iter()
+            .map(|(key, limit)| (key.to_owned(), ApiKeyRateLimiter::new(key, limit)))
+            .collect();
+        Self {
+            ip_limiter: RateLimiter::keyed(Quota::per_minute(limits.compute_units_per_minute)),
+            unidentified_limiter: RateLimiter::direct(Quota::per_minute(
+                limits.compute_units_per_minute,
+            )),
+            api_key_limiters,
+            method_compute_units: limits.method_compute_units.clone(),
+        }
+    }
+
+    /// Spawns a task periodically pruning the IP limiter state. The task terminates when the limiter is dropped
+    /// or a stop signal is received.
+    pub fn spawn_pruning_task(this: Weak<Self>, mut stop_receiver: watch::Receiver<bool>) {
+        tokio::spawn(async move {
+            loop {
+                if tokio::time::timeout(Self::PRUNING_INTERVAL, stop_receiver.changed())
+                    .await
+                    .is_ok()
+                {
+                    break; // Stop signal was received, or its sender was dropped
+                }
+                let Some(this) = this.upgrade() else {
+                    break;
+                };
+                this.ip_limiter.retain_recent();
+                this.ip_limiter.shrink_to_fit();
+            }
+        });
+    }
+
+    /// Returns the compute unit cost of the specified method.
+    fn method_cost(&self, method: &str) -> NonZeroU32 {
+        self.method_compute_units
+            .get(method)
+            .or_else(|| default_method_cost(method))
+            .unwrap_or(NonZeroU32::MIN)
+    }
+
+    /// Checks whether a call to `method` by the specified client should be allowed. Clients not identified
+    /// either by a known API key or by IP address share a single limit.
+    fn check(&self, client: &HttpClientInfo, method: &str) -> bool {
+        let cost = self.method_cost(method);
+        let api_key_limiter = client
+            .api_key
+            .as_ref()
+            .and_then(|key| self.api_key_limiters.get(key));
+
+        let (is_allowed, label) = if let Some(limiter) = api_key_limiter {
+            let is_allowed = limiter.limiter.check_n(cost).is_ok();
+            (is_allowed, limiter.label.as_str())
+        } else if let Some(ip) = &client.ip {
+            let is_allowed = self.ip_limiter.check_key_n(ip, cost).is_ok();
+            (is_allowed, IP_CLIENT_LABEL)
+        } else {
+            let is_allowed = self.unidentified_limiter.check_n(cost).is_ok();
+            (is_allowed, UNIDENTIFIED_CLIENT_LABEL)
+        };
+
+        if is_allowed {
+            HTTP_METRICS.compute_units[label].inc_by(cost.get().into());
+        } else {
+            HTTP_METRICS.rate_limited[label].inc();
+        }
+        is_allowed
+    }
+}
+
+/// Built-in compute unit costs for expensive RPC methods. Methods not listed here cost 1 compute unit.
+fn default_method_cost(method: &str) -> Option<NonZeroU32> {
+    let cost = match method {
+        "debug_traceBlockByNumber" | "debug_traceBlockByHash" | "trace_filter" => 100,
+        "debug_traceCall" | "trace_block" => 50,
+        "debug_traceTransaction" | "eth_getLogs" | "zks_getProof" => 20,
+        "eth_call"
+        | "eth_estimateGas"
+        | "eth_sendRawTransaction"
+        | "zks_estimateFee"
+        | "zks_estimateGasL1ToL2" => 10,
+        _ => return None,
+    };
+    NonZeroU32::new(cost)
+}
+
+/// RPC-level middleware enforcing compute unit limits for HTTP clients. Calls exceeding the limit are rejected
+/// with the -32005 "Limit exceeded" error code (the same code as used by Geth-compatible providers); the HTTP status
+/// of the corresponding response is set to 429 by [`HttpClientInfoLayer`].
+pub(crate) struct HttpRateLimitMiddleware<S> {
+    inner: S,
+    limiter: Arc<HttpRateLimiter>,
+}
+
+impl<S> HttpRateLimitMiddleware<S> {
+    /// JSON-RPC error code returned for rate-limited calls.
+    const ERROR_CODE: i32 = -32_005;
+
+    pub(crate) fn new(inner: S, limiter: Arc<HttpRateLimiter>) -> Self {
+        Self { inner, limiter }
+    }
+}
+
+impl<'a, S> RpcServiceT<'a> for HttpRateLimitMiddleware<S>
+where
+    S: Send + Sync + RpcServiceT<'a>,
+{
+    type Future = ResponseFuture<S::Future>;
+
+    fn call(&self, request: Request<'a>) -> Self::Future {
+        let Some(client) = request.extensions().get::<HttpClientInfo>() else {
+            return ResponseFuture::future(self.inner.call(request));
+        };
+
+        if !self.limiter.check(client, request.method_name()) {
+            client.rate_limited.store(true, Ordering::Relaxed);
+            let rp = MethodResponse::error(
+                request.id,
+                ErrorObject::borrowed(Self::ERROR_CODE, "Rate limit exceeded", None),
+            );
+            return ResponseFuture::ready(rp);
+        }
+        ResponseFuture::future(self.inner.call(request))
+    }
+}
+
 /// RPC-level middleware that adds [`MethodCall`] metadata to method logic. Method handlers can then access this metadata
 /// using [`MethodTracer`], which is a part of `RpcState`. When the handler completes or is dropped, the results are reported
 /// as metrics.
@@ -398,6 +788,112 @@ mod tests {
         }
     }
 
+    #[test]
+    fn extracting_http_client_info() {
+        let remote_ip = IpAddr::from([192, 168, 0, 1]);
+        let trusted_proxies = HashSet::from([remote_ip, [10, 0, 0, 100].into()]);
+        let mut headers = http::HeaderMap::new();
+        let info = HttpClientInfo::new(None, &headers, &trusted_proxies);
+        assert_eq!(info.api_key, None);
+        assert_eq!(info.ip, None);
+
+        let direct_ip = IpAddr::from([1, 2, 3, 4]);
+        let info = HttpClientInfo::new(Some(direct_ip), &headers, &trusted_proxies);
+        assert_eq!(info.ip, Some(direct_ip));
+        // A trusted proxy not forwarding client info cannot be used to identify the client.
+        let info = HttpClientInfo::new(Some(remote_ip), &headers, &trusted_proxies);
+        assert_eq!(info.ip, None);
+
+        headers.insert("x-real-ip", "10.0.0.2".parse().unwrap());
+        let info = HttpClientInfo::new(Some(remote_ip), &headers, &trusted_proxies);
+        assert_eq!(info.ip, Some([10, 0, 0, 2].into()));
+        // Forwarding headers are ignored for untrusted connections.
+        let info = HttpClientInfo::new(Some(direct_ip), &headers, &trusted_proxies);
+        assert_eq!(info.ip, Some(direct_ip));
+
+        // The rightmost untrusted address is used; addresses to the left of it can be spoofed by the client.
+        headers.insert(
+            "x-forwarded-for",
+            "6.6.6.6, 10.0.0.1, 10.0.0.100".parse().unwrap(),
+        );
+        headers.insert("x-api-key", "test-key".parse().unwrap());
+        let info = HttpClientInfo::new(Some(remote_ip), &headers, &trusted_proxies);
+        assert_eq!(info.api_key.as_deref(), Some("test-key"));
+        assert_eq!(info.ip, Some([10, 0, 0, 1].into()));
+        let info = HttpClientInfo::new(Some(direct_ip), &headers, &trusted_proxies);
+        assert_eq!(info.ip, Some(direct_ip));
+        let info = HttpClientInfo::new(Some(remote_ip), &headers, &HashSet::new());
+        assert_eq!(info.ip, Some(remote_ip));
+
+        // Repeated headers are concatenated.
+        headers.append("x-forwarded-for", "10.0.0.3".parse().unwrap());
+        let info = HttpClientInfo::new(Some(remote_ip), &headers, &trusted_proxies);
+        assert_eq!(info.ip, Some([10, 0, 0, 3].into()));
+
+        // Malformed or fully trusted forwarding chains don't identify the client.
+        headers.insert("x-forwarded-for", "10.0.0.1, garbage".parse().unwrap());
+        let info = HttpClientInfo::new(Some(remote_ip), &headers, &trusted_proxies);
+        assert_eq!(info.ip, None);
+        headers.insert("x-forwarded-for", "10.0.0.100".parse().unwrap());
+        let info = HttpClientInfo::new(Some(remote_ip), &headers, &trusted_proxies);
+        assert_eq!(info.ip, None);
+    }
+
+    #[test]
+    fn http_rate_limiter_basics() {
+        let limits = HttpRateLimits {
+            compute_units_per_minute: NonZeroU32::new(100).unwrap(),
+            api_key_limits: [("test-key", NonZeroU32::new(1_000).unwrap())]
+                .into_iter()
+                .collect(),
+            method_compute_units: [("eth_call", NonZeroU32::new(30).unwrap())]
+                .into_iter()
+                .collect(),
+            trusted_proxies: HashSet::new(),
+        };
+        let limiter = HttpRateLimiter::new(&limits);
+        assert_eq!(limiter.method_cost("eth_chainId"), NonZeroU32::MIN);
+        assert_eq!(limiter.method_cost("eth_call").get(), 30);
+        assert_eq!(limiter.method_cost("debug_traceCall").get(), 50);
+
+        let ip_client = HttpClientInfo {
+            ip: Some([10, 0, 0, 1].into()),
+            ..HttpClientInfo::default()
+        };
+        for _ in 0..3 {
+            assert!(limiter.check(&ip_client, "eth_call"));
+        }
+        assert!(!limiter.check(&ip_client, "eth_call"));
+        // Limits are tracked separately for each IP address.
+        let other_ip_client = HttpClientInfo {
+            ip: Some([10, 0, 0, 2].into()),
+            ..HttpClientInfo::default()
+        };
+        assert!(limiter.check(&other_ip_client, "eth_call"));
+
+        // Known API keys take precedence over IP addresses.
+        let api_key_client = HttpClientInfo {
+            api_key: Some("test-key".to_owned()),
+            ..ip_client.clone()
+        };
+        for _ in 0..33 {
+            assert!(limiter.check(&api_key_client, "eth_call"));
+        }
+        assert!(!limiter.check(&api_key_client, "eth_call"));
+
+        // Unknown API keys fall back to IP-based limits.
+        let unknown_key_client = HttpClientInfo {
+            api_key: Some("unknown".to_owned()),
+            ..ip_client
+        };
+        assert!(!limiter.check(&unknown_key_client, "eth_call"));
+
+        // Unidentified clients share a single limit.
+        assert!(limiter.check(&HttpClientInfo::default(), "debug_traceBlockByNumber"));
+        assert!(!limiter.check(&HttpClientInfo::default(), "eth_chainId"));
+        assert!(limiter.check(&other_ip_client, "eth_chainId"));
+    }
+
     #[tokio::test]
     async fn traffic_tracker_basics() {
         let traffic_tracker = TrafficTracker::default();
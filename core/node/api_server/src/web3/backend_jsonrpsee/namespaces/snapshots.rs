@@ -10,7 +10,7 @@ use crate::web3::namespaces::SnapshotsNamespace;
 #[async_trait]
 impl SnapshotsNamespaceServer for SnapshotsNamespace {
     async fn get_all_snapshots(&self) -> RpcResult<AllSnapshots> {
-        self.get_all_snapshots_impl()
+        self.get_all_snapshots_impl(false)
             .await
             .map_err(|err| self.current_method().map_err(err))
     }
@@ -19,7 +19,22 @@ impl SnapshotsNamespaceServer for SnapshotsNamespace {
         &self,
         l1_batch_number: L1BatchNumber,
     ) -> RpcResult<Option<SnapshotHeader>> {
-        self.get_snapshot_by_l1_batch_number_impl(l1_batch_number)
+        self.get_snapshot_by_l1_batch_number_impl(l1_batch_number, false)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn get_all_snapshots_with_deltas(&self) -> RpcResult<AllSnapshots> {
+        self.get_all_snapshots_impl(true)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn get_snapshot_with_deltas(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<SnapshotHeader>> {
+        self.get_snapshot_by_l1_batch_number_impl(l1_batch_number, true)
             .await
             .map_err(|err| self.current_method().map_err(err))
     }
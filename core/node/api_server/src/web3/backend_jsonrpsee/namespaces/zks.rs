@@ -4,8 +4,8 @@ use zksync_multivm::interface::VmEvent;
 use zksync_types::{
     api::{
         state_override::StateOverride, ApiStorageLog, BlockDetails, BridgeAddresses,
-        L1BatchDetails, L2ToL1LogProof, Log, Proof, ProtocolVersion, TransactionDetailedResult,
-        TransactionDetails,
+        L1BatchDetails, L2ToL1LogProof, Log, Proof, ProofOptions, ProtocolVersion,
+        TransactionDetailedResult, TransactionDetails,
     },
     fee::Fee,
     fee_model::{FeeParams, PubdataIndependentBatchFeeModelInput},
@@ -182,8 +182,9 @@ impl ZksNamespaceServer for ZksNamespace {
         address: Address,
         keys: Vec<H256>,
         l1_batch_number: L1BatchNumber,
+        options: Option<ProofOptions>,
     ) -> RpcResult<Option<Proof>> {
-        self.get_proofs_impl(address, keys, l1_batch_number)
+        self.get_proofs_impl(address, keys, l1_batch_number, options.unwrap_or_default())
             .await
             .map_err(|err| self.current_method().map_err(err))
     }
@@ -0,0 +1,503 @@
+//! JSON-RPC transport over IPC (Unix domain sockets).
+//!
+//! The wire format is the same as for Geth / Reth IPC endpoints: requests and responses are JSON values written
+//! to the socket one after another (responses are additionally delimited by newlines). Subscriptions are supported
+//! in the same way as for WebSocket connections; subscription notifications are interleaved with method responses.
+//!
+//! Method calls are dispatched directly to the RPC module, i.e., RPC-level middleware (method metrics,
+//! rate limiting) doesn't apply to IPC calls. This is intentional: IPC is only accessible by co-located
+//! services, and access control is performed using filesystem permissions on the socket (see [`IpcConfig`]).
+//! Resource usage is bounded by the limits on the number of connections, subscriptions per connection
+//! and concurrently processed requests per connection. Otherwise, calls are handled in the same way as by the WebSocket server:
+//! subscriptions are bound to the connection that has created them, subscription IDs are generated by
+//! [`EthSubscriptionIdProvider`], and response size limits are enforced.
+
+use std::{
+    fs::Permissions,
+    os::unix::fs::{FileTypeExt, PermissionsExt},
+    path::Path,
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::Context as _;
+use serde_json::Value;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{unix::OwnedWriteHalf, UnixListener, UnixStream},
+    sync::{mpsc, watch, Semaphore},
+};
+use zksync_web3_decl::jsonrpsee::{
+    core::server::{ConnectionId, MethodResponse, MethodSink, SubscriptionState},
+    types::{
+        error::{ErrorCode, TOO_MANY_SUBSCRIPTIONS_CODE, TOO_MANY_SUBSCRIPTIONS_MSG},
+        ErrorObject, Params, Request,
+    },
+    MethodCallback, Methods,
+};
+
+use super::{pubsub::EthSubscriptionIdProvider, ACCEPT_ERROR_BACKOFF};
+
+/// Capacity of the buffer for subscription notifications sent to a single subscription.
+const SUBSCRIPTION_BUFFER_CAPACITY: usize = 1_024;
+/// Capacity of the outgoing message queue for a single connection.
+const OUTGOING_QUEUE_CAPACITY: usize = 1_024;
+/// Maximum size of a single incoming request. Connections sending larger requests are terminated.
+const MAX_REQUEST_SIZE: usize = 10 << 20; // 10 MiB
+/// Timeout to flush pending responses after the connection is closed by the client.
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Configuration of the IPC transport.
+#[derive(Debug, Clone)]
+pub struct IpcConfig {
+    /// Maximum number of concurrent connections. Connections exceeding the limit are closed immediately.
+    pub max_connections: usize,
+    /// Maximum number of active subscriptions for a single connection.
+    pub max_subscriptions_per_connection: usize,
+    /// Maximum number of requests processed concurrently for a single connection. Once the limit is reached,
+    /// the server stops reading from the connection until some of the in-flight requests complete.
+    pub max_concurrent_requests_per_connection: usize,
+    /// Unix permissions set for the socket file. If not set, permissions are determined by the process umask.
+    pub socket_permissions: Option<u32>,
+}
+
+impl Default for IpcConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: 1_000,
+            max_subscriptions_per_connection: 1_000,
+            max_concurrent_requests_per_connection: 64,
+            socket_permissions: None,
+        }
+    }
+}
+
+/// Settings for [`IpcServer`].
+#[derive(Debug)]
+pub(super) struct IpcServer {
+    pub methods: Methods,
+    pub config: IpcConfig,
+    pub batch_request_size_limit: Option<usize>,
+    /// Global response size limit; overridden for specific methods when constructing `methods`.
+    pub response_body_size_limit: u32,
+}
+
+/// Receiver of subscription notifications. The subscription permit counted towards the per-connection subscription limit
+/// is held by the subscription sink, i.e., it is released once the subscription is terminated.
+type Notifications = mpsc::Receiver<String>;
+
+impl IpcServer {
+    /// Binds a Unix socket at the specified path and sets its permissions if they are configured.
+    /// If a socket file already exists at the path (e.g., left after an unclean shutdown), it is removed.
+    /// If the path is occupied by anything other than a socket, an error is returned.
+    pub fn bind(path: &Path, permissions: Option<u32>) -> anyhow::Result<UnixListener> {
+        match std::fs::symlink_metadata(path) {
+            Ok(metadata) => {
+                anyhow::ensure!(
+                    metadata.file_type().is_socket(),
+                    "cannot bind IPC socket at `{}`: path is occupied by a non-socket file",
+                    path.display()
+                );
+                std::fs::remove_file(path).with_context(|| {
+                    format!("failed removing stale IPC socket at `{}`", path.display())
+                })?;
+                tracing::info!("Removed stale IPC socket at `{}`", path.display());
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => { /* OK */ }
+            Err(err) => {
+                return Err(err).with_context(|| {
+                    format!(
+                        "failed getting metadata for IPC socket at `{}`",
+                        path.display()
+                    )
+                });
+            }
+        }
+        let listener = UnixListener::bind(path)
+            .with_context(|| format!("failed binding IPC socket at `{}`", path.display()))?;
+        if let Some(mode) = permissions {
+            std::fs::set_permissions(path, Permissions::from_mode(mode)).with_context(|| {
+                format!(
+                    "failed setting permissions {mode:o} for IPC socket at `{}`",
+                    path.display()
+                )
+            })?;
+        }
+        Ok(listener)
+    }
+
+    /// Accepts and serves connections until a stop signal is received.
+    pub async fn run(
+        self,
+        listener: UnixListener,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        let this = Arc::new(self);
+        let connection_permits = Arc::new(Semaphore::new(this.config.max_connections));
+        let mut next_connection_id = 0;
+        while !*stop_receiver.borrow() {
+            let accept_result = tokio::select! {
+                res = listener.accept() => res,
+                _ = stop_receiver.changed() => break,
+            };
+            let stream = match accept_result {
+                Ok((stream, _)) => stream,
+                Err(err) => {
+                    tracing::warn!(
+                        "Failed accepting IPC connection: {err}; retrying in {ACCEPT_ERROR_BACKOFF:?}"
+                    );
+                    tokio::select! {
+                        () = tokio::time::sleep(ACCEPT_ERROR_BACKOFF) => continue,
+                        _ = stop_receiver.changed() => break,
+                    }
+                }
+            };
+            let Ok(permit) = connection_permits.clone().try_acquire_owned() else {
+                tracing::warn!(
+                    "Rejecting IPC connection: reached the limit of {} concurrent connections",
+                    this.config.max_connections
+                );
+                continue;
+            };
+
+            let connection_id = ConnectionId(next_connection_id);
+            next_connection_id += 1;
+            let this = this.clone();
+            let stop_receiver = stop_receiver.clone();
+            tokio::spawn(async move {
+                if let Err(err) = this
+                    .serve_connection(stream, connection_id, stop_receiver)
+                    .await
+                {
+                    tracing::info!("IPC connection terminated with error: {err:#}");
+                }
+                drop(permit);
+            });
+        }
+        tracing::info!("Stop signal received, IPC JSON-RPC server is shutting down");
+        Ok(())
+    }
+
+    async fn serve_connection(
+        self: Arc<Self>,
+        stream: UnixStream,
+        connection_id: ConnectionId,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        let (mut reader, writer) = stream.into_split();
+        let (outgoing_sender, outgoing_receiver) = mpsc::channel(OUTGOING_QUEUE_CAPACITY);
+        let write_task = tokio::spawn(Self::write_messages(writer, outgoing_receiver));
+        let request_permits = Arc::new(Semaphore::new(
+            self.config.max_concurrent_requests_per_connection,
+        ));
+        let subscription_permits =
+            Arc::new(Semaphore::new(self.config.max_subscriptions_per_connection));
+
+        let mut buffer = Vec::new();
+        let read_result = 'read: loop {
+            let read_bytes = tokio::select! {
+                res = reader.read_buf(&mut buffer) => res.context("failed reading from IPC socket"),
+                _ = stop_receiver.changed() => break Ok(()),
+            };
+            match read_bytes {
+                Ok(0) => break Ok(()), // The client has closed the connection
+                Ok(_) => { /* continue processing */ }
+                Err(err) => break Err(err),
+            }
+
+            let (requests, consumed_len) = match split_json_values(&buffer) {
+                Ok(output) => output,
+                Err(err) => {
+                    let response =
+                        error_response(&Value::Null, ErrorCode::ParseError, &err.to_string());
+                    outgoing_sender.send(response).await.ok();
+                    break Err(anyhow::Error::from(err).context("malformed IPC request"));
+                }
+            };
+            buffer.drain(..consumed_len);
+            if buffer.len() > MAX_REQUEST_SIZE {
+                let message = format!("request exceeds {MAX_REQUEST_SIZE} bytes");
+                let response = error_response(&Value::Null, ErrorCode::OversizedRequest, &message);
+                outgoing_sender.send(response).await.ok();
+                break Err(anyhow::anyhow!("IPC {message}"));
+            }
+
+            for request in requests {
+                // Stop reading from the connection (i.e., apply backpressure) if the concurrent request limit is reached.
+                let request_permit = tokio::select! {
+                    permit = request_permits.clone().acquire_owned() => {
+                        permit.expect("request semaphore is never closed")
+                    }
+                    _ = stop_receiver.changed() => break 'read Ok(()),
+                };
+                let this = self.clone();
+                let outgoing_sender = outgoing_sender.clone();
+                let subscription_permits = subscription_permits.clone();
+                tokio::spawn(async move {
+                    let (response, notifications) = this
+                        .handle_request(request, connection_id, &subscription_permits)
+                        .await;
+                    if let Some(response) = response {
+                        if outgoing_sender.send(response).await.is_err() {
+                            return; // The connection is closed
+                        }
+                    }
+                    drop(request_permit);
+                    // Subscription notifications must be forwarded after the subscription ID is returned.
+                    for notifications in notifications {
+                        tokio::spawn(forward_notifications(
+                            notifications,
+                            outgoing_sender.clone(),
+                        ));
+                    }
+                });
+            }
+        };
+
+        // Only in-flight request handlers and subscriptions can hold senders after this point.
+        drop(outgoing_sender);
+        if *stop_receiver.borrow() {
+            write_task.abort();
+        } else {
+            // Flush pending responses, e.g. to the requests received before the client has closed its write half.
+            let write_abort_handle = write_task.abort_handle();
+            if tokio::time::timeout(FLUSH_TIMEOUT, write_task)
+                .await
+                .is_err()
+            {
+                write_abort_handle.abort();
+            }
+        }
+        read_result
+    }
+
+    async fn write_messages(
+        mut writer: OwnedWriteHalf,
+        mut outgoing_receiver: mpsc::Receiver<String>,
+    ) -> anyhow::Result<()> {
+        while let Some(mut message) = outgoing_receiver.recv().await {
+            message.push('\n');
+            writer
+                .write_all(message.as_bytes())
+                .await
+                .context("failed writing to IPC socket")?;
+        }
+        Ok(())
+    }
+
+    /// Handles a single request or a batch of requests. Returns the response (`None` if no response should be sent)
+    /// and receivers for subscription notifications.
+    async fn handle_request(
+        &self,
+        request: Value,
+        connection_id: ConnectionId,
+        subscription_permits: &Arc<Semaphore>,
+    ) -> (Option<String>, Vec<Notifications>) {
+        let Value::Array(requests) = request else {
+            let (response, notifications) = self
+                .handle_single_request(request, connection_id, subscription_permits)
+                .await;
+            return (response, notifications.into_iter().collect());
+        };
+
+        if requests.is_empty() {
+            let response = error_response(&Value::Null, ErrorCode::InvalidRequest, "empty batch");
+            return (Some(response), vec![]);
+        }
+        if let Some(limit) = self.batch_request_size_limit {
+            if requests.len() > limit {
+                let message = format!("batch request size exceeds limit of {limit} requests");
+                let response = error_response(&Value::Null, ErrorCode::InvalidRequest, &message);
+                return (Some(response), vec![]);
+            }
+        }
+
+        let mut responses = Vec::with_capacity(requests.len());
+        let mut all_notifications = vec![];
+        for request in requests {
+            let (response, notifications) = self
+                .handle_single_request(request, connection_id, subscription_permits)
+                .await;
+            responses.extend(response);
+            all_notifications.extend(notifications);
+        }
+        let response = (!responses.is_empty()).then(|| format!("[{}]", responses.join(",")));
+        (response, all_notifications)
+    }
+
+    async fn handle_single_request(
+        &self,
+        request: Value,
+        connection_id: ConnectionId,
+        subscription_permits: &Arc<Semaphore>,
+    ) -> (Option<String>, Option<Notifications>) {
+        let is_notification = request.is_object() && request.get("id").is_none();
+        let raw_request = request.to_string();
+        let parsed_request = match serde_json::from_str::<Request>(&raw_request) {
+            Ok(request) => request,
+            Err(err) if is_notification => {
+                tracing::debug!("Failed handling IPC notification: {err}");
+                return (None, None);
+            }
+            Err(err) => {
+                let response =
+                    error_response(&Value::Null, ErrorCode::InvalidRequest, &err.to_string());
+                return (Some(response), None);
+            }
+        };
+
+        let (response, notifications) = self
+            .call_method(parsed_request, connection_id, subscription_permits)
+            .await;
+        ((!is_notification).then_some(response), Some(notifications))
+    }
+
+    /// Calls an RPC method similarly to `Methods::raw_json_request()`, but with the connection-specific state
+    /// (connection ID and subscription limit), the response size limit and subscription ID format used
+    /// by the WebSocket server.
+    async fn call_method(
+        &self,
+        request: Request<'_>,
+        connection_id: ConnectionId,
+        subscription_permits: &Arc<Semaphore>,
+    ) -> (String, Notifications) {
+        let (sink_sender, mut notifications) = mpsc::channel(SUBSCRIPTION_BUFFER_CAPACITY);
+        let Request {
+            id,
+            method,
+            params,
+            mut extensions,
+            ..
+        } = request;
+        let params = Params::new(params.as_ref().map(|params| params.get()));
+        let max_response_size = self.response_body_size_limit as usize;
+        extensions.insert(connection_id);
+
+        let response = match self.methods.method(&method) {
+            None => MethodResponse::error(id, ErrorObject::from(ErrorCode::MethodNotFound)),
+            Some(MethodCallback::Sync(callback)) => {
+                callback(id, params, max_response_size, extensions)
+            }
+            Some(MethodCallback::Async(callback)) => {
+                let id = id.into_owned();
+                let params = params.into_owned();
+                callback(id, params, connection_id, max_response_size, extensions).await
+            }
+            Some(MethodCallback::Subscription(callback)) => {
+                let Ok(subscription_permit) = subscription_permits.clone().try_acquire_owned()
+                else {
+                    let id = serde_json::to_value(&id).unwrap_or(Value::Null);
+                    let code = ErrorCode::ServerError(TOO_MANY_SUBSCRIPTIONS_CODE);
+                    let response = error_response(&id, code, TOO_MANY_SUBSCRIPTIONS_MSG);
+                    return (response, notifications);
+                };
+                let state = SubscriptionState {
+                    conn_id: connection_id,
+                    id_provider: &EthSubscriptionIdProvider,
+                    subscription_permit,
+                };
+                let sink = MethodSink::new_with_limit(sink_sender, self.response_body_size_limit);
+                let response = callback(id, params, sink, state, extensions).await;
+                // The subscription response is additionally sent to the sink. We discard it since it's returned as `response`.
+                notifications.recv().await;
+                response
+            }
+            Some(MethodCallback::Unsubscription(callback)) => {
+                callback(id, params, connection_id, max_response_size, extensions)
+            }
+        };
+
+        let is_success = response.is_success();
+        let (response, on_response_sent) = response.into_parts();
+        if let Some(on_response_sent) = on_response_sent {
+            on_response_sent.notify(is_success);
+        }
+        (response, notifications)
+    }
+}
+
+/// Forwards subscription notifications to the connection. For non-subscription methods,
+/// the notification channel is closed immediately, so this is a no-op. The subscription permit is released
+/// once the subscription is terminated.
+async fn forward_notifications(
+    mut notifications: Notifications,
+    outgoing_sender: mpsc::Sender<String>,
+) {
+    while let Some(notification) = notifications.recv().await {
+        if outgoing_sender.send(notification).await.is_err() {
+            break; // The connection is closed; dropping `notifications` terminates the subscription
+        }
+    }
+}
+
+/// Splits all complete JSON values from the start of the buffer. Returns the parsed values and the number
+/// of bytes they occupy in the buffer.
+fn split_json_values(buffer: &[u8]) -> Result<(Vec<Value>, usize), serde_json::Error> {
+    let mut stream = serde_json::Deserializer::from_slice(buffer).into_iter::<Value>();
+    let mut values = vec![];
+    loop {
+        match stream.next() {
+            Some(Ok(value)) => values.push(value),
+            // The last value is incomplete; it will be parsed once more data is read from the socket.
+            Some(Err(err)) if err.is_eof() => break,
+            Some(Err(err)) => return Err(err),
+            None => break,
+        }
+    }
+    Ok((values, stream.byte_offset()))
+}
+
+fn error_response(id: &Value, code: ErrorCode, message: &str) -> String {
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": {
+            "code": code.code(),
+            "message": format!("{}: {message}", code.message()),
+        },
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splitting_json_values() {
+        let (values, consumed_len) = split_json_values(b"").unwrap();
+        assert!(values.is_empty());
+        assert_eq!(consumed_len, 0);
+
+        let buffer = br#"{"id":1} [{"id":2}]
+{"id":3"#;
+        let (values, consumed_len) = split_json_values(buffer).unwrap();
+        assert_eq!(
+            values,
+            [
+                serde_json::json!({ "id": 1 }),
+                serde_json::json!([{ "id": 2 }])
+            ]
+        );
+        assert_eq!(&buffer[consumed_len..], b"{\"id\":3");
+
+        split_json_values(b"{\"id\":1} }").unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn binding_socket_with_existing_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let socket_path = temp_dir.path().join("zksync.ipc");
+        let stale_listener = IpcServer::bind(&socket_path, None).unwrap();
+        drop(stale_listener);
+        assert!(socket_path.exists());
+        // The stale socket should be removed.
+        IpcServer::bind(&socket_path, None).unwrap();
+
+        let file_path = temp_dir.path().join("file.ipc");
+        std::fs::write(&file_path, "test").unwrap();
+        let err = IpcServer::bind(&file_path, None).unwrap_err().to_string();
+        assert!(err.contains("non-socket file"), "{err}");
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "test");
+    }
+}
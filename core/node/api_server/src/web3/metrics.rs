@@ -84,6 +84,7 @@ impl<'a> ObservedRpcParams<'a> {
 pub(crate) enum ApiTransportLabel {
     Http,
     Ws,
+    Ipc,
 }
 
 impl From<&ApiTransport> for ApiTransportLabel {
@@ -91,6 +92,7 @@ impl From<&ApiTransport> for ApiTransportLabel {
         match transport {
             ApiTransport::Http(_) => Self::Http,
             ApiTransport::WebSocket(_) => Self::Ws,
+            ApiTransport::Ipc(_) => Self::Ipc,
         }
     }
 }
@@ -170,6 +172,7 @@ enum Web3ErrorKind {
     FilterNotFound,
     LogsLimitExceeded,
     InvalidFilterBlockHash,
+    TraceFilterBlockRangeExceeded,
     TreeApiUnavailable,
     Internal,
 }
@@ -186,6 +189,7 @@ impl Web3ErrorKind {
             Web3Error::FilterNotFound => Self::FilterNotFound,
             Web3Error::LogsLimitExceeded(..) => Self::LogsLimitExceeded,
             Web3Error::InvalidFilterBlockHash => Self::InvalidFilterBlockHash,
+            Web3Error::TraceFilterBlockRangeExceeded(_) => Self::TraceFilterBlockRangeExceeded,
             Web3Error::TreeApiUnavailable => Self::TreeApiUnavailable,
             Web3Error::InternalError(_) | Web3Error::MethodNotImplemented => Self::Internal,
         }
@@ -225,6 +229,7 @@ struct Web3ConfigLabels {
     #[metrics(unit = Unit::Bytes)]
     response_body_size_limit: Option<usize>,
     websocket_requests_per_minute_limit: Option<u32>,
+    http_compute_units_per_minute_limit: Option<u32>,
 }
 
 /// Roughly exponential buckets for the `web3_call_block_diff` metric. The distribution should be skewed towards lower values.
@@ -293,6 +298,10 @@ impl ApiMetrics {
             websocket_requests_per_minute_limit: optional
                 .websocket_requests_per_minute_limit
                 .map(Into::into),
+            http_compute_units_per_minute_limit: optional
+                .http_rate_limits
+                .as_ref()
+                .map(|limits| limits.compute_units_per_minute.get()),
         };
         tracing::info!("{transport:?} Web3 server is configured with options: {config_labels:?}");
         if self.web3_info[&transport].set(config_labels).is_err() {
@@ -421,6 +430,9 @@ pub enum SubscriptionType {
     Blocks,
     Txs,
     Logs,
+    Preconfirmations,
+    FullTxs,
+    L1Batches,
 }
 
 #[derive(Debug, Metrics)]
@@ -1,15 +1,23 @@
-use std::{collections::HashSet, net::SocketAddr, num::NonZeroU32, sync::Arc, time::Duration};
+use std::{
+    collections::HashSet,
+    net::{IpAddr, SocketAddr},
+    num::NonZeroU32,
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
 
 use anyhow::Context as _;
 use chrono::NaiveDateTime;
 use futures::future;
 use serde::Deserialize;
 use tokio::{
+    net::TcpListener,
     sync::{mpsc, oneshot, watch, Mutex},
     task::JoinHandle,
 };
 use tower_http::{cors::CorsLayer, metrics::InFlightRequestsLayer};
-use zksync_config::configs::api::{MaxResponseSize, MaxResponseSizeOverrides};
+use zksync_config::configs::api::{ComputeUnitsMap, MaxResponseSize, MaxResponseSizeOverrides};
 use zksync_dal::{helpers::wait_for_l1_batch, ConnectionPool, Core};
 use zksync_health_check::{HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_metadata_calculator::api_server::TreeApiClient;
@@ -18,38 +26,46 @@ use zksync_types::L2BlockNumber;
 use zksync_web3_decl::{
     jsonrpsee::{
         server::{
-            middleware::rpc::either::Either, BatchRequestConfig, RpcServiceBuilder, ServerBuilder,
+            middleware::rpc::either::Either, serve_with_graceful_shutdown, stop_channel,
+            BatchRequestConfig, RpcServiceBuilder, ServerBuilder,
         },
         MethodCallback, Methods, RpcModule,
     },
     namespaces::{
         DebugNamespaceServer, EnNamespaceServer, EthNamespaceServer, EthPubSubServer,
-        NetNamespaceServer, SnapshotsNamespaceServer, UnstableNamespaceServer, Web3NamespaceServer,
-        ZksNamespaceServer,
+        NetNamespaceServer, SnapshotsNamespaceServer, TraceNamespaceServer,
+        UnstableNamespaceServer, Web3NamespaceServer, ZksNamespaceServer,
     },
     types::Filter,
 };
 
 use self::{
     backend_jsonrpsee::{
-        CorrelationMiddleware, LimitMiddleware, MetadataLayer, MethodTracer, ShutdownMiddleware,
+        CorrelationMiddleware, HttpClientInfoLayer, HttpRateLimitMiddleware, HttpRateLimiter,
+        LimitMiddleware, MetadataLayer, MethodTracer, RemoteAddrService, ShutdownMiddleware,
         TrafficTracker,
     },
+    ipc::IpcServer,
     mempool_cache::MempoolCache,
     metrics::API_METRICS,
     namespaces::{
         DebugNamespace, EnNamespace, EthNamespace, NetNamespace, SnapshotsNamespace,
-        UnstableNamespace, Web3Namespace, ZksNamespace,
+        TraceNamespace, UnstableNamespace, Web3Namespace, ZksNamespace,
     },
     pubsub::{EthSubscribe, EthSubscriptionIdProvider, PubSubEvent},
     state::{Filters, InternalApiConfig, RpcState, SealedL2BlockNumber},
 };
+pub use self::{
+    ipc::IpcConfig,
+    pubsub::{Preconfirmations, PreconfirmationsPublisher},
+};
 use crate::{
     execution_sandbox::{BlockStartInfo, VmConcurrencyBarrier},
     tx_sender::TxSender,
 };
 
 pub mod backend_jsonrpsee;
+mod ipc;
 pub mod mempool_cache;
 pub(super) mod metrics;
 pub mod namespaces;
@@ -69,6 +85,8 @@ const NO_REQUESTS_WAIT_TIMEOUT: Duration = Duration::from_secs(30);
 /// Time interval with no requests sent to the API server to declare that traffic to the server is ceased,
 /// and start gracefully shutting down the server.
 const SHUTDOWN_INTERVAL_WITHOUT_REQUESTS: Duration = Duration::from_millis(500);
+/// Delay before accepting new connections after an accept error (e.g., if the process has run out of file descriptors).
+const ACCEPT_ERROR_BACKOFF: Duration = Duration::from_millis(500);
 
 /// Represents all kinds of `Filter`.
 #[derive(Debug, Clone)]
@@ -81,10 +99,11 @@ pub(crate) enum TypedFilter {
     PendingTransactions(NaiveDateTime),
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 enum ApiTransport {
     WebSocket(SocketAddr),
     Http(SocketAddr),
+    Ipc(PathBuf),
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq, strum::EnumString)]
@@ -100,6 +119,7 @@ pub enum Namespace {
     Pubsub,
     Snapshots,
     Unstable,
+    Trace,
 }
 
 impl Namespace {
@@ -113,6 +133,31 @@ impl Namespace {
     ];
 }
 
+/// Compute unit-based rate limits for the HTTP server.
+#[derive(Debug, Clone)]
+pub struct HttpRateLimits {
+    /// Per-minute limit for clients identified by their IP address.
+    pub compute_units_per_minute: NonZeroU32,
+    /// Per-minute limits for clients authenticated with API keys.
+    pub api_key_limits: ComputeUnitsMap,
+    /// Overrides for compute unit costs of RPC methods.
+    pub method_compute_units: ComputeUnitsMap,
+    /// Reverse proxies trusted to set `x-forwarded-for` / `x-real-ip` headers. Clients connecting directly
+    /// are identified by the remote address of the connection.
+    pub trusted_proxies: HashSet<IpAddr>,
+}
+
+impl HttpRateLimits {
+    pub fn new(compute_units_per_minute: NonZeroU32) -> Self {
+        Self {
+            compute_units_per_minute,
+            api_key_limits: ComputeUnitsMap::default(),
+            method_compute_units: ComputeUnitsMap::default(),
+            trusted_proxies: HashSet::new(),
+        }
+    }
+}
+
 /// Handles to the initialized API server.
 #[derive(Debug)]
 pub struct ApiServerHandles {
@@ -132,9 +177,12 @@ struct OptionalApiParams {
     batch_request_size_limit: Option<usize>,
     response_body_size_limit: Option<MaxResponseSize>,
     websocket_requests_per_minute_limit: Option<NonZeroU32>,
+    http_rate_limits: Option<HttpRateLimits>,
+    ipc_config: Option<IpcConfig>,
     tree_api: Option<Arc<dyn TreeApiClient>>,
     mempool_cache: Option<MempoolCache>,
     extended_tracing: bool,
+    preconfirmations: Option<Preconfirmations>,
     pub_sub_events_sender: Option<mpsc::UnboundedSender<PubSubEvent>>,
 }
 
@@ -201,6 +249,13 @@ impl ApiBuilder {
         self
     }
 
+    /// Serves the API over IPC using a Unix domain socket at the specified path. IPC supports the same namespaces
+    /// and subscriptions as the WebSocket transport.
+    pub fn ipc(mut self, path: impl Into<PathBuf>) -> Self {
+        self.transport = Some(ApiTransport::Ipc(path.into()));
+        self
+    }
+
     /// Configures a dedicated DB pool to be used for updating different information,
     /// such as last mined block number or account nonces. This pool is used to execute
     /// in a background task. If not called, the main pool will be used. If the API server is under high load,
@@ -249,6 +304,18 @@ impl ApiBuilder {
         self
     }
 
+    /// Enables compute unit-based rate limiting for HTTP clients. Only has effect for the HTTP server.
+    pub fn with_http_rate_limits(mut self, limits: HttpRateLimits) -> Self {
+        self.optional.http_rate_limits = Some(limits);
+        self
+    }
+
+    /// Sets limits and socket permissions for the IPC server. Only has effect for the IPC transport.
+    pub fn with_ipc_config(mut self, config: IpcConfig) -> Self {
+        self.optional.ipc_config = Some(config);
+        self
+    }
+
     pub fn with_sync_state(mut self, sync_state: SyncState) -> Self {
         self.optional.sync_state = Some(sync_state);
         self
@@ -285,6 +352,12 @@ impl ApiBuilder {
         self
     }
 
+    /// Enables the `preconfirmations` subscription. Only has effect for the WebSocket server with the `pubsub` namespace.
+    pub fn with_preconfirmations(mut self, preconfirmations: Preconfirmations) -> Self {
+        self.optional.preconfirmations = Some(preconfirmations);
+        self
+    }
+
     // Intended for tests only.
     #[doc(hidden)]
     fn with_pub_sub_events(mut self, sender: mpsc::UnboundedSender<PubSubEvent>) -> Self {
@@ -306,6 +379,7 @@ impl ApiBuilder {
         let health_check_name = match &transport {
             ApiTransport::Http(_) => "http_api",
             ApiTransport::WebSocket(_) => "ws_api",
+            ApiTransport::Ipc(_) => "ipc_api",
         };
         let (_, health_updater) = ReactiveHealthCheck::new(health_check_name);
 
@@ -412,6 +486,10 @@ impl ApiServer {
             rpc.merge(SnapshotsNamespace::new(rpc_state.clone()).into_rpc())
                 .context("cannot merge snapshots namespace")?;
         }
+        if namespaces.contains(&Namespace::Trace) {
+            rpc.merge(TraceNamespace::new(rpc_state.clone()).into_rpc())
+                .context("cannot merge trace namespace")?;
+        }
         if namespaces.contains(&Namespace::Unstable) {
             rpc.merge(UnstableNamespace::new(rpc_state).into_rpc())
                 .context("cannot merge unstable namespace")?;
@@ -479,7 +557,7 @@ impl ApiServer {
         // by reporting block difference metrics, so the actual update lag would be much smaller than this value.
         const SEALED_L2_BLOCK_UPDATE_INTERVAL: Duration = Duration::from_millis(25);
 
-        let transport = self.transport;
+        let transport = self.transport.clone();
 
         let (last_sealed_l2_block, sealed_l2_block_update_task) = SealedL2BlockNumber::new(
             self.updaters_pool.clone(),
@@ -488,16 +566,20 @@ impl ApiServer {
         );
 
         let mut tasks = vec![tokio::spawn(sealed_l2_block_update_task)];
-        let pub_sub = if matches!(transport, ApiTransport::WebSocket(_))
+        let pub_sub = if matches!(transport, ApiTransport::WebSocket(_) | ApiTransport::Ipc(_))
             && self.namespaces.contains(&Namespace::Pubsub)
         {
             let mut pub_sub = EthSubscribe::new();
             if let Some(sender) = &self.optional.pub_sub_events_sender {
                 pub_sub.set_events_sender(sender.clone());
             }
+            if let Some(preconfirmations) = &self.optional.preconfirmations {
+                pub_sub.set_preconfirmations(preconfirmations.clone());
+            }
 
             tasks.extend(pub_sub.spawn_notifiers(
                 self.pool.clone(),
+                self.config.l2_chain_id,
                 self.polling_interval,
                 stop_receiver.clone(),
             ));
@@ -587,10 +669,11 @@ impl ApiServer {
         last_sealed_l2_block: SealedL2BlockNumber,
         local_addr_sender: oneshot::Sender<SocketAddr>,
     ) -> anyhow::Result<()> {
-        let transport = self.transport;
-        let (transport_str, is_http, addr) = match transport {
-            ApiTransport::Http(addr) => ("HTTP", true, addr),
-            ApiTransport::WebSocket(addr) => ("WS", false, addr),
+        let transport = self.transport.clone();
+        let (transport_str, is_http) = match &transport {
+            ApiTransport::Http(_) => ("HTTP", true),
+            ApiTransport::WebSocket(_) => ("WS", false),
+            ApiTransport::Ipc(_) => ("IPC", false),
         };
         let transport_label = (&transport).into();
         API_METRICS.observe_config(
@@ -629,8 +712,11 @@ impl ApiServer {
             } else {
                 (u32::MAX, MaxResponseSizeOverrides::empty())
             };
+        let batch_request_size_limit = self.optional.batch_request_size_limit;
         let websocket_requests_per_minute_limit = self.optional.websocket_requests_per_minute_limit;
+        let http_rate_limits = self.optional.http_rate_limits.clone();
         let subscriptions_limit = self.optional.subscriptions_limit;
+        let ipc_config = self.optional.ipc_config.clone().unwrap_or_default();
         let vm_barrier = self.optional.vm_barrier.clone();
         let health_updater = self.health_updater.clone();
         let method_tracer = self.method_tracer.clone();
@@ -648,6 +734,26 @@ impl ApiServer {
         );
         let rpc = Self::override_method_response_sizes(rpc, &max_response_size_overrides)?;
 
+        let addr = match transport {
+            ApiTransport::Http(addr) | ApiTransport::WebSocket(addr) => addr,
+            ApiTransport::Ipc(path) => {
+                let server = IpcServer {
+                    methods: rpc,
+                    config: ipc_config,
+                    batch_request_size_limit,
+                    response_body_size_limit,
+                };
+                return Self::run_ipc_server(
+                    server,
+                    path,
+                    stop_receiver,
+                    health_updater,
+                    vm_barrier,
+                )
+                .await;
+            }
+        };
+
         // Setup CORS.
         let cors = is_http.then(|| {
             CorsLayer::new()
@@ -665,10 +771,22 @@ impl ApiServer {
                 future::ready(())
             }),
         );
+        // Setup compute unit-based rate limiting for HTTP clients.
+        let http_rate_limits = http_rate_limits.filter(|_| is_http);
+        let http_client_info_layer = http_rate_limits
+            .as_ref()
+            .map(|limits| HttpClientInfoLayer::new(limits.trusted_proxies.clone()));
+        let http_rate_limiter =
+            http_rate_limits.map(|limits| Arc::new(HttpRateLimiter::new(&limits)));
+        let has_http_rate_limits = http_rate_limiter.is_some();
+        if let Some(limiter) = &http_rate_limiter {
+            HttpRateLimiter::spawn_pruning_task(Arc::downgrade(limiter), stop_receiver.clone());
+        }
         // Assemble server middleware.
         let middleware = tower::ServiceBuilder::new()
             .layer(in_flight_requests)
-            .option_layer(cors);
+            .option_layer(cors)
+            .option_layer(http_client_info_layer);
 
         // Settings shared by HTTP and WS servers.
         let max_connections = !is_http
@@ -701,6 +819,12 @@ impl ApiServer {
                 tower::layer::layer_fn(move |svc| {
                     LimitMiddleware::new(svc, websocket_requests_per_minute_limit)
                 })
+            }))
+            // Similarly, HTTP rate limiting errors are captured by `metadata_layer`.
+            .option_layer(http_rate_limiter.map(|limiter| {
+                tower::layer::layer_fn(move |svc| {
+                    HttpRateLimitMiddleware::new(svc, limiter.clone())
+                })
             }));
 
         let server_builder = ServerBuilder::default()
@@ -710,7 +834,49 @@ impl ApiServer {
             .set_batch_request_config(batch_request_config)
             .set_rpc_middleware(rpc_middleware);
 
-        let (local_addr, server_handle) = if is_http {
+        let (local_addr, server_handle) = if is_http && has_http_rate_limits {
+            // HTTP-specific settings. If rate limiting is enabled, we run the accept loop ourselves so that
+            // remote addresses of connections are available to the middleware.
+            let listener = TcpListener::bind(addr)
+                .await
+                .context("Failed binding HTTP JSON-RPC server")?;
+            let local_addr = listener.local_addr();
+            let service_builder = server_builder.http_only().to_service_builder();
+            let methods = Methods::from(rpc);
+            let (stop_handle, server_handle) = stop_channel();
+            tokio::spawn(async move {
+                loop {
+                    let (stream, remote_addr) = tokio::select! {
+                        accept_result = listener.accept() => match accept_result {
+                            Ok(connection) => connection,
+                            Err(err) => {
+                                tracing::warn!(
+                                    "Failed accepting HTTP connection: {err}; retrying in {ACCEPT_ERROR_BACKOFF:?}"
+                                );
+                                tokio::time::sleep(ACCEPT_ERROR_BACKOFF).await;
+                                continue;
+                            }
+                        },
+                        () = stop_handle.clone().shutdown() => break,
+                    };
+                    let service = service_builder
+                        .clone()
+                        .build(methods.clone(), stop_handle.clone());
+                    let service = RemoteAddrService::new(service, remote_addr);
+                    let stopped = stop_handle.clone().shutdown();
+                    tokio::spawn(async move {
+                        if let Err(err) =
+                            serve_with_graceful_shutdown(stream, service, stopped).await
+                        {
+                            tracing::debug!(
+                                "Failed serving HTTP connection from {remote_addr}: {err}"
+                            );
+                        }
+                    });
+                }
+            });
+            (local_addr, server_handle)
+        } else if is_http {
             // HTTP-specific settings
             let server = server_builder
                 .http_only()
@@ -790,4 +956,33 @@ impl ApiServer {
         }
         Ok(())
     }
+
+    async fn run_ipc_server(
+        server: IpcServer,
+        path: PathBuf,
+        stop_receiver: watch::Receiver<bool>,
+        health_updater: Arc<HealthUpdater>,
+        vm_barrier: Option<VmConcurrencyBarrier>,
+    ) -> anyhow::Result<()> {
+        let listener = IpcServer::bind(&path, server.config.socket_permissions)?;
+        tracing::info!("Initialized IPC API on `{}`", path.display());
+        health_updater.update(HealthStatus::Ready.into());
+
+        // Unlike with HTTP / WS servers, IPC traffic originates from co-located services rather than from a load balancer,
+        // so we don't wait for the traffic to stop before shutting down.
+        let server_result = server.run(listener, stop_receiver).await;
+        health_updater.update(HealthStatus::ShuttingDown.into());
+        if let Some(vm_barrier) = &vm_barrier {
+            vm_barrier.close();
+        }
+        if let Err(err) = std::fs::remove_file(&path) {
+            tracing::warn!("Failed removing IPC socket at `{}`: {err}", path.display());
+        }
+        drop(health_updater);
+        tracing::info!("IPC JSON-RPC server stopped");
+        if let Some(vm_barrier) = vm_barrier {
+            Self::wait_for_vm(vm_barrier, "IPC").await;
+        }
+        server_result
+    }
 }
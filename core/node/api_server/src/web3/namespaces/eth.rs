@@ -1,5 +1,5 @@
 use anyhow::Context as _;
-use zksync_dal::{CoreDal, DalError};
+use zksync_dal::{Connection, Core, CoreDal, DalError};
 use zksync_system_constants::DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE;
 use zksync_types::{
     api::{
@@ -403,7 +403,13 @@ impl EthNamespace {
 
         let storage_key = StorageKey::new(AccountTreeId::new(address), u256_to_h256(idx));
         let mut connection = self.state.acquire_connection().await?;
-        let block_number = self.state.resolve_block(&mut connection, block_id).await?;
+        let block_number = match self.state.resolve_block(&mut connection, block_id).await {
+            Err(Web3Error::PrunedBlock(first_l2_block)) => self
+                .resolve_retained_storage_block(&mut connection, block_id, &address)
+                .await?
+                .ok_or(Web3Error::PrunedBlock(first_l2_block))?,
+            result => result?,
+        };
         self.set_block_diff(block_number);
         let value = connection
             .storage_web3_dal()
@@ -413,6 +419,30 @@ impl EthNamespace {
         Ok(value)
     }
 
+    /// Resolves a pruned block for a contract with retained storage history. Returns `None` if storage history
+    /// of the contract is not retained, or if the block was pruned before the retention policy for the contract
+    /// took effect.
+    async fn resolve_retained_storage_block(
+        &self,
+        connection: &mut Connection<'_, Core>,
+        block_id: BlockId,
+        address: &Address,
+    ) -> Result<Option<L2BlockNumber>, Web3Error> {
+        let BlockId::Number(BlockNumber::Number(number)) = block_id else {
+            return Ok(None);
+        };
+        let number = RpcState::u64_to_block_number(number);
+        let applied_policy = connection
+            .pruning_dal()
+            .get_applied_retention_policy()
+            .await
+            .map_err(DalError::generalize)?;
+        let first_retained_block = applied_policy.first_block_retaining_storage_of(address);
+        Ok(first_retained_block
+            .is_some_and(|block| number >= block)
+            .then_some(number))
+    }
+
     /// Account nonce.
     pub async fn get_transaction_count_impl(
         &self,
@@ -802,6 +832,9 @@ impl EthNamespace {
                 };
 
                 let mut storage = self.state.acquire_connection().await?;
+                self.state
+                    .ensure_logs_not_pruned(&get_logs_filter, &mut storage)
+                    .await?;
 
                 // Check if there is more than one block in range and there are more than `req_entities_limit` logs that satisfies filter.
                 // In this case we should return error and suggest requesting logs with smaller block range.
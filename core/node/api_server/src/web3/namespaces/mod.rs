@@ -6,12 +6,13 @@ mod en;
 pub(crate) mod eth;
 mod net;
 mod snapshots;
+mod trace;
 mod unstable;
 mod web3;
 mod zks;
 
 pub(super) use self::{
     debug::DebugNamespace, en::EnNamespace, eth::EthNamespace, net::NetNamespace,
-    snapshots::SnapshotsNamespace, unstable::UnstableNamespace, web3::Web3Namespace,
-    zks::ZksNamespace,
+    snapshots::SnapshotsNamespace, trace::TraceNamespace, unstable::UnstableNamespace,
+    web3::Web3Namespace, zks::ZksNamespace,
 };
@@ -22,18 +22,24 @@ impl SnapshotsNamespace {
         &self.state.current_method
     }
 
-    pub async fn get_all_snapshots_impl(&self) -> Result<AllSnapshots, Web3Error> {
+    pub async fn get_all_snapshots_impl(
+        &self,
+        include_deltas: bool,
+    ) -> Result<AllSnapshots, Web3Error> {
         let mut storage_processor = self.state.acquire_connection().await?;
         let mut snapshots_dal = storage_processor.snapshots_dal();
-        Ok(snapshots_dal
-            .get_all_complete_snapshots()
-            .await
-            .map_err(DalError::generalize)?)
+        let snapshots = if include_deltas {
+            snapshots_dal.get_all_complete_snapshots().await
+        } else {
+            snapshots_dal.get_all_complete_full_snapshots().await
+        };
+        Ok(snapshots.map_err(DalError::generalize)?)
     }
 
     pub async fn get_snapshot_by_l1_batch_number_impl(
         &self,
         l1_batch_number: L1BatchNumber,
+        include_deltas: bool,
     ) -> Result<Option<SnapshotHeader>, Web3Error> {
         let mut storage_processor = self.state.acquire_connection().await?;
         let snapshot_metadata = storage_processor
@@ -45,6 +51,10 @@ impl SnapshotsNamespace {
         let Some(snapshot_metadata) = snapshot_metadata else {
             return Ok(None);
         };
+        if !include_deltas && snapshot_metadata.base_l1_batch_number.is_some() {
+            // Delta snapshots are only returned by the dedicated methods, so that legacy clients don't recover from them.
+            return Ok(None);
+        }
 
         let snapshot_files = snapshot_metadata.storage_logs_filepaths;
         let is_complete = snapshot_files.iter().all(Option::is_some);
@@ -74,6 +84,7 @@ impl SnapshotsNamespace {
             version: snapshot_metadata.version.into(),
             l1_batch_number: snapshot_metadata.l1_batch_number,
             l2_block_number,
+            base_l1_batch_number: snapshot_metadata.base_l1_batch_number,
             storage_logs_chunks: chunks,
             factory_deps_filepath: snapshot_metadata.factory_deps_filepath,
         }))
@@ -0,0 +1,139 @@
+use zksync_dal::{Connection, Core, CoreDal, DalError};
+use zksync_multivm::interface::Call;
+use zksync_types::{
+    api::{
+        trace::{Trace, TraceFilter, TraceLocation},
+        BlockId, BlockNumber,
+    },
+    L2BlockNumber, H256,
+};
+use zksync_web3_decl::error::Web3Error;
+
+use super::DebugNamespace;
+use crate::web3::{backend_jsonrpsee::MethodTracer, state::RpcState};
+
+/// Parity-style `trace` namespace built on top of call traces persisted by the state keeper.
+#[derive(Debug)]
+pub(crate) struct TraceNamespace {
+    state: RpcState,
+}
+
+impl TraceNamespace {
+    pub fn new(state: RpcState) -> Self {
+        Self { state }
+    }
+
+    pub(crate) fn current_method(&self) -> &MethodTracer {
+        &self.state.current_method
+    }
+
+    fn flatten_traces(traces: Vec<(Call, TraceLocation)>) -> impl Iterator<Item = Trace> {
+        traces.into_iter().flat_map(|(call, location)| {
+            Trace::flatten(&DebugNamespace::map_call(call, false), location)
+        })
+    }
+
+    pub async fn trace_block_impl(&self, block: BlockNumber) -> Result<Vec<Trace>, Web3Error> {
+        let block_id = BlockId::Number(block);
+        self.current_method().set_block_id(block_id);
+        if matches!(block, BlockNumber::Pending) {
+            // See `EthNamespace::get_block_impl()` for an explanation why this check is needed.
+            return Ok(vec![]);
+        }
+
+        let mut connection = self.state.acquire_connection().await?;
+        let block_number = self.state.resolve_block(&mut connection, block_id).await?;
+        self.current_method()
+            .set_block_diff(self.state.last_sealed_l2_block.diff(block_number));
+
+        let traces = connection
+            .transactions_dal()
+            .get_call_traces_for_l2_blocks(block_number..=block_number)
+            .await
+            .map_err(DalError::generalize)?;
+        Ok(Self::flatten_traces(traces).collect())
+    }
+
+    pub async fn trace_transaction_impl(
+        &self,
+        tx_hash: H256,
+    ) -> Result<Option<Vec<Trace>>, Web3Error> {
+        let mut connection = self.state.acquire_connection().await?;
+        let trace = connection
+            .transactions_dal()
+            .get_call_trace_with_location(tx_hash)
+            .await
+            .map_err(DalError::generalize)?;
+        Ok(trace.map(|trace| Self::flatten_traces(vec![trace]).collect()))
+    }
+
+    async fn resolve_filter_block(
+        &self,
+        connection: &mut Connection<'_, Core>,
+        block: Option<BlockNumber>,
+    ) -> Result<L2BlockNumber, Web3Error> {
+        // Pending blocks don't have traces, so they are treated as the latest sealed block.
+        let block = match block {
+            None | Some(BlockNumber::Pending) => BlockNumber::Latest,
+            Some(block) => block,
+        };
+        self.state
+            .resolve_block(connection, BlockId::Number(block))
+            .await
+    }
+
+    pub async fn trace_filter_impl(&self, filter: TraceFilter) -> Result<Vec<Trace>, Web3Error> {
+        let mut connection = self.state.acquire_connection().await?;
+        let from_block = self
+            .resolve_filter_block(&mut connection, filter.from_block)
+            .await?;
+        let to_block = self
+            .resolve_filter_block(&mut connection, filter.to_block)
+            .await?;
+        if from_block > to_block {
+            return Ok(vec![]);
+        }
+        let block_range_limit = self.state.api_config.trace_filter_block_range_limit;
+        if u64::from(to_block.0 - from_block.0) >= block_range_limit {
+            return Err(Web3Error::TraceFilterBlockRangeExceeded(block_range_limit));
+        }
+        self.current_method()
+            .set_block_diff(self.state.last_sealed_l2_block.diff(from_block));
+
+        let count_limit = self.state.api_config.req_entities_limit;
+        let count = filter
+            .count
+            .map_or(count_limit, |count| count.min(count_limit));
+        let matches_filter = |trace: &Trace| {
+            let from_matches = filter
+                .from_address
+                .as_ref()
+                .map_or(true, |addresses| addresses.contains(&trace.from()));
+            let to_matches = filter
+                .to_address
+                .as_ref()
+                .map_or(true, |addresses| addresses.contains(&trace.to()));
+            from_matches && to_matches
+        };
+
+        // Traces are loaded block by block, so that we can stop once enough traces are collected.
+        let mut traces_to_skip = filter.after.unwrap_or(0);
+        let mut traces = vec![];
+        for block_number in from_block.0..=to_block.0 {
+            if traces.len() >= count {
+                break;
+            }
+            let block_number = L2BlockNumber(block_number);
+            let block_traces = connection
+                .transactions_dal()
+                .get_call_traces_for_l2_blocks(block_number..=block_number)
+                .await
+                .map_err(DalError::generalize)?;
+            let mut matching_traces = Self::flatten_traces(block_traces).filter(matches_filter);
+            let skipped_count = matching_traces.by_ref().take(traces_to_skip).count();
+            traces_to_skip -= skipped_count;
+            traces.extend(matching_traces.take(count - traces.len()));
+        }
+        Ok(traces)
+    }
+}
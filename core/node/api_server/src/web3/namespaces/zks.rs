@@ -9,7 +9,8 @@ use zksync_system_constants::DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE;
 use zksync_types::{
     api::{
         state_override::StateOverride, BlockDetails, BridgeAddresses, GetLogsFilter,
-        L1BatchDetails, L2ToL1LogProof, Proof, ProtocolVersion, StorageProof, TransactionDetails,
+        L1BatchDetails, L2ToL1LogProof, Proof, ProofOptions, ProtocolVersion, StorageMultiProof,
+        StorageProof, TransactionDetails,
     },
     fee::Fee,
     fee_model::{FeeParams, PubdataIndependentBatchFeeModelInput},
@@ -500,13 +501,14 @@ impl ZksNamespace {
         address: Address,
         keys: Vec<H256>,
         l1_batch_number: L1BatchNumber,
+        options: ProofOptions,
     ) -> Result<Option<Proof>, Web3Error> {
         let mut storage = self.state.acquire_connection().await?;
         self.state
             .start_info
             .ensure_not_pruned(l1_batch_number, &mut storage)
             .await?;
-        let hashed_keys = keys
+        let hashed_keys: Vec<_> = keys
             .iter()
             .map(|key| StorageKey::new(AccountTreeId::new(address), *key).hashed_key_u256())
             .collect();
@@ -515,28 +517,39 @@ impl ZksNamespace {
             .tree_api
             .as_deref()
             .ok_or(Web3Error::MethodNotImplemented)?;
+
+        if options.compact {
+            let keys_by_hashed_key: HashMap<_, _> = hashed_keys.iter().copied().zip(keys).collect();
+            let multiproof_result = tree_api.get_multiproof(l1_batch_number, hashed_keys).await;
+            let Some(multiproof) =
+                Self::handle_tree_api_result(multiproof_result, l1_batch_number)?
+            else {
+                return Ok(None);
+            };
+            let storage_proof = multiproof
+                .entries
+                .into_iter()
+                .map(|entry| StorageProof {
+                    key: keys_by_hashed_key[&entry.key],
+                    proof: vec![],
+                    value: entry.value,
+                    index: entry.index,
+                })
+                .collect();
+            return Ok(Some(Proof {
+                address,
+                storage_proof,
+                multiproof: Some(StorageMultiProof {
+                    path_lengths: multiproof.path_lengths,
+                    hashes: multiproof.hashes,
+                }),
+            }));
+        }
+
         let proofs_result = tree_api.get_proofs(l1_batch_number, hashed_keys).await;
-        let proofs = match proofs_result {
-            Ok(proofs) => proofs,
-            Err(TreeApiError::NotReady(_)) => return Err(Web3Error::TreeApiUnavailable),
-            Err(TreeApiError::NoVersion(err)) => {
-                return if err.missing_version > err.version_count {
-                    Ok(None)
-                } else {
-                    Err(Web3Error::InternalError(anyhow::anyhow!(
-                        "L1 batch #{l1_batch_number} is pruned in Merkle tree, but not in Postgres"
-                    )))
-                };
-            }
-            Err(TreeApiError::Internal(err)) => return Err(Web3Error::InternalError(err)),
-            Err(_) => {
-                // This branch is not expected to be executed, but has to be provided since the error is non-exhaustive.
-                return Err(Web3Error::InternalError(anyhow::anyhow!(
-                    "Unspecified tree API error"
-                )));
-            }
+        let Some(proofs) = Self::handle_tree_api_result(proofs_result, l1_batch_number)? else {
+            return Ok(None);
         };
-
         let storage_proof = proofs
             .into_iter()
             .zip(keys)
@@ -551,9 +564,38 @@ impl ZksNamespace {
         Ok(Some(Proof {
             address,
             storage_proof,
+            multiproof: None,
         }))
     }
 
+    /// Converts a tree API response to a Web3 result. Returns `Ok(None)` if the requested L1 batch
+    /// is not processed by the tree yet.
+    fn handle_tree_api_result<T>(
+        result: Result<T, TreeApiError>,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Option<T>, Web3Error> {
+        match result {
+            Ok(value) => Ok(Some(value)),
+            Err(TreeApiError::NotReady(_)) => Err(Web3Error::TreeApiUnavailable),
+            Err(TreeApiError::NoVersion(err)) => {
+                if err.missing_version > err.version_count {
+                    Ok(None)
+                } else {
+                    Err(Web3Error::InternalError(anyhow::anyhow!(
+                        "L1 batch #{l1_batch_number} is pruned in Merkle tree, but not in Postgres"
+                    )))
+                }
+            }
+            Err(TreeApiError::Internal(err)) => Err(Web3Error::InternalError(err)),
+            Err(_) => {
+                // This branch is not expected to be executed, but has to be provided since the error is non-exhaustive.
+                Err(Web3Error::InternalError(anyhow::anyhow!(
+                    "Unspecified tree API error"
+                )))
+            }
+        }
+    }
+
     pub fn get_base_token_l1_address_impl(&self) -> Result<Address, Web3Error> {
         self.state
             .api_config
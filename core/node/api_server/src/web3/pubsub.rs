@@ -1,5 +1,8 @@
 //! (Largely) backend-agnostic logic for dealing with Web3 subscriptions.
 
+use std::collections::HashMap;
+
+use async_trait::async_trait;
 use chrono::NaiveDateTime;
 use futures::FutureExt;
 use tokio::{
@@ -8,8 +11,10 @@ use tokio::{
     time::{interval, Duration},
 };
 use tracing::Instrument as _;
-use zksync_dal::{ConnectionPool, Core, CoreDal};
-use zksync_types::{L2BlockNumber, H128, H256};
+use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
+use zksync_multivm::interface::TxExecutionStatus;
+use zksync_state_keeper::{StateKeeperOutputHandler, UpdatesManager};
+use zksync_types::{api, L1BatchNumber, L2BlockNumber, L2ChainId, H128, H256, U256, U64};
 use zksync_web3_decl::{
     jsonrpsee::{
         core::{server::SubscriptionMessage, SubscriptionResult},
@@ -18,7 +23,10 @@ use zksync_web3_decl::{
         PendingSubscriptionSink, SendTimeoutError, SubscriptionSink,
     },
     namespaces::EthPubSubServer,
-    types::{BlockHeader, Log, PubSubFilter, PubSubResult},
+    types::{
+        BlockHeader, Bytes, Index, L1BatchEvent, L1BatchStage, Log, PreconfirmationStatus,
+        PreconfirmedReceipt, PubSubFilter, PubSubParams, PubSubResult, TxPreconfirmation,
+    },
 };
 
 use super::{
@@ -28,6 +36,13 @@ use super::{
 
 const BROADCAST_CHANNEL_CAPACITY: usize = 1024;
 const SUBSCRIPTION_SINK_SEND_TIMEOUT: Duration = Duration::from_secs(1);
+/// L1 batch stages in the order they are reached; events for each polling iteration are emitted in this order.
+const L1_BATCH_STAGES: [L1BatchStage; 4] = [
+    L1BatchStage::Sealed,
+    L1BatchStage::Committed,
+    L1BatchStage::Proven,
+    L1BatchStage::Executed,
+];
 
 #[derive(Debug, Clone, Copy)]
 pub struct EthSubscriptionIdProvider;
@@ -185,6 +200,73 @@ impl PubSubNotifier {
             .map_err(Into::into)
     }
 
+    async fn notify_full_txs(
+        self,
+        l2_chain_id: L2ChainId,
+        stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        let mut last_time = chrono::Utc::now().naive_utc();
+        let mut timer = interval(self.polling_interval);
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, pubsub_full_tx_notifier is shutting down");
+                break;
+            }
+            timer.tick().await;
+
+            if self.sender.receiver_count() == 0 {
+                // Loading full transactions is relatively expensive, so we skip it if there are no subscribers.
+                last_time = chrono::Utc::now().naive_utc();
+                self.emit_event(PubSubEvent::NotifyIterationFinished(
+                    SubscriptionType::FullTxs,
+                ));
+                continue;
+            }
+
+            let db_latency = PUB_SUB_METRICS.db_poll_latency[&SubscriptionType::FullTxs].start();
+            let new_txs = self.new_full_txs(last_time, l2_chain_id).await?;
+            db_latency.observe();
+
+            if let Some((new_last_time, new_txs)) = new_txs {
+                last_time = new_last_time;
+                let new_txs = new_txs.into_iter().map(PubSubResult::Transaction).collect();
+                self.send_pub_sub_results(new_txs, SubscriptionType::FullTxs);
+            }
+            self.emit_event(PubSubEvent::NotifyIterationFinished(
+                SubscriptionType::FullTxs,
+            ));
+        }
+        Ok(())
+    }
+
+    async fn new_full_txs(
+        &self,
+        last_time: NaiveDateTime,
+        l2_chain_id: L2ChainId,
+    ) -> anyhow::Result<Option<(NaiveDateTime, Vec<api::Transaction>)>> {
+        let mut storage = self.connection_pool.connection_tagged("api").await?;
+        let new_txs = storage
+            .transactions_web3_dal()
+            .get_pending_txs_hashes_after(last_time, None)
+            .await?;
+        let Some(&(new_last_time, _)) = new_txs.last() else {
+            return Ok(None);
+        };
+
+        let hashes: Vec<_> = new_txs.into_iter().map(|(_, hash)| hash).collect();
+        let mut txs: HashMap<_, _> = storage
+            .transactions_web3_dal()
+            .get_transactions(&hashes, l2_chain_id)
+            .await?
+            .into_iter()
+            .map(|tx| (tx.hash, tx))
+            .collect();
+        // Transactions are loaded in an arbitrary order; restore the order in which they were received.
+        // Transactions removed from the mempool in the meantime are skipped.
+        let txs = hashes.iter().filter_map(|hash| txs.remove(hash)).collect();
+        Ok(Some((new_last_time, txs)))
+    }
+
     async fn notify_logs(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
         let Some(mut last_block_number) = self
             .get_starting_l2_block_number(&mut stop_receiver)
@@ -229,13 +311,270 @@ impl PubSubNotifier {
             .await
             .map_err(Into::into)
     }
+
+    async fn notify_l1_batches(self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        // `None` if the numbers need to be reloaded (e.g., after a period without subscribers).
+        let mut last_numbers = None;
+        let mut timer = interval(self.polling_interval);
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, pubsub_l1_batch_notifier is shutting down");
+                break;
+            }
+            timer.tick().await;
+
+            if self.sender.receiver_count() == 0 {
+                // Don't poll Postgres if there are no subscribers. Since events are only delivered to the current
+                // subscribers, numbers are reloaded once subscribers appear without emitting events for the gap.
+                last_numbers = None;
+                self.emit_event(PubSubEvent::NotifyIterationFinished(
+                    SubscriptionType::L1Batches,
+                ));
+                continue;
+            }
+
+            let db_latency = PUB_SUB_METRICS.db_poll_latency[&SubscriptionType::L1Batches].start();
+            let Some(prev_numbers) = last_numbers else {
+                let mut storage = self.connection_pool.connection_tagged("api").await?;
+                last_numbers = Some(Self::l1_batch_stage_numbers(&mut storage).await?);
+                db_latency.observe();
+                self.emit_event(PubSubEvent::NotifyIterationFinished(
+                    SubscriptionType::L1Batches,
+                ));
+                continue;
+            };
+            let (new_numbers, events) = self.new_l1_batch_events(&prev_numbers).await?;
+            db_latency.observe();
+
+            // Numbers may decrease if L1 batches are reverted; in this case, events will be re-emitted
+            // once the batches reach the corresponding stages again.
+            last_numbers = Some(new_numbers);
+            if !events.is_empty() {
+                self.send_pub_sub_results(events, SubscriptionType::L1Batches);
+            }
+            self.emit_event(PubSubEvent::NotifyIterationFinished(
+                SubscriptionType::L1Batches,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the numbers of the last L1 batches that have reached each of [`L1_BATCH_STAGES`].
+    async fn l1_batch_stage_numbers(
+        storage: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<[Option<L1BatchNumber>; 4]> {
+        let mut blocks_dal = storage.blocks_dal();
+        Ok([
+            blocks_dal.get_sealed_l1_batch_number().await?,
+            blocks_dal
+                .get_number_of_last_l1_batch_committed_on_eth()
+                .await?,
+            blocks_dal
+                .get_number_of_last_l1_batch_proven_on_eth()
+                .await?,
+            blocks_dal
+                .get_number_of_last_l1_batch_executed_on_eth()
+                .await?,
+        ])
+    }
+
+    async fn new_l1_batch_events(
+        &self,
+        last_numbers: &[Option<L1BatchNumber>; 4],
+    ) -> anyhow::Result<([Option<L1BatchNumber>; 4], Vec<PubSubResult>)> {
+        let mut storage = self.connection_pool.connection_tagged("api").await?;
+        let numbers = Self::l1_batch_stage_numbers(&mut storage).await?;
+        let ranges = L1_BATCH_STAGES
+            .into_iter()
+            .zip(last_numbers.iter().zip(&numbers))
+            .filter_map(|(stage, (&prev_number, &number))| {
+                let number = number?;
+                let start = prev_number.map_or(L1BatchNumber(0), |prev| prev + 1);
+                (start <= number).then_some((stage, start..=number))
+            });
+
+        // Stage ranges are queried separately rather than as a union; otherwise, a single lagging stage
+        // (e.g., execution) would make each poll load all batches since the oldest unreported one.
+        let mut events = vec![];
+        for (stage, range) in ranges {
+            let batches = storage
+                .blocks_web3_dal()
+                .get_l1_batches_eth_txs(range)
+                .await?;
+            let stage_events = batches.into_iter().filter_map(|batch| {
+                let l1_tx_hash = batch.tx_hash(stage);
+                // Skip batches never processed on L1 (e.g., the genesis batch).
+                if stage != L1BatchStage::Sealed && l1_tx_hash.is_none() {
+                    return None;
+                }
+                Some(PubSubResult::L1Batch(L1BatchEvent {
+                    l1_batch_number: batch.number.0.into(),
+                    stage,
+                    timestamp: batch.timestamp.into(),
+                    l1_tx_hash,
+                }))
+            });
+            events.extend(stage_events);
+        }
+        Ok((numbers, events))
+    }
+}
+
+/// Channel for transaction preconfirmations shared by the state keeper and the WebSocket API server.
+///
+/// Unlike other subscriptions, preconfirmations are not polled from Postgres; they are pushed by
+/// [`PreconfirmationsPublisher`] plugged into the state keeper as an output handler.
+#[derive(Debug, Clone)]
+pub struct Preconfirmations {
+    sender: broadcast::Sender<Vec<PubSubResult>>,
+}
+
+impl Default for Preconfirmations {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
+        Self { sender }
+    }
+}
+
+impl Preconfirmations {
+    /// Creates a state keeper output handler publishing preconfirmations to this channel.
+    pub fn publisher(&self) -> PreconfirmationsPublisher {
+        PreconfirmationsPublisher {
+            sender: self.sender.clone(),
+            pending: Vec::new(),
+        }
+    }
+
+    pub(super) fn subscribe(&self) -> broadcast::Receiver<Vec<PubSubResult>> {
+        self.sender.subscribe()
+    }
+}
+
+/// State keeper output handler publishing results of executed transactions before their L2 block is sealed.
+#[derive(Debug)]
+pub struct PreconfirmationsPublisher {
+    sender: broadcast::Sender<Vec<PubSubResult>>,
+    /// Preconfirmations published for the currently open L2 block; used to retract them on rollback.
+    pending: Vec<TxPreconfirmation>,
+}
+
+impl PreconfirmationsPublisher {
+    fn preconfirmation(updates_manager: &UpdatesManager) -> Option<TxPreconfirmation> {
+        let l2_block = &updates_manager.l2_block;
+        let tx_result = l2_block.executed_transactions.last()?;
+        let index_in_block = l2_block.executed_transactions.len() - 1;
+        let index_in_batch = updates_manager.l1_batch.executed_transactions.len() + index_in_block;
+        let block_number = U64::from(l2_block.number.0);
+        let l1_batch_number = U64::from(updates_manager.l1_batch.number.0);
+
+        // Events of the last transaction are located at the end of the L2 block events.
+        let tx_events_start = l2_block
+            .events
+            .iter()
+            .rposition(|event| event.location.1 as usize != index_in_batch)
+            .map_or(0, |pos| pos + 1);
+        let logs = l2_block.events[tx_events_start..]
+            .iter()
+            .enumerate()
+            .map(|(i, event)| Log {
+                address: event.address,
+                topics: event.indexed_topics.clone(),
+                data: Bytes(event.value.clone()),
+                block_hash: None,
+                block_number: Some(block_number),
+                l1_batch_number: Some(l1_batch_number),
+                transaction_hash: Some(tx_result.hash),
+                transaction_index: Some(Index::from(index_in_block)),
+                log_index: Some(U256::from(tx_events_start + i)),
+                transaction_log_index: Some(U256::from(i)),
+                log_type: None,
+                removed: Some(false),
+                block_timestamp: Some(l2_block.timestamp.into()),
+            })
+            .collect();
+
+        let tx = &tx_result.transaction;
+        let gas_used = tx.gas_limit().saturating_sub(tx_result.refunded_gas.into());
+        let execution_status: u64 = match tx_result.execution_status {
+            TxExecutionStatus::Success => 1,
+            TxExecutionStatus::Failure => 0,
+        };
+        Some(TxPreconfirmation {
+            transaction_hash: tx_result.hash,
+            status: PreconfirmationStatus::Preconfirmed,
+            is_final: false,
+            block_number,
+            l1_batch_number,
+            transaction_index: Index::from(index_in_block),
+            receipt: Some(PreconfirmedReceipt {
+                from: tx.initiator_account(),
+                to: tx.recipient_account(),
+                execution_status: execution_status.into(),
+                gas_used,
+                logs,
+                revert_reason: tx_result.revert_reason.clone(),
+            }),
+        })
+    }
+
+    fn send(&self, results: Vec<PubSubResult>) {
+        // Errors only on 0 receivers, which is fine.
+        self.sender.send(results).ok();
+        PUB_SUB_METRICS.broadcast_channel_len[&SubscriptionType::Preconfirmations]
+            .set(self.sender.len());
+    }
+}
+
+#[async_trait]
+impl StateKeeperOutputHandler for PreconfirmationsPublisher {
+    async fn handle_executed_tx(&mut self, updates_manager: &UpdatesManager) -> anyhow::Result<()> {
+        if let Some(preconfirmation) = Self::preconfirmation(updates_manager) {
+            self.send(vec![PubSubResult::Preconfirmation(preconfirmation.clone())]);
+            self.pending.push(preconfirmation);
+        }
+        Ok(())
+    }
+
+    async fn handle_l2_block(&mut self, _updates_manager: &UpdatesManager) -> anyhow::Result<()> {
+        // Transactions are now persisted, so they can be obtained via the conventional API methods.
+        self.pending.clear();
+        Ok(())
+    }
+
+    async fn handle_l2_block_rollback(
+        &mut self,
+        _updates_manager: &UpdatesManager,
+    ) -> anyhow::Result<()> {
+        let retractions = self
+            .pending
+            .drain(..)
+            .map(|preconfirmation| {
+                tracing::debug!(
+                    "Retracting preconfirmation for transaction {:?}",
+                    preconfirmation.transaction_hash
+                );
+                PubSubResult::Preconfirmation(TxPreconfirmation {
+                    status: PreconfirmationStatus::Retracted,
+                    receipt: None,
+                    ..preconfirmation
+                })
+            })
+            .collect::<Vec<_>>();
+        if !retractions.is_empty() {
+            self.send(retractions);
+        }
+        Ok(())
+    }
 }
 
 /// Subscription support for Web3 APIs.
 pub(super) struct EthSubscribe {
     blocks: broadcast::Sender<Vec<PubSubResult>>,
     transactions: broadcast::Sender<Vec<PubSubResult>>,
+    full_transactions: broadcast::Sender<Vec<PubSubResult>>,
     logs: broadcast::Sender<Vec<PubSubResult>>,
+    l1_batches: broadcast::Sender<Vec<PubSubResult>>,
+    preconfirmations: Option<Preconfirmations>,
     events_sender: Option<mpsc::UnboundedSender<PubSubEvent>>,
 }
 
@@ -243,16 +582,25 @@ impl EthSubscribe {
     pub fn new() -> Self {
         let (blocks, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
         let (transactions, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
+        let (full_transactions, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
         let (logs, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
+        let (l1_batches, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
 
         Self {
             blocks,
             transactions,
+            full_transactions,
             logs,
+            l1_batches,
+            preconfirmations: None,
             events_sender: None,
         }
     }
 
+    pub fn set_preconfirmations(&mut self, preconfirmations: Preconfirmations) {
+        self.preconfirmations = Some(preconfirmations);
+    }
+
     pub fn set_events_sender(&mut self, sender: mpsc::UnboundedSender<PubSubEvent>) {
         self.events_sender = Some(sender);
     }
@@ -350,7 +698,7 @@ impl EthSubscribe {
         &self,
         pending_sink: PendingSubscriptionSink,
         sub_type: String,
-        params: Option<PubSubFilter>,
+        params: Option<PubSubParams>,
     ) {
         let sub_type = match sub_type.as_str() {
             "newHeads" => {
@@ -369,15 +717,31 @@ impl EthSubscribe {
                 let Ok(sink) = pending_sink.accept().await else {
                     return;
                 };
-                let transactions_rx = self.transactions.subscribe();
+                let full_transactions = params
+                    .as_ref()
+                    .map_or(false, PubSubParams::full_transactions);
+                let (sub_type, transactions_rx) = if full_transactions {
+                    (
+                        SubscriptionType::FullTxs,
+                        self.full_transactions.subscribe(),
+                    )
+                } else {
+                    (SubscriptionType::Txs, self.transactions.subscribe())
+                };
                 tokio::spawn(
-                    Self::run_subscriber(sink, SubscriptionType::Txs, transactions_rx, None)
-                        .in_current_span(),
+                    Self::run_subscriber(sink, sub_type, transactions_rx, None).in_current_span(),
                 );
-                Some(SubscriptionType::Txs)
+                Some(sub_type)
             }
             "logs" => {
-                let filter = params.unwrap_or_default();
+                let filter = match params {
+                    None => PubSubFilter::default(),
+                    Some(PubSubParams::Filter(filter)) => filter,
+                    Some(_) => {
+                        Self::reject(pending_sink).await;
+                        return;
+                    }
+                };
                 let topic_count = filter.topics.as_ref().map_or(0, Vec::len);
 
                 if topic_count > EVENT_TOPIC_NUMBER_LIMIT {
@@ -395,6 +759,38 @@ impl EthSubscribe {
                     Some(SubscriptionType::Logs)
                 }
             }
+            "l1Batches" => {
+                let Ok(sink) = pending_sink.accept().await else {
+                    return;
+                };
+                let l1_batches_rx = self.l1_batches.subscribe();
+                tokio::spawn(
+                    Self::run_subscriber(sink, SubscriptionType::L1Batches, l1_batches_rx, None)
+                        .in_current_span(),
+                );
+                Some(SubscriptionType::L1Batches)
+            }
+            "preconfirmations" => {
+                if let Some(preconfirmations) = &self.preconfirmations {
+                    let Ok(sink) = pending_sink.accept().await else {
+                        return;
+                    };
+                    let preconfirmations_rx = preconfirmations.subscribe();
+                    tokio::spawn(
+                        Self::run_subscriber(
+                            sink,
+                            SubscriptionType::Preconfirmations,
+                            preconfirmations_rx,
+                            None,
+                        )
+                        .in_current_span(),
+                    );
+                    Some(SubscriptionType::Preconfirmations)
+                } else {
+                    Self::reject(pending_sink).await;
+                    None
+                }
+            }
             "syncing" => {
                 let Ok(sink) = pending_sink.accept().await else {
                     return;
@@ -426,10 +822,11 @@ impl EthSubscribe {
     pub fn spawn_notifiers(
         &self,
         connection_pool: ConnectionPool<Core>,
+        l2_chain_id: L2ChainId,
         polling_interval: Duration,
         stop_receiver: watch::Receiver<bool>,
     ) -> Vec<JoinHandle<anyhow::Result<()>>> {
-        let mut notifier_tasks = Vec::with_capacity(3);
+        let mut notifier_tasks = Vec::with_capacity(5);
 
         let notifier = PubSubNotifier {
             sender: self.blocks.clone(),
@@ -449,6 +846,25 @@ impl EthSubscribe {
         let notifier_task = tokio::spawn(notifier.notify_txs(stop_receiver.clone()));
         notifier_tasks.push(notifier_task);
 
+        let notifier = PubSubNotifier {
+            sender: self.full_transactions.clone(),
+            connection_pool: connection_pool.clone(),
+            polling_interval,
+            events_sender: self.events_sender.clone(),
+        };
+        let notifier_task =
+            tokio::spawn(notifier.notify_full_txs(l2_chain_id, stop_receiver.clone()));
+        notifier_tasks.push(notifier_task);
+
+        let notifier = PubSubNotifier {
+            sender: self.l1_batches.clone(),
+            connection_pool: connection_pool.clone(),
+            polling_interval,
+            events_sender: self.events_sender.clone(),
+        };
+        let notifier_task = tokio::spawn(notifier.notify_l1_batches(stop_receiver.clone()));
+        notifier_tasks.push(notifier_task);
+
         let notifier = PubSubNotifier {
             sender: self.logs.clone(),
             connection_pool,
@@ -468,9 +884,9 @@ impl EthPubSubServer for EthSubscribe {
         &self,
         pending: PendingSubscriptionSink,
         sub_type: String,
-        filter: Option<PubSubFilter>,
+        params: Option<PubSubParams>,
     ) -> SubscriptionResult {
-        self.sub(pending, sub_type, filter).await;
+        self.sub(pending, sub_type, params).await;
         Ok(())
     }
 }
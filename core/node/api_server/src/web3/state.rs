@@ -109,6 +109,7 @@ pub struct InternalApiConfig {
     pub l2_testnet_paymaster_addr: Option<Address>,
     pub req_entities_limit: usize,
     pub fee_history_limit: u64,
+    pub trace_filter_block_range_limit: u64,
     pub base_token_address: Option<Address>,
     pub filters_disabled: bool,
     pub dummy_verifier: bool,
@@ -160,6 +161,7 @@ impl InternalApiConfig {
             l2_testnet_paymaster_addr: contracts_config.l2_testnet_paymaster_addr,
             req_entities_limit: web3_config.req_entities_limit(),
             fee_history_limit: web3_config.fee_history_limit(),
+            trace_filter_block_range_limit: web3_config.trace_filter_block_range_limit(),
             base_token_address: contracts_config.base_token_addr,
             filters_disabled: web3_config.filters_disabled,
             dummy_verifier: genesis_config.dummy_verifier,
@@ -293,6 +295,32 @@ impl RpcState {
             .map_err(|err| err.generalize().into())
     }
 
+    /// Checks that logs matching the specified filter are not pruned. Logs in pruned blocks are still available
+    /// if they are guaranteed to be retained by the pruning retention policy, which must have been in effect
+    /// since `filter.from_block`.
+    pub(crate) async fn ensure_logs_not_pruned(
+        &self,
+        filter: &api::GetLogsFilter,
+        storage: &mut Connection<'_, Core>,
+    ) -> Result<(), Web3Error> {
+        let first_l2_block = self.start_info.first_l2_block(storage).await?;
+        if filter.from_block >= first_l2_block {
+            return Ok(());
+        }
+        let applied_policy = storage
+            .pruning_dal()
+            .get_applied_retention_policy()
+            .await
+            .map_err(DalError::generalize)?;
+        let first_retained_block =
+            applied_policy.first_block_retaining_events(&filter.addresses, &filter.topics);
+        if first_retained_block.is_some_and(|block| filter.from_block >= block) {
+            Ok(())
+        } else {
+            Err(Web3Error::PrunedBlock(first_l2_block))
+        }
+    }
+
     /// Resolves the specified block ID to a block number, which is guaranteed to be present in the node storage.
     pub(crate) async fn resolve_block(
         &self,
@@ -1,6 +1,6 @@
 //! Test utilities useful for writing unit tests outside of this crate.
 
-use std::{pin::Pin, time::Instant};
+use std::{path::Path, pin::Pin, time::Instant};
 
 use async_trait::async_trait;
 use tokio::sync::watch;
@@ -16,7 +16,7 @@ use zksync_types::{
 };
 use zksync_vm_executor::oneshot::MockOneshotExecutor;
 
-use super::{metrics::ApiTransportLabel, *};
+use super::*;
 use crate::{execution_sandbox::TransactionExecutor, tx_sender::TxSenderConfig};
 
 const TEST_TIMEOUT: Duration = Duration::from_secs(90);
@@ -89,6 +89,19 @@ pub(crate) async fn create_test_tx_sender(
 impl ApiServerHandles {
     /// Waits until the server health check reports the ready state. Must be called once per server instance.
     pub async fn wait_until_ready(&mut self) -> SocketAddr {
+        self.wait_until_healthy().await;
+
+        let mut local_addr_future = Pin::new(&mut self.local_addr);
+        local_addr_future
+            .as_mut()
+            .await
+            .expect("API server panicked");
+        local_addr_future.output_mut().copied().unwrap()
+    }
+
+    /// Waits until the server health check reports the ready state. Unlike [`Self::wait_until_ready()`],
+    /// this doesn't require the server to listen on a TCP socket.
+    pub async fn wait_until_healthy(&self) {
         let started_at = Instant::now();
         loop {
             assert!(
@@ -101,13 +114,6 @@ impl ApiServerHandles {
             }
             tokio::time::sleep(POLL_INTERVAL).await;
         }
-
-        let mut local_addr_future = Pin::new(&mut self.local_addr);
-        local_addr_future
-            .as_mut()
-            .await
-            .expect("API server panicked");
-        local_addr_future.output_mut().copied().unwrap()
     }
 
     pub async fn shutdown(self) {
@@ -138,10 +144,11 @@ pub async fn spawn_http_server(
     stop_receiver: watch::Receiver<bool>,
 ) -> ApiServerHandles {
     spawn_server(
-        ApiTransportLabel::Http,
+        ApiTransport::Http(([0, 0, 0, 0], 0).into()),
         api_config,
         pool,
         None,
+        None,
         tx_executor,
         method_tracer,
         stop_receiver,
@@ -157,10 +164,31 @@ pub async fn spawn_ws_server(
     websocket_requests_per_minute_limit: Option<NonZeroU32>,
 ) -> (ApiServerHandles, mpsc::UnboundedReceiver<PubSubEvent>) {
     spawn_server(
-        ApiTransportLabel::Ws,
+        ApiTransport::WebSocket(([0, 0, 0, 0], 0).into()),
         api_config,
         pool,
         websocket_requests_per_minute_limit,
+        None,
+        MockOneshotExecutor::default(),
+        Arc::default(),
+        stop_receiver,
+    )
+    .await
+}
+
+pub async fn spawn_ipc_server(
+    api_config: InternalApiConfig,
+    pool: ConnectionPool<Core>,
+    socket_path: &Path,
+    ipc_config: IpcConfig,
+    stop_receiver: watch::Receiver<bool>,
+) -> (ApiServerHandles, mpsc::UnboundedReceiver<PubSubEvent>) {
+    spawn_server(
+        ApiTransport::Ipc(socket_path.to_owned()),
+        api_config,
+        pool,
+        None,
+        Some(ipc_config),
         MockOneshotExecutor::default(),
         Arc::default(),
         stop_receiver,
@@ -169,10 +197,11 @@ pub async fn spawn_ws_server(
 }
 
 async fn spawn_server(
-    transport: ApiTransportLabel,
+    transport: ApiTransport,
     api_config: InternalApiConfig,
     pool: ConnectionPool<Core>,
     websocket_requests_per_minute_limit: Option<NonZeroU32>,
+    ipc_config: Option<IpcConfig>,
     tx_executor: MockOneshotExecutor,
     method_tracer: Arc<MethodTracer>,
     stop_receiver: watch::Receiver<bool>,
@@ -182,21 +211,21 @@ async fn spawn_server(
     let (pub_sub_events_sender, pub_sub_events_receiver) = mpsc::unbounded_channel();
 
     let mut namespaces = Namespace::DEFAULT.to_vec();
-    namespaces.extend([Namespace::Debug, Namespace::Snapshots]);
-
-    let server_builder = match transport {
-        ApiTransportLabel::Http => ApiBuilder::jsonrpsee_backend(api_config, pool).http(0),
-        ApiTransportLabel::Ws => {
-            let mut builder = ApiBuilder::jsonrpsee_backend(api_config, pool)
-                .ws(0)
-                .with_subscriptions_limit(100);
-            if let Some(websocket_requests_per_minute_limit) = websocket_requests_per_minute_limit {
-                builder = builder
-                    .with_websocket_requests_per_minute_limit(websocket_requests_per_minute_limit);
-            }
-            builder
+    namespaces.extend([Namespace::Debug, Namespace::Snapshots, Namespace::Trace]);
+
+    let is_http = matches!(transport, ApiTransport::Http(_));
+    let mut server_builder = ApiBuilder::jsonrpsee_backend(api_config, pool);
+    server_builder.transport = Some(transport);
+    if !is_http {
+        server_builder = server_builder.with_subscriptions_limit(100);
+        if let Some(websocket_requests_per_minute_limit) = websocket_requests_per_minute_limit {
+            server_builder = server_builder
+                .with_websocket_requests_per_minute_limit(websocket_requests_per_minute_limit);
         }
-    };
+    }
+    if let Some(ipc_config) = ipc_config {
+        server_builder = server_builder.with_ipc_config(ipc_config);
+    }
     let server_handles = server_builder
         .with_polling_interval(POLL_INTERVAL)
         .with_tx_sender(tx_sender)
@@ -9,7 +9,7 @@ use zksync_web3_decl::{
 
 use super::*;
 
-fn execute_l2_transaction_with_traces(index_in_block: u8) -> TransactionExecutionResult {
+pub(super) fn execute_l2_transaction_with_traces(index_in_block: u8) -> TransactionExecutionResult {
     let first_call_trace = Call {
         from: Address::repeat_byte(index_in_block),
         to: Address::repeat_byte(index_in_block + 1),
@@ -0,0 +1,344 @@
+//! IPC-related tests.
+
+use std::os::unix::fs::PermissionsExt;
+
+use serde_json::json;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines},
+    net::{unix::OwnedReadHalf, UnixStream},
+};
+use zksync_web3_decl::jsonrpsee::types::error::TOO_MANY_SUBSCRIPTIONS_CODE;
+
+use super::{
+    ws::{wait_for_notifiers, wait_for_subscription},
+    *,
+};
+use crate::web3::{metrics::SubscriptionType, testonly::spawn_ipc_server};
+
+async fn next_message(messages: &mut Lines<BufReader<OwnedReadHalf>>) -> serde_json::Value {
+    let message = tokio::time::timeout(TEST_TIMEOUT, messages.next_line())
+        .await
+        .expect("Timed out waiting for IPC message")
+        .expect("failed reading from IPC socket")
+        .expect("IPC connection closed");
+    serde_json::from_str(&message).unwrap()
+}
+
+async fn prepare_storage(pool: &ConnectionPool<Core>) {
+    let mut storage = pool.connection().await.unwrap();
+    StorageInitialization::Genesis
+        .prepare_storage(&NetworkConfig::for_tests(), &mut storage)
+        .await
+        .unwrap();
+}
+
+fn api_config() -> InternalApiConfig {
+    InternalApiConfig::new(
+        &Web3JsonRpcConfig::for_tests(),
+        &ContractsConfig::for_tests(),
+        &GenesisConfig::for_tests(),
+    )
+}
+
+#[tokio::test]
+async fn ipc_server_basics() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    prepare_storage(&pool).await;
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let socket_path = temp_dir.path().join("zksync.ipc");
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let (server_handles, mut pub_sub_events) = spawn_ipc_server(
+        api_config(),
+        pool.clone(),
+        &socket_path,
+        IpcConfig::default(),
+        stop_receiver,
+    )
+    .await;
+    server_handles.wait_until_healthy().await;
+
+    let (reader, mut writer) = UnixStream::connect(&socket_path)
+        .await
+        .unwrap()
+        .into_split();
+    let mut messages = BufReader::new(reader).lines();
+
+    // Requests may be split across multiple writes.
+    writer
+        .write_all(br#"{"jsonrpc":"2.0","id":1,"method":"eth_block"#)
+        .await
+        .unwrap();
+    writer.write_all(br#"Number","params":[]}"#).await.unwrap();
+    let response = next_message(&mut messages).await;
+    assert_eq!(
+        response,
+        json!({ "jsonrpc": "2.0", "id": 1, "result": "0x0" })
+    );
+
+    writer
+        .write_all(
+            br#"[{"jsonrpc":"2.0","id":2,"method":"eth_blockNumber","params":[]},
+                {"jsonrpc":"2.0","id":3,"method":"eth_unknownMethod","params":[]}]"#,
+        )
+        .await
+        .unwrap();
+    let response = next_message(&mut messages).await;
+    let responses = response.as_array().unwrap();
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[0]["id"], 2);
+    assert_eq!(responses[0]["result"], "0x0");
+    assert_eq!(responses[1]["id"], 3);
+    assert_eq!(
+        responses[1]["error"]["code"],
+        ErrorCode::MethodNotFound.code()
+    );
+
+    wait_for_notifiers(&mut pub_sub_events, &[SubscriptionType::Blocks]).await;
+    writer
+        .write_all(br#"{"jsonrpc":"2.0","id":4,"method":"eth_subscribe","params":["newHeads"]}"#)
+        .await
+        .unwrap();
+    let response = next_message(&mut messages).await;
+    assert_eq!(response["id"], 4);
+    let subscription_id = response["result"].clone();
+    assert!(!subscription_id.is_null(), "{response}");
+    wait_for_subscription(&mut pub_sub_events, SubscriptionType::Blocks).await;
+
+    let mut storage = pool.connection().await.unwrap();
+    let new_l2_block = store_l2_block(&mut storage, L2BlockNumber(1), &[])
+        .await
+        .unwrap();
+    drop(storage);
+
+    let notification = next_message(&mut messages).await;
+    assert_eq!(notification["method"], "eth_subscription");
+    assert_eq!(notification["params"]["subscription"], subscription_id);
+    assert_eq!(
+        notification["params"]["result"]["hash"],
+        json!(new_l2_block.hash)
+    );
+
+    stop_sender.send_replace(true);
+    server_handles.shutdown().await;
+    assert!(!socket_path.exists());
+}
+
+fn unsubscribe_request(id: u64, subscription_id: &serde_json::Value) -> String {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "method": "eth_unsubscribe",
+        "params": [subscription_id],
+    })
+    .to_string()
+}
+
+#[tokio::test]
+async fn ipc_server_unsubscribe() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    prepare_storage(&pool).await;
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let socket_path = temp_dir.path().join("zksync.ipc");
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let (server_handles, mut pub_sub_events) = spawn_ipc_server(
+        api_config(),
+        pool.clone(),
+        &socket_path,
+        IpcConfig::default(),
+        stop_receiver,
+    )
+    .await;
+    server_handles.wait_until_healthy().await;
+
+    let (reader, mut writer) = UnixStream::connect(&socket_path)
+        .await
+        .unwrap()
+        .into_split();
+    let mut messages = BufReader::new(reader).lines();
+    let (other_reader, mut other_writer) = UnixStream::connect(&socket_path)
+        .await
+        .unwrap()
+        .into_split();
+    let mut other_messages = BufReader::new(other_reader).lines();
+
+    wait_for_notifiers(&mut pub_sub_events, &[SubscriptionType::Blocks]).await;
+    writer
+        .write_all(br#"{"jsonrpc":"2.0","id":1,"method":"eth_subscribe","params":["newHeads"]}"#)
+        .await
+        .unwrap();
+    let response = next_message(&mut messages).await;
+    assert_eq!(response["id"], 1);
+    let subscription_id = response["result"].clone();
+    // Subscription IDs must have the same format as for the WS server.
+    let subscription_id_str = subscription_id.as_str().unwrap();
+    let subscription_id_hex = subscription_id_str.strip_prefix("0x").unwrap();
+    assert_eq!(subscription_id_hex.len(), 32, "{subscription_id_str}");
+    hex::decode(subscription_id_hex).unwrap();
+    wait_for_subscription(&mut pub_sub_events, SubscriptionType::Blocks).await;
+
+    // Subscriptions cannot be terminated from another connection.
+    other_writer
+        .write_all(unsubscribe_request(2, &subscription_id).as_bytes())
+        .await
+        .unwrap();
+    let response = next_message(&mut other_messages).await;
+    assert_eq!(response["id"], 2);
+    assert_eq!(response["result"], false, "{response}");
+
+    let mut storage = pool.connection().await.unwrap();
+    let new_l2_block = store_l2_block(&mut storage, L2BlockNumber(1), &[])
+        .await
+        .unwrap();
+    drop(storage);
+
+    let notification = next_message(&mut messages).await;
+    assert_eq!(notification["params"]["subscription"], subscription_id);
+    assert_eq!(
+        notification["params"]["result"]["hash"],
+        json!(new_l2_block.hash)
+    );
+
+    writer
+        .write_all(unsubscribe_request(3, &subscription_id).as_bytes())
+        .await
+        .unwrap();
+    let response = next_message(&mut messages).await;
+    assert_eq!(response["id"], 3);
+    assert_eq!(response["result"], true, "{response}");
+
+    writer
+        .write_all(unsubscribe_request(4, &subscription_id).as_bytes())
+        .await
+        .unwrap();
+    let response = next_message(&mut messages).await;
+    assert_eq!(response["id"], 4);
+    assert_eq!(response["result"], false, "{response}");
+
+    stop_sender.send_replace(true);
+    server_handles.shutdown().await;
+}
+
+#[tokio::test]
+async fn ipc_server_limits() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    prepare_storage(&pool).await;
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let socket_path = temp_dir.path().join("zksync.ipc");
+    let ipc_config = IpcConfig {
+        max_connections: 1,
+        max_subscriptions_per_connection: 1,
+        max_concurrent_requests_per_connection: 1,
+        socket_permissions: Some(0o600),
+    };
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let (server_handles, mut pub_sub_events) = spawn_ipc_server(
+        api_config(),
+        pool.clone(),
+        &socket_path,
+        ipc_config,
+        stop_receiver,
+    )
+    .await;
+    server_handles.wait_until_healthy().await;
+
+    let socket_mode = std::fs::metadata(&socket_path)
+        .unwrap()
+        .permissions()
+        .mode();
+    assert_eq!(socket_mode & 0o777, 0o600);
+
+    let (reader, mut writer) = UnixStream::connect(&socket_path)
+        .await
+        .unwrap()
+        .into_split();
+    let mut messages = BufReader::new(reader).lines();
+
+    // Pipelined requests must all be processed despite the concurrency limit.
+    let requests: String = (1..=5)
+        .map(|id| {
+            format!(r#"{{"jsonrpc":"2.0","id":{id},"method":"eth_blockNumber","params":[]}}"#)
+        })
+        .collect();
+    writer.write_all(requests.as_bytes()).await.unwrap();
+    let mut response_ids = vec![];
+    for _ in 0..5 {
+        let response = next_message(&mut messages).await;
+        assert_eq!(response["result"], "0x0", "{response}");
+        response_ids.push(response["id"].as_u64().unwrap());
+    }
+    response_ids.sort_unstable();
+    assert_eq!(response_ids, [1, 2, 3, 4, 5]);
+
+    // The connection limit is reached, so the server should close the second connection immediately.
+    let (second_reader, _second_writer) = UnixStream::connect(&socket_path)
+        .await
+        .unwrap()
+        .into_split();
+    let mut second_messages = BufReader::new(second_reader).lines();
+    let read_result = tokio::time::timeout(TEST_TIMEOUT, second_messages.next_line())
+        .await
+        .expect("Timed out waiting for the second IPC connection to be closed");
+    assert_matches!(read_result, Ok(None) | Err(_));
+
+    wait_for_notifiers(&mut pub_sub_events, &[SubscriptionType::Blocks]).await;
+    let subscribe_request = |id: u64| {
+        format!(r#"{{"jsonrpc":"2.0","id":{id},"method":"eth_subscribe","params":["newHeads"]}}"#)
+    };
+    writer
+        .write_all(subscribe_request(10).as_bytes())
+        .await
+        .unwrap();
+    let response = next_message(&mut messages).await;
+    assert_eq!(response["id"], 10);
+    let subscription_id = response["result"].clone();
+    assert!(!subscription_id.is_null(), "{response}");
+    wait_for_subscription(&mut pub_sub_events, SubscriptionType::Blocks).await;
+
+    writer
+        .write_all(subscribe_request(11).as_bytes())
+        .await
+        .unwrap();
+    let response = next_message(&mut messages).await;
+    assert_eq!(response["id"], 11);
+    assert_eq!(
+        response["error"]["code"], TOO_MANY_SUBSCRIPTIONS_CODE,
+        "{response}"
+    );
+
+    writer
+        .write_all(unsubscribe_request(12, &subscription_id).as_bytes())
+        .await
+        .unwrap();
+    let response = next_message(&mut messages).await;
+    assert_eq!(response["id"], 12);
+    assert_eq!(response["result"], true, "{response}");
+
+    // The subscription slot is released asynchronously once the subscription is terminated.
+    let resubscribe = async {
+        for id in 13.. {
+            writer
+                .write_all(subscribe_request(id).as_bytes())
+                .await
+                .unwrap();
+            let response = next_message(&mut messages).await;
+            assert_eq!(response["id"], id);
+            if !response["result"].is_null() {
+                break;
+            }
+            assert_eq!(
+                response["error"]["code"], TOO_MANY_SUBSCRIPTIONS_CODE,
+                "{response}"
+            );
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    };
+    tokio::time::timeout(TEST_TIMEOUT, resubscribe)
+        .await
+        .expect("Timed out waiting for subscription slot to be released");
+
+    stop_sender.send_replace(true);
+    server_handles.shutdown().await;
+}
@@ -62,7 +62,9 @@ use crate::web3::testonly::{spawn_http_server, spawn_ws_server};
 
 mod debug;
 mod filters;
+mod ipc;
 mod snapshots;
+mod trace;
 mod vm;
 mod ws;
 
@@ -151,6 +153,11 @@ trait HttpTest: Send + Sync {
     fn filters_disabled(&self) -> bool {
         false
     }
+
+    /// Overrides the `trace_filter_block_range_limit` configuration parameter for HTTP server startup
+    fn trace_filter_block_range_limit(&self) -> Option<u64> {
+        None
+    }
 }
 
 /// Storage initialization strategy.
@@ -244,6 +251,9 @@ async fn test_http_server(test: impl HttpTest) {
     let genesis = GenesisConfig::for_tests();
     let mut api_config = InternalApiConfig::new(&web3_config, &contracts_config, &genesis);
     api_config.filters_disabled = test.filters_disabled();
+    if let Some(limit) = test.trace_filter_block_range_limit() {
+        api_config.trace_filter_block_range_limit = limit;
+    }
     let mut server_handles = spawn_http_server(
         api_config,
         pool.clone(),
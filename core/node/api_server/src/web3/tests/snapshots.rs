@@ -109,3 +109,78 @@ async fn snapshot_with_some_chunks() {
 async fn snapshot_with_all_chunks() {
     test_http_server(SnapshotBasicsTest::new(0..SnapshotBasicsTest::CHUNK_COUNT)).await;
 }
+
+#[derive(Debug)]
+struct DeltaSnapshotsTest;
+
+#[async_trait]
+impl HttpTest for DeltaSnapshotsTest {
+    async fn test(
+        &self,
+        client: &DynClient<L2>,
+        pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<()> {
+        let mut storage = pool.connection().await.unwrap();
+        for number in [1, 2] {
+            store_l2_block(&mut storage, L2BlockNumber(number), &[]).await?;
+            seal_l1_batch(&mut storage, L1BatchNumber(number)).await?;
+        }
+        let mut dal = storage.snapshots_dal();
+        dal.add_snapshot(
+            SnapshotVersion::Version1,
+            L1BatchNumber(1),
+            1,
+            "file:///factory_deps",
+        )
+        .await?;
+        dal.add_delta_snapshot(
+            SnapshotVersion::Version1,
+            L1BatchNumber(2),
+            L1BatchNumber(1),
+            1,
+            "file:///factory_deps_delta",
+        )
+        .await?;
+        for number in [1, 2] {
+            let path = format!("file:///storage_logs/snapshot{number}_chunk0");
+            dal.add_storage_logs_filepath_for_snapshot(L1BatchNumber(number), 0, &path)
+                .await?;
+        }
+
+        // Legacy methods must not return delta snapshots.
+        let all_snapshots = client.get_all_snapshots().await?;
+        assert_eq!(all_snapshots.snapshots_l1_batch_numbers, [L1BatchNumber(1)]);
+        let delta_header = client
+            .get_snapshot_by_l1_batch_number(L1BatchNumber(2))
+            .await?;
+        assert!(delta_header.is_none(), "{delta_header:?}");
+
+        let all_snapshots = client.get_all_snapshots_with_deltas().await?;
+        assert_eq!(
+            all_snapshots.snapshots_l1_batch_numbers,
+            [L1BatchNumber(2), L1BatchNumber(1)]
+        );
+        let delta_header = client
+            .get_snapshot_with_deltas(L1BatchNumber(2))
+            .await?
+            .context("no delta snapshot for L1 batch #2")?;
+        assert_eq!(delta_header.l2_block_number, L2BlockNumber(2));
+        assert_eq!(delta_header.base_l1_batch_number, Some(L1BatchNumber(1)));
+        assert_eq!(
+            delta_header.factory_deps_filepath,
+            "file:///factory_deps_delta"
+        );
+
+        let base_header = client
+            .get_snapshot_with_deltas(L1BatchNumber(1))
+            .await?
+            .context("no snapshot for L1 batch #1")?;
+        assert_eq!(base_header.base_l1_batch_number, None);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn delta_snapshots() {
+    test_http_server(DeltaSnapshotsTest).await;
+}
@@ -0,0 +1,326 @@
+//! Tests for the `trace` Web3 namespace.
+
+use zksync_types::{
+    api::trace::{Trace, TraceFilter, TraceType},
+    BOOTLOADER_ADDRESS,
+};
+use zksync_web3_decl::{
+    client::{DynClient, L2},
+    namespaces::TraceNamespaceClient,
+};
+
+use super::{debug::execute_l2_transaction_with_traces, *};
+
+/// Checks that traces for a single transaction produced by [`execute_l2_transaction_with_traces()`]
+/// are correctly flattened.
+fn assert_transaction_traces(
+    traces: &[Trace],
+    tx_result: &TransactionExecutionResult,
+    block_number: L2BlockNumber,
+    index_in_block: usize,
+) {
+    assert_eq!(traces.len(), tx_result.call_traces.len() + 1, "{traces:?}");
+    for trace in traces {
+        assert_eq!(trace.r#type, TraceType::Call);
+        assert_eq!(trace.transaction_hash, tx_result.hash);
+        assert_eq!(trace.block_number, U64::from(block_number.0));
+        assert_eq!(trace.transaction_position, U64::from(index_in_block));
+        assert!(trace.result.is_some(), "{trace:?}");
+        assert_eq!(trace.error, None);
+    }
+
+    let top_level_trace = &traces[0];
+    assert_eq!(top_level_trace.from(), Address::zero());
+    assert_eq!(top_level_trace.to(), BOOTLOADER_ADDRESS);
+    assert!(top_level_trace.trace_address.is_empty());
+    assert_eq!(top_level_trace.subtraces, tx_result.call_traces.len());
+
+    for (i, (trace, call)) in traces[1..].iter().zip(&tx_result.call_traces).enumerate() {
+        assert_eq!(trace.from(), call.from);
+        assert_eq!(trace.to(), call.to);
+        assert_eq!(trace.trace_address, [i]);
+        assert_eq!(trace.subtraces, 0);
+    }
+}
+
+fn assert_invalid_params_error(error: ClientError, expected_message: &str) {
+    if let ClientError::Call(error) = error {
+        assert_eq!(error.code(), ErrorCode::InvalidParams.code());
+        assert!(error.message().contains(expected_message), "{error:?}");
+        assert!(error.data().is_none(), "{error:?}");
+    } else {
+        panic!("Unexpected error: {error:?}");
+    }
+}
+
+#[derive(Debug)]
+struct TraceBlockTest(L2BlockNumber);
+
+#[async_trait]
+impl HttpTest for TraceBlockTest {
+    async fn test(
+        &self,
+        client: &DynClient<L2>,
+        pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<()> {
+        let tx_results = [0, 1, 2].map(execute_l2_transaction_with_traces);
+        let mut storage = pool.connection().await?;
+        let new_l2_block = store_l2_block(&mut storage, self.0, &tx_results).await?;
+        drop(storage);
+
+        let block_numbers = [api::BlockNumber::from(self.0 .0), api::BlockNumber::Latest];
+        for block_number in block_numbers {
+            let traces = client.trace_block(block_number).await?;
+            let traces_per_tx = tx_results[0].call_traces.len() + 1;
+            assert_eq!(traces.len(), tx_results.len() * traces_per_tx);
+
+            for (i, (tx_traces, tx_result)) in
+                traces.chunks(traces_per_tx).zip(&tx_results).enumerate()
+            {
+                assert_transaction_traces(tx_traces, tx_result, self.0, i);
+                for trace in tx_traces {
+                    assert_eq!(trace.block_hash, new_l2_block.hash);
+                }
+            }
+        }
+
+        let traces = client.trace_block(api::BlockNumber::Pending).await?;
+        assert!(traces.is_empty(), "{traces:?}");
+
+        let missing_block_number = api::BlockNumber::from(self.0 .0 + 100);
+        let error = client.trace_block(missing_block_number).await.unwrap_err();
+        assert_invalid_params_error(error, "doesn't exist");
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn tracing_block() {
+    test_http_server(TraceBlockTest(L2BlockNumber(1))).await;
+}
+
+#[derive(Debug)]
+struct TraceBlockTestWithSnapshotRecovery;
+
+#[async_trait]
+impl HttpTest for TraceBlockTestWithSnapshotRecovery {
+    fn storage_initialization(&self) -> StorageInitialization {
+        StorageInitialization::empty_recovery()
+    }
+
+    async fn test(
+        &self,
+        client: &DynClient<L2>,
+        pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<()> {
+        let snapshot_l2_block_number = StorageInitialization::SNAPSHOT_RECOVERY_BLOCK;
+        for number in [L2BlockNumber(0), snapshot_l2_block_number] {
+            let error = client.trace_block(number.0.into()).await.unwrap_err();
+            assert_pruned_block_error(&error, snapshot_l2_block_number + 1);
+        }
+
+        TraceBlockTest(snapshot_l2_block_number + 2)
+            .test(client, pool)
+            .await
+    }
+}
+
+#[tokio::test]
+async fn tracing_block_after_snapshot_recovery() {
+    test_http_server(TraceBlockTestWithSnapshotRecovery).await;
+}
+
+#[derive(Debug)]
+struct TraceTransactionTest;
+
+#[async_trait]
+impl HttpTest for TraceTransactionTest {
+    async fn test(
+        &self,
+        client: &DynClient<L2>,
+        pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<()> {
+        let tx_results = [0, 1].map(execute_l2_transaction_with_traces);
+        let mut storage = pool.connection().await?;
+        store_l2_block(&mut storage, L2BlockNumber(1), &tx_results).await?;
+        drop(storage);
+
+        for (i, tx_result) in tx_results.iter().enumerate() {
+            let traces = client
+                .trace_transaction(tx_result.hash)
+                .await?
+                .context("no transaction traces")?;
+            assert_transaction_traces(&traces, tx_result, L2BlockNumber(1), i);
+        }
+
+        let traces = client.trace_transaction(H256::repeat_byte(0xff)).await?;
+        assert!(traces.is_none(), "{traces:?}");
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn tracing_transaction() {
+    test_http_server(TraceTransactionTest).await;
+}
+
+#[derive(Debug)]
+struct TraceFilterTest;
+
+impl TraceFilterTest {
+    const BLOCK_RANGE_LIMIT: u64 = 2;
+
+    fn block_filter(from_block: u32, to_block: u32) -> TraceFilter {
+        TraceFilter {
+            from_block: Some(from_block.into()),
+            to_block: Some(to_block.into()),
+            ..TraceFilter::default()
+        }
+    }
+
+    /// Returns `(tx_hash, trace_address)` pairs for the traces for easier comparison.
+    fn trace_ids(traces: &[Trace]) -> Vec<(H256, Vec<usize>)> {
+        traces
+            .iter()
+            .map(|trace| (trace.transaction_hash, trace.trace_address.clone()))
+            .collect()
+    }
+}
+
+#[async_trait]
+impl HttpTest for TraceFilterTest {
+    fn trace_filter_block_range_limit(&self) -> Option<u64> {
+        Some(Self::BLOCK_RANGE_LIMIT)
+    }
+
+    async fn test(
+        &self,
+        client: &DynClient<L2>,
+        pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<()> {
+        // Indices start from 1 so that calls in transactions don't originate from the zero address
+        // (which is the sender of top-level calls).
+        let first_block_txs = [1, 2].map(execute_l2_transaction_with_traces);
+        let second_block_txs = [3].map(execute_l2_transaction_with_traces);
+        let mut storage = pool.connection().await?;
+        store_l2_block(&mut storage, L2BlockNumber(1), &first_block_txs).await?;
+        store_l2_block(&mut storage, L2BlockNumber(2), &second_block_txs).await?;
+        drop(storage);
+        let [tx1, tx2] = first_block_txs.map(|tx| tx.hash);
+        let [tx3] = second_block_txs.map(|tx| tx.hash);
+
+        // Block range filtering
+        let traces = client.trace_filter(Self::block_filter(1, 2)).await?;
+        assert_eq!(traces.len(), 9);
+        let traces = client.trace_filter(Self::block_filter(2, 2)).await?;
+        assert_eq!(
+            Self::trace_ids(&traces),
+            [(tx3, vec![]), (tx3, vec![0]), (tx3, vec![1])]
+        );
+        // The default block range is the latest block
+        let traces = client.trace_filter(TraceFilter::default()).await?;
+        assert_eq!(traces.len(), 3);
+        assert!(traces.iter().all(|trace| trace.transaction_hash == tx3));
+        let traces = client.trace_filter(Self::block_filter(2, 1)).await?;
+        assert!(traces.is_empty(), "{traces:?}");
+
+        // `fromAddress` filter; addresses in the filter are OR-ed
+        let filter = TraceFilter {
+            from_address: Some(vec![Address::repeat_byte(1), Address::repeat_byte(3)]),
+            ..Self::block_filter(1, 2)
+        };
+        let traces = client.trace_filter(filter).await?;
+        assert_eq!(Self::trace_ids(&traces), [(tx1, vec![0]), (tx3, vec![0])]);
+
+        let filter = TraceFilter {
+            from_address: Some(vec![Address::zero()]),
+            ..Self::block_filter(1, 2)
+        };
+        let traces = client.trace_filter(filter).await?;
+        assert_eq!(
+            Self::trace_ids(&traces),
+            [(tx1, vec![]), (tx2, vec![]), (tx3, vec![])]
+        );
+
+        // `toAddress` filter
+        let filter = TraceFilter {
+            to_address: Some(vec![Address::repeat_byte(3)]),
+            ..Self::block_filter(1, 2)
+        };
+        let traces = client.trace_filter(filter).await?;
+        assert_eq!(Self::trace_ids(&traces), [(tx2, vec![0])]);
+        assert_eq!(traces[0].from(), Address::repeat_byte(2));
+
+        let filter = TraceFilter {
+            to_address: Some(vec![BOOTLOADER_ADDRESS, Address::repeat_byte(0xab - 2)]),
+            ..Self::block_filter(1, 2)
+        };
+        let traces = client.trace_filter(filter).await?;
+        assert_eq!(
+            Self::trace_ids(&traces),
+            [(tx1, vec![]), (tx2, vec![]), (tx2, vec![1]), (tx3, vec![])]
+        );
+
+        // `fromAddress` and `toAddress` filters must match simultaneously
+        let filter = TraceFilter {
+            from_address: Some(vec![Address::repeat_byte(2)]),
+            to_address: Some(vec![Address::repeat_byte(3)]),
+            ..Self::block_filter(1, 2)
+        };
+        let traces = client.trace_filter(filter).await?;
+        assert_eq!(Self::trace_ids(&traces), [(tx2, vec![0])]);
+
+        let filter = TraceFilter {
+            from_address: Some(vec![Address::repeat_byte(1)]),
+            to_address: Some(vec![Address::repeat_byte(3)]),
+            ..Self::block_filter(1, 2)
+        };
+        let traces = client.trace_filter(filter).await?;
+        assert!(traces.is_empty(), "{traces:?}");
+
+        // Pagination is applied after address filtering
+        let filter = TraceFilter {
+            from_address: Some(vec![Address::zero()]),
+            after: Some(1),
+            count: Some(1),
+            ..Self::block_filter(1, 2)
+        };
+        let traces = client.trace_filter(filter).await?;
+        assert_eq!(Self::trace_ids(&traces), [(tx2, vec![])]);
+
+        // Pagination spans block boundaries
+        let filter = TraceFilter {
+            after: Some(5),
+            count: Some(2),
+            ..Self::block_filter(1, 2)
+        };
+        let traces = client.trace_filter(filter).await?;
+        assert_eq!(Self::trace_ids(&traces), [(tx2, vec![1]), (tx3, vec![])]);
+        let filter = TraceFilter {
+            after: Some(9),
+            ..Self::block_filter(1, 2)
+        };
+        let traces = client.trace_filter(filter).await?;
+        assert!(traces.is_empty(), "{traces:?}");
+
+        // Block range limit
+        let error = client
+            .trace_filter(Self::block_filter(0, 2))
+            .await
+            .unwrap_err();
+        assert_invalid_params_error(error, "block range is too large");
+        let filter = TraceFilter {
+            from_block: Some(0_u32.into()),
+            ..TraceFilter::default()
+        };
+        let error = client.trace_filter(filter).await.unwrap_err();
+        assert_invalid_params_error(error, "block range is too large");
+
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn filtering_traces() {
+    test_http_server(TraceFilterTest).await;
+}
@@ -8,7 +8,14 @@ use http::StatusCode;
 use tokio::sync::watch;
 use zksync_config::configs::chain::NetworkConfig;
 use zksync_dal::ConnectionPool;
-use zksync_types::{api, Address, Bloom, L1BatchNumber, H160, H256, U64};
+use zksync_multivm::interface::{ExecutionResult, VmEvent, VmExecutionResultAndLogs};
+use zksync_state_keeper::{
+    testonly::create_updates_manager, StateKeeperOutputHandler, UpdatesManager,
+};
+use zksync_types::{
+    aggregated_operations::AggregatedActionType, api, Address, Bloom, L1BatchNumber, L2ChainId,
+    H160, H256, U64,
+};
 use zksync_web3_decl::{
     client::{WsClient, L2},
     jsonrpsee::{
@@ -20,13 +27,16 @@ use zksync_web3_decl::{
         rpc_params,
     },
     namespaces::{EthNamespaceClient, ZksNamespaceClient},
-    types::{BlockHeader, Bytes, PubSubFilter},
+    types::{
+        BlockHeader, Bytes, L1BatchEvent, L1BatchStage, PreconfirmationStatus, PubSubFilter,
+        PubSubResult,
+    },
 };
 
 use super::*;
 use crate::web3::metrics::SubscriptionType;
 
-async fn wait_for_subscription(
+pub(super) async fn wait_for_subscription(
     events: &mut mpsc::UnboundedReceiver<PubSubEvent>,
     sub_type: SubscriptionType,
 ) {
@@ -48,7 +58,7 @@ async fn wait_for_subscription(
         .expect("Timed out waiting for subscription")
 }
 
-async fn wait_for_notifiers(
+pub(super) async fn wait_for_notifiers(
     events: &mut mpsc::UnboundedReceiver<PubSubEvent>,
     sub_types: &[SubscriptionType],
 ) {
@@ -111,8 +121,12 @@ async fn notifiers_start_after_snapshot_recovery() {
     let (events_sender, mut events_receiver) = mpsc::unbounded_channel();
     let mut subscribe_logic = EthSubscribe::new();
     subscribe_logic.set_events_sender(events_sender);
-    let notifier_handles =
-        subscribe_logic.spawn_notifiers(pool.clone(), POLL_INTERVAL, stop_receiver);
+    let notifier_handles = subscribe_logic.spawn_notifiers(
+        pool.clone(),
+        L2ChainId::default(),
+        POLL_INTERVAL,
+        stop_receiver,
+    );
     assert!(!notifier_handles.is_empty());
 
     // Wait a little doing nothing and check that notifier tasks are still active (i.e., have not panicked).
@@ -132,7 +146,9 @@ async fn notifiers_start_after_snapshot_recovery() {
         &[
             SubscriptionType::Blocks,
             SubscriptionType::Txs,
+            SubscriptionType::FullTxs,
             SubscriptionType::Logs,
+            SubscriptionType::L1Batches,
         ],
     )
     .await;
@@ -348,6 +364,166 @@ async fn basic_subscriptions_after_snapshot_recovery() {
     .await;
 }
 
+#[derive(Debug)]
+struct FullPendingTransactionsTest;
+
+#[async_trait]
+impl WsTest for FullPendingTransactionsTest {
+    async fn test(
+        &self,
+        client: &WsClient<L2>,
+        pool: &ConnectionPool<Core>,
+        mut pub_sub_events: mpsc::UnboundedReceiver<PubSubEvent>,
+    ) -> anyhow::Result<()> {
+        wait_for_notifiers(&mut pub_sub_events, &[SubscriptionType::FullTxs]).await;
+
+        // Check both geth-style and object params.
+        let params = rpc_params!["newPendingTransactions", true];
+        let mut txs_subscription = client
+            .subscribe::<api::Transaction, _>("eth_subscribe", params, "eth_unsubscribe")
+            .await?;
+        wait_for_subscription(&mut pub_sub_events, SubscriptionType::FullTxs).await;
+        let params = rpc_params![
+            "newPendingTransactions",
+            serde_json::json!({ "fullTransactions": true })
+        ];
+        let mut other_txs_subscription = client
+            .subscribe::<api::Transaction, _>("eth_subscribe", params, "eth_unsubscribe")
+            .await?;
+        wait_for_subscription(&mut pub_sub_events, SubscriptionType::FullTxs).await;
+
+        // Pending transactions params are not a valid logs filter.
+        let params = rpc_params!["logs", true];
+        let err = client
+            .subscribe::<api::Log, _>("eth_subscribe", params, "eth_unsubscribe")
+            .await
+            .unwrap_err();
+        assert_matches!(
+            err,
+            ClientError::Call(err) if err.code() == ErrorCode::InvalidParams.code()
+        );
+
+        let mut storage = pool.connection().await?;
+        let tx_results: Vec<_> = (1..=3)
+            .map(|fee_per_gas| execute_l2_transaction(create_l2_transaction(fee_per_gas, 2)))
+            .collect();
+        let tx_hashes: HashSet<_> = tx_results.iter().map(|result| result.hash).collect();
+        store_l2_block(&mut storage, L2BlockNumber(1), &tx_results).await?;
+        drop(storage);
+
+        for subscription in [&mut txs_subscription, &mut other_txs_subscription] {
+            let mut received_txs = vec![];
+            while received_txs.len() < tx_hashes.len() {
+                let tx = tokio::time::timeout(TEST_TIMEOUT, subscription.next())
+                    .await
+                    .context("Timed out waiting for new tx")?
+                    .context("Pending txs subscription terminated")??;
+                received_txs.push(tx);
+            }
+            let received_hashes: HashSet<_> = received_txs.iter().map(|tx| tx.hash).collect();
+            assert_eq!(received_hashes, tx_hashes);
+            for tx in &received_txs {
+                assert_eq!(tx.chain_id, L2ChainId::default().as_u64().into());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn full_pending_transactions_subscription() {
+    test_ws_server(FullPendingTransactionsTest).await;
+}
+
+#[derive(Debug)]
+struct L1BatchSubscriptionsTest;
+
+impl L1BatchSubscriptionsTest {
+    async fn next_event(
+        subscription: &mut Subscription<L1BatchEvent>,
+    ) -> anyhow::Result<L1BatchEvent> {
+        tokio::time::timeout(TEST_TIMEOUT, subscription.next())
+            .await
+            .context("Timed out waiting for L1 batch event")?
+            .context("L1 batches subscription terminated")?
+            .map_err(Into::into)
+    }
+}
+
+#[async_trait]
+impl WsTest for L1BatchSubscriptionsTest {
+    async fn test(
+        &self,
+        client: &WsClient<L2>,
+        pool: &ConnectionPool<Core>,
+        mut pub_sub_events: mpsc::UnboundedReceiver<PubSubEvent>,
+    ) -> anyhow::Result<()> {
+        wait_for_notifiers(&mut pub_sub_events, &[SubscriptionType::L1Batches]).await;
+
+        let params = rpc_params!["l1Batches"];
+        let mut subscription = client
+            .subscribe::<L1BatchEvent, _>("eth_subscribe", params, "eth_unsubscribe")
+            .await?;
+        wait_for_subscription(&mut pub_sub_events, SubscriptionType::L1Batches).await;
+        // The notifier doesn't poll Postgres without subscribers, so wait until it picks up the current state.
+        wait_for_notifiers(&mut pub_sub_events, &[SubscriptionType::L1Batches]).await;
+
+        let mut storage = pool.connection().await?;
+        store_l2_block(&mut storage, L2BlockNumber(1), &[]).await?;
+        seal_l1_batch(&mut storage, L1BatchNumber(1)).await?;
+
+        let event = Self::next_event(&mut subscription).await?;
+        assert_eq!(event.l1_batch_number, 1.into());
+        assert_eq!(event.stage, L1BatchStage::Sealed);
+        assert_eq!(event.l1_tx_hash, None);
+
+        // Commit the batch on L1; the event should only be emitted once the L1 tx is confirmed.
+        let eth_tx = storage
+            .eth_sender_dal()
+            .save_eth_tx(
+                0,
+                vec![],
+                AggregatedActionType::Commit,
+                Address::default(),
+                0,
+                None,
+                None,
+                false,
+            )
+            .await?;
+        let eth_tx_hash = H256::repeat_byte(0x23);
+        storage
+            .eth_sender_dal()
+            .insert_tx_history(eth_tx.id, 0, 0, None, eth_tx_hash, &[], 0)
+            .await?;
+        storage
+            .blocks_dal()
+            .set_eth_tx_id(
+                L1BatchNumber(1)..=L1BatchNumber(1),
+                eth_tx.id,
+                AggregatedActionType::Commit,
+            )
+            .await?;
+        wait_for_notifiers(&mut pub_sub_events, &[SubscriptionType::L1Batches]).await;
+        storage
+            .eth_sender_dal()
+            .confirm_tx(eth_tx_hash, U256::zero())
+            .await?;
+        drop(storage);
+
+        let event = Self::next_event(&mut subscription).await?;
+        assert_eq!(event.l1_batch_number, 1.into());
+        assert_eq!(event.stage, L1BatchStage::Committed);
+        assert_eq!(event.l1_tx_hash, Some(eth_tx_hash));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn l1_batch_subscriptions() {
+    test_ws_server(L1BatchSubscriptionsTest).await;
+}
+
 #[derive(Debug)]
 struct LogSubscriptionsTest {
     snapshot_recovery: bool,
@@ -739,3 +915,82 @@ impl WsTest for BatchGetsRateLimitedTest {
 async fn batch_rate_limiting() {
     test_ws_server(BatchGetsRateLimitedTest).await;
 }
+
+fn execute_tx(updates_manager: &mut UpdatesManager, tx_index_in_batch: u32) -> H256 {
+    let tx = create_l2_transaction(10, 100);
+    let tx_hash = tx.hash();
+    let event = VmEvent {
+        location: (L1BatchNumber(1), tx_index_in_batch),
+        address: Address::repeat_byte(1),
+        indexed_topics: vec![H256::repeat_byte(2)],
+        value: vec![3; 32],
+    };
+    let mut execution_result = VmExecutionResultAndLogs {
+        result: ExecutionResult::Success { output: vec![] },
+        logs: Default::default(),
+        statistics: Default::default(),
+        refunds: Default::default(),
+    };
+    execution_result.logs.events = vec![event];
+    updates_manager.extend_from_executed_transaction(
+        tx.into(),
+        execution_result,
+        vec![],
+        Default::default(),
+        Default::default(),
+        vec![],
+    );
+    tx_hash
+}
+
+#[tokio::test]
+async fn preconfirmations_are_published_and_retracted() {
+    let preconfirmations = Preconfirmations::default();
+    let mut publisher = preconfirmations.publisher();
+    let mut preconfirmations_rx = preconfirmations.subscribe();
+    let mut updates_manager = create_updates_manager();
+
+    let mut tx_hashes = vec![];
+    for i in 0..2 {
+        tx_hashes.push(execute_tx(&mut updates_manager, i));
+        publisher
+            .handle_executed_tx(&updates_manager)
+            .await
+            .unwrap();
+
+        let results = preconfirmations_rx.try_recv().unwrap();
+        let [PubSubResult::Preconfirmation(preconfirmation)] = results.as_slice() else {
+            panic!("Unexpected results: {results:?}");
+        };
+        assert_eq!(preconfirmation.transaction_hash, tx_hashes[i as usize]);
+        assert_eq!(preconfirmation.status, PreconfirmationStatus::Preconfirmed);
+        assert_eq!(preconfirmation.transaction_index, U64::from(i));
+        let receipt = preconfirmation.receipt.as_ref().unwrap();
+        assert_eq!(receipt.execution_status, U64::one());
+        // Only the event emitted by the transaction itself must be included.
+        assert_eq!(receipt.logs.len(), 1);
+        assert_eq!(receipt.logs[0].log_index, Some(i.into()));
+        assert_eq!(
+            receipt.logs[0].transaction_hash,
+            Some(tx_hashes[i as usize])
+        );
+    }
+
+    publisher
+        .handle_l2_block_rollback(&updates_manager)
+        .await
+        .unwrap();
+    let results = preconfirmations_rx.try_recv().unwrap();
+    let retracted_hashes: Vec<_> = results
+        .iter()
+        .map(|result| match result {
+            PubSubResult::Preconfirmation(preconfirmation) => {
+                assert_eq!(preconfirmation.status, PreconfirmationStatus::Retracted);
+                assert!(preconfirmation.receipt.is_none());
+                preconfirmation.transaction_hash
+            }
+            _ => panic!("Unexpected result: {result:?}"),
+        })
+        .collect();
+    assert_eq!(retracted_hashes, tx_hashes);
+}
@@ -34,7 +34,7 @@ fn not_found() -> Response<String> {
 
 impl RestApi {
     #[tracing::instrument(skip(query))]
-    fn validate_contract_verification_query(
+    pub(super) fn validate_contract_verification_query(
         query: &VerificationIncomingRequest,
     ) -> Result<(), Response<String>> {
         if query.source_code_data.compiler_type() != query.compiler_versions.compiler_type() {
@@ -0,0 +1,570 @@
+//! Etherscan-compatible facade for the contract verification API.
+//!
+//! Implements the subset of the [Etherscan contracts API](https://docs.etherscan.io/api-endpoints/contracts)
+//! used by Foundry and Hardhat verification plugins: `verifysourcecode`, `checkverifystatus`, `getsourcecode`
+//! and `getabi` actions of the `contract` module. Requests are mapped onto the native verification requests,
+//! so they are processed by the contract verifier in the same way.
+
+use std::{collections::HashMap, sync::Arc};
+
+use axum::{
+    extract::{Query, State},
+    response::Response,
+    Form,
+};
+use serde::Serialize;
+use zksync_dal::CoreDal;
+use zksync_types::{
+    contract_verification_api::{
+        CompilerVersions, SourceCodeData, VerificationIncomingRequest, VerificationInfo,
+        VerificationRequestStatus,
+    },
+    web3::Bytes,
+    Address,
+};
+
+use super::{api_decl::RestApi, metrics::METRICS};
+
+const PENDING_STATUS: &str = "Pending in queue";
+const VERIFIED_STATUS: &str = "Pass - Verified";
+const FAILED_STATUS: &str = "Fail - Unable to verify";
+const NOT_VERIFIED_MESSAGE: &str = "Contract source code not verified";
+
+/// Response envelope used by the Etherscan API. Errors are returned with the 200 HTTP status code
+/// and are distinguished by the `status` field.
+#[derive(Debug, Serialize)]
+pub(super) struct EtherscanResponse {
+    status: &'static str,
+    message: &'static str,
+    result: serde_json::Value,
+}
+
+impl EtherscanResponse {
+    fn ok(result: impl Into<serde_json::Value>) -> Self {
+        Self {
+            status: "1",
+            message: "OK",
+            result: result.into(),
+        }
+    }
+
+    fn error(result: impl Into<String>) -> Self {
+        Self {
+            status: "0",
+            message: "NOTOK",
+            result: serde_json::Value::String(result.into()),
+        }
+    }
+
+    fn into_response(self) -> Response<String> {
+        Response::builder()
+            .status(axum::http::StatusCode::OK)
+            .header(axum::http::header::CONTENT_TYPE, "application/json")
+            .body(serde_json::to_string(&self).expect("Failed to serialize"))
+            .unwrap()
+    }
+}
+
+/// Supported Etherscan API actions.
+#[derive(Debug)]
+enum EtherscanAction {
+    VerifySourceCode(Box<VerificationIncomingRequest>),
+    CheckVerifyStatus(usize),
+    GetSourceCode(Address),
+    GetAbi(Address),
+}
+
+/// Etherscan request parameters. Parameter names are case-insensitive.
+#[derive(Debug, Default)]
+struct EtherscanParams(HashMap<String, String>);
+
+impl EtherscanParams {
+    fn extend(&mut self, params: HashMap<String, String>) {
+        self.0.extend(
+            params
+                .into_iter()
+                .map(|(name, value)| (name.to_ascii_lowercase(), value)),
+        );
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.0
+            .get(name)
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+    }
+
+    fn required(&self, name: &str) -> Result<&str, String> {
+        self.get(name)
+            .ok_or_else(|| format!("Missing or empty `{name}` parameter"))
+    }
+
+    fn flag(&self, name: &str) -> Result<bool, String> {
+        match self.get(name) {
+            None | Some("0" | "false") => Ok(false),
+            Some("1" | "true") => Ok(true),
+            Some(value) => Err(format!("Invalid `{name}` parameter: {value:?}")),
+        }
+    }
+
+    fn address(&self, name: &str) -> Result<Address, String> {
+        let value = self.required(name)?;
+        value
+            .parse()
+            .map_err(|_| format!("Invalid `{name}` parameter: {value:?}"))
+    }
+
+    fn parse_action(&self) -> Result<EtherscanAction, String> {
+        let module = self.required("module")?;
+        if module != "contract" {
+            return Err(format!("Unsupported module: {module:?}"));
+        }
+        Ok(match self.required("action")? {
+            "verifysourcecode" => {
+                EtherscanAction::VerifySourceCode(Box::new(self.verification_request()?))
+            }
+            "checkverifystatus" => {
+                let guid = self.required("guid")?;
+                let id = guid
+                    .parse()
+                    .map_err(|_| format!("Invalid `guid` parameter: {guid:?}"))?;
+                EtherscanAction::CheckVerifyStatus(id)
+            }
+            "getsourcecode" => EtherscanAction::GetSourceCode(self.address("address")?),
+            "getabi" => EtherscanAction::GetAbi(self.address("address")?),
+            action => return Err(format!("Unsupported action: {action:?}")),
+        })
+    }
+
+    fn verification_request(&self) -> Result<VerificationIncomingRequest, String> {
+        let source_code = self.required("sourcecode")?;
+        let code_format = self.get("codeformat").unwrap_or("solidity-single-file");
+        let source_code_data = match code_format {
+            "solidity-single-file" => SourceCodeData::SolSingleFile(source_code.to_owned()),
+            "solidity-standard-json-input" => {
+                let input = serde_json::from_str(source_code)
+                    .map_err(|err| format!("Invalid standard JSON input: {err}"))?;
+                SourceCodeData::StandardJsonInput(input)
+            }
+            "vyper-json" => SourceCodeData::VyperMultiFile(parse_vyper_json_sources(source_code)?),
+            _ => return Err(format!("Unsupported `codeformat`: {code_format:?}")),
+        };
+
+        let compiler_version = normalize_compiler_version(self.required("compilerversion")?);
+        let compiler_versions = match &source_code_data {
+            SourceCodeData::VyperMultiFile(_) => CompilerVersions::Vyper {
+                compiler_zkvyper_version: normalize_zk_compiler_version(
+                    self.required("zkvyperversion")?,
+                ),
+                compiler_vyper_version: compiler_version,
+            },
+            _ => CompilerVersions::Solc {
+                compiler_zksolc_version: normalize_zk_compiler_version(
+                    self.required("zksolcversion")?,
+                ),
+                compiler_solc_version: compiler_version,
+            },
+        };
+
+        // Etherscan misspells the parameter name; we support both spellings.
+        let constructor_arguments = self
+            .get("constructorarguements")
+            .or_else(|| self.get("constructorarguments"))
+            .unwrap_or_default();
+        let constructor_arguments = hex::decode(constructor_arguments.trim_start_matches("0x"))
+            .map_err(|err| format!("Invalid constructor arguments: {err}"))?;
+
+        Ok(VerificationIncomingRequest {
+            contract_address: self.address("contractaddress")?,
+            source_code_data,
+            contract_name: self.required("contractname")?.to_owned(),
+            compiler_versions,
+            optimization_used: self.flag("optimizationused")?,
+            optimizer_mode: self.get("optimizermode").map(str::to_owned),
+            constructor_arguments: Bytes(constructor_arguments),
+            is_system: self.flag("issystem")? || self.flag("enableeravmextensions")?,
+            force_evmla: self.flag("forceevmla")?,
+        })
+    }
+}
+
+/// Extracts sources from the Vyper standard JSON input.
+fn parse_vyper_json_sources(input: &str) -> Result<HashMap<String, String>, String> {
+    #[derive(serde::Deserialize)]
+    struct VyperSource {
+        content: String,
+    }
+
+    #[derive(serde::Deserialize)]
+    struct VyperJsonInput {
+        sources: HashMap<String, VyperSource>,
+    }
+
+    let input: VyperJsonInput =
+        serde_json::from_str(input).map_err(|err| format!("Invalid Vyper JSON input: {err}"))?;
+    Ok(input
+        .sources
+        .into_iter()
+        .map(|(path, source)| (path, source.content))
+        .collect())
+}
+
+/// Converts a compiler version in the Etherscan format (e.g., `v0.8.24+commit.e11b9ed9`) to the format
+/// used by the contract verifier (e.g., `0.8.24`). Versions of EraVM forks (e.g., `zkVM-0.8.24-1.0.1`) are returned as is.
+fn normalize_compiler_version(version: &str) -> String {
+    let version = version.strip_prefix('v').unwrap_or(version);
+    match version.split_once('+') {
+        Some((version, _)) => version.to_owned(),
+        None => version.to_owned(),
+    }
+}
+
+/// Converts a ZK compiler version to the format used by the contract verifier (e.g., `v1.5.3`).
+fn normalize_zk_compiler_version(version: &str) -> String {
+    if version.starts_with('v') {
+        version.to_owned()
+    } else {
+        format!("v{version}")
+    }
+}
+
+fn verification_status_result(status: VerificationRequestStatus) -> EtherscanResponse {
+    match status.status.as_str() {
+        "successful" => EtherscanResponse::ok(VERIFIED_STATUS),
+        "failed" => {
+            let mut details = status.error.unwrap_or_default();
+            for compilation_error in status.compilation_errors.into_iter().flatten() {
+                if !details.is_empty() {
+                    details.push('\n');
+                }
+                details.push_str(&compilation_error);
+            }
+            if details.is_empty() {
+                EtherscanResponse::error(FAILED_STATUS)
+            } else {
+                EtherscanResponse::error(format!("{FAILED_STATUS}: {details}"))
+            }
+        }
+        _ => EtherscanResponse::error(PENDING_STATUS),
+    }
+}
+
+fn source_code_result(info: Option<VerificationInfo>) -> serde_json::Value {
+    let Some(info) = info else {
+        return serde_json::json!([{
+            "SourceCode": "",
+            "ABI": NOT_VERIFIED_MESSAGE,
+            "ContractName": "",
+            "CompilerVersion": "",
+            "ZkCompilerVersion": "",
+            "OptimizationUsed": "",
+            "OptimizerMode": "",
+            "ConstructorArguments": "",
+            "EVMVersion": "",
+            "Library": "",
+            "LicenseType": "",
+            "Proxy": "0",
+            "Implementation": "",
+            "SwarmSource": "",
+        }]);
+    };
+
+    let request = info.request.req;
+    let source_code = match request.source_code_data {
+        SourceCodeData::SolSingleFile(source) | SourceCodeData::YulSingleFile(source) => source,
+        // Etherscan wraps JSON inputs into double braces.
+        SourceCodeData::StandardJsonInput(input) => {
+            format!("{{{}}}", serde_json::Value::Object(input))
+        }
+        SourceCodeData::VyperMultiFile(sources) => {
+            let sources: serde_json::Map<_, _> = sources
+                .into_iter()
+                .map(|(path, content)| (path, serde_json::json!({ "content": content })))
+                .collect();
+            let input = serde_json::json!({ "language": "Vyper", "sources": sources });
+            format!("{{{input}}}")
+        }
+    };
+    serde_json::json!([{
+        "SourceCode": source_code,
+        "ABI": info.artifacts.abi.to_string(),
+        "ContractName": request.contract_name,
+        "CompilerVersion": request.compiler_versions.compiler_version(),
+        "ZkCompilerVersion": request.compiler_versions.zk_compiler_version(),
+        "OptimizationUsed": if request.optimization_used { "1" } else { "0" },
+        "OptimizerMode": request.optimizer_mode.unwrap_or_default(),
+        "ConstructorArguments": hex::encode(&request.constructor_arguments.0),
+        "EVMVersion": "Default",
+        "Library": "",
+        "LicenseType": "",
+        "Proxy": "0",
+        "Implementation": "",
+        "SwarmSource": "",
+    }])
+}
+
+impl RestApi {
+    /// Etherscan-compatible endpoint accepting parameters in the query string.
+    #[tracing::instrument(skip(self_, params))]
+    pub async fn etherscan_get(
+        State(self_): State<Arc<Self>>,
+        Query(params): Query<HashMap<String, String>>,
+    ) -> Response<String> {
+        let mut etherscan_params = EtherscanParams::default();
+        etherscan_params.extend(params);
+        self_.etherscan(etherscan_params).await.into_response()
+    }
+
+    /// Etherscan-compatible endpoint accepting parameters in the query string and / or the URL-encoded form body.
+    #[tracing::instrument(skip(self_, query_params, form_params))]
+    pub async fn etherscan_post(
+        State(self_): State<Arc<Self>>,
+        Query(query_params): Query<HashMap<String, String>>,
+        Form(form_params): Form<HashMap<String, String>>,
+    ) -> Response<String> {
+        let mut etherscan_params = EtherscanParams::default();
+        etherscan_params.extend(query_params);
+        etherscan_params.extend(form_params);
+        self_.etherscan(etherscan_params).await.into_response()
+    }
+
+    async fn etherscan(&self, params: EtherscanParams) -> EtherscanResponse {
+        let action = match params.parse_action() {
+            Ok(action) => action,
+            Err(err) => return EtherscanResponse::error(err),
+        };
+        match action {
+            EtherscanAction::VerifySourceCode(request) => {
+                let method_latency = METRICS.call[&"etherscan_verifysourcecode"].start();
+                let response = self.etherscan_verify_source_code(*request).await;
+                method_latency.observe();
+                response
+            }
+            EtherscanAction::CheckVerifyStatus(id) => {
+                let method_latency = METRICS.call[&"etherscan_checkverifystatus"].start();
+                let status = self
+                    .replica_connection_pool
+                    .connection_tagged("api")
+                    .await
+                    .unwrap()
+                    .contract_verification_dal()
+                    .get_verification_request_status(id)
+                    .await
+                    .unwrap();
+                method_latency.observe();
+                match status {
+                    Some(status) => verification_status_result(status),
+                    None => EtherscanResponse::error(format!("Unknown GUID: {id}")),
+                }
+            }
+            EtherscanAction::GetSourceCode(address) => {
+                let method_latency = METRICS.call[&"etherscan_getsourcecode"].start();
+                let info = self.etherscan_verification_info(address).await;
+                method_latency.observe();
+                EtherscanResponse::ok(source_code_result(info))
+            }
+            EtherscanAction::GetAbi(address) => {
+                let method_latency = METRICS.call[&"etherscan_getabi"].start();
+                let info = self.etherscan_verification_info(address).await;
+                method_latency.observe();
+                match info {
+                    Some(info) => EtherscanResponse::ok(info.artifacts.abi.to_string()),
+                    None => EtherscanResponse::error(NOT_VERIFIED_MESSAGE),
+                }
+            }
+        }
+    }
+
+    async fn etherscan_verify_source_code(
+        &self,
+        request: VerificationIncomingRequest,
+    ) -> EtherscanResponse {
+        if let Err(err) = Self::validate_contract_verification_query(&request) {
+            return EtherscanResponse::error(err.into_body());
+        }
+
+        let mut storage = self
+            .master_connection_pool
+            .connection_tagged("api")
+            .await
+            .unwrap();
+        if !storage
+            .storage_logs_dal()
+            .is_contract_deployed_at_address(request.contract_address)
+            .await
+        {
+            return EtherscanResponse::error("There is no deployed contract on this address");
+        }
+        if storage
+            .contract_verification_dal()
+            .is_contract_verified(request.contract_address)
+            .await
+            .unwrap()
+        {
+            return EtherscanResponse::error("Contract source code already verified");
+        }
+
+        let request_id = storage
+            .contract_verification_dal()
+            .add_contract_verification_request(request)
+            .await
+            .unwrap();
+        EtherscanResponse::ok(request_id.to_string())
+    }
+
+    async fn etherscan_verification_info(&self, address: Address) -> Option<VerificationInfo> {
+        self.replica_connection_pool
+            .connection_tagged("api")
+            .await
+            .unwrap()
+            .contract_verification_dal()
+            .get_contract_verification_info(address)
+            .await
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(pairs: &[(&str, &str)]) -> EtherscanParams {
+        let mut params = EtherscanParams::default();
+        params.extend(
+            pairs
+                .iter()
+                .map(|&(name, value)| (name.to_owned(), value.to_owned()))
+                .collect(),
+        );
+        params
+    }
+
+    #[test]
+    fn parsing_verify_source_code_request() {
+        let params = params(&[
+            ("module", "contract"),
+            ("action", "verifysourcecode"),
+            (
+                "contractaddress",
+                "0x0000000000000000000000000000000000010000",
+            ),
+            ("sourceCode", r#"{"language":"Solidity","sources":{}}"#),
+            ("codeformat", "solidity-standard-json-input"),
+            ("contractname", "contracts/Counter.sol:Counter"),
+            ("compilerversion", "v0.8.24+commit.e11b9ed9"),
+            ("zksolcVersion", "1.5.3"),
+            ("optimizationUsed", "1"),
+            ("constructorArguements", "0102"),
+        ]);
+        let EtherscanAction::VerifySourceCode(request) = params.parse_action().unwrap() else {
+            panic!("unexpected action");
+        };
+
+        assert_eq!(request.contract_address, Address::from_low_u64_be(0x10000));
+        assert!(matches!(
+            request.source_code_data,
+            SourceCodeData::StandardJsonInput(_)
+        ));
+        assert_eq!(request.contract_name, "contracts/Counter.sol:Counter");
+        assert_eq!(request.compiler_versions.compiler_version(), "0.8.24");
+        assert_eq!(request.compiler_versions.zk_compiler_version(), "v1.5.3");
+        assert!(request.optimization_used);
+        assert_eq!(request.constructor_arguments.0, [1, 2]);
+        assert!(!request.is_system);
+    }
+
+    #[test]
+    fn parsing_vyper_verification_request() {
+        let params = params(&[
+            ("module", "contract"),
+            ("action", "verifysourcecode"),
+            (
+                "contractaddress",
+                "0x0000000000000000000000000000000000010000",
+            ),
+            (
+                "sourceCode",
+                r#"{"language":"Vyper","sources":{"Counter.vy":{"content":"x: uint256"}}}"#,
+            ),
+            ("codeformat", "vyper-json"),
+            ("contractname", "Counter"),
+            ("compilerversion", "0.3.10"),
+            ("zkvyperVersion", "v1.5.4"),
+        ]);
+        let EtherscanAction::VerifySourceCode(request) = params.parse_action().unwrap() else {
+            panic!("unexpected action");
+        };
+
+        let SourceCodeData::VyperMultiFile(sources) = &request.source_code_data else {
+            panic!(
+                "unexpected source code data: {:?}",
+                request.source_code_data
+            );
+        };
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources["Counter.vy"], "x: uint256");
+        assert!(matches!(
+            request.compiler_versions,
+            CompilerVersions::Vyper { .. }
+        ));
+        assert_eq!(request.compiler_versions.zk_compiler_version(), "v1.5.4");
+    }
+
+    #[test]
+    fn parsing_other_actions() {
+        let action = params(&[
+            ("module", "contract"),
+            ("action", "checkverifystatus"),
+            ("guid", "42"),
+        ])
+        .parse_action()
+        .unwrap();
+        assert!(matches!(action, EtherscanAction::CheckVerifyStatus(42)));
+
+        let action = params(&[
+            ("module", "contract"),
+            ("action", "getabi"),
+            ("address", "0x0000000000000000000000000000000000010000"),
+        ])
+        .parse_action()
+        .unwrap();
+        assert!(matches!(action, EtherscanAction::GetAbi(_)));
+
+        let err = params(&[("module", "account"), ("action", "balance")])
+            .parse_action()
+            .unwrap_err();
+        assert!(err.contains("Unsupported module"), "{err}");
+        let err = params(&[("module", "contract"), ("action", "verifysourcecode")])
+            .parse_action()
+            .unwrap_err();
+        assert!(err.contains("sourcecode"), "{err}");
+    }
+
+    #[test]
+    fn mapping_verification_status() {
+        let status = |status: &str| VerificationRequestStatus {
+            status: status.to_owned(),
+            error: None,
+            compilation_errors: None,
+        };
+
+        let response = verification_status_result(status("queued"));
+        assert_eq!(response.status, "0");
+        assert_eq!(response.result, PENDING_STATUS);
+        let response = verification_status_result(status("in_progress"));
+        assert_eq!(response.result, PENDING_STATUS);
+        let response = verification_status_result(status("successful"));
+        assert_eq!(response.status, "1");
+        assert_eq!(response.result, VERIFIED_STATUS);
+
+        let response = verification_status_result(VerificationRequestStatus {
+            error: Some("Compilation error".to_owned()),
+            ..status("failed")
+        });
+        assert_eq!(response.status, "0");
+        assert_eq!(
+            response.result,
+            format!("{FAILED_STATUS}: Compilation error")
+        );
+    }
+}
@@ -7,6 +7,7 @@ use self::api_decl::RestApi;
 
 mod api_decl;
 mod api_impl;
+mod etherscan;
 mod metrics;
 
 pub async fn start_server(
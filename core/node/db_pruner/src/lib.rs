@@ -8,7 +8,10 @@ use std::{
 use anyhow::Context as _;
 use serde::{Deserialize, Serialize};
 use tokio::sync::watch;
-use zksync_dal::{pruning_dal::PruningInfo, Connection, ConnectionPool, Core, CoreDal};
+use zksync_dal::{
+    pruning_dal::{PruningInfo, RetentionPolicy},
+    Connection, ConnectionPool, Core, CoreDal,
+};
 use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_types::{L1BatchNumber, L2BlockNumber};
 
@@ -36,6 +39,8 @@ pub struct DbPrunerConfig {
     /// Minimum age of an L1 batch in order for it to be eligible for pruning. Setting this to zero
     /// will effectively disable this pruning criterion.
     pub minimum_l1_batch_age: Duration,
+    /// Policy specifying data that should be kept when hard-pruning L1 batches.
+    pub retention_policy: RetentionPolicy,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -244,9 +249,10 @@ impl DbPruner {
 
         let mut dal = transaction.pruning_dal();
         let stats = tokio::select! {
-            result = dal.hard_prune_batches_range(
+            result = dal.hard_prune_batches_range_with_retention(
                 last_soft_pruned_l1_batch,
                 last_soft_pruned_l2_block,
+                &self.config.retention_policy,
             ) => result?,
 
             _ = stop_receiver.changed() => {
@@ -79,6 +79,7 @@ async fn is_l1_batch_prunable_works() {
             removal_delay: Duration::ZERO,
             pruned_batch_chunk_size: 1,
             minimum_l1_batch_age: Duration::ZERO,
+            retention_policy: RetentionPolicy::default(),
         },
         ConnectionPool::test_pool().await,
         vec![failing_check, other_failing_check],
@@ -153,6 +154,7 @@ async fn hard_pruning_ignores_conditions_checks() {
             removal_delay: Duration::ZERO,
             pruned_batch_chunk_size: 5,
             minimum_l1_batch_age: Duration::ZERO,
+            retention_policy: RetentionPolicy::default(),
         },
         pool.clone(),
         vec![nothing_prunable_check],
@@ -192,6 +194,7 @@ async fn pruner_catches_up_with_hard_pruning_up_to_soft_pruning_boundary_ignorin
             removal_delay: Duration::ZERO,
             pruned_batch_chunk_size: 5,
             minimum_l1_batch_age: Duration::ZERO,
+            retention_policy: RetentionPolicy::default(),
         },
         pool.clone(),
         vec![], //No checks, so every batch is prunable
@@ -240,6 +243,7 @@ async fn unconstrained_pruner_with_fresh_database() {
             removal_delay: Duration::ZERO,
             pruned_batch_chunk_size: 3,
             minimum_l1_batch_age: Duration::ZERO,
+            retention_policy: RetentionPolicy::default(),
         },
         pool.clone(),
         vec![], //No checks, so every batch is prunable
@@ -290,6 +294,7 @@ async fn pruning_blocked_after_first_chunk() {
             removal_delay: Duration::ZERO,
             pruned_batch_chunk_size: 3,
             minimum_l1_batch_age: Duration::ZERO,
+            retention_policy: RetentionPolicy::default(),
         },
         pool.clone(),
         vec![first_chunk_prunable_check],
@@ -340,6 +345,7 @@ async fn pruner_is_resistant_to_errors() {
             removal_delay: Duration::ZERO,
             pruned_batch_chunk_size: 3,
             minimum_l1_batch_age: Duration::ZERO,
+            retention_policy: RetentionPolicy::default(),
         },
         pool.clone(),
         vec![erroneous_condition],
@@ -509,6 +515,7 @@ async fn pruner_with_real_conditions() {
         removal_delay: Duration::from_millis(10), // non-zero to not have a tight loop in `DbPruner::run()`
         pruned_batch_chunk_size: 1,
         minimum_l1_batch_age: Duration::ZERO,
+        retention_policy: RetentionPolicy::default(),
     };
     let pruner = DbPruner::new(config, pool.clone());
     let mut health_check = pruner.health_check();
@@ -565,6 +572,7 @@ async fn pruning_iteration_timely_shuts_down() {
             removal_delay: Duration::MAX, // intentionally chosen so that pruning iterations stuck
             pruned_batch_chunk_size: 3,
             minimum_l1_batch_age: Duration::ZERO,
+            retention_policy: RetentionPolicy::default(),
         },
         pool.clone(),
         vec![], //No checks, so every batch is prunable
@@ -594,6 +602,7 @@ async fn pruner_timely_shuts_down() {
             removal_delay: Duration::MAX, // intentionally chosen so that pruning iterations stuck
             pruned_batch_chunk_size: 3,
             minimum_l1_batch_age: Duration::ZERO,
+            retention_policy: RetentionPolicy::default(),
         },
         pool.clone(),
         vec![], //No checks, so every batch is prunable
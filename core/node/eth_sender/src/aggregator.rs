@@ -12,7 +12,7 @@ use zksync_types::{
     helpers::unix_timestamp_ms,
     protocol_version::{L1VerifierConfig, ProtocolSemanticVersion},
     pubdata_da::PubdataDA,
-    L1BatchNumber, ProtocolVersionId,
+    L1BatchNumber, L1BlockNumber, ProtocolVersionId,
 };
 
 use super::{
@@ -112,12 +112,17 @@ impl Aggregator {
         }
     }
 
+    /// Returns the next operation to send to L1, if any.
+    ///
+    /// If `last_finalized_l1_block` is set, only L1 batches in which all priority operations originate
+    /// from L1 blocks up to (and including) this block can be committed.
     pub async fn get_next_ready_operation(
         &mut self,
         storage: &mut Connection<'_, Core>,
         base_system_contracts_hashes: BaseSystemContractsHashes,
         protocol_version_id: ProtocolVersionId,
         l1_verifier_config: L1VerifierConfig,
+        last_finalized_l1_block: Option<L1BlockNumber>,
     ) -> Option<AggregatedOperation> {
         let Some(last_sealed_l1_batch_number) = storage
             .blocks_dal()
@@ -154,6 +159,7 @@ impl Aggregator {
                 last_sealed_l1_batch_number,
                 base_system_contracts_hashes,
                 protocol_version_id,
+                last_finalized_l1_block,
             )
             .await
         }
@@ -192,6 +198,7 @@ impl Aggregator {
         last_sealed_batch: L1BatchNumber,
         base_system_contracts_hashes: BaseSystemContractsHashes,
         protocol_version_id: ProtocolVersionId,
+        last_finalized_l1_block: Option<L1BlockNumber>,
     ) -> Option<AggregatedOperation> {
         let mut blocks_dal = storage.blocks_dal();
         let last_committed_l1_batch = blocks_dal
@@ -199,7 +206,7 @@ impl Aggregator {
             .await
             .unwrap()?;
 
-        let ready_for_commit_l1_batches = if protocol_version_id.is_pre_boojum() {
+        let mut ready_for_commit_l1_batches = if protocol_version_id.is_pre_boojum() {
             blocks_dal
                 .pre_boojum_get_ready_for_commit_l1_batches(
                     limit,
@@ -233,6 +240,28 @@ impl Aggregator {
                 }
             });
 
+        if let Some(last_finalized_l1_block) = last_finalized_l1_block {
+            // Priority operations from non-finalized L1 blocks may be reorged out of L1; batches containing them
+            // must not be committed until the corresponding L1 blocks are finalized.
+            let first_unsafe_l1_batch = storage
+                .transactions_dal()
+                .get_first_l1_batch_with_priority_op_after_l1_block(last_finalized_l1_block)
+                .await
+                .unwrap();
+            if let Some(first_unsafe_l1_batch) = first_unsafe_l1_batch {
+                let ready_count = ready_for_commit_l1_batches.len();
+                ready_for_commit_l1_batches
+                    .retain(|l1_batch| l1_batch.header.number < first_unsafe_l1_batch);
+                if ready_for_commit_l1_batches.len() < ready_count {
+                    tracing::debug!(
+                        "Capped L1 batches ready for commit before L1 batch #{first_unsafe_l1_batch}, \
+                         which contains priority operations from L1 blocks after the last finalized block \
+                         #{last_finalized_l1_block}"
+                    );
+                }
+            }
+        }
+
         let batches = extract_ready_subrange(
             storage,
             &mut self.commit_criteria,
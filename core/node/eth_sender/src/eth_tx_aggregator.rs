@@ -21,8 +21,8 @@ use zksync_types::{
     protocol_version::{L1VerifierConfig, PACKED_SEMVER_MINOR_MASK},
     pubdata_da::PubdataDA,
     settlement::SettlementMode,
-    web3::{contract::Error as Web3ContractError, BlockNumber},
-    Address, L2ChainId, ProtocolVersionId, SLChainId, H256, U256,
+    web3::{contract::Error as Web3ContractError, BlockId, BlockNumber},
+    Address, L1BlockNumber, L2ChainId, ProtocolVersionId, SLChainId, H256, U256,
 };
 
 use super::aggregated_operations::AggregatedOperation;
@@ -65,6 +65,19 @@ pub struct EthTxAggregator {
     pool: ConnectionPool<Core>,
     settlement_mode: SettlementMode,
     sl_chain_id: SLChainId,
+    /// If set, the aggregator doesn't create new `eth_txs` while the receiver holds `true`.
+    pause_receiver: Option<watch::Receiver<bool>>,
+    /// If set, L1 batches are committed only if all priority operations in them originate from finalized L1 blocks.
+    priority_ops_finality: Option<PriorityOpsFinality>,
+}
+
+/// Definition of L1 finality used to check whether priority operations are finalized. Must be the same
+/// as the definition used by `EthWatch`.
+#[derive(Debug, Clone, Copy)]
+struct PriorityOpsFinality {
+    /// If set, an L1 block is considered finalized once it has this many confirmations. Otherwise,
+    /// the `finalized` block tag is used.
+    confirmations_for_eth_event: Option<u64>,
 }
 
 struct TxData {
@@ -119,9 +132,39 @@ impl EthTxAggregator {
             pool,
             settlement_mode,
             sl_chain_id,
+            pause_receiver: None,
+            priority_ops_finality: None,
         }
     }
 
+    /// Allows pausing the aggregator. While the provided receiver holds `true`, the aggregator doesn't create new `eth_txs`.
+    pub fn with_pause_receiver(mut self, pause_receiver: watch::Receiver<bool>) -> Self {
+        self.pause_receiver = Some(pause_receiver);
+        self
+    }
+
+    /// Makes the aggregator commit only L1 batches in which all priority operations originate from finalized L1 blocks.
+    /// Should be enabled if priority operations are processed before their L1 blocks are finalized, so that
+    /// operations that can be reorged out of L1 are never committed.
+    ///
+    /// `confirmations_for_eth_event` must be taken from the `EthWatch` config, so that L1 finality is defined
+    /// in the same way as for `EthWatch`.
+    pub fn with_finalized_priority_ops_only(
+        mut self,
+        confirmations_for_eth_event: Option<u64>,
+    ) -> Self {
+        self.priority_ops_finality = Some(PriorityOpsFinality {
+            confirmations_for_eth_event,
+        });
+        self
+    }
+
+    fn is_paused(&self) -> bool {
+        self.pause_receiver
+            .as_ref()
+            .map_or(false, |receiver| *receiver.borrow())
+    }
+
     pub async fn run(mut self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
         let pool = self.pool.clone();
         loop {
@@ -132,7 +175,9 @@ impl EthTxAggregator {
                 break;
             }
 
-            if let Err(err) = self.loop_iteration(&mut storage).await {
+            if self.is_paused() {
+                tracing::trace!("eth_tx_aggregator is paused, skipping iteration");
+            } else if let Err(err) = self.loop_iteration(&mut storage).await {
                 // Web3 API request failures can cause this,
                 // and anything more important is already properly reported.
                 tracing::warn!("eth_sender error {err:?}");
@@ -329,6 +374,27 @@ impl EthTxAggregator {
         Ok(vk_hash)
     }
 
+    /// Returns the last finalized L1 block, using the same finality definition as `EthWatch`.
+    async fn get_last_finalized_l1_block(
+        &self,
+        finality: PriorityOpsFinality,
+    ) -> Result<L1BlockNumber, EthSenderError> {
+        let client = (*self.eth_client).as_ref();
+        let block_number = if let Some(confirmations) = finality.confirmations_for_eth_event {
+            let latest_block_number = client.block_number().await?.as_u64();
+            latest_block_number.saturating_sub(confirmations) as u32
+        } else {
+            client
+                .block(BlockId::Number(BlockNumber::Finalized))
+                .await?
+                .expect("Finalized block must be present on L1")
+                .number
+                .expect("Finalized block must contain number")
+                .as_u32()
+        };
+        Ok(L1BlockNumber(block_number))
+    }
+
     #[tracing::instrument(skip_all, name = "EthTxAggregator::loop_iteration")]
     async fn loop_iteration(
         &mut self,
@@ -354,6 +420,11 @@ impl EthTxAggregator {
         let l1_verifier_config = L1VerifierConfig {
             snark_wrapper_vk_hash,
         };
+        let last_finalized_l1_block = if let Some(finality) = self.priority_ops_finality {
+            Some(self.get_last_finalized_l1_block(finality).await?)
+        } else {
+            None
+        };
         if let Some(agg_op) = self
             .aggregator
             .get_next_ready_operation(
@@ -361,6 +432,7 @@ impl EthTxAggregator {
                 base_system_contracts_hashes,
                 protocol_version_id,
                 l1_verifier_config,
+                last_finalized_l1_block,
             )
             .await
         {
@@ -1,8 +1,11 @@
 use assert_matches::assert_matches;
 use test_casing::{test_casing, Product};
+use zksync_config::EthConfig;
+use zksync_contracts::BaseSystemContractsHashes;
 use zksync_dal::{ConnectionPool, Core, CoreDal};
 use zksync_l1_contract_interface::i_executor::methods::ExecuteBatches;
-use zksync_node_test_utils::create_l1_batch;
+use zksync_node_test_utils::{create_l1_batch, execute_l1_transaction};
+use zksync_object_store::MockObjectStore;
 use zksync_types::{
     aggregated_operations::AggregatedActionType,
     block::L1BatchHeader,
@@ -11,15 +14,17 @@ use zksync_types::{
     },
     ethabi::Token,
     helpers::unix_timestamp_ms,
+    l1::{L1Tx, L1TxCommonData},
+    protocol_version::L1VerifierConfig,
     web3::contract::Error,
-    ProtocolVersionId, H256,
+    Execute, L1BatchNumber, L1BlockNumber, PriorityOpId, ProtocolVersionId, H256,
 };
 
 use crate::{
     abstract_l1_interface::OperatorType,
     aggregated_operations::AggregatedOperation,
     tester::{EthSenderTester, TestL1Batch},
-    EthSenderError,
+    Aggregator, EthSenderError,
 };
 
 fn get_dummy_operation(number: u32) -> AggregatedOperation {
@@ -742,3 +747,68 @@ async fn get_multicall_data(commitment_mode: L1BatchCommitmentMode) {
     let multicall_data = tester.aggregator.get_multicall_data().await;
     assert!(multicall_data.is_ok());
 }
+
+#[test_log::test(tokio::test)]
+async fn l1_batches_with_unfinalized_priority_ops_are_not_committed() {
+    let mut tester = EthSenderTester::new(
+        ConnectionPool::<Core>::test_pool().await,
+        vec![100; 100],
+        false,
+        false,
+        L1BatchCommitmentMode::Rollup,
+    )
+    .await;
+    // Genesis L1 batch and 2 L1 batches ready for commit.
+    for _ in 0..3 {
+        tester.seal_l1_batch().await;
+    }
+
+    let priority_op = L1Tx {
+        execute: Execute::default(),
+        common_data: L1TxCommonData {
+            serial_id: PriorityOpId(0),
+            canonical_tx_hash: H256::repeat_byte(0x11),
+            ..L1TxCommonData::default()
+        },
+        received_timestamp_ms: 0,
+    };
+    let mut storage = tester.storage().await;
+    storage
+        .transactions_dal()
+        .insert_transaction_l1(&priority_op, L1BlockNumber(20))
+        .await
+        .unwrap();
+    storage
+        .transactions_dal()
+        .mark_txs_as_executed_in_l1_batch(L1BatchNumber(2), &[execute_l1_transaction(priority_op)])
+        .await
+        .unwrap();
+
+    let mut aggregator = Aggregator::new(
+        EthConfig::for_tests().sender.unwrap(),
+        MockObjectStore::arc(),
+        false,
+        L1BatchCommitmentMode::Rollup,
+    );
+    let committed_l1_batches = [(19, vec![1]), (20, vec![1, 2])];
+    for (last_finalized_l1_block, expected_l1_batches) in committed_l1_batches {
+        let operation = aggregator
+            .get_next_ready_operation(
+                &mut storage,
+                BaseSystemContractsHashes::default(),
+                ProtocolVersionId::latest(),
+                L1VerifierConfig::default(),
+                Some(L1BlockNumber(last_finalized_l1_block)),
+            )
+            .await
+            .expect("no commit operation");
+        let AggregatedOperation::Commit(_, l1_batches, _) = operation else {
+            panic!("unexpected operation: {operation:?}");
+        };
+        let l1_batch_numbers: Vec<_> = l1_batches
+            .iter()
+            .map(|batch| batch.header.number.0)
+            .collect();
+        assert_eq!(l1_batch_numbers, expected_l1_batches);
+    }
+}
@@ -0,0 +1,46 @@
+//! Circuit breaker halting the node if a priority operation processed before finalization is reorged out of L1.
+
+use std::sync::{Arc, Mutex};
+
+use zksync_circuit_breaker::{CircuitBreaker, CircuitBreakerError};
+use zksync_types::{L1BlockNumber, PriorityOpId};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct ReorgedPriorityOp {
+    pub serial_id: PriorityOpId,
+    pub l1_block: L1BlockNumber,
+}
+
+/// Circuit breaker tripped by [`EthWatch`](crate::EthWatch) if a priority operation processed before its L1 block
+/// was finalized is reorged out of L1 (i.e., it's either missing from the canonical chain, or its contents have changed).
+/// Once tripped, the circuit breaker remains tripped until the node is restarted.
+#[derive(Debug, Clone, Default)]
+pub struct PriorityOpsReorgCircuitBreaker(Arc<Mutex<Option<ReorgedPriorityOp>>>);
+
+impl PriorityOpsReorgCircuitBreaker {
+    pub(crate) fn trip(&self, op: ReorgedPriorityOp) {
+        let mut reorged_op = self.0.lock().expect("circuit breaker state is poisoned");
+        reorged_op.get_or_insert(op);
+    }
+
+    pub(crate) fn reorged_op(&self) -> Option<ReorgedPriorityOp> {
+        *self.0.lock().expect("circuit breaker state is poisoned")
+    }
+}
+
+#[async_trait::async_trait]
+impl CircuitBreaker for PriorityOpsReorgCircuitBreaker {
+    fn name(&self) -> &'static str {
+        "priority_ops_l1_reorg"
+    }
+
+    async fn check(&self) -> Result<(), CircuitBreakerError> {
+        if let Some(op) = self.reorged_op() {
+            return Err(CircuitBreakerError::PriorityOpReorged {
+                serial_id: op.serial_id.0,
+                l1_block: op.l1_block.0.into(),
+            });
+        }
+        Ok(())
+    }
+}
@@ -25,6 +25,8 @@ pub trait EthClient: 'static + fmt::Debug + Send + Sync {
     ) -> EnrichedClientResult<Vec<Log>>;
     /// Returns finalized L1 block number.
     async fn finalized_block_number(&self) -> EnrichedClientResult<u64>;
+    /// Returns the latest L1 block number.
+    async fn block_number(&self) -> EnrichedClientResult<u64>;
     /// Returns scheduler verification key hash by verifier address.
     async fn scheduler_vk_hash(&self, verifier_address: Address)
         -> Result<H256, ContractCallError>;
@@ -264,6 +266,10 @@ impl EthClient for EthHttpQueryClient {
         }
     }
 
+    async fn block_number(&self) -> EnrichedClientResult<u64> {
+        Ok(self.client.block_number().await?.as_u64())
+    }
+
     fn set_topics(&mut self, topics: Vec<H256>) {
         self.topics = topics;
     }
@@ -1,20 +1,23 @@
 //! Ethereum watcher polls the Ethereum node for the relevant events, such as priority operations (aka L1 transactions),
 //! protocol upgrades etc.
 //! New events are accepted to the ZKsync network once they have the sufficient amount of L1 confirmations.
+//! Optionally, priority operations can be accepted before their L1 block is finalized; in this case, they are tracked
+//! until finalization, and a reorg affecting them trips [`PriorityOpsReorgCircuitBreaker`].
 
-use std::time::Duration;
+use std::{collections::HashMap, time::Duration};
 
 use anyhow::Context as _;
 use tokio::sync::watch;
-use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
+use zksync_dal::{Connection, ConnectionPool, Core, CoreDal, DalError};
 use zksync_system_constants::PRIORITY_EXPIRATION;
 use zksync_types::{
-    ethabi::Contract, protocol_version::ProtocolSemanticVersion,
-    web3::BlockNumber as Web3BlockNumber, Address, PriorityOpId,
+    ethabi::Contract, l1::L1Tx, protocol_version::ProtocolSemanticVersion,
+    web3::BlockNumber as Web3BlockNumber, Address, L1BlockNumber, PriorityOpId, H256,
 };
 
-pub use self::client::EthHttpQueryClient;
+pub use self::{circuit_breaker::PriorityOpsReorgCircuitBreaker, client::EthHttpQueryClient};
 use self::{
+    circuit_breaker::ReorgedPriorityOp,
     client::{EthClient, RETRY_LIMIT},
     event_processors::{
         EventProcessor, EventProcessorError, GovernanceUpgradesEventProcessor,
@@ -24,6 +27,7 @@ use self::{
 };
 use crate::event_processors::DecentralizedUpgradesEventProcessor;
 
+mod circuit_breaker;
 mod client;
 mod event_processors;
 mod metrics;
@@ -45,6 +49,11 @@ pub struct EthWatch {
     event_processors: Vec<Box<dyn EventProcessor>>,
     last_processed_ethereum_block: u64,
     pool: ConnectionPool<Core>,
+    priority_ops_topic: H256,
+    /// Number of L1 confirmations after which priority operations are processed before finalization.
+    confirmations_for_priority_ops: Option<u64>,
+    last_processed_unfinalized_block: u64,
+    reorg_circuit_breaker: PriorityOpsReorgCircuitBreaker,
 }
 
 impl EthWatch {
@@ -55,14 +64,17 @@ impl EthWatch {
         mut client: Box<dyn EthClient>,
         pool: ConnectionPool<Core>,
         poll_interval: Duration,
+        confirmations_for_priority_ops: Option<u64>,
     ) -> anyhow::Result<Self> {
         let mut storage = pool.connection_tagged("eth_watch").await?;
-        let state = Self::initialize_state(&*client, &mut storage).await?;
+        let state =
+            Self::initialize_state(&*client, &mut storage, confirmations_for_priority_ops).await?;
         tracing::info!("initialized state: {state:?}");
         drop(storage);
 
         let priority_ops_processor =
             PriorityOpsEventProcessor::new(state.next_expected_priority_id)?;
+        let priority_ops_topic = priority_ops_processor.relevant_topic();
         let governance_upgrades_processor = GovernanceUpgradesEventProcessor::new(
             diamond_proxy_addr,
             state.last_seen_protocol_version,
@@ -90,13 +102,24 @@ impl EthWatch {
             event_processors,
             last_processed_ethereum_block: state.last_processed_ethereum_block,
             pool,
+            priority_ops_topic,
+            confirmations_for_priority_ops,
+            last_processed_unfinalized_block: state.last_processed_ethereum_block,
+            reorg_circuit_breaker: PriorityOpsReorgCircuitBreaker::default(),
         })
     }
 
+    /// Returns the circuit breaker tripped if a priority operation processed before finalization is reorged out of L1.
+    /// The circuit breaker can only be tripped if processing priority operations before finalization is enabled.
+    pub fn reorg_circuit_breaker(&self) -> PriorityOpsReorgCircuitBreaker {
+        self.reorg_circuit_breaker.clone()
+    }
+
     #[tracing::instrument(name = "EthWatch::initialize_state", skip_all)]
     async fn initialize_state(
         client: &dyn EthClient,
         storage: &mut Connection<'_, Core>,
+        confirmations_for_priority_ops: Option<u64>,
     ) -> anyhow::Result<EthWatchState> {
         let next_expected_priority_id: PriorityOpId = storage
             .transactions_dal()
@@ -110,6 +133,10 @@ impl EthWatch {
             .await?
             .context("expected at least one (genesis) version to be present in DB")?;
 
+        let finalized_block_number = client
+            .finalized_block_number()
+            .await
+            .context("cannot get current Ethereum block")?;
         let last_processed_ethereum_block = match storage
             .transactions_dal()
             .get_last_processed_l1_block()
@@ -117,13 +144,18 @@ impl EthWatch {
         {
             // There are some priority ops processed - start from the last processed eth block
             // but subtract 1 in case the server stopped mid-block.
-            Some(block) => block.0.saturating_sub(1).into(),
+            Some(block) => {
+                let block = u64::from(block.0.saturating_sub(1));
+                if confirmations_for_priority_ops.is_some() {
+                    // Priority ops may have been processed from non-finalized blocks. Make sure that finality-gated
+                    // events are not skipped, and that non-finalized priority ops are checked for reorgs.
+                    block.min(finalized_block_number)
+                } else {
+                    block
+                }
+            }
             // There are no priority ops processed - to be safe, scan the last 50k blocks.
-            None => client
-                .finalized_block_number()
-                .await
-                .context("cannot get current Ethereum block")?
-                .saturating_sub(PRIORITY_EXPIRATION),
+            None => finalized_block_number.saturating_sub(PRIORITY_EXPIRATION),
         };
 
         Ok(EthWatchState {
@@ -155,10 +187,14 @@ impl EthWatch {
                     // This is an error because otherwise we could potentially miss a priority operation
                     // thus entering priority mode, which is not desired.
                     tracing::error!("Failed to process new blocks: {err}");
-                    self.last_processed_ethereum_block =
-                        Self::initialize_state(&*self.client, &mut storage)
-                            .await?
-                            .last_processed_ethereum_block;
+                    self.last_processed_ethereum_block = Self::initialize_state(
+                        &*self.client,
+                        &mut storage,
+                        self.confirmations_for_priority_ops,
+                    )
+                    .await?
+                    .last_processed_ethereum_block;
+                    self.last_processed_unfinalized_block = self.last_processed_ethereum_block;
                 }
             }
         }
@@ -172,34 +208,147 @@ impl EthWatch {
         &mut self,
         storage: &mut Connection<'_, Core>,
     ) -> Result<(), EventProcessorError> {
+        if let Some(op) = self.reorg_circuit_breaker.reorged_op() {
+            // Do not process any new events; the node will be halted by the circuit breaker.
+            tracing::warn!("Skipping processing new blocks because of an L1 reorg affecting priority op: {op:?}");
+            return Ok(());
+        }
+
         let stage_latency = METRICS.poll_eth_node[&PollStage::Request].start();
         let to_block = self.client.finalized_block_number().await?;
-        if to_block <= self.last_processed_ethereum_block {
+        if self.confirmations_for_priority_ops.is_some() {
+            // Must be performed before the finalized block is advanced, so that priority ops finalized
+            // since the last check are checked one last time.
+            if self.check_unfinalized_priority_ops(storage).await? {
+                return Ok(());
+            }
+        }
+
+        if to_block > self.last_processed_ethereum_block {
+            let events = self
+                .client
+                .get_events(
+                    Web3BlockNumber::Number(self.last_processed_ethereum_block.into()),
+                    Web3BlockNumber::Number(to_block.into()),
+                    RETRY_LIMIT,
+                )
+                .await?;
+            stage_latency.observe();
+
+            for processor in &mut self.event_processors {
+                let relevant_topic = processor.relevant_topic();
+                let processor_events = events
+                    .iter()
+                    .filter(|event| event.topics.first() == Some(&relevant_topic))
+                    .cloned()
+                    .collect();
+                processor
+                    .process_events(storage, &*self.client, processor_events)
+                    .await?;
+            }
+            self.last_processed_ethereum_block = to_block;
+        }
+
+        if let Some(confirmations) = self.confirmations_for_priority_ops {
+            self.process_unfinalized_priority_ops(storage, confirmations)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Processes priority ops that have the configured number of confirmations, but are not finalized yet.
+    async fn process_unfinalized_priority_ops(
+        &mut self,
+        storage: &mut Connection<'_, Core>,
+        confirmations: u64,
+    ) -> Result<(), EventProcessorError> {
+        let to_block = self
+            .client
+            .block_number()
+            .await?
+            .saturating_sub(confirmations);
+        let from_block = self
+            .last_processed_unfinalized_block
+            .max(self.last_processed_ethereum_block);
+        if to_block <= from_block {
             return Ok(());
         }
 
         let events = self
             .client
             .get_events(
-                Web3BlockNumber::Number(self.last_processed_ethereum_block.into()),
+                Web3BlockNumber::Number(from_block.into()),
                 Web3BlockNumber::Number(to_block.into()),
                 RETRY_LIMIT,
             )
             .await?;
-        stage_latency.observe();
-
-        for processor in &mut self.event_processors {
-            let relevant_topic = processor.relevant_topic();
-            let processor_events = events
-                .iter()
-                .filter(|event| event.topics.first() == Some(&relevant_topic))
-                .cloned()
-                .collect();
-            processor
-                .process_events(storage, &*self.client, processor_events)
-                .await?;
-        }
-        self.last_processed_ethereum_block = to_block;
+        let priority_ops_topic = self.priority_ops_topic;
+        let events = events
+            .into_iter()
+            .filter(|event| event.topics.first() == Some(&priority_ops_topic))
+            .collect();
+        let processor = self
+            .event_processors
+            .iter_mut()
+            .find(|processor| processor.relevant_topic() == priority_ops_topic)
+            .context("no priority ops processor")?;
+        processor
+            .process_events(storage, &*self.client, events)
+            .await?;
+        self.last_processed_unfinalized_block = to_block;
         Ok(())
     }
+
+    /// Checks that priority ops processed from L1 blocks after the last processed finalized block are still present
+    /// on L1 with the same contents. If this is not the case, trips the reorg circuit breaker and returns `true`.
+    async fn check_unfinalized_priority_ops(
+        &mut self,
+        storage: &mut Connection<'_, Core>,
+    ) -> Result<bool, EventProcessorError> {
+        let last_finalized_block = self.last_processed_ethereum_block;
+        let processed_ops = storage
+            .transactions_dal()
+            .get_priority_ops_after_l1_block(L1BlockNumber(last_finalized_block as u32))
+            .await
+            .map_err(DalError::generalize)?;
+        if processed_ops.is_empty() {
+            return Ok(false);
+        }
+
+        let latest_block = self.client.block_number().await?;
+        let events = self
+            .client
+            .get_events(
+                Web3BlockNumber::Number(last_finalized_block.into()),
+                Web3BlockNumber::Number(latest_block.into()),
+                RETRY_LIMIT,
+            )
+            .await?;
+        let mut canonical_ops = HashMap::new();
+        for event in events {
+            if event.topics.first() != Some(&self.priority_ops_topic) {
+                continue;
+            }
+            let tx = L1Tx::try_from(event)
+                .map_err(|err| EventProcessorError::log_parse(err, "priority op"))?;
+            canonical_ops.insert(tx.serial_id(), tx.hash());
+        }
+
+        for (serial_id, l1_block, tx_hash) in processed_ops {
+            let canonical_hash = canonical_ops.get(&serial_id);
+            if canonical_hash != Some(&tx_hash) {
+                tracing::error!(
+                    "Priority op #{serial_id} with hash {tx_hash:?} processed from L1 block #{l1_block} was reorged out of L1; \
+                     canonical op hash: {canonical_hash:?}"
+                );
+                METRICS.priority_ops_reorgs.inc();
+                self.reorg_circuit_breaker.trip(ReorgedPriorityOp {
+                    serial_id,
+                    l1_block,
+                });
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
 }
@@ -1,6 +1,7 @@
 use std::{collections::HashMap, convert::TryInto, sync::Arc};
 
 use tokio::sync::RwLock;
+use zksync_circuit_breaker::{CircuitBreaker, CircuitBreakerError};
 use zksync_contracts::{chain_admin_contract, governance_contract, hyperchain_contract};
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
 use zksync_eth_client::{ContractCallError, EnrichedClientResult};
@@ -23,6 +24,7 @@ struct FakeEthClientData {
     diamond_upgrades: HashMap<u64, Vec<Log>>,
     governance_upgrades: HashMap<u64, Vec<Log>>,
     last_finalized_block_number: u64,
+    last_block_number: u64,
 }
 
 impl FakeEthClientData {
@@ -32,6 +34,7 @@ impl FakeEthClientData {
             diamond_upgrades: Default::default(),
             governance_upgrades: Default::default(),
             last_finalized_block_number: 0,
+            last_block_number: 0,
         }
     }
 
@@ -57,6 +60,10 @@ impl FakeEthClientData {
     fn set_last_finalized_block_number(&mut self, number: u64) {
         self.last_finalized_block_number = number;
     }
+
+    fn set_last_block_number(&mut self, number: u64) {
+        self.last_block_number = number;
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -86,6 +93,14 @@ impl MockEthClient {
             .set_last_finalized_block_number(number);
     }
 
+    async fn set_last_block_number(&mut self, number: u64) {
+        self.inner.write().await.set_last_block_number(number);
+    }
+
+    async fn remove_transactions(&mut self, eth_block: u64) {
+        self.inner.write().await.transactions.remove(&eth_block);
+    }
+
     async fn block_to_number(&self, block: BlockNumber) -> u64 {
         match block {
             BlockNumber::Earliest => 0,
@@ -136,6 +151,13 @@ impl EthClient for MockEthClient {
         Ok(self.inner.read().await.last_finalized_block_number)
     }
 
+    async fn block_number(&self) -> EnrichedClientResult<u64> {
+        let inner = self.inner.read().await;
+        Ok(inner
+            .last_block_number
+            .max(inner.last_finalized_block_number))
+    }
+
     async fn diamond_cut_by_version(
         &self,
         _packed_version: H256,
@@ -204,6 +226,13 @@ fn build_upgrade_tx(id: ProtocolVersionId, eth_block: u64) -> ProtocolUpgradeTx
 }
 
 async fn create_test_watcher(connection_pool: ConnectionPool<Core>) -> (EthWatch, MockEthClient) {
+    create_test_watcher_with_confirmations(connection_pool, None).await
+}
+
+async fn create_test_watcher_with_confirmations(
+    connection_pool: ConnectionPool<Core>,
+    confirmations_for_priority_ops: Option<u64>,
+) -> (EthWatch, MockEthClient) {
     let client = MockEthClient::new();
     let watcher = EthWatch::new(
         Address::default(),
@@ -212,6 +241,7 @@ async fn create_test_watcher(connection_pool: ConnectionPool<Core>) -> (EthWatch
         Box::new(client.clone()),
         connection_pool,
         std::time::Duration::from_nanos(1),
+        confirmations_for_priority_ops,
     )
     .await
     .unwrap();
@@ -258,6 +288,148 @@ async fn test_normal_operation_l1_txs() {
     assert_eq!(db_tx.common_data.serial_id.0, 2);
 }
 
+async fn get_db_serial_ids(storage: &mut Connection<'_, Core>) -> Vec<u64> {
+    let mut serial_ids: Vec<_> = get_all_db_txs(storage)
+        .await
+        .into_iter()
+        .map(|tx| L1Tx::try_from(tx).unwrap().serial_id().0)
+        .collect();
+    serial_ids.sort_unstable();
+    serial_ids
+}
+
+#[tokio::test]
+async fn processing_priority_ops_before_finalization() {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    setup_db(&connection_pool).await;
+    let (mut watcher, mut client) =
+        create_test_watcher_with_confirmations(connection_pool.clone(), Some(2)).await;
+
+    let mut storage = connection_pool.connection().await.unwrap();
+    client
+        .add_transactions(&[build_l1_tx(0, 10), build_l1_tx(1, 14), build_l1_tx(2, 18)])
+        .await;
+    client.set_last_finalized_block_number(5).await;
+    client.set_last_block_number(16).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    assert_eq!(get_db_serial_ids(&mut storage).await, [0, 1]);
+
+    client.set_last_block_number(20).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    assert_eq!(get_db_serial_ids(&mut storage).await, [0, 1, 2]);
+
+    // Finalizing blocks shouldn't lead to duplicate processing.
+    client.set_last_finalized_block_number(20).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    assert_eq!(get_db_serial_ids(&mut storage).await, [0, 1, 2]);
+    watcher.reorg_circuit_breaker().check().await.unwrap();
+}
+
+#[tokio::test]
+async fn governance_upgrades_are_finality_gated_with_priority_ops_confirmations() {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    setup_db(&connection_pool).await;
+    let (mut watcher, mut client) =
+        create_test_watcher_with_confirmations(connection_pool.clone(), Some(0)).await;
+
+    let mut storage = connection_pool.connection().await.unwrap();
+    client
+        .add_governance_upgrades(&[(
+            ProtocolUpgrade {
+                version: ProtocolSemanticVersion {
+                    minor: ProtocolVersionId::next(),
+                    patch: 0.into(),
+                },
+                tx: None,
+                ..Default::default()
+            },
+            10,
+        )])
+        .await;
+    client.set_last_finalized_block_number(5).await;
+    client.set_last_block_number(15).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    let db_versions = storage.protocol_versions_dal().all_versions().await;
+    assert_eq!(db_versions.len(), 1);
+
+    client.set_last_finalized_block_number(15).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    let db_versions = storage.protocol_versions_dal().all_versions().await;
+    assert_eq!(db_versions.len(), 2);
+    assert_eq!(db_versions[1].minor, ProtocolVersionId::next());
+}
+
+#[tokio::test]
+async fn reincluded_priority_op_does_not_trip_circuit_breaker() {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    setup_db(&connection_pool).await;
+    let (mut watcher, mut client) =
+        create_test_watcher_with_confirmations(connection_pool.clone(), Some(1)).await;
+
+    let mut storage = connection_pool.connection().await.unwrap();
+    client
+        .add_transactions(&[build_l1_tx(0, 10), build_l1_tx(1, 14)])
+        .await;
+    client.set_last_finalized_block_number(5).await;
+    client.set_last_block_number(15).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    assert_eq!(get_db_serial_ids(&mut storage).await, [0, 1]);
+
+    // Op #1 is moved to another block by a reorg.
+    client.remove_transactions(14).await;
+    client.add_transactions(&[build_l1_tx(1, 15)]).await;
+    client.set_last_block_number(16).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    watcher.reorg_circuit_breaker().check().await.unwrap();
+}
+
+#[tokio::test]
+async fn detecting_reorg_of_unfinalized_priority_op() {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    setup_db(&connection_pool).await;
+    let (mut watcher, mut client) =
+        create_test_watcher_with_confirmations(connection_pool.clone(), Some(1)).await;
+
+    let mut storage = connection_pool.connection().await.unwrap();
+    client
+        .add_transactions(&[build_l1_tx(0, 10), build_l1_tx(1, 14)])
+        .await;
+    client.set_last_finalized_block_number(5).await;
+    client.set_last_block_number(15).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    assert_eq!(get_db_serial_ids(&mut storage).await, [0, 1]);
+
+    // Op #1 is replaced with another op by a reorg.
+    client.remove_transactions(14).await;
+    let mut replaced_tx = build_l1_tx(1, 15);
+    replaced_tx.execute.calldata = vec![4, 5, 6];
+    let replaced_tx = L1Tx::try_from(
+        Transaction::try_from(abi::Transaction::try_from(Transaction::from(replaced_tx)).unwrap())
+            .unwrap(),
+    )
+    .unwrap();
+    client
+        .add_transactions(&[replaced_tx, build_l1_tx(2, 16)])
+        .await;
+    client.set_last_block_number(17).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+
+    let err = watcher.reorg_circuit_breaker().check().await.unwrap_err();
+    assert!(
+        matches!(
+            err,
+            CircuitBreakerError::PriorityOpReorged {
+                serial_id: 1,
+                l1_block: 14
+            }
+        ),
+        "{err:?}"
+    );
+    // No new priority ops should be processed after the reorg is detected.
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    assert_eq!(get_db_serial_ids(&mut storage).await, [0, 1]);
+}
+
 #[tokio::test]
 async fn test_gap_in_governance_upgrades() {
     let connection_pool = ConnectionPool::<Core>::test_pool().await;
@@ -305,6 +477,7 @@ async fn test_normal_operation_governance_upgrades() {
         Box::new(client.clone()),
         connection_pool.clone(),
         std::time::Duration::from_nanos(1),
+        None,
     )
     .await
     .unwrap();
@@ -0,0 +1,168 @@
+//! EIP-1559-like adjustment of the L2 base fee based on the gas usage of L2 blocks.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use vise::{Gauge, Metrics};
+use zksync_config::configs::chain::StateKeeperConfig;
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_l2_base_fee")]
+struct L2BaseFeeMetrics {
+    /// Current L2 base fee in the base token denomination.
+    current: Gauge<u64>,
+    /// L2 gas used by the last processed L2 block.
+    last_block_gas_used: Gauge<u64>,
+}
+
+#[vise::register]
+static METRICS: vise::Global<L2BaseFeeMetrics> = vise::Global::new();
+
+/// Tracks the L2 base fee, adjusting it after each L2 block depending on how much gas the block used
+/// compared to the target.
+///
+/// Similarly to EIP-1559, the base fee grows if a block uses more gas than the target and decreases otherwise;
+/// the relative change per block is bounded by `1 / max_change_denominator`. The base fee never drops below
+/// the minimal L2 gas price. The fee is not persisted, so it starts from the minimal price after a node restart.
+///
+/// While the fee is updated after each L2 block, it's only applied when fee input is computed, i.e. when
+/// a new L1 batch is opened; L2 blocks within a batch share the batch base fee.
+#[derive(Debug)]
+pub struct L2BaseFeeAdjuster {
+    min_base_fee: u64,
+    gas_target: u64,
+    max_change_denominator: u64,
+    base_fee: AtomicU64,
+}
+
+impl L2BaseFeeAdjuster {
+    /// Creates an adjuster from the state keeper config. Returns `None` if the L2 block gas target is not configured.
+    pub fn from_state_keeper_config(config: &StateKeeperConfig) -> Option<Self> {
+        let gas_target = config.l2_block_gas_target?;
+        Some(Self::new(
+            config.minimal_l2_gas_price,
+            gas_target,
+            config.l2_base_fee_max_change_denominator(),
+        ))
+    }
+
+    pub fn new(min_base_fee: u64, gas_target: u64, max_change_denominator: u64) -> Self {
+        assert!(gas_target > 0, "L2 block gas target must be positive");
+        assert!(
+            max_change_denominator > 0,
+            "L2 base fee max change denominator must be positive"
+        );
+        METRICS.current.set(min_base_fee);
+        Self {
+            min_base_fee,
+            gas_target,
+            max_change_denominator,
+            base_fee: AtomicU64::new(min_base_fee),
+        }
+    }
+
+    /// Returns the current L2 base fee.
+    pub fn base_fee(&self) -> u64 {
+        self.base_fee.load(Ordering::Relaxed)
+    }
+
+    /// Adjusts the base fee based on the L2 gas used by the latest L2 block. Returns the updated base fee.
+    ///
+    /// This method is expected to be called by a single writer (the state keeper), so the update is not atomic
+    /// w.r.t. concurrent updates.
+    pub fn update(&self, gas_used: u64) -> u64 {
+        let base_fee = self.base_fee();
+        let new_base_fee = self.next_base_fee(base_fee, gas_used);
+        self.base_fee.store(new_base_fee, Ordering::Relaxed);
+
+        METRICS.current.set(new_base_fee);
+        METRICS.last_block_gas_used.set(gas_used);
+        tracing::trace!(
+            "Adjusted L2 base fee {base_fee} -> {new_base_fee} (gas used: {gas_used}, target: {})",
+            self.gas_target
+        );
+        new_base_fee
+    }
+
+    fn next_base_fee(&self, base_fee: u64, gas_used: u64) -> u64 {
+        // Intermediate values may overflow `u64`, so we use `u128` for computations.
+        let (base_fee_u128, gas_target) = (u128::from(base_fee), u128::from(self.gas_target));
+        let denominator = u128::from(self.max_change_denominator);
+        let new_base_fee = if gas_used > self.gas_target {
+            let gas_delta = u128::from(gas_used - self.gas_target);
+            // As in EIP-1559, the base fee always increases by at least 1 if the target is exceeded.
+            let fee_delta = (base_fee_u128 * gas_delta / gas_target / denominator).max(1);
+            base_fee_u128.saturating_add(fee_delta)
+        } else {
+            let gas_delta = u128::from(self.gas_target - gas_used);
+            let fee_delta = base_fee_u128 * gas_delta / gas_target / denominator;
+            base_fee_u128.saturating_sub(fee_delta)
+        };
+        let new_base_fee = u64::try_from(new_base_fee).unwrap_or(u64::MAX);
+        new_base_fee.max(self.min_base_fee)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIN_BASE_FEE: u64 = 100_000_000;
+    const GAS_TARGET: u64 = 10_000_000;
+
+    #[test]
+    fn base_fee_is_stable_at_target() {
+        let adjuster = L2BaseFeeAdjuster::new(MIN_BASE_FEE, GAS_TARGET, 8);
+        assert_eq!(adjuster.update(GAS_TARGET), MIN_BASE_FEE);
+        assert_eq!(adjuster.base_fee(), MIN_BASE_FEE);
+    }
+
+    #[test]
+    fn base_fee_increases_for_full_blocks() {
+        let adjuster = L2BaseFeeAdjuster::new(MIN_BASE_FEE, GAS_TARGET, 8);
+        // Block uses twice the target; the fee should grow by 1/8.
+        assert_eq!(adjuster.update(2 * GAS_TARGET), MIN_BASE_FEE / 8 * 9);
+        let fee = adjuster.update(GAS_TARGET + GAS_TARGET / 2);
+        assert_eq!(fee, MIN_BASE_FEE / 8 * 9 + MIN_BASE_FEE / 8 * 9 / 16);
+    }
+
+    #[test]
+    fn base_fee_decreases_for_empty_blocks() {
+        let adjuster = L2BaseFeeAdjuster::new(MIN_BASE_FEE, GAS_TARGET, 8);
+        for _ in 0..10 {
+            adjuster.update(3 * GAS_TARGET);
+        }
+        let mut fee = adjuster.base_fee();
+        assert!(fee > MIN_BASE_FEE, "{fee}");
+
+        loop {
+            let new_fee = adjuster.update(0);
+            if new_fee == MIN_BASE_FEE {
+                break;
+            }
+            assert_eq!(new_fee, (fee - fee / 8).max(MIN_BASE_FEE));
+            fee = new_fee;
+        }
+        // The fee must not drop below the minimum.
+        assert_eq!(adjuster.update(0), MIN_BASE_FEE);
+    }
+
+    #[test]
+    fn base_fee_always_increases_if_target_is_exceeded() {
+        let adjuster = L2BaseFeeAdjuster::new(1, GAS_TARGET, 8);
+        assert_eq!(adjuster.update(GAS_TARGET + 1), 2);
+    }
+
+    #[test]
+    fn adjuster_is_not_created_without_gas_target() {
+        let config = StateKeeperConfig::for_tests();
+        assert!(L2BaseFeeAdjuster::from_state_keeper_config(&config).is_none());
+
+        let config = StateKeeperConfig {
+            l2_block_gas_target: Some(GAS_TARGET),
+            ..config
+        };
+        let adjuster = L2BaseFeeAdjuster::from_state_keeper_config(&config).unwrap();
+        assert_eq!(adjuster.base_fee(), config.minimal_l2_gas_price);
+        assert_eq!(adjuster.max_change_denominator, 8);
+    }
+}
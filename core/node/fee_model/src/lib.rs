@@ -13,8 +13,10 @@ use zksync_types::{
 use zksync_utils::ceil_div_u256;
 
 use crate::l1_gas_price::GasAdjuster;
+pub use crate::l2_base_fee::L2BaseFeeAdjuster;
 
 pub mod l1_gas_price;
+mod l2_base_fee;
 
 /// Trait responsible for providing numerator and denominator for adjusting gas price that is denominated
 /// in a non-eth base token
@@ -69,12 +71,13 @@ pub struct MainNodeFeeInputProvider {
     provider: Arc<GasAdjuster>,
     base_token_ratio_provider: Arc<dyn BaseTokenRatioProvider>,
     config: FeeModelConfig,
+    l2_base_fee_adjuster: Option<Arc<L2BaseFeeAdjuster>>,
 }
 
 #[async_trait]
 impl BatchFeeModelInputProvider for MainNodeFeeInputProvider {
     fn get_fee_model_params(&self) -> FeeParams {
-        match self.config {
+        match self.config() {
             FeeModelConfig::V1(config) => FeeParams::V1(FeeParamsV1 {
                 config,
                 l1_gas_price: self.provider.estimate_effective_gas_price(),
@@ -99,6 +102,30 @@ impl MainNodeFeeInputProvider {
             provider,
             base_token_ratio_provider,
             config,
+            l2_base_fee_adjuster: None,
+        }
+    }
+
+    /// Makes the minimal L2 gas price dynamic, taking it from the provided L2 base fee adjuster.
+    pub fn with_l2_base_fee_adjuster(mut self, adjuster: Arc<L2BaseFeeAdjuster>) -> Self {
+        self.l2_base_fee_adjuster = Some(adjuster);
+        self
+    }
+
+    fn config(&self) -> FeeModelConfig {
+        let Some(adjuster) = &self.l2_base_fee_adjuster else {
+            return self.config;
+        };
+        let base_fee = adjuster.base_fee();
+        match self.config {
+            FeeModelConfig::V1(mut config) => {
+                config.minimal_l2_gas_price = config.minimal_l2_gas_price.max(base_fee);
+                FeeModelConfig::V1(config)
+            }
+            FeeModelConfig::V2(mut config) => {
+                config.minimal_l2_gas_price = config.minimal_l2_gas_price.max(base_fee);
+                FeeModelConfig::V2(config)
+            }
         }
     }
 }
@@ -2,7 +2,7 @@
 //! It initializes the Merkle tree with the basic setup (such as fields of special service accounts),
 //! setups the required databases, and outputs the data required to initialize a smart contract.
 
-use std::fmt::Formatter;
+use std::{collections::HashMap, fmt::Formatter};
 
 use anyhow::Context as _;
 use zksync_config::GenesisConfig;
@@ -26,7 +26,7 @@ use zksync_types::{
     AccountTreeId, Address, Bloom, L1BatchNumber, L1ChainId, L2BlockNumber, L2ChainId,
     ProtocolVersion, ProtocolVersionId, StorageKey, H256, U256,
 };
-use zksync_utils::{bytecode::hash_bytecode, u256_to_h256};
+use zksync_utils::{be_words_to_bytes, bytecode::hash_bytecode, u256_to_h256};
 
 use crate::utils::{
     add_eth_token, get_deduped_log_queries, get_storage_logs,
@@ -202,23 +202,11 @@ pub async fn insert_genesis_batch(
     .await?;
     tracing::info!("chain_schema_genesis is complete");
 
-    let deduped_log_queries =
-        get_deduped_log_queries(&get_storage_logs(genesis_params.system_contracts()));
-
-    let (deduplicated_writes, _): (Vec<_>, Vec<_>) = deduped_log_queries
+    let storage_logs: Vec<TreeInstruction> = genesis_storage_writes(genesis_params)
         .into_iter()
-        .partition(|log_query| log_query.rw_flag);
-
-    let storage_logs: Vec<TreeInstruction> = deduplicated_writes
-        .iter()
         .enumerate()
-        .map(|(index, log)| {
-            TreeInstruction::write(
-                StorageKey::new(AccountTreeId::new(log.address), u256_to_h256(log.key))
-                    .hashed_key_u256(),
-                (index + 1) as u64,
-                u256_to_h256(log.written_value),
-            )
+        .map(|(index, (key, value))| {
+            TreeInstruction::write(key.hashed_key_u256(), (index + 1) as u64, value)
         })
         .collect();
 
@@ -259,6 +247,34 @@ pub async fn insert_genesis_batch(
     })
 }
 
+/// Returns deduplicated storage writes performed in the genesis L1 batch. Writes are ordered by their enumeration
+/// indices, i.e., the first write has enumeration index 1, the second one has index 2, etc.
+pub fn genesis_storage_writes(genesis_params: &GenesisParams) -> Vec<(StorageKey, H256)> {
+    get_deduped_log_queries(&get_storage_logs(genesis_params.system_contracts()))
+        .into_iter()
+        .filter(|log_query| log_query.rw_flag)
+        .map(|log| {
+            let key = StorageKey::new(AccountTreeId::new(log.address), u256_to_h256(log.key));
+            (key, u256_to_h256(log.written_value))
+        })
+        .collect()
+}
+
+/// Returns factory deps inserted in the genesis L1 batch, i.e. bytecodes of system contracts and base system contracts
+/// keyed by their hashes.
+pub fn genesis_factory_deps(genesis_params: &GenesisParams) -> HashMap<H256, Vec<u8>> {
+    let base_contracts = genesis_params.base_system_contracts();
+    let base_factory_deps = [&base_contracts.bootloader, &base_contracts.default_aa]
+        .into_iter()
+        .map(|contract| (contract.hash, be_words_to_bytes(&contract.code)));
+    genesis_params
+        .system_contracts()
+        .iter()
+        .map(|contract| (hash_bytecode(&contract.bytecode), contract.bytecode.clone()))
+        .chain(base_factory_deps)
+        .collect()
+}
+
 pub async fn is_genesis_needed(storage: &mut Connection<'_, Core>) -> Result<bool, GenesisError> {
     Ok(storage.blocks_dal().is_genesis_needed().await?)
 }
@@ -0,0 +1,88 @@
+//! Blob provider backed by an HTTP blob archive.
+
+use anyhow::Context as _;
+use serde::Deserialize;
+use zksync_l1_contract_interface::i_executor::commit::kzg::ZK_SYNC_BYTES_PER_BLOB;
+use zksync_types::url::SensitiveUrl;
+
+use crate::l1_data::BlobProvider;
+
+/// Size of an EIP-4844 blob in bytes.
+const BLOB_SIZE: usize = 131_072;
+/// Size of a BLS12-381 field element the blob consists of.
+const FIELD_ELEMENT_SIZE: usize = 32;
+
+#[derive(Debug, Deserialize)]
+struct BlobResponse {
+    /// Hex-encoded blob data (with the `0x` prefix).
+    data: String,
+}
+
+/// [`BlobProvider`] fetching blobs from a Blobscan-compatible archive. Blobs are requested
+/// as `GET {base_url}/blobs/{kzg_commitment}`, where the commitment is hex-encoded with the `0x` prefix.
+///
+/// Blobs returned by the archive are not trusted; they are verified against KZG commitments by the recovery logic.
+#[derive(Debug)]
+pub struct BlobApiClient {
+    client: reqwest::Client,
+    base_url: SensitiveUrl,
+}
+
+impl BlobApiClient {
+    pub fn new(base_url: SensitiveUrl) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl BlobProvider for BlobApiClient {
+    async fn get_blob(&self, kzg_commitment: &[u8]) -> anyhow::Result<Option<Vec<u8>>> {
+        let url = format!(
+            "{}/blobs/0x{}",
+            self.base_url.expose_str().trim_end_matches('/'),
+            hex::encode(kzg_commitment)
+        );
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .context("failed requesting blob")?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let response = response
+            .error_for_status()
+            .context("blob archive returned error")?;
+        let response: BlobResponse = response
+            .json()
+            .await
+            .context("failed parsing blob archive response")?;
+        let blob = hex::decode(response.data.trim_start_matches("0x"))
+            .context("blob data is not valid hex")?;
+        blob_to_pubdata(&blob).map(Some)
+    }
+}
+
+/// Converts an EIP-4844 blob into ZKsync pubdata. Each 32-byte field element of the blob encodes 31 bytes of pubdata
+/// in big-endian order, with the most significant byte set to zero.
+pub(crate) fn blob_to_pubdata(blob: &[u8]) -> anyhow::Result<Vec<u8>> {
+    anyhow::ensure!(
+        blob.len() == BLOB_SIZE,
+        "unexpected blob size: expected {BLOB_SIZE} bytes, got {}",
+        blob.len()
+    );
+
+    let mut pubdata = Vec::with_capacity(ZK_SYNC_BYTES_PER_BLOB);
+    for (i, element) in blob.chunks(FIELD_ELEMENT_SIZE).enumerate() {
+        anyhow::ensure!(
+            element[0] == 0,
+            "field element #{i} in blob has non-zero most significant byte"
+        );
+        pubdata.extend_from_slice(&element[1..]);
+    }
+    Ok(pubdata)
+}
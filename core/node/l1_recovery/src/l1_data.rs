@@ -0,0 +1,483 @@
+//! Fetching L1 batch data from L1.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    ops::RangeInclusive,
+    sync::Arc,
+};
+
+use anyhow::Context as _;
+use zksync_eth_client::{
+    clients::{DynClient, L1},
+    CallFunctionArgs, EthInterface,
+};
+use zksync_l1_contract_interface::i_executor::{
+    commit::kzg::{KzgInfo, ZK_SYNC_BYTES_PER_BLOB},
+    structures::StoredBatchInfo,
+};
+use zksync_system_constants::{L1_MESSENGER_ADDRESS, L2_TO_L1_LOGS_TREE_ROOT_KEY};
+use zksync_types::{
+    abi,
+    commitment::SerializeCommitment,
+    ethabi::{self, Token},
+    l1::L1Tx,
+    l2_to_l1_log::L2ToL1Log,
+    protocol_upgrade::ProtocolUpgrade,
+    web3::{BlockId, BlockNumber, FilterBuilder},
+    Address, L1BatchNumber, H256, U256,
+};
+use zksync_utils::{bytecode::hash_bytecode, h256_to_u256};
+
+/// These are used by the L1 contracts to indicate what DA layer is used for pubdata.
+const PUBDATA_SOURCE_CALLDATA: u8 = 0;
+const PUBDATA_SOURCE_BLOBS: u8 = 1;
+/// Size of a pubdata commitment for a single blob: opening point (16 bytes), opening value (32 bytes),
+/// KZG commitment (48 bytes) and opening proof (48 bytes).
+const PUBDATA_COMMITMENT_SIZE: usize = 144;
+/// Offset of the KZG commitment in a pubdata commitment.
+const KZG_COMMITMENT_OFFSET: usize = 48;
+const KZG_COMMITMENT_SIZE: usize = 48;
+
+/// Provider of EIP-4844 blobs used to publish pubdata.
+#[async_trait::async_trait]
+pub trait BlobProvider: fmt::Debug + Send + Sync {
+    /// Returns pubdata stored in the blob with the specified KZG commitment, or `None` if the blob is not available.
+    /// The returned data may be padded with zero bytes.
+    async fn get_blob(&self, kzg_commitment: &[u8]) -> anyhow::Result<Option<Vec<u8>>>;
+}
+
+/// Data about an L1 batch commitment extracted from a `BlockCommit` event.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct BlockCommitEvent {
+    pub tx_hash: H256,
+    pub l1_block_number: u64,
+    pub batch_hash: H256,
+    pub commitment: H256,
+}
+
+/// L1 batch data extracted from its commit transaction.
+#[derive(Debug, Clone)]
+pub(crate) struct CommittedBatch {
+    pub number: L1BatchNumber,
+    pub timestamp: u64,
+    pub index_repeated_storage_changes: u64,
+    pub new_state_root: H256,
+    pub number_of_layer1_txs: U256,
+    pub priority_operations_hash: H256,
+    pub l2_logs_tree_root: H256,
+    pub commitment: H256,
+    pub pubdata: Vec<u8>,
+}
+
+impl CommittedBatch {
+    pub fn stored_batch_info(&self) -> StoredBatchInfo {
+        StoredBatchInfo {
+            batch_number: self.number.0.into(),
+            batch_hash: self.new_state_root,
+            index_repeated_storage_changes: self.index_repeated_storage_changes,
+            number_of_layer1_txs: self.number_of_layer1_txs,
+            priority_operations_hash: self.priority_operations_hash,
+            l2_logs_tree_root: self.l2_logs_tree_root,
+            timestamp: self.timestamp.into(),
+            commitment: self.commitment,
+        }
+    }
+}
+
+/// Fetches L1 batch data from the diamond proxy contract and blobs.
+#[derive(Debug)]
+pub(crate) struct L1DataFetcher {
+    l1_client: Box<DynClient<L1>>,
+    contract: ethabi::Contract,
+    diamond_proxy_addr: Address,
+    blob_provider: Option<Arc<dyn BlobProvider>>,
+    /// Last fetched commit transaction and the `CommitBatchInfo` tokens decoded from it. Commit transactions
+    /// usually commit multiple batches at once, so this saves on L1 requests.
+    last_commit_tx: Option<(H256, Vec<Token>)>,
+}
+
+impl L1DataFetcher {
+    pub fn new(l1_client: Box<DynClient<L1>>, diamond_proxy_addr: Address) -> Self {
+        Self {
+            l1_client: l1_client.for_component("l1_recovery"),
+            contract: zksync_contracts::hyperchain_contract(),
+            diamond_proxy_addr,
+            blob_provider: None,
+            last_commit_tx: None,
+        }
+    }
+
+    pub fn with_blob_provider(mut self, blob_provider: Arc<dyn BlobProvider>) -> Self {
+        self.blob_provider = Some(blob_provider);
+        self
+    }
+
+    pub async fn total_batches_executed(&self) -> anyhow::Result<L1BatchNumber> {
+        let count: U256 = CallFunctionArgs::new("getTotalBatchesExecuted", ())
+            .for_contract(self.diamond_proxy_addr, &self.contract)
+            .call(&self.l1_client)
+            .await?;
+        let count = u32::try_from(count)
+            .map_err(|_| anyhow::anyhow!("L1 batch number overflow: {count}"))?;
+        Ok(L1BatchNumber(count))
+    }
+
+    pub async fn stored_batch_hash(&self, number: L1BatchNumber) -> anyhow::Result<H256> {
+        Ok(
+            CallFunctionArgs::new("storedBatchHash", U256::from(number.0))
+                .for_contract(self.diamond_proxy_addr, &self.contract)
+                .call(&self.l1_client)
+                .await?,
+        )
+    }
+
+    /// Returns the packed protocol semantic version active at the specified L1 block.
+    pub async fn protocol_version(&self, l1_block_number: u64) -> anyhow::Result<U256> {
+        Ok(CallFunctionArgs::new("getProtocolVersion", ())
+            .with_block(BlockId::Number(BlockNumber::Number(l1_block_number.into())))
+            .for_contract(self.diamond_proxy_addr, &self.contract)
+            .call(&self.l1_client)
+            .await?)
+    }
+
+    /// Scans `BlockCommit` events emitted by the diamond proxy starting from `first_l1_block`. If a batch was committed
+    /// several times (e.g., because of a revert), the latest commitment wins.
+    pub async fn block_commit_events(
+        &self,
+        first_l1_block: u64,
+        l1_block_range: u64,
+        l1_batches: RangeInclusive<L1BatchNumber>,
+    ) -> anyhow::Result<BTreeMap<L1BatchNumber, BlockCommitEvent>> {
+        anyhow::ensure!(l1_block_range > 0, "L1 block range must be positive");
+        let event_signature = self
+            .contract
+            .event("BlockCommit")
+            .context("L1 contract does not have `BlockCommit` event")?
+            .signature();
+        let last_l1_block = self.l1_client.block_number().await?.as_u64();
+
+        let mut events = BTreeMap::new();
+        let mut from_block = first_l1_block;
+        while from_block <= last_l1_block {
+            let to_block = from_block
+                .saturating_add(l1_block_range - 1)
+                .min(last_l1_block);
+            let filter = FilterBuilder::default()
+                .address(vec![self.diamond_proxy_addr])
+                .from_block(BlockNumber::Number(from_block.into()))
+                .to_block(BlockNumber::Number(to_block.into()))
+                .topics(Some(vec![event_signature]), None, None, None)
+                .build();
+            let logs = self.l1_client.logs(&filter).await?;
+            tracing::debug!(
+                "Fetched {} `BlockCommit` events from L1 blocks {from_block}..={to_block}",
+                logs.len()
+            );
+
+            for log in logs {
+                anyhow::ensure!(
+                    log.topics.len() == 4,
+                    "unexpected `BlockCommit` event topics: {:?}",
+                    log.topics
+                );
+                let batch_number = h256_to_u256(log.topics[1]);
+                let Ok(batch_number) = u32::try_from(batch_number) else {
+                    anyhow::bail!("L1 batch number overflow: {batch_number}");
+                };
+                let batch_number = L1BatchNumber(batch_number);
+                if !l1_batches.contains(&batch_number) {
+                    continue;
+                }
+                let event = BlockCommitEvent {
+                    tx_hash: log.transaction_hash.context("missing tx hash in event")?,
+                    l1_block_number: log
+                        .block_number
+                        .context("missing block number in event")?
+                        .as_u64(),
+                    batch_hash: log.topics[2],
+                    commitment: log.topics[3],
+                };
+                events.insert(batch_number, event);
+            }
+            from_block = to_block + 1;
+        }
+        Ok(events)
+    }
+
+    /// Collects factory deps supplied with L1 transactions (priority operations and protocol upgrade transactions)
+    /// in L1 blocks `first_l1_block..=last_l1_block`. Such bytecodes are not published in pubdata since they are
+    /// already available on L1. Bytecodes are checked against hashes in the corresponding transactions
+    /// during parsing.
+    pub async fn l1_tx_factory_deps(
+        &self,
+        first_l1_block: u64,
+        last_l1_block: u64,
+        l1_block_range: u64,
+    ) -> anyhow::Result<HashMap<H256, Vec<u8>>> {
+        anyhow::ensure!(l1_block_range > 0, "L1 block range must be positive");
+        let priority_request_signature = self
+            .contract
+            .event("NewPriorityRequest")
+            .context("L1 contract does not have `NewPriorityRequest` event")?
+            .signature();
+        let upgrade_signature = self
+            .contract
+            .event("ExecuteUpgrade")
+            .context("L1 contract does not have `ExecuteUpgrade` event")?
+            .signature();
+
+        let mut factory_deps = HashMap::new();
+        let mut from_block = first_l1_block;
+        while from_block <= last_l1_block {
+            let to_block = from_block
+                .saturating_add(l1_block_range - 1)
+                .min(last_l1_block);
+            let filter = FilterBuilder::default()
+                .address(vec![self.diamond_proxy_addr])
+                .from_block(BlockNumber::Number(from_block.into()))
+                .to_block(BlockNumber::Number(to_block.into()))
+                .topics(
+                    Some(vec![priority_request_signature, upgrade_signature]),
+                    None,
+                    None,
+                    None,
+                )
+                .build();
+            let logs = self.l1_client.logs(&filter).await?;
+            tracing::debug!(
+                "Fetched {} priority request / upgrade events from L1 blocks {from_block}..={to_block}",
+                logs.len()
+            );
+
+            for log in logs {
+                let tx_deps = if log.topics.first() == Some(&priority_request_signature) {
+                    let request = abi::NewPriorityRequest::decode(&log.data.0)
+                        .context("failed decoding `NewPriorityRequest` event")?;
+                    let tx = L1Tx::try_from(request)
+                        .context("invalid priority transaction in `NewPriorityRequest` event")?;
+                    tx.execute.factory_deps
+                } else {
+                    match ProtocolUpgrade::try_from_diamond_cut(&log.data.0) {
+                        Ok(upgrade) => upgrade
+                            .tx
+                            .map(|tx| tx.execute.factory_deps)
+                            .unwrap_or_default(),
+                        Err(err) => {
+                            // Diamond cuts not performing a protocol upgrade (e.g., ones only replacing facets)
+                            // don't have factory deps.
+                            tracing::info!(
+                                "Skipping `ExecuteUpgrade` event in L1 block {:?} that is not a protocol upgrade: {err:#}",
+                                log.block_number
+                            );
+                            continue;
+                        }
+                    }
+                };
+                factory_deps.extend(
+                    tx_deps
+                        .into_iter()
+                        .map(|bytecode| (hash_bytecode(&bytecode), bytecode)),
+                );
+            }
+            from_block = to_block + 1;
+        }
+        Ok(factory_deps)
+    }
+
+    /// Fetches and decodes batch data from the commit transaction referenced by `event`.
+    pub async fn committed_batch(
+        &mut self,
+        number: L1BatchNumber,
+        event: &BlockCommitEvent,
+    ) -> anyhow::Result<CommittedBatch> {
+        let last_tx_hash = self.last_commit_tx.as_ref().map(|(tx_hash, _)| *tx_hash);
+        if last_tx_hash != Some(event.tx_hash) {
+            let tokens = self.fetch_commit_tx(event.tx_hash).await?;
+            self.last_commit_tx = Some((event.tx_hash, tokens));
+        }
+        let (_, batch_tokens) = self.last_commit_tx.as_ref().unwrap();
+
+        let batch_token = batch_tokens
+            .iter()
+            .find(|token| {
+                let Token::Tuple(fields) = token else {
+                    return false;
+                };
+                matches!(fields.first(), Some(Token::Uint(value)) if *value == number.0.into())
+            })
+            .with_context(|| {
+                format!(
+                    "commit transaction {:?} doesn't contain data for L1 batch #{number}",
+                    event.tx_hash
+                )
+            })?
+            .clone();
+        let (mut batch, pubdata_commitments) =
+            parse_commit_batch_info(batch_token, event.commitment)?;
+        batch.pubdata = self
+            .fetch_pubdata(&pubdata_commitments)
+            .await
+            .with_context(|| format!("failed fetching pubdata for L1 batch #{number}"))?;
+        Ok(batch)
+    }
+
+    async fn fetch_commit_tx(&self, tx_hash: H256) -> anyhow::Result<Vec<Token>> {
+        let tx = self
+            .l1_client
+            .get_tx(tx_hash)
+            .await?
+            .with_context(|| format!("commit transaction {tx_hash:?} is not found on L1"))?;
+        let commit_function = self
+            .contract
+            .function("commitBatchesSharedBridge")
+            .context("L1 contract does not have `commitBatchesSharedBridge` function")?;
+
+        let input = &tx.input.0;
+        anyhow::ensure!(
+            input.len() >= 4 && input[..4] == commit_function.short_signature(),
+            "transaction {tx_hash:?} doesn't call `commitBatchesSharedBridge`; batches committed \
+             with older protocol versions cannot be recovered from L1"
+        );
+        let mut tokens = commit_function
+            .decode_input(&input[4..])
+            .with_context(|| format!("failed decoding calldata of transaction {tx_hash:?}"))?;
+        tokens
+            .pop()
+            .and_then(Token::into_array)
+            .context("unexpected signature for L1 commit function")
+    }
+
+    async fn fetch_pubdata(&self, pubdata_commitments: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let Some((&source, data)) = pubdata_commitments.split_first() else {
+            anyhow::bail!("pubdata commitments are empty");
+        };
+        match source {
+            PUBDATA_SOURCE_CALLDATA => {
+                // Calldata consists of the pubdata and its 32-byte blob commitment.
+                anyhow::ensure!(
+                    data.len() >= 32,
+                    "L1 batch doesn't publish pubdata; only rollup batches can be recovered from L1"
+                );
+                let (pubdata, blob_commitment) = data.split_at(data.len() - 32);
+                anyhow::ensure!(
+                    pubdata.len() <= ZK_SYNC_BYTES_PER_BLOB,
+                    "pubdata is too large: {} bytes",
+                    pubdata.len()
+                );
+                anyhow::ensure!(
+                    KzgInfo::new(pubdata).to_blob_commitment()[..] == *blob_commitment,
+                    "pubdata doesn't match its blob commitment"
+                );
+                Ok(pubdata.to_vec())
+            }
+            PUBDATA_SOURCE_BLOBS => {
+                anyhow::ensure!(
+                    data.len() % PUBDATA_COMMITMENT_SIZE == 0,
+                    "unexpected length of blob commitments: {}",
+                    data.len()
+                );
+                let blob_provider = self
+                    .blob_provider
+                    .as_ref()
+                    .context("L1 batch publishes pubdata in blobs, but blob provider is not configured")?;
+
+                let mut pubdata = vec![];
+                for (i, commitment) in data.chunks(PUBDATA_COMMITMENT_SIZE).enumerate() {
+                    let kzg_commitment = &commitment
+                        [KZG_COMMITMENT_OFFSET..KZG_COMMITMENT_OFFSET + KZG_COMMITMENT_SIZE];
+                    let blob = blob_provider
+                        .get_blob(kzg_commitment)
+                        .await
+                        .with_context(|| format!("failed getting blob #{i}"))?
+                        .with_context(|| format!("blob #{i} is not available"))?;
+                    anyhow::ensure!(
+                        blob.len() <= ZK_SYNC_BYTES_PER_BLOB,
+                        "blob #{i} is too large: {} bytes",
+                        blob.len()
+                    );
+                    anyhow::ensure!(
+                        KzgInfo::new(&blob).to_pubdata_commitment()[..] == *commitment,
+                        "blob #{i} doesn't match its commitment"
+                    );
+                    pubdata.extend(blob);
+                }
+                Ok(pubdata)
+            }
+            _ => anyhow::bail!(
+                "unsupported pubdata source: {source}; only pubdata published in calldata or blobs can be recovered"
+            ),
+        }
+    }
+}
+
+/// Parses a `CommitBatchInfo` token. Returns the parsed batch without pubdata and raw pubdata commitments.
+fn parse_commit_batch_info(
+    token: Token,
+    commitment: H256,
+) -> anyhow::Result<(CommittedBatch, Vec<u8>)> {
+    const FIELD_COUNT: usize = 10;
+
+    let Token::Tuple(fields) = token else {
+        anyhow::bail!("unexpected `CommitBatchInfo` token: {token:?}");
+    };
+    let fields: [Token; FIELD_COUNT] = fields.try_into().map_err(|fields: Vec<_>| {
+        anyhow::anyhow!(
+            "unexpected number of `CommitBatchInfo` fields: {}",
+            fields.len()
+        )
+    })?;
+    let [batch_number, timestamp, index_repeated_storage_changes, new_state_root, number_of_layer1_txs, priority_operations_hash, _bootloader_heap_initial_contents_hash, _events_queue_state_hash, system_logs, pubdata_commitments] =
+        fields;
+
+    let batch_number = batch_number.into_uint().context("`batchNumber`")?;
+    let timestamp = timestamp.into_uint().context("`timestamp`")?;
+    let index_repeated_storage_changes = index_repeated_storage_changes
+        .into_uint()
+        .context("`indexRepeatedStorageChanges`")?;
+    let system_logs = system_logs.into_bytes().context("`systemLogs`")?;
+    anyhow::ensure!(
+        system_logs.len() % L2ToL1Log::SERIALIZED_SIZE == 0,
+        "unexpected length of system logs: {}",
+        system_logs.len()
+    );
+    let l2_logs_tree_root_key = H256::from_low_u64_be(L2_TO_L1_LOGS_TREE_ROOT_KEY.into());
+    let l2_logs_tree_root = system_logs
+        .chunks(L2ToL1Log::SERIALIZED_SIZE)
+        .map(L2ToL1Log::from_slice)
+        .find(|log| log.sender == L1_MESSENGER_ADDRESS && log.key == l2_logs_tree_root_key)
+        .context("system logs don't contain L2-to-L1 logs tree root")?
+        .value;
+
+    let batch = CommittedBatch {
+        number: L1BatchNumber(
+            batch_number
+                .try_into()
+                .map_err(|_| anyhow::anyhow!("L1 batch number overflow: {batch_number}"))?,
+        ),
+        timestamp: timestamp
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("timestamp overflow: {timestamp}"))?,
+        index_repeated_storage_changes: index_repeated_storage_changes.try_into().map_err(
+            |_| anyhow::anyhow!("enumeration index overflow: {index_repeated_storage_changes}"),
+        )?,
+        new_state_root: fixed_bytes_to_h256(new_state_root).context("`newStateRoot`")?,
+        number_of_layer1_txs: number_of_layer1_txs
+            .into_uint()
+            .context("`numberOfLayer1Txs`")?,
+        priority_operations_hash: fixed_bytes_to_h256(priority_operations_hash)
+            .context("`priorityOperationsHash`")?,
+        l2_logs_tree_root,
+        commitment,
+        pubdata: vec![],
+    };
+    let pubdata_commitments = pubdata_commitments
+        .into_bytes()
+        .context("`pubdataCommitments`")?;
+    Ok((batch, pubdata_commitments))
+}
+
+fn fixed_bytes_to_h256(token: Token) -> Option<H256> {
+    let bytes = token.into_fixed_bytes()?;
+    (bytes.len() == 32).then(|| H256::from_slice(&bytes))
+}
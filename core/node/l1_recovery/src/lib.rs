@@ -0,0 +1,462 @@
+//! Recovery of node state exclusively from data published on L1.
+//!
+//! The recovery scans `BlockCommit` events emitted by the diamond proxy contract, decodes pubdata of all committed
+//! L1 batches from their commit transactions (or from blobs referenced by them), and reconstructs storage state
+//! by replaying state diffs on top of the genesis state. Bytecodes not published in pubdata (i.e., factory deps
+//! supplied with priority operations and protocol upgrade transactions) are taken from the corresponding L1 events.
+//! The state is verified by rebuilding the Merkle tree and comparing hashes of the `StoredBatchInfo` structs
+//! with ones stored in the diamond proxy contract; thus, the recovery doesn't need to trust the main node.
+//!
+//! The recovered state is persisted to Postgres in the same way as snapshot recovery does it, so that
+//! the node can proceed from the recovered L1 batch as if it was recovered from a snapshot.
+
+use std::{collections::HashMap, path::PathBuf, sync::Arc, time::Instant};
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_eth_client::clients::{DynClient, L1};
+use zksync_l1_contract_interface::i_executor::structures::StoredBatchInfo;
+use zksync_merkle_tree::RocksDBWrapper;
+use zksync_node_genesis::{genesis_factory_deps, genesis_storage_writes, GenesisParams};
+use zksync_system_constants::{
+    SYSTEM_CONTEXT_CURRENT_L2_BLOCK_HASHES_POSITION, SYSTEM_CONTEXT_CURRENT_L2_BLOCK_INFO_POSITION,
+    SYSTEM_CONTEXT_CURRENT_TX_ROLLING_HASH_POSITION, SYSTEM_CONTEXT_STORED_L2_BLOCK_HASHES,
+};
+use zksync_types::{
+    block::{unpack_block_info, L2BlockHasher},
+    get_known_code_key, get_system_context_key,
+    protocol_version::ProtocolSemanticVersion,
+    snapshots::SnapshotRecoveryStatus,
+    web3::keccak256,
+    Address, L1BatchNumber, L2BlockNumber, H256, U256,
+};
+use zksync_utils::{h256_to_u256, u256_to_h256};
+
+pub use crate::{blob_api::BlobApiClient, l1_data::BlobProvider};
+use crate::{
+    l1_data::{CommittedBatch, L1DataFetcher},
+    pubdata::ParsedPubdata,
+    state::RecoveredState,
+};
+
+mod blob_api;
+mod l1_data;
+mod pubdata;
+mod state;
+#[cfg(test)]
+mod tests;
+
+/// Number of storage logs persisted to Postgres in a single chunk.
+const STORAGE_LOGS_CHUNK_SIZE: usize = 100_000;
+/// Number of factory deps persisted to Postgres in a single chunk.
+const FACTORY_DEPS_CHUNK_SIZE: usize = 1_000;
+
+/// Configuration for [`L1Recovery`].
+#[derive(Debug, Clone)]
+pub struct L1RecoveryConfig {
+    /// Address of the diamond proxy contract of the chain on L1.
+    pub diamond_proxy_addr: Address,
+    /// First L1 block to scan for `BlockCommit` events; should be set to the block the diamond proxy was deployed in.
+    pub first_l1_block: u64,
+    /// Maximum number of L1 blocks to query events for in a single `eth_getLogs` call.
+    pub l1_block_range: u64,
+    /// L1 batch to recover. If not specified, the last L1 batch executed on L1 will be recovered.
+    pub l1_batch: Option<L1BatchNumber>,
+    /// Directory for a temporary Merkle tree used to verify the recovered state. It is removed after recovery.
+    pub tree_path: PathBuf,
+}
+
+/// Recovers node storage from L1 data. See the crate-level docs for details.
+#[derive(Debug)]
+pub struct L1Recovery {
+    config: L1RecoveryConfig,
+    pool: ConnectionPool<Core>,
+    genesis_params: GenesisParams,
+    fetcher: L1DataFetcher,
+}
+
+impl L1Recovery {
+    /// Creates a new recovery. `genesis_params` may be provided by an untrusted source (e.g., the main node);
+    /// they are verified against the genesis L1 batch hash stored on L1.
+    pub fn new(
+        config: L1RecoveryConfig,
+        l1_client: Box<DynClient<L1>>,
+        pool: ConnectionPool<Core>,
+        genesis_params: GenesisParams,
+    ) -> Self {
+        Self {
+            fetcher: L1DataFetcher::new(l1_client, config.diamond_proxy_addr),
+            config,
+            pool,
+            genesis_params,
+        }
+    }
+
+    /// Sets the provider of blobs. Required if the chain published pubdata in blobs.
+    pub fn with_blob_provider(mut self, blob_provider: Arc<dyn BlobProvider>) -> Self {
+        self.fetcher = self.fetcher.with_blob_provider(blob_provider);
+        self
+    }
+
+    /// Runs the recovery. Returns `Ok(None)` if the recovery was interrupted by a stop signal.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if Postgres is not empty, L1 data cannot be fetched, or the recovered state doesn't match
+    /// data stored on L1.
+    pub async fn run(
+        mut self,
+        stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<Option<SnapshotRecoveryStatus>> {
+        let started_at = Instant::now();
+        self.ensure_empty_storage().await?;
+
+        let target_l1_batch = match self.config.l1_batch {
+            Some(number) => number,
+            None => self.fetcher.total_batches_executed().await?,
+        };
+        anyhow::ensure!(
+            target_l1_batch > L1BatchNumber(0),
+            "no L1 batches to recover; use genesis instead"
+        );
+        tracing::info!("Starting recovery of L1 batch #{target_l1_batch} from L1 data");
+
+        let batch_range = L1BatchNumber(1)..=target_l1_batch;
+        let events = self
+            .fetcher
+            .block_commit_events(
+                self.config.first_l1_block,
+                self.config.l1_block_range,
+                batch_range.clone(),
+            )
+            .await?;
+        tracing::info!("Fetched {} `BlockCommit` events from L1", events.len());
+
+        let tree_path = self.config.tree_path.clone();
+        if tree_path.exists() {
+            tracing::info!(
+                "Removing Merkle tree left from the previous recovery attempt at `{}`",
+                tree_path.display()
+            );
+            tokio::fs::remove_dir_all(&tree_path)
+                .await
+                .with_context(|| {
+                    format!("failed removing Merkle tree at `{}`", tree_path.display())
+                })?;
+        }
+        let genesis_writes = genesis_storage_writes(&self.genesis_params);
+        let (mut state, genesis_root_hash) = tokio::task::spawn_blocking(move || {
+            let db = RocksDBWrapper::new(&tree_path).context("failed opening Merkle tree")?;
+            let mut state = RecoveredState::new(db)?;
+            let root_hash = state.apply_genesis(&genesis_writes)?;
+            anyhow::Ok((state, root_hash))
+        })
+        .await
+        .context("panicked initializing Merkle tree")??;
+        self.verify_genesis(&state, genesis_root_hash).await?;
+        tracing::info!("Verified genesis L1 batch with root hash {genesis_root_hash:?}");
+
+        let mut factory_deps = genesis_factory_deps(&self.genesis_params);
+        let mut last_batch = None;
+        for number in batch_range {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, L1 recovery is shutting down");
+                return Ok(None);
+            }
+
+            let event = events
+                .get(&number)
+                .with_context(|| format!("no `BlockCommit` event for L1 batch #{number} on L1"))?;
+            let batch = self.fetcher.committed_batch(number, event).await?;
+            let pubdata = ParsedPubdata::parse(&batch.pubdata)
+                .with_context(|| format!("failed parsing pubdata for L1 batch #{number}"))?;
+            factory_deps.extend(pubdata.factory_deps()?);
+
+            let (new_state, root_hash) = with_state(state, move |state| {
+                state.apply_batch(number, &pubdata.state_diffs)
+            })
+            .await
+            .with_context(|| format!("failed applying state diffs for L1 batch #{number}"))?;
+            state = new_state;
+            self.verify_batch(&state, &batch, event.batch_hash, root_hash)
+                .await
+                .with_context(|| format!("failed verifying L1 batch #{number}"))?;
+            tracing::info!(
+                "Recovered and verified L1 batch #{number} with root hash {root_hash:?}"
+            );
+            last_batch = Some((batch, event.l1_block_number));
+        }
+        let (last_batch, commit_l1_block) = last_batch.context("no L1 batches recovered")?;
+        self.add_l1_tx_factory_deps(&state, &mut factory_deps, commit_l1_block)
+            .await?;
+
+        let status = self
+            .recovery_status(&state, &last_batch, commit_l1_block)
+            .await?;
+        tracing::info!("Persisting recovered state to Postgres: {status:?}");
+        self.persist(state, factory_deps, &status).await?;
+
+        let tree_path = &self.config.tree_path;
+        if let Err(err) = tokio::fs::remove_dir_all(tree_path).await {
+            tracing::warn!(
+                "Failed removing temporary Merkle tree at `{}`: {err}",
+                tree_path.display()
+            );
+        }
+        tracing::info!(
+            "Recovered L1 batch #{target_l1_batch} from L1 in {:?}",
+            started_at.elapsed()
+        );
+        Ok(Some(status))
+    }
+
+    async fn ensure_empty_storage(&self) -> anyhow::Result<()> {
+        let mut storage = self.pool.connection_tagged("l1_recovery").await?;
+        anyhow::ensure!(
+            storage.blocks_dal().is_genesis_needed().await?,
+            "Postgres already contains L1 batches; L1 recovery can only be performed on an empty database"
+        );
+        let snapshot_status = storage
+            .snapshot_recovery_dal()
+            .get_applied_snapshot_status()
+            .await?;
+        anyhow::ensure!(
+            snapshot_status.is_none(),
+            "Postgres already contains recovered state: {snapshot_status:?}"
+        );
+        Ok(())
+    }
+
+    async fn verify_genesis(&self, state: &RecoveredState, root_hash: H256) -> anyhow::Result<()> {
+        let config = self.genesis_params.config();
+        let expected_root_hash = config
+            .genesis_root_hash
+            .context("genesis root hash is not set")?;
+        anyhow::ensure!(
+            root_hash == expected_root_hash,
+            "genesis root hash mismatch: recovered {root_hash:?}, expected {expected_root_hash:?}"
+        );
+        let rollup_last_leaf_index = state.next_enumeration_index();
+        let expected_leaf_index = config
+            .rollup_last_leaf_index
+            .context("genesis rollup last leaf index is not set")?;
+        anyhow::ensure!(
+            rollup_last_leaf_index == expected_leaf_index,
+            "genesis rollup last leaf index mismatch: recovered {rollup_last_leaf_index}, expected {expected_leaf_index}"
+        );
+
+        let genesis_batch_info = StoredBatchInfo {
+            batch_number: 0,
+            batch_hash: root_hash,
+            index_repeated_storage_changes: rollup_last_leaf_index,
+            number_of_layer1_txs: U256::zero(),
+            priority_operations_hash: H256(keccak256(&[])),
+            // `l2_l1_merkle_root` for the genesis batch is set to 0 on L1.
+            l2_logs_tree_root: H256::zero(),
+            timestamp: U256::zero(),
+            commitment: config
+                .genesis_commitment
+                .context("genesis commitment is not set")?,
+        };
+        self.verify_stored_batch_hash(L1BatchNumber(0), &genesis_batch_info)
+            .await
+    }
+
+    async fn verify_batch(
+        &self,
+        state: &RecoveredState,
+        batch: &CommittedBatch,
+        event_batch_hash: H256,
+        root_hash: H256,
+    ) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            root_hash == batch.new_state_root,
+            "root hash mismatch: recovered {root_hash:?}, committed {:?}",
+            batch.new_state_root
+        );
+        anyhow::ensure!(
+            root_hash == event_batch_hash,
+            "root hash mismatch: recovered {root_hash:?}, `BlockCommit` event has {event_batch_hash:?}"
+        );
+        let next_index = state.next_enumeration_index();
+        anyhow::ensure!(
+            next_index == batch.index_repeated_storage_changes,
+            "enumeration index mismatch: recovered {next_index}, committed {}",
+            batch.index_repeated_storage_changes
+        );
+        self.verify_stored_batch_hash(batch.number, &batch.stored_batch_info())
+            .await
+    }
+
+    async fn verify_stored_batch_hash(
+        &self,
+        number: L1BatchNumber,
+        batch_info: &StoredBatchInfo,
+    ) -> anyhow::Result<()> {
+        let stored_hash = self.fetcher.stored_batch_hash(number).await?;
+        let batch_hash = batch_info.hash();
+        anyhow::ensure!(
+            batch_hash == stored_hash,
+            "hash of L1 batch #{number} mismatch: recovered {batch_hash:?}, stored on L1 {stored_hash:?} \
+             (recovered batch info: {batch_info:?})"
+        );
+        Ok(())
+    }
+
+    /// Adds factory deps supplied with L1 transactions, which are not published in pubdata. Only bytecodes
+    /// marked as known in the recovered state are added; other bytecodes (e.g., ones for priority operations
+    /// not executed in the recovered L1 batches) are skipped.
+    async fn add_l1_tx_factory_deps(
+        &self,
+        state: &RecoveredState,
+        factory_deps: &mut HashMap<H256, Vec<u8>>,
+        last_l1_block: u64,
+    ) -> anyhow::Result<()> {
+        let l1_tx_factory_deps = self
+            .fetcher
+            .l1_tx_factory_deps(
+                self.config.first_l1_block,
+                last_l1_block,
+                self.config.l1_block_range,
+            )
+            .await
+            .context("failed fetching factory deps for L1 transactions")?;
+
+        let mut added_count = 0;
+        for (hash, bytecode) in l1_tx_factory_deps {
+            if factory_deps.contains_key(&hash) {
+                continue;
+            }
+            let is_known = !state.value(&get_known_code_key(&hash))?.is_zero();
+            if is_known {
+                factory_deps.insert(hash, bytecode);
+                added_count += 1;
+            } else {
+                tracing::debug!("Skipping bytecode {hash:?} from L1 transaction: it's not marked as known in recovered state");
+            }
+        }
+        tracing::info!("Added {added_count} factory deps supplied with L1 transactions");
+        Ok(())
+    }
+
+    /// Extracts info about the last L2 block in the recovered batch from the system context contract storage.
+    async fn recovery_status(
+        &self,
+        state: &RecoveredState,
+        batch: &CommittedBatch,
+        commit_l1_block: u64,
+    ) -> anyhow::Result<SnapshotRecoveryStatus> {
+        let packed_version = self.fetcher.protocol_version(commit_l1_block).await?;
+        let protocol_version = ProtocolSemanticVersion::try_from_packed(packed_version)
+            .map_err(|err| anyhow::anyhow!("invalid protocol version on L1: {err}"))?;
+
+        let block_info = state.value(&get_system_context_key(
+            SYSTEM_CONTEXT_CURRENT_L2_BLOCK_INFO_POSITION,
+        ))?;
+        let (l2_block_number, l2_block_timestamp) = unpack_block_info(h256_to_u256(block_info));
+        let l2_block_number = u32::try_from(l2_block_number)
+            .map_err(|_| anyhow::anyhow!("L2 block number overflow: {l2_block_number}"))?;
+        anyhow::ensure!(
+            l2_block_number > 0,
+            "recovered state doesn't contain L2 block info"
+        );
+        let prev_block_hash_position =
+            h256_to_u256(SYSTEM_CONTEXT_CURRENT_L2_BLOCK_HASHES_POSITION)
+                + U256::from((l2_block_number - 1) % SYSTEM_CONTEXT_STORED_L2_BLOCK_HASHES);
+        let prev_block_hash = state.value(&get_system_context_key(u256_to_h256(
+            prev_block_hash_position,
+        )))?;
+        let txs_rolling_hash = state.value(&get_system_context_key(
+            SYSTEM_CONTEXT_CURRENT_TX_ROLLING_HASH_POSITION,
+        ))?;
+        let l2_block_number = L2BlockNumber(l2_block_number);
+        let l2_block_hash = L2BlockHasher::hash(
+            l2_block_number,
+            l2_block_timestamp,
+            prev_block_hash,
+            txs_rolling_hash,
+            protocol_version.minor,
+        );
+
+        let chunk_count = state.storage_log_count().div_ceil(STORAGE_LOGS_CHUNK_SIZE);
+        Ok(SnapshotRecoveryStatus {
+            l1_batch_number: batch.number,
+            l1_batch_root_hash: batch.new_state_root,
+            l1_batch_timestamp: batch.timestamp,
+            l2_block_number,
+            l2_block_hash,
+            l2_block_timestamp,
+            protocol_version: protocol_version.minor,
+            storage_logs_chunks_processed: vec![true; chunk_count],
+        })
+    }
+
+    async fn persist(
+        &self,
+        mut state: RecoveredState,
+        factory_deps: HashMap<H256, Vec<u8>>,
+        status: &SnapshotRecoveryStatus,
+    ) -> anyhow::Result<()> {
+        let mut storage = self.pool.connection_tagged("l1_recovery").await?;
+        let mut transaction = storage.start_transaction().await?;
+
+        let factory_deps: Vec<_> = factory_deps.into_iter().collect();
+        for chunk in factory_deps.chunks(FACTORY_DEPS_CHUNK_SIZE) {
+            let chunk: HashMap<_, _> = chunk.iter().cloned().collect();
+            transaction
+                .factory_deps_dal()
+                .insert_factory_deps(status.l2_block_number, &chunk)
+                .await?;
+        }
+        tracing::info!("Persisted {} factory deps", factory_deps.len());
+
+        let log_count = state.storage_log_count();
+        for start_index in (0..log_count).step_by(STORAGE_LOGS_CHUNK_SIZE) {
+            let (new_state, storage_logs) = with_state(state, move |state| {
+                state.storage_logs(start_index, STORAGE_LOGS_CHUNK_SIZE)
+            })
+            .await?;
+            state = new_state;
+            transaction
+                .storage_logs_dal()
+                .insert_storage_logs_from_snapshot(status.l2_block_number, &storage_logs)
+                .await?;
+            transaction
+                .storage_logs_dedup_dal()
+                .insert_initial_writes_from_snapshot(&storage_logs)
+                .await?;
+        }
+        tracing::info!("Persisted {log_count} storage logs");
+
+        transaction
+            .snapshot_recovery_dal()
+            .insert_initial_recovery_status(status)
+            .await?;
+        // Insert artificial entries into the pruning log so that it matches the recovery metadata,
+        // same as snapshot recovery does.
+        transaction
+            .pruning_dal()
+            .soft_prune_batches_range(status.l1_batch_number, status.l2_block_number)
+            .await?;
+        transaction
+            .pruning_dal()
+            .hard_prune_batches_range(status.l1_batch_number, status.l2_block_number)
+            .await?;
+        transaction.commit().await?;
+        Ok(())
+    }
+}
+
+/// Runs a potentially blocking operation on the recovered state on a blocking thread.
+async fn with_state<T: Send + 'static>(
+    mut state: RecoveredState,
+    operation: impl FnOnce(&mut RecoveredState) -> anyhow::Result<T> + Send + 'static,
+) -> anyhow::Result<(RecoveredState, T)> {
+    tokio::task::spawn_blocking(move || {
+        let output = operation(&mut state)?;
+        Ok((state, output))
+    })
+    .await
+    .context("panicked while processing recovered state")?
+}
@@ -0,0 +1,254 @@
+//! Parsing pubdata published by L1 batches.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Context as _;
+use zksync_system_constants::{COMPRESSOR_ADDRESS, L1_MESSENGER_ADDRESS};
+use zksync_types::{
+    commitment::SerializeCommitment,
+    l2_to_l1_log::L2ToL1Log,
+    web3::keccak256,
+    writes::{compression::COMPRESSION_VERSION_NUMBER, BYTES_PER_ENUMERATION_INDEX},
+    H256, U256,
+};
+use zksync_utils::{address_to_h256, bytecode::hash_bytecode};
+
+/// Key of a state diff published in pubdata.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum StateDiffKey {
+    /// Initial write identified by the hashed storage key.
+    Initial(H256),
+    /// Repeated write identified by the enumeration index of the key.
+    Repeated(u64),
+}
+
+/// New value of a storage slot compressed relative to its previous value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CompressedValue {
+    /// Uncompressed value.
+    Full(U256),
+    /// The value should be added to the previous value.
+    Add(U256),
+    /// The value should be subtracted from the previous value.
+    Sub(U256),
+    /// The value replaces the previous value, but is encoded with fewer than 32 bytes.
+    Transform(U256),
+}
+
+impl CompressedValue {
+    /// Parses a value with the metadata byte encoded by `compress_with_best_strategy()`.
+    fn parse(cursor: &mut ByteCursor<'_>) -> anyhow::Result<Self> {
+        let metadata = cursor.read_u8()?;
+        let operation = metadata & 0b111;
+        let len = usize::from(metadata >> 3);
+        if operation == 0 {
+            anyhow::ensure!(len == 0, "unexpected length {len} for uncompressed value");
+            return Ok(Self::Full(U256::from_big_endian(cursor.read_bytes(32)?)));
+        }
+
+        anyhow::ensure!(len < 32, "unexpected length {len} for compressed value");
+        let value = U256::from_big_endian(cursor.read_bytes(len)?);
+        Ok(match operation {
+            1 => Self::Add(value),
+            2 => Self::Sub(value),
+            3 => Self::Transform(value),
+            _ => anyhow::bail!("unknown compression operation: {operation}"),
+        })
+    }
+
+    /// Applies this value to the previous value of the slot.
+    pub(crate) fn apply(self, prev_value: U256) -> U256 {
+        match self {
+            Self::Full(value) | Self::Transform(value) => value,
+            Self::Add(diff) => prev_value.overflowing_add(diff).0,
+            Self::Sub(diff) => prev_value.overflowing_sub(diff).0,
+        }
+    }
+}
+
+/// Pubdata of a single L1 batch.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ParsedPubdata {
+    pub user_logs: Vec<L2ToL1Log>,
+    pub l2_to_l1_messages: Vec<Vec<u8>>,
+    pub published_bytecodes: Vec<Vec<u8>>,
+    /// State diffs in the order of their publication; initial writes come first.
+    pub state_diffs: Vec<(StateDiffKey, CompressedValue)>,
+}
+
+impl ParsedPubdata {
+    /// Parses pubdata in the format produced by the L1 messenger contract. Trailing zero bytes (e.g., padding
+    /// of the last blob) are ignored.
+    pub(crate) fn parse(pubdata: &[u8]) -> anyhow::Result<Self> {
+        let mut cursor = ByteCursor::new(pubdata);
+
+        let log_count = cursor
+            .read_u32()
+            .context("failed reading number of user logs")?;
+        let user_logs = (0..log_count)
+            .map(|_| {
+                let log = cursor.read_bytes(L2ToL1Log::SERIALIZED_SIZE)?;
+                Ok(L2ToL1Log::from_slice(log))
+            })
+            .collect::<anyhow::Result<_>>()
+            .context("failed reading user logs")?;
+        let l2_to_l1_messages = cursor
+            .read_byte_arrays()
+            .context("failed reading L2-to-L1 messages")?;
+        let published_bytecodes = cursor
+            .read_byte_arrays()
+            .context("failed reading published bytecodes")?;
+        let state_diffs =
+            Self::parse_state_diffs(&mut cursor).context("failed reading state diffs")?;
+
+        anyhow::ensure!(
+            cursor.remaining().iter().all(|&byte| byte == 0),
+            "pubdata has {} unexpected trailing bytes",
+            cursor.remaining().len()
+        );
+        Ok(Self {
+            user_logs,
+            l2_to_l1_messages,
+            published_bytecodes,
+            state_diffs,
+        })
+    }
+
+    /// Inverse of `compress_state_diffs()` from `zksync_types`.
+    fn parse_state_diffs(
+        cursor: &mut ByteCursor<'_>,
+    ) -> anyhow::Result<Vec<(StateDiffKey, CompressedValue)>> {
+        let version = cursor.read_u8()?;
+        anyhow::ensure!(
+            version == COMPRESSION_VERSION_NUMBER,
+            "unsupported state diff compression version: {version}"
+        );
+        let len = cursor.read_bytes(3)?;
+        let len = u32::from_be_bytes([0, len[0], len[1], len[2]]) as usize;
+        let enumeration_index_size = cursor.read_u8()?;
+        anyhow::ensure!(
+            enumeration_index_size == BYTES_PER_ENUMERATION_INDEX,
+            "unsupported enumeration index size: {enumeration_index_size}"
+        );
+
+        let mut cursor = ByteCursor::new(cursor.read_bytes(len)?);
+        let initial_write_count = cursor.read_u16()?;
+        let mut state_diffs = Vec::with_capacity(initial_write_count.into());
+        for _ in 0..initial_write_count {
+            let hashed_key = H256::from_slice(cursor.read_bytes(32)?);
+            let value = CompressedValue::parse(&mut cursor)?;
+            state_diffs.push((StateDiffKey::Initial(hashed_key), value));
+        }
+        while !cursor.remaining().is_empty() {
+            let enumeration_index = cursor.read_u32()?;
+            let value = CompressedValue::parse(&mut cursor)?;
+            state_diffs.push((StateDiffKey::Repeated(enumeration_index.into()), value));
+        }
+        Ok(state_diffs)
+    }
+
+    /// Returns bytecodes published in this batch keyed by their hashes. Besides uncompressed bytecodes, this includes
+    /// bytecodes published by the compressor contract as L2-to-L1 messages.
+    pub(crate) fn factory_deps(&self) -> anyhow::Result<HashMap<H256, Vec<u8>>> {
+        let compressor_key = address_to_h256(&COMPRESSOR_ADDRESS);
+        let compressed_bytecode_hashes: HashSet<_> = self
+            .user_logs
+            .iter()
+            .filter(|log| log.sender == L1_MESSENGER_ADDRESS && log.key == compressor_key)
+            .map(|log| log.value)
+            .collect();
+
+        let mut factory_deps: HashMap<_, _> = self
+            .published_bytecodes
+            .iter()
+            .map(|bytecode| (hash_bytecode(bytecode), bytecode.clone()))
+            .collect();
+        for message in &self.l2_to_l1_messages {
+            if compressed_bytecode_hashes.contains(&H256(keccak256(message))) {
+                let bytecode = decompress_bytecode(message)?;
+                factory_deps.insert(hash_bytecode(&bytecode), bytecode);
+            }
+        }
+        Ok(factory_deps)
+    }
+}
+
+/// Inverse of the bytecode compression performed by the VM. The compressed bytecode has the following format:
+///
+/// - 2 bytes: the number of entries in the dictionary (N)
+/// - N * 8 bytes: dictionary entries (8-byte bytecode chunks)
+/// - remaining bytes: 2-byte indices of bytecode chunks in the dictionary
+pub(crate) fn decompress_bytecode(compressed: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let mut cursor = ByteCursor::new(compressed);
+    let dictionary_len = usize::from(cursor.read_u16()?);
+    let dictionary = cursor
+        .read_bytes(dictionary_len * 8)
+        .context("compressed bytecode dictionary is truncated")?;
+    let encoded_data = cursor.remaining();
+    anyhow::ensure!(
+        encoded_data.len() % 2 == 0,
+        "compressed bytecode has odd encoded data length"
+    );
+
+    let mut bytecode = Vec::with_capacity(encoded_data.len() * 4);
+    for index in encoded_data.chunks(2) {
+        let index = usize::from(u16::from_be_bytes([index[0], index[1]]));
+        anyhow::ensure!(
+            index < dictionary_len,
+            "compressed bytecode references chunk #{index}, while its dictionary has {dictionary_len} entries"
+        );
+        bytecode.extend_from_slice(&dictionary[index * 8..(index + 1) * 8]);
+    }
+    Ok(bytecode)
+}
+
+#[derive(Debug)]
+struct ByteCursor<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> ByteCursor<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    fn remaining(&self) -> &'a [u8] {
+        self.data
+    }
+
+    fn read_bytes(&mut self, len: usize) -> anyhow::Result<&'a [u8]> {
+        anyhow::ensure!(
+            self.data.len() >= len,
+            "unexpected end of data: requested {len} bytes, {} remaining",
+            self.data.len()
+        );
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(bytes)
+    }
+
+    fn read_u8(&mut self) -> anyhow::Result<u8> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_u16(&mut self) -> anyhow::Result<u16> {
+        let bytes = self.read_bytes(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn read_u32(&mut self) -> anyhow::Result<u32> {
+        let bytes = self.read_bytes(4)?;
+        Ok(u32::from_be_bytes(bytes.try_into().unwrap()))
+    }
+
+    /// Reads byte arrays encoded as `count as u32 || (len as u32 || bytes)*`.
+    fn read_byte_arrays(&mut self) -> anyhow::Result<Vec<Vec<u8>>> {
+        let count = self.read_u32()?;
+        (0..count)
+            .map(|_| {
+                let len = self.read_u32()? as usize;
+                Ok(self.read_bytes(len)?.to_vec())
+            })
+            .collect()
+    }
+}
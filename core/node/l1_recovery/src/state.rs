@@ -0,0 +1,149 @@
+//! Storage state reconstructed from L1 data.
+
+use anyhow::Context as _;
+use zksync_merkle_tree::{MerkleTree, RocksDBWrapper, TreeEntry};
+use zksync_types::{snapshots::SnapshotStorageLog, L1BatchNumber, StorageKey, H256, U256};
+use zksync_utils::{h256_to_u256, u256_to_h256};
+
+use crate::pubdata::{CompressedValue, StateDiffKey};
+
+/// Storage state reconstructed from L1 data. Consists of a Merkle tree and an index of tree leaves, which is necessary
+/// to resolve repeated writes in pubdata (they refer to storage slots by enumeration indices).
+#[derive(Debug)]
+pub(crate) struct RecoveredState {
+    tree: MerkleTree<RocksDBWrapper>,
+    /// Hashed keys of tree leaves together with the L1 batch of their initial write, ordered by enumeration index
+    /// (i.e., the leaf with index 1 is the first one).
+    leaves: Vec<(H256, L1BatchNumber)>,
+}
+
+impl RecoveredState {
+    pub fn new(db: RocksDBWrapper) -> anyhow::Result<Self> {
+        let tree = MerkleTree::new(db)?;
+        anyhow::ensure!(
+            tree.latest_version().is_none(),
+            "Merkle tree used for L1 recovery is not empty"
+        );
+        Ok(Self {
+            tree,
+            leaves: vec![],
+        })
+    }
+
+    pub fn root_hash(&self) -> H256 {
+        self.tree.latest_root_hash()
+    }
+
+    /// Returns the enumeration index that will be assigned to the next inserted leaf.
+    pub fn next_enumeration_index(&self) -> u64 {
+        self.leaves.len() as u64 + 1
+    }
+
+    /// Applies storage writes from the genesis L1 batch. Writes must be ordered by their enumeration indices.
+    pub fn apply_genesis(&mut self, writes: &[(StorageKey, H256)]) -> anyhow::Result<H256> {
+        anyhow::ensure!(self.leaves.is_empty(), "genesis is already applied");
+        let entries = writes
+            .iter()
+            .enumerate()
+            .map(|(i, (key, value))| {
+                self.leaves.push((key.hashed_key(), L1BatchNumber(0)));
+                TreeEntry::new(key.hashed_key_u256(), i as u64 + 1, *value)
+            })
+            .collect();
+        Ok(self.tree.extend(entries)?.root_hash)
+    }
+
+    /// Applies state diffs published by an L1 batch and returns the new root hash of the tree.
+    pub fn apply_batch(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        state_diffs: &[(StateDiffKey, CompressedValue)],
+    ) -> anyhow::Result<H256> {
+        let version = self
+            .tree
+            .latest_version()
+            .context("genesis is not applied")?;
+        let keys = state_diffs
+            .iter()
+            .map(|(key, _)| match *key {
+                StateDiffKey::Initial(hashed_key) => Ok(hashed_key),
+                StateDiffKey::Repeated(index) => {
+                    let (hashed_key, _) = index
+                        .checked_sub(1)
+                        .and_then(|idx| self.leaves.get(idx as usize))
+                        .with_context(|| {
+                            format!("repeated write refers to unknown leaf #{index}")
+                        })?;
+                    Ok(*hashed_key)
+                }
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let tree_keys: Vec<_> = keys.iter().map(|key| tree_key(*key)).collect();
+        let prev_entries = self.tree.entries(version, &tree_keys)?;
+
+        let mut entries = Vec::with_capacity(state_diffs.len());
+        for (((key, value), hashed_key), prev_entry) in
+            state_diffs.iter().zip(keys).zip(prev_entries)
+        {
+            let leaf_index = match key {
+                StateDiffKey::Initial(_) => {
+                    anyhow::ensure!(
+                        prev_entry.leaf_index == 0,
+                        "initial write to key {hashed_key:?}, which already has leaf #{}",
+                        prev_entry.leaf_index
+                    );
+                    self.leaves.push((hashed_key, l1_batch_number));
+                    self.leaves.len() as u64
+                }
+                StateDiffKey::Repeated(index) => *index,
+            };
+            let new_value = value.apply(h256_to_u256(prev_entry.value));
+            entries.push(TreeEntry::new(
+                prev_entry.key,
+                leaf_index,
+                u256_to_h256(new_value),
+            ));
+        }
+        Ok(self.tree.extend(entries)?.root_hash)
+    }
+
+    /// Returns the current value of the specified storage slot.
+    pub fn value(&self, key: &StorageKey) -> anyhow::Result<H256> {
+        let version = self.tree.latest_version().context("tree is empty")?;
+        let entries = self.tree.entries(version, &[key.hashed_key_u256()])?;
+        Ok(entries[0].value)
+    }
+
+    /// Returns the total number of storage logs (i.e., tree leaves).
+    pub fn storage_log_count(&self) -> usize {
+        self.leaves.len()
+    }
+
+    /// Returns storage logs for leaves with enumeration indices in `start_index..(start_index + count)`.
+    pub fn storage_logs(
+        &self,
+        start_index: usize,
+        count: usize,
+    ) -> anyhow::Result<Vec<SnapshotStorageLog>> {
+        let version = self.tree.latest_version().context("tree is empty")?;
+        let end_index = (start_index + count).min(self.leaves.len());
+        let leaves = &self.leaves[start_index..end_index];
+        let tree_keys: Vec<_> = leaves.iter().map(|(key, _)| tree_key(*key)).collect();
+        let entries = self.tree.entries(version, &tree_keys)?;
+
+        let logs = leaves
+            .iter()
+            .zip(entries)
+            .map(|(&(key, l1_batch_number), entry)| SnapshotStorageLog {
+                key,
+                value: entry.value,
+                l1_batch_number_of_initial_write: l1_batch_number,
+                enumeration_index: entry.leaf_index,
+            });
+        Ok(logs.collect())
+    }
+}
+
+fn tree_key(hashed_key: H256) -> U256 {
+    U256::from_little_endian(hashed_key.as_bytes())
+}
@@ -0,0 +1,884 @@
+//! Tests for L1 recovery.
+
+use std::{iter, path::Path};
+
+use assert_matches::assert_matches;
+use test_casing::{test_casing, Product};
+use zksync_eth_client::{clients::MockSettlementLayer, EthInterface, Options};
+use zksync_l1_contract_interface::{
+    i_executor::{
+        commit::kzg::{KzgInfo, ZK_SYNC_BYTES_PER_BLOB},
+        methods::CommitBatches,
+    },
+    Tokenize,
+};
+use zksync_merkle_tree::{MerkleTree, PatchSet, TreeEntry};
+use zksync_node_genesis::{insert_genesis_batch, mock_genesis_config};
+use zksync_node_test_utils::{create_l1_batch, create_l1_batch_metadata};
+use zksync_system_constants::{
+    COMPRESSOR_ADDRESS, L1_MESSENGER_ADDRESS, L2_TO_L1_LOGS_TREE_ROOT_KEY,
+};
+use zksync_types::{
+    abi,
+    block::pack_block_info,
+    commitment::{L1BatchCommitmentMode, L1BatchWithMetadata},
+    ethabi::{self, Token},
+    l2_to_l1_log::{L2ToL1Log, SystemL2ToL1Log, UserL2ToL1Log},
+    pubdata_da::PubdataDA,
+    web3::Log,
+    writes::{compress_state_diffs, StateDiffRecord},
+    AccountTreeId, ProtocolVersionId, StorageKey, PRIORITY_OPERATION_L2_TX_TYPE,
+    PROTOCOL_UPGRADE_TX_TYPE,
+};
+use zksync_utils::{address_to_h256, bytecode::hash_bytecode};
+
+use super::*;
+use crate::{
+    blob_api::blob_to_pubdata,
+    pubdata::{decompress_bytecode, CompressedValue, StateDiffKey},
+};
+
+const DIAMOND_PROXY_ADDR: Address = Address::repeat_byte(1);
+const VALIDATOR_TIMELOCK_ADDR: Address = Address::repeat_byte(23);
+const CHAIN_ID: u32 = 270;
+const PUBDATA_SOURCES: [PubdataDA; 2] = [PubdataDA::Calldata, PubdataDA::Blobs];
+/// Bytecode supplied with a priority operation that is not executed in any L1 batch.
+const NOT_EXECUTED_BYTECODE: [u8; 32] = [0x7f; 32];
+
+fn user_storage_key(slot: u64) -> StorageKey {
+    StorageKey::new(
+        AccountTreeId::new(Address::repeat_byte(0xaa)),
+        H256::from_low_u64_be(slot),
+    )
+}
+
+fn prev_l2_block_hash(l2_block_number: u32) -> H256 {
+    H256::from_low_u64_be(u64::from(l2_block_number) + 1_000)
+}
+
+fn txs_rolling_hash(l2_block_number: u32) -> H256 {
+    H256::repeat_byte(l2_block_number as u8)
+}
+
+/// Storage writes performed by the system context contract when starting an L2 block.
+fn l2_block_writes(l2_block_number: u32) -> Vec<(StorageKey, U256)> {
+    let prev_hash_position = h256_to_u256(SYSTEM_CONTEXT_CURRENT_L2_BLOCK_HASHES_POSITION)
+        + U256::from((l2_block_number - 1) % SYSTEM_CONTEXT_STORED_L2_BLOCK_HASHES);
+    vec![
+        (
+            get_system_context_key(SYSTEM_CONTEXT_CURRENT_L2_BLOCK_INFO_POSITION),
+            pack_block_info(l2_block_number.into(), l2_block_number.into()),
+        ),
+        (
+            get_system_context_key(u256_to_h256(prev_hash_position)),
+            h256_to_u256(prev_l2_block_hash(l2_block_number)),
+        ),
+        (
+            get_system_context_key(SYSTEM_CONTEXT_CURRENT_TX_ROLLING_HASH_POSITION),
+            h256_to_u256(txs_rolling_hash(l2_block_number)),
+        ),
+    ]
+}
+
+fn expected_l2_block_hash(l2_block_number: u32) -> H256 {
+    L2BlockHasher::hash(
+        L2BlockNumber(l2_block_number),
+        l2_block_number.into(),
+        prev_l2_block_hash(l2_block_number),
+        txs_rolling_hash(l2_block_number),
+        ProtocolVersionId::latest(),
+    )
+}
+
+/// Inverse of [`decompress_bytecode()`].
+fn compress_bytecode(bytecode: &[u8]) -> Vec<u8> {
+    let mut dictionary: Vec<&[u8]> = vec![];
+    let mut indices = vec![];
+    for chunk in bytecode.chunks(8) {
+        let index = match dictionary.iter().position(|&entry| entry == chunk) {
+            Some(index) => index,
+            None => {
+                dictionary.push(chunk);
+                dictionary.len() - 1
+            }
+        };
+        indices.push(index as u16);
+    }
+
+    let mut compressed = (dictionary.len() as u16).to_be_bytes().to_vec();
+    compressed.extend(dictionary.concat());
+    for index in indices {
+        compressed.extend(index.to_be_bytes());
+    }
+    compressed
+}
+
+fn block_commit_log(l1_batch: &L1BatchWithMetadata) -> Log {
+    let event_signature = zksync_contracts::hyperchain_contract()
+        .event("BlockCommit")
+        .unwrap()
+        .signature();
+    Log {
+        address: DIAMOND_PROXY_ADDR,
+        topics: vec![
+            event_signature,
+            H256::from_low_u64_be(l1_batch.header.number.0.into()),
+            l1_batch.metadata.root_hash,
+            l1_batch.metadata.commitment,
+        ],
+        ..Log::default()
+    }
+}
+
+/// Returns a `NewPriorityRequest` event for a priority transaction supplying `bytecodes` as factory deps.
+fn priority_request_log(serial_id: u64, bytecodes: Vec<Vec<u8>>) -> Log {
+    let tx = abi::L2CanonicalTransaction {
+        tx_type: PRIORITY_OPERATION_L2_TX_TYPE.into(),
+        nonce: serial_id.into(),
+        factory_deps: bytecodes
+            .iter()
+            .map(|bytecode| h256_to_u256(hash_bytecode(bytecode)))
+            .collect(),
+        ..abi::L2CanonicalTransaction::default()
+    };
+    let request = abi::NewPriorityRequest {
+        tx_id: serial_id.into(),
+        tx_hash: tx.hash().into(),
+        expiration_timestamp: u64::MAX,
+        transaction: Box::new(tx),
+        factory_deps: bytecodes,
+    };
+    let event_signature = zksync_contracts::hyperchain_contract()
+        .event("NewPriorityRequest")
+        .unwrap()
+        .signature();
+    Log {
+        address: DIAMOND_PROXY_ADDR,
+        topics: vec![event_signature],
+        data: ethabi::encode(&request.encode()).into(),
+        ..Log::default()
+    }
+}
+
+/// Returns an `ExecuteUpgrade` event for a protocol upgrade with an upgrade transaction supplying `bytecode`
+/// as a factory dep.
+fn upgrade_log(bytecode: Vec<u8>) -> Log {
+    let tx = abi::L2CanonicalTransaction {
+        tx_type: PROTOCOL_UPGRADE_TX_TYPE.into(),
+        nonce: (ProtocolVersionId::latest() as u16).into(),
+        factory_deps: vec![h256_to_u256(hash_bytecode(&bytecode))],
+        ..abi::L2CanonicalTransaction::default()
+    };
+    let upgrade = abi::ProposedUpgrade {
+        l2_protocol_upgrade_tx: Box::new(tx),
+        factory_deps: vec![bytecode],
+        bootloader_hash: [0; 32],
+        default_account_hash: [0; 32],
+        verifier: Address::zero(),
+        verifier_params: abi::VerifierParams::default(),
+        l1_contracts_upgrade_calldata: vec![],
+        post_upgrade_calldata: vec![],
+        upgrade_timestamp: U256::zero(),
+        new_protocol_version: ProtocolSemanticVersion {
+            minor: ProtocolVersionId::latest(),
+            patch: 0.into(),
+        }
+        .pack(),
+    };
+    let init_calldata = [0_u8; 4]
+        .into_iter()
+        .chain(ethabi::encode(&[upgrade.encode()]))
+        .collect();
+    let diamond_cut = Token::Tuple(vec![
+        Token::Array(vec![]),
+        Token::Address(Address::zero()),
+        Token::Bytes(init_calldata),
+    ]);
+    let event_signature = zksync_contracts::hyperchain_contract()
+        .event("ExecuteUpgrade")
+        .unwrap()
+        .signature();
+    Log {
+        address: DIAMOND_PROXY_ADDR,
+        topics: vec![event_signature],
+        data: ethabi::encode(&[diamond_cut]).into(),
+        ..Log::default()
+    }
+}
+
+/// Chain with L1 batches built on top of the genesis state. Storage state and root hashes are computed independently
+/// from the recovery logic.
+struct TestChain {
+    genesis_params: GenesisParams,
+    genesis_batch_info: StoredBatchInfo,
+    tree: MerkleTree<PatchSet>,
+    /// Enumeration indices and values of storage slots after each L1 batch (starting from the genesis batch).
+    storage_by_batch: Vec<HashMap<StorageKey, (u64, U256)>>,
+    /// Bytecodes published by each L1 batch.
+    factory_deps_by_batch: Vec<HashMap<H256, Vec<u8>>>,
+    /// Bytecodes supplied with L1 transactions executed in each L1 batch (starting from L1 batch #1).
+    /// These bytecodes are not published in pubdata.
+    l1_tx_bytecodes: Vec<Vec<u8>>,
+    batches: Vec<L1BatchWithMetadata>,
+}
+
+impl TestChain {
+    async fn new(batch_count: u32) -> Self {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut storage = pool.connection().await.unwrap();
+        let genesis = insert_genesis_batch(&mut storage, &GenesisParams::mock())
+            .await
+            .unwrap();
+        let mut config = mock_genesis_config();
+        config.genesis_root_hash = Some(genesis.root_hash);
+        config.genesis_commitment = Some(genesis.commitment);
+        config.rollup_last_leaf_index = Some(genesis.rollup_last_leaf_index);
+        let genesis_params = GenesisParams::load_genesis_params(config).unwrap();
+
+        let genesis_writes = genesis_storage_writes(&genesis_params);
+        let entries = genesis_writes
+            .iter()
+            .enumerate()
+            .map(|(i, (key, value))| TreeEntry::new(key.hashed_key_u256(), i as u64 + 1, *value))
+            .collect();
+        let mut tree = MerkleTree::new(PatchSet::default()).unwrap();
+        let root_hash = tree.extend(entries).unwrap().root_hash;
+        assert_eq!(root_hash, genesis.root_hash);
+        let genesis_storage = genesis_writes
+            .into_iter()
+            .enumerate()
+            .map(|(i, (key, value))| (key, (i as u64 + 1, h256_to_u256(value))))
+            .collect();
+
+        let genesis_batch_info = StoredBatchInfo {
+            batch_number: 0,
+            batch_hash: genesis.root_hash,
+            index_repeated_storage_changes: genesis.rollup_last_leaf_index,
+            number_of_layer1_txs: U256::zero(),
+            priority_operations_hash: H256(keccak256(&[])),
+            l2_logs_tree_root: H256::zero(),
+            timestamp: U256::zero(),
+            commitment: genesis.commitment,
+        };
+        let mut this = Self {
+            factory_deps_by_batch: vec![genesis_factory_deps(&genesis_params)],
+            genesis_params,
+            genesis_batch_info,
+            tree,
+            storage_by_batch: vec![genesis_storage],
+            l1_tx_bytecodes: vec![],
+            batches: vec![],
+        };
+        for number in 1..=batch_count {
+            this.push_batch(number);
+        }
+        this
+    }
+
+    fn push_batch(&mut self, number: u32) {
+        let mut writes = l2_block_writes(number);
+        writes.push((user_storage_key(number.into()), U256::from(number) * 1_000));
+        if number > 1 {
+            // Repeated write to a slot initialized in the first batch.
+            writes.push((user_storage_key(1), U256::from(1_000 + number)));
+        }
+        let bytecode = vec![number as u8; 32];
+        let compressible_bytecode = [[number as u8; 8], [0xff; 8]].repeat(6).concat();
+        let compressed_bytecode = compress_bytecode(&compressible_bytecode);
+        let l1_tx_bytecode = vec![number as u8 | 0x80; 32];
+        // The L1 transaction bytecode is marked as known during execution, but is not published.
+        writes.push((
+            get_known_code_key(&hash_bytecode(&l1_tx_bytecode)),
+            U256::one(),
+        ));
+
+        let mut storage = self.storage_by_batch.last().unwrap().clone();
+        let mut next_index = storage.len() as u64 + 1;
+        let mut records: Vec<_> = writes
+            .into_iter()
+            .map(|(key, final_value)| {
+                let (enumeration_index, initial_value) =
+                    storage.get(&key).copied().unwrap_or_default();
+                StateDiffRecord {
+                    address: *key.address(),
+                    key: h256_to_u256(*key.key()),
+                    derived_key: StorageKey::raw_hashed_key(key.address(), key.key()),
+                    enumeration_index,
+                    initial_value,
+                    final_value,
+                }
+            })
+            .collect();
+        // Leaf indices are assigned in the same order as in the metadata calculator.
+        records.sort_unstable_by_key(|record| (record.address, record.key));
+        let mut entries = vec![];
+        for record in &records {
+            let key = StorageKey::new(AccountTreeId::new(record.address), u256_to_h256(record.key));
+            let leaf_index = if record.is_write_initial() {
+                next_index += 1;
+                next_index - 1
+            } else {
+                record.enumeration_index
+            };
+            storage.insert(key, (leaf_index, record.final_value));
+            entries.push(TreeEntry::new(
+                key.hashed_key_u256(),
+                leaf_index,
+                u256_to_h256(record.final_value),
+            ));
+        }
+        let root_hash = self.tree.extend(entries).unwrap().root_hash;
+
+        let mut header = create_l1_batch(number);
+        let l2_logs_tree_root = H256::repeat_byte(number as u8);
+        header.system_logs = vec![SystemL2ToL1Log(L2ToL1Log {
+            sender: L1_MESSENGER_ADDRESS,
+            key: H256::from_low_u64_be(L2_TO_L1_LOGS_TREE_ROOT_KEY.into()),
+            value: l2_logs_tree_root,
+            ..L2ToL1Log::default()
+        })];
+        header.l2_to_l1_logs.push(UserL2ToL1Log(L2ToL1Log {
+            sender: L1_MESSENGER_ADDRESS,
+            key: address_to_h256(&COMPRESSOR_ADDRESS),
+            value: H256(keccak256(&compressed_bytecode)),
+            ..L2ToL1Log::default()
+        }));
+        header.l2_to_l1_messages.push(compressed_bytecode);
+        let mut metadata = create_l1_batch_metadata(number);
+        metadata.root_hash = root_hash;
+        metadata.rollup_last_leaf_index = next_index;
+        metadata.l2_l1_merkle_root = l2_logs_tree_root;
+        metadata.state_diffs_compressed = compress_state_diffs(records);
+
+        self.storage_by_batch.push(storage);
+        self.factory_deps_by_batch.push(HashMap::from([
+            (hash_bytecode(&bytecode), bytecode.clone()),
+            (hash_bytecode(&compressible_bytecode), compressible_bytecode),
+            (hash_bytecode(&l1_tx_bytecode), l1_tx_bytecode.clone()),
+        ]));
+        self.l1_tx_bytecodes.push(l1_tx_bytecode);
+        self.batches.push(L1BatchWithMetadata {
+            header,
+            metadata,
+            raw_published_factory_deps: vec![bytecode],
+        });
+    }
+
+    /// Creates a mock L1 client emulating the diamond proxy contract with all batches in the chain executed.
+    fn mock_l1_client(&self) -> MockSettlementLayer {
+        let contract = zksync_contracts::hyperchain_contract();
+        let stored_batch_hashes: Vec<_> = iter::once(self.genesis_batch_info.hash())
+            .chain(
+                self.batches
+                    .iter()
+                    .map(|batch| StoredBatchInfo::from(batch).hash()),
+            )
+            .collect();
+        let total_batches_executed = self.batches.len();
+
+        MockSettlementLayer::builder()
+            .with_call_handler(move |call, _block_id| {
+                assert_eq!(call.to, Some(DIAMOND_PROXY_ADDR));
+                let data = &call.data.as_ref().unwrap().0;
+                let (selector, args) = data.split_at(4);
+                let function = contract
+                    .functions()
+                    .find(|function| function.short_signature() == selector)
+                    .unwrap_or_else(|| panic!("unexpected call: {call:?}"));
+                match function.name.as_str() {
+                    "storedBatchHash" => {
+                        let args = function.decode_input(args).unwrap();
+                        let number = args[0].clone().into_uint().unwrap().as_usize();
+                        Token::FixedBytes(stored_batch_hashes[number].as_bytes().to_vec())
+                    }
+                    "getTotalBatchesExecuted" => Token::Uint(total_batches_executed.into()),
+                    "getProtocolVersion" => {
+                        Token::Uint(ProtocolVersionId::latest().into_packed_semver_with_patch(0))
+                    }
+                    name => panic!("unexpected call to `{name}`"),
+                }
+            })
+            .build()
+    }
+
+    /// Emulates L1 transactions (priority operations and protocol upgrades) supplying bytecodes
+    /// for the specified batches.
+    fn l1_tx_logs(&self, batches: &[L1BatchWithMetadata]) -> Vec<Log> {
+        batches
+            .iter()
+            .map(|batch| {
+                let number = batch.header.number.0;
+                let bytecode = self.l1_tx_bytecodes[number as usize - 1].clone();
+                if number == 1 {
+                    upgrade_log(bytecode)
+                } else {
+                    priority_request_log(number.into(), vec![bytecode])
+                }
+            })
+            .collect()
+    }
+
+    async fn send_tx(
+        client: &MockSettlementLayer,
+        nonce: usize,
+        input_data: Vec<u8>,
+        logs: Vec<Log>,
+    ) {
+        let signed_tx = client
+            .sign_prepared_tx(
+                input_data,
+                VALIDATOR_TIMELOCK_ADDR,
+                Options {
+                    nonce: Some(nonce.into()),
+                    ..Options::default()
+                },
+            )
+            .unwrap();
+        client.as_ref().send_raw_tx(signed_tx.raw_tx).await.unwrap();
+        client.execute_tx(signed_tx.hash, true, 1).with_logs(logs);
+    }
+
+    async fn commit_batches(
+        &self,
+        client: &MockSettlementLayer,
+        batches_per_tx: usize,
+        pubdata_da: PubdataDA,
+    ) {
+        let contract = zksync_contracts::hyperchain_contract();
+        let commit_function = contract.function("commitBatchesSharedBridge").unwrap();
+        for (i, batches) in self.batches.chunks(batches_per_tx).enumerate() {
+            let mut l1_tx_logs = self.l1_tx_logs(batches);
+            if i == 0 {
+                // Priority operation that is never executed; its bytecode must not be recovered.
+                l1_tx_logs.push(priority_request_log(
+                    1_000,
+                    vec![NOT_EXECUTED_BYTECODE.to_vec()],
+                ));
+            }
+            Self::send_tx(client, 2 * i, vec![], l1_tx_logs).await;
+
+            // The last committed batch is not checked by recovery, so it's OK to use `batches[0]`.
+            let tokens = CommitBatches {
+                last_committed_l1_batch: &batches[0],
+                l1_batches: batches,
+                pubdata_da,
+                mode: L1BatchCommitmentMode::Rollup,
+            }
+            .into_tokens();
+            let tokens: Vec<_> = iter::once(Token::Uint(CHAIN_ID.into()))
+                .chain(tokens)
+                .collect();
+            let input_data = commit_function.encode_input(&tokens).unwrap();
+            let commit_logs = batches.iter().map(block_commit_log).collect();
+            Self::send_tx(client, 2 * i + 1, input_data, commit_logs).await;
+        }
+    }
+
+    fn recovery(
+        &self,
+        client: MockSettlementLayer,
+        pool: ConnectionPool<Core>,
+        tree_path: &Path,
+    ) -> L1Recovery {
+        let config = L1RecoveryConfig {
+            diamond_proxy_addr: DIAMOND_PROXY_ADDR,
+            first_l1_block: 0,
+            // Use a small range to test pagination.
+            l1_block_range: 2,
+            l1_batch: None,
+            tree_path: tree_path.to_owned(),
+        };
+        L1Recovery::new(
+            config,
+            Box::new(client.into_client()),
+            pool,
+            self.genesis_params.clone(),
+        )
+        .with_blob_provider(Arc::new(MockBlobProvider::new(self)))
+    }
+
+    async fn assert_recovered(
+        &self,
+        pool: &ConnectionPool<Core>,
+        status: &SnapshotRecoveryStatus,
+        number: L1BatchNumber,
+    ) {
+        let batch = &self.batches[number.0 as usize - 1];
+        assert_eq!(status.l1_batch_number, number);
+        assert_eq!(status.l1_batch_root_hash, batch.metadata.root_hash);
+        assert_eq!(status.l1_batch_timestamp, batch.header.timestamp);
+        assert_eq!(status.l2_block_number, L2BlockNumber(number.0));
+        assert_eq!(status.l2_block_timestamp, u64::from(number.0));
+        assert_eq!(status.l2_block_hash, expected_l2_block_hash(number.0));
+        assert_eq!(status.protocol_version, ProtocolVersionId::latest());
+
+        let mut storage = pool.connection().await.unwrap();
+        let persisted_status = storage
+            .snapshot_recovery_dal()
+            .get_applied_snapshot_status()
+            .await
+            .unwrap();
+        assert_eq!(persisted_status.as_ref(), Some(status));
+
+        let expected_storage = &self.storage_by_batch[number.0 as usize];
+        let hashed_keys: Vec<_> = expected_storage
+            .keys()
+            .map(StorageKey::hashed_key)
+            .collect();
+        let values = storage
+            .storage_logs_dal()
+            .get_storage_values(&hashed_keys, status.l2_block_number)
+            .await
+            .unwrap();
+        let initial_writes = storage
+            .storage_logs_dedup_dal()
+            .dump_all_initial_writes_for_tests()
+            .await;
+        assert_eq!(initial_writes.len(), expected_storage.len());
+        let enumeration_indices: HashMap<_, _> = initial_writes
+            .into_iter()
+            .map(|write| (write.hashed_key, write.index))
+            .collect();
+        for (key, &(index, value)) in expected_storage {
+            let hashed_key = key.hashed_key();
+            assert_eq!(values[&hashed_key], Some(u256_to_h256(value)), "{key:?}");
+            assert_eq!(enumeration_indices[&hashed_key], index, "{key:?}");
+        }
+
+        for factory_deps in &self.factory_deps_by_batch[..=number.0 as usize] {
+            for (&hash, bytecode) in factory_deps {
+                let persisted_bytecode = storage
+                    .factory_deps_dal()
+                    .get_sealed_factory_dep(hash)
+                    .await
+                    .unwrap();
+                assert_eq!(persisted_bytecode.as_ref(), Some(bytecode), "{hash:?}");
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct MockBlobProvider(HashMap<Vec<u8>, Vec<u8>>);
+
+impl MockBlobProvider {
+    fn new(chain: &TestChain) -> Self {
+        let blobs = chain.batches.iter().flat_map(|batch| {
+            let pubdata = batch.construct_pubdata();
+            let blobs: Vec<_> = pubdata
+                .chunks(ZK_SYNC_BYTES_PER_BLOB)
+                .map(|blob| {
+                    let commitment = KzgInfo::new(blob).to_pubdata_commitment();
+                    (commitment[48..96].to_vec(), blob.to_vec())
+                })
+                .collect();
+            blobs
+        });
+        Self(blobs.collect())
+    }
+}
+
+#[async_trait::async_trait]
+impl BlobProvider for MockBlobProvider {
+    async fn get_blob(&self, kzg_commitment: &[u8]) -> anyhow::Result<Option<Vec<u8>>> {
+        Ok(self.0.get(kzg_commitment).cloned())
+    }
+}
+
+#[test]
+fn parsing_pubdata() {
+    let records = vec![
+        StateDiffRecord {
+            address: Address::repeat_byte(2),
+            key: 1.into(),
+            derived_key: [1; 32],
+            enumeration_index: 0,
+            initial_value: 0.into(),
+            final_value: 5.into(),
+        },
+        StateDiffRecord {
+            address: Address::repeat_byte(1),
+            key: 2.into(),
+            derived_key: [2; 32],
+            enumeration_index: 0,
+            initial_value: 0.into(),
+            final_value: U256::MAX - 1,
+        },
+        StateDiffRecord {
+            address: Address::repeat_byte(1),
+            key: 3.into(),
+            derived_key: [3; 32],
+            enumeration_index: 3,
+            initial_value: 1_000_000.into(),
+            final_value: 1_000_001.into(),
+        },
+        StateDiffRecord {
+            address: Address::repeat_byte(1),
+            key: 4.into(),
+            derived_key: [4; 32],
+            enumeration_index: 4,
+            initial_value: U256::MAX,
+            final_value: U256::MAX - 1_000,
+        },
+        StateDiffRecord {
+            address: Address::repeat_byte(1),
+            key: 5.into(),
+            derived_key: [5; 32],
+            enumeration_index: 5,
+            initial_value: U256::MAX,
+            final_value: 1.into(),
+        },
+    ];
+    let mut batch = L1BatchWithMetadata {
+        header: create_l1_batch(1),
+        metadata: create_l1_batch_metadata(1),
+        raw_published_factory_deps: vec![vec![1; 32], vec![2; 96]],
+    };
+    batch.metadata.state_diffs_compressed = compress_state_diffs(records.clone());
+
+    let mut pubdata = batch.construct_pubdata();
+    // Emulate blob padding.
+    pubdata.extend([0; 10]);
+    let parsed = ParsedPubdata::parse(&pubdata).unwrap();
+    let expected_logs: Vec<_> = batch
+        .header
+        .l2_to_l1_logs
+        .iter()
+        .map(|log| log.0.clone())
+        .collect();
+    assert_eq!(parsed.user_logs, expected_logs);
+    assert_eq!(parsed.l2_to_l1_messages, batch.header.l2_to_l1_messages);
+    assert_eq!(parsed.published_bytecodes, batch.raw_published_factory_deps);
+
+    // Initial writes must come first, ordered by address and key.
+    let expected_keys = [
+        StateDiffKey::Initial(H256([2; 32])),
+        StateDiffKey::Initial(H256([1; 32])),
+        StateDiffKey::Repeated(3),
+        StateDiffKey::Repeated(4),
+        StateDiffKey::Repeated(5),
+    ];
+    let keys: Vec<_> = parsed.state_diffs.iter().map(|(key, _)| *key).collect();
+    assert_eq!(keys, expected_keys);
+    for ((key, value), record) in parsed
+        .state_diffs
+        .iter()
+        .zip([1, 0, 2, 3, 4].map(|i| &records[i]))
+    {
+        assert_eq!(
+            value.apply(record.initial_value),
+            record.final_value,
+            "{key:?}"
+        );
+    }
+    assert_matches!(parsed.state_diffs[2].1, CompressedValue::Add(_));
+
+    pubdata.push(1);
+    let err = ParsedPubdata::parse(&pubdata).unwrap_err();
+    assert!(err.to_string().contains("trailing bytes"), "{err}");
+}
+
+#[test]
+fn decompressing_bytecode() {
+    let bytecode = [[1; 8], [2; 8], [1; 8], [3; 8]].repeat(3).concat();
+    let compressed = compress_bytecode(&bytecode);
+    assert_eq!(decompress_bytecode(&compressed).unwrap(), bytecode);
+
+    let mut invalid_compressed = compressed;
+    let len = invalid_compressed.len();
+    invalid_compressed[len - 2..].copy_from_slice(&3_u16.to_be_bytes());
+    let err = decompress_bytecode(&invalid_compressed).unwrap_err();
+    assert!(err.to_string().contains("references chunk #3"), "{err}");
+}
+
+#[test]
+fn extracting_factory_deps_from_pubdata() {
+    let bytecode = [[1; 8], [2; 8]].repeat(6).concat();
+    let compressed_bytecode = compress_bytecode(&bytecode);
+    let compressor_log = L2ToL1Log {
+        sender: L1_MESSENGER_ADDRESS,
+        key: address_to_h256(&COMPRESSOR_ADDRESS),
+        value: H256(keccak256(&compressed_bytecode)),
+        ..L2ToL1Log::default()
+    };
+    let published_bytecode = vec![3; 32];
+    let pubdata = ParsedPubdata {
+        user_logs: vec![compressor_log],
+        // The second message isn't a compressed bytecode since it's not accompanied by a compressor log.
+        l2_to_l1_messages: vec![compressed_bytecode, compress_bytecode(&[4; 32])],
+        published_bytecodes: vec![published_bytecode.clone()],
+        state_diffs: vec![],
+    };
+
+    let factory_deps = pubdata.factory_deps().unwrap();
+    assert_eq!(
+        factory_deps,
+        HashMap::from([
+            (hash_bytecode(&bytecode), bytecode),
+            (hash_bytecode(&published_bytecode), published_bytecode),
+        ])
+    );
+}
+
+#[test]
+fn converting_blob_to_pubdata() {
+    let pubdata: Vec<_> = (0..ZK_SYNC_BYTES_PER_BLOB).map(|i| i as u8).collect();
+    let blob: Vec<_> = pubdata
+        .chunks(31)
+        .flat_map(|chunk| iter::once(0).chain(chunk.iter().copied()))
+        .collect();
+    assert_eq!(blob_to_pubdata(&blob).unwrap(), pubdata);
+
+    let err = blob_to_pubdata(&blob[..blob.len() - 1]).unwrap_err();
+    assert!(err.to_string().contains("unexpected blob size"), "{err}");
+
+    let mut invalid_blob = blob;
+    invalid_blob[32] = 1;
+    let err = blob_to_pubdata(&invalid_blob).unwrap_err();
+    assert!(err.to_string().contains("field element #1"), "{err}");
+}
+
+#[test_casing(4, Product((PUBDATA_SOURCES, [1, 3])))]
+#[tokio::test]
+async fn recovering_state_from_l1(pubdata_da: PubdataDA, batches_per_tx: usize) {
+    let chain = TestChain::new(5).await;
+    let client = chain.mock_l1_client();
+    chain
+        .commit_batches(&client, batches_per_tx, pubdata_da)
+        .await;
+
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let tree_path = temp_dir.path().join("tree");
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let status = chain
+        .recovery(client, pool.clone(), &tree_path)
+        .run(stop_receiver)
+        .await
+        .unwrap()
+        .expect("recovery was interrupted");
+
+    chain
+        .assert_recovered(&pool, &status, L1BatchNumber(5))
+        .await;
+    assert!(!tree_path.exists());
+
+    let not_executed_bytecode = pool
+        .connection()
+        .await
+        .unwrap()
+        .factory_deps_dal()
+        .get_sealed_factory_dep(hash_bytecode(&NOT_EXECUTED_BYTECODE))
+        .await
+        .unwrap();
+    assert_eq!(not_executed_bytecode, None);
+}
+
+#[tokio::test]
+async fn recovering_specific_l1_batch() {
+    let chain = TestChain::new(5).await;
+    let client = chain.mock_l1_client();
+    chain.commit_batches(&client, 2, PubdataDA::Calldata).await;
+
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let mut recovery = chain.recovery(client, pool.clone(), &temp_dir.path().join("tree"));
+    recovery.config.l1_batch = Some(L1BatchNumber(3));
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let status = recovery.run(stop_receiver).await.unwrap().unwrap();
+
+    chain
+        .assert_recovered(&pool, &status, L1BatchNumber(3))
+        .await;
+}
+
+#[tokio::test]
+async fn recovery_is_interrupted_by_stop_signal() {
+    let chain = TestChain::new(2).await;
+    let client = chain.mock_l1_client();
+    chain.commit_batches(&client, 1, PubdataDA::Calldata).await;
+
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let (_stop_sender, stop_receiver) = watch::channel(true);
+    let status = chain
+        .recovery(client, pool.clone(), &temp_dir.path().join("tree"))
+        .run(stop_receiver)
+        .await
+        .unwrap();
+    assert!(status.is_none());
+
+    let mut storage = pool.connection().await.unwrap();
+    let persisted_status = storage
+        .snapshot_recovery_dal()
+        .get_applied_snapshot_status()
+        .await
+        .unwrap();
+    assert!(persisted_status.is_none());
+}
+
+#[tokio::test]
+async fn recovery_detects_root_hash_mismatch() {
+    let mut chain = TestChain::new(3).await;
+    // Emulate a batch with a root hash not matching its pubdata. All L1 data is consistent with this hash.
+    chain.batches[1].metadata.root_hash = H256::repeat_byte(0xfe);
+    let client = chain.mock_l1_client();
+    chain.commit_batches(&client, 1, PubdataDA::Calldata).await;
+
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let err = chain
+        .recovery(client, pool, &temp_dir.path().join("tree"))
+        .run(stop_receiver)
+        .await
+        .unwrap_err();
+    let err = format!("{err:#}");
+    assert!(err.contains("L1 batch #2"), "{err}");
+    assert!(err.contains("root hash mismatch"), "{err}");
+}
+
+#[tokio::test]
+async fn recovery_detects_genesis_mismatch() {
+    let mut chain = TestChain::new(1).await;
+    let client = chain.mock_l1_client();
+    chain.commit_batches(&client, 1, PubdataDA::Calldata).await;
+
+    let mut config = chain.genesis_params.config().clone();
+    config.genesis_commitment = Some(H256::repeat_byte(0xfe));
+    chain.genesis_params = GenesisParams::load_genesis_params(config).unwrap();
+
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let err = chain
+        .recovery(client, pool, &temp_dir.path().join("tree"))
+        .run(stop_receiver)
+        .await
+        .unwrap_err();
+    let err = format!("{err:#}");
+    assert!(err.contains("hash of L1 batch #0 mismatch"), "{err}");
+}
+
+#[tokio::test]
+async fn recovery_requires_empty_storage() {
+    let chain = TestChain::new(1).await;
+    let client = chain.mock_l1_client();
+    chain.commit_batches(&client, 1, PubdataDA::Calldata).await;
+
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    insert_genesis_batch(&mut storage, &GenesisParams::mock())
+        .await
+        .unwrap();
+    drop(storage);
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let err = chain
+        .recovery(client, pool, &temp_dir.path().join("tree"))
+        .run(stop_receiver)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("empty database"), "{err}");
+}
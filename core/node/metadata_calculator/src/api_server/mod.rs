@@ -10,7 +10,7 @@ use axum::{
     response::{IntoResponse, Response},
     routing, Json, Router,
 };
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use tokio::sync::watch;
 use zksync_crypto_primitives::hasher::blake2::Blake2Hasher;
 use zksync_health_check::{CheckHealth, Health, HealthStatus};
@@ -77,6 +77,63 @@ impl TreeEntryWithProof {
     }
 }
 
+/// Entry in a [`TreeMultiProof`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TreeMultiProofEntry {
+    pub key: U256,
+    #[serde(default, skip_serializing_if = "H256::is_zero")]
+    pub value: H256,
+    #[serde(default, skip_serializing_if = "TreeEntryWithProof::is_zero")]
+    pub index: u64,
+}
+
+/// Compact proof for multiple tree entries that shares hashes for common subtrees.
+///
+/// Unlike [`TreeEntryWithProof`], `hashes` are enumerated in the verification order, i.e., starting from
+/// the leaf level; see [`zksync_merkle_tree::TreeMultiProof`] for details.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TreeMultiProof {
+    /// Proven entries ordered by key.
+    pub entries: Vec<TreeMultiProofEntry>,
+    /// Merkle path length for each of `entries`.
+    pub path_lengths: Vec<u16>,
+    /// Hashes of sibling subtrees that cannot be computed from `entries`.
+    pub hashes: Vec<H256>,
+}
+
+impl TreeMultiProof {
+    fn new(src: zksync_merkle_tree::TreeMultiProof) -> Self {
+        let entries = src.entries.into_iter().map(|entry| TreeMultiProofEntry {
+            key: entry.key,
+            value: entry.value,
+            index: entry.leaf_index,
+        });
+        Self {
+            entries: entries.collect(),
+            path_lengths: src.path_lengths,
+            hashes: src.hashes,
+        }
+    }
+
+    /// Verifies the multiproof.
+    pub fn verify(&self, trusted_root_hash: H256) -> anyhow::Result<()> {
+        let entries = self
+            .entries
+            .iter()
+            .map(|entry| zksync_merkle_tree::TreeEntry {
+                key: entry.key,
+                value: entry.value,
+                leaf_index: entry.index,
+            });
+        zksync_merkle_tree::TreeMultiProof {
+            entries: entries.collect(),
+            path_lengths: self.path_lengths.clone(),
+            hashes: self.hashes.clone(),
+        }
+        .verify(&Blake2Hasher, trusted_root_hash)
+    }
+}
+
 /// Server-side tree API error.
 #[derive(Debug)]
 enum TreeApiServerError {
@@ -175,6 +232,13 @@ pub trait TreeApiClient: 'static + Send + Sync + fmt::Debug {
         l1_batch_number: L1BatchNumber,
         hashed_keys: Vec<U256>,
     ) -> Result<Vec<TreeEntryWithProof>, TreeApiError>;
+
+    /// Obtains a compact multiproof for the specified `hashed_keys` at the specified tree version (= L1 batch number).
+    async fn get_multiproof(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        hashed_keys: Vec<U256>,
+    ) -> Result<TreeMultiProof, TreeApiError>;
 }
 
 /// In-memory client implementation.
@@ -202,6 +266,21 @@ impl TreeApiClient for LazyAsyncTreeReader {
             Err(TreeApiError::NotReady(None))
         }
     }
+
+    async fn get_multiproof(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        hashed_keys: Vec<U256>,
+    ) -> Result<TreeMultiProof, TreeApiError> {
+        if let Some(reader) = self.read() {
+            reader
+                .get_multiproof_inner(l1_batch_number, hashed_keys)
+                .await
+                .map_err(TreeApiError::NoVersion)
+        } else {
+            Err(TreeApiError::NotReady(None))
+        }
+    }
 }
 
 /// [`TreeApiClient`] implementation requesting data from a Merkle tree API server.
@@ -210,6 +289,7 @@ pub struct TreeApiHttpClient {
     inner: reqwest::Client,
     info_url: String,
     proofs_url: String,
+    multiproof_url: String,
 }
 
 impl TreeApiHttpClient {
@@ -224,6 +304,7 @@ impl TreeApiHttpClient {
             inner: client,
             info_url: url_base.to_owned(),
             proofs_url: format!("{url_base}/proofs"),
+            multiproof_url: format!("{url_base}/multiproof"),
         }
     }
 }
@@ -270,9 +351,38 @@ impl TreeApiClient for TreeApiHttpClient {
         l1_batch_number: L1BatchNumber,
         hashed_keys: Vec<U256>,
     ) -> Result<Vec<TreeEntryWithProof>, TreeApiError> {
+        let response: TreeProofsResponse = self
+            .post_proofs_request(&self.proofs_url, "proofs", l1_batch_number, hashed_keys)
+            .await?;
+        Ok(response.entries)
+    }
+
+    async fn get_multiproof(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        hashed_keys: Vec<U256>,
+    ) -> Result<TreeMultiProof, TreeApiError> {
+        self.post_proofs_request(
+            &self.multiproof_url,
+            "multiproof",
+            l1_batch_number,
+            hashed_keys,
+        )
+        .await
+    }
+}
+
+impl TreeApiHttpClient {
+    async fn post_proofs_request<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        description: &str,
+        l1_batch_number: L1BatchNumber,
+        hashed_keys: Vec<U256>,
+    ) -> Result<T, TreeApiError> {
         let response = self
             .inner
-            .post(&self.proofs_url)
+            .post(url)
             .json(&TreeProofsRequest {
                 l1_batch_number,
                 hashed_keys,
@@ -282,7 +392,7 @@ impl TreeApiClient for TreeApiHttpClient {
             .map_err(|err| {
                 TreeApiError::for_request(
                     err,
-                    format_args!("proofs for L1 batch #{l1_batch_number}"),
+                    format_args!("{description} for L1 batch #{l1_batch_number}"),
                 )
             })?;
 
@@ -300,12 +410,13 @@ impl TreeApiClient for TreeApiHttpClient {
         }
 
         let response = response.error_for_status().with_context(|| {
-            format!("requesting proofs for L1 batch #{l1_batch_number} returned non-OK response")
+            format!(
+                "requesting {description} for L1 batch #{l1_batch_number} returned non-OK response"
+            )
         })?;
-        let response: TreeProofsResponse = response.json().await.with_context(|| {
-            format!("failed deserializing proofs for L1 batch #{l1_batch_number}")
-        })?;
-        Ok(response.entries)
+        Ok(response.json().await.with_context(|| {
+            format!("failed deserializing {description} for L1 batch #{l1_batch_number}")
+        })?)
     }
 }
 
@@ -329,6 +440,18 @@ impl AsyncTreeReader {
         Ok(proofs.into_iter().map(TreeEntryWithProof::new).collect())
     }
 
+    async fn get_multiproof_inner(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        hashed_keys: Vec<U256>,
+    ) -> Result<TreeMultiProof, NoVersionError> {
+        let proof = self
+            .clone()
+            .entries_with_multiproof(l1_batch_number, hashed_keys)
+            .await?;
+        Ok(TreeMultiProof::new(proof))
+    }
+
     async fn get_proofs_handler(
         State(this): State<Self>,
         Json(request): Json<TreeProofsRequest>,
@@ -343,6 +466,19 @@ impl AsyncTreeReader {
         Ok(Json(response))
     }
 
+    async fn get_multiproof_handler(
+        State(this): State<Self>,
+        Json(request): Json<TreeProofsRequest>,
+    ) -> Result<Json<TreeMultiProof>, TreeApiServerError> {
+        let latency = API_METRICS.latency[&MerkleTreeApiMethod::GetMultiProof].start();
+        let proof = this
+            .get_multiproof_inner(request.l1_batch_number, request.hashed_keys)
+            .await
+            .map_err(TreeApiServerError::NoTreeVersion)?;
+        latency.observe();
+        Ok(Json(proof))
+    }
+
     async fn create_api_server(
         self,
         bind_address: &SocketAddr,
@@ -353,6 +489,7 @@ impl AsyncTreeReader {
         let app = Router::new()
             .route("/", routing::get(Self::info_handler))
             .route("/proofs", routing::post(Self::get_proofs_handler))
+            .route("/multiproof", routing::post(Self::get_multiproof_handler))
             .with_state(self);
 
         let listener = tokio::net::TcpListener::bind(bind_address)
@@ -52,16 +52,33 @@ async fn merkle_tree_api() {
     hashed_keys.extend((0_u8..10).map(|byte| U256::from_big_endian(&[byte; 32])));
 
     let proofs = api_client
-        .get_proofs(L1BatchNumber(5), hashed_keys)
+        .get_proofs(L1BatchNumber(5), hashed_keys.clone())
         .await
         .unwrap();
     assert_eq!(proofs.len(), 20);
+    let separate_proofs_len: usize = proofs.iter().map(|proof| proof.merkle_path.len()).sum();
     for (i, proof) in proofs.into_iter().enumerate() {
         let should_be_present = i < 10;
         assert_eq!(proof.index == 0, !should_be_present);
         assert!(!proof.merkle_path.is_empty());
     }
 
+    let multiproof = api_client
+        .get_multiproof(L1BatchNumber(5), hashed_keys)
+        .await
+        .unwrap();
+    assert_eq!(multiproof.entries.len(), 20);
+    assert_eq!(
+        multiproof
+            .entries
+            .iter()
+            .filter(|entry| entry.index != 0)
+            .count(),
+        10
+    );
+    assert!(multiproof.hashes.len() < separate_proofs_len);
+    multiproof.verify(tree_info.root_hash).unwrap();
+
     let err = api_client
         .get_proofs(L1BatchNumber(10), vec![])
         .await
@@ -0,0 +1,794 @@
+//! Merkle tree checkpoints, i.e., consistent RocksDB snapshots of the tree exported to an object store.
+//!
+//! Unlike snapshot recovery, importing a checkpoint doesn't require rehashing tree leaves, so it's much faster
+//! for large trees. A checkpoint consists of raw RocksDB files and a manifest with the tree L1 batch and root hash.
+//! Files are split into fixed-size chunks stored as separate objects, so that neither export nor import
+//! needs to hold an entire (potentially multi-GB) file in memory.
+//! The manifest is uploaded after all files, and the pointer to the latest checkpoint is updated after the manifest,
+//! so that importers never observe partially uploaded checkpoints. Only a few latest checkpoints are retained;
+//! older checkpoints are removed after the pointer is updated.
+
+use std::{
+    io,
+    num::NonZeroU32,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    fs,
+    io::{AsyncReadExt, AsyncWriteExt},
+    sync::watch,
+};
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_merkle_tree::{domain::ZkSyncTreeReader, MerkleTreeColumnFamily};
+use zksync_object_store::{
+    Bucket, ObjectStore, ObjectStoreError, StoredObject, _reexports::BoxedError,
+};
+use zksync_storage::RocksDB;
+use zksync_types::{L1BatchNumber, H256};
+
+use crate::{
+    helpers::{AsyncTreeReader, LazyAsyncTreeReader},
+    metrics::{CheckpointStage, CHECKPOINT_METRICS},
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Default maximum size of a checkpoint file chunk stored in the object store.
+const DEFAULT_CHUNK_SIZE: u64 = 64 << 20; // 64 MiB
+/// Default number of latest checkpoints retained in the object store. Retaining more than one checkpoint
+/// allows importers that have started downloading the previous checkpoint to finish the download.
+const DEFAULT_RETAINED_CHECKPOINTS: usize = 2;
+
+/// Information about a file in a Merkle tree checkpoint.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TreeCheckpointFileInfo {
+    /// File name relative to the RocksDB directory.
+    pub name: String,
+    /// File size in bytes.
+    pub size: u64,
+}
+
+/// Manifest of a Merkle tree checkpoint stored in the object store.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TreeCheckpointManifest {
+    /// Latest L1 batch processed by the tree in the checkpoint.
+    pub l1_batch_number: L1BatchNumber,
+    /// Tree root hash after processing `l1_batch_number`.
+    pub root_hash: H256,
+    /// Number of leaves in the tree after processing `l1_batch_number`.
+    pub leaf_count: u64,
+    /// Maximum size of a file chunk in bytes. Each file is stored as `ceil(size / chunk_size)` chunks;
+    /// all chunks except for the last one have exactly `chunk_size` bytes.
+    pub chunk_size: u64,
+    /// RocksDB files constituting the checkpoint.
+    pub files: Vec<TreeCheckpointFileInfo>,
+}
+
+impl TreeCheckpointManifest {
+    fn total_size(&self) -> u64 {
+        self.files.iter().map(|file| file.size).sum()
+    }
+
+    fn chunk_count(&self, file: &TreeCheckpointFileInfo) -> u64 {
+        file.size.div_ceil(self.chunk_size)
+    }
+
+    fn expected_chunk_size(&self, file: &TreeCheckpointFileInfo, chunk_index: u64) -> u64 {
+        (file.size - chunk_index * self.chunk_size).min(self.chunk_size)
+    }
+}
+
+impl StoredObject for TreeCheckpointManifest {
+    const BUCKET: Bucket = Bucket::MerkleTreeCheckpoints;
+    type Key<'a> = L1BatchNumber;
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        format!("tree_checkpoint_l1_batch_{key}_manifest.json")
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, BoxedError> {
+        serde_json::to_vec(self).map_err(From::from)
+    }
+
+    fn deserialize(bytes: Vec<u8>) -> Result<Self, BoxedError> {
+        serde_json::from_slice(&bytes).map_err(From::from)
+    }
+}
+
+/// Pointer to the latest exported Merkle tree checkpoint.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct LatestTreeCheckpoint {
+    l1_batch_number: L1BatchNumber,
+    /// L1 batch numbers of older checkpoints still stored in the object store, in the ascending order.
+    /// Used to remove old checkpoints since object stores cannot be listed.
+    #[serde(default)]
+    previous_l1_batches: Vec<L1BatchNumber>,
+}
+
+impl StoredObject for LatestTreeCheckpoint {
+    const BUCKET: Bucket = Bucket::MerkleTreeCheckpoints;
+    type Key<'a> = ();
+
+    fn encode_key((): Self::Key<'_>) -> String {
+        "latest_tree_checkpoint.json".to_owned()
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, BoxedError> {
+        serde_json::to_vec(self).map_err(From::from)
+    }
+
+    fn deserialize(bytes: Vec<u8>) -> Result<Self, BoxedError> {
+        serde_json::from_slice(&bytes).map_err(From::from)
+    }
+}
+
+/// Raw contents of a chunk of a file in a Merkle tree checkpoint. RocksDB files are already compressed,
+/// so they are stored as-is.
+#[derive(Debug)]
+struct TreeCheckpointFileChunk(Vec<u8>);
+
+impl StoredObject for TreeCheckpointFileChunk {
+    const BUCKET: Bucket = Bucket::MerkleTreeCheckpoints;
+    /// L1 batch number of the checkpoint + file name + zero-based chunk index.
+    type Key<'a> = (L1BatchNumber, &'a str, u64);
+
+    fn encode_key((l1_batch_number, file_name, chunk_index): Self::Key<'_>) -> String {
+        format!("tree_checkpoint_l1_batch_{l1_batch_number}_{file_name}_chunk_{chunk_index}")
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, BoxedError> {
+        Ok(self.0.clone())
+    }
+
+    fn deserialize(bytes: Vec<u8>) -> Result<Self, BoxedError> {
+        Ok(Self(bytes))
+    }
+}
+
+async fn fetch_latest_checkpoint_pointer(
+    object_store: &dyn ObjectStore,
+) -> anyhow::Result<Option<LatestTreeCheckpoint>> {
+    match object_store.get::<LatestTreeCheckpoint>(()).await {
+        Ok(latest) => Ok(Some(latest)),
+        Err(ObjectStoreError::KeyNotFound(_)) => Ok(None),
+        Err(err) => Err(anyhow::Error::from(err).context("failed fetching latest tree checkpoint")),
+    }
+}
+
+async fn fetch_latest_checkpoint(
+    object_store: &dyn ObjectStore,
+) -> anyhow::Result<Option<L1BatchNumber>> {
+    let pointer = fetch_latest_checkpoint_pointer(object_store).await?;
+    Ok(pointer.map(|pointer| pointer.l1_batch_number))
+}
+
+/// Removes an object from the store, treating missing objects as already removed.
+async fn remove_if_exists<V: StoredObject>(
+    object_store: &dyn ObjectStore,
+    key: V::Key<'_>,
+) -> Result<(), ObjectStoreError> {
+    match object_store.remove::<V>(key).await {
+        Ok(()) | Err(ObjectStoreError::KeyNotFound(_)) => Ok(()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Tree L1 batch and root hash read from a checkpoint.
+#[derive(Debug)]
+struct CheckpointRoot {
+    l1_batch_number: L1BatchNumber,
+    root_hash: H256,
+    leaf_count: u64,
+}
+
+/// Opens the tree RocksDB at the specified path and reads its latest L1 batch and root hash.
+async fn read_checkpoint_root(path: PathBuf) -> anyhow::Result<CheckpointRoot> {
+    tokio::task::spawn_blocking(move || {
+        let db = RocksDB::<MerkleTreeColumnFamily>::new(&path).with_context(|| {
+            format!(
+                "failed opening Merkle tree checkpoint at `{}`",
+                path.display()
+            )
+        })?;
+        let reader = ZkSyncTreeReader::new(db.into())?;
+        let l1_batch_number = reader
+            .next_l1_batch_number()
+            .checked_sub(1)
+            .context("checkpoint contains an empty tree")?;
+        let l1_batch_number = L1BatchNumber(l1_batch_number);
+        let (root_hash, leaf_count) = reader.root_info(l1_batch_number).with_context(|| {
+            format!("checkpoint contains no tree root for L1 batch #{l1_batch_number}")
+        })?;
+        Ok(CheckpointRoot {
+            l1_batch_number,
+            root_hash,
+            leaf_count,
+        })
+    })
+    .await
+    .context("reading Merkle tree checkpoint panicked")?
+}
+
+/// Lists files in a RocksDB checkpoint directory, skipping RocksDB info logs that aren't necessary to open the DB.
+async fn list_checkpoint_files(path: &Path) -> anyhow::Result<Vec<TreeCheckpointFileInfo>> {
+    let mut files = vec![];
+    let mut entries = fs::read_dir(path).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let metadata = entry.metadata().await?;
+        if !metadata.is_file() {
+            continue;
+        }
+        let name = entry.file_name().into_string().map_err(|name| {
+            anyhow::anyhow!("checkpoint contains file with non-UTF-8 name {name:?}")
+        })?;
+        if name.starts_with("LOG") {
+            continue;
+        }
+        files.push(TreeCheckpointFileInfo {
+            name,
+            size: metadata.len(),
+        });
+    }
+    files.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+    Ok(files)
+}
+
+async fn remove_dir_if_exists(path: &Path) -> anyhow::Result<()> {
+    match fs::remove_dir_all(path).await {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(err) => {
+            Err(err).with_context(|| format!("failed removing directory `{}`", path.display()))
+        }
+    }
+}
+
+async fn is_empty_dir(path: &Path) -> anyhow::Result<bool> {
+    match fs::read_dir(path).await {
+        Ok(mut entries) => Ok(entries.next_entry().await?.is_none()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(true),
+        Err(err) => {
+            Err(err).with_context(|| format!("failed reading directory `{}`", path.display()))
+        }
+    }
+}
+
+fn sibling_path(db_path: &Path, suffix: &str) -> PathBuf {
+    let mut path = db_path.as_os_str().to_owned();
+    path.push(suffix);
+    path.into()
+}
+
+/// Task periodically exporting Merkle tree checkpoints to the object store.
+#[derive(Debug)]
+#[must_use = "Task should `run()` in a managed Tokio task"]
+pub struct MerkleTreeCheckpointTask {
+    tree_reader: LazyAsyncTreeReader,
+    object_store: Arc<dyn ObjectStore>,
+    /// Directory to create checkpoints in. Should be located on the same filesystem as the tree RocksDB
+    /// so that RocksDB can hard-link SST files instead of copying them.
+    checkpoint_path: PathBuf,
+    export_interval: NonZeroU32,
+    poll_interval: Duration,
+    chunk_size: u64,
+    retained_checkpoints: usize,
+}
+
+impl MerkleTreeCheckpointTask {
+    pub(super) fn new(
+        tree_reader: LazyAsyncTreeReader,
+        object_store: Arc<dyn ObjectStore>,
+        db_path: &Path,
+        export_interval: NonZeroU32,
+        poll_interval: Duration,
+    ) -> Self {
+        Self {
+            tree_reader,
+            object_store,
+            checkpoint_path: sibling_path(db_path, "_checkpoint"),
+            export_interval,
+            poll_interval,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            retained_checkpoints: DEFAULT_RETAINED_CHECKPOINTS,
+        }
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let tree_reader = tokio::select! {
+            reader = self.tree_reader.wait() => reader,
+            _ = stop_receiver.changed() => {
+                tracing::info!("Stop signal received before Merkle tree is initialized; shutting down checkpoint export");
+                return Ok(());
+            }
+        };
+        let Some(tree_reader) = tree_reader else {
+            tracing::info!("Merkle tree dropped; shutting down checkpoint export");
+            return Ok(());
+        };
+
+        let mut last_exported_l1_batch = fetch_latest_checkpoint(&*self.object_store).await?;
+        tracing::info!(
+            "Starting exporting Merkle tree checkpoints every {} L1 batches; latest exported checkpoint: {last_exported_l1_batch:?}",
+            self.export_interval
+        );
+
+        while !*stop_receiver.borrow_and_update() {
+            let next_l1_batch_number = tree_reader.clone().info().await.next_l1_batch_number;
+            let should_export = next_l1_batch_number.checked_sub(1).is_some_and(|latest| {
+                last_exported_l1_batch
+                    .map_or(true, |last| latest >= last.0 + self.export_interval.get())
+            });
+
+            if should_export {
+                // Export errors are not fatal; the export will be retried on the next iteration.
+                match Self::export_checkpoint(
+                    tree_reader.clone(),
+                    &*self.object_store,
+                    &self.checkpoint_path,
+                    self.chunk_size,
+                    self.retained_checkpoints,
+                )
+                .await
+                {
+                    Ok(manifest) => last_exported_l1_batch = Some(manifest.l1_batch_number),
+                    Err(err) => tracing::warn!("Failed exporting Merkle tree checkpoint: {err:#}"),
+                }
+                remove_dir_if_exists(&self.checkpoint_path).await?;
+            }
+
+            if tokio::time::timeout(self.poll_interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+
+        tracing::info!("Stop signal received, Merkle tree checkpoint export is shutting down");
+        Ok(())
+    }
+
+    async fn export_checkpoint(
+        tree_reader: AsyncTreeReader,
+        object_store: &dyn ObjectStore,
+        checkpoint_path: &Path,
+        chunk_size: u64,
+        retained_checkpoints: usize,
+    ) -> anyhow::Result<TreeCheckpointManifest> {
+        remove_dir_if_exists(checkpoint_path).await?;
+        if let Some(parent) = checkpoint_path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        let latency = CHECKPOINT_METRICS.latency[&CheckpointStage::Create].start();
+        tree_reader
+            .create_checkpoint(checkpoint_path.to_owned())
+            .await?;
+        let root = read_checkpoint_root(checkpoint_path.to_owned()).await?;
+        let files = list_checkpoint_files(checkpoint_path).await?;
+        let manifest = TreeCheckpointManifest {
+            l1_batch_number: root.l1_batch_number,
+            root_hash: root.root_hash,
+            leaf_count: root.leaf_count,
+            chunk_size,
+            files,
+        };
+        let latency = latency.observe();
+        tracing::info!(
+            "Created Merkle tree checkpoint for L1 batch #{} with {} files ({}B total) in {latency:?}",
+            manifest.l1_batch_number,
+            manifest.files.len(),
+            manifest.total_size()
+        );
+
+        let latency = CHECKPOINT_METRICS.latency[&CheckpointStage::Upload].start();
+        let l1_batch_number = manifest.l1_batch_number;
+        for file in &manifest.files {
+            Self::upload_file(object_store, &manifest, file, checkpoint_path).await?;
+        }
+        object_store
+            .put(l1_batch_number, &manifest)
+            .await
+            .context("failed uploading checkpoint manifest")?;
+        let mut previous_l1_batches = vec![];
+        if let Some(previous) = fetch_latest_checkpoint_pointer(object_store).await? {
+            previous_l1_batches = previous.previous_l1_batches;
+            previous_l1_batches.push(previous.l1_batch_number);
+        }
+        // The checkpoint may be re-exported for the same L1 batch, e.g. if the previous export has failed after updating the pointer.
+        previous_l1_batches.retain(|&number| number != l1_batch_number);
+        let pointer = LatestTreeCheckpoint {
+            l1_batch_number,
+            previous_l1_batches,
+        };
+        object_store
+            .put((), &pointer)
+            .await
+            .context("failed updating latest checkpoint")?;
+        let latency = latency.observe();
+
+        CHECKPOINT_METRICS
+            .exported_l1_batch_number
+            .set(l1_batch_number.0.into());
+        CHECKPOINT_METRICS.exported_size.set(manifest.total_size());
+        tracing::info!(
+            "Exported Merkle tree checkpoint for L1 batch #{l1_batch_number} with root hash {:?} in {latency:?}",
+            manifest.root_hash
+        );
+
+        // Errors are not fatal; removal will be retried after the next export since removed checkpoints are still
+        // listed in the latest checkpoint pointer.
+        if let Err(err) =
+            Self::remove_old_checkpoints(object_store, pointer, retained_checkpoints).await
+        {
+            tracing::warn!("Failed removing old Merkle tree checkpoints: {err:#}");
+        }
+        Ok(manifest)
+    }
+
+    /// Removes all checkpoints except for `retained_checkpoints` latest ones and updates the latest checkpoint pointer.
+    async fn remove_old_checkpoints(
+        object_store: &dyn ObjectStore,
+        mut pointer: LatestTreeCheckpoint,
+        retained_checkpoints: usize,
+    ) -> anyhow::Result<()> {
+        let retained_previous_count = retained_checkpoints.saturating_sub(1);
+        let removed_count = pointer
+            .previous_l1_batches
+            .len()
+            .saturating_sub(retained_previous_count);
+        if removed_count == 0 {
+            return Ok(());
+        }
+
+        let removed_l1_batches: Vec<_> =
+            pointer.previous_l1_batches.drain(..removed_count).collect();
+        for l1_batch_number in removed_l1_batches {
+            Self::remove_checkpoint(object_store, l1_batch_number).await?;
+        }
+        object_store
+            .put((), &pointer)
+            .await
+            .context("failed updating latest checkpoint")?;
+        Ok(())
+    }
+
+    /// Removes file chunks and then the manifest of the specified checkpoint. The manifest is removed last so that
+    /// removal can be retried if it fails midway.
+    async fn remove_checkpoint(
+        object_store: &dyn ObjectStore,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        let manifest = match object_store
+            .get::<TreeCheckpointManifest>(l1_batch_number)
+            .await
+        {
+            Ok(manifest) => manifest,
+            Err(ObjectStoreError::KeyNotFound(_)) => {
+                tracing::info!(
+                    "Manifest for Merkle tree checkpoint for L1 batch #{l1_batch_number} is missing; assuming the checkpoint is removed"
+                );
+                return Ok(());
+            }
+            Err(err) => {
+                return Err(err).with_context(|| {
+                    format!("failed fetching manifest for tree checkpoint for L1 batch #{l1_batch_number}")
+                });
+            }
+        };
+
+        for file in &manifest.files {
+            for chunk_index in 0..manifest.chunk_count(file) {
+                let key = (l1_batch_number, file.name.as_str(), chunk_index);
+                remove_if_exists::<TreeCheckpointFileChunk>(object_store, key)
+                    .await
+                    .with_context(|| {
+                        format!(
+                            "failed removing chunk #{chunk_index} of checkpoint file `{}`",
+                            file.name
+                        )
+                    })?;
+            }
+        }
+        remove_if_exists::<TreeCheckpointManifest>(object_store, l1_batch_number)
+            .await
+            .context("failed removing checkpoint manifest")?;
+        tracing::info!("Removed Merkle tree checkpoint for L1 batch #{l1_batch_number}");
+        Ok(())
+    }
+
+    async fn upload_file(
+        object_store: &dyn ObjectStore,
+        manifest: &TreeCheckpointManifest,
+        file: &TreeCheckpointFileInfo,
+        checkpoint_path: &Path,
+    ) -> anyhow::Result<()> {
+        let mut reader = fs::File::open(checkpoint_path.join(&file.name))
+            .await
+            .with_context(|| format!("failed opening checkpoint file `{}`", file.name))?;
+        for chunk_index in 0..manifest.chunk_count(file) {
+            let expected_size = manifest.expected_chunk_size(file, chunk_index);
+            let mut chunk = Vec::with_capacity(expected_size as usize);
+            (&mut reader)
+                .take(expected_size)
+                .read_to_end(&mut chunk)
+                .await
+                .with_context(|| format!("failed reading checkpoint file `{}`", file.name))?;
+            anyhow::ensure!(
+                chunk.len() as u64 == expected_size,
+                "checkpoint file `{}` was truncated while being uploaded",
+                file.name
+            );
+            object_store
+                .put(
+                    (manifest.l1_batch_number, file.name.as_str(), chunk_index),
+                    &TreeCheckpointFileChunk(chunk),
+                )
+                .await
+                .with_context(|| {
+                    format!(
+                        "failed uploading chunk #{chunk_index} of checkpoint file `{}`",
+                        file.name
+                    )
+                })?;
+        }
+        Ok(())
+    }
+}
+
+/// Root hash of the checkpoint L1 batch as stored in Postgres.
+#[derive(Debug)]
+enum PostgresRootHash {
+    Available(H256),
+    /// The checkpoint L1 batch is pruned or precedes the snapshot the node was recovered from.
+    Pruned,
+    /// A stop signal was received while waiting for the root hash.
+    Interrupted,
+}
+
+/// Outcome of [`TreeCheckpointImporter::import()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum CheckpointImportOutcome {
+    /// A checkpoint for the specified L1 batch was imported.
+    Imported(L1BatchNumber),
+    /// The import was skipped, e.g. because the tree is already initialized or there are no checkpoints.
+    Skipped,
+    /// The import was interrupted by a stop signal.
+    Interrupted,
+}
+
+/// Imports the latest Merkle tree checkpoint from the object store if the tree is not initialized.
+#[derive(Debug)]
+pub(super) struct TreeCheckpointImporter {
+    object_store: Arc<dyn ObjectStore>,
+    db_path: PathBuf,
+    poll_interval: Duration,
+}
+
+impl TreeCheckpointImporter {
+    pub fn new(
+        object_store: Arc<dyn ObjectStore>,
+        db_path: &Path,
+        poll_interval: Duration,
+    ) -> Self {
+        Self {
+            object_store,
+            db_path: db_path.to_owned(),
+            poll_interval,
+        }
+    }
+
+    /// Imports the latest checkpoint into the tree directory, validating its root hash against Postgres.
+    pub async fn import(
+        &self,
+        pool: &ConnectionPool<Core>,
+        stop_receiver: &mut watch::Receiver<bool>,
+    ) -> anyhow::Result<CheckpointImportOutcome> {
+        if !is_empty_dir(&self.db_path).await? {
+            tracing::info!(
+                "Merkle tree at `{}` is already initialized; skipping checkpoint import",
+                self.db_path.display()
+            );
+            return Ok(CheckpointImportOutcome::Skipped);
+        }
+        let Some(l1_batch_number) = fetch_latest_checkpoint(&*self.object_store).await? else {
+            tracing::info!(
+                "No Merkle tree checkpoints in the object store; skipping checkpoint import"
+            );
+            return Ok(CheckpointImportOutcome::Skipped);
+        };
+        let manifest: TreeCheckpointManifest = self
+            .object_store
+            .get(l1_batch_number)
+            .await
+            .with_context(|| {
+                format!(
+                    "failed fetching manifest for tree checkpoint for L1 batch #{l1_batch_number}"
+                )
+            })?;
+        anyhow::ensure!(
+            manifest.l1_batch_number == l1_batch_number,
+            "Tree checkpoint manifest has unexpected L1 batch number: expected {l1_batch_number}, got {}",
+            manifest.l1_batch_number
+        );
+        anyhow::ensure!(
+            manifest.chunk_size > 0,
+            "Tree checkpoint manifest for L1 batch #{l1_batch_number} has zero chunk size"
+        );
+
+        match self
+            .wait_for_root_hash(pool, l1_batch_number, stop_receiver)
+            .await?
+        {
+            PostgresRootHash::Available(expected_root_hash) => {
+                anyhow::ensure!(
+                    manifest.root_hash == expected_root_hash,
+                    "Root hash in the tree checkpoint for L1 batch #{l1_batch_number} ({:?}) differs from the root hash in Postgres ({expected_root_hash:?})",
+                    manifest.root_hash
+                );
+            }
+            PostgresRootHash::Pruned => {
+                tracing::warn!(
+                    "L1 batch #{l1_batch_number} of the latest tree checkpoint is not present in Postgres, probably because it's pruned; \
+                     skipping checkpoint import"
+                );
+                return Ok(CheckpointImportOutcome::Skipped);
+            }
+            PostgresRootHash::Interrupted => return Ok(CheckpointImportOutcome::Interrupted),
+        }
+
+        tracing::info!(
+            "Importing Merkle tree checkpoint for L1 batch #{l1_batch_number} with {} files ({}B total)",
+            manifest.files.len(),
+            manifest.total_size()
+        );
+        let import_path = sibling_path(&self.db_path, "_checkpoint_import");
+        remove_dir_if_exists(&import_path).await?;
+        fs::create_dir_all(&import_path).await?;
+
+        let latency = CHECKPOINT_METRICS.latency[&CheckpointStage::Download].start();
+        for file in &manifest.files {
+            let is_completed = Self::download_file(
+                &*self.object_store,
+                &manifest,
+                file,
+                &import_path,
+                stop_receiver,
+            )
+            .await?;
+            if !is_completed {
+                return Ok(CheckpointImportOutcome::Interrupted);
+            }
+        }
+        let latency = latency.observe();
+        tracing::info!(
+            "Downloaded Merkle tree checkpoint for L1 batch #{l1_batch_number} in {latency:?}"
+        );
+
+        let latency = CHECKPOINT_METRICS.latency[&CheckpointStage::Validate].start();
+        let root = read_checkpoint_root(import_path.clone()).await?;
+        anyhow::ensure!(
+            root.l1_batch_number == l1_batch_number && root.root_hash == manifest.root_hash,
+            "Imported tree checkpoint is inconsistent with its manifest: expected L1 batch #{l1_batch_number} with root hash {:?}, \
+             got L1 batch #{} with root hash {:?}",
+            manifest.root_hash,
+            root.l1_batch_number,
+            root.root_hash
+        );
+        latency.observe();
+
+        // The tree directory is either missing or empty, so it's safe to remove it.
+        remove_dir_if_exists(&self.db_path).await?;
+        if let Some(parent) = self.db_path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        fs::rename(&import_path, &self.db_path)
+            .await
+            .with_context(|| {
+                format!(
+                    "failed moving imported tree checkpoint to `{}`",
+                    self.db_path.display()
+                )
+            })?;
+        tracing::info!(
+            "Imported Merkle tree checkpoint for L1 batch #{l1_batch_number} with root hash {:?}",
+            manifest.root_hash
+        );
+        Ok(CheckpointImportOutcome::Imported(l1_batch_number))
+    }
+
+    async fn wait_for_root_hash(
+        &self,
+        pool: &ConnectionPool<Core>,
+        l1_batch_number: L1BatchNumber,
+        stop_receiver: &mut watch::Receiver<bool>,
+    ) -> anyhow::Result<PostgresRootHash> {
+        loop {
+            let mut storage = pool.connection_tagged("metadata_calculator").await?;
+            let earliest_l1_batch = storage.blocks_dal().get_earliest_l1_batch_number().await?;
+            if earliest_l1_batch.is_some_and(|earliest| earliest > l1_batch_number) {
+                return Ok(PostgresRootHash::Pruned);
+            }
+            let tree_data = storage
+                .blocks_dal()
+                .get_l1_batch_tree_data(l1_batch_number)
+                .await?;
+            drop(storage);
+            if let Some(tree_data) = tree_data {
+                return Ok(PostgresRootHash::Available(tree_data.hash));
+            }
+
+            tracing::info!(
+                "Waiting for the root hash for L1 batch #{l1_batch_number} to appear in Postgres in order to validate \
+                 the tree checkpoint. On external nodes, root hashes can be fetched from the main node by the tree data fetcher"
+            );
+            if tokio::time::timeout(self.poll_interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                return Ok(PostgresRootHash::Interrupted);
+            }
+        }
+    }
+
+    /// Downloads a single checkpoint file chunk by chunk. Returns `false` if the download was interrupted
+    /// by a stop signal.
+    async fn download_file(
+        object_store: &dyn ObjectStore,
+        manifest: &TreeCheckpointManifest,
+        file: &TreeCheckpointFileInfo,
+        import_path: &Path,
+        stop_receiver: &watch::Receiver<bool>,
+    ) -> anyhow::Result<bool> {
+        // Guard against path traversal via a malicious manifest.
+        let is_plain_name = Path::new(&file.name)
+            .file_name()
+            .is_some_and(|name| name == file.name.as_str());
+        anyhow::ensure!(
+            is_plain_name,
+            "Invalid file name in tree checkpoint: {:?}",
+            file.name
+        );
+
+        let mut writer = fs::File::create(import_path.join(&file.name))
+            .await
+            .with_context(|| format!("failed creating checkpoint file `{}`", file.name))?;
+        for chunk_index in 0..manifest.chunk_count(file) {
+            if *stop_receiver.borrow() {
+                return Ok(false);
+            }
+
+            let TreeCheckpointFileChunk(chunk) = object_store
+                .get((manifest.l1_batch_number, file.name.as_str(), chunk_index))
+                .await
+                .with_context(|| {
+                    format!(
+                        "failed downloading chunk #{chunk_index} of checkpoint file `{}`",
+                        file.name
+                    )
+                })?;
+            let expected_size = manifest.expected_chunk_size(file, chunk_index);
+            anyhow::ensure!(
+                chunk.len() as u64 == expected_size,
+                "Unexpected size of chunk #{chunk_index} of checkpoint file `{}`: expected {expected_size}B, got {}B",
+                file.name,
+                chunk.len()
+            );
+            writer
+                .write_all(&chunk)
+                .await
+                .with_context(|| format!("failed writing checkpoint file `{}`", file.name))?;
+        }
+        writer
+            .sync_all()
+            .await
+            .with_context(|| format!("failed syncing checkpoint file `{}`", file.name))?;
+        Ok(true)
+    }
+}
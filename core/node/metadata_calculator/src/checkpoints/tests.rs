@@ -0,0 +1,304 @@
+//! Tests for Merkle tree checkpoints.
+
+use assert_matches::assert_matches;
+use tempfile::TempDir;
+use zksync_config::configs::database::MerkleTreeMode;
+use zksync_object_store::MockObjectStore;
+use zksync_types::block::L1BatchTreeData;
+
+use super::*;
+use crate::{
+    helpers::{create_db, AsyncTree},
+    tests::{
+        expected_tree_hash, extend_db_state, gen_storage_logs, mock_config, reset_db_state,
+        run_calculator, setup_calculator,
+    },
+    GenericAsyncTree, MetadataCalculator,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// Small chunk size to check that checkpoint files are split into multiple chunks.
+const CHUNK_SIZE: u64 = 1_024;
+
+/// Builds a tree with 5 L1 batches and exports its checkpoint to the returned object store.
+async fn prepare_checkpoint(
+    pool: &ConnectionPool<Core>,
+    temp_dir: &TempDir,
+) -> (Arc<dyn ObjectStore>, TreeCheckpointManifest) {
+    let object_store = MockObjectStore::arc();
+    let manifest = prepare_checkpoint_in(pool, temp_dir, &*object_store).await;
+    (object_store, manifest)
+}
+
+/// Builds a tree with 5 L1 batches and exports its checkpoint to the provided object store.
+async fn prepare_checkpoint_in(
+    pool: &ConnectionPool<Core>,
+    temp_dir: &TempDir,
+    object_store: &dyn ObjectStore,
+) -> TreeCheckpointManifest {
+    let (calculator, _) = setup_calculator(&temp_dir.path().join("main"), pool.clone(), true).await;
+    reset_db_state(pool, 5).await;
+    let root_hash = run_calculator(calculator).await;
+
+    let (calculator, _) = setup_calculator(&temp_dir.path().join("main"), pool.clone(), true).await;
+    let GenericAsyncTree::Ready(tree) = calculator.create_tree().await.unwrap() else {
+        panic!("Unexpected tree state");
+    };
+    let checkpoint_path = temp_dir.path().join("checkpoint");
+    let manifest = MerkleTreeCheckpointTask::export_checkpoint(
+        tree.reader(),
+        object_store,
+        &checkpoint_path,
+        CHUNK_SIZE,
+        DEFAULT_RETAINED_CHECKPOINTS,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(manifest.l1_batch_number, L1BatchNumber(5));
+    assert_eq!(manifest.root_hash, root_hash);
+    assert!(!manifest.files.is_empty());
+    assert!(manifest
+        .files
+        .iter()
+        .all(|file| !file.name.starts_with("LOG")));
+    assert_eq!(manifest.chunk_size, CHUNK_SIZE);
+    assert!(manifest
+        .files
+        .iter()
+        .any(|file| manifest.chunk_count(file) > 1));
+    let latest = fetch_latest_checkpoint(object_store).await.unwrap();
+    assert_eq!(latest, Some(L1BatchNumber(5)));
+    manifest
+}
+
+/// Stores a fake checkpoint with a single file split into 2 chunks.
+async fn store_fake_checkpoint(
+    object_store: &dyn ObjectStore,
+    l1_batch_number: L1BatchNumber,
+) -> TreeCheckpointManifest {
+    let manifest = TreeCheckpointManifest {
+        l1_batch_number,
+        root_hash: H256::repeat_byte(0x23),
+        leaf_count: 10,
+        chunk_size: CHUNK_SIZE,
+        files: vec![TreeCheckpointFileInfo {
+            name: "000001.sst".to_owned(),
+            size: CHUNK_SIZE + 1,
+        }],
+    };
+    let file = &manifest.files[0];
+    for chunk_index in 0..manifest.chunk_count(file) {
+        let chunk_size = manifest.expected_chunk_size(file, chunk_index);
+        let chunk = TreeCheckpointFileChunk(vec![0; chunk_size as usize]);
+        let key = (l1_batch_number, file.name.as_str(), chunk_index);
+        object_store.put(key, &chunk).await.unwrap();
+    }
+    object_store.put(l1_batch_number, &manifest).await.unwrap();
+    manifest
+}
+
+async fn assert_checkpoint_exists(
+    object_store: &dyn ObjectStore,
+    manifest: &TreeCheckpointManifest,
+    should_exist: bool,
+) {
+    let l1_batch_number = manifest.l1_batch_number;
+    let stored_manifest = object_store
+        .get::<TreeCheckpointManifest>(l1_batch_number)
+        .await;
+    let file = &manifest.files[0];
+    let chunks = (0..manifest.chunk_count(file))
+        .map(|chunk_index| (l1_batch_number, file.name.as_str(), chunk_index));
+    if should_exist {
+        assert_eq!(stored_manifest.unwrap(), *manifest);
+        for key in chunks {
+            object_store
+                .get::<TreeCheckpointFileChunk>(key)
+                .await
+                .unwrap();
+        }
+    } else {
+        assert_matches!(stored_manifest, Err(ObjectStoreError::KeyNotFound(_)));
+        for key in chunks {
+            let chunk = object_store.get::<TreeCheckpointFileChunk>(key).await;
+            assert_matches!(chunk, Err(ObjectStoreError::KeyNotFound(_)));
+        }
+    }
+}
+
+#[tokio::test]
+async fn old_checkpoints_are_removed() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let object_store = MockObjectStore::arc();
+    let oldest_manifest = store_fake_checkpoint(&*object_store, L1BatchNumber(2)).await;
+    let previous_manifest = store_fake_checkpoint(&*object_store, L1BatchNumber(3)).await;
+    let pointer = LatestTreeCheckpoint {
+        l1_batch_number: L1BatchNumber(3),
+        previous_l1_batches: vec![L1BatchNumber(2)],
+    };
+    object_store.put((), &pointer).await.unwrap();
+
+    let manifest = prepare_checkpoint_in(&pool, &temp_dir, &*object_store).await;
+
+    let pointer = object_store.get::<LatestTreeCheckpoint>(()).await.unwrap();
+    assert_eq!(
+        pointer,
+        LatestTreeCheckpoint {
+            l1_batch_number: manifest.l1_batch_number,
+            previous_l1_batches: vec![L1BatchNumber(3)],
+        }
+    );
+    assert_checkpoint_exists(&*object_store, &oldest_manifest, false).await;
+    assert_checkpoint_exists(&*object_store, &previous_manifest, true).await;
+    let stored_manifest = object_store
+        .get::<TreeCheckpointManifest>(manifest.l1_batch_number)
+        .await
+        .unwrap();
+    assert_eq!(stored_manifest, manifest);
+}
+
+#[tokio::test]
+async fn exporting_and_importing_checkpoint() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let (object_store, manifest) = prepare_checkpoint(&pool, &temp_dir).await;
+
+    let imported_path = temp_dir.path().join("imported");
+    let importer = TreeCheckpointImporter::new(object_store, &imported_path, POLL_INTERVAL);
+    let (_stop_sender, mut stop_receiver) = watch::channel(false);
+    let outcome = importer.import(&pool, &mut stop_receiver).await.unwrap();
+    assert_eq!(outcome, CheckpointImportOutcome::Imported(L1BatchNumber(5)));
+    assert!(!sibling_path(&imported_path, "_checkpoint_import").exists());
+
+    let db = create_db(mock_config(&imported_path)).await.unwrap();
+    let tree = AsyncTree::new(db.into(), MerkleTreeMode::Full).unwrap();
+    assert_eq!(tree.next_l1_batch_number(), L1BatchNumber(6));
+    assert_eq!(tree.root_hash(), manifest.root_hash);
+    drop(tree);
+
+    // Repeated import should be a no-op since the tree is already initialized.
+    let outcome = importer.import(&pool, &mut stop_receiver).await.unwrap();
+    assert_eq!(outcome, CheckpointImportOutcome::Skipped);
+}
+
+#[tokio::test]
+async fn checkpoint_with_root_hash_mismatch_is_rejected() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let (object_store, _) = prepare_checkpoint(&pool, &temp_dir).await;
+
+    let bogus_tree_data = L1BatchTreeData {
+        hash: H256::repeat_byte(0xfe),
+        rollup_last_leaf_index: 200,
+    };
+    pool.connection()
+        .await
+        .unwrap()
+        .blocks_dal()
+        .save_l1_batch_tree_data(L1BatchNumber(5), &bogus_tree_data)
+        .await
+        .unwrap();
+
+    let imported_path = temp_dir.path().join("imported");
+    let importer = TreeCheckpointImporter::new(object_store, &imported_path, POLL_INTERVAL);
+    let (_stop_sender, mut stop_receiver) = watch::channel(false);
+    let err = importer
+        .import(&pool, &mut stop_receiver)
+        .await
+        .unwrap_err();
+    let err = format!("{err:#}");
+    assert!(
+        err.contains("differs from the root hash in Postgres"),
+        "{err}"
+    );
+    assert!(!imported_path.exists());
+}
+
+#[tokio::test]
+async fn checkpoint_with_truncated_chunk_is_rejected() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let (object_store, manifest) = prepare_checkpoint(&pool, &temp_dir).await;
+
+    let file = manifest
+        .files
+        .iter()
+        .find(|file| manifest.chunk_count(file) > 1)
+        .unwrap();
+    let key = (manifest.l1_batch_number, file.name.as_str(), 0);
+    let TreeCheckpointFileChunk(mut chunk) = object_store.get(key).await.unwrap();
+    chunk.pop();
+    object_store
+        .put(key, &TreeCheckpointFileChunk(chunk))
+        .await
+        .unwrap();
+
+    let imported_path = temp_dir.path().join("imported");
+    let importer = TreeCheckpointImporter::new(object_store, &imported_path, POLL_INTERVAL);
+    let (_stop_sender, mut stop_receiver) = watch::channel(false);
+    let err = importer
+        .import(&pool, &mut stop_receiver)
+        .await
+        .unwrap_err();
+    let err = format!("{err:#}");
+    assert!(err.contains("Unexpected size of chunk #0"), "{err}");
+    assert!(!imported_path.exists());
+}
+
+#[tokio::test]
+async fn checkpoint_import_waits_for_root_hash() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let (object_store, manifest) = prepare_checkpoint(&pool, &temp_dir).await;
+
+    let tree_data = pool
+        .connection()
+        .await
+        .unwrap()
+        .blocks_dal()
+        .get_l1_batch_tree_data(L1BatchNumber(5))
+        .await
+        .unwrap()
+        .expect("no tree data");
+    assert_eq!(tree_data.hash, manifest.root_hash);
+    // Recreate L1 batches without tree data, emulating an external node that hasn't fetched root hashes yet.
+    reset_db_state(&pool, 5).await;
+
+    let imported_path = temp_dir.path().join("imported");
+    let importer = TreeCheckpointImporter::new(object_store, &imported_path, POLL_INTERVAL);
+    let (stop_sender, mut stop_receiver) = watch::channel(false);
+    let import_task = tokio::spawn(async move {
+        let outcome = importer.import(&pool, &mut stop_receiver).await.unwrap();
+        (outcome, pool)
+    });
+    tokio::time::sleep(POLL_INTERVAL * 3).await;
+    assert!(!import_task.is_finished());
+
+    stop_sender.send_replace(true);
+    let (outcome, _) = import_task.await.unwrap();
+    assert_eq!(outcome, CheckpointImportOutcome::Interrupted);
+    assert!(!imported_path.exists());
+}
+
+#[tokio::test]
+async fn calculator_continues_from_imported_checkpoint() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let (checkpoint_store, _) = prepare_checkpoint(&pool, &temp_dir).await;
+
+    let mut storage = pool.connection().await.unwrap();
+    let logs = gen_storage_logs(100..200, 3);
+    extend_db_state(&mut storage, logs).await;
+    drop(storage);
+
+    let mut config = mock_config(&temp_dir.path().join("imported"));
+    config.checkpoints.import_enabled = true;
+    let calculator = MetadataCalculator::new(config, Some(MockObjectStore::arc()), pool.clone())
+        .await
+        .unwrap()
+        .with_checkpoint_store(checkpoint_store);
+    let root_hash = run_calculator(calculator).await;
+    assert_eq!(root_hash, expected_tree_hash(&pool, true).await);
+}
@@ -1,9 +1,10 @@
 //! Various helpers for the metadata calculator.
 
 use std::{
+    any::Any,
     collections::{BTreeMap, HashSet},
     future::Future,
-    path::Path,
+    path::{Path, PathBuf},
     sync::Arc,
     time::Duration,
 };
@@ -16,14 +17,15 @@ use serde::{Deserialize, Serialize};
 #[cfg(test)]
 use tokio::sync::mpsc;
 use tokio::sync::watch;
-use zksync_config::configs::database::MerkleTreeMode;
-use zksync_dal::{Connection, Core, CoreDal};
+use zksync_config::configs::database::{MerkleTreeMode, MerkleTreeStorage};
+use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
 use zksync_health_check::{CheckHealth, Health, HealthStatus, ReactiveHealthCheck};
 use zksync_merkle_tree::{
     domain::{TreeMetadata, ZkSyncTree, ZkSyncTreeReader},
     recovery::{MerkleTreeRecovery, PersistenceThreadHandle},
-    Database, Key, MerkleTreeColumnFamily, NoVersionError, RocksDBWrapper, TreeEntry,
-    TreeEntryWithProof, TreeInstruction,
+    unstable::{DeserializeError, Manifest, Node, NodeKey, NodeKeys, ProfiledTreeOperation, Root},
+    Database, Key, MerkleTreeColumnFamily, NoVersionError, PatchSet, PruneDatabase, PrunePatchSet,
+    RocksDBWrapper, TreeEntry, TreeEntryWithProof, TreeInstruction, TreeMultiProof,
 };
 use zksync_storage::{RocksDB, RocksDBOptions, StalledWritesRetries, WeakRocksDB};
 use zksync_types::{
@@ -34,6 +36,7 @@ use zksync_types::{
 
 use super::{
     metrics::{LoadChangesStage, TreeUpdateStage, METRICS},
+    postgres_storage::{PostgresTreeDatabase, PostgresTreeStorage},
     pruning::PruningHandles,
     MetadataCalculatorConfig, MetadataCalculatorRecoveryConfig,
 };
@@ -176,6 +179,101 @@ fn create_db_sync(config: &MetadataCalculatorConfig) -> anyhow::Result<RocksDBWr
     Ok(db)
 }
 
+/// Creates a tree database with the storage backend specified in the config. `pool` is only used
+/// for the Postgres storage.
+pub(super) async fn create_tree_db(
+    config: MetadataCalculatorConfig,
+    pool: &ConnectionPool<Core>,
+) -> anyhow::Result<TreeDatabase> {
+    Ok(match config.storage {
+        MerkleTreeStorage::RocksDB => create_db(config).await?.into(),
+        MerkleTreeStorage::Postgres => {
+            tracing::info!("Initializing Merkle tree database in Postgres");
+            let storage = PostgresTreeStorage::new(pool.clone(), tokio::runtime::Handle::current());
+            storage.into_database().into()
+        }
+    })
+}
+
+/// Merkle tree database used by [`MetadataCalculator`](crate::MetadataCalculator).
+#[derive(Debug, Clone)]
+pub(crate) enum TreeDatabase {
+    RocksDB(RocksDBWrapper),
+    Postgres(PostgresTreeDatabase),
+}
+
+impl From<RocksDBWrapper> for TreeDatabase {
+    fn from(db: RocksDBWrapper) -> Self {
+        Self::RocksDB(db)
+    }
+}
+
+impl From<PostgresTreeDatabase> for TreeDatabase {
+    fn from(db: PostgresTreeDatabase) -> Self {
+        Self::Postgres(db)
+    }
+}
+
+impl TreeDatabase {
+    fn as_dyn(&self) -> &dyn PruneDatabase {
+        match self {
+            Self::RocksDB(db) => db,
+            Self::Postgres(db) => db,
+        }
+    }
+
+    fn as_dyn_mut(&mut self) -> &mut dyn PruneDatabase {
+        match self {
+            Self::RocksDB(db) => db,
+            Self::Postgres(db) => db,
+        }
+    }
+}
+
+impl Database for TreeDatabase {
+    fn try_manifest(&self) -> Result<Option<Manifest>, DeserializeError> {
+        self.as_dyn().try_manifest()
+    }
+
+    fn try_root(&self, version: u64) -> Result<Option<Root>, DeserializeError> {
+        self.as_dyn().try_root(version)
+    }
+
+    fn try_tree_node(
+        &self,
+        key: &NodeKey,
+        is_leaf: bool,
+    ) -> Result<Option<Node>, DeserializeError> {
+        self.as_dyn().try_tree_node(key, is_leaf)
+    }
+
+    fn tree_nodes(&self, keys: &NodeKeys) -> Vec<Option<Node>> {
+        self.as_dyn().tree_nodes(keys)
+    }
+
+    fn start_profiling(&self, operation: ProfiledTreeOperation) -> Box<dyn Any> {
+        self.as_dyn().start_profiling(operation)
+    }
+
+    fn apply_patch(&mut self, patch: PatchSet) -> anyhow::Result<()> {
+        self.as_dyn_mut().apply_patch(patch)
+    }
+}
+
+impl PruneDatabase for TreeDatabase {
+    fn min_stale_key_version(&self) -> Option<u64> {
+        self.as_dyn().min_stale_key_version()
+    }
+
+    fn stale_keys(&self, version: u64) -> Vec<NodeKey> {
+        self.as_dyn().stale_keys(version)
+    }
+
+    fn prune(&mut self, patch: PrunePatchSet) -> anyhow::Result<()> {
+        self.as_dyn_mut().prune(patch)
+    }
+}
+
 /// Wrapper around the "main" tree implementation used by [`MetadataCalculator`].
 ///
 /// Async methods provided by this wrapper are not cancel-safe! This is probably not an issue;
@@ -186,7 +284,7 @@ fn create_db_sync(config: &MetadataCalculatorConfig) -> anyhow::Result<RocksDBWr
 /// cancellation is most probably the reason.
 #[derive(Debug)]
 pub(super) struct AsyncTree {
-    inner: Option<ZkSyncTree>,
+    inner: Option<ZkSyncTree<TreeDatabase>>,
     mode: MerkleTreeMode,
 }
 
@@ -194,22 +292,20 @@ impl AsyncTree {
     const INCONSISTENT_MSG: &'static str =
         "`AsyncTree` is in inconsistent state, which could occur after one of its async methods was cancelled or returned an error";
 
-    pub fn new(db: RocksDBWrapper, mode: MerkleTreeMode) -> anyhow::Result<Self> {
-        let tree = match mode {
-            MerkleTreeMode::Full => ZkSyncTree::new(db),
-            MerkleTreeMode::Lightweight => ZkSyncTree::new_lightweight(db),
-        }?;
+    pub fn new(db: TreeDatabase, mode: MerkleTreeMode) -> anyhow::Result<Self> {
+        let lightweight = matches!(mode, MerkleTreeMode::Lightweight);
+        let tree = ZkSyncTree::with_database(db, lightweight)?;
         Ok(Self {
             inner: Some(tree),
             mode,
         })
     }
 
-    fn as_ref(&self) -> &ZkSyncTree {
+    fn as_ref(&self) -> &ZkSyncTree<TreeDatabase> {
         self.inner.as_ref().expect(Self::INCONSISTENT_MSG)
     }
 
-    fn as_mut(&mut self) -> &mut ZkSyncTree {
+    fn as_mut(&mut self) -> &mut ZkSyncTree<TreeDatabase> {
         self.inner.as_mut().expect(Self::INCONSISTENT_MSG)
     }
 
@@ -302,14 +398,21 @@ impl AsyncTree {
 /// Async version of [`ZkSyncTreeReader`].
 #[derive(Debug, Clone)]
 pub struct AsyncTreeReader {
-    inner: ZkSyncTreeReader,
+    inner: ZkSyncTreeReader<TreeDatabase>,
     mode: MerkleTreeMode,
 }
 
 impl AsyncTreeReader {
     fn downgrade(&self) -> WeakAsyncTreeReader {
+        let db = match self.inner.db() {
+            TreeDatabase::RocksDB(db) => {
+                WeakTreeDatabase::RocksDB(db.clone().into_inner().downgrade())
+            }
+            // Postgres connection pool doesn't block node shutdown, so it's fine to hold a strong reference.
+            TreeDatabase::Postgres(db) => WeakTreeDatabase::Postgres(db.clone()),
+        };
         WeakAsyncTreeReader {
-            db: self.inner.db().clone().into_inner().downgrade(),
+            db,
             mode: self.mode,
         }
     }
@@ -366,19 +469,59 @@ impl AsyncTreeReader {
             .await
             .unwrap()
     }
+
+    pub async fn entries_with_multiproof(
+        self,
+        l1_batch_number: L1BatchNumber,
+        keys: Vec<Key>,
+    ) -> Result<TreeMultiProof, NoVersionError> {
+        tokio::task::spawn_blocking(move || {
+            self.inner.entries_with_multiproof(l1_batch_number, &keys)
+        })
+        .await
+        .unwrap()
+    }
+
+    /// Creates a consistent RocksDB checkpoint of the tree at the specified path, which must not exist.
+    pub(super) async fn create_checkpoint(self, path: PathBuf) -> anyhow::Result<()> {
+        let TreeDatabase::RocksDB(db) = self.inner.db() else {
+            anyhow::bail!("Merkle tree checkpoints are only supported for RocksDB storage");
+        };
+        let db = db.clone().into_inner();
+        tokio::task::spawn_blocking(move || {
+            db.create_checkpoint(&path).with_context(|| {
+                format!(
+                    "failed creating Merkle tree checkpoint at `{}`",
+                    path.display()
+                )
+            })
+        })
+        .await
+        .context("creating Merkle tree checkpoint panicked")?
+    }
+}
+
+#[derive(Debug)]
+enum WeakTreeDatabase {
+    RocksDB(WeakRocksDB<MerkleTreeColumnFamily>),
+    Postgres(PostgresTreeDatabase),
 }
 
 /// Version of async tree reader that holds a weak reference to RocksDB. Used in [`MerkleTreeHealthCheck`].
 #[derive(Debug)]
 struct WeakAsyncTreeReader {
-    db: WeakRocksDB<MerkleTreeColumnFamily>,
+    db: WeakTreeDatabase,
     mode: MerkleTreeMode,
 }
 
 impl WeakAsyncTreeReader {
     fn upgrade(&self) -> Option<AsyncTreeReader> {
+        let db = match &self.db {
+            WeakTreeDatabase::RocksDB(db) => RocksDBWrapper::from(db.upgrade()?).into(),
+            WeakTreeDatabase::Postgres(db) => db.clone().into(),
+        };
         Some(AsyncTreeReader {
-            inner: ZkSyncTreeReader::new(self.db.upgrade()?.into()).ok()?,
+            inner: ZkSyncTreeReader::with_database(db).ok()?,
             mode: self.mode,
         })
     }
@@ -409,7 +552,7 @@ impl LazyAsyncTreeReader {
 /// Async wrapper for [`MerkleTreeRecovery`].
 #[derive(Debug, Default)]
 pub(super) struct AsyncTreeRecovery {
-    inner: Option<MerkleTreeRecovery<RocksDBWrapper>>,
+    inner: Option<MerkleTreeRecovery<TreeDatabase>>,
     mode: MerkleTreeMode,
 }
 
@@ -418,7 +561,7 @@ impl AsyncTreeRecovery {
         "`AsyncTreeRecovery` is in inconsistent state, which could occur after one of its async methods was cancelled";
 
     pub fn new(
-        db: RocksDBWrapper,
+        db: TreeDatabase,
         recovered_version: u64,
         mode: MerkleTreeMode,
         config: &MetadataCalculatorRecoveryConfig,
@@ -428,7 +571,7 @@ impl AsyncTreeRecovery {
 
     // Public for testing purposes
     pub fn with_handle(
-        db: RocksDBWrapper,
+        db: TreeDatabase,
         recovered_version: u64,
         mode: MerkleTreeMode,
         config: &MetadataCalculatorRecoveryConfig,
@@ -542,7 +685,7 @@ impl AsyncTreeRecovery {
 pub(super) enum GenericAsyncTree {
     /// Uninitialized tree.
     Empty {
-        db: RocksDBWrapper,
+        db: TreeDatabase,
         mode: MerkleTreeMode,
     },
     /// The tree during recovery.
@@ -552,10 +695,7 @@ pub(super) enum GenericAsyncTree {
 }
 
 impl GenericAsyncTree {
-    pub async fn new(
-        db: RocksDBWrapper,
-        config: &MetadataCalculatorConfig,
-    ) -> anyhow::Result<Self> {
+    pub async fn new(db: TreeDatabase, config: &MetadataCalculatorConfig) -> anyhow::Result<Self> {
         let mode = config.mode;
         let recovery = config.recovery.clone();
         tokio::task::spawn_blocking(move || {
@@ -979,7 +1119,7 @@ mod tests {
 
     async fn create_tree(temp_dir: &TempDir) -> AsyncTree {
         let db = create_db(mock_config(temp_dir.path())).await.unwrap();
-        AsyncTree::new(db, MerkleTreeMode::Full).unwrap()
+        AsyncTree::new(db.into(), MerkleTreeMode::Full).unwrap()
     }
 
     async fn assert_log_equivalence(
@@ -3,6 +3,7 @@
 
 use std::{
     num::{NonZeroU32, NonZeroUsize},
+    path::Path,
     sync::Arc,
     time::{Duration, Instant},
 };
@@ -11,26 +12,31 @@ use anyhow::Context as _;
 use tokio::sync::{oneshot, watch};
 use zksync_config::configs::{
     chain::{OperationsManagerConfig, StateKeeperConfig},
-    database::{MerkleTreeConfig, MerkleTreeMode},
+    database::{MerkleTreeConfig, MerkleTreeMode, MerkleTreeStorage},
 };
 use zksync_dal::{ConnectionPool, Core};
 use zksync_health_check::{CheckHealth, HealthUpdater, ReactiveHealthCheck};
 use zksync_object_store::ObjectStore;
 
 use self::{
-    helpers::{create_db, Delayer, GenericAsyncTree, MerkleTreeHealth, MerkleTreeHealthCheck},
+    checkpoints::{CheckpointImportOutcome, TreeCheckpointImporter},
+    helpers::{create_tree_db, Delayer, GenericAsyncTree, MerkleTreeHealth, MerkleTreeHealthCheck},
     metrics::{ConfigLabels, METRICS},
     pruning::PruningHandles,
     updater::TreeUpdater,
 };
 pub use self::{
+    checkpoints::{MerkleTreeCheckpointTask, TreeCheckpointFileInfo, TreeCheckpointManifest},
     helpers::{AsyncTreeReader, LazyAsyncTreeReader, MerkleTreeInfo},
+    postgres_storage::{PostgresTreeDatabase, PostgresTreeStorage},
     pruning::MerkleTreePruningTask,
 };
 
 pub mod api_server;
+mod checkpoints;
 mod helpers;
 mod metrics;
+mod postgres_storage;
 mod pruning;
 mod recovery;
 #[cfg(test)]
@@ -61,6 +67,16 @@ impl Default for MetadataCalculatorRecoveryConfig {
     }
 }
 
+/// Configuration of Merkle tree checkpoints (consistent RocksDB snapshots of the tree stored in an object store).
+#[derive(Debug, Clone, Default)]
+pub struct MetadataCalculatorCheckpointsConfig {
+    /// If set, a checkpoint is exported once the tree advances by the specified number of L1 batches
+    /// since the latest exported checkpoint.
+    pub export_interval: Option<NonZeroU32>,
+    /// Whether to import the latest checkpoint on start if the tree is not initialized.
+    pub import_enabled: bool,
+}
+
 /// Configuration of [`MetadataCalculator`].
 #[derive(Debug, Clone)]
 pub struct MetadataCalculatorConfig {
@@ -71,6 +87,8 @@ pub struct MetadataCalculatorConfig {
     pub max_open_files: Option<NonZeroU32>,
     /// Configuration of the Merkle tree mode.
     pub mode: MerkleTreeMode,
+    /// Storage backend for the Merkle tree. RocksDB-specific options are ignored for the Postgres storage.
+    pub storage: MerkleTreeStorage,
     /// Interval between polling Postgres for updates if no progress was made by the tree.
     pub delay_interval: Duration,
     /// Maximum number of L1 batches to get from Postgres on a single update iteration.
@@ -93,6 +111,8 @@ pub struct MetadataCalculatorConfig {
     pub sealed_batches_have_protective_reads: bool,
     /// Configuration specific to the Merkle tree recovery.
     pub recovery: MetadataCalculatorRecoveryConfig,
+    /// Configuration of Merkle tree checkpoints.
+    pub checkpoints: MetadataCalculatorCheckpointsConfig,
 }
 
 impl MetadataCalculatorConfig {
@@ -105,6 +125,7 @@ impl MetadataCalculatorConfig {
             db_path: merkle_tree_config.path.clone(),
             max_open_files: None,
             mode: merkle_tree_config.mode,
+            storage: merkle_tree_config.storage,
             delay_interval: operation_config.delay_interval(),
             max_l1_batches_per_iter: merkle_tree_config.max_l1_batches_per_iter,
             multi_get_chunk_size: merkle_tree_config.multi_get_chunk_size,
@@ -116,6 +137,10 @@ impl MetadataCalculatorConfig {
                 .protective_reads_persistence_enabled,
             // The main node isn't supposed to be recovered yet, so this value doesn't matter much
             recovery: MetadataCalculatorRecoveryConfig::default(),
+            checkpoints: MetadataCalculatorCheckpointsConfig {
+                export_interval: merkle_tree_config.checkpoint_interval_l1_batches,
+                import_enabled: merkle_tree_config.checkpoint_import_enabled,
+            },
         }
     }
 }
@@ -126,8 +151,10 @@ pub struct MetadataCalculator {
     tree_reader: watch::Sender<Option<AsyncTreeReader>>,
     pruning_handles_sender: oneshot::Sender<PruningHandles>,
     object_store: Option<Arc<dyn ObjectStore>>,
+    checkpoint_store: Option<Arc<dyn ObjectStore>>,
     pool: ConnectionPool<Core>,
     recovery_pool: ConnectionPool<Core>,
+    tree_storage_pool: ConnectionPool<Core>,
     delayer: Delayer,
     health_updater: HealthUpdater,
     max_l1_batches_per_iter: usize,
@@ -161,13 +188,22 @@ impl MetadataCalculator {
                 "Cannot run lightweight tree with an object store; the tree won't produce information to be stored in the store"
             );
         }
+        let checkpoints_enabled =
+            config.checkpoints.export_interval.is_some() || config.checkpoints.import_enabled;
+        if matches!(config.storage, MerkleTreeStorage::Postgres) && checkpoints_enabled {
+            anyhow::bail!(
+                "Merkle tree checkpoints are not supported for the Postgres tree storage"
+            );
+        }
 
         let (_, health_updater) = ReactiveHealthCheck::new("tree");
         Ok(Self {
             tree_reader: watch::channel(None).0,
             pruning_handles_sender: oneshot::channel().0,
             object_store,
+            checkpoint_store: None,
             recovery_pool: pool.clone(),
+            tree_storage_pool: pool.clone(),
             pool,
             delayer: Delayer::new(config.delay_interval),
             health_updater,
@@ -183,6 +219,21 @@ impl MetadataCalculator {
         self
     }
 
+    /// Sets a separate pool used to store tree nodes if the tree uses the Postgres storage. It should have
+    /// multiple connections since the tree, its pruner and the tree API access the storage concurrently.
+    /// If not set, the main pool is used.
+    pub fn with_tree_storage_pool(mut self, tree_storage_pool: ConnectionPool<Core>) -> Self {
+        self.tree_storage_pool = tree_storage_pool;
+        self
+    }
+
+    /// Sets the object store used to export and import Merkle tree checkpoints. Must be called if checkpoints
+    /// are enabled in the calculator config.
+    pub fn with_checkpoint_store(mut self, checkpoint_store: Arc<dyn ObjectStore>) -> Self {
+        self.checkpoint_store = Some(checkpoint_store);
+        self
+    }
+
     /// Returns a health check for this calculator.
     pub fn tree_health_check(&self) -> impl CheckHealth {
         MerkleTreeHealthCheck::new(self.health_updater.subscribe(), self.tree_reader())
@@ -202,19 +253,66 @@ impl MetadataCalculator {
         MerkleTreePruningTask::new(pruning_handles, self.pool.clone(), poll_interval)
     }
 
+    /// Returns a task periodically exporting Merkle tree checkpoints to the object store, or `None` if checkpoint export
+    /// is disabled in the config.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if checkpoint export is enabled, but the checkpoint store is not set.
+    pub fn checkpoint_task(&self) -> anyhow::Result<Option<MerkleTreeCheckpointTask>> {
+        let Some(export_interval) = self.config.checkpoints.export_interval else {
+            return Ok(None);
+        };
+        let object_store = self
+            .checkpoint_store
+            .clone()
+            .context("Merkle tree checkpoint export requires an object store")?;
+        Ok(Some(MerkleTreeCheckpointTask::new(
+            self.tree_reader(),
+            object_store,
+            Path::new(&self.config.db_path),
+            export_interval,
+            self.config.delay_interval,
+        )))
+    }
+
+    /// Imports the latest tree checkpoint if checkpoint import is enabled. Returns `false` if the import
+    /// was interrupted by a stop signal.
+    async fn import_checkpoint(
+        &self,
+        stop_receiver: &mut watch::Receiver<bool>,
+    ) -> anyhow::Result<bool> {
+        if !self.config.checkpoints.import_enabled {
+            return Ok(true);
+        }
+        let object_store = self
+            .checkpoint_store
+            .clone()
+            .context("Merkle tree checkpoint import requires an object store")?;
+        let importer = TreeCheckpointImporter::new(
+            object_store,
+            Path::new(&self.config.db_path),
+            self.config.delay_interval,
+        );
+        let outcome = importer.import(&self.pool, stop_receiver).await?;
+        Ok(outcome != CheckpointImportOutcome::Interrupted)
+    }
+
     async fn create_tree(&self) -> anyhow::Result<GenericAsyncTree> {
         self.health_updater
             .update(MerkleTreeHealth::Initialization.into());
 
         let started_at = Instant::now();
-        let db = create_db(self.config.clone()).await.with_context(|| {
-            format!(
-                "failed opening Merkle tree RocksDB with configuration {:?}",
-                self.config
-            )
-        })?;
+        let db = create_tree_db(self.config.clone(), &self.tree_storage_pool)
+            .await
+            .with_context(|| {
+                format!(
+                    "failed opening Merkle tree database with configuration {:?}",
+                    self.config
+                )
+            })?;
         tracing::info!(
-            "Opened Merkle tree RocksDB with configuration {:?} in {:?}",
+            "Opened Merkle tree database with configuration {:?} in {:?}",
             self.config,
             started_at.elapsed()
         );
@@ -223,6 +321,15 @@ impl MetadataCalculator {
     }
 
     pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        self.health_updater
+            .update(MerkleTreeHealth::Initialization.into());
+        if !self.import_checkpoint(&mut stop_receiver).await? {
+            tracing::info!(
+                "Stop signal received during Merkle tree checkpoint import; shutting down"
+            );
+            return Ok(());
+        }
+
         let tree = self.create_tree().await?;
         let tree = tree
             .ensure_ready(
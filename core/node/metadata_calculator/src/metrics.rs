@@ -253,3 +253,30 @@ pub(super) struct MetadataCalculatorRecoveryMetrics {
 #[vise::register]
 pub(super) static RECOVERY_METRICS: vise::Global<MetadataCalculatorRecoveryMetrics> =
     vise::Global::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "stage", rename_all = "snake_case")]
+pub(super) enum CheckpointStage {
+    Create,
+    Upload,
+    Download,
+    Validate,
+}
+
+/// Metrics for exporting and importing Merkle tree checkpoints.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_metadata_calculator_checkpoints")]
+pub(super) struct MetadataCalculatorCheckpointMetrics {
+    /// L1 batch number of the latest exported checkpoint.
+    pub exported_l1_batch_number: Gauge<u64>,
+    /// Total size of files in the latest exported checkpoint.
+    #[metrics(unit = Unit::Bytes)]
+    pub exported_size: Gauge<u64>,
+    /// Latency of a checkpoint export / import stage.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub latency: Family<CheckpointStage, Histogram<Duration>>,
+}
+
+#[vise::register]
+pub(super) static CHECKPOINT_METRICS: vise::Global<MetadataCalculatorCheckpointMetrics> =
+    vise::Global::new();
@@ -0,0 +1,189 @@
+//! Postgres-backed storage for the Merkle tree.
+
+use std::{collections::BTreeMap, future::Future};
+
+use anyhow::Context as _;
+use tokio::runtime::Handle;
+use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
+use zksync_merkle_tree::{KeyValueDatabase, KeyValueOp, KeyValueStorage};
+
+/// Merkle tree database storing tree nodes in Postgres.
+pub type PostgresTreeDatabase = KeyValueDatabase<PostgresTreeStorage>;
+
+/// [`KeyValueStorage`] implementation storing tree nodes and stale keys in Postgres.
+///
+/// The Merkle tree is synchronous, so this storage blocks on async DB queries using the provided Tokio runtime handle.
+/// It should preferably be used from a blocking context (e.g., inside [`tokio::task::spawn_blocking()`]). Using it
+/// from an async task is supported via [`tokio::task::block_in_place()`], but only with a multi-threaded runtime;
+/// with a current-thread runtime, this will panic.
+#[derive(Debug, Clone)]
+pub struct PostgresTreeStorage {
+    pool: ConnectionPool<Core>,
+    runtime: Handle,
+}
+
+impl PostgresTreeStorage {
+    pub fn new(pool: ConnectionPool<Core>, runtime: Handle) -> Self {
+        Self { pool, runtime }
+    }
+
+    /// Creates a tree database based on this storage.
+    pub fn into_database(self) -> PostgresTreeDatabase {
+        KeyValueDatabase::new(self)
+    }
+
+    fn block_on<T>(&self, future: impl Future<Output = anyhow::Result<T>>) -> anyhow::Result<T> {
+        // `block_in_place()` is a no-op outside async tasks (e.g., in blocking threads), and allows blocking
+        // on the future without starving the runtime when called from an async task.
+        tokio::task::block_in_place(|| self.runtime.block_on(future))
+    }
+
+    async fn write_ops(
+        connection: &mut Connection<'_, Core>,
+        ops: Vec<KeyValueOp>,
+    ) -> anyhow::Result<()> {
+        let mut buffer = WriteBuffer::default();
+        for op in ops {
+            match op {
+                KeyValueOp::PutNode { key, value } => {
+                    if !buffer.deleted_nodes.is_empty() || !buffer.stale_keys.is_empty() {
+                        buffer.flush(connection).await?;
+                    }
+                    buffer.put_nodes.insert(key, value);
+                }
+                KeyValueOp::DeleteNode { key } => {
+                    if !buffer.put_nodes.is_empty() || !buffer.stale_keys.is_empty() {
+                        buffer.flush(connection).await?;
+                    }
+                    buffer.deleted_nodes.push(key);
+                }
+                KeyValueOp::PutStaleKey {
+                    replaced_in_version,
+                    key,
+                } => {
+                    // Stale keys are stored separately from nodes, so they don't need to be ordered w.r.t. node ops.
+                    buffer.stale_keys.push((replaced_in_version, key));
+                }
+                KeyValueOp::DeleteNodeRange { start, end } => {
+                    buffer.flush(connection).await?;
+                    connection
+                        .merkle_tree_storage_dal()
+                        .delete_node_range(&start, &end)
+                        .await?;
+                }
+                KeyValueOp::DeleteStaleKeys { versions } => {
+                    buffer.flush(connection).await?;
+                    connection
+                        .merkle_tree_storage_dal()
+                        .delete_stale_keys(versions)
+                        .await?;
+                }
+            }
+        }
+        buffer.flush(connection).await
+    }
+}
+
+/// Buffer for consecutive write operations of the same kind, so that they can be persisted with a single query.
+#[derive(Debug, Default)]
+struct WriteBuffer {
+    put_nodes: BTreeMap<Vec<u8>, Vec<u8>>,
+    deleted_nodes: Vec<Vec<u8>>,
+    stale_keys: Vec<(u64, Vec<u8>)>,
+}
+
+impl WriteBuffer {
+    async fn flush(&mut self, connection: &mut Connection<'_, Core>) -> anyhow::Result<()> {
+        let mut dal = connection.merkle_tree_storage_dal();
+        if !self.put_nodes.is_empty() {
+            let nodes: Vec<_> = std::mem::take(&mut self.put_nodes).into_iter().collect();
+            dal.upsert_nodes(&nodes).await?;
+        }
+        if !self.deleted_nodes.is_empty() {
+            dal.delete_nodes(&std::mem::take(&mut self.deleted_nodes))
+                .await?;
+        }
+        if !self.stale_keys.is_empty() {
+            dal.insert_stale_keys(&std::mem::take(&mut self.stale_keys))
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+impl KeyValueStorage for PostgresTreeStorage {
+    fn node(&self, key: &[u8]) -> anyhow::Result<Option<Vec<u8>>> {
+        let mut nodes = self.nodes(&[key.to_vec()])?;
+        Ok(nodes.pop().flatten())
+    }
+
+    fn nodes(&self, keys: &[Vec<u8>]) -> anyhow::Result<Vec<Option<Vec<u8>>>> {
+        self.block_on(async {
+            let mut connection = self.pool.connection_tagged("merkle_tree").await?;
+            let mut nodes = connection.merkle_tree_storage_dal().get_nodes(keys).await?;
+            Ok(keys.iter().map(|key| nodes.remove(key)).collect())
+        })
+    }
+
+    fn min_stale_key_version(&self) -> anyhow::Result<Option<u64>> {
+        self.block_on(async {
+            let mut connection = self.pool.connection_tagged("merkle_tree").await?;
+            Ok(connection
+                .merkle_tree_storage_dal()
+                .get_min_stale_key_version()
+                .await?)
+        })
+    }
+
+    fn stale_keys(&self, replaced_in_version: u64) -> anyhow::Result<Vec<Vec<u8>>> {
+        self.block_on(async {
+            let mut connection = self.pool.connection_tagged("merkle_tree").await?;
+            Ok(connection
+                .merkle_tree_storage_dal()
+                .get_stale_keys(replaced_in_version)
+                .await?)
+        })
+    }
+
+    fn write(&mut self, ops: Vec<KeyValueOp>) -> anyhow::Result<()> {
+        self.block_on(async {
+            let mut connection = self.pool.connection_tagged("merkle_tree").await?;
+            let mut transaction = connection.start_transaction().await?;
+            Self::write_ops(&mut transaction, ops).await?;
+            transaction
+                .commit()
+                .await
+                .context("failed committing Merkle tree changes")
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_casing::test_casing;
+    use zksync_merkle_tree::unstable::testonly::*;
+
+    use super::*;
+
+    type TestScenario = fn(&mut PostgresTreeDatabase);
+
+    const SCENARIOS: [TestScenario; 7] = [
+        test_inserting_node_in_non_empty_database,
+        test_inserting_node_in_non_empty_database_with_moved_key,
+        test_reading_keys_does_not_change_child_version,
+        test_read_ops_are_not_reflected_in_patch,
+        test_tree_handles_keys_at_terminal_level,
+        test_recovery_workflow_with_multiple_stages,
+        test_consistency_and_pruning,
+    ];
+
+    #[test_casing(7, SCENARIOS)]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn postgres_tree_storage(scenario: TestScenario) {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut db = PostgresTreeStorage::new(pool, Handle::current()).into_database();
+        tokio::task::spawn_blocking(move || scenario(&mut db))
+            .await
+            .unwrap();
+    }
+}
@@ -7,10 +7,12 @@ use serde::Serialize;
 use tokio::sync::{oneshot, watch};
 use zksync_dal::{ConnectionPool, Core, CoreDal};
 use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
-use zksync_merkle_tree::{MerkleTreePruner, MerkleTreePrunerHandle, RocksDBWrapper};
+use zksync_merkle_tree::{MerkleTreePruner, MerkleTreePrunerHandle};
 use zksync_types::L1BatchNumber;
 
-pub(super) type PruningHandles = (MerkleTreePruner<RocksDBWrapper>, MerkleTreePrunerHandle);
+use crate::helpers::TreeDatabase;
+
+pub(super) type PruningHandles = (MerkleTreePruner<TreeDatabase>, MerkleTreePrunerHandle);
 
 #[derive(Debug, Serialize)]
 #[serde(tag = "stage", rename_all = "snake_case")]
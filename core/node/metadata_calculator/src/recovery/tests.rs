@@ -50,7 +50,8 @@ async fn create_tree_recovery(
     config: &MetadataCalculatorRecoveryConfig,
 ) -> (AsyncTreeRecovery, Option<PersistenceThreadHandle>) {
     let db = create_db(mock_config(path)).await.unwrap();
-    AsyncTreeRecovery::with_handle(db, l1_batch.0.into(), MerkleTreeMode::Full, config).unwrap()
+    AsyncTreeRecovery::with_handle(db.into(), l1_batch.0.into(), MerkleTreeMode::Full, config)
+        .unwrap()
 }
 
 #[tokio::test]
@@ -9,11 +9,11 @@ use test_casing::{test_casing, Product};
 use tokio::sync::{mpsc, watch};
 use zksync_config::configs::{
     chain::{OperationsManagerConfig, StateKeeperConfig},
-    database::{MerkleTreeConfig, MerkleTreeMode},
+    database::{MerkleTreeConfig, MerkleTreeMode, MerkleTreeStorage},
 };
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
 use zksync_health_check::{CheckHealth, HealthStatus};
-use zksync_merkle_tree::domain::ZkSyncTree;
+use zksync_merkle_tree::{domain::ZkSyncTree, RocksDBWrapper};
 use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
 use zksync_node_test_utils::{create_l1_batch, create_l2_block};
 use zksync_object_store::{MockObjectStore, ObjectStore};
@@ -26,7 +26,8 @@ use zksync_types::{
 use zksync_utils::u32_to_h256;
 
 use super::{
-    helpers::L1BatchWithLogs, GenericAsyncTree, MetadataCalculator, MetadataCalculatorConfig,
+    helpers::L1BatchWithLogs, GenericAsyncTree, MetadataCalculator,
+    MetadataCalculatorCheckpointsConfig, MetadataCalculatorConfig,
     MetadataCalculatorRecoveryConfig,
 };
 use crate::helpers::{AsyncTree, Delayer};
@@ -50,6 +51,7 @@ pub(super) fn mock_config(db_path: &Path) -> MetadataCalculatorConfig {
         db_path: db_path.to_str().unwrap().to_owned(),
         max_open_files: None,
         mode: MerkleTreeMode::Full,
+        storage: MerkleTreeStorage::RocksDB,
         delay_interval: POLL_INTERVAL,
         max_l1_batches_per_iter: 10,
         multi_get_chunk_size: 500,
@@ -59,6 +61,7 @@ pub(super) fn mock_config(db_path: &Path) -> MetadataCalculatorConfig {
         stalled_writes_timeout: Duration::ZERO, // writes should never be stalled in tests
         sealed_batches_have_protective_reads: true,
         recovery: MetadataCalculatorRecoveryConfig::default(),
+        checkpoints: MetadataCalculatorCheckpointsConfig::default(),
     }
 }
 
@@ -93,6 +96,7 @@ async fn low_level_genesis_creation() {
     reset_db_state(&pool, 1).await;
 
     let db = RocksDB::new(temp_dir.path()).unwrap();
+    let db = RocksDBWrapper::from(db);
     let mut tree = AsyncTree::new(db.into(), MerkleTreeMode::Lightweight).unwrap();
     let (_stop_sender, mut stop_receiver) = watch::channel(false);
     tree.ensure_consistency(&Delayer::new(POLL_INTERVAL), &pool, &mut stop_receiver)
@@ -263,7 +267,54 @@ async fn basic_workflow(sealed_protective_reads: bool) {
     assert_eq!(tree.next_l1_batch_number(), L1BatchNumber(2));
 }
 
-async fn expected_tree_hash(pool: &ConnectionPool<Core>, sealed_protective_reads: bool) -> H256 {
+#[tokio::test(flavor = "multi_thread")] // Postgres tree storage requires a multi-threaded runtime
+async fn basic_workflow_with_postgres_tree_storage() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let (merkle_tree_config, operation_config) =
+        create_config(temp_dir.path(), MerkleTreeMode::Full);
+    let merkle_tree_config = MerkleTreeConfig {
+        storage: MerkleTreeStorage::Postgres,
+        ..merkle_tree_config
+    };
+    let state_keeper_config = StateKeeperConfig {
+        protective_reads_persistence_enabled: true,
+        ..Default::default()
+    };
+
+    let calculator = setup_calculator_with_options(
+        &merkle_tree_config,
+        &operation_config,
+        &state_keeper_config,
+        pool.clone(),
+        None,
+    )
+    .await;
+    reset_db_state(&pool, 1).await;
+    let merkle_tree_hash = run_calculator(calculator).await;
+    let expected_tree_hash = expected_tree_hash(&pool, true).await;
+    assert_eq!(merkle_tree_hash, expected_tree_hash);
+
+    // Check that the tree is persisted across restarts.
+    let calculator = setup_calculator_with_options(
+        &merkle_tree_config,
+        &operation_config,
+        &state_keeper_config,
+        pool,
+        None,
+    )
+    .await;
+    let tree = calculator.create_tree().await.unwrap();
+    let GenericAsyncTree::Ready(tree) = tree else {
+        panic!("Unexpected tree state: {tree:?}");
+    };
+    assert_eq!(tree.next_l1_batch_number(), L1BatchNumber(2));
+}
+
+pub(crate) async fn expected_tree_hash(
+    pool: &ConnectionPool<Core>,
+    sealed_protective_reads: bool,
+) -> H256 {
     let mut storage = pool.connection().await.unwrap();
     let processed_l1_batch_number = if sealed_protective_reads {
         storage
@@ -0,0 +1,89 @@
+use anyhow::Context as _;
+use zksync_config::configs::AddressPolicyConfig;
+use zksync_state_keeper::{AddressPolicy, AddressPolicyUpdater};
+
+use crate::{
+    implementations::resources::{
+        eth_interface::EthInterfaceResource, state_keeper::AddressPolicyResource,
+    },
+    service::StopReceiver,
+    task::{Task, TaskId},
+    wiring_layer::{WiringError, WiringLayer},
+    FromContext, IntoContext,
+};
+
+/// Wiring layer for the address policy shared by the state keeper and the API server.
+///
+/// The policy is loaded during wiring, so that a malformed policy file prevents the node from starting.
+///
+/// ## Requests resources
+///
+/// - `EthInterfaceResource` (optional; required if the on-chain denylist is configured)
+///
+/// ## Adds resources
+///
+/// - `AddressPolicyResource`
+///
+/// ## Adds tasks
+///
+/// - `AddressPolicyUpdater`
+#[derive(Debug)]
+pub struct AddressPolicyLayer {
+    config: AddressPolicyConfig,
+}
+
+#[derive(Debug, FromContext)]
+#[context(crate = crate)]
+pub struct Input {
+    pub eth_client: Option<EthInterfaceResource>,
+}
+
+#[derive(Debug, IntoContext)]
+#[context(crate = crate)]
+pub struct Output {
+    pub address_policy: AddressPolicyResource,
+    #[context(task)]
+    pub updater: AddressPolicyUpdater,
+}
+
+impl AddressPolicyLayer {
+    pub fn new(config: AddressPolicyConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for AddressPolicyLayer {
+    type Input = Input;
+    type Output = Output;
+
+    fn layer_name(&self) -> &'static str {
+        "address_policy_layer"
+    }
+
+    async fn wire(self, input: Self::Input) -> Result<Self::Output, WiringError> {
+        let eth_client = input.eth_client.map(|client| client.0);
+        let policy = AddressPolicy::default();
+        let mut updater = AddressPolicyUpdater::new(policy.clone(), self.config, eth_client)?;
+        updater
+            .load()
+            .await
+            .context("failed loading initial address policy")?;
+
+        Ok(Output {
+            address_policy: policy.into(),
+            updater,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Task for AddressPolicyUpdater {
+    fn id(&self) -> TaskId {
+        "address_policy_updater".into()
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        (*self).run(stop_receiver.0).await
+    }
+}
@@ -0,0 +1,99 @@
+use std::{net::SocketAddr, sync::Arc};
+
+use secrecy::ExposeSecret;
+use zksync_config::configs::AdminSecrets;
+use zksync_node_api_server::admin::{AdminControls, AdminServerHandle};
+
+use crate::{
+    implementations::resources::admin::AdminControlsResource,
+    service::StopReceiver,
+    task::{Task, TaskId, TaskKind},
+    wiring_layer::{WiringError, WiringLayer},
+    FromContext, IntoContext,
+};
+
+/// Wiring layer for the admin server.
+///
+/// Expects other layers to register pausable tasks and one-off actions in [`AdminControls`]
+/// using [`AdminControlsResource`]. The added task spawns a server exposing these controls;
+/// all requests to the server must be authenticated with the token from [`AdminSecrets`].
+#[derive(Debug)]
+pub struct AdminServerLayer {
+    bind_addr: SocketAddr,
+    secrets: AdminSecrets,
+}
+
+#[derive(Debug, FromContext)]
+#[context(crate = crate)]
+pub struct Input {
+    #[context(default)]
+    pub admin_controls: AdminControlsResource,
+}
+
+#[derive(Debug, IntoContext)]
+#[context(crate = crate)]
+pub struct Output {
+    #[context(task)]
+    pub admin_server_task: AdminServerTask,
+}
+
+impl AdminServerLayer {
+    pub fn new(bind_addr: SocketAddr, secrets: AdminSecrets) -> Self {
+        Self { bind_addr, secrets }
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for AdminServerLayer {
+    type Input = Input;
+    type Output = Output;
+
+    fn layer_name(&self) -> &'static str {
+        "admin_server_layer"
+    }
+
+    async fn wire(self, input: Self::Input) -> Result<Self::Output, WiringError> {
+        if self.secrets.auth_token.expose_secret().is_empty() {
+            return Err(WiringError::Configuration(
+                "admin server auth token must not be empty".into(),
+            ));
+        }
+
+        let admin_server_task = AdminServerTask {
+            bind_addr: self.bind_addr,
+            secrets: self.secrets,
+            controls: input.admin_controls.0,
+        };
+        Ok(Output { admin_server_task })
+    }
+}
+
+#[derive(Debug)]
+pub struct AdminServerTask {
+    bind_addr: SocketAddr,
+    secrets: AdminSecrets,
+    controls: Arc<AdminControls>,
+}
+
+#[async_trait::async_trait]
+impl Task for AdminServerTask {
+    fn kind(&self) -> TaskKind {
+        TaskKind::UnconstrainedTask
+    }
+
+    fn id(&self) -> TaskId {
+        "admin_server".into()
+    }
+
+    async fn run(self: Box<Self>, mut stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        let handle = AdminServerHandle::spawn_server(
+            self.bind_addr,
+            self.secrets.auth_token.expose_secret(),
+            self.controls,
+        );
+        stop_receiver.0.changed().await?;
+        handle.stop().await;
+
+        Ok(())
+    }
+}
@@ -9,13 +9,15 @@ use zksync_types::L1ChainId;
 
 use crate::{
     implementations::resources::{
+        admin::AdminControlsResource,
         eth_interface::EthInterfaceResource,
+        healthcheck::AppHealthCheckResource,
         l1_tx_params::TxParamsResource,
         pools::{MasterPool, PoolResource},
         price_api_client::PriceAPIClientResource,
     },
     service::StopReceiver,
-    task::{Task, TaskId},
+    task::{RestartPolicy, RestartableTask, Task, TaskId},
     wiring_layer::{WiringError, WiringLayer},
     FromContext, IntoContext,
 };
@@ -24,6 +26,8 @@ use crate::{
 ///
 /// Responsible for orchestrating communications with external API feeds to get ETH<->BaseToken
 /// conversion ratios and persisting them both in the DB and in the L1.
+/// The persister is restarted on errors (e.g., if the price API is temporarily unavailable),
+/// and can be paused via the admin server.
 #[derive(Debug)]
 pub struct BaseTokenRatioPersisterLayer {
     config: BaseTokenAdjusterConfig,
@@ -40,16 +44,22 @@ pub struct Input {
     pub price_api_client: PriceAPIClientResource,
     pub eth_client: EthInterfaceResource,
     pub tx_params: TxParamsResource,
+    #[context(default)]
+    pub app_health: AppHealthCheckResource,
+    #[context(default)]
+    pub admin_controls: AdminControlsResource,
 }
 
 #[derive(Debug, IntoContext)]
 #[context(crate = crate)]
 pub struct Output {
     #[context(task)]
-    pub persister: BaseTokenRatioPersister,
+    pub persister: RestartableTask,
 }
 
 impl BaseTokenRatioPersisterLayer {
+    const RESTART_POLICY: RestartPolicy = RestartPolicy::exponential_backoff(10);
+
     pub fn new(
         config: BaseTokenAdjusterConfig,
         contracts_config: ContractsConfig,
@@ -122,6 +132,15 @@ impl WiringLayer for BaseTokenRatioPersisterLayer {
             price_api_client.0,
             l1_behaviour,
         );
+        let pause_receiver = input
+            .admin_controls
+            .0
+            .register_task("base_token_ratio_persister")
+            .map_err(WiringError::internal)?;
+        let persister = RestartableTask::new(Self::RESTART_POLICY, move || persister.clone())
+            .with_health_check(&input.app_health.0, "base_token_ratio_persister_restarts")
+            .map_err(WiringError::internal)?
+            .with_pause_receiver(pause_receiver);
 
         Ok(Output { persister })
     }
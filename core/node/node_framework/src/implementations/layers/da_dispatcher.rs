@@ -3,16 +3,17 @@ use zksync_da_dispatcher::DataAvailabilityDispatcher;
 
 use crate::{
     implementations::resources::{
+        admin::AdminControlsResource,
         da_client::DAClientResource,
         pools::{MasterPool, PoolResource},
     },
     service::StopReceiver,
-    task::{Task, TaskId},
+    task::{RestartPolicy, RestartableTask, Task, TaskId},
     wiring_layer::{WiringError, WiringLayer},
     FromContext, IntoContext,
 };
 
-/// A layer that wires the data availability dispatcher task.
+/// A layer that wires the data availability dispatcher task. The dispatcher can be paused via the admin server.
 #[derive(Debug)]
 pub struct DataAvailabilityDispatcherLayer {
     state_keeper_config: StateKeeperConfig,
@@ -24,13 +25,15 @@ pub struct DataAvailabilityDispatcherLayer {
 pub struct Input {
     pub master_pool: PoolResource<MasterPool>,
     pub da_client: DAClientResource,
+    #[context(default)]
+    pub admin_controls: AdminControlsResource,
 }
 
 #[derive(Debug, IntoContext)]
 #[context(crate = crate)]
 pub struct Output {
     #[context(task)]
-    pub da_dispatcher_task: DataAvailabilityDispatcher,
+    pub da_dispatcher_task: RestartableTask,
 }
 
 impl DataAvailabilityDispatcherLayer {
@@ -65,8 +68,20 @@ impl WiringLayer for DataAvailabilityDispatcherLayer {
             }
         }
 
-        let da_dispatcher_task =
-            DataAvailabilityDispatcher::new(master_pool, self.da_config, da_client);
+        let pause_receiver = input
+            .admin_controls
+            .0
+            .register_task("da_dispatcher")
+            .map_err(WiringError::internal)?;
+        // The dispatcher isn't restarted on errors; wrapping it is only needed to support pausing.
+        let da_dispatcher_task = RestartableTask::new(RestartPolicy::FailFast, move || {
+            DataAvailabilityDispatcher::new(
+                master_pool.clone(),
+                self.da_config.clone(),
+                da_client.clone(),
+            )
+        })
+        .with_pause_receiver(pause_receiver);
 
         Ok(Output { da_dispatcher_task })
     }
@@ -7,6 +7,7 @@ use zksync_types::{commitment::L1BatchCommitmentMode, settlement::SettlementMode
 
 use crate::{
     implementations::resources::{
+        admin::AdminControlsResource,
         circuit_breakers::CircuitBreakersResource,
         eth_interface::{BoundEthInterfaceForBlobsResource, BoundEthInterfaceResource},
         object_store::ObjectStoreResource,
@@ -32,6 +33,7 @@ use crate::{
 /// - `BoundEthInterfaceForBlobsResource` (optional)
 /// - `ObjectStoreResource`
 /// - `CircuitBreakersResource` (adds a circuit breaker)
+/// - `AdminControlsResource` (registers the aggregator as a pausable task)
 ///
 /// ## Adds tasks
 ///
@@ -55,6 +57,8 @@ pub struct Input {
     pub object_store: ObjectStoreResource,
     #[context(default)]
     pub circuit_breakers: CircuitBreakersResource,
+    #[context(default)]
+    pub admin_controls: AdminControlsResource,
 }
 
 #[derive(Debug, IntoContext)]
@@ -125,6 +129,20 @@ impl WiringLayer for EthTxAggregatorLayer {
             self.settlement_mode,
         )
         .await;
+        let pause_receiver = input
+            .admin_controls
+            .0
+            .register_task("eth_tx_aggregator")
+            .map_err(WiringError::internal)?;
+        let mut eth_tx_aggregator = eth_tx_aggregator.with_pause_receiver(pause_receiver);
+        let eth_watch_config = self.eth_sender_config.watcher.as_ref();
+        if let Some(eth_watch_config) =
+            eth_watch_config.filter(|config| config.confirmations_for_priority_ops.is_some())
+        {
+            // Priority ops may be reorged out of L1 before finalization, so batches with them must not be committed.
+            eth_tx_aggregator = eth_tx_aggregator
+                .with_finalized_priority_ops_only(eth_watch_config.confirmations_for_eth_event);
+        }
 
         // Insert circuit breaker.
         input
@@ -4,6 +4,7 @@ use zksync_eth_watch::{EthHttpQueryClient, EthWatch};
 
 use crate::{
     implementations::resources::{
+        circuit_breakers::CircuitBreakersResource,
         eth_interface::EthInterfaceResource,
         pools::{MasterPool, PoolResource},
     },
@@ -17,6 +18,9 @@ use crate::{
 ///
 /// Responsible for initializing and running of [`EthWatch`] component, that polls the Ethereum node for the relevant events,
 /// such as priority operations (aka L1 transactions), protocol upgrades etc.
+///
+/// If priority operations are processed before finalization, adds a circuit breaker halting the node
+/// if a processed priority operation is reorged out of L1.
 #[derive(Debug)]
 pub struct EthWatchLayer {
     eth_watch_config: EthWatchConfig,
@@ -28,6 +32,8 @@ pub struct EthWatchLayer {
 pub struct Input {
     pub master_pool: PoolResource<MasterPool>,
     pub eth_client: EthInterfaceResource,
+    #[context(default)]
+    pub circuit_breakers: CircuitBreakersResource,
 }
 
 #[derive(Debug, IntoContext)]
@@ -77,9 +83,22 @@ impl WiringLayer for EthWatchLayer {
             Box::new(eth_client),
             main_pool,
             self.eth_watch_config.poll_interval(),
+            self.eth_watch_config.confirmations_for_priority_ops,
         )
         .await?;
 
+        if self
+            .eth_watch_config
+            .confirmations_for_priority_ops
+            .is_some()
+        {
+            input
+                .circuit_breakers
+                .breakers
+                .insert(Box::new(eth_watch.reorg_circuit_breaker()))
+                .await;
+        }
+
         Ok(Output { eth_watch })
     }
 }
@@ -4,15 +4,20 @@ use zksync_house_keeper::{
 };
 
 use crate::{
-    implementations::resources::pools::{PoolResource, ReplicaPool},
+    implementations::resources::{
+        healthcheck::AppHealthCheckResource,
+        pools::{PoolResource, ReplicaPool},
+    },
     service::StopReceiver,
-    task::{Task, TaskId},
+    task::{RestartPolicy, RestartableTask, Task, TaskId},
     wiring_layer::{WiringError, WiringLayer},
     FromContext, IntoContext,
 };
 
 /// Wiring layer for `HouseKeeper` - a component responsible for managing prover jobs
 /// and auxiliary server activities.
+///
+/// House keeper tasks are not critical for the node operation, so they are restarted on errors.
 #[derive(Debug)]
 pub struct HouseKeeperLayer {
     house_keeper_config: HouseKeeperConfig,
@@ -22,16 +27,20 @@ pub struct HouseKeeperLayer {
 #[context(crate = crate)]
 pub struct Input {
     pub replica_pool: PoolResource<ReplicaPool>,
+    #[context(default)]
+    pub app_health: AppHealthCheckResource,
 }
 
 #[derive(Debug, IntoContext)]
 #[context(crate = crate)]
 pub struct Output {
     #[context(task)]
-    pub l1_batch_metrics_reporter: L1BatchMetricsReporter,
+    pub l1_batch_metrics_reporter: RestartableTask,
 }
 
 impl HouseKeeperLayer {
+    const RESTART_POLICY: RestartPolicy = RestartPolicy::exponential_backoff(10);
+
     pub fn new(house_keeper_config: HouseKeeperConfig) -> Self {
         Self {
             house_keeper_config,
@@ -53,11 +62,14 @@ impl WiringLayer for HouseKeeperLayer {
         let replica_pool = input.replica_pool.get().await?;
 
         // Initialize and add tasks
-        let l1_batch_metrics_reporter = L1BatchMetricsReporter::new(
-            self.house_keeper_config
-                .l1_batch_metrics_reporting_interval_ms,
-            replica_pool.clone(),
-        );
+        let reporting_interval_ms = self
+            .house_keeper_config
+            .l1_batch_metrics_reporting_interval_ms;
+        let l1_batch_metrics_reporter = RestartableTask::new(Self::RESTART_POLICY, move || {
+            L1BatchMetricsReporter::new(reporting_interval_ms, replica_pool.clone())
+        })
+        .with_health_check(&input.app_health.0, "l1_batch_metrics_reporter_restarts")
+        .map_err(WiringError::internal)?;
 
         Ok(Output {
             l1_batch_metrics_reporter,
@@ -1,13 +1,13 @@
 use std::sync::Arc;
 
 use zksync_config::configs::chain::StateKeeperConfig;
-use zksync_node_fee_model::{ApiFeeInputProvider, MainNodeFeeInputProvider};
+use zksync_node_fee_model::{ApiFeeInputProvider, L2BaseFeeAdjuster, MainNodeFeeInputProvider};
 use zksync_types::fee_model::FeeModelConfig;
 
 use crate::{
     implementations::resources::{
         base_token_ratio_provider::BaseTokenRatioProviderResource,
-        fee_input::{ApiFeeInputResource, SequencerFeeInputResource},
+        fee_input::{ApiFeeInputResource, L2BaseFeeAdjusterResource, SequencerFeeInputResource},
         gas_adjuster::GasAdjusterResource,
         l1_tx_params::TxParamsResource,
         pools::{PoolResource, ReplicaPool},
@@ -18,6 +18,9 @@ use crate::{
 
 /// Wiring layer for L1 gas interfaces.
 /// Adds several resources that depend on L1 gas price.
+///
+/// If the L2 block gas target is configured, also adds the L2 base fee adjuster, which makes
+/// the sequencer fee input depend on L2 block gas usage.
 #[derive(Debug)]
 pub struct L1GasLayer {
     state_keeper_config: StateKeeperConfig,
@@ -39,6 +42,7 @@ pub struct Output {
     pub sequencer_fee_input: SequencerFeeInputResource,
     pub api_fee_input: ApiFeeInputResource,
     pub l1_tx_params: TxParamsResource,
+    pub l2_base_fee_adjuster: Option<L2BaseFeeAdjusterResource>,
 }
 
 impl L1GasLayer {
@@ -61,11 +65,18 @@ impl WiringLayer for L1GasLayer {
     async fn wire(self, input: Self::Input) -> Result<Self::Output, WiringError> {
         let ratio_provider = input.base_token_ratio_provider;
 
-        let main_fee_input_provider = Arc::new(MainNodeFeeInputProvider::new(
+        let mut main_fee_input_provider = MainNodeFeeInputProvider::new(
             input.gas_adjuster.0.clone(),
             ratio_provider.0,
             FeeModelConfig::from_state_keeper_config(&self.state_keeper_config),
-        ));
+        );
+        let l2_base_fee_adjuster =
+            L2BaseFeeAdjuster::from_state_keeper_config(&self.state_keeper_config).map(Arc::new);
+        if let Some(adjuster) = &l2_base_fee_adjuster {
+            main_fee_input_provider =
+                main_fee_input_provider.with_l2_base_fee_adjuster(adjuster.clone());
+        }
+        let main_fee_input_provider = Arc::new(main_fee_input_provider);
 
         let replica_pool = input.replica_pool.get().await?;
         let api_fee_input_provider = Arc::new(ApiFeeInputProvider::new(
@@ -77,6 +88,7 @@ impl WiringLayer for L1GasLayer {
             sequencer_fee_input: main_fee_input_provider.into(),
             api_fee_input: api_fee_input_provider.into(),
             l1_tx_params: input.gas_adjuster.0.into(),
+            l2_base_fee_adjuster: l2_base_fee_adjuster.map(L2BaseFeeAdjusterResource),
         })
     }
 }
@@ -1,9 +1,12 @@
 use zksync_logs_bloom_backfill::LogsBloomBackfill;
 
 use crate::{
-    implementations::resources::pools::{MasterPool, PoolResource},
+    implementations::resources::{
+        healthcheck::AppHealthCheckResource,
+        pools::{MasterPool, PoolResource},
+    },
     service::StopReceiver,
-    task::{Task, TaskId, TaskKind},
+    task::{RestartPolicy, RestartableTask, Task, TaskId, TaskKind},
     wiring_layer::{WiringError, WiringLayer},
     FromContext, IntoContext,
 };
@@ -11,20 +14,27 @@ use crate::{
 /// Wiring layer for ethereum watcher
 ///
 /// Responsible for initializing and running of [`LogsBloomBackfill`] task, that backfills `logsBloom` for old blocks.
+/// The task is restarted on errors, since backfilling is not critical for the node operation.
 #[derive(Debug)]
 pub struct LogsBloomBackfillLayer;
 
+impl LogsBloomBackfillLayer {
+    const RESTART_POLICY: RestartPolicy = RestartPolicy::exponential_backoff(10);
+}
+
 #[derive(Debug, FromContext)]
 #[context(crate = crate)]
 pub struct Input {
     pub master_pool: PoolResource<MasterPool>,
+    #[context(default)]
+    pub app_health: AppHealthCheckResource,
 }
 
 #[derive(Debug, IntoContext)]
 #[context(crate = crate)]
 pub struct Output {
     #[context(task)]
-    pub logs_bloom_backfill: LogsBloomBackfill,
+    pub logs_bloom_backfill: RestartableTask,
 }
 
 #[async_trait::async_trait]
@@ -38,7 +48,11 @@ impl WiringLayer for LogsBloomBackfillLayer {
 
     async fn wire(self, input: Self::Input) -> Result<Self::Output, WiringError> {
         let pool = input.master_pool.get_singleton().await?;
-        let logs_bloom_backfill = LogsBloomBackfill::new(pool);
+        let logs_bloom_backfill = RestartableTask::new(Self::RESTART_POLICY, move || {
+            LogsBloomBackfill::new(pool.clone())
+        })
+        .with_health_check(&input.app_health.0, "logs_bloom_backfill_restarts")
+        .map_err(WiringError::internal)?;
         Ok(Output {
             logs_bloom_backfill,
         })
@@ -1,22 +1,29 @@
-use std::{num::NonZeroUsize, sync::Arc};
+use std::{num::NonZeroUsize, sync::Arc, time::Duration};
 
 use anyhow::Context;
 use zksync_node_sync::MainNodeHealthCheck;
 use zksync_types::{url::SensitiveUrl, L2ChainId};
-use zksync_web3_decl::client::{Client, DynClient, L2};
+use zksync_web3_decl::client::{Client, DynClient, FailoverClient, L2};
 
 use crate::{
     implementations::resources::{
-        healthcheck::AppHealthCheckResource, main_node_client::MainNodeClientResource,
+        healthcheck::AppHealthCheckResource,
+        main_node_client::{ConsistentMainNodeClientResource, MainNodeClientResource},
     },
+    service::StopReceiver,
+    task::{Task, TaskId},
     wiring_layer::{WiringError, WiringLayer},
     FromContext, IntoContext,
 };
 
 /// Wiring layer for main node client.
+///
+/// If fallback URLs are specified (e.g., for trusted external nodes), the client fails over to them
+/// if the main node is unavailable, and switches back once the main node recovers.
 #[derive(Debug)]
 pub struct MainNodeClientLayer {
     url: SensitiveUrl,
+    fallback_urls: Vec<SensitiveUrl>,
     rate_limit_rps: NonZeroUsize,
     l2_chain_id: L2ChainId,
 }
@@ -32,16 +39,34 @@ pub struct Input {
 #[context(crate = crate)]
 pub struct Output {
     pub main_node_client: MainNodeClientResource,
+    pub consistent_main_node_client: ConsistentMainNodeClientResource,
+    #[context(task)]
+    pub upstreams_monitor: Option<UpstreamsMonitorTask>,
 }
 
 impl MainNodeClientLayer {
     pub fn new(url: SensitiveUrl, rate_limit_rps: NonZeroUsize, l2_chain_id: L2ChainId) -> Self {
         Self {
             url,
+            fallback_urls: vec![],
             rate_limit_rps,
             l2_chain_id,
         }
     }
+
+    /// Sets URLs of upstreams used if the main node is unavailable, ordered by priority.
+    pub fn with_fallback_urls(mut self, fallback_urls: Vec<SensitiveUrl>) -> Self {
+        self.fallback_urls = fallback_urls;
+        self
+    }
+
+    fn build_client(&self, url: SensitiveUrl) -> anyhow::Result<Box<DynClient<L2>>> {
+        let client = Client::http(url)?
+            .for_network(self.l2_chain_id.into())
+            .with_allowed_requests_per_second(self.rate_limit_rps)
+            .build();
+        Ok(Box::new(client))
+    }
 }
 
 #[async_trait::async_trait]
@@ -54,13 +79,31 @@ impl WiringLayer for MainNodeClientLayer {
     }
 
     async fn wire(self, input: Self::Input) -> Result<Self::Output, WiringError> {
-        let main_node_client = Client::http(self.url)
-            .context("failed creating JSON-RPC client for main node")?
-            .for_network(self.l2_chain_id.into())
-            .with_allowed_requests_per_second(self.rate_limit_rps)
-            .build();
+        let main_node_client = self
+            .build_client(self.url.clone())
+            .context("failed creating JSON-RPC client for main node")?;
 
-        let client = Box::new(main_node_client) as Box<DynClient<L2>>;
+        let (client, consistent_client, upstreams_monitor) = if self.fallback_urls.is_empty() {
+            (main_node_client.clone(), main_node_client, None)
+        } else {
+            let mut upstreams = vec![main_node_client];
+            for (i, url) in self.fallback_urls.iter().enumerate() {
+                let client = self.build_client(url.clone()).with_context(|| {
+                    format!("failed creating JSON-RPC client for fallback upstream #{i}")
+                })?;
+                upstreams.push(client);
+            }
+            let client = FailoverClient::new(upstreams);
+            let consistent_client = Box::new(client.clone().consistent()) as Box<DynClient<L2>>;
+            let monitor = UpstreamsMonitorTask {
+                client: client.clone(),
+            };
+            (
+                Box::new(client) as Box<DynClient<L2>>,
+                consistent_client,
+                Some(monitor),
+            )
+        };
 
         // Insert healthcheck
         input
@@ -71,6 +114,36 @@ impl WiringLayer for MainNodeClientLayer {
 
         Ok(Output {
             main_node_client: client.into(),
+            consistent_main_node_client: consistent_client.into(),
+            upstreams_monitor,
         })
     }
 }
+
+/// Task periodically checking health of main node client upstreams.
+#[derive(Debug)]
+pub struct UpstreamsMonitorTask {
+    client: FailoverClient<L2>,
+}
+
+impl UpstreamsMonitorTask {
+    const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+}
+
+#[async_trait::async_trait]
+impl Task for UpstreamsMonitorTask {
+    fn id(&self) -> TaskId {
+        "main_node_upstreams_monitor".into()
+    }
+
+    async fn run(self: Box<Self>, mut stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        while !*stop_receiver.0.borrow() {
+            self.client.check_upstreams().await;
+            tokio::time::timeout(Self::CHECK_INTERVAL, stop_receiver.0.changed())
+                .await
+                .ok();
+        }
+        tracing::info!("Stop signal received, main node upstreams monitor is shutting down");
+        Ok(())
+    }
+}
@@ -5,10 +5,18 @@ use std::{
 };
 
 use anyhow::Context as _;
-use zksync_config::configs::{api::MerkleTreeApiConfig, database::MerkleTreeMode};
+use zksync_config::{
+    configs::{
+        api::MerkleTreeApiConfig,
+        database::{MerkleTreeMode, MerkleTreeStorage},
+    },
+    ObjectStoreConfig,
+};
 use zksync_metadata_calculator::{
-    LazyAsyncTreeReader, MerkleTreePruningTask, MetadataCalculator, MetadataCalculatorConfig,
+    LazyAsyncTreeReader, MerkleTreeCheckpointTask, MerkleTreePruningTask, MetadataCalculator,
+    MetadataCalculatorConfig,
 };
+use zksync_object_store::ObjectStoreFactory;
 use zksync_storage::RocksDB;
 
 use crate::{
@@ -30,14 +38,18 @@ pub struct MetadataCalculatorLayer {
     config: MetadataCalculatorConfig,
     tree_api_config: Option<MerkleTreeApiConfig>,
     pruning_config: Option<Duration>,
+    checkpoint_object_store_config: Option<ObjectStoreConfig>,
 }
 
+/// Number of connections in the pool used by the Postgres-backed Merkle tree storage.
+const TREE_STORAGE_POOL_SIZE: u32 = 5;
+
 #[derive(Debug, FromContext)]
 #[context(crate = crate)]
 pub struct Input {
     pub master_pool: PoolResource<MasterPool>,
     pub replica_pool: PoolResource<ReplicaPool>,
-    /// Only needed for `MerkleTreeMode::Full`
+    /// Only needed for `MerkleTreeMode::Full`, or for tree checkpoints if a dedicated object store is not configured
     pub object_store: Option<ObjectStoreResource>,
     #[context(default)]
     pub app_health: AppHealthCheckResource,
@@ -55,6 +67,9 @@ pub struct Output {
     /// Only provided if configuration is provided.
     #[context(task)]
     pub pruning_task: Option<MerkleTreePruningTask>,
+    /// Only provided if checkpoint export is enabled in the configuration.
+    #[context(task)]
+    pub checkpoint_task: Option<MerkleTreeCheckpointTask>,
     pub rocksdb_shutdown_hook: ShutdownHook,
 }
 
@@ -64,6 +79,7 @@ impl MetadataCalculatorLayer {
             config,
             tree_api_config: None,
             pruning_config: None,
+            checkpoint_object_store_config: None,
         }
     }
 
@@ -76,6 +92,13 @@ impl MetadataCalculatorLayer {
         self.pruning_config = Some(pruning_config);
         self
     }
+
+    /// Sets a dedicated object store for Merkle tree checkpoints. If not set, checkpoints will use
+    /// the object store from the context.
+    pub fn with_checkpoint_object_store_config(mut self, config: ObjectStoreConfig) -> Self {
+        self.checkpoint_object_store_config = Some(config);
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -92,8 +115,30 @@ impl WiringLayer for MetadataCalculatorLayer {
         // The number of connections in a recovery pool is based on the mainnet recovery runs. It doesn't need
         // to be particularly accurate at this point, since the main node isn't expected to recover from a snapshot.
         let recovery_pool = input.replica_pool.get_custom(10).await?;
+        let tree_storage_pool = match self.config.storage {
+            MerkleTreeStorage::RocksDB => None,
+            // Tree nodes are written, so the pool must be a master one.
+            MerkleTreeStorage::Postgres => {
+                Some(input.master_pool.get_custom(TREE_STORAGE_POOL_SIZE).await?)
+            }
+        };
         let app_health = input.app_health.0;
 
+        let checkpoints_config = &self.config.checkpoints;
+        let checkpoint_store =
+            if checkpoints_config.export_interval.is_none() && !checkpoints_config.import_enabled {
+                None
+            } else if let Some(config) = self.checkpoint_object_store_config {
+                Some(ObjectStoreFactory::new(config).create_store().await?)
+            } else {
+                let store = input.object_store.as_ref().ok_or_else(|| {
+                    WiringError::Configuration(
+                        "Object store is required for Merkle tree checkpoints".into(),
+                    )
+                })?;
+                Some(store.0.clone())
+            };
+
         let object_store = match self.config.mode {
             MerkleTreeMode::Lightweight => None,
             MerkleTreeMode::Full => {
@@ -113,6 +158,12 @@ impl WiringLayer for MetadataCalculatorLayer {
         )
         .await?
         .with_recovery_pool(recovery_pool);
+        if let Some(checkpoint_store) = checkpoint_store {
+            metadata_calculator = metadata_calculator.with_checkpoint_store(checkpoint_store);
+        }
+        if let Some(tree_storage_pool) = tree_storage_pool {
+            metadata_calculator = metadata_calculator.with_tree_storage_pool(tree_storage_pool);
+        }
 
         app_health
             .insert_custom_component(Arc::new(metadata_calculator.tree_health_check()))
@@ -140,6 +191,10 @@ impl WiringLayer for MetadataCalculatorLayer {
             )
             .transpose()?;
 
+        let checkpoint_task = metadata_calculator
+            .checkpoint_task()
+            .map_err(|err| WiringError::Configuration(format!("{err:#}")))?;
+
         let tree_api_client = TreeApiClientResource(Arc::new(metadata_calculator.tree_reader()));
 
         let rocksdb_shutdown_hook = ShutdownHook::new("rocksdb_terminaton", async {
@@ -154,6 +209,7 @@ impl WiringLayer for MetadataCalculatorLayer {
             tree_api_client,
             tree_api_task,
             pruning_task,
+            checkpoint_task,
             rocksdb_shutdown_hook,
         })
     }
@@ -195,6 +251,17 @@ impl Task for TreeApiTask {
     }
 }
 
+#[async_trait::async_trait]
+impl Task for MerkleTreeCheckpointTask {
+    fn id(&self) -> TaskId {
+        "merkle_tree_checkpoint_task".into()
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        (*self).run(stop_receiver.0).await
+    }
+}
+
 #[async_trait::async_trait]
 impl Task for MerkleTreePruningTask {
     fn id(&self) -> TaskId {
@@ -1,3 +1,5 @@
+pub mod address_policy;
+pub mod admin_server;
 pub mod base_token;
 pub mod batch_status_updater;
 pub mod block_reverter;
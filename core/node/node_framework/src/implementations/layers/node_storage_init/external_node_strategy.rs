@@ -1,18 +1,22 @@
 use std::{num::NonZeroUsize, sync::Arc};
 
 // Re-export to initialize the layer without having to depend on the crate directly.
-pub use zksync_node_storage_init::SnapshotRecoveryConfig;
 use zksync_node_storage_init::{
-    external_node::{ExternalNodeGenesis, ExternalNodeReverter, ExternalNodeSnapshotRecovery},
+    external_node::{
+        ExternalNodeGenesis, ExternalNodeL1Recovery, ExternalNodeReverter,
+        ExternalNodeSnapshotRecovery,
+    },
     InitializeStorage, NodeInitializationStrategy, RevertStorage,
 };
-use zksync_types::L2ChainId;
+pub use zksync_node_storage_init::{L1RecoveryConfig, SnapshotRecoveryConfig};
+use zksync_types::{url::SensitiveUrl, L2ChainId};
 
 use super::NodeInitializationStrategyResource;
 use crate::{
     implementations::resources::{
+        eth_interface::EthInterfaceResource,
         healthcheck::AppHealthCheckResource,
-        main_node_client::MainNodeClientResource,
+        main_node_client::{ConsistentMainNodeClientResource, MainNodeClientResource},
         pools::{MasterPool, PoolResource},
         reverter::BlockReverterResource,
     },
@@ -26,6 +30,10 @@ pub struct ExternalNodeInitStrategyLayer {
     pub l2_chain_id: L2ChainId,
     pub max_postgres_concurrency: NonZeroUsize,
     pub snapshot_recovery_config: Option<SnapshotRecoveryConfig>,
+    /// Configuration for recovering storage from L1 data. Mutually exclusive with `snapshot_recovery_config`.
+    pub l1_recovery_config: Option<L1RecoveryConfig>,
+    /// URL of the blob archive used during recovery from L1. Only used if `l1_recovery_config` is set.
+    pub l1_recovery_blob_api_url: Option<SensitiveUrl>,
 }
 
 #[derive(Debug, FromContext)]
@@ -33,6 +41,10 @@ pub struct ExternalNodeInitStrategyLayer {
 pub struct Input {
     pub master_pool: PoolResource<MasterPool>,
     pub main_node_client: MainNodeClientResource,
+    /// Used for reorg detection.
+    pub consistent_main_node_client: ConsistentMainNodeClientResource,
+    /// Only required for recovery from L1.
+    pub eth_client: Option<EthInterfaceResource>,
     pub block_reverter: Option<BlockReverterResource>,
     #[context(default)]
     pub app_health: AppHealthCheckResource,
@@ -56,6 +68,7 @@ impl WiringLayer for ExternalNodeInitStrategyLayer {
     async fn wire(self, input: Self::Input) -> Result<Self::Output, WiringError> {
         let pool = input.master_pool.get().await?;
         let MainNodeClientResource(client) = input.main_node_client;
+        let ConsistentMainNodeClientResource(consistent_client) = input.consistent_main_node_client;
         let AppHealthCheckResource(app_health) = input.app_health;
         let block_reverter = match input.block_reverter {
             Some(reverter) => {
@@ -74,8 +87,14 @@ impl WiringLayer for ExternalNodeInitStrategyLayer {
             client: client.clone(),
             pool: pool.clone(),
         });
-        let snapshot_recovery = match self.snapshot_recovery_config {
-            Some(recovery_config) => {
+        let snapshot_recovery = match (self.snapshot_recovery_config, self.l1_recovery_config) {
+            (Some(_), Some(_)) => {
+                return Err(WiringError::Configuration(
+                    "Snapshot recovery and recovery from L1 cannot be enabled simultaneously"
+                        .into(),
+                ));
+            }
+            (Some(recovery_config), None) => {
                 // Add a connection for checking whether the storage is initialized.
                 let recovery_pool = input
                     .master_pool
@@ -90,11 +109,25 @@ impl WiringLayer for ExternalNodeInitStrategyLayer {
                 });
                 Some(recovery)
             }
-            None => None,
+            (None, Some(recovery_config)) => {
+                let EthInterfaceResource(l1_client) = input.eth_client.ok_or_else(|| {
+                    WiringError::Configuration("Recovery from L1 requires an L1 client".into())
+                })?;
+                let recovery: Arc<dyn InitializeStorage> = Arc::new(ExternalNodeL1Recovery {
+                    l2_chain_id: self.l2_chain_id,
+                    main_node_client: client.clone(),
+                    l1_client,
+                    pool: pool.clone(),
+                    recovery_config,
+                    blob_api_url: self.l1_recovery_blob_api_url,
+                });
+                Some(recovery)
+            }
+            (None, None) => None,
         };
         // We always want to detect reorgs, even if we can't roll them back.
         let block_reverter = Some(Arc::new(ExternalNodeReverter {
-            client,
+            client: consistent_client,
             pool: pool.clone(),
             reverter: block_reverter,
         }) as Arc<dyn RevertStorage>);
@@ -1,22 +1,30 @@
+use std::sync::Arc;
+
 use zksync_health_check::{HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_vlog::prometheus::PrometheusExporterConfig;
 
 use crate::{
     implementations::resources::healthcheck::AppHealthCheckResource,
     service::StopReceiver,
-    task::{Task, TaskId, TaskKind},
+    task::{RestartPolicy, RestartableTask, Task, TaskId, TaskKind},
     wiring_layer::{WiringError, WiringLayer},
     FromContext, IntoContext,
 };
 
 /// Wiring layer for Prometheus exporter server.
+///
+/// The exporter is not critical for the node operation, so it's restarted on errors.
 #[derive(Debug)]
 pub struct PrometheusExporterLayer(pub PrometheusExporterConfig);
 
+impl PrometheusExporterLayer {
+    const RESTART_POLICY: RestartPolicy = RestartPolicy::exponential_backoff(10);
+}
+
 #[derive(Debug)]
 pub struct PrometheusExporterTask {
     config: PrometheusExporterConfig,
-    prometheus_health_updater: HealthUpdater,
+    prometheus_health_updater: Arc<HealthUpdater>,
 }
 
 #[derive(Debug, FromContext)]
@@ -30,7 +38,7 @@ pub struct Input {
 #[context(crate = crate)]
 pub struct Output {
     #[context(task)]
-    pub task: PrometheusExporterTask,
+    pub task: RestartableTask,
 }
 
 #[async_trait::async_trait]
@@ -52,10 +60,13 @@ impl WiringLayer for PrometheusExporterLayer {
             .insert_component(prometheus_health_check)
             .map_err(WiringError::internal)?;
 
-        let task = PrometheusExporterTask {
-            config: self.0,
-            prometheus_health_updater,
-        };
+        let prometheus_health_updater = Arc::new(prometheus_health_updater);
+        let task = RestartableTask::new(Self::RESTART_POLICY, move || PrometheusExporterTask {
+            config: self.0.clone(),
+            prometheus_health_updater: prometheus_health_updater.clone(),
+        })
+        .with_health_check(&input.app_health.0, "prometheus_exporter_restarts")
+        .map_err(WiringError::internal)?;
 
         Ok(Output { task })
     }
@@ -76,7 +87,9 @@ impl Task for PrometheusExporterTask {
         self.prometheus_health_updater
             .update(HealthStatus::Ready.into());
         let res = prometheus_task.await;
-        drop(self.prometheus_health_updater);
+        // The updater is shared among task restarts, so it cannot be dropped here.
+        self.prometheus_health_updater
+            .update(HealthStatus::ShutDown.into());
         res
     }
 }
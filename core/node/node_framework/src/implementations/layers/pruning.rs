@@ -1,5 +1,6 @@
 use std::time::Duration;
 
+use zksync_dal::pruning_dal::RetentionPolicy;
 use zksync_node_db_pruner::{DbPruner, DbPrunerConfig};
 
 use crate::{
@@ -19,6 +20,7 @@ pub struct PruningLayer {
     pruning_removal_delay: Duration,
     pruning_chunk_size: u32,
     minimum_l1_batch_age: Duration,
+    retention_policy: RetentionPolicy,
 }
 
 #[derive(Debug, FromContext)]
@@ -46,8 +48,15 @@ impl PruningLayer {
             pruning_removal_delay,
             pruning_chunk_size,
             minimum_l1_batch_age,
+            retention_policy: RetentionPolicy::default(),
         }
     }
+
+    /// Sets the policy specifying data retained beyond the pruning horizon. By default, all data is pruned.
+    pub fn with_retention_policy(mut self, retention_policy: RetentionPolicy) -> Self {
+        self.retention_policy = retention_policy;
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -67,6 +76,7 @@ impl WiringLayer for PruningLayer {
                 removal_delay: self.pruning_removal_delay,
                 pruned_batch_chunk_size: self.pruning_chunk_size,
                 minimum_l1_batch_age: self.minimum_l1_batch_age,
+                retention_policy: self.retention_policy,
             },
             main_pool,
         );
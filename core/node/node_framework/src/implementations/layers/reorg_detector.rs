@@ -3,7 +3,7 @@ use zksync_reorg_detector::{self, ReorgDetector};
 use crate::{
     implementations::resources::{
         healthcheck::AppHealthCheckResource,
-        main_node_client::MainNodeClientResource,
+        main_node_client::ConsistentMainNodeClientResource,
         pools::{MasterPool, PoolResource},
     },
     service::StopReceiver,
@@ -22,7 +22,7 @@ pub struct ReorgDetectorLayer;
 #[derive(Debug, FromContext)]
 #[context(crate = crate)]
 pub struct Input {
-    pub main_node_client: MainNodeClientResource,
+    pub main_node_client: ConsistentMainNodeClientResource,
     pub master_pool: PoolResource<MasterPool>,
     #[context(default)]
     pub app_health: AppHealthCheckResource,
@@ -45,7 +45,7 @@ impl WiringLayer for ReorgDetectorLayer {
     }
 
     async fn wire(self, input: Self::Input) -> Result<Self::Output, WiringError> {
-        let MainNodeClientResource(main_node_client) = input.main_node_client;
+        let ConsistentMainNodeClientResource(main_node_client) = input.main_node_client;
         let pool = input.master_pool.get().await?;
 
         let reorg_detector = ReorgDetector::new(main_node_client, pool);
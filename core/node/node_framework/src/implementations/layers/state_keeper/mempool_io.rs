@@ -1,16 +1,23 @@
+use std::sync::Arc;
+
 use anyhow::Context as _;
+use serde::Deserialize;
 use zksync_config::configs::{
     chain::{MempoolConfig, StateKeeperConfig},
     wallets,
 };
-use zksync_state_keeper::{MempoolFetcher, MempoolGuard, MempoolIO, SequencerSealer};
-use zksync_types::L2ChainId;
+use zksync_state_keeper::{
+    l1_batch_seal_requests, L1BatchSealTrigger, MempoolFetcher, MempoolGuard, MempoolIO,
+    SequencerSealer,
+};
+use zksync_types::{L1BatchNumber, L2ChainId};
 
 use crate::{
     implementations::resources::{
+        admin::{AdminAction, AdminControlsResource},
         fee_input::SequencerFeeInputResource,
         pools::{MasterPool, PoolResource},
-        state_keeper::{ConditionalSealerResource, StateKeeperIOResource},
+        state_keeper::{AddressPolicyResource, ConditionalSealerResource, StateKeeperIOResource},
     },
     service::StopReceiver,
     task::{Task, TaskId},
@@ -20,10 +27,19 @@ use crate::{
 
 /// Wiring layer for `MempoolIO`, an IO part of state keeper used by the main node.
 ///
+/// Registers the following admin actions:
+///
+/// - `seal_l1_batch`: seals the current L2 block and L1 batch at the next safe point.
+/// - `stop_l2_txs_after_l1_batch`: stops accepting L2 transactions in L1 batches after the specified one
+///   (params: `{ "l1_batch": number | null }`; `null` resumes accepting L2 transactions). The setting is persisted
+///   in Postgres, so it survives node restarts.
+///
 /// ## Requests resources
 ///
 /// - `FeeInputResource`
 /// - `PoolResource<MasterPool>`
+/// - `AddressPolicyResource` (optional)
+/// - `AdminControlsResource` (optional)
 ///
 /// ## Adds resources
 ///
@@ -46,6 +62,9 @@ pub struct MempoolIOLayer {
 pub struct Input {
     pub fee_input: SequencerFeeInputResource,
     pub master_pool: PoolResource<MasterPool>,
+    pub address_policy: Option<AddressPolicyResource>,
+    #[context(default)]
+    pub admin_controls: AdminControlsResource,
 }
 
 #[derive(Debug, IntoContext)]
@@ -130,6 +149,33 @@ impl WiringLayer for MempoolIOLayer {
             self.mempool_config.delay_interval(),
             self.zksync_network_id,
         )?;
+        let io = match input.address_policy {
+            Some(AddressPolicyResource(policy)) => io.with_address_policy(policy),
+            None => io,
+        };
+        let (seal_trigger, seal_requests) = l1_batch_seal_requests();
+        let seal_trigger_pool = master_pool
+            .get_singleton()
+            .await
+            .context("Get master pool")?;
+        let seal_trigger = seal_trigger
+            .with_persistence(seal_trigger_pool)
+            .await
+            .context("failed restoring persisted L1 batch seal settings")?;
+        let io = io.with_seal_requests(seal_requests);
+        let admin_controls = input.admin_controls.0;
+        admin_controls
+            .register_action(
+                "seal_l1_batch",
+                Arc::new(SealL1BatchAction(seal_trigger.clone())),
+            )
+            .map_err(WiringError::internal)?;
+        admin_controls
+            .register_action(
+                "stop_l2_txs_after_l1_batch",
+                Arc::new(StopL2TxsAction(seal_trigger)),
+            )
+            .map_err(WiringError::internal)?;
 
         // Create sealer.
         let sealer = SequencerSealer::new(self.state_keeper_config);
@@ -142,6 +188,40 @@ impl WiringLayer for MempoolIOLayer {
     }
 }
 
+/// Admin action forcing the state keeper to seal the current L1 batch.
+#[derive(Debug)]
+struct SealL1BatchAction(L1BatchSealTrigger);
+
+#[async_trait::async_trait]
+impl AdminAction for SealL1BatchAction {
+    async fn run(&self, _params: serde_json::Value) -> anyhow::Result<serde_json::Value> {
+        let sealed_l1_batch = self.0.seal_l1_batch().await?;
+        Ok(serde_json::json!({ "sealed_l1_batch": sealed_l1_batch }))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StopL2TxsParams {
+    l1_batch: Option<L1BatchNumber>,
+}
+
+/// Admin action making the state keeper stop (or resume) accepting L2 transactions after a certain L1 batch.
+#[derive(Debug)]
+struct StopL2TxsAction(L1BatchSealTrigger);
+
+#[async_trait::async_trait]
+impl AdminAction for StopL2TxsAction {
+    async fn run(&self, params: serde_json::Value) -> anyhow::Result<serde_json::Value> {
+        // Require params explicitly so that an accidental empty request doesn't resume accepting L2 transactions.
+        anyhow::ensure!(!params.is_null(), "`l1_batch` param is required");
+        let params: StopL2TxsParams =
+            serde_json::from_value(params).context("invalid action params")?;
+        self.0.stop_after_l1_batch(params.l1_batch).await?;
+        Ok(serde_json::json!({ "stop_after_l1_batch": self.0.stopped_after_l1_batch() }))
+    }
+}
+
 #[async_trait::async_trait]
 impl Task for MempoolFetcher {
     fn id(&self) -> TaskId {
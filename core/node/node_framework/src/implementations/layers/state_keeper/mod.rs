@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
 use anyhow::Context;
+use tokio::sync::watch;
 pub use zksync_state::RocksdbStorageOptions;
 use zksync_state::{AsyncCatchupTask, OwnedStorage, ReadStorageFactory};
 use zksync_state_keeper::{
@@ -12,6 +13,7 @@ use zksync_vm_executor::interface::BatchExecutorFactory;
 
 use crate::{
     implementations::resources::{
+        admin::AdminControlsResource,
         pools::{MasterPool, PoolResource},
         state_keeper::{
             BatchExecutorResource, ConditionalSealerResource, OutputHandlerResource,
@@ -30,6 +32,9 @@ pub mod mempool_io;
 pub mod output_handler;
 
 /// Wiring layer for the state keeper.
+///
+/// The state keeper can be paused via the admin server. While paused, it doesn't execute new transactions,
+/// but still seals L2 blocks and L1 batches according to the sealing criteria (e.g., on timeouts).
 #[derive(Debug)]
 pub struct StateKeeperLayer {
     state_keeper_db_path: String,
@@ -44,6 +49,8 @@ pub struct Input {
     pub output_handler: OutputHandlerResource,
     pub conditional_sealer: ConditionalSealerResource,
     pub master_pool: PoolResource<MasterPool>,
+    #[context(default)]
+    pub admin_controls: AdminControlsResource,
 }
 
 #[derive(Debug, IntoContext)]
@@ -92,6 +99,11 @@ impl WiringLayer for StateKeeperLayer {
             .context("HandleStateKeeperOutput was provided but taken by another task")?;
         let sealer = input.conditional_sealer.0;
         let master_pool = input.master_pool;
+        let pause_receiver = input
+            .admin_controls
+            .0
+            .register_task("state_keeper")
+            .map_err(WiringError::internal)?;
 
         let (storage_factory, rocksdb_catchup) = AsyncRocksdbCache::new(
             master_pool.get_custom(2).await?,
@@ -105,6 +117,7 @@ impl WiringLayer for StateKeeperLayer {
             output_handler,
             sealer,
             storage_factory: Arc::new(storage_factory),
+            pause_receiver,
         };
 
         let rocksdb_termination_hook = ShutdownHook::new("rocksdb_terminaton", async {
@@ -128,6 +141,7 @@ pub struct StateKeeperTask {
     output_handler: OutputHandler,
     sealer: Arc<dyn ConditionalSealer>,
     storage_factory: Arc<dyn ReadStorageFactory>,
+    pause_receiver: watch::Receiver<bool>,
 }
 
 #[async_trait::async_trait]
@@ -144,7 +158,8 @@ impl Task for StateKeeperTask {
             self.output_handler,
             self.sealer,
             self.storage_factory,
-        );
+        )
+        .with_pause_receiver(self.pause_receiver);
         state_keeper.run().await
     }
 }
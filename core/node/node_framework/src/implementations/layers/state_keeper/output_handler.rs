@@ -1,16 +1,18 @@
 use anyhow::Context as _;
 use zksync_node_framework_derive::FromContext;
 use zksync_state_keeper::{
-    io::seal_logic::l2_block_seal_subtasks::L2BlockSealProcess, L2BlockSealerTask, OutputHandler,
-    StateKeeperPersistence, TreeWritesPersistence,
+    io::seal_logic::l2_block_seal_subtasks::L2BlockSealProcess, L2BaseFeeUpdater,
+    L2BlockSealerTask, OutputHandler, StateKeeperPersistence, TreeWritesPersistence,
 };
 use zksync_types::Address;
 
 use crate::{
     implementations::resources::{
+        fee_input::L2BaseFeeAdjusterResource,
         pools::{MasterPool, PoolResource},
         state_keeper::OutputHandlerResource,
         sync_state::SyncStateResource,
+        web3_api::PreconfirmationsResource,
     },
     resource::Unique,
     service::StopReceiver,
@@ -25,6 +27,8 @@ use crate::{
 ///
 /// - `PoolResource<MasterPool>`
 /// - `SyncStateResource` (optional)
+/// - `L2BaseFeeAdjusterResource` (optional)
+/// - `PreconfirmationsResource` (optional)
 ///
 /// ## Adds resources
 ///
@@ -52,6 +56,8 @@ pub struct OutputHandlerLayer {
 pub struct Input {
     pub master_pool: PoolResource<MasterPool>,
     pub sync_state: Option<SyncStateResource>,
+    pub l2_base_fee_adjuster: Option<L2BaseFeeAdjusterResource>,
+    pub preconfirmations: Option<PreconfirmationsResource>,
 }
 
 #[derive(Debug, IntoContext)]
@@ -121,6 +127,12 @@ impl WiringLayer for OutputHandlerLayer {
         if let Some(sync_state) = input.sync_state {
             output_handler = output_handler.with_handler(Box::new(sync_state.0));
         }
+        if let Some(L2BaseFeeAdjusterResource(adjuster)) = input.l2_base_fee_adjuster {
+            output_handler = output_handler.with_handler(Box::new(L2BaseFeeUpdater::new(adjuster)));
+        }
+        if let Some(PreconfirmationsResource(preconfirmations)) = input.preconfirmations {
+            output_handler = output_handler.with_handler(Box::new(preconfirmations.publisher()));
+        }
         let output_handler = OutputHandlerResource(Unique::new(output_handler));
 
         Ok(Output {
@@ -0,0 +1,39 @@
+use zksync_node_api_server::web3::Preconfirmations;
+
+use crate::{
+    implementations::resources::web3_api::PreconfirmationsResource,
+    wiring_layer::{WiringError, WiringLayer},
+    IntoContext,
+};
+
+/// Wiring layer for the transaction preconfirmations channel.
+///
+/// Should be added before the state keeper and WebSocket API server layers, which publish to / subscribe to the channel.
+///
+/// ## Adds resources
+///
+/// - `PreconfirmationsResource`
+#[derive(Debug)]
+pub struct PreconfirmationsLayer;
+
+#[derive(Debug, IntoContext)]
+#[context(crate = crate)]
+pub struct Output {
+    pub preconfirmations: PreconfirmationsResource,
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for PreconfirmationsLayer {
+    type Input = ();
+    type Output = Output;
+
+    fn layer_name(&self) -> &'static str {
+        "preconfirmations_layer"
+    }
+
+    async fn wire(self, _input: Self::Input) -> Result<Self::Output, WiringError> {
+        Ok(Output {
+            preconfirmations: Preconfirmations::default().into(),
+        })
+    }
+}
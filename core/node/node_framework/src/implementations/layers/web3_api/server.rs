@@ -1,9 +1,11 @@
-use std::{num::NonZeroU32, time::Duration};
+use std::{num::NonZeroU32, path::PathBuf, time::Duration};
 
 use tokio::{sync::oneshot, task::JoinHandle};
 use zksync_circuit_breaker::replication_lag::ReplicationLagChecker;
 use zksync_config::configs::api::MaxResponseSize;
-use zksync_node_api_server::web3::{state::InternalApiConfig, ApiBuilder, ApiServer, Namespace};
+use zksync_node_api_server::web3::{
+    state::InternalApiConfig, ApiBuilder, ApiServer, HttpRateLimits, IpcConfig, Namespace,
+};
 
 use crate::{
     implementations::resources::{
@@ -11,7 +13,9 @@ use crate::{
         healthcheck::AppHealthCheckResource,
         pools::{PoolResource, ReplicaPool},
         sync_state::SyncStateResource,
-        web3_api::{MempoolCacheResource, TreeApiClientResource, TxSenderResource},
+        web3_api::{
+            MempoolCacheResource, PreconfirmationsResource, TreeApiClientResource, TxSenderResource,
+        },
     },
     service::StopReceiver,
     task::{Task, TaskId},
@@ -20,7 +24,7 @@ use crate::{
 };
 
 /// Set of optional variables that can be altered to modify the behavior of API builder.
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct Web3ServerOptionalConfig {
     pub namespaces: Option<Vec<Namespace>>,
     pub filters_limit: Option<usize>,
@@ -28,6 +32,10 @@ pub struct Web3ServerOptionalConfig {
     pub batch_request_size_limit: Option<usize>,
     pub response_body_size_limit: Option<MaxResponseSize>,
     pub websocket_requests_per_minute_limit: Option<NonZeroU32>,
+    /// Only has effect for the HTTP server.
+    pub http_rate_limits: Option<HttpRateLimits>,
+    /// Only has effect for the IPC server.
+    pub ipc_config: Option<IpcConfig>,
     pub with_extended_tracing: bool,
     // Used by circuit breaker.
     pub replication_lag_limit: Option<Duration>,
@@ -58,6 +66,12 @@ impl Web3ServerOptionalConfig {
             api_builder = api_builder
                 .with_websocket_requests_per_minute_limit(websocket_requests_per_minute_limit);
         }
+        if let Some(http_rate_limits) = self.http_rate_limits {
+            api_builder = api_builder.with_http_rate_limits(http_rate_limits);
+        }
+        if let Some(ipc_config) = self.ipc_config {
+            api_builder = api_builder.with_ipc_config(ipc_config);
+        }
         if let Some(polling_interval) = self.polling_interval {
             api_builder = api_builder.with_polling_interval(polling_interval);
         }
@@ -67,10 +81,11 @@ impl Web3ServerOptionalConfig {
 }
 
 /// Internal-only marker of chosen transport.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 enum Transport {
-    Http,
-    Ws,
+    Http(u16),
+    Ws(u16),
+    Ipc(PathBuf),
 }
 
 /// Wiring layer for Web3 JSON RPC server.
@@ -82,6 +97,7 @@ enum Transport {
 /// - `SyncStateResource` (optional)
 /// - `TreeApiClientResource` (optional)
 /// - `MempoolCacheResource`
+/// - `PreconfirmationsResource` (optional)
 /// - `CircuitBreakersResource` (adds a circuit breaker)
 /// - `AppHealthCheckResource` (adds a health check)
 ///
@@ -92,7 +108,6 @@ enum Transport {
 #[derive(Debug)]
 pub struct Web3ServerLayer {
     transport: Transport,
-    port: u16,
     internal_api_config: InternalApiConfig,
     optional_config: Web3ServerOptionalConfig,
 }
@@ -105,6 +120,7 @@ pub struct Input {
     pub sync_state: Option<SyncStateResource>,
     pub tree_api_client: Option<TreeApiClientResource>,
     pub mempool_cache: MempoolCacheResource,
+    pub preconfirmations: Option<PreconfirmationsResource>,
     #[context(default)]
     pub circuit_breakers: CircuitBreakersResource,
     #[context(default)]
@@ -127,8 +143,7 @@ impl Web3ServerLayer {
         optional_config: Web3ServerOptionalConfig,
     ) -> Self {
         Self {
-            transport: Transport::Http,
-            port,
+            transport: Transport::Http(port),
             internal_api_config,
             optional_config,
         }
@@ -140,8 +155,21 @@ impl Web3ServerLayer {
         optional_config: Web3ServerOptionalConfig,
     ) -> Self {
         Self {
-            transport: Transport::Ws,
-            port,
+            transport: Transport::Ws(port),
+            internal_api_config,
+            optional_config,
+        }
+    }
+
+    /// Creates a layer for the IPC server listening on a Unix domain socket at the specified path.
+    /// The IPC server supports the same namespaces and subscriptions as the WS server.
+    pub fn ipc(
+        path: PathBuf,
+        internal_api_config: InternalApiConfig,
+        optional_config: Web3ServerOptionalConfig,
+    ) -> Self {
+        Self {
+            transport: Transport::Ipc(path),
             internal_api_config,
             optional_config,
         }
@@ -155,8 +183,9 @@ impl WiringLayer for Web3ServerLayer {
 
     fn layer_name(&self) -> &'static str {
         match self.transport {
-            Transport::Http => "web3_http_server_layer",
-            Transport::Ws => "web3_ws_server_layer",
+            Transport::Http(_) => "web3_http_server_layer",
+            Transport::Ws(_) => "web3_ws_server_layer",
+            Transport::Ipc(_) => "web3_ipc_server_layer",
         }
     }
 
@@ -180,17 +209,23 @@ impl WiringLayer for Web3ServerLayer {
         if let Some(client) = tree_api_client {
             api_builder = api_builder.with_tree_api(client);
         }
-        match self.transport {
-            Transport::Http => {
-                api_builder = api_builder.http(self.port);
+        match &self.transport {
+            Transport::Http(port) => {
+                api_builder = api_builder.http(*port);
+            }
+            Transport::Ws(port) => {
+                api_builder = api_builder.ws(*port);
             }
-            Transport::Ws => {
-                api_builder = api_builder.ws(self.port);
+            Transport::Ipc(path) => {
+                api_builder = api_builder.ipc(path.clone());
             }
         }
         if let Some(sync_state) = sync_state {
             api_builder = api_builder.with_sync_state(sync_state);
         }
+        if let Some(PreconfirmationsResource(preconfirmations)) = input.preconfirmations {
+            api_builder = api_builder.with_preconfirmations(preconfirmations);
+        }
         if let Some(pruning_info_refresh_interval) =
             self.optional_config.pruning_info_refresh_interval
         {
@@ -257,8 +292,9 @@ type ApiJoinHandle = JoinHandle<anyhow::Result<()>>;
 impl Task for Web3ApiTask {
     fn id(&self) -> TaskId {
         match self.transport {
-            Transport::Http => "web3_http_server".into(),
-            Transport::Ws => "web3_ws_server".into(),
+            Transport::Http(_) => "web3_http_server".into(),
+            Transport::Ws(_) => "web3_ws_server".into(),
+            Transport::Ipc(_) => "web3_ipc_server".into(),
         }
     }
 
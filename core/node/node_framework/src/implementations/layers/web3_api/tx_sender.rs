@@ -18,7 +18,7 @@ use crate::{
         fee_input::ApiFeeInputResource,
         main_node_client::MainNodeClientResource,
         pools::{PoolResource, ReplicaPool},
-        state_keeper::ConditionalSealerResource,
+        state_keeper::{AddressPolicyResource, ConditionalSealerResource},
         web3_api::{TxSenderResource, TxSinkResource},
     },
     service::StopReceiver,
@@ -42,6 +42,7 @@ pub struct PostgresStorageCachesConfig {
 /// - `TxSinkResource`
 /// - `PoolResource<ReplicaPool>`
 /// - `ConditionalSealerResource` (optional)
+/// - `AddressPolicyResource` (optional)
 /// - `FeeInputResource`
 ///
 /// ## Adds resources
@@ -70,6 +71,7 @@ pub struct Input {
     pub fee_input: ApiFeeInputResource,
     pub main_node_client: Option<MainNodeClientResource>,
     pub sealer: Option<ConditionalSealerResource>,
+    pub address_policy: Option<AddressPolicyResource>,
 }
 
 #[derive(Debug, IntoContext)]
@@ -153,6 +155,9 @@ impl WiringLayer for TxSenderLayer {
         if let Some(sealer) = sealer {
             tx_sender = tx_sender.with_sealer(sealer);
         }
+        if let Some(AddressPolicyResource(address_policy)) = input.address_policy {
+            tx_sender = tx_sender.with_address_policy(address_policy);
+        }
 
         // Add the task for updating the whitelisted tokens for the AA cache.
         let whitelisted_tokens_for_aa_update_task = if self.whitelisted_tokens_for_aa_cache {
@@ -0,0 +1,17 @@
+use std::sync::Arc;
+
+// Public re-exports from external crate to minimize the required dependencies.
+pub use zksync_node_api_server::admin::{AdminAction, AdminControls, AdminControlsError};
+
+use crate::resource::Resource;
+
+/// A resource that provides [`AdminControls`] to the service. Components supporting pausing and one-off admin actions
+/// register in the controls; the controls are exposed by the admin server (if it's enabled).
+#[derive(Debug, Clone, Default)]
+pub struct AdminControlsResource(pub Arc<AdminControls>);
+
+impl Resource for AdminControlsResource {
+    fn name() -> String {
+        "common/admin_controls".into()
+    }
+}
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use zksync_node_fee_model::BatchFeeModelInputProvider;
+use zksync_node_fee_model::{BatchFeeModelInputProvider, L2BaseFeeAdjuster};
 
 use crate::resource::Resource;
 
@@ -35,3 +35,13 @@ impl<T: BatchFeeModelInputProvider> From<Arc<T>> for ApiFeeInputResource {
         Self(provider)
     }
 }
+
+/// A resource that provides [`L2BaseFeeAdjuster`] shared by the sequencer fee input provider and the state keeper.
+#[derive(Debug, Clone)]
+pub struct L2BaseFeeAdjusterResource(pub Arc<L2BaseFeeAdjuster>);
+
+impl Resource for L2BaseFeeAdjusterResource {
+    fn name() -> String {
+        "common/l2_base_fee_adjuster".into()
+    }
+}
@@ -17,3 +17,20 @@ impl<T: Into<Box<DynClient<L2>>>> From<T> for MainNodeClientResource {
         Self(client.into())
     }
 }
+
+/// A resource that provides L2 interface object for consistency checks (e.g., reorg detection). Unlike [`MainNodeClientResource`],
+/// the client never sends requests to an upstream lagging behind other upstreams.
+#[derive(Debug, Clone)]
+pub struct ConsistentMainNodeClientResource(pub Box<DynClient<L2>>);
+
+impl Resource for ConsistentMainNodeClientResource {
+    fn name() -> String {
+        "external_node/consistent_main_node_client".into()
+    }
+}
+
+impl<T: Into<Box<DynClient<L2>>>> From<T> for ConsistentMainNodeClientResource {
+    fn from(client: T) -> Self {
+        Self(client.into())
+    }
+}
@@ -1,4 +1,5 @@
 pub mod action_queue;
+pub mod admin;
 pub mod base_token_ratio_provider;
 pub mod circuit_breakers;
 pub mod da_client;
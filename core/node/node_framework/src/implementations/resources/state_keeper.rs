@@ -1,7 +1,9 @@
 use std::sync::Arc;
 
 use zksync_state::OwnedStorage;
-use zksync_state_keeper::{seal_criteria::ConditionalSealer, OutputHandler, StateKeeperIO};
+use zksync_state_keeper::{
+    seal_criteria::ConditionalSealer, AddressPolicy, OutputHandler, StateKeeperIO,
+};
 use zksync_vm_executor::interface::BatchExecutorFactory;
 
 use crate::resource::{Resource, Unique};
@@ -78,3 +80,19 @@ where
         Self(Arc::new(sealer))
     }
 }
+
+/// A resource that provides the [`AddressPolicy`] shared by the state keeper and the API server.
+#[derive(Debug, Clone)]
+pub struct AddressPolicyResource(pub AddressPolicy);
+
+impl Resource for AddressPolicyResource {
+    fn name() -> String {
+        "state_keeper/address_policy".into()
+    }
+}
+
+impl From<AddressPolicy> for AddressPolicyResource {
+    fn from(policy: AddressPolicy) -> Self {
+        Self(policy)
+    }
+}
@@ -3,7 +3,7 @@ use std::sync::Arc;
 use zksync_metadata_calculator::api_server::TreeApiClient;
 use zksync_node_api_server::{
     tx_sender::{tx_sink::TxSink, TxSender},
-    web3::mempool_cache::MempoolCache,
+    web3::{mempool_cache::MempoolCache, Preconfirmations},
 };
 
 use crate::resource::Resource;
@@ -71,3 +71,19 @@ impl From<MempoolCache> for MempoolCacheResource {
         Self(cache)
     }
 }
+
+/// A resource that provides the [`Preconfirmations`] channel shared by the state keeper and the WebSocket API server.
+#[derive(Debug, Clone)]
+pub struct PreconfirmationsResource(pub Preconfirmations);
+
+impl Resource for PreconfirmationsResource {
+    fn name() -> String {
+        "api/preconfirmations".into()
+    }
+}
+
+impl From<Preconfirmations> for PreconfirmationsResource {
+    fn from(preconfirmations: Preconfirmations) -> Self {
+        Self(preconfirmations)
+    }
+}
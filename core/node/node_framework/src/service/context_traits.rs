@@ -1,4 +1,11 @@
-use crate::{resource::Resource, service::context::ServiceContext, wiring_layer::WiringError};
+use crate::{
+    resource::Resource,
+    service::{
+        context::ServiceContext,
+        wiring_graph::{LayerDescription, ResourceRequest},
+    },
+    wiring_layer::WiringError,
+};
 
 /// Trait used as input for wiring layers, aiming to provide all the resources the layer needs for wiring.
 ///
@@ -33,12 +40,20 @@ use crate::{resource::Resource, service::context::ServiceContext, wiring_layer::
 /// ```
 pub trait FromContext: Sized {
     fn from_context(context: &mut ServiceContext<'_>) -> Result<Self, WiringError>;
+
+    /// Records resources requested by [`Self::from_context()`] without accessing the context.
+    /// Used to build the [`WiringGraph`](super::WiringGraph). The default implementation records nothing.
+    fn describe_input(_description: &mut LayerDescription) {}
 }
 
 impl<T: Resource + Clone> FromContext for T {
     fn from_context(context: &mut ServiceContext<'_>) -> Result<Self, WiringError> {
         context.get_resource::<T>()
     }
+
+    fn describe_input(description: &mut LayerDescription) {
+        description.request_resource::<T>(ResourceRequest::Required);
+    }
 }
 
 impl FromContext for () {
@@ -55,6 +70,10 @@ impl<T: FromContext> FromContext for Option<T> {
             Err(err) => Err(err),
         }
     }
+
+    fn describe_input(description: &mut LayerDescription) {
+        description.optional(T::describe_input);
+    }
 }
 
 /// Trait used as output for wiring layers, aiming to provide all the resources and tasks the layer creates.
@@ -106,6 +125,10 @@ impl<T: FromContext> FromContext for Option<T> {
 /// ```
 pub trait IntoContext {
     fn into_context(self, context: &mut ServiceContext<'_>) -> Result<(), WiringError>;
+
+    /// Records resources and tasks provided by [`Self::into_context()`] without accessing the context.
+    /// Used to build the [`WiringGraph`](super::WiringGraph). The default implementation records nothing.
+    fn describe_output(_description: &mut LayerDescription) {}
 }
 
 // Unfortunately, without specialization we cannot provide a blanket implementation for `T: Task`
@@ -114,6 +137,10 @@ impl<T: Resource> IntoContext for T {
     fn into_context(self, context: &mut ServiceContext<'_>) -> Result<(), WiringError> {
         context.insert_resource(self)
     }
+
+    fn describe_output(description: &mut LayerDescription) {
+        description.provide_resource::<T>();
+    }
 }
 
 impl IntoContext for () {
@@ -130,4 +157,8 @@ impl<T: IntoContext> IntoContext for Option<T> {
             Ok(())
         }
     }
+
+    fn describe_output(description: &mut LayerDescription) {
+        description.optional(T::describe_output);
+    }
 }
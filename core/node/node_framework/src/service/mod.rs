@@ -12,6 +12,10 @@ pub use self::{
     error::ZkStackServiceError,
     shutdown_hook::ShutdownHook,
     stop_receiver::StopReceiver,
+    wiring_graph::{
+        LayerDescription, ProvidedResource, ProvidedShutdownHook, ProvidedTask, RequestedResource,
+        ResourceRequest, WiringGraph, WiringGraphFormat,
+    },
 };
 use crate::{
     resource::{ResourceId, StoredResource},
@@ -32,6 +36,7 @@ mod shutdown_hook;
 mod stop_receiver;
 #[cfg(test)]
 mod tests;
+mod wiring_graph;
 
 // A reasonable amount of time for any task to finish the shutdown process
 const TASK_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
@@ -42,7 +47,7 @@ pub struct ZkStackServiceBuilder {
     /// List of wiring layers.
     // Note: It has to be a `Vec` and not e.g. `HashMap` because the order in which we
     // iterate through it matters.
-    layers: Vec<(&'static str, WireFn)>,
+    layers: Vec<(LayerDescription, WireFn)>,
     /// Tokio runtime used to spawn tasks.
     runtime: Runtime,
 }
@@ -93,13 +98,19 @@ impl ZkStackServiceBuilder {
         if !self
             .layers
             .iter()
-            .any(|(existing_name, _)| name == *existing_name)
+            .any(|(existing, _)| name == existing.name)
         {
-            self.layers.push((name, layer.into_wire_fn()));
+            self.layers.push((layer.describe(), layer.into_wire_fn()));
         }
         self
     }
 
+    /// Returns the wiring graph for the added layers. The graph is built without wiring the layers, so it can be used
+    /// as a dry run to check the node composition using [`WiringGraph::validate()`].
+    pub fn wiring_graph(&self) -> WiringGraph {
+        wiring_graph(&self.layers)
+    }
+
     /// Builds the service.
     pub fn build(self) -> ZkStackService {
         let (stop_sender, _stop_receiver) = watch::channel(false);
@@ -122,7 +133,7 @@ pub struct ZkStackService {
     /// Cache of resources that have been requested at least by one task.
     resources: HashMap<ResourceId, Box<dyn StoredResource>>,
     /// List of wiring layers.
-    layers: Vec<(&'static str, WireFn)>,
+    layers: Vec<(LayerDescription, WireFn)>,
     /// Different kinds of tasks for the service.
     runnables: Runnables,
 
@@ -137,7 +148,17 @@ pub struct ZkStackService {
 
 type TaskFuture = NamedFuture<Fuse<JoinHandle<anyhow::Result<()>>>>;
 
+fn wiring_graph(layers: &[(LayerDescription, WireFn)]) -> WiringGraph {
+    let layers = layers.iter().map(|(description, _)| description.clone());
+    WiringGraph::new(layers.collect())
+}
+
 impl ZkStackService {
+    /// Returns the wiring graph for the service layers. See [`ZkStackServiceBuilder::wiring_graph()`] for details.
+    pub fn wiring_graph(&self) -> WiringGraph {
+        wiring_graph(&self.layers)
+    }
+
     /// Runs the system.
     ///
     /// In case of errors during wiring phase, will return the list of all the errors that happened, in the order
@@ -184,7 +205,7 @@ impl ZkStackService {
         let mut errors: Vec<(String, WiringError)> = Vec::new();
 
         let runtime_handle = self.runtime.handle().clone();
-        for (name, WireFn(wire_fn)) in wiring_layers {
+        for (LayerDescription { name, .. }, WireFn(wire_fn)) in wiring_layers {
             // We must process wiring layers sequentially and in the same order as they were added.
             let mut context = ServiceContext::new(name, self);
             let task_result = wire_fn(&runtime_handle, &mut context);
@@ -44,4 +44,8 @@ impl IntoContext for ShutdownHook {
         context.add_shutdown_hook(self);
         Ok(())
     }
+
+    fn describe_output(description: &mut super::LayerDescription) {
+        description.add_shutdown_hook();
+    }
 }
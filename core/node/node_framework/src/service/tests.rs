@@ -1,13 +1,24 @@
-use std::sync::{Arc, Mutex};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
 
 use anyhow::anyhow;
 use assert_matches::assert_matches;
 use tokio::{runtime::Runtime, sync::Barrier};
+use zksync_health_check::{AppHealthCheck, Health, HealthStatus};
 
 use crate::{
-    service::{StopReceiver, WiringError, WiringLayer, ZkStackServiceBuilder, ZkStackServiceError},
-    task::{Task, TaskId},
-    IntoContext,
+    resource::Resource,
+    service::{
+        ResourceRequest, ShutdownHook, StopReceiver, WiringError, WiringGraphFormat, WiringLayer,
+        ZkStackServiceBuilder, ZkStackServiceError,
+    },
+    task::{RestartPolicy, RestartableTask, Task, TaskId},
+    FromContext, IntoContext,
 };
 
 // `ZkStack` Service's `new()` method has to have a check for nested runtime.
@@ -252,3 +263,417 @@ fn test_task_run() {
     let res2 = *remaining_task_was_run.lock().unwrap();
     assert!(res2, "Incorrect resource value");
 }
+
+#[derive(Debug)]
+struct FlakyTaskLayer {
+    failures: usize,
+    panic: bool,
+    run_time: Duration,
+    max_restarts: usize,
+    healthy_period: Duration,
+    attempts: Arc<AtomicUsize>,
+    app_health: Arc<AppHealthCheck>,
+    last_health: Arc<Mutex<Option<Health>>>,
+}
+
+#[derive(Debug, IntoContext)]
+#[context(crate = crate)]
+struct FlakyTaskLayerOutput {
+    #[context(task)]
+    task: RestartableTask,
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for FlakyTaskLayer {
+    type Input = ();
+    type Output = FlakyTaskLayerOutput;
+
+    fn layer_name(&self) -> &'static str {
+        "flaky_task_layer"
+    }
+
+    async fn wire(self, _input: Self::Input) -> Result<Self::Output, WiringError> {
+        let policy = RestartPolicy::exponential_backoff(self.max_restarts)
+            .with_backoff(Duration::from_millis(1), Duration::from_millis(10))
+            .with_healthy_period(self.healthy_period);
+        let app_health = self.app_health.clone();
+        let task = RestartableTask::new(policy, move || FlakyTask {
+            failures: self.failures,
+            panic: self.panic,
+            run_time: self.run_time,
+            attempts: self.attempts.clone(),
+            app_health: self.app_health.clone(),
+            last_health: self.last_health.clone(),
+        })
+        .with_health_check(&app_health, "flaky_task_restarts")
+        .map_err(WiringError::internal)?;
+        Ok(FlakyTaskLayerOutput { task })
+    }
+}
+
+/// Task failing the specified number of times (each time after running for `run_time`), and then exiting successfully.
+#[derive(Debug)]
+struct FlakyTask {
+    failures: usize,
+    panic: bool,
+    run_time: Duration,
+    attempts: Arc<AtomicUsize>,
+    app_health: Arc<AppHealthCheck>,
+    last_health: Arc<Mutex<Option<Health>>>,
+}
+
+#[async_trait::async_trait]
+impl Task for FlakyTask {
+    fn id(&self) -> TaskId {
+        "flaky_task".into()
+    }
+
+    async fn run(self: Box<Self>, _stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        let attempt = self.attempts.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(self.run_time).await;
+        let health = self.app_health.check_health().await;
+        *self.last_health.lock().unwrap() = health.components().get("flaky_task_restarts").cloned();
+
+        if attempt < self.failures {
+            if self.panic {
+                panic!("flaky task panicked");
+            }
+            anyhow::bail!("flaky task failed");
+        }
+        Ok(())
+    }
+}
+
+impl FlakyTaskLayer {
+    fn new(failures: usize, max_restarts: usize) -> Self {
+        Self {
+            failures,
+            panic: false,
+            run_time: Duration::ZERO,
+            max_restarts,
+            healthy_period: Duration::from_secs(60),
+            attempts: Arc::default(),
+            app_health: Arc::default(),
+            last_health: Arc::default(),
+        }
+    }
+
+    fn run_service(&self) -> Result<(), ZkStackServiceError> {
+        let mut zk_stack_service = ZkStackServiceBuilder::new().unwrap();
+        zk_stack_service.add_layer(Self {
+            failures: self.failures,
+            panic: self.panic,
+            run_time: self.run_time,
+            max_restarts: self.max_restarts,
+            healthy_period: self.healthy_period,
+            attempts: self.attempts.clone(),
+            app_health: self.app_health.clone(),
+            last_health: self.last_health.clone(),
+        });
+        zk_stack_service.build().run(None)
+    }
+}
+
+// Failing tasks wrapped in `RestartableTask` should be restarted.
+#[test]
+fn test_restartable_task_recovers_after_failures() {
+    let layer = FlakyTaskLayer::new(2, 3);
+    layer.run_service().unwrap();
+    assert_eq!(layer.attempts.load(Ordering::SeqCst), 3);
+
+    let health = layer.last_health.lock().unwrap().clone().unwrap();
+    assert_matches!(health.status(), HealthStatus::Affected);
+    let details = health.details().unwrap();
+    assert_eq!(details["restarts"], 2);
+    assert_eq!(details["max_restarts"], 3);
+    assert_eq!(details["last_error"], "flaky task failed");
+}
+
+// Panicking tasks wrapped in `RestartableTask` should be restarted as well.
+#[test]
+fn test_restartable_task_recovers_after_panic() {
+    let layer = FlakyTaskLayer {
+        panic: true,
+        ..FlakyTaskLayer::new(1, 3)
+    };
+    layer.run_service().unwrap();
+    assert_eq!(layer.attempts.load(Ordering::SeqCst), 2);
+}
+
+// The service should fail once the restart budget is exhausted.
+#[test]
+fn test_restartable_task_with_exhausted_restarts() {
+    let layer = FlakyTaskLayer::new(usize::MAX, 2);
+    let err = layer.run_service().unwrap_err();
+    assert_matches!(err, ZkStackServiceError::Task(errors) if errors.len() == 1);
+    assert_eq!(layer.attempts.load(Ordering::SeqCst), 3);
+}
+
+// The restart counter should be reset once the task recovers, so that infrequent failures don't exhaust the budget.
+#[test]
+fn test_restartable_task_resets_restarts_after_recovery() {
+    let layer = FlakyTaskLayer {
+        run_time: Duration::from_millis(50),
+        healthy_period: Duration::from_millis(10),
+        ..FlakyTaskLayer::new(3, 1)
+    };
+    layer.run_service().unwrap();
+    assert_eq!(layer.attempts.load(Ordering::SeqCst), 4);
+
+    let health = layer.last_health.lock().unwrap().clone().unwrap();
+    assert_matches!(health.status(), HealthStatus::Ready);
+    let details = health.details().unwrap();
+    assert_eq!(details["restarts"], 0);
+    assert_eq!(details["last_error"], "flaky task failed");
+}
+
+// Tasks with the fail-fast policy should not be restarted.
+#[test]
+fn test_restartable_task_with_fail_fast_policy() {
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let task_attempts = attempts.clone();
+    let app_health = Arc::new(AppHealthCheck::default());
+    let task = RestartableTask::new(RestartPolicy::FailFast, move || FlakyTask {
+        failures: 1,
+        panic: false,
+        run_time: Duration::ZERO,
+        attempts: task_attempts.clone(),
+        app_health: app_health.clone(),
+        last_health: Arc::default(),
+    });
+
+    let (_stop_sender, stop_receiver) = tokio::sync::watch::channel(false);
+    let runtime = Runtime::new().unwrap();
+    let result = runtime.block_on(Box::new(task).run(StopReceiver(stop_receiver)));
+    assert!(result.is_err());
+    assert_eq!(attempts.load(Ordering::SeqCst), 1);
+}
+
+/// Task running until it receives a stop signal.
+#[derive(Debug)]
+struct LongRunningTask {
+    starts: Arc<AtomicUsize>,
+    stops: Arc<AtomicUsize>,
+}
+
+#[async_trait::async_trait]
+impl Task for LongRunningTask {
+    fn id(&self) -> TaskId {
+        "long_running_task".into()
+    }
+
+    async fn run(self: Box<Self>, mut stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.starts.fetch_add(1, Ordering::SeqCst);
+        stop_receiver.0.changed().await?;
+        self.stops.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+async fn wait_for_counter(counter: &AtomicUsize, expected: usize) {
+    while counter.load(Ordering::SeqCst) < expected {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+// Pausing a `RestartableTask` should stop the current task instance, and resuming it should start a new one.
+#[test]
+fn test_pausing_restartable_task() {
+    let starts = Arc::new(AtomicUsize::new(0));
+    let stops = Arc::new(AtomicUsize::new(0));
+    let (task_starts, task_stops) = (starts.clone(), stops.clone());
+    let (pause_sender, pause_receiver) = tokio::sync::watch::channel(false);
+    let task = RestartableTask::new(RestartPolicy::FailFast, move || LongRunningTask {
+        starts: task_starts.clone(),
+        stops: task_stops.clone(),
+    })
+    .with_pause_receiver(pause_receiver);
+
+    let (stop_sender, stop_receiver) = tokio::sync::watch::channel(false);
+    let runtime = Runtime::new().unwrap();
+    runtime.block_on(async {
+        let task_handle = tokio::spawn(Box::new(task).run(StopReceiver(stop_receiver)));
+        wait_for_counter(&starts, 1).await;
+
+        pause_sender.send_replace(true);
+        wait_for_counter(&stops, 1).await;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(starts.load(Ordering::SeqCst), 1);
+
+        pause_sender.send_replace(false);
+        wait_for_counter(&starts, 2).await;
+
+        // The task should exit if the stop signal is received while it's paused.
+        pause_sender.send_replace(true);
+        wait_for_counter(&stops, 2).await;
+        stop_sender.send_replace(true);
+        task_handle.await.unwrap().unwrap();
+    });
+    assert_eq!(starts.load(Ordering::SeqCst), 2);
+}
+
+#[derive(Debug, Clone, Default)]
+struct TestResource;
+
+impl Resource for TestResource {
+    fn name() -> String {
+        "test/resource".into()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct DefaultResource;
+
+impl Resource for DefaultResource {
+    fn name() -> String {
+        "test/default_resource".into()
+    }
+}
+
+#[derive(Debug)]
+struct ProducerLayer(&'static str);
+
+#[derive(Debug, IntoContext)]
+#[context(crate = crate)]
+struct ProducerLayerOutput {
+    resource: TestResource,
+    #[context(task)]
+    task: Option<ErrorTask>,
+    hook: ShutdownHook,
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for ProducerLayer {
+    type Input = ();
+    type Output = ProducerLayerOutput;
+
+    fn layer_name(&self) -> &'static str {
+        self.0
+    }
+
+    async fn wire(self, _input: Self::Input) -> Result<Self::Output, WiringError> {
+        unreachable!("layer should not be wired")
+    }
+}
+
+#[derive(Debug)]
+struct ConsumerLayer;
+
+#[derive(Debug, FromContext)]
+#[context(crate = crate)]
+struct ConsumerLayerInput {
+    _resource: TestResource,
+    _optional_resource: Option<TestResource>,
+    #[context(default)]
+    _default_resource: DefaultResource,
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for ConsumerLayer {
+    type Input = ConsumerLayerInput;
+    type Output = ();
+
+    fn layer_name(&self) -> &'static str {
+        "consumer_layer"
+    }
+
+    async fn wire(self, _input: Self::Input) -> Result<Self::Output, WiringError> {
+        unreachable!("layer should not be wired")
+    }
+}
+
+// The wiring graph should describe layer inputs and outputs without wiring the layers.
+#[test]
+fn test_wiring_graph() {
+    let mut zk_stack_service = ZkStackServiceBuilder::new().unwrap();
+    zk_stack_service
+        .add_layer(ProducerLayer("producer_layer"))
+        .add_layer(ConsumerLayer);
+    let graph = zk_stack_service.wiring_graph();
+    graph.validate().unwrap();
+
+    let [producer, consumer] = graph.layers() else {
+        panic!("unexpected layers: {:?}", graph.layers());
+    };
+    assert_eq!(producer.name, "producer_layer");
+    assert!(producer.inputs.is_empty());
+    assert_eq!(producer.resources.len(), 1);
+    assert_eq!(producer.resources[0].name, "test/resource");
+    assert!(!producer.resources[0].optional);
+    assert_eq!(producer.tasks.len(), 1);
+    assert_eq!(producer.tasks[0].field, "task");
+    assert!(producer.tasks[0].type_name.ends_with("ErrorTask"));
+    assert!(producer.tasks[0].optional);
+    assert_eq!(producer.shutdown_hooks.len(), 1);
+    assert!(!producer.shutdown_hooks[0].optional);
+
+    let requests: Vec<_> = consumer
+        .inputs
+        .iter()
+        .map(|input| (input.name.as_str(), input.request))
+        .collect();
+    assert_eq!(
+        requests,
+        [
+            ("test/resource", ResourceRequest::Required),
+            ("test/resource", ResourceRequest::Optional),
+            ("test/default_resource", ResourceRequest::Default),
+        ]
+    );
+    assert!(consumer.resources.is_empty() && consumer.tasks.is_empty());
+    assert!(consumer.shutdown_hooks.is_empty());
+
+    let json: serde_json::Value =
+        serde_json::from_str(&graph.render(WiringGraphFormat::Json)).unwrap();
+    assert_eq!(json["layers"][0]["name"], "producer_layer");
+    assert_eq!(json["layers"][1]["inputs"][2]["request"], "default");
+
+    let dot = graph.render(WiringGraphFormat::Dot);
+    assert!(dot.starts_with("digraph wiring {"), "{dot}");
+    assert!(
+        dot.contains("resource0 [shape=ellipse, label=\"test/resource\"];"),
+        "{dot}"
+    );
+    assert!(dot.contains("layer0 -> resource0;"), "{dot}");
+    assert!(dot.contains("resource0 -> layer1;"), "{dot}");
+    assert!(
+        dot.contains("layer0 -> layer0_task0 [style=dashed];"),
+        "{dot}"
+    );
+    assert!(dot.contains("layer0 -> layer0_hook0;"), "{dot}");
+}
+
+// Wiring graph validation should detect resources requested before they are provided.
+#[test]
+fn test_wiring_graph_with_lacking_resource() {
+    let mut zk_stack_service = ZkStackServiceBuilder::new().unwrap();
+    zk_stack_service
+        .add_layer(ConsumerLayer)
+        .add_layer(ProducerLayer("producer_layer"));
+    let err = zk_stack_service.wiring_graph().validate().unwrap_err();
+    let ZkStackServiceError::Wiring(errors) = err else {
+        panic!("unexpected error: {err:?}");
+    };
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].0, "consumer_layer");
+    assert_matches!(&errors[0].1, WiringError::ResourceLacking { name, .. } if name == "test/resource");
+}
+
+// Wiring graph validation should detect resources provided multiple times.
+#[test]
+fn test_wiring_graph_with_duplicate_resource() {
+    let mut zk_stack_service = ZkStackServiceBuilder::new().unwrap();
+    zk_stack_service
+        .add_layer(ProducerLayer("producer_layer"))
+        .add_layer(ProducerLayer("other_producer_layer"));
+    let err = zk_stack_service.wiring_graph().validate().unwrap_err();
+    let ZkStackServiceError::Wiring(errors) = err else {
+        panic!("unexpected error: {err:?}");
+    };
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].0, "other_producer_layer");
+    assert_matches!(
+        &errors[0].1,
+        WiringError::ResourceAlreadyProvided { name, .. } if name == "test/resource"
+    );
+}
@@ -0,0 +1,328 @@
+//! Static description of the wiring graph, i.e. resources and tasks consumed and produced by wiring layers.
+
+use std::{
+    any::type_name,
+    collections::{hash_map, HashMap},
+    fmt::{self, Write as _},
+    str::FromStr,
+};
+
+use serde::Serialize;
+
+use super::ZkStackServiceError;
+use crate::{
+    resource::{Resource, ResourceId},
+    task::Task,
+    wiring_layer::WiringError,
+};
+
+/// How a resource is requested by a wiring layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResourceRequest {
+    /// Resource must be provided by one of the preceding layers.
+    Required,
+    /// Resource is used if it's provided by one of the preceding layers.
+    Optional,
+    /// Resource is used if it's provided by one of the preceding layers; otherwise, a default value is inserted.
+    Default,
+}
+
+/// Resource requested by a wiring layer.
+#[derive(Debug, Clone, Serialize)]
+pub struct RequestedResource {
+    #[serde(skip)]
+    pub id: ResourceId,
+    pub name: String,
+    pub request: ResourceRequest,
+}
+
+/// Resource provided by a wiring layer.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProvidedResource {
+    #[serde(skip)]
+    pub id: ResourceId,
+    pub name: String,
+    /// Whether the resource may not be provided depending on the layer configuration.
+    pub optional: bool,
+}
+
+/// Task added by a wiring layer.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProvidedTask {
+    /// Name of the field in the layer output holding the task. Task IDs are only known after wiring,
+    /// so this name is used instead.
+    pub field: &'static str,
+    pub type_name: &'static str,
+    /// Whether the task may not be added depending on the layer configuration.
+    pub optional: bool,
+}
+
+/// Shutdown hook added by a wiring layer. Hook names are only known after wiring, so they are not recorded.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProvidedShutdownHook {
+    /// Whether the hook may not be added depending on the layer configuration.
+    pub optional: bool,
+}
+
+/// Description of resources and tasks consumed and produced by a single wiring layer.
+///
+/// Descriptions are collected via [`FromContext::describe_input()`](super::FromContext::describe_input())
+/// and [`IntoContext::describe_output()`](super::IntoContext::describe_output()), which are implemented
+/// by the corresponding derive macros, so they don't require running the layer.
+#[derive(Debug, Clone, Serialize)]
+pub struct LayerDescription {
+    pub name: &'static str,
+    pub inputs: Vec<RequestedResource>,
+    pub resources: Vec<ProvidedResource>,
+    pub tasks: Vec<ProvidedTask>,
+    pub shutdown_hooks: Vec<ProvidedShutdownHook>,
+    #[serde(skip)]
+    optional_depth: usize,
+}
+
+impl LayerDescription {
+    pub(crate) fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            inputs: vec![],
+            resources: vec![],
+            tasks: vec![],
+            shutdown_hooks: vec![],
+            optional_depth: 0,
+        }
+    }
+
+    /// Records a requested resource. If called within [`Self::optional()`], a required resource is recorded as optional.
+    pub fn request_resource<T: Resource>(&mut self, mut request: ResourceRequest) {
+        if self.optional_depth > 0 && request == ResourceRequest::Required {
+            request = ResourceRequest::Optional;
+        }
+        self.inputs.push(RequestedResource {
+            id: ResourceId::of::<T>(),
+            name: T::name(),
+            request,
+        });
+    }
+
+    /// Records a provided resource.
+    pub fn provide_resource<T: Resource>(&mut self) {
+        self.resources.push(ProvidedResource {
+            id: ResourceId::of::<T>(),
+            name: T::name(),
+            optional: self.optional_depth > 0,
+        });
+    }
+
+    /// Records an added task.
+    pub fn add_task<T: Task>(&mut self, field: &'static str) {
+        self.tasks.push(ProvidedTask {
+            field,
+            type_name: type_name::<T>(),
+            optional: self.optional_depth > 0,
+        });
+    }
+
+    /// Records an added shutdown hook.
+    pub fn add_shutdown_hook(&mut self) {
+        self.shutdown_hooks.push(ProvidedShutdownHook {
+            optional: self.optional_depth > 0,
+        });
+    }
+
+    /// Marks everything recorded by the provided closure as optional.
+    pub fn optional(&mut self, describe: impl FnOnce(&mut Self)) {
+        self.optional_depth += 1;
+        describe(self);
+        self.optional_depth -= 1;
+    }
+}
+
+/// Output format for [`WiringGraph`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WiringGraphFormat {
+    Json,
+    Dot,
+}
+
+impl FromStr for WiringGraphFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Self::Json),
+            "dot" => Ok(Self::Dot),
+            _ => Err(format!(
+                "unknown wiring graph format `{s}`; expected `json` or `dot`"
+            )),
+        }
+    }
+}
+
+/// Wiring graph of the service: resources and tasks consumed and produced by all wiring layers, in the order
+/// the layers are wired.
+///
+/// The graph is built without wiring layers, so it can be used to check the node composition without starting
+/// anything (e.g., to debug missing resources), and to visualize it.
+#[derive(Debug, Clone, Serialize)]
+pub struct WiringGraph {
+    layers: Vec<LayerDescription>,
+}
+
+impl WiringGraph {
+    pub(crate) fn new(layers: Vec<LayerDescription>) -> Self {
+        Self { layers }
+    }
+
+    /// Returns descriptions of all layers in the order of wiring.
+    pub fn layers(&self) -> &[LayerDescription] {
+        &self.layers
+    }
+
+    /// Validates the graph by simulating resource flow between layers.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkStackServiceError::Wiring`] listing all found errors:
+    ///
+    /// - A required resource is not provided by any preceding layer. If the resource is provided optionally,
+    ///   this is not considered an error since the layer configuration is not known statically.
+    /// - A resource is unconditionally provided by a layer after it was unconditionally provided
+    ///   (or inserted with the default value) before.
+    pub fn validate(&self) -> Result<(), ZkStackServiceError> {
+        // Values are `true` if the resource is guaranteed to be provided.
+        let mut available = HashMap::<ResourceId, bool>::new();
+        let mut errors = vec![];
+
+        for layer in &self.layers {
+            for input in &layer.inputs {
+                match input.request {
+                    ResourceRequest::Required if !available.contains_key(&input.id) => {
+                        errors.push((
+                            layer.name.to_owned(),
+                            WiringError::ResourceLacking {
+                                id: input.id.clone(),
+                                name: input.name.clone(),
+                            },
+                        ));
+                    }
+                    ResourceRequest::Default => {
+                        available.insert(input.id.clone(), true);
+                    }
+                    ResourceRequest::Required | ResourceRequest::Optional => { /* do nothing */ }
+                }
+            }
+
+            for resource in &layer.resources {
+                let is_provided = available.get(&resource.id).copied().unwrap_or(false);
+                if is_provided && !resource.optional {
+                    errors.push((
+                        layer.name.to_owned(),
+                        WiringError::ResourceAlreadyProvided {
+                            id: resource.id.clone(),
+                            name: resource.name.clone(),
+                        },
+                    ));
+                }
+                available.insert(resource.id.clone(), is_provided || !resource.optional);
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ZkStackServiceError::Wiring(errors))
+        }
+    }
+
+    /// Renders the graph in the specified format.
+    pub fn render(&self, format: WiringGraphFormat) -> String {
+        match format {
+            WiringGraphFormat::Json => self.to_json(),
+            WiringGraphFormat::Dot => self.to_dot(),
+        }
+    }
+
+    /// Renders the graph as pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("failed serializing wiring graph")
+    }
+
+    /// Renders the graph in the Graphviz DOT format. Layers are represented as boxes, resources as ellipses,
+    /// tasks as diamonds, and shutdown hooks as octagons. Optional dependencies are represented with dashed edges.
+    pub fn to_dot(&self) -> String {
+        self.write_dot(String::new())
+            .expect("writing to `String` never fails")
+    }
+
+    fn write_dot(&self, mut out: String) -> Result<String, fmt::Error> {
+        fn edge_style(optional: bool) -> &'static str {
+            if optional {
+                " [style=dashed]"
+            } else {
+                ""
+            }
+        }
+
+        writeln!(out, "digraph wiring {{")?;
+        writeln!(out, "    rankdir=LR;")?;
+        let mut resource_indices = HashMap::new();
+        let resource_names = self.layers.iter().flat_map(|layer| {
+            let input_names = layer.inputs.iter().map(|input| &input.name);
+            input_names.chain(layer.resources.iter().map(|resource| &resource.name))
+        });
+        for name in resource_names {
+            let idx = resource_indices.len();
+            if let hash_map::Entry::Vacant(entry) = resource_indices.entry(name.as_str()) {
+                entry.insert(idx);
+                writeln!(out, "    resource{idx} [shape=ellipse, label={name:?}];")?;
+            }
+        }
+
+        for (layer_idx, layer) in self.layers.iter().enumerate() {
+            let layer_node = format!("layer{layer_idx}");
+            writeln!(out, "    {layer_node} [shape=box, label={:?}];", layer.name)?;
+            for input in &layer.inputs {
+                let resource_idx = resource_indices[input.name.as_str()];
+                let optional = input.request != ResourceRequest::Required;
+                writeln!(
+                    out,
+                    "    resource{resource_idx} -> {layer_node}{};",
+                    edge_style(optional)
+                )?;
+            }
+            for resource in &layer.resources {
+                let resource_idx = resource_indices[resource.name.as_str()];
+                writeln!(
+                    out,
+                    "    {layer_node} -> resource{resource_idx}{};",
+                    edge_style(resource.optional)
+                )?;
+            }
+            for (task_idx, task) in layer.tasks.iter().enumerate() {
+                let task_node = format!("{layer_node}_task{task_idx}");
+                let label = format!("{} ({})", task.field, task.type_name);
+                writeln!(out, "    {task_node} [shape=diamond, label={label:?}];")?;
+                writeln!(
+                    out,
+                    "    {layer_node} -> {task_node}{};",
+                    edge_style(task.optional)
+                )?;
+            }
+            for (hook_idx, hook) in layer.shutdown_hooks.iter().enumerate() {
+                let hook_node = format!("{layer_node}_hook{hook_idx}");
+                writeln!(
+                    out,
+                    "    {hook_node} [shape=octagon, label=\"shutdown hook\"];"
+                )?;
+                writeln!(
+                    out,
+                    "    {layer_node} -> {hook_node}{};",
+                    edge_style(hook.optional)
+                )?;
+            }
+        }
+        writeln!(out, "}}")?;
+        Ok(out)
+    }
+}
@@ -7,9 +7,13 @@ use std::{
 
 use tokio::sync::Barrier;
 
-pub use self::types::{TaskId, TaskKind};
+pub use self::{
+    restart::{RestartPolicy, RestartableTask},
+    types::{TaskId, TaskKind},
+};
 use crate::service::StopReceiver;
 
+mod restart;
 mod types;
 
 /// A task implementation.
@@ -56,6 +60,12 @@ mod types;
 /// A task that can run without waiting for preconditions and can exit without stopping the service.
 /// Usually such tasks may be used for satisfying a precondition, for example, they can perform the database
 /// setup.
+///
+/// ## Restarts
+///
+/// By default, an error returned by any task brings the whole service down. Non-critical tasks can be wrapped
+/// into [`RestartableTask`] to be restarted on errors according to a [`RestartPolicy`]. A [`RestartableTask`]
+/// can also be paused and resumed at runtime (e.g., via the admin server).
 #[async_trait::async_trait]
 pub trait Task: 'static + Send {
     /// Returns the kind of the task.
@@ -0,0 +1,396 @@
+//! Restart policies for tasks.
+
+use std::{fmt, future, time::Duration};
+
+use tokio::sync::watch;
+use zksync_health_check::{
+    AppHealthCheck, AppHealthCheckError, Health, HealthStatus, HealthUpdater, ReactiveHealthCheck,
+};
+use zksync_utils::panic_extractor::try_extract_panic_message;
+
+use super::{Task, TaskId, TaskKind};
+use crate::service::StopReceiver;
+
+/// Policy determining what the service does when a task returns an error or panics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RestartPolicy {
+    /// The error is propagated to the service, which shuts down the node. This is the default behavior
+    /// for all tasks, and it must be used for tasks critical for the node operation (e.g., the state keeper or consensus).
+    #[default]
+    FailFast,
+    /// The task is restarted with an exponentially growing delay. Once the number of restarts exceeds `max_restarts`,
+    /// the error is propagated to the service. If a restarted task instance runs without errors for `healthy_period`,
+    /// the task is considered recovered, and the restart counter (and thus the backoff) is reset.
+    ExponentialBackoff {
+        initial_backoff: Duration,
+        max_backoff: Duration,
+        max_restarts: usize,
+        healthy_period: Duration,
+    },
+}
+
+impl RestartPolicy {
+    const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+    const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(60);
+    const DEFAULT_HEALTHY_PERIOD: Duration = Duration::from_secs(300);
+
+    /// Creates a policy restarting a task with the default backoff (starting from 1s and capped at 1 min).
+    /// The restart counter is reset once the task runs without errors for 5 min.
+    pub const fn exponential_backoff(max_restarts: usize) -> Self {
+        Self::ExponentialBackoff {
+            initial_backoff: Self::DEFAULT_INITIAL_BACKOFF,
+            max_backoff: Self::DEFAULT_MAX_BACKOFF,
+            max_restarts,
+            healthy_period: Self::DEFAULT_HEALTHY_PERIOD,
+        }
+    }
+
+    /// Sets backoff bounds for the policy. No-op for [`Self::FailFast`].
+    #[must_use]
+    pub fn with_backoff(self, initial: Duration, max: Duration) -> Self {
+        match self {
+            Self::FailFast => self,
+            Self::ExponentialBackoff {
+                max_restarts,
+                healthy_period,
+                ..
+            } => Self::ExponentialBackoff {
+                initial_backoff: initial,
+                max_backoff: max.max(initial),
+                max_restarts,
+                healthy_period,
+            },
+        }
+    }
+
+    /// Sets the period after which a restarted task running without errors is considered recovered.
+    /// No-op for [`Self::FailFast`].
+    #[must_use]
+    pub fn with_healthy_period(self, period: Duration) -> Self {
+        match self {
+            Self::FailFast => self,
+            Self::ExponentialBackoff {
+                initial_backoff,
+                max_backoff,
+                max_restarts,
+                ..
+            } => Self::ExponentialBackoff {
+                initial_backoff,
+                max_backoff,
+                max_restarts,
+                healthy_period: period,
+            },
+        }
+    }
+
+    fn max_restarts(&self) -> usize {
+        match self {
+            Self::FailFast => 0,
+            Self::ExponentialBackoff { max_restarts, .. } => *max_restarts,
+        }
+    }
+
+    fn healthy_period(&self) -> Option<Duration> {
+        match self {
+            Self::FailFast => None,
+            Self::ExponentialBackoff { healthy_period, .. } => Some(*healthy_period),
+        }
+    }
+
+    /// Returns the delay before the restart with the specified 0-based index.
+    fn backoff(&self, restart_idx: usize) -> Duration {
+        match self {
+            Self::FailFast => Duration::ZERO,
+            Self::ExponentialBackoff {
+                initial_backoff,
+                max_backoff,
+                ..
+            } => {
+                let multiplier = 1_u32.checked_shl(restart_idx as u32).unwrap_or(u32::MAX);
+                initial_backoff
+                    .checked_mul(multiplier)
+                    .map_or(*max_backoff, |backoff| backoff.min(*max_backoff))
+            }
+        }
+    }
+}
+
+type TaskFactory = Box<dyn Fn() -> Box<dyn Task> + Send + Sync>;
+
+/// Task wrapper restarting the wrapped task according to a [`RestartPolicy`].
+///
+/// Since [`Task::run()`] consumes the task, the wrapper is created from a factory producing task instances.
+/// The kind and ID of the wrapper are the same as for the produced tasks. Returning from the wrapped task
+/// (as opposed to returning an error) is not considered a failure and is propagated to the service as usual.
+/// Errors returned after the stop signal was received are propagated as well.
+///
+/// Restarts are reported via a health check component if it's registered using [`Self::with_health_check()`].
+/// The component becomes [`HealthStatus::Affected`] after the first restart, and returns to [`HealthStatus::Ready`]
+/// once the task recovers (i.e., runs without errors for the healthy period of the policy).
+///
+/// The task can be paused using [`Self::with_pause_receiver()`]. Pausing stops the current task instance
+/// by sending a stop signal to it; resuming creates a new instance. Neither counts as a restart.
+pub struct RestartableTask {
+    id: TaskId,
+    kind: TaskKind,
+    policy: RestartPolicy,
+    first_instance: Option<Box<dyn Task>>,
+    factory: TaskFactory,
+    health_updater: Option<HealthUpdater>,
+    pause_receiver: Option<watch::Receiver<bool>>,
+}
+
+impl fmt::Debug for RestartableTask {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("RestartableTask")
+            .field("id", &self.id)
+            .field("kind", &self.kind)
+            .field("policy", &self.policy)
+            .field("health_updater", &self.health_updater)
+            .field("pause_receiver", &self.pause_receiver)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RestartableTask {
+    /// Creates a restartable task using the provided factory. The factory is called immediately to create the first task instance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the task is a [`TaskKind::Precondition`]; preconditions cannot be restarted.
+    pub fn new<T: Task>(
+        policy: RestartPolicy,
+        factory: impl Fn() -> T + Send + Sync + 'static,
+    ) -> Self {
+        let first_instance = factory();
+        let kind = first_instance.kind();
+        assert!(
+            !matches!(kind, TaskKind::Precondition),
+            "preconditions cannot be restarted"
+        );
+        Self {
+            id: first_instance.id(),
+            kind,
+            policy,
+            first_instance: Some(Box::new(first_instance)),
+            factory: Box::new(move || Box::new(factory())),
+            health_updater: None,
+            pause_receiver: None,
+        }
+    }
+
+    /// Allows pausing the task. The task is paused while the receiver holds `true`, and is resumed once it holds `false`.
+    /// If the sender is dropped, the task is no longer paused.
+    #[must_use]
+    pub fn with_pause_receiver(mut self, pause_receiver: watch::Receiver<bool>) -> Self {
+        self.pause_receiver = Some(pause_receiver);
+        self
+    }
+
+    /// Registers a health check component with the specified name reporting task restarts.
+    pub fn with_health_check(
+        mut self,
+        app_health: &AppHealthCheck,
+        component_name: &'static str,
+    ) -> Result<Self, AppHealthCheckError> {
+        let (health_check, health_updater) = ReactiveHealthCheck::new(component_name);
+        app_health.insert_component(health_check)?;
+        self.health_updater = Some(health_updater);
+        Ok(self)
+    }
+
+    fn update_health(&self, status: HealthStatus, restarts: usize, last_error: Option<&str>) {
+        if let Some(updater) = &self.health_updater {
+            let health = Health::from(status).with_details(serde_json::json!({
+                "restarts": restarts,
+                "max_restarts": self.policy.max_restarts(),
+                "last_error": last_error,
+                "paused": self.is_paused(),
+            }));
+            updater.update(health);
+        }
+    }
+
+    fn is_paused(&self) -> bool {
+        self.pause_receiver
+            .as_ref()
+            .map_or(false, |receiver| *receiver.borrow())
+    }
+
+    /// Waits until the task is resumed. Returns `false` if the stop signal was received while waiting.
+    async fn wait_for_resume(&mut self, stop_receiver: &mut StopReceiver) -> bool {
+        let Some(pause_receiver) = &mut self.pause_receiver else {
+            return true;
+        };
+        tokio::select! {
+            // An error means that the pause sender was dropped; in this case, the task shouldn't remain paused.
+            _ = pause_receiver.wait_for(|&paused| !paused) => true,
+            _ = stop_receiver.0.wait_for(|&stop| stop) => false,
+        }
+    }
+
+    /// Waits until either the stop signal is received or the task is paused.
+    async fn wait_for_stop_or_pause(
+        stop_receiver: &mut StopReceiver,
+        pause_receiver: Option<&mut watch::Receiver<bool>>,
+    ) {
+        let pause = async {
+            if let Some(receiver) = pause_receiver {
+                if receiver.wait_for(|&paused| paused).await.is_ok() {
+                    return;
+                }
+            }
+            // The pause sender is dropped or missing, so the task cannot be paused.
+            future::pending().await
+        };
+        tokio::select! {
+            _ = stop_receiver.0.wait_for(|&stop| stop) => {}
+            () = pause => {}
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Task for RestartableTask {
+    fn kind(&self) -> TaskKind {
+        self.kind
+    }
+
+    fn id(&self) -> TaskId {
+        self.id.clone()
+    }
+
+    async fn run(self: Box<Self>, mut stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        let mut this = *self;
+        let mut next_instance = this.first_instance.take();
+        let mut restarts = 0;
+        let mut last_error: Option<String> = None;
+
+        loop {
+            if this.is_paused() {
+                tracing::info!("Task {} is paused", this.id);
+                this.update_health(HealthStatus::Affected, restarts, last_error.as_deref());
+                if !this.wait_for_resume(&mut stop_receiver).await {
+                    tracing::info!("Stop signal received while task {} is paused", this.id);
+                    return Ok(());
+                }
+                tracing::info!("Task {} is resumed", this.id);
+            }
+
+            let status = if restarts == 0 {
+                HealthStatus::Ready
+            } else {
+                HealthStatus::Affected
+            };
+            this.update_health(status, restarts, last_error.as_deref());
+
+            let task = next_instance.take().unwrap_or_else(|| (this.factory)());
+            // The task instance gets a separate stop signal, so that it can be stopped when the task is paused.
+            let (instance_stop_sender, instance_stop_receiver) = watch::channel(false);
+            // Spawn the task as a separate Tokio task, so that panics can be handled in the same way as errors.
+            let handle = tokio::runtime::Handle::current();
+            let mut instance = handle.spawn(task.run(StopReceiver(instance_stop_receiver)));
+            let mut recovery_deadline = this
+                .policy
+                .healthy_period()
+                .filter(|_| restarts > 0)
+                .map(|period| tokio::time::Instant::now() + period);
+            let instance_result = loop {
+                let recovery = async move {
+                    match recovery_deadline {
+                        Some(deadline) => tokio::time::sleep_until(deadline).await,
+                        None => future::pending().await,
+                    }
+                };
+                tokio::select! {
+                    res = &mut instance => break Some(res),
+                    () = Self::wait_for_stop_or_pause(&mut stop_receiver, this.pause_receiver.as_mut()) => break None,
+                    () = recovery => {
+                        tracing::info!(
+                            "Task {} has recovered after {restarts} restarts; resetting restart counter",
+                            this.id
+                        );
+                        restarts = 0;
+                        recovery_deadline = None;
+                        this.update_health(HealthStatus::Ready, restarts, last_error.as_deref());
+                    }
+                }
+            };
+            let (instance_result, is_paused) = match instance_result {
+                Some(res) => (res, false),
+                None => {
+                    let is_paused = this.is_paused() && !*stop_receiver.0.borrow();
+                    instance_stop_sender.send_replace(true);
+                    (instance.await, is_paused)
+                }
+            };
+
+            if is_paused {
+                match instance_result {
+                    Ok(Ok(())) => {}
+                    Ok(Err(err)) => {
+                        tracing::warn!("Task {} failed while being paused: {err:?}", this.id);
+                    }
+                    Err(panic_err) => {
+                        let panic_msg = try_extract_panic_message(panic_err);
+                        tracing::warn!("Task {} panicked while being paused: {panic_msg}", this.id);
+                    }
+                }
+                continue;
+            }
+
+            let err = match instance_result {
+                Ok(Ok(())) => {
+                    if this.kind.is_oneshot() {
+                        // A completed oneshot task shouldn't be reported as shut down, since this would make
+                        // the entire app unhealthy.
+                        if let Some(updater) = this.health_updater.take() {
+                            updater.freeze();
+                        }
+                    }
+                    return Ok(());
+                }
+                Ok(Err(err)) => err,
+                Err(panic_err) => {
+                    let panic_msg = try_extract_panic_message(panic_err);
+                    anyhow::anyhow!("Task {} panicked: {panic_msg}", this.id)
+                }
+            };
+
+            if *stop_receiver.0.borrow() {
+                return Err(err);
+            }
+            if restarts >= this.policy.max_restarts() {
+                if this.policy != RestartPolicy::FailFast {
+                    tracing::error!(
+                        "Task {} failed after {restarts} restarts; not restarting it anymore",
+                        this.id
+                    );
+                }
+                return Err(err);
+            }
+
+            let backoff = this.policy.backoff(restarts);
+            restarts += 1;
+            tracing::warn!(
+                "Task {} failed: {err:?}; restarting it in {backoff:?} (restart {restarts}/{})",
+                this.id,
+                this.policy.max_restarts()
+            );
+            last_error = Some(format!("{err:#}"));
+            this.update_health(HealthStatus::Affected, restarts, last_error.as_deref());
+
+            if tokio::time::timeout(backoff, stop_receiver.0.changed())
+                .await
+                .is_ok()
+            {
+                tracing::info!(
+                    "Stop signal received while waiting to restart task {}",
+                    this.id
+                );
+                return Ok(());
+            }
+        }
+    }
+}
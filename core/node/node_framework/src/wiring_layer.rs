@@ -2,7 +2,11 @@ use std::fmt;
 
 use tokio::runtime;
 
-use crate::{resource::ResourceId, service::ServiceContext, FromContext, IntoContext};
+use crate::{
+    resource::ResourceId,
+    service::{LayerDescription, ServiceContext},
+    FromContext, IntoContext,
+};
 
 /// An envelope for the wiring layer function.
 /// Since `WiringLayer` has associated types, we cannot easily erase the types via `dyn WiringLayer`,
@@ -39,6 +43,14 @@ pub trait WiringLayer: 'static + Send + Sync {
 }
 
 pub(crate) trait WiringLayerExt: WiringLayer {
+    /// Describes resources and tasks consumed and produced by the layer, based on its input and output types.
+    fn describe(&self) -> LayerDescription {
+        let mut description = LayerDescription::new(self.layer_name());
+        Self::Input::describe_input(&mut description);
+        Self::Output::describe_output(&mut description);
+        description
+    }
+
     /// Hires the actual type of the wiring layer into the closure, so that rest of application
     /// doesn't have to know it.
     fn into_wire_fn(self) -> WireFn
@@ -0,0 +1,134 @@
+use std::{sync::Arc, time::Instant};
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_eth_client::clients::{DynClient, L1};
+use zksync_node_l1_recovery::{BlobApiClient, L1Recovery, L1RecoveryConfig};
+use zksync_shared_metrics::{SnapshotRecoveryStage, APP_METRICS};
+use zksync_snapshots_applier::{
+    RecoveryCompletionStatus, SnapshotsApplierMainNodeClient, SnapshotsApplierTask,
+};
+use zksync_types::{snapshots::SnapshotRecoveryStatus, url::SensitiveUrl, L2ChainId};
+use zksync_web3_decl::client::L2;
+
+use crate::InitializeStorage;
+
+/// Recovers external node storage from data published on L1. Unlike snapshot recovery, the recovered state
+/// doesn't need to be trusted; the main node is only used to fetch genesis params (which are verified against L1)
+/// and token metadata.
+#[derive(Debug)]
+pub struct ExternalNodeL1Recovery {
+    pub l2_chain_id: L2ChainId,
+    pub main_node_client: Box<DynClient<L2>>,
+    pub l1_client: Box<DynClient<L1>>,
+    pub pool: ConnectionPool<Core>,
+    pub recovery_config: L1RecoveryConfig,
+    /// URL of the blob archive used to fetch pubdata published in blobs. Required if the chain uses blobs for DA.
+    pub blob_api_url: Option<SensitiveUrl>,
+}
+
+impl ExternalNodeL1Recovery {
+    async fn recover_tokens(&self, status: &SnapshotRecoveryStatus) -> anyhow::Result<()> {
+        let mut storage = self.pool.connection_tagged("en").await?;
+        let token_count = storage
+            .tokens_dal()
+            .get_all_l2_token_addresses()
+            .await?
+            .len();
+        if token_count > 0 {
+            tracing::info!(
+                "{token_count} tokens are already present in DB; skipping token recovery"
+            );
+            return Ok(());
+        }
+
+        let tokens = self
+            .main_node_client
+            .fetch_tokens(status.l2_block_number)
+            .await?;
+        let l2_addresses = tokens.iter().map(|token| token.l2_address);
+        let deployed_contracts = storage
+            .storage_logs_dal()
+            .filter_deployed_contracts(l2_addresses, Some(status.l2_block_number))
+            .await?;
+        let bogus_tokens: Vec<_> = tokens
+            .iter()
+            .filter(|token| {
+                // L2 ether doesn't have a deployed contract
+                !token.l2_address.is_zero() && !deployed_contracts.contains_key(&token.l2_address)
+            })
+            .collect();
+        anyhow::ensure!(
+            bogus_tokens.is_empty(),
+            "Main node returned tokens that are not deployed on L2: {bogus_tokens:?}"
+        );
+        storage.tokens_dal().add_tokens(&tokens).await?;
+        tracing::info!("Recovered {} tokens from main node", tokens.len());
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl InitializeStorage for ExternalNodeL1Recovery {
+    async fn initialize_storage(&self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        tracing::warn!("Proceeding with recovery from L1 data. This is an experimental feature; use at your own risk");
+
+        let mut storage = self.pool.connection_tagged("en").await?;
+        let status = storage
+            .snapshot_recovery_dal()
+            .get_applied_snapshot_status()
+            .await?;
+        drop(storage);
+
+        let status = if let Some(status) = status {
+            tracing::info!("Storage is already recovered: {status:?}");
+            status
+        } else {
+            let main_node_client = self.main_node_client.clone().for_component("l1_recovery");
+            let genesis_params = zksync_node_sync::genesis::create_genesis_params(
+                &main_node_client,
+                self.l2_chain_id,
+            )
+            .await
+            .context("failed fetching genesis params from main node")?;
+            let mut recovery = L1Recovery::new(
+                self.recovery_config.clone(),
+                self.l1_client.clone(),
+                self.pool.clone(),
+                genesis_params,
+            );
+            if let Some(blob_api_url) = &self.blob_api_url {
+                recovery =
+                    recovery.with_blob_provider(Arc::new(BlobApiClient::new(blob_api_url.clone())));
+            } else {
+                tracing::info!("Blob archive URL is not configured; recovery will fail if the chain published pubdata in blobs");
+            }
+
+            let recovery_started_at = Instant::now();
+            let Some(status) = recovery
+                .run(stop_receiver)
+                .await
+                .context("recovery from L1 failed")?
+            else {
+                // The task was canceled; all the other tasks are canceled as well.
+                return Ok(());
+            };
+            let latency = recovery_started_at.elapsed();
+            APP_METRICS.snapshot_recovery_latency[&SnapshotRecoveryStage::Postgres].set(latency);
+            tracing::info!("Recovered Postgres from L1 in {latency:?}");
+            status
+        };
+        self.recover_tokens(&status).await
+    }
+
+    async fn is_initialized(&self) -> anyhow::Result<bool> {
+        let mut storage = self.pool.connection_tagged("en").await?;
+        let completed = matches!(
+            SnapshotsApplierTask::is_recovery_completed(&mut storage, &self.main_node_client)
+                .await?,
+            RecoveryCompletionStatus::Completed
+        );
+        Ok(completed)
+    }
+}
@@ -7,7 +7,7 @@ use zksync_health_check::AppHealthCheck;
 use zksync_object_store::ObjectStoreFactory;
 use zksync_shared_metrics::{SnapshotRecoveryStage, APP_METRICS};
 use zksync_snapshots_applier::{
-    RecoveryCompletionStatus, SnapshotsApplierConfig, SnapshotsApplierTask,
+    RecoveryCompletionStatus, SnapshotArchiveClient, SnapshotsApplierConfig, SnapshotsApplierTask,
 };
 use zksync_web3_decl::client::{DynClient, L2};
 
@@ -36,24 +36,24 @@ impl InitializeStorage for ExternalNodeSnapshotRecovery {
             );
         }
 
-        let object_store_config =
-            self.recovery_config.object_store_config.clone().context(
-                "Snapshot object store must be presented if snapshot recovery is activated",
-            )?;
-        let object_store = ObjectStoreFactory::new(object_store_config)
-            .create_store()
-            .await?;
-
         let config = SnapshotsApplierConfig {
             max_concurrency: self.max_concurrency,
             ..SnapshotsApplierConfig::default()
         };
-        let mut snapshots_applier_task = SnapshotsApplierTask::new(
-            config,
-            self.pool.clone(),
-            Box::new(self.client.clone().for_component("snapshot_recovery")),
-            object_store,
-        );
+        let mut snapshots_applier_task = if let Some(archive_path) =
+            &self.recovery_config.archive_path
+        {
+            SnapshotsApplierTask::from_archive(config, self.pool.clone(), archive_path).await?
+        } else {
+            let main_node_client = Box::new(self.client.clone().for_component("snapshot_recovery"));
+            let object_store_config = self.recovery_config.object_store_config.clone().context(
+                "Snapshot object store must be presented if snapshot recovery is activated",
+            )?;
+            let object_store = ObjectStoreFactory::new(object_store_config)
+                .create_store()
+                .await?;
+            SnapshotsApplierTask::new(config, self.pool.clone(), main_node_client, object_store)
+        };
         if let Some(snapshot_l1_batch) = self.recovery_config.snapshot_l1_batch_override {
             tracing::info!(
                 "Using a specific snapshot with L1 batch #{snapshot_l1_batch}; this may not work \
@@ -86,10 +86,14 @@ impl InitializeStorage for ExternalNodeSnapshotRecovery {
 
     async fn is_initialized(&self) -> anyhow::Result<bool> {
         let mut storage = self.pool.connection_tagged("en").await?;
-        let completed = matches!(
-            SnapshotsApplierTask::is_recovery_completed(&mut storage, &self.client).await?,
-            RecoveryCompletionStatus::Completed
-        );
+        // If recovering from an archive, the recovery data (specifically, tokens) must be checked against the archive.
+        let status = if let Some(archive_path) = &self.recovery_config.archive_path {
+            let client = SnapshotArchiveClient::open(archive_path).await?;
+            SnapshotsApplierTask::is_recovery_completed(&mut storage, &client).await?
+        } else {
+            SnapshotsApplierTask::is_recovery_completed(&mut storage, &self.client).await?
+        };
+        let completed = matches!(status, RecoveryCompletionStatus::Completed);
         Ok(completed)
     }
 }
@@ -131,6 +135,7 @@ mod tests {
                 snapshot_l1_batch_override: None,
                 drop_storage_key_preimages: false,
                 object_store_config: None,
+                archive_path: None,
             },
             app_health,
         };
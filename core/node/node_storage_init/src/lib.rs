@@ -1,10 +1,12 @@
-use std::{future::Future, sync::Arc, time::Duration};
+use std::{future::Future, path::PathBuf, sync::Arc, time::Duration};
 
 use tokio::sync::watch;
 use zksync_config::ObjectStoreConfig;
 use zksync_dal::{ConnectionPool, Core, CoreDal as _};
 use zksync_types::L1BatchNumber;
 
+pub use zksync_node_l1_recovery::L1RecoveryConfig;
+
 pub use crate::traits::{InitializeStorage, RevertStorage};
 
 pub mod external_node;
@@ -17,6 +19,8 @@ pub struct SnapshotRecoveryConfig {
     pub snapshot_l1_batch_override: Option<L1BatchNumber>,
     pub drop_storage_key_preimages: bool,
     pub object_store_config: Option<ObjectStoreConfig>,
+    /// Path to a local snapshot archive. If specified, takes precedence over `object_store_config`.
+    pub archive_path: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone, Copy)]
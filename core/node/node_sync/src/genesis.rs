@@ -30,7 +30,8 @@ pub async fn perform_genesis_if_needed(
     Ok(())
 }
 
-async fn create_genesis_params(
+/// Fetches genesis params from the main node. The returned params are not verified.
+pub async fn create_genesis_params(
     client: &dyn MainNodeClient,
     zksync_chain_id: L2ChainId,
 ) -> anyhow::Result<GenesisParams> {
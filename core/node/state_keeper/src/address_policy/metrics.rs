@@ -0,0 +1,43 @@
+//! Metrics for the address policy.
+
+use vise::{Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, LabeledFamily, Metrics};
+
+/// Stage at which a transaction was checked against the address policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "stage", rename_all = "snake_case")]
+pub enum AddressPolicyStage {
+    /// Transaction submission via the API server.
+    Api,
+    /// Transaction execution in the state keeper.
+    StateKeeper,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "list", rename_all = "snake_case")]
+pub(super) enum AddressPolicyListKind {
+    Allowlist,
+    Denylist,
+    OnchainDenylist,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "source", rename_all = "snake_case")]
+pub(super) enum AddressPolicySource {
+    File,
+    Onchain,
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_address_policy")]
+pub(super) struct AddressPolicyMetrics {
+    /// Number of transactions rejected by the address policy.
+    #[metrics(labels = ["stage", "reason"])]
+    pub rejected_txs: LabeledFamily<(AddressPolicyStage, &'static str), Counter, 2>,
+    /// Number of addresses in each of the policy lists.
+    pub list_size: Family<AddressPolicyListKind, Gauge<usize>>,
+    /// Number of errors reloading the policy from a specific source.
+    pub reload_errors: Family<AddressPolicySource, Counter>,
+}
+
+#[vise::register]
+pub(super) static ADDRESS_POLICY_METRICS: vise::Global<AddressPolicyMetrics> = vise::Global::new();
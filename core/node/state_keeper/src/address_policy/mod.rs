@@ -0,0 +1,291 @@
+//! Address-based policy restricting the transactions that can be included by the sequencer.
+//!
+//! The policy is checked twice: when a transaction is submitted via the API server (so that the user gets
+//! an immediate error), and by [`MempoolIO`](crate::MempoolIO) right before a transaction is executed.
+//! The second check is a final guard covering transactions inserted into the mempool before the policy
+//! was updated. L1 transactions are never checked since they cannot be censored by the sequencer.
+
+use std::{
+    collections::HashSet,
+    sync::{Arc, RwLock},
+    time::SystemTime,
+};
+
+use anyhow::Context as _;
+use serde::Deserialize;
+use tokio::sync::watch;
+use zksync_config::configs::AddressPolicyConfig;
+use zksync_eth_client::{
+    clients::{DynClient, L1},
+    CallFunctionArgs,
+};
+use zksync_types::{ethabi, Address, ExecuteTransactionCommon, Transaction, H256};
+
+pub use self::metrics::AddressPolicyStage;
+use self::metrics::{AddressPolicyListKind, AddressPolicySource, ADDRESS_POLICY_METRICS};
+
+mod metrics;
+#[cfg(test)]
+mod tests;
+
+/// ABI of the L1 contract providing the on-chain denylist.
+const ONCHAIN_DENYLIST_ABI: &str = r#"[{
+    "type": "function",
+    "name": "getDeniedAddresses",
+    "inputs": [],
+    "outputs": [{ "name": "", "type": "address[]", "internalType": "address[]" }],
+    "stateMutability": "view"
+}]"#;
+
+/// Static part of the address policy, normally loaded from a JSON file.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AddressPolicyList {
+    /// If specified, only transactions initiated by one of these accounts are accepted.
+    #[serde(default)]
+    pub allowlist: Option<HashSet<Address>>,
+    /// Transactions initiated by, sent to or paid for by one of these accounts are rejected.
+    #[serde(default)]
+    pub denylist: HashSet<Address>,
+}
+
+/// Reason for a transaction to be rejected by [`AddressPolicy`].
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum AddressPolicyViolation {
+    #[error("initiator {0:?} is not allowed to send transactions")]
+    InitiatorNotAllowed(Address),
+    #[error("initiator {0:?} is denied")]
+    DeniedInitiator(Address),
+    #[error("recipient {0:?} is denied")]
+    DeniedRecipient(Address),
+    #[error("paymaster {0:?} is denied")]
+    DeniedPaymaster(Address),
+}
+
+impl AddressPolicyViolation {
+    pub fn as_metric_label(&self) -> &'static str {
+        match self {
+            Self::InitiatorNotAllowed(_) => "initiator_not_allowed",
+            Self::DeniedInitiator(_) => "denied_initiator",
+            Self::DeniedRecipient(_) => "denied_recipient",
+            Self::DeniedPaymaster(_) => "denied_paymaster",
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct AddressPolicyState {
+    static_list: AddressPolicyList,
+    onchain_denylist: HashSet<Address>,
+}
+
+impl AddressPolicyState {
+    fn is_denied(&self, address: &Address) -> bool {
+        self.static_list.denylist.contains(address) || self.onchain_denylist.contains(address)
+    }
+}
+
+/// Shared handle to the address policy. Cloning the handle is cheap; all clones observe policy updates
+/// performed by [`AddressPolicyUpdater`].
+#[derive(Debug, Clone, Default)]
+pub struct AddressPolicy(Arc<RwLock<AddressPolicyState>>);
+
+impl AddressPolicy {
+    /// Creates a policy with the specified static lists and an empty on-chain denylist.
+    pub fn new(static_list: AddressPolicyList) -> Self {
+        let this = Self::default();
+        this.set_static_list(static_list);
+        this
+    }
+
+    fn set_static_list(&self, list: AddressPolicyList) {
+        let allowlist_len = list.allowlist.as_ref().map_or(0, HashSet::len);
+        ADDRESS_POLICY_METRICS.list_size[&AddressPolicyListKind::Allowlist].set(allowlist_len);
+        ADDRESS_POLICY_METRICS.list_size[&AddressPolicyListKind::Denylist].set(list.denylist.len());
+        self.0
+            .write()
+            .expect("address policy is poisoned")
+            .static_list = list;
+    }
+
+    fn set_onchain_denylist(&self, denylist: HashSet<Address>) {
+        ADDRESS_POLICY_METRICS.list_size[&AddressPolicyListKind::OnchainDenylist]
+            .set(denylist.len());
+        self.0
+            .write()
+            .expect("address policy is poisoned")
+            .onchain_denylist = denylist;
+    }
+
+    /// Checks addresses associated with a transaction against the policy.
+    pub fn check_addresses(
+        &self,
+        initiator: Address,
+        recipient: Address,
+        paymaster: Option<Address>,
+    ) -> Result<(), AddressPolicyViolation> {
+        let state = self.0.read().expect("address policy is poisoned");
+        if let Some(allowlist) = &state.static_list.allowlist {
+            if !allowlist.contains(&initiator) {
+                return Err(AddressPolicyViolation::InitiatorNotAllowed(initiator));
+            }
+        }
+        if state.is_denied(&initiator) {
+            return Err(AddressPolicyViolation::DeniedInitiator(initiator));
+        }
+        if state.is_denied(&recipient) {
+            return Err(AddressPolicyViolation::DeniedRecipient(recipient));
+        }
+        match paymaster {
+            Some(paymaster) if state.is_denied(&paymaster) => {
+                Err(AddressPolicyViolation::DeniedPaymaster(paymaster))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks a transaction against the policy. L1 and upgrade transactions always pass the check.
+    pub fn check_tx(&self, tx: &Transaction) -> Result<(), AddressPolicyViolation> {
+        let ExecuteTransactionCommon::L2(common_data) = &tx.common_data else {
+            return Ok(());
+        };
+        let paymaster = common_data.paymaster_params.paymaster;
+        let paymaster = (paymaster != Address::zero()).then_some(paymaster);
+        self.check_addresses(
+            common_data.initiator_address,
+            tx.recipient_account(),
+            paymaster,
+        )
+    }
+
+    /// Reports a transaction rejected by the policy to metrics and the audit log.
+    pub fn report_rejection(
+        stage: AddressPolicyStage,
+        tx_hash: H256,
+        violation: &AddressPolicyViolation,
+    ) {
+        ADDRESS_POLICY_METRICS.rejected_txs[&(stage, violation.as_metric_label())].inc();
+        tracing::warn!(
+            tx.hash = ?tx_hash,
+            ?stage,
+            "Transaction {tx_hash:?} rejected by address policy: {violation}"
+        );
+    }
+}
+
+/// Task periodically reloading [`AddressPolicy`] from the policy file and / or the on-chain denylist.
+#[derive(Debug)]
+pub struct AddressPolicyUpdater {
+    policy: AddressPolicy,
+    config: AddressPolicyConfig,
+    eth_client: Option<Box<DynClient<L1>>>,
+    onchain_denylist_abi: ethabi::Contract,
+    policy_file_modified_at: Option<SystemTime>,
+}
+
+impl AddressPolicyUpdater {
+    /// Creates a new updater. `eth_client` must be provided if the on-chain denylist is configured.
+    pub fn new(
+        policy: AddressPolicy,
+        config: AddressPolicyConfig,
+        eth_client: Option<Box<DynClient<L1>>>,
+    ) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            config.onchain_denylist_addr.is_none() || eth_client.is_some(),
+            "L1 client is required to load the on-chain denylist"
+        );
+        let onchain_denylist_abi = ethabi::Contract::load(ONCHAIN_DENYLIST_ABI.as_bytes())
+            .context("invalid on-chain denylist ABI")?;
+        Ok(Self {
+            policy,
+            config,
+            eth_client: eth_client.map(|client| client.for_component("address_policy")),
+            onchain_denylist_abi,
+            policy_file_modified_at: None,
+        })
+    }
+
+    /// Loads the policy once. This should be called before the node starts accepting transactions
+    /// so that the policy is never empty when it's first applied.
+    pub async fn load(&mut self) -> anyhow::Result<()> {
+        self.reload_policy_file().await?;
+        self.reload_onchain_denylist().await
+    }
+
+    async fn reload_policy_file(&mut self) -> anyhow::Result<()> {
+        let Some(path) = &self.config.policy_file_path else {
+            return Ok(());
+        };
+        let metadata = tokio::fs::metadata(path)
+            .await
+            .with_context(|| format!("failed reading metadata for address policy file `{path}`"))?;
+        let modified_at = metadata.modified().ok();
+        if modified_at.is_some() && modified_at == self.policy_file_modified_at {
+            return Ok(()); // The file wasn't changed since the last reload.
+        }
+
+        let contents = tokio::fs::read(path)
+            .await
+            .with_context(|| format!("failed reading address policy file `{path}`"))?;
+        let list: AddressPolicyList = serde_json::from_slice(&contents)
+            .with_context(|| format!("address policy file `{path}` is malformed"))?;
+        tracing::info!(
+            "Loaded address policy from `{path}`: allowlist has {} addresses, denylist has {} addresses",
+            list.allowlist.as_ref().map_or(0, HashSet::len),
+            list.denylist.len()
+        );
+        self.policy.set_static_list(list);
+        self.policy_file_modified_at = modified_at;
+        Ok(())
+    }
+
+    async fn reload_onchain_denylist(&self) -> anyhow::Result<()> {
+        let (Some(contract_address), Some(eth_client)) =
+            (self.config.onchain_denylist_addr, &self.eth_client)
+        else {
+            return Ok(());
+        };
+
+        let tokens: Vec<ethabi::Token> = CallFunctionArgs::new("getDeniedAddresses", ())
+            .for_contract(contract_address, &self.onchain_denylist_abi)
+            .call(eth_client.as_ref())
+            .await
+            .context("failed loading on-chain denylist")?;
+        let denylist = tokens
+            .into_iter()
+            .map(|token| {
+                token
+                    .into_address()
+                    .context("denylist entry is not an address")
+            })
+            .collect::<anyhow::Result<HashSet<_>>>()?;
+        tracing::debug!(
+            "Loaded on-chain denylist with {} addresses from {contract_address:?}",
+            denylist.len()
+        );
+        self.policy.set_onchain_denylist(denylist);
+        Ok(())
+    }
+
+    pub async fn run(mut self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let reload_interval = self.config.reload_interval();
+        while !*stop_receiver.borrow_and_update() {
+            // Reload errors are not fatal: the previously loaded policy remains in effect.
+            if let Err(err) = self.reload_policy_file().await {
+                ADDRESS_POLICY_METRICS.reload_errors[&AddressPolicySource::File].inc();
+                tracing::warn!("Failed reloading address policy file: {err:#}");
+            }
+            if let Err(err) = self.reload_onchain_denylist().await {
+                ADDRESS_POLICY_METRICS.reload_errors[&AddressPolicySource::Onchain].inc();
+                tracing::warn!("Failed reloading on-chain denylist: {err:#}");
+            }
+
+            // Error here corresponds to a timeout w/o `stop_receiver` changed; we're OK with this.
+            tokio::time::timeout(reload_interval, stop_receiver.changed())
+                .await
+                .ok();
+        }
+        tracing::info!("Stop signal received, address policy updater is shutting down");
+        Ok(())
+    }
+}
@@ -0,0 +1,169 @@
+//! Tests for the address policy.
+
+use assert_matches::assert_matches;
+use zksync_eth_client::clients::MockSettlementLayer;
+use zksync_node_test_utils::create_l2_transaction;
+use zksync_test_account::Account;
+use zksync_types::PriorityOpId;
+
+use super::*;
+use crate::testonly::l1_transaction;
+
+const ALLOWED: Address = Address::repeat_byte(1);
+const DENIED: Address = Address::repeat_byte(2);
+const OTHER: Address = Address::repeat_byte(3);
+const DENYLIST_CONTRACT: Address = Address::repeat_byte(0xde);
+
+fn config(policy_file_path: Option<String>) -> AddressPolicyConfig {
+    AddressPolicyConfig {
+        policy_file_path,
+        onchain_denylist_addr: None,
+        reload_interval_ms: 10,
+    }
+}
+
+#[test]
+fn checking_addresses_with_denylist() {
+    let policy = AddressPolicy::new(AddressPolicyList {
+        allowlist: None,
+        denylist: HashSet::from([DENIED]),
+    });
+
+    policy.check_addresses(ALLOWED, OTHER, None).unwrap();
+    policy
+        .check_addresses(OTHER, ALLOWED, Some(ALLOWED))
+        .unwrap();
+    assert_eq!(
+        policy.check_addresses(DENIED, OTHER, None).unwrap_err(),
+        AddressPolicyViolation::DeniedInitiator(DENIED)
+    );
+    assert_eq!(
+        policy.check_addresses(OTHER, DENIED, None).unwrap_err(),
+        AddressPolicyViolation::DeniedRecipient(DENIED)
+    );
+    assert_eq!(
+        policy
+            .check_addresses(OTHER, OTHER, Some(DENIED))
+            .unwrap_err(),
+        AddressPolicyViolation::DeniedPaymaster(DENIED)
+    );
+}
+
+#[test]
+fn checking_addresses_with_allowlist() {
+    let policy = AddressPolicy::new(AddressPolicyList {
+        allowlist: Some(HashSet::from([ALLOWED, DENIED])),
+        denylist: HashSet::from([DENIED]),
+    });
+
+    policy.check_addresses(ALLOWED, OTHER, None).unwrap();
+    assert_eq!(
+        policy.check_addresses(OTHER, ALLOWED, None).unwrap_err(),
+        AddressPolicyViolation::InitiatorNotAllowed(OTHER)
+    );
+    // The denylist takes precedence over the allowlist.
+    assert_eq!(
+        policy.check_addresses(DENIED, ALLOWED, None).unwrap_err(),
+        AddressPolicyViolation::DeniedInitiator(DENIED)
+    );
+}
+
+#[test]
+fn checking_transactions() {
+    let tx = create_l2_transaction(10, 100);
+    let initiator = tx.initiator_account();
+    let policy = AddressPolicy::new(AddressPolicyList {
+        allowlist: None,
+        denylist: HashSet::from([initiator]),
+    });
+    assert_matches!(
+        policy.check_tx(&tx.into()),
+        Err(AddressPolicyViolation::DeniedInitiator(addr)) if addr == initiator
+    );
+
+    let mut account = Account::random();
+    let l1_tx = l1_transaction(&mut account, PriorityOpId(0));
+    let policy = AddressPolicy::new(AddressPolicyList {
+        allowlist: Some(HashSet::new()),
+        denylist: HashSet::from([l1_tx.initiator_account()]),
+    });
+    policy.check_tx(&l1_tx).unwrap();
+}
+
+#[tokio::test]
+async fn reloading_policy_file() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let path = temp_dir.path().join("policy.json");
+    std::fs::write(
+        &path,
+        r#"{ "denylist": ["0x0202020202020202020202020202020202020202"] }"#,
+    )
+    .unwrap();
+
+    let policy = AddressPolicy::default();
+    let config = config(Some(path.to_str().unwrap().to_owned()));
+    let mut updater = AddressPolicyUpdater::new(policy.clone(), config, None).unwrap();
+    updater.load().await.unwrap();
+    assert_matches!(
+        policy.check_addresses(DENIED, OTHER, None),
+        Err(AddressPolicyViolation::DeniedInitiator(_))
+    );
+    policy.check_addresses(ALLOWED, OTHER, None).unwrap();
+
+    // Force the modification time to change; some filesystems have coarse timestamps.
+    updater.policy_file_modified_at = None;
+    std::fs::write(
+        &path,
+        r#"{ "allowlist": ["0x0101010101010101010101010101010101010101"] }"#,
+    )
+    .unwrap();
+    updater.load().await.unwrap();
+    policy.check_addresses(ALLOWED, DENIED, None).unwrap();
+    assert_matches!(
+        policy.check_addresses(OTHER, ALLOWED, None),
+        Err(AddressPolicyViolation::InitiatorNotAllowed(_))
+    );
+
+    // A malformed file must not reset the previously loaded policy.
+    updater.policy_file_modified_at = None;
+    std::fs::write(&path, "not a JSON").unwrap();
+    updater.load().await.unwrap_err();
+    assert_matches!(
+        policy.check_addresses(OTHER, ALLOWED, None),
+        Err(AddressPolicyViolation::InitiatorNotAllowed(_))
+    );
+}
+
+#[tokio::test]
+async fn loading_onchain_denylist() {
+    let mock = MockSettlementLayer::builder()
+        .with_call_handler(|call, _block_id| {
+            assert_eq!(call.to, Some(DENYLIST_CONTRACT));
+            ethabi::Token::Array(vec![ethabi::Token::Address(DENIED)])
+        })
+        .build();
+    let config = AddressPolicyConfig {
+        onchain_denylist_addr: Some(DENYLIST_CONTRACT),
+        ..config(None)
+    };
+
+    let policy = AddressPolicy::default();
+    let mut updater =
+        AddressPolicyUpdater::new(policy.clone(), config, Some(Box::new(mock.into_client())))
+            .unwrap();
+    updater.load().await.unwrap();
+    assert_matches!(
+        policy.check_addresses(OTHER, DENIED, None),
+        Err(AddressPolicyViolation::DeniedRecipient(_))
+    );
+    policy.check_addresses(OTHER, ALLOWED, None).unwrap();
+}
+
+#[test]
+fn onchain_denylist_requires_l1_client() {
+    let config = AddressPolicyConfig {
+        onchain_denylist_addr: Some(DENYLIST_CONTRACT),
+        ..config(None)
+    };
+    AddressPolicyUpdater::new(AddressPolicy::default(), config, None).unwrap_err();
+}
@@ -40,8 +40,7 @@ use zksync_vm_executor::batch::MainBatchExecutorFactory;
 use super::{read_storage_factory::RocksdbStorageFactory, StorageType};
 use crate::{
     testonly,
-    testonly::BASE_SYSTEM_CONTRACTS,
-    tests::{default_l1_batch_env, default_system_env},
+    testonly::{default_l1_batch_env, default_system_env, BASE_SYSTEM_CONTRACTS},
     AsyncRocksdbCache,
 };
 
@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use zksync_node_fee_model::L2BaseFeeAdjuster;
+
+use crate::{io::StateKeeperOutputHandler, updates::UpdatesManager};
+
+/// Output handler feeding L2 gas usage of sealed L2 blocks to [`L2BaseFeeAdjuster`].
+///
+/// Fictive L2 blocks closing L1 batches are ignored since they never contain transactions.
+#[derive(Debug)]
+pub struct L2BaseFeeUpdater(Arc<L2BaseFeeAdjuster>);
+
+impl L2BaseFeeUpdater {
+    pub fn new(adjuster: Arc<L2BaseFeeAdjuster>) -> Self {
+        Self(adjuster)
+    }
+}
+
+#[async_trait]
+impl StateKeeperOutputHandler for L2BaseFeeUpdater {
+    async fn handle_l2_block(&mut self, updates_manager: &UpdatesManager) -> anyhow::Result<()> {
+        self.0.update(updates_manager.l2_block.l2_gas_used);
+        Ok(())
+    }
+}
@@ -22,15 +22,17 @@ use zksync_utils::time::millis_since_epoch;
 use zksync_vm_executor::storage::L1BatchParamsProvider;
 
 use crate::{
+    address_policy::{AddressPolicy, AddressPolicyStage},
     io::{
         common::{load_pending_batch, poll_iters, IoCursor},
         seal_logic::l2_block_seal_subtasks::L2BlockSealProcess,
-        L1BatchParams, L2BlockParams, PendingBatchData, StateKeeperIO,
+        L1BatchParams, L1BatchSealRequests, L2BlockParams, PendingBatchData, StateKeeperIO,
     },
     mempool_actor::l2_tx_filter,
     metrics::{L2BlockSealReason, AGGREGATION_METRICS, KEEPER_METRICS},
     seal_criteria::{
-        IoSealCriteria, L2BlockMaxPayloadSizeSealer, TimeoutSealer, UnexecutableReason,
+        IoSealCriteria, L2BlockGasTargetSealer, L2BlockMaxPayloadSizeSealer, TimeoutSealer,
+        UnexecutableReason,
     },
     updates::UpdatesManager,
     MempoolGuard,
@@ -46,6 +48,7 @@ pub struct MempoolIO {
     pool: ConnectionPool<Core>,
     timeout_sealer: TimeoutSealer,
     l2_block_max_payload_size_sealer: L2BlockMaxPayloadSizeSealer,
+    l2_block_gas_target_sealer: L2BlockGasTargetSealer,
     filter: L2TxFilter,
     l1_batch_params_provider: L1BatchParamsProvider,
     fee_account: Address,
@@ -55,10 +58,19 @@ pub struct MempoolIO {
     // Used to keep track of gas prices to set accepted price per pubdata byte in blocks.
     batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
     chain_id: L2ChainId,
+    address_policy: Option<AddressPolicy>,
+    seal_requests: Option<L1BatchSealRequests>,
+    // Number of the currently open L1 batch (or the next batch to be opened, if there's no open batch).
+    current_l1_batch: L1BatchNumber,
 }
 
 impl IoSealCriteria for MempoolIO {
     fn should_seal_l1_batch_unconditionally(&mut self, manager: &UpdatesManager) -> bool {
+        if let Some(seal_requests) = &mut self.seal_requests {
+            if seal_requests.should_seal_l1_batch(manager) {
+                return true;
+            }
+        }
         self.timeout_sealer
             .should_seal_l1_batch_unconditionally(manager)
     }
@@ -77,6 +89,14 @@ impl IoSealCriteria for MempoolIO {
             return true;
         }
 
+        if self
+            .l2_block_gas_target_sealer
+            .should_seal_l2_block(manager)
+        {
+            AGGREGATION_METRICS.l2_block_reason_inc(&L2BlockSealReason::GasTarget);
+            return true;
+        }
+
         false
     }
 }
@@ -90,6 +110,7 @@ impl StateKeeperIO for MempoolIO {
     async fn initialize(&mut self) -> anyhow::Result<(IoCursor, Option<PendingBatchData>)> {
         let mut storage = self.pool.connection_tagged("state_keeper").await?;
         let cursor = IoCursor::new(&mut storage).await?;
+        self.current_l1_batch = cursor.l1_batch;
         self.l1_batch_params_provider
             .initialize(&mut storage)
             .await
@@ -165,6 +186,10 @@ impl StateKeeperIO for MempoolIO {
         // Block until at least one transaction in the mempool can match the filter (or timeout happens).
         // This is needed to ensure that block timestamp is not too old.
         for _ in 0..poll_iters(self.delay_interval, max_wait) {
+            if let Some(seal_requests) = &mut self.seal_requests {
+                seal_requests.respond_without_open_batch();
+            }
+
             // We cannot create two L1 batches or L2 blocks with the same timestamp (forbidden by the bootloader).
             // Hence, we wait until the current timestamp is larger than the timestamp of the previous L2 block.
             // We can use `timeout_at` since `sleep_past` is cancel-safe; it only uses `sleep()` async calls.
@@ -198,11 +223,17 @@ impl StateKeeperIO for MempoolIO {
             .await
             .context("failed creating L2 transaction filter")?;
 
-            if !self.mempool.has_next(&self.filter) {
+            let has_next_tx = if self.accepts_l2_txs(cursor.l1_batch) {
+                self.mempool.has_next(&self.filter)
+            } else {
+                self.mempool.has_next_l1()
+            };
+            if !has_next_tx {
                 tokio::time::sleep(self.delay_interval).await;
                 continue;
             }
 
+            self.current_l1_batch = cursor.l1_batch;
             return Ok(Some(L1BatchParams {
                 protocol_version,
                 validation_computational_gas_limit: self.validation_computational_gas_limit,
@@ -248,7 +279,11 @@ impl StateKeeperIO for MempoolIO {
         let started_at = Instant::now();
         while started_at.elapsed() <= max_wait {
             let get_latency = KEEPER_METRICS.get_tx_from_mempool.start();
-            let maybe_tx = self.mempool.next_transaction(&self.filter);
+            let maybe_tx = if self.accepts_l2_txs(self.current_l1_batch) {
+                self.mempool.next_transaction(&self.filter)
+            } else {
+                self.mempool.next_l1_transaction()
+            };
             get_latency.observe();
 
             if let Some(tx) = maybe_tx {
@@ -264,6 +299,20 @@ impl StateKeeperIO for MempoolIO {
                         .await?;
                     continue;
                 }
+                // Same as above: the address policy is checked on the API level, but it may have changed
+                // since the transaction was inserted into the mempool.
+                if let Some(address_policy) = &self.address_policy {
+                    if let Err(violation) = address_policy.check_tx(&tx) {
+                        AddressPolicy::report_rejection(
+                            AddressPolicyStage::StateKeeper,
+                            tx.hash(),
+                            &violation,
+                        );
+                        self.reject(&tx, UnexecutableReason::AddressPolicy(violation))
+                            .await?;
+                        continue;
+                    }
+                }
                 return Ok(Some(tx));
             } else {
                 tokio::time::sleep(self.delay_interval).await;
@@ -434,6 +483,7 @@ impl MempoolIO {
             pool,
             timeout_sealer: TimeoutSealer::new(config),
             l2_block_max_payload_size_sealer: L2BlockMaxPayloadSizeSealer::new(config),
+            l2_block_gas_target_sealer: L2BlockGasTargetSealer::new(config),
             filter: L2TxFilter::default(),
             // ^ Will be initialized properly on the first newly opened batch
             l1_batch_params_provider: L1BatchParamsProvider::new(),
@@ -443,8 +493,29 @@ impl MempoolIO {
             delay_interval,
             batch_fee_input_provider,
             chain_id,
+            address_policy: None,
+            seal_requests: None,
+            current_l1_batch: L1BatchNumber(0),
         })
     }
+
+    /// Enables checking transactions taken from the mempool against the provided address policy.
+    pub fn with_address_policy(mut self, address_policy: AddressPolicy) -> Self {
+        self.address_policy = Some(address_policy);
+        self
+    }
+
+    /// Enables handling external seal requests, e.g. ones made via the admin server.
+    pub fn with_seal_requests(mut self, seal_requests: L1BatchSealRequests) -> Self {
+        self.seal_requests = Some(seal_requests);
+        self
+    }
+
+    fn accepts_l2_txs(&self, l1_batch_number: L1BatchNumber) -> bool {
+        self.seal_requests
+            .as_ref()
+            .map_or(true, |requests| requests.accepts_l2_txs(l1_batch_number))
+    }
 }
 
 /// Getters required for testing the MempoolIO.
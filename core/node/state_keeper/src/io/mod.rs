@@ -11,16 +11,20 @@ use zksync_vm_executor::storage::l1_batch_params;
 
 pub use self::{
     common::IoCursor,
+    l2_base_fee::L2BaseFeeUpdater,
     output_handler::{OutputHandler, StateKeeperOutputHandler},
     persistence::{L2BlockSealerTask, StateKeeperPersistence, TreeWritesPersistence},
+    seal_requests::{l1_batch_seal_requests, L1BatchSealRequests, L1BatchSealTrigger},
 };
 use super::seal_criteria::{IoSealCriteria, UnexecutableReason};
 
 pub mod common;
+mod l2_base_fee;
 pub(crate) mod mempool;
 mod output_handler;
 mod persistence;
 pub mod seal_logic;
+mod seal_requests;
 #[cfg(test)]
 mod tests;
 
@@ -16,9 +16,29 @@ pub trait StateKeeperOutputHandler: 'static + Send + fmt::Debug {
         Ok(())
     }
 
+    /// Handles a transaction executed by the state keeper and included into the currently open L2 block.
+    /// The transaction is the last one in `updates_manager.l2_block.executed_transactions`. This method is not called
+    /// for transactions re-executed when restoring the pending L1 batch. The default implementation does nothing.
+    async fn handle_executed_tx(
+        &mut self,
+        _updates_manager: &UpdatesManager,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
     /// Handles an L2 block produced by the state keeper.
     async fn handle_l2_block(&mut self, updates_manager: &UpdatesManager) -> anyhow::Result<()>;
 
+    /// Handles a rollback of the currently open L2 block, e.g. on state keeper shutdown or error. All transactions passed
+    /// to [`Self::handle_executed_tx()`] since the last sealed L2 block will not be persisted.
+    /// The default implementation does nothing.
+    async fn handle_l2_block_rollback(
+        &mut self,
+        _updates_manager: &UpdatesManager,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
     /// Handles an L1 batch produced by the state keeper.
     async fn handle_l1_batch(
         &mut self,
@@ -64,6 +84,44 @@ impl OutputHandler {
         Ok(())
     }
 
+    pub(crate) async fn handle_executed_tx(
+        &mut self,
+        updates_manager: &UpdatesManager,
+    ) -> anyhow::Result<()> {
+        for handler in &mut self.inner {
+            handler
+                .handle_executed_tx(updates_manager)
+                .await
+                .with_context(|| {
+                    format!("failed handling executed transaction on handler {handler:?}")
+                })?;
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        name = "OutputHandler::handle_l2_block_rollback"
+        skip_all,
+        fields(l2_block = %updates_manager.l2_block.number)
+    )]
+    pub(crate) async fn handle_l2_block_rollback(
+        &mut self,
+        updates_manager: &UpdatesManager,
+    ) -> anyhow::Result<()> {
+        for handler in &mut self.inner {
+            handler
+                .handle_l2_block_rollback(updates_manager)
+                .await
+                .with_context(|| {
+                    format!(
+                        "failed handling L2 block #{} rollback on handler {handler:?}",
+                        updates_manager.l2_block.number
+                    )
+                })?;
+        }
+        Ok(())
+    }
+
     #[tracing::instrument(
         name = "OutputHandler::handle_l2_block"
         skip_all,
@@ -363,10 +363,8 @@ mod tests {
     use super::*;
     use crate::{
         io::L2BlockParams,
-        tests::{
-            create_execution_result, create_transaction, create_updates_manager,
-            default_l1_batch_env, default_system_env, Query,
-        },
+        testonly::{create_updates_manager, default_l1_batch_env, default_system_env},
+        tests::{create_execution_result, create_transaction, Query},
         OutputHandler,
     };
 
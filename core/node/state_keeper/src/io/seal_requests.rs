@@ -0,0 +1,262 @@
+//! Externally triggered L1 batch seal requests.
+
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use tokio::sync::{mpsc, oneshot, watch};
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_types::L1BatchNumber;
+
+use crate::{metrics::AGGREGATION_METRICS, updates::UpdatesManager};
+
+type SealResponder = oneshot::Sender<Option<L1BatchNumber>>;
+
+/// Creates a connected pair of a seal trigger (to be used by the external code, e.g. the admin server)
+/// and the seal requests receiver (to be used by [`StateKeeperIO`](super::StateKeeperIO) implementations).
+pub fn l1_batch_seal_requests() -> (L1BatchSealTrigger, L1BatchSealRequests) {
+    let (seal_sender, seal_receiver) = mpsc::unbounded_channel();
+    let (stop_after_sender, stop_after_receiver) = watch::channel(None);
+    let trigger = L1BatchSealTrigger {
+        seal_sender,
+        stop_after_sender: Arc::new(stop_after_sender),
+        pool: None,
+    };
+    let requests = L1BatchSealRequests {
+        seal_receiver,
+        stop_after_receiver,
+    };
+    (trigger, requests)
+}
+
+/// Handle allowing to request sealing the current L1 batch, or to stop accepting L2 transactions after a certain L1 batch.
+#[derive(Debug, Clone)]
+pub struct L1BatchSealTrigger {
+    seal_sender: mpsc::UnboundedSender<SealResponder>,
+    stop_after_sender: Arc<watch::Sender<Option<L1BatchNumber>>>,
+    pool: Option<ConnectionPool<Core>>,
+}
+
+impl L1BatchSealTrigger {
+    /// Makes the L1 batch after which L2 transactions are not accepted persistent, so that it survives node restarts.
+    /// The previously persisted value (if any) is restored immediately. Should be called before cloning the trigger.
+    pub async fn with_persistence(mut self, pool: ConnectionPool<Core>) -> anyhow::Result<Self> {
+        let mut storage = pool.connection_tagged("state_keeper").await?;
+        let stop_after = storage
+            .state_keeper_dal()
+            .get_l2_txs_stop_l1_batch()
+            .await?;
+        drop(storage);
+
+        if let Some(number) = stop_after {
+            tracing::info!(
+                "Restored persisted setting: state keeper doesn't accept L2 transactions after L1 batch #{number}"
+            );
+        }
+        self.stop_after_sender.send_replace(stop_after);
+        self.pool = Some(pool);
+        Ok(self)
+    }
+
+    /// Requests to seal the current L2 block and L1 batch at the next safe point. Returns the number of the L1 batch
+    /// that will be sealed, or `None` if the current L1 batch is empty (i.e., there's nothing to seal).
+    ///
+    /// The request is sent immediately when this method is called; the returned future only waits for the response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the state keeper is not running.
+    pub fn seal_l1_batch(
+        &self,
+    ) -> impl std::future::Future<Output = anyhow::Result<Option<L1BatchNumber>>> {
+        let (responder, response) = oneshot::channel();
+        let send_result = self.seal_sender.send(responder);
+        async move {
+            send_result.ok().context("state keeper is not running")?;
+            response
+                .await
+                .context("state keeper stopped before processing seal request")
+        }
+    }
+
+    /// Makes the state keeper stop accepting L2 transactions in L1 batches after the specified one. L1 (priority)
+    /// transactions are still processed. Passing `None` resumes accepting L2 transactions.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the setting cannot be persisted (only if persistence is enabled
+    /// via [`Self::with_persistence()`]). In this case, the setting is not changed.
+    pub async fn stop_after_l1_batch(&self, number: Option<L1BatchNumber>) -> anyhow::Result<()> {
+        if let Some(pool) = &self.pool {
+            let mut storage = pool.connection_tagged("state_keeper").await?;
+            storage
+                .state_keeper_dal()
+                .set_l2_txs_stop_l1_batch(number)
+                .await
+                .context("failed persisting L1 batch to stop L2 transactions after")?;
+        }
+        self.set_stop_after_l1_batch(number);
+        Ok(())
+    }
+
+    /// Changes the in-memory setting without persisting it.
+    pub(crate) fn set_stop_after_l1_batch(&self, number: Option<L1BatchNumber>) {
+        let prev_number = self.stop_after_sender.send_replace(number);
+        if prev_number != number {
+            if let Some(number) = number {
+                tracing::info!(
+                    "State keeper will stop accepting L2 transactions after L1 batch #{number}"
+                );
+            } else {
+                tracing::info!("State keeper will resume accepting L2 transactions");
+            }
+        }
+    }
+
+    /// Returns the L1 batch after which L2 transactions are not accepted, if any.
+    pub fn stopped_after_l1_batch(&self) -> Option<L1BatchNumber> {
+        *self.stop_after_sender.borrow()
+    }
+}
+
+/// Receiver of requests sent via [`L1BatchSealTrigger`].
+#[derive(Debug)]
+pub struct L1BatchSealRequests {
+    seal_receiver: mpsc::UnboundedReceiver<SealResponder>,
+    stop_after_receiver: watch::Receiver<Option<L1BatchNumber>>,
+}
+
+impl L1BatchSealRequests {
+    /// Processes pending seal requests for the currently open L1 batch. Returns `true` if the batch should be sealed.
+    /// Empty batches are never sealed; requests received for an empty batch are responded to with `None`.
+    pub fn should_seal_l1_batch(&mut self, manager: &UpdatesManager) -> bool {
+        const RULE_NAME: &str = "manual";
+
+        let l1_batch_number = manager.l1_batch.number;
+        let is_empty = manager.pending_executed_transactions_len() == 0;
+        let mut should_seal = false;
+        while let Ok(responder) = self.seal_receiver.try_recv() {
+            if is_empty {
+                tracing::info!(
+                    "Ignoring seal request for L1 batch #{l1_batch_number} since it is empty"
+                );
+                responder.send(None).ok();
+            } else {
+                responder.send(Some(l1_batch_number)).ok();
+                should_seal = true;
+            }
+        }
+
+        if should_seal {
+            AGGREGATION_METRICS.l1_batch_reason_inc_criterion(RULE_NAME);
+            tracing::info!("Decided to seal L1 batch #{l1_batch_number} using rule `{RULE_NAME}`");
+        }
+        should_seal
+    }
+
+    /// Responds to pending seal requests when there is no open L1 batch (e.g., if the state keeper is waiting
+    /// for transactions to open a new batch). There's nothing to seal in this case.
+    pub fn respond_without_open_batch(&mut self) {
+        while let Ok(responder) = self.seal_receiver.try_recv() {
+            responder.send(None).ok();
+        }
+    }
+
+    /// Checks whether L2 transactions should be accepted in the specified L1 batch.
+    pub fn accepts_l2_txs(&self, l1_batch_number: L1BatchNumber) -> bool {
+        self.stop_after_receiver
+            .borrow()
+            .map_or(true, |stop_after| l1_batch_number <= stop_after)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_multivm::interface::VmExecutionMetrics;
+
+    use super::*;
+    use crate::{
+        testonly::create_updates_manager,
+        tests::{create_execution_result, create_transaction},
+        utils::new_block_gas_count,
+    };
+
+    #[tokio::test]
+    async fn seal_requests_basics() {
+        let (trigger, mut requests) = l1_batch_seal_requests();
+        let mut updates_manager = create_updates_manager();
+        let l1_batch_number = updates_manager.l1_batch.number;
+
+        // Empty batches must not be sealed.
+        let response = trigger.seal_l1_batch();
+        assert!(!requests.should_seal_l1_batch(&updates_manager));
+        assert_eq!(response.await.unwrap(), None);
+        // There are no pending requests.
+        assert!(!requests.should_seal_l1_batch(&updates_manager));
+
+        let tx = create_transaction(10, 100);
+        updates_manager.extend_from_executed_transaction(
+            tx,
+            create_execution_result([]),
+            vec![],
+            new_block_gas_count(),
+            VmExecutionMetrics::default(),
+            vec![],
+        );
+        let response = trigger.seal_l1_batch();
+        assert!(requests.should_seal_l1_batch(&updates_manager));
+        assert_eq!(response.await.unwrap(), Some(l1_batch_number));
+        // The request is one-off.
+        assert!(!requests.should_seal_l1_batch(&updates_manager));
+
+        let response = trigger.seal_l1_batch();
+        requests.respond_without_open_batch();
+        assert_eq!(response.await.unwrap(), None);
+
+        drop(requests);
+        trigger.seal_l1_batch().await.unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn stopping_after_l1_batch() {
+        let (trigger, requests) = l1_batch_seal_requests();
+        assert!(requests.accepts_l2_txs(L1BatchNumber(1)));
+
+        trigger
+            .stop_after_l1_batch(Some(L1BatchNumber(2)))
+            .await
+            .unwrap();
+        assert_eq!(trigger.stopped_after_l1_batch(), Some(L1BatchNumber(2)));
+        assert!(requests.accepts_l2_txs(L1BatchNumber(1)));
+        assert!(requests.accepts_l2_txs(L1BatchNumber(2)));
+        assert!(!requests.accepts_l2_txs(L1BatchNumber(3)));
+
+        trigger.stop_after_l1_batch(None).await.unwrap();
+        assert!(requests.accepts_l2_txs(L1BatchNumber(3)));
+    }
+
+    #[tokio::test]
+    async fn stopping_after_l1_batch_survives_restart() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let (trigger, _) = l1_batch_seal_requests();
+        let trigger = trigger.with_persistence(pool.clone()).await.unwrap();
+        assert_eq!(trigger.stopped_after_l1_batch(), None);
+        trigger
+            .stop_after_l1_batch(Some(L1BatchNumber(2)))
+            .await
+            .unwrap();
+        drop(trigger);
+
+        // Emulate a node restart.
+        let (trigger, requests) = l1_batch_seal_requests();
+        let trigger = trigger.with_persistence(pool.clone()).await.unwrap();
+        assert_eq!(trigger.stopped_after_l1_batch(), Some(L1BatchNumber(2)));
+        assert!(requests.accepts_l2_txs(L1BatchNumber(2)));
+        assert!(!requests.accepts_l2_txs(L1BatchNumber(3)));
+
+        trigger.stop_after_l1_batch(None).await.unwrap();
+        let (trigger, requests) = l1_batch_seal_requests();
+        let trigger = trigger.with_persistence(pool).await.unwrap();
+        assert_eq!(trigger.stopped_after_l1_batch(), None);
+        assert!(requests.accepts_l2_txs(L1BatchNumber(3)));
+    }
+}
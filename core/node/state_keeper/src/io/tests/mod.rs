@@ -20,7 +20,10 @@ use zksync_utils::time::seconds_since_epoch;
 
 use self::tester::Tester;
 use crate::{
-    io::{seal_logic::l2_block_seal_subtasks::L2BlockSealProcess, StateKeeperIO},
+    io::{
+        l1_batch_seal_requests, seal_logic::l2_block_seal_subtasks::L2BlockSealProcess,
+        StateKeeperIO,
+    },
     mempool_actor::l2_tx_filter,
     testonly::BASE_SYSTEM_CONTRACTS,
     tests::{create_execution_result, create_transaction, Query},
@@ -552,3 +555,55 @@ async fn different_timestamp_for_l2_blocks_in_same_batch(commitment_mode: L1Batc
         .expect("no new L2 block params");
     assert!(l2_block_params.timestamp > current_timestamp);
 }
+
+#[tokio::test]
+async fn not_accepting_l2_txs_after_l1_batch() {
+    let connection_pool = ConnectionPool::<Core>::constrained_test_pool(1).await;
+    let tester = Tester::new(L1BatchCommitmentMode::Rollup);
+    tester.genesis(&connection_pool).await;
+    let (mempool, mut guard) = tester.create_test_mempool_io(connection_pool).await;
+    let (seal_trigger, seal_requests) = l1_batch_seal_requests();
+    let mut mempool = mempool.with_seal_requests(seal_requests);
+    let (io_cursor, _) = mempool.initialize().await.unwrap();
+    assert_eq!(io_cursor.l1_batch, L1BatchNumber(1));
+
+    let filter = l2_tx_filter(
+        &tester.create_batch_fee_input_provider().await,
+        ProtocolVersionId::latest().into(),
+    )
+    .await
+    .unwrap();
+    let tx = tester.insert_tx(&mut guard, filter.fee_per_gas, filter.gas_per_pubdata);
+
+    // L2 txs are not accepted in L1 batch #1, so the new batch shouldn't be opened.
+    seal_trigger
+        .stop_after_l1_batch(Some(L1BatchNumber(0)))
+        .await
+        .unwrap();
+    let params = mempool
+        .wait_for_new_batch_params(&io_cursor, Duration::from_secs(2))
+        .await
+        .unwrap();
+    assert!(params.is_none(), "{params:?}");
+    let next_tx = mempool
+        .wait_for_next_tx(Duration::from_secs(1))
+        .await
+        .unwrap();
+    assert!(next_tx.is_none(), "{next_tx:?}");
+
+    seal_trigger
+        .stop_after_l1_batch(Some(L1BatchNumber(1)))
+        .await
+        .unwrap();
+    mempool
+        .wait_for_new_batch_params(&io_cursor, Duration::from_secs(10))
+        .await
+        .unwrap()
+        .expect("No batch params in the test mempool");
+    let next_tx = mempool
+        .wait_for_next_tx(Duration::from_secs(10))
+        .await
+        .unwrap()
+        .expect("No tx in the test mempool");
+    assert_eq!(next_tx.hash(), tx.hash());
+}
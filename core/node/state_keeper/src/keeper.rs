@@ -70,6 +70,7 @@ pub struct ZkSyncStateKeeper {
     batch_executor: Box<dyn BatchExecutorFactory<OwnedStorage>>,
     sealer: Arc<dyn ConditionalSealer>,
     storage_factory: Arc<dyn ReadStorageFactory>,
+    pause_receiver: Option<watch::Receiver<bool>>,
 }
 
 impl ZkSyncStateKeeper {
@@ -88,9 +89,18 @@ impl ZkSyncStateKeeper {
             output_handler,
             sealer,
             storage_factory,
+            pause_receiver: None,
         }
     }
 
+    /// Allows pausing the state keeper. While the provided receiver holds `true`, the state keeper doesn't execute
+    /// new transactions, but still seals L2 blocks and L1 batches according to the sealing criteria.
+    #[must_use]
+    pub fn with_pause_receiver(mut self, pause_receiver: watch::Receiver<bool>) -> Self {
+        self.pause_receiver = Some(pause_receiver);
+        self
+    }
+
     pub async fn run(mut self) -> anyhow::Result<()> {
         match self.run_inner().await {
             Ok(_) => unreachable!(),
@@ -163,16 +173,29 @@ impl ZkSyncStateKeeper {
         let mut l1_batch_seal_delta: Option<Instant> = None;
         while !self.is_canceled() {
             // This function will run until the batch can be sealed.
-            self.process_l1_batch(
-                &mut *batch_executor,
-                &mut updates_manager,
-                protocol_upgrade_tx,
-            )
-            .await?;
+            let mut process_result = self
+                .process_l1_batch(
+                    &mut *batch_executor,
+                    &mut updates_manager,
+                    protocol_upgrade_tx,
+                )
+                .await;
+            if process_result.is_ok() && !updates_manager.l2_block.executed_transactions.is_empty()
+            {
+                process_result = self
+                    .seal_l2_block(&updates_manager)
+                    .await
+                    .map_err(Error::Fatal);
+            }
+            if process_result.is_err() && !updates_manager.l2_block.executed_transactions.is_empty()
+            {
+                // Transactions in the open L2 block won't be persisted; notify handlers that may have exposed them.
+                self.rollback_open_l2_block(&updates_manager).await;
+            }
+            process_result?;
 
             // Finish current batch.
             if !updates_manager.l2_block.executed_transactions.is_empty() {
-                self.seal_l2_block(&updates_manager).await?;
                 // We've sealed the L2 block that we had, but we still need to set up the timestamp
                 // for the fictive L2 block.
                 let new_l2_block_params =
@@ -286,6 +309,22 @@ impl ZkSyncStateKeeper {
         *self.stop_receiver.borrow()
     }
 
+    fn is_paused(&self) -> bool {
+        self.pause_receiver
+            .as_ref()
+            .map_or(false, |receiver| *receiver.borrow())
+    }
+
+    /// Waits until the state keeper is resumed, but no longer than [`POLL_WAIT_DURATION`] so that cancellation
+    /// and sealing criteria are checked in the meantime.
+    async fn wait_for_resume(&mut self) {
+        if let Some(receiver) = &mut self.pause_receiver {
+            tokio::time::timeout(POLL_WAIT_DURATION, receiver.wait_for(|&paused| !paused))
+                .await
+                .ok();
+        }
+    }
+
     async fn load_upgrade_tx(
         &mut self,
         protocol_version: ProtocolVersionId,
@@ -401,6 +440,21 @@ impl ZkSyncStateKeeper {
             })
     }
 
+    /// Notifies output handlers that transactions in the open L2 block will not be persisted. Errors are logged
+    /// rather than returned so that they don't shadow the error that caused the rollback.
+    async fn rollback_open_l2_block(&mut self, updates_manager: &UpdatesManager) {
+        let rollback_result = self
+            .output_handler
+            .handle_l2_block_rollback(updates_manager)
+            .await;
+        if let Err(err) = rollback_result {
+            tracing::warn!(
+                "Failed notifying output handlers about rollback of L2 block #{}: {err:#}",
+                updates_manager.l2_block.number
+            );
+        }
+    }
+
     #[tracing::instrument(
         skip_all,
         fields(
@@ -584,6 +638,12 @@ impl ZkSyncStateKeeper {
                     .await?;
             }
 
+            if self.is_paused() {
+                tracing::trace!("State keeper is paused; not waiting for new transactions");
+                self.wait_for_resume().await;
+                continue;
+            }
+
             let waiting_latency = KEEPER_METRICS.waiting_for_tx.start();
             let Some(tx) = self
                 .io
@@ -630,6 +690,9 @@ impl ZkSyncStateKeeper {
                         tx_execution_metrics,
                         call_tracer_result,
                     );
+                    self.output_handler
+                        .handle_executed_tx(updates_manager)
+                        .await?;
                 }
                 SealResolution::ExcludeAndSeal => {
                     batch_executor.rollback_last_tx().await.with_context(|| {
@@ -710,6 +773,9 @@ impl ZkSyncStateKeeper {
                     tx_execution_metrics,
                     vec![],
                 );
+                self.output_handler
+                    .handle_executed_tx(updates_manager)
+                    .await?;
                 Ok(())
             }
             SealResolution::ExcludeAndSeal => {
@@ -843,6 +909,8 @@ impl ZkSyncStateKeeper {
                     cumulative_size: encoding_len,
                     writes_metrics: tx_writes_metrics,
                     gas_remaining: *gas_remaining,
+                    l2_block_count: 1,
+                    priority_tx_count: usize::from(tx.is_l1()),
                 };
                 let block_data = SealData {
                     execution_metrics: tx_data.execution_metrics
@@ -854,6 +922,9 @@ impl ZkSyncStateKeeper {
                         + updates_manager.pending_txs_encoding_size(),
                     writes_metrics: block_writes_metrics,
                     gas_remaining: *gas_remaining,
+                    l2_block_count: updates_manager.pending_l2_blocks_count(),
+                    priority_tx_count: tx_data.priority_tx_count
+                        + updates_manager.pending_priority_txs_len(),
                 };
 
                 self.sealer.should_seal_l1_batch(
@@ -1,6 +1,8 @@
 pub use self::{
+    address_policy::{AddressPolicy, AddressPolicyUpdater},
     io::{
-        mempool::MempoolIO, L2BlockParams, L2BlockSealerTask, OutputHandler, StateKeeperIO,
+        l1_batch_seal_requests, mempool::MempoolIO, L1BatchSealRequests, L1BatchSealTrigger,
+        L2BaseFeeUpdater, L2BlockParams, L2BlockSealerTask, OutputHandler, StateKeeperIO,
         StateKeeperOutputHandler, StateKeeperPersistence, TreeWritesPersistence,
     },
     keeper::ZkSyncStateKeeper,
@@ -11,6 +13,7 @@ pub use self::{
     updates::UpdatesManager,
 };
 
+pub mod address_policy;
 pub mod executor;
 pub mod io;
 mod keeper;
@@ -203,6 +203,7 @@ impl From<&SealResolution> for SealResolutionLabel {
 pub(super) enum L2BlockSealReason {
     Timeout,
     PayloadSize,
+    GasTarget,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelSet)]
@@ -6,7 +6,7 @@
 
 use std::fmt;
 
-use zksync_config::configs::chain::StateKeeperConfig;
+use zksync_config::configs::chain::{SealCriterionKind, StateKeeperConfig};
 use zksync_types::ProtocolVersionId;
 
 use super::{criteria, SealCriterion, SealData, SealResolution, AGGREGATION_METRICS};
@@ -37,12 +37,18 @@ pub trait ConditionalSealer: 'static + fmt::Debug + Send + Sync {
 /// Implementation of [`ConditionalSealer`] used by the main node.
 /// Internally uses a set of [`SealCriterion`]s to determine whether the batch should be sealed.
 ///
-/// The checks are deterministic, i.e., should depend solely on execution metrics and [`StateKeeperConfig`].
-/// Non-deterministic seal criteria are expressed using [`IoSealCriteria`](super::IoSealCriteria).
+/// The set of criteria may depend on the protocol version; see [`StateKeeperConfig::seal_criteria`].
+///
+/// The checks are deterministic, i.e., should depend solely on execution metrics and [`StateKeeperConfig`]
+/// (with the exception of the L1 batch age criterion). Other non-deterministic seal criteria are expressed
+/// using [`IoSealCriteria`](super::IoSealCriteria).
 #[derive(Debug, Default)]
 pub struct SequencerSealer {
     config: StateKeeperConfig,
+    /// Criteria used for protocol versions not covered by `versioned_sealers`.
     sealers: Vec<Box<dyn SealCriterion>>,
+    /// Criteria sets together with the minimum protocol version they apply to, sorted by the version.
+    versioned_sealers: Vec<(u16, Vec<Box<dyn SealCriterion>>)>,
 }
 
 impl ConditionalSealer for SequencerSealer {
@@ -51,7 +57,7 @@ impl ConditionalSealer for SequencerSealer {
         data: &SealData,
         protocol_version: ProtocolVersionId,
     ) -> Option<&'static str> {
-        for sealer in &self.sealers {
+        for sealer in self.sealers(protocol_version) {
             const MOCK_BLOCK_TIMESTAMP: u128 = 0;
             const TX_COUNT: usize = 1;
 
@@ -86,7 +92,7 @@ impl ConditionalSealer for SequencerSealer {
         );
 
         let mut final_seal_resolution = SealResolution::NoSeal;
-        for sealer in &self.sealers {
+        for sealer in self.sealers(protocol_version) {
             let seal_resolution = sealer.should_seal(
                 &self.config,
                 block_open_timestamp_ms,
@@ -117,8 +123,23 @@ impl ConditionalSealer for SequencerSealer {
 
 impl SequencerSealer {
     pub fn new(config: StateKeeperConfig) -> Self {
-        let sealers = Self::default_sealers(&config);
-        Self { config, sealers }
+        let sealers = Self::create_sealers(&config, SealCriterionKind::DEFAULT);
+        let mut versioned_sealers: Vec<_> = config
+            .seal_criteria
+            .iter()
+            .map(|set| {
+                let sealers = Self::create_sealers(&config, &set.criteria);
+                (set.from_protocol_version, sealers)
+            })
+            .collect();
+        // Sorting is stable, so if several sets have the same version, the last one takes precedence.
+        versioned_sealers.sort_by_key(|(from_version, _)| *from_version);
+
+        Self {
+            config,
+            sealers,
+            versioned_sealers,
+        }
     }
 
     #[cfg(test)]
@@ -126,20 +147,62 @@ impl SequencerSealer {
         config: StateKeeperConfig,
         sealers: Vec<Box<dyn SealCriterion>>,
     ) -> Self {
-        Self { config, sealers }
+        Self {
+            config,
+            sealers,
+            versioned_sealers: vec![],
+        }
     }
 
-    fn default_sealers(config: &StateKeeperConfig) -> Vec<Box<dyn SealCriterion>> {
-        vec![
-            Box::new(criteria::SlotsCriterion),
-            Box::new(criteria::GasCriterion),
-            Box::new(criteria::PubDataBytesCriterion {
+    fn create_sealers(
+        config: &StateKeeperConfig,
+        kinds: &[SealCriterionKind],
+    ) -> Vec<Box<dyn SealCriterion>> {
+        kinds
+            .iter()
+            .map(|&kind| Self::create_sealer(config, kind))
+            .collect()
+    }
+
+    fn create_sealer(
+        config: &StateKeeperConfig,
+        kind: SealCriterionKind,
+    ) -> Box<dyn SealCriterion> {
+        let has_limit = match kind {
+            SealCriterionKind::L1BatchAge => config.max_l1_batch_age_ms.is_some(),
+            SealCriterionKind::L2BlocksCount => config.max_l2_blocks_per_batch.is_some(),
+            SealCriterionKind::PriorityTxsCount => config.max_priority_txs_per_batch.is_some(),
+            _ => true,
+        };
+        if !has_limit {
+            tracing::warn!(
+                "Seal criterion {kind:?} is enabled, but its limit is not configured; the criterion will have no effect"
+            );
+        }
+
+        match kind {
+            SealCriterionKind::Slots => Box::new(criteria::SlotsCriterion),
+            SealCriterionKind::Gas => Box::new(criteria::GasCriterion),
+            SealCriterionKind::PubdataBytes => Box::new(criteria::PubDataBytesCriterion {
                 max_pubdata_per_batch: config.max_pubdata_per_batch,
             }),
-            Box::new(criteria::CircuitsCriterion),
-            Box::new(criteria::TxEncodingSizeCriterion),
-            Box::new(criteria::GasForBatchTipCriterion),
-        ]
+            SealCriterionKind::Circuits => Box::new(criteria::CircuitsCriterion),
+            SealCriterionKind::TxEncodingSize => Box::new(criteria::TxEncodingSizeCriterion),
+            SealCriterionKind::GasForBatchTip => Box::new(criteria::GasForBatchTipCriterion),
+            SealCriterionKind::L1BatchAge => Box::new(criteria::L1BatchAgeCriterion),
+            SealCriterionKind::L2BlocksCount => Box::new(criteria::L2BlocksCountCriterion),
+            SealCriterionKind::PriorityTxsCount => Box::new(criteria::PriorityTxsCountCriterion),
+        }
+    }
+
+    /// Returns seal criteria applicable to the specified protocol version.
+    fn sealers(&self, protocol_version: ProtocolVersionId) -> &[Box<dyn SealCriterion>] {
+        let version = protocol_version as u16;
+        self.versioned_sealers
+            .iter()
+            .rev()
+            .find(|(from_version, _)| *from_version <= version)
+            .map_or(&self.sealers, |(_, sealers)| sealers)
     }
 }
 
@@ -170,3 +233,67 @@ impl ConditionalSealer for NoopSealer {
         SealResolution::NoSeal
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use zksync_config::configs::chain::SealCriteriaSet;
+
+    use super::*;
+
+    fn criterion_names(sealer: &SequencerSealer, protocol_version: ProtocolVersionId) -> Vec<&str> {
+        sealer
+            .sealers(protocol_version)
+            .iter()
+            .map(|sealer| sealer.prom_criterion_name())
+            .collect()
+    }
+
+    #[test]
+    fn seal_criteria_depend_on_protocol_version() {
+        let config = StateKeeperConfig {
+            max_l2_blocks_per_batch: Some(10),
+            max_priority_txs_per_batch: Some(5),
+            seal_criteria: vec![
+                SealCriteriaSet {
+                    from_protocol_version: ProtocolVersionId::Version24 as u16,
+                    criteria: vec![SealCriterionKind::Slots, SealCriterionKind::L2BlocksCount],
+                },
+                SealCriteriaSet {
+                    from_protocol_version: ProtocolVersionId::Version22 as u16,
+                    criteria: vec![SealCriterionKind::PriorityTxsCount],
+                },
+            ],
+            ..StateKeeperConfig::for_tests()
+        };
+        let sealer = SequencerSealer::new(config);
+
+        let default_names = criterion_names(&sealer, ProtocolVersionId::Version21);
+        assert_eq!(
+            default_names,
+            [
+                "slots",
+                "gas",
+                "pub_data_size",
+                "circuits_criterion",
+                "tx_encoding_size",
+                "gas_for_batch_tip"
+            ]
+        );
+        assert_eq!(
+            criterion_names(&sealer, ProtocolVersionId::Version22),
+            ["priority_txs_count"]
+        );
+        assert_eq!(
+            criterion_names(&sealer, ProtocolVersionId::Version23),
+            ["priority_txs_count"]
+        );
+        assert_eq!(
+            criterion_names(&sealer, ProtocolVersionId::Version24),
+            ["slots", "l2_blocks_count"]
+        );
+        assert_eq!(
+            criterion_names(&sealer, ProtocolVersionId::latest()),
+            ["slots", "l2_blocks_count"]
+        );
+    }
+}
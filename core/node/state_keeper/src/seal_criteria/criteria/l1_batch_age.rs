@@ -0,0 +1,83 @@
+use zksync_types::ProtocolVersionId;
+use zksync_utils::time::millis_since_epoch;
+
+use crate::seal_criteria::{SealCriterion, SealData, SealResolution, StateKeeperConfig};
+
+/// Checks whether we should seal the batch because it has been open for too long (in terms of wall-clock time).
+///
+/// Unlike the `block_commit_deadline_ms` timeout, this criterion is only checked after executing a transaction,
+/// and is configurable per protocol version.
+#[derive(Debug)]
+pub struct L1BatchAgeCriterion;
+
+impl SealCriterion for L1BatchAgeCriterion {
+    fn should_seal(
+        &self,
+        config: &StateKeeperConfig,
+        block_open_timestamp_ms: u128,
+        _tx_count: usize,
+        _block_data: &SealData,
+        _tx_data: &SealData,
+        _protocol_version: ProtocolVersionId,
+    ) -> SealResolution {
+        let Some(max_age_ms) = config.max_l1_batch_age_ms else {
+            return SealResolution::NoSeal;
+        };
+        let age_ms = millis_since_epoch().saturating_sub(block_open_timestamp_ms);
+        if age_ms >= u128::from(max_age_ms) {
+            SealResolution::IncludeAndSeal
+        } else {
+            SealResolution::NoSeal
+        }
+    }
+
+    fn prom_criterion_name(&self) -> &'static str {
+        "l1_batch_age"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_l1_batch_age_seal_criterion() {
+        let config = StateKeeperConfig {
+            max_l1_batch_age_ms: Some(60_000),
+            ..Default::default()
+        };
+        let criterion = L1BatchAgeCriterion;
+        let now_ms = millis_since_epoch();
+
+        let fresh_batch_resolution = criterion.should_seal(
+            &config,
+            now_ms - 1_000,
+            1,
+            &SealData::default(),
+            &SealData::default(),
+            ProtocolVersionId::latest(),
+        );
+        assert_eq!(fresh_batch_resolution, SealResolution::NoSeal);
+
+        let old_batch_resolution = criterion.should_seal(
+            &config,
+            now_ms - 61_000,
+            1,
+            &SealData::default(),
+            &SealData::default(),
+            ProtocolVersionId::latest(),
+        );
+        assert_eq!(old_batch_resolution, SealResolution::IncludeAndSeal);
+
+        // The criterion is a no-op if the max age is not configured.
+        let resolution = criterion.should_seal(
+            &StateKeeperConfig::default(),
+            0,
+            1,
+            &SealData::default(),
+            &SealData::default(),
+            ProtocolVersionId::latest(),
+        );
+        assert_eq!(resolution, SealResolution::NoSeal);
+    }
+}
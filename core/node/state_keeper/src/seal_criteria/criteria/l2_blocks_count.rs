@@ -0,0 +1,72 @@
+use zksync_types::ProtocolVersionId;
+
+use crate::seal_criteria::{SealCriterion, SealData, SealResolution, StateKeeperConfig};
+
+/// Checks whether we should seal the batch because it contains the maximum allowed number of L2 blocks.
+#[derive(Debug)]
+pub struct L2BlocksCountCriterion;
+
+impl SealCriterion for L2BlocksCountCriterion {
+    fn should_seal(
+        &self,
+        config: &StateKeeperConfig,
+        _block_open_timestamp_ms: u128,
+        _tx_count: usize,
+        block_data: &SealData,
+        _tx_data: &SealData,
+        _protocol_version: ProtocolVersionId,
+    ) -> SealResolution {
+        let Some(max_l2_blocks) = config.max_l2_blocks_per_batch else {
+            return SealResolution::NoSeal;
+        };
+        if block_data.l2_block_count >= max_l2_blocks as usize {
+            SealResolution::IncludeAndSeal
+        } else {
+            SealResolution::NoSeal
+        }
+    }
+
+    fn prom_criterion_name(&self) -> &'static str {
+        "l2_blocks_count"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_l2_blocks_count_seal_criterion() {
+        let config = StateKeeperConfig {
+            max_l2_blocks_per_batch: Some(10),
+            ..Default::default()
+        };
+        let criterion = L2BlocksCountCriterion;
+
+        let almost_full_block_resolution = criterion.should_seal(
+            &config,
+            Default::default(),
+            1,
+            &SealData {
+                l2_block_count: 9,
+                ..SealData::default()
+            },
+            &SealData::default(),
+            ProtocolVersionId::latest(),
+        );
+        assert_eq!(almost_full_block_resolution, SealResolution::NoSeal);
+
+        let full_block_resolution = criterion.should_seal(
+            &config,
+            Default::default(),
+            1,
+            &SealData {
+                l2_block_count: 10,
+                ..SealData::default()
+            },
+            &SealData::default(),
+            ProtocolVersionId::latest(),
+        );
+        assert_eq!(full_block_resolution, SealResolution::IncludeAndSeal);
+    }
+}
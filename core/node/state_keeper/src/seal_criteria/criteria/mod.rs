@@ -1,12 +1,17 @@
 mod gas;
 mod gas_for_batch_tip;
 mod geometry_seal_criteria;
+mod l1_batch_age;
+mod l2_blocks_count;
+mod priority_txs_count;
 mod pubdata_bytes;
 mod slots;
 mod tx_encoding_size;
 
 pub(crate) use self::{
     gas::GasCriterion, gas_for_batch_tip::GasForBatchTipCriterion,
-    geometry_seal_criteria::CircuitsCriterion, pubdata_bytes::PubDataBytesCriterion,
-    slots::SlotsCriterion, tx_encoding_size::TxEncodingSizeCriterion,
+    geometry_seal_criteria::CircuitsCriterion, l1_batch_age::L1BatchAgeCriterion,
+    l2_blocks_count::L2BlocksCountCriterion, priority_txs_count::PriorityTxsCountCriterion,
+    pubdata_bytes::PubDataBytesCriterion, slots::SlotsCriterion,
+    tx_encoding_size::TxEncodingSizeCriterion,
 };
@@ -0,0 +1,70 @@
+use zksync_types::ProtocolVersionId;
+
+use crate::seal_criteria::{SealCriterion, SealData, SealResolution, StateKeeperConfig};
+
+/// Checks whether we should seal the batch because it contains the maximum allowed number of L1 -> L2
+/// priority transactions.
+#[derive(Debug)]
+pub struct PriorityTxsCountCriterion;
+
+impl SealCriterion for PriorityTxsCountCriterion {
+    fn should_seal(
+        &self,
+        config: &StateKeeperConfig,
+        _block_open_timestamp_ms: u128,
+        _tx_count: usize,
+        block_data: &SealData,
+        _tx_data: &SealData,
+        _protocol_version: ProtocolVersionId,
+    ) -> SealResolution {
+        let Some(max_priority_txs) = config.max_priority_txs_per_batch else {
+            return SealResolution::NoSeal;
+        };
+        let max_priority_txs = max_priority_txs as usize;
+        if block_data.priority_tx_count > max_priority_txs {
+            SealResolution::ExcludeAndSeal
+        } else if block_data.priority_tx_count == max_priority_txs {
+            SealResolution::IncludeAndSeal
+        } else {
+            SealResolution::NoSeal
+        }
+    }
+
+    fn prom_criterion_name(&self) -> &'static str {
+        "priority_txs_count"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_resolution(priority_tx_count: usize, expected: SealResolution) {
+        let config = StateKeeperConfig {
+            max_priority_txs_per_batch: Some(5),
+            ..Default::default()
+        };
+        let resolution = PriorityTxsCountCriterion.should_seal(
+            &config,
+            Default::default(),
+            priority_tx_count,
+            &SealData {
+                priority_tx_count,
+                ..SealData::default()
+            },
+            &SealData {
+                priority_tx_count: 1,
+                ..SealData::default()
+            },
+            ProtocolVersionId::latest(),
+        );
+        assert_eq!(resolution, expected);
+    }
+
+    #[test]
+    fn test_priority_txs_count_seal_criterion() {
+        check_resolution(4, SealResolution::NoSeal);
+        check_resolution(5, SealResolution::IncludeAndSeal);
+        check_resolution(6, SealResolution::ExcludeAndSeal);
+    }
+}
@@ -27,6 +27,7 @@ pub(super) mod criteria;
 
 pub use self::conditional_sealer::{ConditionalSealer, NoopSealer, SequencerSealer};
 use super::{
+    address_policy::AddressPolicyViolation,
     metrics::AGGREGATION_METRICS,
     updates::UpdatesManager,
     utils::{gas_count_from_tx_and_metrics, gas_count_from_writes},
@@ -68,6 +69,7 @@ pub enum UnexecutableReason {
     OutOfGasForBatchTip,
     BootloaderOutOfGas,
     NotEnoughGasProvided,
+    AddressPolicy(AddressPolicyViolation),
 }
 
 impl UnexecutableReason {
@@ -82,6 +84,7 @@ impl UnexecutableReason {
             UnexecutableReason::OutOfGasForBatchTip => "OutOfGasForBatchTip",
             UnexecutableReason::BootloaderOutOfGas => "BootloaderOutOfGas",
             UnexecutableReason::NotEnoughGasProvided => "NotEnoughGasProvided",
+            UnexecutableReason::AddressPolicy(_) => "AddressPolicy",
         }
     }
 }
@@ -106,6 +109,9 @@ impl fmt::Display for UnexecutableReason {
             UnexecutableReason::OutOfGasForBatchTip => write!(f, "Out of gas for batch tip"),
             UnexecutableReason::BootloaderOutOfGas => write!(f, "Bootloader out of gas"),
             UnexecutableReason::NotEnoughGasProvided => write!(f, "Not enough gas provided"),
+            UnexecutableReason::AddressPolicy(violation) => {
+                write!(f, "Rejected by address policy: {violation}")
+            }
         }
     }
 }
@@ -163,6 +169,8 @@ pub struct SealData {
     pub(super) cumulative_size: usize,
     pub(super) writes_metrics: DeduplicatedWritesMetrics,
     pub(super) gas_remaining: u32,
+    pub(super) l2_block_count: usize,
+    pub(super) priority_tx_count: usize,
 }
 
 impl SealData {
@@ -183,6 +191,8 @@ impl SealData {
             cumulative_size: transaction.bootloader_encoding_size(),
             writes_metrics,
             gas_remaining: tx_metrics.gas_remaining,
+            l2_block_count: 1,
+            priority_tx_count: usize::from(transaction.is_l1()),
         }
     }
 }
@@ -275,12 +285,34 @@ impl L2BlockMaxPayloadSizeSealer {
     }
 }
 
+/// Seals an L2 block once it uses the target amount of L2 gas. No-op if the gas target is not configured.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct L2BlockGasTargetSealer {
+    gas_target: Option<u64>,
+}
+
+impl L2BlockGasTargetSealer {
+    pub fn new(config: &StateKeeperConfig) -> Self {
+        Self {
+            gas_target: config.l2_block_gas_target,
+        }
+    }
+
+    pub fn should_seal_l2_block(&mut self, manager: &UpdatesManager) -> bool {
+        self.gas_target
+            .map_or(false, |target| manager.l2_block.l2_gas_used >= target)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use zksync_utils::time::seconds_since_epoch;
 
     use super::*;
-    use crate::tests::{create_execution_result, create_transaction, create_updates_manager};
+    use crate::{
+        testonly::create_updates_manager,
+        tests::{create_execution_result, create_transaction},
+    };
 
     fn apply_tx_to_manager(tx: Transaction, manager: &mut UpdatesManager) {
         manager.extend_from_executed_transaction(
@@ -348,4 +380,34 @@ mod tests {
             "L2 block with payload encoding size equal or greater than max payload size should be sealed"
         );
     }
+
+    #[test]
+    fn gas_target_l2_block_sealer() {
+        let tx = create_transaction(10, 100);
+        let gas_limit = tx.gas_limit().as_u64();
+
+        let mut disabled_sealer = L2BlockGasTargetSealer { gas_target: None };
+        let mut gas_target_sealer = L2BlockGasTargetSealer {
+            gas_target: Some(2 * gas_limit),
+        };
+
+        let mut manager = create_updates_manager();
+        assert!(
+            !gas_target_sealer.should_seal_l2_block(&manager),
+            "Empty L2 block shouldn't be sealed"
+        );
+
+        apply_tx_to_manager(tx, &mut manager);
+        assert!(
+            !gas_target_sealer.should_seal_l2_block(&manager),
+            "L2 block below the gas target shouldn't be sealed"
+        );
+
+        apply_tx_to_manager(create_transaction(10, 100), &mut manager);
+        assert!(
+            gas_target_sealer.should_seal_l2_block(&manager),
+            "L2 block reaching the gas target should be sealed"
+        );
+        assert!(!disabled_sealer.should_seal_l2_block(&manager));
+    }
 }
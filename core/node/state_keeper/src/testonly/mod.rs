@@ -5,26 +5,76 @@ use async_trait::async_trait;
 use once_cell::sync::Lazy;
 use zksync_contracts::BaseSystemContracts;
 use zksync_dal::{ConnectionPool, Core, CoreDal as _};
-use zksync_multivm::interface::{
-    executor::{BatchExecutor, BatchExecutorFactory},
-    storage::{InMemoryStorage, StorageView},
-    BatchTransactionExecutionResult, ExecutionResult, FinishedL1Batch, L1BatchEnv, L2BlockEnv,
-    SystemEnv, VmExecutionResultAndLogs,
+use zksync_multivm::{
+    interface::{
+        executor::{BatchExecutor, BatchExecutorFactory},
+        storage::{InMemoryStorage, StorageView},
+        BatchTransactionExecutionResult, ExecutionResult, FinishedL1Batch, L1BatchEnv, L2BlockEnv,
+        SystemEnv, TxExecutionMode, VmExecutionResultAndLogs,
+    },
+    vm_latest::constants::BATCH_COMPUTATIONAL_GAS_LIMIT,
 };
 use zksync_state::OwnedStorage;
 use zksync_test_account::Account;
 use zksync_types::{
-    fee::Fee, utils::storage_key_for_standard_token_balance, AccountTreeId, Address, Execute,
-    L1BatchNumber, L2BlockNumber, PriorityOpId, StorageLog, Transaction, L2_BASE_TOKEN_ADDRESS,
-    SYSTEM_CONTEXT_MINIMAL_BASE_FEE, U256,
+    block::L2BlockHasher,
+    fee::Fee,
+    fee_model::{BatchFeeInput, PubdataIndependentBatchFeeModelInput},
+    utils::storage_key_for_standard_token_balance,
+    AccountTreeId, Address, Execute, L1BatchNumber, L2BlockNumber, L2ChainId, PriorityOpId,
+    ProtocolVersionId, StorageLog, Transaction, L2_BASE_TOKEN_ADDRESS,
+    SYSTEM_CONTEXT_MINIMAL_BASE_FEE, U256, ZKPORTER_IS_AVAILABLE,
 };
 use zksync_utils::u256_to_h256;
 
+use crate::UpdatesManager;
+
 pub mod test_batch_executor;
 
 pub(super) static BASE_SYSTEM_CONTRACTS: Lazy<BaseSystemContracts> =
     Lazy::new(BaseSystemContracts::load_from_disk);
 
+/// Returns a [`SystemEnv`] with the latest protocol version and default limits.
+pub fn default_system_env() -> SystemEnv {
+    SystemEnv {
+        zk_porter_available: ZKPORTER_IS_AVAILABLE,
+        version: ProtocolVersionId::latest(),
+        base_system_smart_contracts: BASE_SYSTEM_CONTRACTS.clone(),
+        bootloader_gas_limit: BATCH_COMPUTATIONAL_GAS_LIMIT,
+        execution_mode: TxExecutionMode::VerifyExecute,
+        default_validation_computational_gas_limit: BATCH_COMPUTATIONAL_GAS_LIMIT,
+        chain_id: L2ChainId::from(270),
+    }
+}
+
+/// Returns an [`L1BatchEnv`] for the specified L1 batch, which starts with an L2 block with the same number and timestamp.
+pub fn default_l1_batch_env(number: u32, timestamp: u64, fee_account: Address) -> L1BatchEnv {
+    L1BatchEnv {
+        previous_batch_hash: None,
+        number: L1BatchNumber(number),
+        timestamp,
+        fee_account,
+        enforced_base_fee: None,
+        first_l2_block: L2BlockEnv {
+            number,
+            timestamp,
+            prev_block_hash: L2BlockHasher::legacy_hash(L2BlockNumber(number - 1)),
+            max_virtual_blocks_to_create: 1,
+        },
+        fee_input: BatchFeeInput::PubdataIndependent(PubdataIndependentBatchFeeModelInput {
+            fair_l2_gas_price: 1,
+            fair_pubdata_price: 1,
+            l1_gas_price: 1,
+        }),
+    }
+}
+
+/// Creates an [`UpdatesManager`] for L1 batch #1 with no executed transactions.
+pub fn create_updates_manager() -> UpdatesManager {
+    let l1_batch_env = default_l1_batch_env(1, 1, Address::default());
+    UpdatesManager::new(&l1_batch_env, &default_system_env())
+}
+
 /// Creates a `TxExecutionResult` object denoting a successful tx execution.
 pub(crate) fn successful_exec() -> BatchTransactionExecutionResult {
     BatchTransactionExecutionResult {
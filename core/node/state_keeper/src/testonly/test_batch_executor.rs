@@ -32,7 +32,10 @@ use zksync_types::{
 };
 
 use crate::{
-    io::{IoCursor, L1BatchParams, L2BlockParams, PendingBatchData, StateKeeperIO},
+    io::{
+        l1_batch_seal_requests, IoCursor, L1BatchParams, L1BatchSealRequests, L1BatchSealTrigger,
+        L2BlockParams, PendingBatchData, StateKeeperIO,
+    },
     seal_criteria::{IoSealCriteria, SequencerSealer, UnexecutableReason},
     testonly::{successful_exec, BASE_SYSTEM_CONTRACTS},
     updates::UpdatesManager,
@@ -58,6 +61,8 @@ pub(crate) struct TestScenario {
     pending_batch: Option<PendingBatchData>,
     l1_batch_seal_fn: Box<SealFn>,
     l2_block_seal_fn: Box<SealFn>,
+    seal_trigger: L1BatchSealTrigger,
+    seal_requests: L1BatchSealRequests,
 }
 
 type SealFn = dyn FnMut(&UpdatesManager) -> bool + Send + Sync;
@@ -74,14 +79,22 @@ impl fmt::Debug for TestScenario {
 
 impl TestScenario {
     pub(crate) fn new() -> Self {
+        let (seal_trigger, seal_requests) = l1_batch_seal_requests();
         Self {
             actions: VecDeque::new(),
             pending_batch: None,
             l1_batch_seal_fn: Box::new(|_| false),
             l2_block_seal_fn: Box::new(|_| false),
+            seal_trigger,
+            seal_requests,
         }
     }
 
+    /// Returns the trigger for external seal requests handled by the test IO.
+    pub(crate) fn seal_trigger(&self) -> L1BatchSealTrigger {
+        self.seal_trigger.clone()
+    }
+
     /// Adds a pending batch data that would be fed into the state keeper.
     /// Note that during processing pending batch, state keeper do *not* call `seal_l2_block` method on the IO (since
     /// it only recovers the temporary state).
@@ -104,6 +117,26 @@ impl TestScenario {
         self
     }
 
+    /// Requests to seal the current L1 batch via [`L1BatchSealTrigger`] once the state keeper requests a transaction
+    /// from IO. No transaction is returned for this request.
+    pub(crate) fn request_l1_batch_seal(mut self, description: &'static str) -> Self {
+        self.actions
+            .push_back(ScenarioItem::RequestL1BatchSeal(description));
+        self
+    }
+
+    /// Makes IO stop returning L2 transactions in L1 batches after the specified one (or resume returning them
+    /// if `None` is provided).
+    pub(crate) fn stop_l2_txs_after_l1_batch(
+        mut self,
+        description: &'static str,
+        number: Option<L1BatchNumber>,
+    ) -> Self {
+        self.actions
+            .push_back(ScenarioItem::StopL2TxsAfterL1Batch(description, number));
+        self
+    }
+
     /// Expect the state keeper to request a transaction from IO.
     /// Adds both a transaction and an outcome of this transaction (that would be returned to the state keeper from the
     /// batch executor).
@@ -290,6 +323,10 @@ enum ScenarioItem {
     NoTxsUntilNextAction(&'static str),
     /// Increments protocol version in IO state.
     IncrementProtocolVersion(&'static str),
+    /// Requests to seal the current L1 batch via [`L1BatchSealTrigger`].
+    RequestL1BatchSeal(&'static str),
+    /// Stops or resumes accepting L2 transactions via [`L1BatchSealTrigger`].
+    StopL2TxsAfterL1Batch(&'static str, Option<L1BatchNumber>),
     Tx(&'static str, Transaction, BatchTransactionExecutionResult),
     Rollback(&'static str, Transaction),
     Reject(&'static str, Transaction, UnexecutableReason),
@@ -314,6 +351,15 @@ impl fmt::Debug for ScenarioItem {
                 .debug_tuple("IncrementProtocolVersion")
                 .field(descr)
                 .finish(),
+            Self::RequestL1BatchSeal(descr) => formatter
+                .debug_tuple("RequestL1BatchSeal")
+                .field(descr)
+                .finish(),
+            Self::StopL2TxsAfterL1Batch(descr, number) => formatter
+                .debug_tuple("StopL2TxsAfterL1Batch")
+                .field(descr)
+                .field(number)
+                .finish(),
             Self::Tx(descr, tx, result) => formatter
                 .debug_tuple("Tx")
                 .field(descr)
@@ -559,6 +605,8 @@ pub(crate) struct TestIO {
     pending_batch: Option<PendingBatchData>,
     l1_batch_seal_fn: Box<SealFn>,
     l2_block_seal_fn: Box<SealFn>,
+    seal_trigger: L1BatchSealTrigger,
+    seal_requests: L1BatchSealRequests,
     actions: Arc<Mutex<VecDeque<ScenarioItem>>>,
     /// Internal flag that is being set if scenario was configured to return `None` to all the transaction
     /// requests until some other action happens.
@@ -606,6 +654,8 @@ impl TestIO {
             pending_batch: scenario.pending_batch,
             l1_batch_seal_fn: scenario.l1_batch_seal_fn,
             l2_block_seal_fn: scenario.l2_block_seal_fn,
+            seal_trigger: scenario.seal_trigger,
+            seal_requests: scenario.seal_requests,
             actions,
             l2_block_number,
             fee_account: FEE_ACCOUNT,
@@ -647,6 +697,11 @@ impl TestIO {
                     // This is a mock item, so pop an actual one for the IO to process.
                     continue;
                 }
+                ScenarioItem::StopL2TxsAfterL1Batch(_, number) => {
+                    self.seal_trigger.set_stop_after_l1_batch(*number);
+                    // This is a mock item, so pop an actual one for the IO to process.
+                    continue;
+                }
                 _ => break action,
             }
         }
@@ -655,7 +710,7 @@ impl TestIO {
 
 impl IoSealCriteria for TestIO {
     fn should_seal_l1_batch_unconditionally(&mut self, manager: &UpdatesManager) -> bool {
-        (self.l1_batch_seal_fn)(manager)
+        self.seal_requests.should_seal_l1_batch(manager) || (self.l1_batch_seal_fn)(manager)
     }
 
     fn should_seal_l2_block(&mut self, manager: &UpdatesManager) -> bool {
@@ -738,10 +793,25 @@ impl StateKeeperIO for TestIO {
             return Ok(None);
         }
 
+        if let ScenarioItem::RequestL1BatchSeal(_) = action {
+            // The request is sent synchronously, so we don't need to wait for the response.
+            drop(self.seal_trigger.seal_l1_batch());
+            return Ok(None);
+        }
+
         // We shouldn't, process normally.
-        let ScenarioItem::Tx(_, tx, _) = action else {
+        let ScenarioItem::Tx(descr, tx, result) = action else {
             panic!("Unexpected action: {:?}", action);
         };
+        // Mimic `MempoolIO`, which doesn't return L2 transactions if they are not accepted in the current L1 batch.
+        // (`batch_number` is the number of the next L1 batch to be opened.)
+        let current_l1_batch = L1BatchNumber(self.batch_number.0 - 1);
+        if !tx.is_l1() && !self.seal_requests.accepts_l2_txs(current_l1_batch) {
+            tokio::time::sleep(max_wait).await;
+            let action = ScenarioItem::Tx(descr, tx, result);
+            self.actions.lock().unwrap().push_front(action);
+            return Ok(None);
+        }
         Ok(Some(tx))
     }
 
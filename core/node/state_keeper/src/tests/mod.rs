@@ -3,26 +3,27 @@ use std::{
         atomic::{AtomicBool, AtomicU64, Ordering},
         Arc,
     },
-    time::Instant,
+    time::{Duration, Instant},
 };
 
+use async_trait::async_trait;
 use tokio::sync::watch;
 use zksync_config::configs::chain::StateKeeperConfig;
 use zksync_multivm::{
     interface::{
-        ExecutionResult, Halt, L1BatchEnv, L2BlockEnv, Refunds, SystemEnv, TxExecutionMode,
-        VmExecutionLogs, VmExecutionResultAndLogs, VmExecutionStatistics,
+        ExecutionResult, Halt, Refunds, SystemEnv, TxExecutionMode, VmExecutionLogs,
+        VmExecutionResultAndLogs, VmExecutionStatistics,
     },
     vm_latest::constants::BATCH_COMPUTATIONAL_GAS_LIMIT,
 };
 use zksync_node_test_utils::create_l2_transaction;
+use zksync_test_account::Account;
 use zksync_types::{
     aggregated_operations::AggregatedActionType,
     block::{BlockGasCount, L2BlockExecutionData, L2BlockHasher},
-    fee_model::{BatchFeeInput, PubdataIndependentBatchFeeModelInput},
-    AccountTreeId, Address, L1BatchNumber, L2BlockNumber, L2ChainId, ProtocolVersionId, StorageKey,
-    StorageLog, StorageLogKind, StorageLogWithPreviousValue, Transaction, H256, U256,
-    ZKPORTER_IS_AVAILABLE,
+    AccountTreeId, Address, L1BatchNumber, L2BlockNumber, L2ChainId, PriorityOpId,
+    ProtocolVersionId, StorageKey, StorageLog, StorageLogKind, StorageLogWithPreviousValue,
+    Transaction, H256, U256,
 };
 use zksync_utils::u256_to_h256;
 
@@ -34,7 +35,7 @@ use crate::{
         SequencerSealer, UnexecutableReason,
     },
     testonly::{
-        successful_exec,
+        default_l1_batch_env, l1_transaction, successful_exec,
         test_batch_executor::{
             random_tx, random_upgrade_tx, rejected_exec, successful_exec_with_log,
             MockReadStorageFactory, TestBatchExecutorBuilder, TestIO, TestScenario, FEE_ACCOUNT,
@@ -43,7 +44,7 @@ use crate::{
     },
     updates::UpdatesManager,
     utils::{gas_count_from_tx_and_metrics, l1_batch_base_cost},
-    ZkSyncStateKeeper,
+    StateKeeperOutputHandler, ZkSyncStateKeeper,
 };
 
 /// Creates a mock `PendingBatchData` object containing the provided sequence of L2 blocks.
@@ -63,48 +64,6 @@ pub(crate) fn pending_batch_data(pending_l2_blocks: Vec<L2BlockExecutionData>) -
     }
 }
 
-pub(super) fn default_system_env() -> SystemEnv {
-    SystemEnv {
-        zk_porter_available: ZKPORTER_IS_AVAILABLE,
-        version: ProtocolVersionId::latest(),
-        base_system_smart_contracts: BASE_SYSTEM_CONTRACTS.clone(),
-        bootloader_gas_limit: BATCH_COMPUTATIONAL_GAS_LIMIT,
-        execution_mode: TxExecutionMode::VerifyExecute,
-        default_validation_computational_gas_limit: BATCH_COMPUTATIONAL_GAS_LIMIT,
-        chain_id: L2ChainId::from(270),
-    }
-}
-
-pub(super) fn default_l1_batch_env(
-    number: u32,
-    timestamp: u64,
-    fee_account: Address,
-) -> L1BatchEnv {
-    L1BatchEnv {
-        previous_batch_hash: None,
-        number: L1BatchNumber(number),
-        timestamp,
-        fee_account,
-        enforced_base_fee: None,
-        first_l2_block: L2BlockEnv {
-            number,
-            timestamp,
-            prev_block_hash: L2BlockHasher::legacy_hash(L2BlockNumber(number - 1)),
-            max_virtual_blocks_to_create: 1,
-        },
-        fee_input: BatchFeeInput::PubdataIndependent(PubdataIndependentBatchFeeModelInput {
-            fair_l2_gas_price: 1,
-            fair_pubdata_price: 1,
-            l1_gas_price: 1,
-        }),
-    }
-}
-
-pub(super) fn create_updates_manager() -> UpdatesManager {
-    let l1_batch_env = default_l1_batch_env(1, 1, Address::default());
-    UpdatesManager::new(&l1_batch_env, &default_system_env())
-}
-
 pub(super) fn create_transaction(fee_per_gas: u64, gas_per_pubdata: u64) -> Transaction {
     create_l2_transaction(fee_per_gas, gas_per_pubdata).into()
 }
@@ -626,3 +585,137 @@ async fn protocol_upgrade() {
         .run(sealer)
         .await;
 }
+
+#[tokio::test]
+async fn sealing_l1_batch_on_request() {
+    TestScenario::new()
+        .next_tx("First tx", random_tx(1), successful_exec())
+        .request_l1_batch_seal("Seal is requested after the first tx")
+        .l2_block_sealed_with("L2 block is sealed on request", |updates| {
+            assert_eq!(updates.l2_block.executed_transactions.len(), 1);
+        })
+        .batch_sealed_with("Batch 1 is sealed on request", |updates| {
+            assert_eq!(updates.l1_batch.executed_transactions.len(), 1);
+        })
+        .request_l1_batch_seal("Seal request for an empty batch is ignored")
+        .next_tx("First tx in batch 2", random_tx(2), successful_exec())
+        .next_tx("Second tx in batch 2", random_tx(3), successful_exec())
+        .request_l1_batch_seal("Seal is requested after two txs")
+        .l2_block_sealed("L2 block is sealed on request")
+        .batch_sealed_with("Batch 2 is sealed on request", |updates| {
+            assert_eq!(updates.l1_batch.executed_transactions.len(), 2);
+        })
+        .run(SequencerSealer::default())
+        .await;
+}
+
+#[tokio::test]
+async fn not_accepting_l2_txs_after_l1_batch() {
+    let resumed = Arc::new(AtomicBool::new(false));
+    let resumed_checker = resumed.clone();
+    let mut account = Account::random();
+
+    let scenario = TestScenario::new()
+        .stop_l2_txs_after_l1_batch(
+            "L2 txs are accepted only in batch 1",
+            Some(L1BatchNumber(1)),
+        )
+        .next_tx("L2 tx in batch 1", random_tx(1), successful_exec())
+        .request_l1_batch_seal("Batch 1 is sealed on request")
+        .l2_block_sealed("L2 block with the L2 tx")
+        .batch_sealed("Batch 1")
+        .next_tx(
+            "L1 txs are still accepted",
+            l1_transaction(&mut account, PriorityOpId(0)),
+            successful_exec(),
+        )
+        .next_tx(
+            "L2 tx is accepted after resuming",
+            random_tx(2),
+            successful_exec(),
+        )
+        .request_l1_batch_seal("Batch 2 is sealed on request")
+        .l2_block_sealed_with("L2 block with L1 and L2 txs", move |updates| {
+            assert!(
+                resumed_checker.load(Ordering::Relaxed),
+                "L2 tx was accepted before resuming"
+            );
+            assert_eq!(updates.l2_block.executed_transactions.len(), 2);
+        })
+        .batch_sealed("Batch 2");
+
+    let seal_trigger = scenario.seal_trigger();
+    tokio::spawn(async move {
+        tokio::time::sleep(POLL_WAIT_DURATION * 2).await;
+        resumed.store(true, Ordering::Relaxed);
+        seal_trigger.stop_after_l1_batch(None).await.unwrap();
+    });
+    scenario.run(SequencerSealer::default()).await;
+}
+
+/// Output handler failing to handle L2 blocks and recording rolled back transactions.
+#[derive(Debug, Default)]
+struct FailingL2BlockHandler {
+    executed_txs: Arc<AtomicU64>,
+    rolled_back_txs: Arc<AtomicU64>,
+}
+
+#[async_trait]
+impl StateKeeperOutputHandler for FailingL2BlockHandler {
+    async fn handle_executed_tx(
+        &mut self,
+        _updates_manager: &UpdatesManager,
+    ) -> anyhow::Result<()> {
+        self.executed_txs.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn handle_l2_block(&mut self, _updates_manager: &UpdatesManager) -> anyhow::Result<()> {
+        anyhow::bail!("emulated L2 block error")
+    }
+
+    async fn handle_l2_block_rollback(
+        &mut self,
+        updates_manager: &UpdatesManager,
+    ) -> anyhow::Result<()> {
+        let tx_count = updates_manager.l2_block.executed_transactions.len() as u64;
+        self.rolled_back_txs.fetch_add(tx_count, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn open_l2_block_is_rolled_back_on_error() {
+    let sealer = SequencerSealer::with_sealers(StateKeeperConfig::default(), vec![]);
+    let scenario = TestScenario::new()
+        .seal_l2_block_when(|updates| updates.l2_block.executed_transactions.len() == 2)
+        .next_tx("First tx", random_tx(1), successful_exec())
+        .next_tx("Second tx", random_tx(2), successful_exec())
+        .l2_block_sealed("L2 block 1");
+    let batch_executor = TestBatchExecutorBuilder::new(&scenario);
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let (io, output_handler) = TestIO::new(stop_sender, scenario);
+    let handler = FailingL2BlockHandler::default();
+    let executed_txs = handler.executed_txs.clone();
+    let rolled_back_txs = handler.rolled_back_txs.clone();
+    let output_handler = output_handler.with_handler(Box::new(handler));
+
+    let state_keeper = ZkSyncStateKeeper::new(
+        stop_receiver,
+        Box::new(io),
+        Box::new(batch_executor),
+        output_handler,
+        Arc::new(sealer),
+        Arc::new(MockReadStorageFactory),
+    );
+    let err = tokio::time::timeout(Duration::from_secs(60), state_keeper.run())
+        .await
+        .expect("state keeper got stuck")
+        .unwrap_err();
+    assert!(
+        format!("{err:#}").contains("emulated L2 block error"),
+        "{err:#}"
+    );
+    assert_eq!(executed_txs.load(Ordering::SeqCst), 2);
+    assert_eq!(rolled_back_txs.load(Ordering::SeqCst), 2);
+}
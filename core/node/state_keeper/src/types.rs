@@ -51,6 +51,20 @@ impl MempoolGuard {
             .next_transaction(filter)
     }
 
+    pub fn has_next_l1(&self) -> bool {
+        self.0
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .has_next_l1()
+    }
+
+    pub fn next_l1_transaction(&mut self) -> Option<Transaction> {
+        self.0
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .next_l1_transaction()
+    }
+
     pub fn rollback(&mut self, rejected: &Transaction) {
         self.0
             .lock()
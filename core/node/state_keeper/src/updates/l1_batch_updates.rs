@@ -15,6 +15,8 @@ pub struct L1BatchUpdates {
     // how much L1 gas will it take to submit this block?
     pub l1_gas_count: BlockGasCount,
     pub txs_encoding_size: usize,
+    /// Number of sealed L2 blocks in the batch.
+    pub l2_blocks_count: usize,
     pub finished: Option<FinishedL1Batch>,
 }
 
@@ -27,6 +29,7 @@ impl L1BatchUpdates {
             block_execution_metrics: Default::default(),
             l1_gas_count: new_block_gas_count(),
             txs_encoding_size: 0,
+            l2_blocks_count: 0,
             finished: None,
         }
     }
@@ -44,6 +47,7 @@ impl L1BatchUpdates {
         self.l1_gas_count += l2_block_updates.l1_gas_count;
         self.block_execution_metrics += l2_block_updates.block_execution_metrics;
         self.txs_encoding_size += l2_block_updates.txs_encoding_size;
+        self.l2_blocks_count += 1;
     }
 }
 
@@ -50,6 +50,8 @@ pub struct L2BlockUpdates {
     pub new_factory_deps: HashMap<H256, Vec<u8>>,
     /// How much L1 gas will it take to submit this block?
     pub l1_gas_count: BlockGasCount,
+    /// L2 gas used by transactions in this block, i.e., the sum of gas limits minus refunds.
+    pub l2_gas_used: u64,
     pub block_execution_metrics: VmExecutionMetrics,
     pub txs_encoding_size: usize,
     pub payload_encoding_size: usize,
@@ -76,6 +78,7 @@ impl L2BlockUpdates {
             system_l2_to_l1_logs: vec![],
             new_factory_deps: HashMap::new(),
             l1_gas_count: BlockGasCount::default(),
+            l2_gas_used: 0,
             block_execution_metrics: VmExecutionMetrics::default(),
             txs_encoding_size: 0,
             payload_encoding_size: 0,
@@ -164,6 +167,11 @@ impl L2BlockUpdates {
         self.new_factory_deps.extend(known_bytecodes);
 
         self.l1_gas_count += tx_l1_gas_this_tx;
+        // The gas limit is user-provided and may not fit into `u64`.
+        let gas_limit = u64::try_from(tx.gas_limit()).unwrap_or(u64::MAX);
+        self.l2_gas_used = self
+            .l2_gas_used
+            .saturating_add(gas_limit.saturating_sub(gas_refunded));
         self.block_execution_metrics += execution_metrics;
         self.txs_encoding_size += tx.bootloader_encoding_size();
         self.payload_encoding_size +=
@@ -206,6 +214,7 @@ impl L2BlockUpdates {
 #[cfg(test)]
 mod tests {
     use zksync_multivm::vm_latest::TransactionVmExt;
+    use zksync_types::{ExecuteTransactionCommon, U256};
 
     use super::*;
     use crate::tests::{create_execution_result, create_transaction};
@@ -220,6 +229,7 @@ mod tests {
             ProtocolVersionId::latest(),
         );
         let tx = create_transaction(10, 100);
+        let gas_limit = tx.gas_limit().as_u64();
         let bootloader_encoding_size = tx.bootloader_encoding_size();
         let payload_encoding_size =
             zksync_protobuf::repr::encode::<zksync_dal::consensus::proto::Transaction>(&tx).len();
@@ -239,9 +249,37 @@ mod tests {
         assert_eq!(accumulator.user_l2_to_l1_logs.len(), 0);
         assert_eq!(accumulator.system_l2_to_l1_logs.len(), 0);
         assert_eq!(accumulator.l1_gas_count, Default::default());
+        // The execution result has no refunds.
+        assert_eq!(accumulator.l2_gas_used, gas_limit);
         assert_eq!(accumulator.new_factory_deps.len(), 0);
         assert_eq!(accumulator.block_execution_metrics.l2_to_l1_logs, 0);
         assert_eq!(accumulator.txs_encoding_size, bootloader_encoding_size);
         assert_eq!(accumulator.payload_encoding_size, payload_encoding_size);
     }
+
+    #[test]
+    fn applying_l2_tx_with_huge_gas_limit() {
+        let mut accumulator = L2BlockUpdates::new(
+            0,
+            L2BlockNumber(0),
+            H256::random(),
+            0,
+            ProtocolVersionId::latest(),
+        );
+        let mut tx = create_transaction(10, 100);
+        let ExecuteTransactionCommon::L2(common_data) = &mut tx.common_data else {
+            unreachable!();
+        };
+        common_data.fee.gas_limit = U256::MAX;
+
+        accumulator.extend_from_executed_transaction(
+            tx,
+            create_execution_result([]),
+            BlockGasCount::default(),
+            VmExecutionMetrics::default(),
+            vec![],
+            vec![],
+        );
+        assert_eq!(accumulator.l2_gas_used, u64::MAX);
+    }
 }
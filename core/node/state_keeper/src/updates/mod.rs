@@ -182,6 +182,21 @@ impl UpdatesManager {
         self.l1_batch.executed_transactions.len() + self.l2_block.executed_transactions.len()
     }
 
+    /// Returns the number of L2 blocks in the current L1 batch, including the open L2 block.
+    pub(crate) fn pending_l2_blocks_count(&self) -> usize {
+        self.l1_batch.l2_blocks_count + 1
+    }
+
+    pub(crate) fn pending_priority_txs_len(&self) -> usize {
+        let l2_block_priority_txs = self
+            .l2_block
+            .executed_transactions
+            .iter()
+            .filter(|tx| tx.transaction.is_l1())
+            .count();
+        self.l1_batch.priority_ops_onchain_data.len() + l2_block_priority_txs
+    }
+
     pub(crate) fn pending_l1_gas_count(&self) -> BlockGasCount {
         self.l1_batch.l1_gas_count + self.l2_block.l1_gas_count
     }
@@ -217,7 +232,8 @@ pub struct L2BlockSealCommand {
 mod tests {
     use super::*;
     use crate::{
-        tests::{create_execution_result, create_transaction, create_updates_manager},
+        testonly::create_updates_manager,
+        tests::{create_execution_result, create_transaction},
         utils::new_block_gas_count,
     };
 
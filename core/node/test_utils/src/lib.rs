@@ -19,6 +19,7 @@ use zksync_types::{
     },
     fee::Fee,
     fee_model::BatchFeeInput,
+    l1::L1Tx,
     l2::L2Tx,
     l2_to_l1_log::{L2ToL1Log, UserL2ToL1Log},
     protocol_version::ProtocolSemanticVersion,
@@ -170,6 +171,20 @@ pub fn execute_l2_transaction(transaction: L2Tx) -> TransactionExecutionResult {
     }
 }
 
+pub fn execute_l1_transaction(transaction: L1Tx) -> TransactionExecutionResult {
+    TransactionExecutionResult {
+        hash: transaction.hash(),
+        transaction: transaction.into(),
+        execution_info: VmExecutionMetrics::default(),
+        execution_status: TxExecutionStatus::Success,
+        refunded_gas: 0,
+        operator_suggested_refund: 0,
+        compressed_bytecodes: vec![],
+        call_traces: vec![],
+        revert_reason: None,
+    }
+}
+
 /// Concise representation of a storage snapshot for testing recovery.
 #[derive(Debug)]
 pub struct Snapshot {
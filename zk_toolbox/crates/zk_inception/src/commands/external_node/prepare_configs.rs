@@ -71,6 +71,7 @@ fn prepare_configs(
                 .http_url,
         )?,
         main_node_rate_limit_rps: None,
+        main_node_fallback_urls: vec![],
         gateway_url: None,
     };
     let mut general_en = general.clone();
@@ -121,6 +122,7 @@ fn prepare_configs(
         l1: Some(L1Secrets {
             l1_rpc_url: SensitiveUrl::from_str(&args.l1_rpc_url).context("l1_rpc_url")?,
         }),
+        admin: None,
     };
     secrets.save_with_base_path(shell, en_configs_path)?;
     let dirs = recreate_rocksdb_dirs(shell, &config.rocks_db_path, RocksDBDirOption::ExternalNode)?;